@@ -1,5 +1,13 @@
+// Provides `output_transform`, matching the surface format
+#import <output_transform>
+
 const PI: f32 = 3.14159265359; 
 const F0_DEFAULT: f32 = 0.04;
+const NORMAL_MAP_SPACE_TANGENT: u32 = 0u;
+const NORMAL_MAP_SPACE_OBJECT: u32 = 1u;
+const ALPHA_MODE_OPAQUE: u32 = 0u;
+const ALPHA_MODE_MASK: u32 = 1u;
+const ALPHA_MODE_BLEND: u32 = 2u;
 
 struct VertexData {
     @builtin(vertex_index) vertex_index: u32,
@@ -8,6 +16,11 @@ struct VertexData {
     @location(2) tangent: vec3<f32>,
     @location(3) bitangent: vec3<f32>,
     @location(4) uv: vec2<f32>,
+    @location(11) color: vec4<f32>,
+#ifdef SKINNED
+    @location(9) joints: vec4<u32>,
+    @location(10) weights: vec4<f32>,
+#endif
 }
 
 struct InstanceData {
@@ -24,6 +37,12 @@ struct FragmentData {
     @location(2) tangent: vec3<f32>,
     @location(3) bitangent: vec3<f32>,
     @location(4) normal: vec3<f32>,
+    // Object to world space rotation, needed for object-space normal maps
+    @location(5) model_space_rotation_0: vec3<f32>,
+    @location(6) model_space_rotation_1: vec3<f32>,
+    @location(7) model_space_rotation_2: vec3<f32>,
+    // Linear vertex color, multiplied into the albedo
+    @location(8) color: vec4<f32>,
 }
 
 struct CameraUniform {
@@ -44,18 +63,53 @@ struct Light {
     position: vec4<f32>,     // xyz: position, w: padding
     color: vec4<f32>,        // xyz: color, w: intensity
     direction: vec4<f32>,    // xyz: direction, w: type
-    params: vec4<f32>,       // x: inner cone angle, y: outer cone angle, zw: padding
+    params: vec4<f32>,       // x: inner cone angle, y: outer cone angle, z: range (0 = infinite), w: padding
+}
+
+struct LightStore {
+    // Amount of valid lights, the array always has at least one entry
+    count: u32,
+    lights: array<Light>,
 }
 
 struct PBRFactors {
-    albedo_factor: vec3<f32>,
+    albedo_factor: vec4<f32>,
     metallic_factor: f32,
     roughness_factor: f32,
+    // See NORMAL_MAP_SPACE_* constants
+    normal_map_space: u32,
+    // Fragments below are discarded if alpha_mode is ALPHA_MODE_MASK
+    alpha_cutoff: f32,
+    // See ALPHA_MODE_* constants
+    alpha_mode: u32,
+    emissive_factor: vec3<f32>,
+    // Not clamped, allows for HDR emission
+    emissive_strength: f32,
+    // Scales the XY of the sampled normal
+    normal_scale: f32,
+    // Lerps the occlusion towards 1.0 (none) if below 1.0
+    occlusion_strength: f32,
+}
+
+#ifdef CLEARCOAT
+struct ClearcoatFactors {
+    clearcoat_factor: f32,
+    clearcoat_roughness: f32,
 }
+#endif
+
+#ifdef SHEEN
+struct SheenFactors {
+    sheen_color: vec3<f32>,
+    sheen_roughness: f32,
+}
+#endif
 
 struct PBRData {
     // Albedo (color) texture sample
     albedo: vec3<f32>,
+    // Albedo alpha
+    alpha: f32,
     // Metallic factor
     metallic: f32,
     // Roughness factor
@@ -76,11 +130,25 @@ struct PBRData {
     V: vec3<f32>,
     // Dot product (multiplication) of normal and outgoing light
     NdotV: f32,
+#ifdef CLEARCOAT
+    // Strength of the clearcoat layer
+    clearcoat: f32,
+    // Roughness of the clearcoat layer
+    clearcoat_roughness: f32,
+    // Normal of the clearcoat layer
+    clearcoat_N: vec3<f32>,
+    // Like NdotV, but for the clearcoat normal
+    clearcoat_NdotV: f32,
+#endif
+#ifdef SHEEN
+    sheen_color: vec3<f32>,
+    sheen_roughness: f32,
+#endif
 }
 
 @group(0) @binding(0) var<uniform> camera: CameraUniform;
 
-@group(0) @binding(1) var<storage> light_store: array<Light>;
+@group(0) @binding(1) var<storage> light_store: LightStore;
 
 @group(0) @binding(2) var diffuse_env_map: texture_cube<f32>;
 @group(0) @binding(3) var diffuse_sampler: sampler;
@@ -111,17 +179,51 @@ struct PBRData {
 
 @group(1) @binding(12) var<uniform> pbr_factors: PBRFactors;
 
+// Optional extensions, bound after the factors in this order if defined
+#ifdef CLEARCOAT
+@group(1) @binding(13) var clearcoat_normal_texture: texture_2d<f32>;
+@group(1) @binding(14) var clearcoat_normal_sampler: sampler;
+
+@group(1) @binding(15) var<uniform> clearcoat_factors: ClearcoatFactors;
+#endif
+
+#ifdef SHEEN
+#ifdef CLEARCOAT
+@group(1) @binding(16) var<uniform> sheen_factors: SheenFactors;
+#else
+@group(1) @binding(13) var<uniform> sheen_factors: SheenFactors;
+#endif
+#endif
+
+#ifdef SKINNED
+// Joint matrices of the skin, weighted per vertex
+@group(2) @binding(0) var<storage> joint_palette: array<mat4x4<f32>>;
+
+fn skin_matrix(vertex: VertexData) -> mat4x4<f32> {
+    return joint_palette[vertex.joints.x] * vertex.weights.x
+        + joint_palette[vertex.joints.y] * vertex.weights.y
+        + joint_palette[vertex.joints.z] * vertex.weights.z
+        + joint_palette[vertex.joints.w] * vertex.weights.w;
+}
+#endif
+
 @vertex
 fn entrypoint_vertex(
     vertex: VertexData,
     instance: InstanceData
 ) -> FragmentData {
-    let model_space_matrix = mat4x4<f32>(
+    let instance_matrix = mat4x4<f32>(
         instance.model_space_matrix_0,
         instance.model_space_matrix_1,
         instance.model_space_matrix_2,
         instance.model_space_matrix_3,
     );
+#ifdef SKINNED
+    // Skinning happens in mesh space, before the instance is applied
+    let model_space_matrix = instance_matrix * skin_matrix(vertex);
+#else
+    let model_space_matrix = instance_matrix;
+#endif
 
     // Calculate world position
     let world_position = model_space_matrix * vec4<f32>(vertex.position, 1.0);
@@ -147,12 +249,25 @@ fn entrypoint_vertex(
     // Transform Normal
     out.normal = (model_space_matrix * vec4<f32>(vertex.normal, 0.0)).xyz;
 
+    out.model_space_rotation_0 = model_space_matrix[0].xyz;
+    out.model_space_rotation_1 = model_space_matrix[1].xyz;
+    out.model_space_rotation_2 = model_space_matrix[2].xyz;
+
+    out.color = vertex.color;
+
     return out;
 }
 
 @fragment
-fn entrypoint_fragment(in: FragmentData) -> @location(0) vec4<f32> {
-    let pbr = pbr_data(in);
+fn entrypoint_fragment(
+    in: FragmentData,
+    @builtin(front_facing) front_facing: bool,
+) -> @location(0) vec4<f32> {
+    let pbr = pbr_data(facing_fragment_data(in, front_facing));
+    if pbr_factors.alpha_mode == ALPHA_MODE_MASK && pbr.alpha < pbr_factors.alpha_cutoff {
+        discard;
+    }
+
     var output = vec3(0.0);
 
     // IBL Ambient light
@@ -168,7 +283,26 @@ fn entrypoint_fragment(in: FragmentData) -> @location(0) vec4<f32> {
 
     // Tonemap / HDR 
     let tone_mapped_color = aces_tone_map(output);
-    return vec4<f32>(tone_mapped_color, 1.0);
+
+    // Encode for the surface (e.g. sRGB encode on linear surfaces)
+    let alpha = select(1.0, pbr.alpha, pbr_factors.alpha_mode == ALPHA_MODE_BLEND);
+    return vec4<f32>(output_transform(tone_mapped_color), alpha);
+}
+
+/// Flips the normal frame of back faces, which are only rasterized for double sided materials
+fn facing_fragment_data(fragment_data: FragmentData, front_facing: bool) -> FragmentData {
+    if front_facing {
+        return fragment_data;
+    }
+
+    var out = fragment_data;
+    out.tangent = -fragment_data.tangent;
+    out.bitangent = -fragment_data.bitangent;
+    out.normal = -fragment_data.normal;
+    out.model_space_rotation_0 = -fragment_data.model_space_rotation_0;
+    out.model_space_rotation_1 = -fragment_data.model_space_rotation_1;
+    out.model_space_rotation_2 = -fragment_data.model_space_rotation_2;
+    return out;
 }
 
 // Note: Unused in favor of ACES
@@ -196,14 +330,28 @@ fn aces_tone_map(color: vec3<f32>) -> vec3<f32> {
 fn calculate_light_contribution(pbr: PBRData, world_position: vec3<f32>) -> vec3<f32> {
     var Lo = vec3(0.0);
 
-    for (var i = u32(0); i < arrayLength(&light_store); i++) {
-        let light = light_store[i];
+    for (var i = u32(0); i < light_store.count; i++) {
+        let light = light_store.lights[i];
         Lo += calculate_light_brdf(light, pbr, world_position); 
     }
 
     return Lo;
 }
 
+// Inverse-square falloff, smoothly windowed to reach zero at the light range.
+// A range of zero means the light reaches infinitely far.
+// Mirrors `LightDescriptor::attenuation`.
+fn calculate_distance_attenuation(light_distance: f32, range: f32) -> f32 {
+    let inverse_square = 1.0 / max(light_distance * light_distance, 0.0001);
+    if (range <= 0.0) {
+        return inverse_square;
+    }
+
+    let ratio = light_distance / range;
+    let window = clamp(1.0 - ratio * ratio * ratio * ratio, 0.0, 1.0);
+    return inverse_square * window * window;
+}
+
 fn calculate_light_brdf(light: Light, pbr: PBRData, world_position: vec3<f32>) -> vec3<f32> {
     var L: vec3<f32>;
     var light_distance: f32 = 1.0;
@@ -215,9 +363,7 @@ fn calculate_light_brdf(light: Light, pbr: PBRData, world_position: vec3<f32>) -
         L = light.position.xyz - world_position;
         light_distance = length(L);
         L = normalize(L);
-        // Attenuation for point lights: 1.0 / (constant + linear * distance + quadratic * distance^2)
-        // Using a simple quadratic attenuation for now
-        attenuation = 1.0 / (light_distance * light_distance);
+        attenuation = calculate_distance_attenuation(light_distance, light.params.z);
     } else if (light.direction.w == LIGHT_TYPE_DIRECTIONAL) {
         // Directional light
         L = normalize(-light.direction.xyz);
@@ -229,7 +375,7 @@ fn calculate_light_brdf(light: Light, pbr: PBRData, world_position: vec3<f32>) -
         light_distance = length(L);
         L = normalize(L);
         // Attenuation for spot lights
-        attenuation = 1.0 / (light_distance * light_distance);
+        attenuation = calculate_distance_attenuation(light_distance, light.params.z);
         
         // Spot light angle calculation
         let light_direction = normalize(-light.direction.xyz);
@@ -244,11 +390,12 @@ fn calculate_light_brdf(light: Light, pbr: PBRData, world_position: vec3<f32>) -
         return vec3(0.0);
     }
 
+    let radiance = light.color.rgb * light.color.w * attenuation;
     let H = normalize(pbr.V + L);
     let NdotL = clamp(dot(pbr.N, L), 0.0, 1.0);
     let NdotH = clamp(dot(pbr.N, H), 0.0, 1.0);
 
-    var Lo: vec3<f32>;
+    var Lo = vec3(0.0);
     if NdotL > 0.0 {
         // Normal distribution of the microfacets
         let D = distribution_ggx(NdotH, pbr.roughness);
@@ -266,8 +413,18 @@ fn calculate_light_brdf(light: Light, pbr: PBRData, world_position: vec3<f32>) -
         let kD = vec3(1.0) - kS;
         let diffuse = kD * pbr.albedo / PI;
         
-        Lo += (diffuse + specular) * light.color.rgb * light.color.w * attenuation * NdotL;
+        Lo += (diffuse + specular) * radiance * NdotL;
+
+#ifdef SHEEN
+        Lo += sheen_brdf(NdotL, NdotH, pbr) * radiance * NdotL;
+#endif
     }
+
+#ifdef CLEARCOAT
+    // The clearcoat layer reflects parts of the light before it reaches the base
+    let clearcoat_fresnel = calculate_clearcoat_fresnel(pbr);
+    Lo = Lo * (1.0 - clearcoat_fresnel) + clearcoat_brdf(L, H, pbr) * radiance * clearcoat_fresnel;
+#endif
     return Lo;
 }
 
@@ -284,10 +441,97 @@ fn calculate_ambient_ibl(pbr: PBRData) -> vec3<f32> {
     let specular_color = mix(F0, pbr.albedo, pbr.metallic);
     var specular_ibl = pbr.ibl_specular * (F * pbr.brdf_lut.x + pbr.brdf_lut.y);
 
+    var ambient = diffuse_ibl + specular_ibl;
+
+#ifdef CLEARCOAT
+    let clearcoat_fresnel = calculate_clearcoat_fresnel(pbr);
+    let clearcoat_ibl = sample_specular_ibl(
+        reflect(-pbr.V, pbr.clearcoat_N),
+        pbr.clearcoat_roughness
+    );
+    ambient = ambient * (1.0 - clearcoat_fresnel) + clearcoat_ibl * clearcoat_fresnel;
+#endif
+
     // Ambient light calculation (IBL), multiplied by ambient occlusion
-    return (diffuse_ibl + specular_ibl) * pbr.occlusion;
+    return ambient * pbr.occlusion;
+}
+
+/// Samples the specular environment map, blurred according to the roughness
+fn sample_specular_ibl(R: vec3<f32>, roughness: f32) -> vec3<f32> {
+    let specular_mip_count = textureNumLevels(specular_env_map);
+    let specular_mip_level = roughness * roughness * f32(specular_mip_count - 1u);
+    let specular_sample = textureSampleLevel(
+        specular_env_map,
+        specular_sampler,
+        R,
+        specular_mip_level
+    ).rgb;
+    let specular_clamped = clamp(specular_sample, vec3(0.0), vec3(1.0));
+    return pow(specular_clamped, vec3(camera.global_gamma));
 }
 
+#ifdef CLEARCOAT
+/// Samples the fragment's clearcoat normal (tangent space) and transforms it into world space
+fn sample_clearcoat_normal_from_map(fragment_data: FragmentData) -> vec3<f32> {
+    let normal_sample = textureSample(
+        clearcoat_normal_texture,
+        clearcoat_normal_sampler,
+        fragment_data.uv
+    ).rgb;
+    let mapped_normal = 2.0 * normal_sample - 1.0;
+
+    let TBN = mat3x3(
+        fragment_data.tangent,
+        fragment_data.bitangent,
+        fragment_data.normal,
+    );
+    return normalize(TBN * mapped_normal);
+}
+
+// Share of the light reflected by the clearcoat layer.
+// The layer is a dielectric, thus always uses the default F0.
+fn calculate_clearcoat_fresnel(pbr: PBRData) -> f32 {
+    let F = F0_DEFAULT + (1.0 - F0_DEFAULT) * pow(1.0 - pbr.clearcoat_NdotV, 5.0);
+    return F * pbr.clearcoat;
+}
+
+// Specular-only BRDF of the clearcoat layer.
+// Fresnel is applied by the caller to blend with the base layer.
+fn clearcoat_brdf(L: vec3<f32>, H: vec3<f32>, pbr: PBRData) -> vec3<f32> {
+    let NdotL = clamp(dot(pbr.clearcoat_N, L), 0.0, 1.0);
+    if NdotL <= 0.0 {
+        return vec3(0.0);
+    }
+    let NdotH = clamp(dot(pbr.clearcoat_N, H), 0.0, 1.0);
+
+    let D = distribution_ggx(NdotH, pbr.clearcoat_roughness);
+    let G = schlick_smith_ggx(NdotL, pbr.clearcoat_NdotV, pbr.clearcoat_roughness);
+    let denominator = 4.0 * NdotL * pbr.clearcoat_NdotV + 0.0001; // +0.0001 prevents division by zero
+    return vec3(D * G / denominator * NdotL);
+}
+#endif
+
+#ifdef SHEEN
+// "Charlie" sheen distribution (Estevez and Kulla)
+fn distribution_charlie(NdotH: f32, roughness: f32) -> f32 {
+    let alpha = max(roughness * roughness, 0.0001);
+    let inverse_alpha = 1.0 / alpha;
+    let sin2h = max(1.0 - NdotH * NdotH, 0.0078125); // Prevents pow(0, x) artifacts
+    return (2.0 + inverse_alpha) * pow(sin2h, inverse_alpha * 0.5) / (2.0 * PI);
+}
+
+// Sheen visibility term (Neubelt and Pettineo)
+fn visibility_neubelt(NdotL: f32, NdotV: f32) -> f32 {
+    return 1.0 / (4.0 * (NdotL + NdotV - NdotL * NdotV) + 0.0001);
+}
+
+fn sheen_brdf(NdotL: f32, NdotH: f32, pbr: PBRData) -> vec3<f32> {
+    let D = distribution_charlie(NdotH, pbr.sheen_roughness);
+    let V = visibility_neubelt(NdotL, pbr.NdotV);
+    return pbr.sheen_color * D * V;
+}
+#endif
+
 /// Samples the fragment's normal and transforms it into world space
 fn sample_normal_from_map(fragment_data: FragmentData) -> vec3<f32> {
     let normal_sample = textureSample(
@@ -295,14 +539,25 @@ fn sample_normal_from_map(fragment_data: FragmentData) -> vec3<f32> {
         normal_sampler,
         fragment_data.uv
     ).rgb;
-    let tangent_normal = 2.0 * normal_sample - 1.0;
+    var mapped_normal = 2.0 * normal_sample - 1.0;
+    mapped_normal = vec3(mapped_normal.xy * pbr_factors.normal_scale, mapped_normal.z);
+
+    if pbr_factors.normal_map_space == NORMAL_MAP_SPACE_OBJECT {
+        // Object-space normals only need the model rotation, no TBN
+        let model_space_rotation = mat3x3(
+            fragment_data.model_space_rotation_0,
+            fragment_data.model_space_rotation_1,
+            fragment_data.model_space_rotation_2,
+        );
+        return normalize(model_space_rotation * mapped_normal);
+    }
 
     let TBN = mat3x3(
         fragment_data.tangent,
         fragment_data.bitangent,
         fragment_data.normal,
     );
-    let N = normalize(TBN * tangent_normal);
+    let N = normalize(TBN * mapped_normal);
     return N;
 }
 
@@ -349,8 +604,9 @@ fn pbr_data(fragment_data: FragmentData) -> PBRData {
         albedo_texture,
         albedo_sampler,
         fragment_data.uv
-    ).rgb;
-    let albedo_factored = albedo_sample * pbr_factors.albedo_factor.rgb;
+    );
+    out.alpha = albedo_sample.a * pbr_factors.albedo_factor.a * fragment_data.color.a;
+    let albedo_factored = albedo_sample.rgb * pbr_factors.albedo_factor.rgb * fragment_data.color.rgb;
     let albedo_clamped = clamp(albedo_factored, vec3(0.0), vec3(1.0));
     let albedo_gamma_applied = pow(albedo_clamped, vec3(camera.global_gamma));
     out.albedo = albedo_gamma_applied;
@@ -379,7 +635,7 @@ fn pbr_data(fragment_data: FragmentData) -> PBRData {
         fragment_data.uv
     ).r;
     let occlusion_clamped = clamp(occlusion_sample, 0.0, 1.0);
-    out.occlusion = occlusion_clamped;
+    out.occlusion = mix(1.0, occlusion_clamped, pbr_factors.occlusion_strength);
 
     let emissive_sample = textureSample(
         emissive_texture,
@@ -388,7 +644,7 @@ fn pbr_data(fragment_data: FragmentData) -> PBRData {
     ).rgb;
     let emissive_clamped = clamp(emissive_sample, vec3(0.0), vec3(1.0));
     let emissive_gamma_applied = pow(emissive_clamped, vec3(camera.global_gamma));
-    out.emissive = emissive_gamma_applied;
+    out.emissive = emissive_gamma_applied * pbr_factors.emissive_factor * pbr_factors.emissive_strength;
 
     let diffuse_sample = textureSample(
         diffuse_env_map,
@@ -399,17 +655,7 @@ fn pbr_data(fragment_data: FragmentData) -> PBRData {
     let diffuse_gamma_applied = pow(diffuse_clamped, vec3(camera.global_gamma));
     out.ibl_diffuse = diffuse_gamma_applied;
 
-    let specular_mip_count = textureNumLevels(specular_env_map);
-    let specular_mip_level = out.roughness * out.roughness * f32(specular_mip_count - 1u);
-    let specular_sample = textureSampleLevel(
-        specular_env_map,
-        specular_sampler,
-        R,
-        specular_mip_level
-    ).rgb;
-    let specular_clamped = clamp(specular_sample, vec3(0.0), vec3(1.0));
-    let specular_gamma_applied = pow(specular_clamped, vec3(camera.global_gamma));
-    out.ibl_specular = specular_gamma_applied;
+    out.ibl_specular = sample_specular_ibl(R, out.roughness);
 
     let brdf_lut_sample = textureSample(
         ibl_brdf_lut_texture,
@@ -420,5 +666,17 @@ fn pbr_data(fragment_data: FragmentData) -> PBRData {
     )).rg;
     out.brdf_lut = brdf_lut_sample;
 
+#ifdef CLEARCOAT
+    out.clearcoat = clamp(clearcoat_factors.clearcoat_factor, 0.0, 1.0);
+    out.clearcoat_roughness = clamp(clearcoat_factors.clearcoat_roughness, 0.0001, 0.9999);
+    out.clearcoat_N = sample_clearcoat_normal_from_map(fragment_data);
+    out.clearcoat_NdotV = clamp(dot(out.clearcoat_N, out.V), 0.0, 1.0);
+#endif
+
+#ifdef SHEEN
+    out.sheen_color = sheen_factors.sheen_color;
+    out.sheen_roughness = clamp(sheen_factors.sheen_roughness, 0.0001, 1.0);
+#endif
+
     return out;
 }
@@ -17,6 +17,7 @@ impl Element for DamagedHelmet {
             WorldEvent::Import(ImportTask::Gltf {
                 file_path: Self::FILE_NAME.to_string(),
                 task: GltfImport::WholeFile,
+                settings: Default::default(),
             }),
         ))
     }
@@ -2,9 +2,9 @@ use orbital::app::input::{InputAxis, InputButton};
 use orbital::app::standard::StandardApp;
 use orbital::app::{AppRuntime, AppSettings};
 use orbital::camera_controller::{
-    ButtonAxis, CameraController, CameraControllerAxisInputMode, CameraControllerButtonInputMode,
-    CameraControllerDescriptor, CameraControllerMouseInputMode, CameraControllerMouseInputType,
-    CameraControllerMovementType, CameraControllerRotationType,
+    AxisResponse, ButtonAxis, CameraController, CameraControllerAxisInputMode,
+    CameraControllerButtonInputMode, CameraControllerDescriptor, CameraControllerMouseInputMode,
+    CameraControllerMouseInputType, CameraControllerMovementType, CameraControllerRotationType,
 };
 use orbital::gilrs::Button;
 use orbital::winit::keyboard::{KeyCode, PhysicalKey};
@@ -42,7 +42,8 @@ pub fn entrypoint(event_loop_result: Result<EventLoop<()>, EventLoopError>) {
                 button_down: Some(InputButton::Keyboard(PhysicalKey::Code(KeyCode::KeyQ))),
                 speed: 1.0,
                 ignore_pitch_for_forward_movement: true,
-                axis_dead_zone: 0.1,
+                axis_response: AxisResponse::cutoff(0.1),
+                smoothing: None,
             },
             rotation_type: CameraControllerRotationType::Free {
                 mouse_input: Some(CameraControllerMouseInputMode {
@@ -72,8 +73,10 @@ pub fn entrypoint(event_loop_result: Result<EventLoop<()>, EventLoopError>) {
                     ],
                     sensitivity: 1.0,
                 }),
-                axis_dead_zone: 0.1,
+                axis_response: AxisResponse::cutoff(0.1),
+                smoothing: None,
             },
+            zoom_mode: None,
             camera_descriptor: Default::default(),
         })),
         Box::new(WorldEnvironment),
@@ -16,6 +16,7 @@ impl Element for PBRSpheres {
             WorldEvent::Import(ImportTask::Gltf {
                 file_path: Self::FILE_NAME.into(),
                 task: GltfImport::WholeFile,
+                settings: Default::default(),
             }),
         ))
     }
@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::str;
 
@@ -6,8 +6,13 @@ const MODEL_FILES_DIR: &str = "../Assets/ModelFiles";
 const MODEL_SCRIPT_GLTF_EXPORT: &str = "../Assets/ModelScripts/blender_gltf_export.py";
 const MODEL_SCRIPT_PBR_SPHERE_GEN: &str = "../Assets/ModelScripts/pbr_grid.py";
 const MODELS_DIR: &str = "../Assets/Models";
+const SHADERS_DIR: &str = "../Assets/Shaders";
 
 fn main() {
+    if std::env::var("CARGO_FEATURE_EMBED_SHADERS").is_ok() {
+        embed_shaders();
+    }
+
     println!("cargo::rerun-if-env-changed=SKIP_GLTF_EXPORT");
 
     if std::env::var("SKIP_GLTF_EXPORT").is_ok() {
@@ -117,3 +122,54 @@ fn blender_convert_to_gltf(filepath: &str, script_path: &PathBuf, output_path: &
         println!("cargo::warning=Exported Blender file '{filepath}' successfully!");
     }
 }
+
+/// Writes the shader lib as a list of `(directive, include_str!(...))`
+/// pairs into `OUT_DIR/embedded_shaders.rs`.
+/// Directives are derived the same way `ShaderPreprocessor::import_folder`
+/// derives them.
+fn embed_shaders() {
+    let shaders_path =
+        std::fs::canonicalize(SHADERS_DIR).expect("Failed to canonicalize shaders folder!");
+    println!("cargo::rerun-if-changed={}", shaders_path.display());
+
+    let mut files = Vec::new();
+    collect_wgsl_files(&shaders_path, &mut files);
+    files.sort();
+
+    let entries = files
+        .iter()
+        .map(|path| {
+            let directive = path
+                .strip_prefix(&shaders_path)
+                .expect("Shader must be inside the shaders folder!")
+                .to_str()
+                .expect("Shader path must be UTF-8!")
+                .replace("\\", "/")
+                .replace(".wgsl", "")
+                .to_lowercase();
+
+            format!(
+                "({directive:?}, include_str!({:?})),",
+                path.display().to_string()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let out_path = PathBuf::from(std::env::var("OUT_DIR").expect("OUT_DIR must be set!"))
+        .join("embedded_shaders.rs");
+    std::fs::write(out_path, format!("&[\n{entries}\n]"))
+        .expect("Failed writing embedded shaders!");
+}
+
+fn collect_wgsl_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    for entry in std::fs::read_dir(dir).expect("Failed reading shaders folder!") {
+        let path = entry.expect("Failed reading shaders folder entry!").path();
+
+        if path.is_dir() {
+            collect_wgsl_files(&path, files);
+        } else if path.extension().is_some_and(|x| x == "wgsl") {
+            files.push(path);
+        }
+    }
+}
@@ -1,3 +1,5 @@
+#[cfg(feature = "gamepad_input")]
+use crate::app::input::Rumble;
 use crate::element::Message;
 use winit::{dpi::Position, window::Cursor};
 
@@ -23,6 +25,22 @@ pub enum AppEvent {
     ///
     /// Check [Window::set_cursor_grab](winit::window::Window::set_cursor_grab) for more information and compatibility.
     ChangeCursorGrabbed(bool),
+    /// Captures or releases the mouse cursor.
+    /// Capturing grabs and hides the cursor, releasing ungrabs and shows it
+    /// again, e.g. when opening a menu.
+    /// Gets send directly to [winit], issues may appear in log.
+    ///
+    /// Additionally, [InputState::mouse_captured](crate::app::input::InputState::mouse_captured)
+    /// is updated, which [CameraController](crate::camera_controller::CameraController)s
+    /// use to ignore mouse movement while the cursor is released.
+    ChangeMouseCapture(bool),
+    /// Allows or disallows IMEs to compose text, e.g. while a text field is
+    /// focused.
+    /// Disallowed unless enabled via [AppSettings::ime_allowed](crate::app::AppSettings::ime_allowed).
+    /// Gets send directly to [winit], issues may appear in log.
+    ///
+    /// Check [Window::set_ime_allowed](winit::window::Window::set_ime_allowed) for more information and compatibility.
+    ChangeImeAllowed(bool),
     /// Requested that the app will close itself as soon as possible.
     /// The internal event loop will be stopped and the window will be closed.
     /// If there are other child-threads or processes active, they _may_ remain.
@@ -48,7 +66,19 @@ pub enum AppEvent {
     ///
     /// This does not immediately force a redraw, but instead queue it to be executed as soon as possible. Thus, there might be some slight delay between sending this event and the redraw happening.
     RequestRedraw,
+    /// Pauses updates.
+    /// Updates still happen, but with a delta time of zero, until
+    /// [AppEvent::Resume] is send.
+    Pause,
+    /// Resumes updates after [AppEvent::Pause].
+    Resume,
     /// Sends a message to the App instead of an Element.
     /// Useful for communicating with the overall app to change fundamental settings like, for example, which renderer to use.
     SendMessage(Message),
+    /// Rumbles one or all gamepads, e.g. as feedback for hits or collisions.
+    /// Gamepads without force feedback support are ignored.
+    ///
+    /// Check [Rumble] for details.
+    #[cfg(feature = "gamepad_input")]
+    Rumble(Rumble),
 }
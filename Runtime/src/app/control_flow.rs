@@ -0,0 +1,59 @@
+use std::time::{Duration, Instant};
+
+use winit::{event::StartCause, event_loop::ControlFlow};
+
+/// Defines when the event loop wakes up and how often frames are drawn.
+/// Set via [AppSettings::control_flow](super::AppSettings::control_flow).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ControlFlowStrategy {
+    /// Continuously polls for events and redraws as fast as possible
+    /// (or as fast as VSync allows).
+    /// Best suited for games.
+    #[default]
+    Continuous,
+    /// Sleeps until an event arrives.
+    /// Frames are only drawn when a redraw is requested, e.g. via
+    /// [AppEvent::RequestRedraw](super::AppEvent::RequestRedraw).
+    /// Best suited for tools and other mostly static applications.
+    OnDemand,
+    /// Redraws continuously, but at most the given amount of frames per
+    /// second, sleeping in between.
+    Throttled(u32),
+}
+
+impl ControlFlowStrategy {
+    /// Returns the time between two frames for [Self::Throttled].
+    pub fn frame_time(&self) -> Option<Duration> {
+        match self {
+            Self::Throttled(fps) => Some(Duration::from_secs_f64(1.0 / (*fps).max(1) as f64)),
+            _ => None,
+        }
+    }
+
+    /// Returns the [ControlFlow] to use after a frame was drawn at
+    /// `last_frame`.
+    pub fn control_flow(&self, last_frame: Instant) -> ControlFlow {
+        match self {
+            Self::Continuous => ControlFlow::Poll,
+            Self::OnDemand => ControlFlow::Wait,
+            Self::Throttled(_) => ControlFlow::WaitUntil(last_frame + self.frame_time().unwrap()),
+        }
+    }
+
+    /// Whether the next frame should be requested right after a frame
+    /// was drawn.
+    pub fn redraw_after_frame(&self) -> bool {
+        matches!(self, Self::Continuous)
+    }
+
+    /// Whether a redraw should be requested when the event loop wakes up
+    /// due to `cause`.
+    pub fn redraw_on_wake(&self, cause: StartCause) -> bool {
+        match self {
+            Self::Continuous => false,
+            // Redraw requests wake up the event loop themselves
+            Self::OnDemand => false,
+            Self::Throttled(_) => matches!(cause, StartCause::ResumeTimeReached { .. }),
+        }
+    }
+}
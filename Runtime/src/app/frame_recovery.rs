@@ -0,0 +1,46 @@
+use wgpu::SurfaceError;
+
+use crate::logging::{error, info, warn};
+
+/// How to proceed after a frame couldn't be acquired from the surface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameFailure {
+    /// Skips the current frame, the next frame will try again.
+    Skip,
+    /// Rendering can't continue, the app should shut down.
+    Exit,
+}
+
+/// Acquires the next frame via `acquire`, recovering from surface errors
+/// where possible:
+///
+/// - [SurfaceError::Lost] and [SurfaceError::Outdated] reconfigure the
+///   surface via `reconfigure` and retry once.
+///   If the retry fails as well, the frame is skipped.
+/// - [SurfaceError::Timeout] and [SurfaceError::Other] skip the frame.
+/// - [SurfaceError::OutOfMemory] requests an exit.
+pub fn acquire_frame_with_recovery<T>(
+    mut acquire: impl FnMut() -> Result<T, SurfaceError>,
+    reconfigure: impl FnOnce(),
+) -> Result<T, FrameFailure> {
+    match acquire() {
+        Ok(frame) => Ok(frame),
+        Err(e @ (SurfaceError::Lost | SurfaceError::Outdated)) => {
+            info!("Surface needs reconfiguration ({e}), reconfiguring and retrying ...");
+            reconfigure();
+
+            acquire().map_err(|e| {
+                warn!("Failed to acquire frame after reconfiguration, skipping frame: {e}");
+                FrameFailure::Skip
+            })
+        }
+        Err(SurfaceError::OutOfMemory) => {
+            error!("Out of memory while acquiring frame! Shutting down ...");
+            Err(FrameFailure::Exit)
+        }
+        Err(e) => {
+            warn!("Failed to acquire frame, skipping frame: {e}");
+            Err(FrameFailure::Skip)
+        }
+    }
+}
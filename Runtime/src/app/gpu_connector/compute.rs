@@ -0,0 +1,207 @@
+use cgmath::Vector3;
+use wgpu::{
+    naga::ShaderStage,
+    util::{BufferInitDescriptor, DeviceExt},
+    BindGroupDescriptor, BindGroupEntry, BindGroupLayoutDescriptor, BindGroupLayoutEntry,
+    BindingResource, BindingType, BufferUsages, CommandEncoderDescriptor, ComputePassDescriptor,
+    ComputePipelineDescriptor, PipelineLayoutDescriptor, PollType, ShaderModuleDescriptor,
+    ShaderStages, TextureView,
+};
+
+use crate::{
+    resources::{validate_shader_entry_points, BufferDescriptor, ShaderError, ShaderSource},
+    shader_preprocessor::ShaderPreprocessor,
+};
+
+use super::{ComputeError, GpuConnector};
+
+/// A resource bound to a compute shader by [GpuConnector::dispatch].
+/// Bindings are placed in `@group(0)`, with the position inside the
+/// bindings slice being the `@binding` index.
+#[derive(Debug, Clone, Copy)]
+pub enum ComputeBinding<'a> {
+    /// A buffer created from the descriptor's data.
+    Buffer(&'a BufferDescriptor),
+    /// An existing texture view, e.g. a storage texture to write to.
+    /// `ty` has to be one of the texture binding types.
+    Texture {
+        view: &'a TextureView,
+        ty: BindingType,
+    },
+}
+
+impl GpuConnector {
+    /// Preprocesses the shader, builds a compute pipeline for `entry_point`
+    /// and dispatches it with the given amount of workgroups.
+    ///
+    /// If `read_back` is set, the buffer at that binding is copied back once
+    /// the dispatch finished and its content returned.
+    /// This blocks until the GPU is done.
+    pub fn dispatch(
+        &self,
+        shader_source: ShaderSource,
+        entry_point: &str,
+        bindings: &[ComputeBinding],
+        workgroups: Vector3<u32>,
+        read_back: Option<u32>,
+    ) -> Result<Option<Vec<u8>>, ComputeError> {
+        if let Some(binding) = read_back {
+            if !matches!(
+                bindings.get(binding as usize),
+                Some(ComputeBinding::Buffer(_))
+            ) {
+                return Err(ComputeError::InvalidReadBack { binding });
+            }
+        }
+
+        let source = Self::preprocess_compute_shader(shader_source, entry_point)
+            .map_err(ComputeError::Shader)?;
+
+        let device = self.device();
+        let shader = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("Compute Dispatch"),
+            source: wgpu::ShaderSource::Wgsl(source.into()),
+        });
+
+        let buffers = bindings
+            .iter()
+            .enumerate()
+            .map(|(i, binding)| match binding {
+                ComputeBinding::Buffer(descriptor) => {
+                    let mut usage = descriptor.usage;
+                    if read_back == Some(i as u32) {
+                        usage |= BufferUsages::COPY_SRC;
+                    }
+
+                    Some(device.create_buffer_init(&BufferInitDescriptor {
+                        label: Some("Compute Dispatch"),
+                        contents: &descriptor.data,
+                        usage,
+                    }))
+                }
+                ComputeBinding::Texture { .. } => None,
+            })
+            .collect::<Vec<_>>();
+
+        let layout_entries = bindings
+            .iter()
+            .enumerate()
+            .map(|(i, binding)| BindGroupLayoutEntry {
+                binding: i as u32,
+                visibility: ShaderStages::COMPUTE,
+                ty: match binding {
+                    ComputeBinding::Buffer(descriptor) => BindingType::Buffer {
+                        ty: descriptor.ty,
+                        has_dynamic_offset: descriptor.has_dynamic_offset,
+                        min_binding_size: descriptor.min_binding_size,
+                    },
+                    ComputeBinding::Texture { ty, .. } => *ty,
+                },
+                count: match binding {
+                    ComputeBinding::Buffer(descriptor) => descriptor.count,
+                    ComputeBinding::Texture { .. } => None,
+                },
+            })
+            .collect::<Vec<_>>();
+        let bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("Compute Dispatch"),
+            entries: &layout_entries,
+        });
+
+        let entries = bindings
+            .iter()
+            .zip(&buffers)
+            .enumerate()
+            .map(|(i, (binding, buffer))| BindGroupEntry {
+                binding: i as u32,
+                resource: match (binding, buffer) {
+                    (ComputeBinding::Texture { view, .. }, _) => BindingResource::TextureView(view),
+                    (ComputeBinding::Buffer(_), Some(buffer)) => buffer.as_entire_binding(),
+                    (ComputeBinding::Buffer(_), None) => unreachable!(),
+                },
+            })
+            .collect::<Vec<_>>();
+        let bind_group = device.create_bind_group(&BindGroupDescriptor {
+            label: Some("Compute Dispatch"),
+            layout: &bind_group_layout,
+            entries: &entries,
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("Compute Dispatch"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_compute_pipeline(&ComputePipelineDescriptor {
+            label: Some("Compute Dispatch"),
+            layout: Some(&pipeline_layout),
+            module: &shader,
+            entry_point: Some(entry_point),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+
+        let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+            label: Some("Compute Dispatch"),
+        });
+        let mut pass = encoder.begin_compute_pass(&ComputePassDescriptor {
+            label: Some("Compute Dispatch"),
+            ..Default::default()
+        });
+        pass.set_pipeline(&pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.dispatch_workgroups(workgroups.x, workgroups.y, workgroups.z);
+        drop(pass);
+
+        let Some(binding) = read_back else {
+            self.queue().submit([encoder.finish()]);
+            return Ok(None);
+        };
+
+        let source_buffer = buffers[binding as usize]
+            .as_ref()
+            .expect("Read back binding was checked to be a buffer");
+        let read_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Compute Dispatch Read Buffer"),
+            size: source_buffer.size(),
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        encoder.copy_buffer_to_buffer(source_buffer, 0, &read_buffer, 0, source_buffer.size());
+        self.queue().submit([encoder.finish()]);
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        read_buffer
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                let _ = sender.send(result);
+            });
+        device
+            .poll(PollType::Wait)
+            .map_err(|e| ComputeError::ReadBack(e.to_string()))?;
+        receiver
+            .recv()
+            .map_err(|e| ComputeError::ReadBack(e.to_string()))?
+            .map_err(|e| ComputeError::ReadBack(e.to_string()))?;
+
+        let data = read_buffer.slice(..).get_mapped_range().to_vec();
+        read_buffer.unmap();
+
+        Ok(Some(data))
+    }
+
+    fn preprocess_compute_shader(
+        shader_source: ShaderSource,
+        entry_point: &str,
+    ) -> Result<String, ShaderError> {
+        let shader_source = shader_source.read_as_string()?;
+        let source = ShaderPreprocessor::with_shared(|preprocessor| {
+            preprocessor.parse_shader(shader_source)
+        })
+        .and_then(|result| result)
+        .map_err(ShaderError::ShaderPreprocessor)?;
+        validate_shader_entry_points(&source, &[(entry_point, ShaderStage::Compute)])?;
+
+        Ok(source)
+    }
+}
@@ -0,0 +1,18 @@
+use wgpu::{Features, Limits};
+
+/// Configures which [Features] and [Limits] a [GpuConnector](super::GpuConnector)
+/// requests from the adapter when creating the device.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GpuConnectorConfig {
+    pub required_features: Features,
+    pub required_limits: Limits,
+}
+
+impl Default for GpuConnectorConfig {
+    fn default() -> Self {
+        Self {
+            required_features: Features::default() | Features::POLYGON_MODE_LINE,
+            required_limits: Limits::default(),
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use std::fmt::Display;
+
+use wgpu::{Features, RequestAdapterError, RequestDeviceError};
+
+use crate::resources::ShaderError;
+
+#[derive(Debug)]
+pub enum GpuConnectorError {
+    /// Neither a suitable adapter nor a fallback adapter was found.
+    NoAdapters(RequestAdapterError),
+    /// The chosen adapter doesn't support all requested [Features].
+    MissingFeatures {
+        adapter: String,
+        missing: Features,
+    },
+    /// The chosen adapter can't satisfy the requested limits.
+    /// Each entry is the limit name, the requested and the allowed value.
+    LimitsExceeded {
+        adapter: String,
+        limits: Vec<(&'static str, u64, u64)>,
+    },
+    RequestDevice(RequestDeviceError),
+}
+
+impl std::error::Error for GpuConnectorError {}
+
+impl Display for GpuConnectorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GpuConnectorError::NoAdapters(e) => {
+                write!(f, "No suitable GPU adapters or fallback adapter found: {e}")
+            }
+            GpuConnectorError::MissingFeatures { adapter, missing } => {
+                write!(
+                    f,
+                    "Adapter {adapter} is missing required features: {missing:?}"
+                )
+            }
+            GpuConnectorError::LimitsExceeded { adapter, limits } => {
+                write!(f, "Adapter {adapter} can't satisfy required limits:")?;
+                for (name, requested, allowed) in limits {
+                    write!(f, " {name} (requested {requested}, allowed {allowed})")?;
+                }
+
+                Ok(())
+            }
+            GpuConnectorError::RequestDevice(e) => write!(f, "Failed requesting device: {e}"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ComputeError {
+    Shader(ShaderError),
+    /// The binding to read back doesn't exist or isn't a buffer.
+    InvalidReadBack {
+        binding: u32,
+    },
+    ReadBack(String),
+}
+
+impl std::error::Error for ComputeError {}
+
+impl Display for ComputeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ComputeError::Shader(e) => write!(f, "Compute shader failed: {e}"),
+            ComputeError::InvalidReadBack { binding } => {
+                write!(
+                    f,
+                    "Binding {binding} can't be read back, as it isn't a buffer"
+                )
+            }
+            ComputeError::ReadBack(e) => write!(f, "Failed reading back buffer: {e}"),
+        }
+    }
+}
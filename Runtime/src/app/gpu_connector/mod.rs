@@ -0,0 +1,451 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use wgpu::{
+    Adapter, Backend, BackendOptions, Backends, CompositeAlphaMode, Device, DeviceDescriptor,
+    DeviceLostReason, DeviceType, Instance, InstanceDescriptor, InstanceFlags,
+    MemoryBudgetThresholds, MemoryHints, PowerPreference, PresentMode, Queue, RequestAdapterError,
+    RequestAdapterOptions, Surface, SurfaceConfiguration, TextureUsages, Trace,
+};
+
+use winit::dpi::PhysicalSize;
+
+use crate::logging::{debug, error, info, warn};
+
+mod config;
+pub use config::*;
+
+mod error;
+pub use error::*;
+
+mod compute;
+pub use compute::*;
+
+/// Bundles everything needed to talk to a GPU: the [Instance], the chosen
+/// [Adapter], the logical [Device] with its [Queue] and, if rendering to a
+/// window, the [Surface] with its [SurfaceConfiguration].
+///
+/// Fields are dropped in declaration order, which guarantees the surface is
+/// gone before the device.
+#[derive(Debug)]
+pub struct GpuConnector {
+    surface: Option<Surface<'static>>,
+    surface_configuration: Option<SurfaceConfiguration>,
+    queue: Queue,
+    device: Device,
+    adapter: Adapter,
+    instance: Instance,
+    /// Set once the device got lost, e.g. due to a driver reset.
+    device_lost: Arc<AtomicBool>,
+}
+
+impl GpuConnector {
+    /// Creates a connector without a surface, e.g. for headless rendering
+    /// or compute work.
+    pub fn new(instance: Instance, config: &GpuConnectorConfig) -> Result<Self, GpuConnectorError> {
+        let adapter = Self::make_adapter(&instance, None)?;
+
+        Self::from_adapter(instance, adapter, config)
+    }
+
+    /// Creates a connector presenting to `surface`.
+    /// Only adapters compatible with the surface are considered and the
+    /// surface gets configured for `size`.
+    pub fn with_surface(
+        instance: Instance,
+        surface: Surface<'static>,
+        size: PhysicalSize<u32>,
+        vsync_enabled: bool,
+        config: &GpuConnectorConfig,
+    ) -> Result<Self, GpuConnectorError> {
+        let adapter = Self::make_adapter(&instance, Some(&surface))?;
+
+        let mut connector = Self::from_adapter(instance, adapter, config)?;
+        connector.attach_surface(surface, size, vsync_enabled);
+
+        Ok(connector)
+    }
+
+    /// Creates a connector using the given `adapter`.
+    /// Fails if the adapter doesn't support the requested features or
+    /// limits.
+    pub fn from_adapter(
+        instance: Instance,
+        adapter: Adapter,
+        config: &GpuConnectorConfig,
+    ) -> Result<Self, GpuConnectorError> {
+        let (device, queue) = Self::make_device_and_queue(&adapter, config)?;
+
+        Ok(Self::from_parts(instance, adapter, device, queue))
+    }
+
+    /// Creates a connector from already existing parts, without a surface.
+    pub fn from_parts(instance: Instance, adapter: Adapter, device: Device, queue: Queue) -> Self {
+        let device_lost = Arc::new(AtomicBool::new(false));
+        let device_lost_flag = device_lost.clone();
+        device.set_device_lost_callback(move |reason, message| {
+            match reason {
+                DeviceLostReason::Destroyed => debug!("GPU device destroyed: {message}"),
+                _ => error!("GPU device lost ({reason:?}): {message}"),
+            }
+            device_lost_flag.store(true, Ordering::Release);
+        });
+
+        Self {
+            surface: None,
+            surface_configuration: None,
+            queue,
+            device,
+            adapter,
+            instance,
+            device_lost,
+        }
+    }
+
+    /// Whether the [Device] got lost.
+    /// A lost device can't be used anymore and has to be recreated,
+    /// including all resources created with it.
+    pub fn is_device_lost(&self) -> bool {
+        self.device_lost.load(Ordering::Acquire)
+    }
+
+    fn make_adapter(
+        instance: &Instance,
+        compatible_surface: Option<&Surface>,
+    ) -> Result<Adapter, GpuConnectorError> {
+        let adapters_ranked = Self::retrieve_and_rank_adapters(instance, compatible_surface);
+
+        Self::choose_adapter(adapters_ranked, || {
+            pollster::block_on(instance.request_adapter(&RequestAdapterOptions {
+                power_preference: PowerPreference::LowPower,
+                force_fallback_adapter: true,
+                compatible_surface,
+            }))
+        })
+    }
+
+    /// Picks an adapter from the ranked list.
+    /// If no adapter was found, `request_fallback` is called to retrieve a
+    /// fallback (i.e. software) adapter instead.
+    pub(crate) fn choose_adapter(
+        mut adapters_ranked: Vec<(Adapter, (u8, u8, u128, usize))>,
+        request_fallback: impl FnOnce() -> Result<Adapter, RequestAdapterError>,
+    ) -> Result<Adapter, GpuConnectorError> {
+        if adapters_ranked.is_empty() {
+            warn!("No suitable GPU adapters found! Retrying with a fallback adapter ...");
+
+            let fallback_adapter = request_fallback().map_err(GpuConnectorError::NoAdapters)?;
+            info!(
+                "Chosen fallback adapter: {}\n{:?}",
+                fallback_adapter.get_info().name,
+                fallback_adapter.get_info()
+            );
+
+            return Ok(fallback_adapter);
+        }
+
+        let (chosen_adapter, chosen_score) = adapters_ranked.swap_remove(adapters_ranked.len() - 1);
+        info!(
+            "Chosen adapter: {} {:?}\n{:?}",
+            chosen_adapter.get_info().name,
+            chosen_score,
+            chosen_adapter.get_info()
+        );
+
+        Ok(chosen_adapter)
+    }
+
+    /// Creates an [Instance] with backends and flags taken from the
+    /// environment, if set.
+    pub fn make_instance() -> Instance {
+        let instance = Instance::new(&InstanceDescriptor {
+            backends: Backends::from_env().unwrap_or(Backends::all()),
+            flags: InstanceFlags::from_build_config().with_env(),
+            memory_budget_thresholds: MemoryBudgetThresholds::default(),
+            backend_options: BackendOptions::from_env_or_default(),
+        });
+
+        debug!("Instance: {instance:#?}");
+
+        instance
+    }
+
+    fn retrieve_and_rank_adapters(
+        instance: &Instance,
+        compatible_surface: Option<&Surface>,
+    ) -> Vec<(Adapter, (u8, u8, u128, usize))> {
+        let mut valid_adapters_ranked: Vec<_> = instance
+            .enumerate_adapters(Backends::all())
+            .into_iter()
+            // Remove any adapters that don't support the surface
+            .filter(|adapter| {
+                compatible_surface.is_none()
+                    || adapter.is_surface_supported(compatible_surface.unwrap())
+            })
+            // Initialize scoring
+            .map(|adapter| (adapter, (0u8, 0u8, 0u128, 0usize)))
+            // Map and match device types based on preference
+            .map(|(adapter, pri)| {
+                let device_pri = match adapter.get_info().device_type {
+                    DeviceType::DiscreteGpu => 3,
+                    DeviceType::IntegratedGpu => 2,
+                    DeviceType::VirtualGpu => 1,
+                    DeviceType::Cpu => 0,
+                    DeviceType::Other => 0,
+                };
+
+                (adapter, (device_pri, pri.1, pri.2, pri.3))
+            })
+            // Map and match device backends based on preference
+            .map(|(adapter, pri)| {
+                let backend_pri = match adapter.get_info().backend {
+                    // DX12 and Metal should be preferred where available (i.e. on Windows and macOS) over Vulkan
+                    Backend::Dx12 => 4,
+                    Backend::Metal => 4,
+                    // Vulkan is the universal default
+                    Backend::Vulkan => 3,
+                    // In Webbrowsers, only WebGPU should be available (or WebGL which should fall below into Backend::Gl). To prevent this from being chosen, somehow, on Desktop platforms over something more performant we set a lower score than above, but higher than OpenGL.
+                    Backend::BrowserWebGpu => 2,
+                    // OpenGL and Empty are not recommended at all and may not even work at all
+                    Backend::Gl => 1,
+                    Backend::Noop => 0,
+                };
+
+                (adapter, (pri.0, backend_pri, pri.2, pri.3))
+            })
+            // For each limit, increase the score.
+            // Thus, higher limits == higher score.
+            .map(|(adapter, pri)| {
+                let mut limits_sum = 0u128;
+                limits_sum += adapter.limits().max_texture_dimension_1d as u128;
+                limits_sum += adapter.limits().max_texture_dimension_2d as u128;
+                limits_sum += adapter.limits().max_texture_dimension_3d as u128;
+                limits_sum += adapter.limits().max_texture_array_layers as u128;
+                limits_sum += adapter.limits().max_bind_groups as u128;
+                limits_sum += adapter.limits().max_bindings_per_bind_group as u128;
+                limits_sum += adapter
+                    .limits()
+                    .max_dynamic_uniform_buffers_per_pipeline_layout
+                    as u128;
+                limits_sum += adapter
+                    .limits()
+                    .max_dynamic_storage_buffers_per_pipeline_layout
+                    as u128;
+                limits_sum += adapter.limits().max_sampled_textures_per_shader_stage as u128;
+                limits_sum += adapter.limits().max_samplers_per_shader_stage as u128;
+                limits_sum += adapter.limits().max_storage_buffers_per_shader_stage as u128;
+                limits_sum += adapter.limits().max_storage_textures_per_shader_stage as u128;
+                limits_sum += adapter.limits().max_uniform_buffers_per_shader_stage as u128;
+                limits_sum += adapter.limits().max_uniform_buffer_binding_size as u128;
+                limits_sum += adapter.limits().max_storage_buffer_binding_size as u128;
+                limits_sum += adapter.limits().max_vertex_buffers as u128;
+                limits_sum += adapter.limits().max_buffer_size as u128;
+                limits_sum += adapter.limits().max_vertex_attributes as u128;
+                limits_sum += adapter.limits().max_vertex_buffer_array_stride as u128;
+                limits_sum += adapter.limits().min_uniform_buffer_offset_alignment as u128;
+                limits_sum += adapter.limits().min_storage_buffer_offset_alignment as u128;
+                limits_sum += adapter.limits().max_inter_stage_shader_components as u128;
+                limits_sum += adapter.limits().max_color_attachments as u128;
+                limits_sum += adapter.limits().max_color_attachment_bytes_per_sample as u128;
+                limits_sum += adapter.limits().max_compute_workgroup_storage_size as u128;
+                limits_sum += adapter.limits().max_compute_invocations_per_workgroup as u128;
+                limits_sum += adapter.limits().max_compute_workgroup_size_x as u128;
+                limits_sum += adapter.limits().max_compute_workgroup_size_y as u128;
+                limits_sum += adapter.limits().max_compute_workgroup_size_z as u128;
+                limits_sum += adapter.limits().max_compute_workgroups_per_dimension as u128;
+                limits_sum += adapter.limits().min_subgroup_size as u128;
+                limits_sum += adapter.limits().max_subgroup_size as u128;
+                limits_sum += adapter.limits().max_push_constant_size as u128;
+                limits_sum += adapter.limits().max_non_sampler_bindings as u128;
+
+                (adapter, (pri.0, pri.1, limits_sum, pri.3))
+            })
+            // For each feature, increase the score.
+            // Thus, more features == higher score.
+            .map(|(adapter, pri)| {
+                let features_count = adapter.features().iter().count();
+
+                (adapter, (pri.0, pri.1, pri.2, features_count))
+            })
+            .collect::<Vec<_>>();
+
+        // Sort adapters
+        valid_adapters_ranked.sort_by(|a, b| b.1.cmp(&a.1));
+
+        info!("Following GPU adapters found:");
+        valid_adapters_ranked
+            .iter()
+            .enumerate()
+            .for_each(|(i, (adapter, score))| {
+                info!("#{}: {} {:?}", i, adapter.get_info().name, score)
+            });
+
+        valid_adapters_ranked
+    }
+
+    fn make_device_and_queue(
+        adapter: &Adapter,
+        config: &GpuConnectorConfig,
+    ) -> Result<(Device, Queue), GpuConnectorError> {
+        let missing = config.required_features - adapter.features();
+        if !missing.is_empty() {
+            return Err(GpuConnectorError::MissingFeatures {
+                adapter: adapter.get_info().name,
+                missing,
+            });
+        }
+
+        let mut limits = Vec::new();
+        config.required_limits.check_limits_with_fail_fn(
+            &adapter.limits(),
+            false,
+            |name, requested, allowed| limits.push((name, requested, allowed)),
+        );
+        if !limits.is_empty() {
+            return Err(GpuConnectorError::LimitsExceeded {
+                adapter: adapter.get_info().name,
+                limits,
+            });
+        }
+
+        let (device, queue) = pollster::block_on(adapter.request_device(&DeviceDescriptor {
+            label: Some("Orbital GPU"),
+            required_features: config.required_features,
+            required_limits: config.required_limits.clone(),
+            memory_hints: MemoryHints::Performance,
+            trace: Trace::Off,
+        }))
+        .map_err(GpuConnectorError::RequestDevice)?;
+        debug!("Device: {device:?}");
+        debug!("Queue: {queue:?}");
+
+        Ok((device, queue))
+    }
+
+    /// Creates a [SurfaceConfiguration] for `surface` with the given size.
+    /// The first format and present mode the adapter reports are used, with
+    /// the present mode being replaced by the vsync preference.
+    pub fn make_surface_configuration(
+        surface: &Surface,
+        adapter: &Adapter,
+        window_size: PhysicalSize<u32>,
+        vsync_enabled: bool,
+    ) -> SurfaceConfiguration {
+        let caps = surface.get_capabilities(adapter);
+        let mut surface_configuration = SurfaceConfiguration {
+            usage: TextureUsages::RENDER_ATTACHMENT,
+            format: *caps
+                .formats
+                .first()
+                .expect("Surface is required to have at least one format set!"),
+            width: window_size.width,
+            height: window_size.height,
+            desired_maximum_frame_latency: 2,
+            present_mode: *caps
+                .present_modes
+                .first()
+                .expect("Surface is required to have at least one present mode set!"),
+            alpha_mode: CompositeAlphaMode::Auto,
+            view_formats: vec![],
+        };
+
+        surface_configuration.present_mode = match vsync_enabled {
+            true => PresentMode::AutoVsync,
+            false => PresentMode::AutoNoVsync,
+        };
+
+        // Add SRGB view format
+        surface_configuration
+            .view_formats
+            .push(surface_configuration.format.add_srgb_suffix());
+
+        surface_configuration
+    }
+
+    /// Attaches `surface` to this connector, reusing the existing device.
+    /// Any previously attached surface is released first.
+    pub fn attach_surface(
+        &mut self,
+        surface: Surface<'static>,
+        size: PhysicalSize<u32>,
+        vsync_enabled: bool,
+    ) {
+        if !self.adapter.is_surface_supported(&surface) {
+            warn!(
+                "Adapter {} doesn't report support for the attached surface!",
+                self.adapter.get_info().name
+            );
+        }
+
+        self.release_surface();
+        self.surface_configuration = Some(Self::make_surface_configuration(
+            &surface,
+            &self.adapter,
+            size,
+            vsync_enabled,
+        ));
+        self.surface = Some(surface);
+
+        self.configure_surface();
+    }
+
+    /// Reconfigures the surface with a new size.
+    /// Sizes are clamped to at least 1x1 as a surface can't be configured
+    /// with a zero size (e.g. while a window is minimized).
+    /// Does nothing if there is no surface.
+    pub fn resize(&mut self, size: PhysicalSize<u32>) {
+        if let Some(surface_configuration) = self.surface_configuration.as_mut() {
+            surface_configuration.width = size.width.max(1);
+            surface_configuration.height = size.height.max(1);
+        }
+
+        self.configure_surface();
+    }
+
+    /// Applies the current [SurfaceConfiguration] to the surface.
+    /// Does nothing if there is no surface.
+    pub fn configure_surface(&self) {
+        if let (Some(surface), Some(surface_configuration)) =
+            (&self.surface, &self.surface_configuration)
+        {
+            surface.configure(&self.device, surface_configuration);
+        }
+    }
+
+    /// Drops the surface and its configuration, keeping the device alive.
+    pub fn release_surface(&mut self) {
+        self.surface_configuration = None;
+        self.surface = None;
+    }
+
+    pub fn instance(&self) -> &Instance {
+        &self.instance
+    }
+
+    pub fn adapter(&self) -> &Adapter {
+        &self.adapter
+    }
+
+    pub fn device(&self) -> &Device {
+        &self.device
+    }
+
+    pub fn queue(&self) -> &Queue {
+        &self.queue
+    }
+
+    pub fn surface(&self) -> Option<&Surface<'static>> {
+        self.surface.as_ref()
+    }
+
+    pub fn surface_configuration(&self) -> Option<&SurfaceConfiguration> {
+        self.surface_configuration.as_ref()
+    }
+
+    /// Changes to the configuration only apply once
+    /// [Self::configure_surface] is called.
+    pub fn surface_configuration_mut(&mut self) -> Option<&mut SurfaceConfiguration> {
+        self.surface_configuration.as_mut()
+    }
+}
@@ -36,6 +36,20 @@ pub enum InputEvent {
         device_id: DeviceId,
         delta: (f64, f64),
     },
+    /// Committed text, either produced by a key press or committed by an IME.  
+    /// May contain control characters, such as backspace (`\u{8}`).
+    Text {
+        string: String,
+    },
+    /// IME composition (pre-edit) text that hasn't been committed yet.  
+    /// An empty string ends the composition.
+    TextComposition {
+        string: String,
+    },
+    /// Emitted when an IME gets enabled or disabled.
+    ImeState {
+        enabled: bool,
+    },
     DeviceConnected {
         device_id: DeviceId,
     },
@@ -12,3 +12,14 @@ pub use button::*;
 
 mod axis;
 pub use axis::*;
+
+mod text;
+pub use text::*;
+
+#[cfg(feature = "gamepad_input")]
+mod rumble;
+#[cfg(feature = "gamepad_input")]
+pub use rumble::*;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,115 @@
+use std::time::{Duration, Instant};
+
+use gilrs::{
+    ff::{BaseEffect, BaseEffectType, Effect, EffectBuilder, Replay, Ticks},
+    GamepadId, Gilrs,
+};
+use log::{debug, warn};
+
+/// Rumble (force feedback) to play on gamepads.
+/// Send it via [AppEvent::Rumble](crate::app::AppEvent::Rumble).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rumble {
+    /// The gamepad to rumble.
+    /// `None` rumbles all connected gamepads.
+    pub gamepad: Option<GamepadId>,
+    /// Magnitude of the strong, low frequency motor, from `0.0` to `1.0`.
+    pub strong: f32,
+    /// Magnitude of the weak, high frequency motor, from `0.0` to `1.0`.
+    pub weak: f32,
+    pub duration: Duration,
+}
+
+impl Rumble {
+    /// Converts a magnitude from `0.0` to `1.0` into the range used by
+    /// [gilrs].
+    pub fn magnitude_to_u16(magnitude: f32) -> u16 {
+        (magnitude.clamp(0.0, 1.0) * u16::MAX as f32).round() as u16
+    }
+
+    fn make_effect(
+        &self,
+        gamepads: &[GamepadId],
+        gil: &mut Gilrs,
+    ) -> Result<Effect, gilrs::ff::Error> {
+        let scheduling = Replay {
+            play_for: Ticks::from_ms(self.duration.as_millis().min(u32::MAX as u128) as u32),
+            ..Default::default()
+        };
+
+        EffectBuilder::new()
+            .add_effect(BaseEffect {
+                kind: BaseEffectType::Strong {
+                    magnitude: Self::magnitude_to_u16(self.strong),
+                },
+                scheduling,
+                ..Default::default()
+            })
+            .add_effect(BaseEffect {
+                kind: BaseEffectType::Weak {
+                    magnitude: Self::magnitude_to_u16(self.weak),
+                },
+                scheduling,
+                ..Default::default()
+            })
+            .gamepads(gamepads)
+            .finish(gil)
+    }
+}
+
+/// Plays [Rumble]s and keeps their effects alive until they are done,
+/// as [gilrs] stops effects once they are dropped.
+#[derive(Default)]
+pub struct RumblePlayer {
+    effects: Vec<(Effect, Instant)>,
+}
+
+impl RumblePlayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts playing the given [Rumble].
+    /// Gamepads without force feedback support are skipped, if none is
+    /// left, nothing happens.
+    pub fn play(&mut self, rumble: Rumble, gil: &mut Gilrs) {
+        let gamepads = gil
+            .gamepads()
+            .filter(|(id, _)| rumble.gamepad.is_none_or(|gamepad| gamepad == *id))
+            .filter(|(id, gamepad)| {
+                let supported = gamepad.is_ff_supported();
+                if !supported {
+                    debug!("Gamepad {id} doesn't support force feedback, skipping rumble");
+                }
+                supported
+            })
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>();
+
+        if gamepads.is_empty() {
+            debug!("No gamepad to rumble found for {rumble:?}");
+            return;
+        }
+
+        match rumble
+            .make_effect(&gamepads, gil)
+            .and_then(|effect| effect.play().map(|_| effect))
+        {
+            Ok(effect) => self
+                .effects
+                .push((effect, Instant::now() + rumble.duration)),
+            Err(e) => warn!("Failed playing rumble {rumble:?}: {e}"),
+        }
+    }
+
+    /// Drops all effects that finished playing.
+    pub fn update(&mut self) {
+        let now = Instant::now();
+        self.effects.retain(|(_, end)| *end > now);
+    }
+
+    /// Number of effects currently playing.
+    pub fn playing(&self) -> usize {
+        self.effects.len()
+    }
+}
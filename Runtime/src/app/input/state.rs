@@ -3,9 +3,10 @@ use gilrs::Axis;
 use hashbrown::HashMap;
 use log::warn;
 use winit::dpi::PhysicalSize;
-use winit::event::{ElementState, MouseScrollDelta};
+use winit::event::{ElementState, KeyEvent, MouseScrollDelta};
+use winit::keyboard::{Key, NamedKey};
 
-use super::{InputAxis, InputButton, InputEvent, InputId};
+use super::{InputAxis, InputButton, InputEvent, InputId, TextInput};
 
 #[derive(Debug, Clone)]
 pub struct InputState {
@@ -13,6 +14,10 @@ pub struct InputState {
     delta_states: HashMap<InputId, HashMap<InputAxis, Vector2<f64>>>,
     mouse_cursor_position_state: Vector2<f64>,
     surface_size: Option<Vector2<u64>>,
+    scale_factor: f64,
+    key_presses: Vec<KeyEvent>,
+    text_input: TextInput,
+    mouse_captured: bool,
 }
 
 impl Default for InputState {
@@ -28,13 +33,21 @@ impl InputState {
             delta_states: HashMap::new(),
             mouse_cursor_position_state: Vector2::zero(),
             surface_size: None,
+            scale_factor: 1.0,
+            key_presses: Vec::new(),
+            text_input: TextInput::new(),
+            mouse_captured: true,
         }
     }
 
-    /// Resets all delta values back to zero.
+    /// Resets all delta values back to zero and clears recorded key presses
+    /// as well as entered text.
     /// This should be called after updating, but before the next cycle.
     /// I.e. after rendering is a good time.
     pub fn reset_deltas(&mut self) {
+        self.key_presses.clear();
+        self.text_input.reset();
+
         self.delta_states.iter_mut().for_each(|(_, state)| {
             state
                 .iter_mut()
@@ -57,14 +70,21 @@ impl InputState {
                 device_id,
                 event,
                 is_synthetic: _,
-            } => (
-                InputId::KeyboardOrMouse(device_id),
-                Some((
-                    InputButton::Keyboard(event.physical_key),
-                    event.state == ElementState::Pressed,
-                )),
-                None,
-            ),
+            } => {
+                if event.state == ElementState::Pressed {
+                    self.key_presses.push(event.clone());
+                    self.handle_key_text(&event.logical_key, event.text.as_deref());
+                }
+
+                (
+                    InputId::KeyboardOrMouse(device_id),
+                    Some((
+                        InputButton::Keyboard(event.physical_key),
+                        event.state == ElementState::Pressed,
+                    )),
+                    None,
+                )
+            }
             InputEvent::MouseButton {
                 device_id,
                 state,
@@ -101,6 +121,18 @@ impl InputState {
                 self.mouse_cursor_position_state = vector_delta;
                 return; // No further processing required!
             }
+            InputEvent::Text { string } => {
+                self.text_input.commit(&string);
+                return;
+            }
+            InputEvent::TextComposition { string } => {
+                self.text_input.compose(&string);
+                return;
+            }
+            InputEvent::ImeState { enabled } => {
+                self.text_input.set_ime_enabled(enabled);
+                return;
+            }
             InputEvent::MouseMovedDelta { device_id, delta } => {
                 let vector_delta = Vector2::new(delta.0, delta.1);
 
@@ -213,10 +245,84 @@ impl InputState {
         }
     }
 
+    /// Feeds the text produced by a key press into the [TextInput].
+    pub(super) fn handle_key_text(&mut self, logical_key: &Key, text: Option<&str>) {
+        if let Key::Named(NamedKey::Backspace) = logical_key {
+            // IMEs only consume Backspace while composing, which
+            // TextInput::backspace ignores.
+            // Otherwise, nothing but the key press deletes text.
+            self.text_input.backspace();
+        } else if !self.text_input.is_ime_enabled() {
+            // While an IME is active, text arrives committed by the IME.
+            if let Some(text) = text {
+                self.text_input.commit(text);
+            }
+        }
+    }
+
+    /// Returns all keyboard key presses, including repeats, since the last
+    /// [`Self::reset_deltas`] in the order they happened.
+    /// Useful for text input, as each [`KeyEvent`] carries the produced text.
+    pub fn key_presses(&self) -> &[KeyEvent] {
+        &self.key_presses
+    }
+
+    /// Returns the text entered this frame.  
+    /// See [TextInput] for details.
+    pub fn text_input(&self) -> &TextInput {
+        &self.text_input
+    }
+
+    pub fn text_input_mut(&mut self) -> &mut TextInput {
+        &mut self.text_input
+    }
+
+    /// The mouse cursor position in physical pixels.
     pub fn mouse_cursor_position_state(&self) -> Vector2<f64> {
         self.mouse_cursor_position_state
     }
 
+    /// The mouse cursor position in logical pixels, i.e. divided by the
+    /// current [scale factor](Self::scale_factor).
+    pub fn mouse_cursor_position_logical(&self) -> Vector2<f64> {
+        self.mouse_cursor_position_state / self.scale_factor
+    }
+
+    /// The current scale factor (DPI) of the window.
+    /// Defaults to `1.0`.
+    pub fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+
+    /// Updates the scale factor.
+    /// The last known cursor position is converted into the new physical
+    /// pixel space, so it stays consistent until the next cursor movement.
+    pub fn set_scale_factor(&mut self, scale_factor: f64) {
+        self.mouse_cursor_position_state *= scale_factor / self.scale_factor;
+        self.scale_factor = scale_factor;
+    }
+
+    /// Whether the mouse is currently captured by the window.
+    /// Defaults to `true`.
+    ///
+    /// Changed via [AppEvent::ChangeMouseCapture](crate::app::AppEvent::ChangeMouseCapture).
+    pub fn mouse_captured(&self) -> bool {
+        self.mouse_captured
+    }
+
+    /// Updates the mouse capture state.
+    /// Any mouse movement accumulated so far is discarded, so toggling the
+    /// capture never results in a sudden jump.
+    pub fn set_mouse_captured(&mut self, captured: bool) {
+        self.mouse_captured = captured;
+
+        self.delta_states.iter_mut().for_each(|(_, state)| {
+            if let Some(delta) = state.get_mut(&InputAxis::MouseMovement) {
+                *delta = Vector2::zero();
+            }
+        });
+    }
+
     pub fn button_state_specific(
         &self,
         input_button: &InputButton,
@@ -404,4 +510,9 @@ impl InputState {
     pub fn surface_resize(&mut self, size: PhysicalSize<u32>) {
         self.surface_size = Some(Vector2::new(size.width as u64, size.height as u64));
     }
+
+    /// The surface size in physical pixels, if known.
+    pub fn surface_size(&self) -> Option<Vector2<u64>> {
+        self.surface_size
+    }
 }
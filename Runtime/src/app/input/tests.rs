@@ -0,0 +1,129 @@
+use super::{InputEvent, InputState, TextInput};
+use winit::keyboard::{Key, NamedKey};
+
+fn text(string: &str) -> InputEvent {
+    InputEvent::Text {
+        string: string.into(),
+    }
+}
+
+#[test]
+fn text_accumulates() {
+    let mut state = InputState::new();
+
+    state.handle_event(text("Hel"));
+    state.handle_event(text("lo"));
+    assert_eq!(state.text_input().committed(), "Hello");
+}
+
+#[test]
+fn text_resets_each_frame() {
+    let mut state = InputState::new();
+    let mut field = String::new();
+
+    state.handle_event(text("Hello"));
+    state.text_input().apply_to(&mut field);
+
+    // Text is a delta, just like mouse movement
+    state.reset_deltas();
+    assert!(state.text_input().is_empty());
+    assert_eq!(state.text_input().committed(), "");
+
+    state.handle_event(text(" 世界"));
+    state.text_input().apply_to(&mut field);
+    assert_eq!(field, "Hello 世界");
+}
+
+#[test]
+fn text_backspace() {
+    let mut state = InputState::new();
+
+    state.handle_event(text("abc"));
+    state.handle_event(text(&TextInput::BACKSPACE.to_string()));
+    assert_eq!(state.text_input().committed(), "ab");
+
+    state.handle_event(text("\u{8}\u{8}\u{8}x"));
+    assert_eq!(state.text_input().committed(), "x");
+    // Deleting past this frame's text deletes earlier text
+    assert_eq!(state.text_input().backspaces(), 1);
+
+    let mut field = String::from("previous");
+    state.text_input().apply_to(&mut field);
+    assert_eq!(field, "previoux");
+}
+
+#[test]
+fn text_ignores_control_characters() {
+    let mut state = InputState::new();
+
+    state.handle_event(text("a\r\n\u{1b}b"));
+
+    assert_eq!(state.text_input().committed(), "ab");
+}
+
+#[test]
+fn text_composition() {
+    let mut state = InputState::new();
+
+    state.handle_event(InputEvent::ImeState { enabled: true });
+    state.handle_event(text("a"));
+    state.handle_event(InputEvent::TextComposition {
+        string: "に".into(),
+    });
+    state.handle_event(InputEvent::TextComposition {
+        string: "にほ".into(),
+    });
+    assert_eq!(state.text_input().committed(), "a");
+    assert_eq!(state.text_input().composition(), "にほ");
+
+    // Backspace during composition is handled by the IME
+    state.text_input_mut().backspace();
+    assert_eq!(state.text_input().committed(), "a");
+
+    state.handle_event(text("日本"));
+    assert_eq!(state.text_input().committed(), "a日本");
+    assert_eq!(state.text_input().composition(), "");
+
+    state.handle_event(InputEvent::TextComposition {
+        string: "ご".into(),
+    });
+    state.handle_event(InputEvent::ImeState { enabled: false });
+    assert_eq!(state.text_input().composition(), "");
+    assert!(!state.text_input().is_ime_enabled());
+}
+
+#[test]
+fn text_backspace_key_with_ime() {
+    let mut state = InputState::new();
+    let backspace = Key::Named(NamedKey::Backspace);
+
+    state.handle_event(InputEvent::ImeState { enabled: true });
+    state.handle_event(text("ab"));
+
+    // Without a composition, the IME leaves Backspace to the application
+    state.handle_key_text(&backspace, Some("\u{8}"));
+    assert_eq!(state.text_input().committed(), "a");
+
+    // Key text is committed by the IME instead
+    state.handle_key_text(&Key::Character("x".into()), Some("x"));
+    assert_eq!(state.text_input().committed(), "a");
+
+    state.handle_event(InputEvent::TextComposition {
+        string: "に".into(),
+    });
+    state.handle_key_text(&backspace, Some("\u{8}"));
+    assert_eq!(state.text_input().committed(), "a");
+    assert_eq!(state.text_input().composition(), "に");
+}
+
+#[cfg(feature = "gamepad_input")]
+#[test]
+fn rumble_magnitude_conversion() {
+    use super::Rumble;
+
+    assert_eq!(Rumble::magnitude_to_u16(0.0), 0);
+    assert_eq!(Rumble::magnitude_to_u16(0.5), 32768);
+    assert_eq!(Rumble::magnitude_to_u16(1.0), u16::MAX);
+    assert_eq!(Rumble::magnitude_to_u16(-1.0), 0);
+    assert_eq!(Rumble::magnitude_to_u16(2.0), u16::MAX);
+}
@@ -0,0 +1,128 @@
+/// Text entered during the current frame, filled by
+/// [InputEvent::Text](super::InputEvent::Text) and
+/// [InputEvent::TextComposition](super::InputEvent::TextComposition) events.
+///
+/// Like other input deltas, committed text only covers a single frame and is
+/// cleared by [InputState::reset_deltas](super::InputState::reset_deltas).
+/// Consumers, like a text field, keep their own text and
+/// [apply](TextInput::apply_to) each frame's input to it.
+///
+/// Text is split into three parts:
+/// - [TextInput::backspaces]: How many characters entered before this frame
+///   got deleted.
+/// - [TextInput::committed]: Text that has been entered this frame, either
+///   directly by a key press or committed by an IME.
+/// - [TextInput::composition]: Text that is currently being composed by an
+///   IME (pre-edit), but not yet committed.
+///   This should be displayed (e.g. underlined) after the committed text,
+///   but must not be treated as entered yet.
+///   Unlike committed text, the composition persists across frames.
+///   IMEs only compose while allowed, see
+///   [AppEvent::ChangeImeAllowed](crate::app::AppEvent::ChangeImeAllowed).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TextInput {
+    backspaces: usize,
+    committed: String,
+    composition: String,
+    ime_enabled: bool,
+}
+
+impl TextInput {
+    /// Backspace control character, as produced by most platforms when
+    /// pressing the backspace key.
+    pub const BACKSPACE: char = '\u{8}';
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Commits text to the buffer.
+    ///
+    /// Any [TextInput::BACKSPACE] removes the last committed character.
+    /// Other control characters (e.g. return or escape) are ignored.
+    /// Committing also ends any active composition.
+    pub fn commit(&mut self, text: &str) {
+        self.composition.clear();
+
+        for c in text.chars() {
+            if c == Self::BACKSPACE {
+                self.backspace();
+            } else if !c.is_control() {
+                self.committed.push(c);
+            }
+        }
+    }
+
+    /// Replaces the current IME composition (pre-edit) text.  
+    /// An empty string ends the composition.
+    pub fn compose(&mut self, text: &str) {
+        self.composition.clear();
+        self.composition.push_str(text);
+    }
+
+    /// Removes the last committed character.  
+    /// If nothing has been committed this frame, a character entered in an
+    /// earlier frame is deleted instead, see [TextInput::backspaces].
+    ///
+    /// Does nothing while a composition is active, as the IME handles
+    /// editing of the composition itself.
+    pub fn backspace(&mut self) {
+        if !self.composition.is_empty() {
+            return;
+        }
+
+        if self.committed.pop().is_none() {
+            self.backspaces += 1;
+        }
+    }
+
+    pub fn set_ime_enabled(&mut self, enabled: bool) {
+        self.ime_enabled = enabled;
+
+        if !enabled {
+            self.composition.clear();
+        }
+    }
+
+    /// Whether an IME is currently active.  
+    /// While active, text is expected to arrive committed by the IME
+    /// instead of by key presses.
+    pub fn is_ime_enabled(&self) -> bool {
+        self.ime_enabled
+    }
+
+    /// Number of characters to delete from text entered before this frame.
+    /// Backspaces always apply before [TextInput::committed].
+    pub fn backspaces(&self) -> usize {
+        self.backspaces
+    }
+
+    pub fn committed(&self) -> &str {
+        &self.committed
+    }
+
+    pub fn composition(&self) -> &str {
+        &self.composition
+    }
+
+    /// Whether any text has been entered or deleted this frame.
+    pub fn is_empty(&self) -> bool {
+        self.backspaces == 0 && self.committed.is_empty()
+    }
+
+    /// Applies this frame's [backspaces](TextInput::backspaces) and
+    /// [committed text](TextInput::committed) to `text`.
+    pub fn apply_to(&self, text: &mut String) {
+        for _ in 0..self.backspaces {
+            text.pop();
+        }
+        text.push_str(&self.committed);
+    }
+
+    /// Clears this frame's input.
+    /// The composition and IME state are kept, as they span frames.
+    pub fn reset(&mut self) {
+        self.backspaces = 0;
+        self.committed.clear();
+    }
+}
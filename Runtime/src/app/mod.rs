@@ -36,11 +36,26 @@ pub use app_event::*;
 mod timer;
 pub use timer::*;
 
+mod frame_recovery;
+pub use frame_recovery::*;
+
+mod scale_factor;
+pub use scale_factor::*;
+
+mod control_flow;
+pub use control_flow::*;
+
+mod gpu_connector;
+pub use gpu_connector::*;
+
 pub mod input;
 use input::*;
 
 pub mod standard;
 
+#[cfg(test)]
+mod tests;
+
 /// The main application trait that defines the interface between your application
 /// and the underlying platform-specific event loop.
 ///
@@ -1,29 +1,28 @@
 use std::mem::transmute;
 use std::thread;
+use std::time::Instant;
 
 use async_std::task::block_on;
 use cgmath::Vector2;
 use gilrs::Gilrs;
-use wgpu::{
-    Adapter, Backend, BackendOptions, Backends, CompositeAlphaMode, Device, DeviceDescriptor,
-    DeviceType, Features, Instance, InstanceDescriptor, InstanceFlags, Limits,
-    MemoryBudgetThresholds, MemoryHints, PresentMode, Queue, Surface, SurfaceConfiguration,
-    SurfaceError, SurfaceTexture, TextureUsages, TextureViewDescriptor, Trace,
-};
+use wgpu::{Surface, SurfaceError, SurfaceTexture, TextureViewDescriptor};
 use winit::{
     application::ApplicationHandler,
-    dpi::PhysicalSize,
     error::EventLoopError,
-    event::{DeviceEvent, DeviceId, WindowEvent},
+    event::{DeviceEvent, DeviceId, Ime, StartCause, WindowEvent},
     event_loop::{ActiveEventLoop, EventLoop},
     window::{CursorGrabMode, Window, WindowId},
 };
 
+#[cfg(feature = "gamepad_input")]
+use super::input::RumblePlayer;
+use super::{
+    acquire_frame_with_recovery, App, AppSettings, FrameFailure, GpuConnector, GpuConnectorError,
+};
 use super::{
     input::{InputEvent, InputState},
-    Timer,
+    rescale_surface_configuration, Timer,
 };
-use super::{App, AppSettings};
 use crate::{
     app::AppEvent,
     element::Element,
@@ -38,17 +37,16 @@ pub struct AppRuntime<AppImpl: App> {
     runtime_settings: AppSettings,
     // Window related
     window: Option<Window>,
-    surface: Option<Surface<'static>>,
-    surface_configuration: Option<SurfaceConfiguration>,
     // Device related
-    instance: Option<Instance>,
-    adapter: Option<Adapter>,
-    device: Option<Device>,
-    queue: Option<Queue>,
+    connector: Option<GpuConnector>,
     timer: Option<Timer>,
+    /// Kept outside the [Timer], as it gets recreated on resumption.
+    paused: bool,
     input_state: InputState,
     #[cfg(feature = "gamepad_input")]
     gil: Gilrs,
+    #[cfg(feature = "gamepad_input")]
+    rumble_player: RumblePlayer,
 }
 
 impl<AppImpl: App> AppRuntime<AppImpl> {
@@ -69,263 +67,92 @@ impl<AppImpl: App> AppRuntime<AppImpl> {
             app_messages: Vec::new(),
             runtime_settings: settings,
             window: None,
-            surface: None,
-            surface_configuration: None,
-            instance: None,
-            adapter: None,
-            device: None,
-            queue: None,
+            connector: None,
             timer: None,
+            paused: false,
             input_state: InputState::new(),
             #[cfg(feature = "gamepad_input")]
             gil: Gilrs::new().expect("Gamepad input initialization failed!"),
+            #[cfg(feature = "gamepad_input")]
+            rumble_player: RumblePlayer::new(),
         };
 
         event_loop.run_app(&mut app_runtime)
     }
 
-    fn make_instance() -> Instance {
-        let instance = Instance::new(&InstanceDescriptor {
-            backends: Backends::from_env().unwrap_or(Backends::all()),
-            flags: InstanceFlags::from_build_config().with_env(),
-            memory_budget_thresholds: MemoryBudgetThresholds::default(),
-            backend_options: BackendOptions::from_env_or_default(),
-        });
-
-        debug!("Instance: {instance:#?}");
-
-        instance
-    }
-
-    fn retrieve_and_rank_adapters(
-        instance: &Instance,
-        compatible_surface: Option<&Surface>,
-    ) -> Vec<(Adapter, (u8, u8, u128, usize))> {
-        let mut valid_adapters_ranked: Vec<_> = instance
-            .enumerate_adapters(Backends::all())
-            .into_iter()
-            // Remove any adapters that don't support the surface
-            .filter(|adapter| {
-                compatible_surface.is_none()
-                    || adapter.is_surface_supported(compatible_surface.unwrap())
-            })
-            // Initialize scoring
-            .map(|adapter| (adapter, (0u8, 0u8, 0u128, 0usize)))
-            // Map and match device types based on preference
-            .map(|(adapter, pri)| {
-                let device_pri = match adapter.get_info().device_type {
-                    DeviceType::DiscreteGpu => 3,
-                    DeviceType::IntegratedGpu => 2,
-                    DeviceType::VirtualGpu => 1,
-                    DeviceType::Cpu => 0,
-                    DeviceType::Other => 0,
-                };
-
-                (adapter, (device_pri, pri.1, pri.2, pri.3))
-            })
-            // Map and match device backends based on preference
-            .map(|(adapter, pri)| {
-                let backend_pri = match adapter.get_info().backend {
-                    // DX12 and Metal should be preferred where available (i.e. on Windows and macOS) over Vulkan
-                    Backend::Dx12 => 4,
-                    Backend::Metal => 4,
-                    // Vulkan is the universal default
-                    Backend::Vulkan => 3,
-                    // In Webbrowsers, only WebGPU should be available (or WebGL which should fall below into Backend::Gl). To prevent this from being chosen, somehow, on Desktop platforms over something more performant we set a lower score than above, but higher than OpenGL.
-                    Backend::BrowserWebGpu => 2,
-                    // OpenGL and Empty are not recommended at all and may not even work at all
-                    Backend::Gl => 1,
-                    Backend::Noop => 0,
-                };
-
-                (adapter, (pri.0, backend_pri, pri.2, pri.3))
-            })
-            // For each limit, increase the score.
-            // Thus, higher limits == higher score.
-            .map(|(adapter, pri)| {
-                let mut limits_sum = 0u128;
-                limits_sum += adapter.limits().max_texture_dimension_1d as u128;
-                limits_sum += adapter.limits().max_texture_dimension_2d as u128;
-                limits_sum += adapter.limits().max_texture_dimension_3d as u128;
-                limits_sum += adapter.limits().max_texture_array_layers as u128;
-                limits_sum += adapter.limits().max_bind_groups as u128;
-                limits_sum += adapter.limits().max_bindings_per_bind_group as u128;
-                limits_sum += adapter
-                    .limits()
-                    .max_dynamic_uniform_buffers_per_pipeline_layout
-                    as u128;
-                limits_sum += adapter
-                    .limits()
-                    .max_dynamic_storage_buffers_per_pipeline_layout
-                    as u128;
-                limits_sum += adapter.limits().max_sampled_textures_per_shader_stage as u128;
-                limits_sum += adapter.limits().max_samplers_per_shader_stage as u128;
-                limits_sum += adapter.limits().max_storage_buffers_per_shader_stage as u128;
-                limits_sum += adapter.limits().max_storage_textures_per_shader_stage as u128;
-                limits_sum += adapter.limits().max_uniform_buffers_per_shader_stage as u128;
-                limits_sum += adapter.limits().max_uniform_buffer_binding_size as u128;
-                limits_sum += adapter.limits().max_storage_buffer_binding_size as u128;
-                limits_sum += adapter.limits().max_vertex_buffers as u128;
-                limits_sum += adapter.limits().max_buffer_size as u128;
-                limits_sum += adapter.limits().max_vertex_attributes as u128;
-                limits_sum += adapter.limits().max_vertex_buffer_array_stride as u128;
-                limits_sum += adapter.limits().min_uniform_buffer_offset_alignment as u128;
-                limits_sum += adapter.limits().min_storage_buffer_offset_alignment as u128;
-                limits_sum += adapter.limits().max_inter_stage_shader_components as u128;
-                limits_sum += adapter.limits().max_color_attachments as u128;
-                limits_sum += adapter.limits().max_color_attachment_bytes_per_sample as u128;
-                limits_sum += adapter.limits().max_compute_workgroup_storage_size as u128;
-                limits_sum += adapter.limits().max_compute_invocations_per_workgroup as u128;
-                limits_sum += adapter.limits().max_compute_workgroup_size_x as u128;
-                limits_sum += adapter.limits().max_compute_workgroup_size_y as u128;
-                limits_sum += adapter.limits().max_compute_workgroup_size_z as u128;
-                limits_sum += adapter.limits().max_compute_workgroups_per_dimension as u128;
-                limits_sum += adapter.limits().min_subgroup_size as u128;
-                limits_sum += adapter.limits().max_subgroup_size as u128;
-                limits_sum += adapter.limits().max_push_constant_size as u128;
-                limits_sum += adapter.limits().max_non_sampler_bindings as u128;
-
-                (adapter, (pri.0, pri.1, limits_sum, pri.3))
-            })
-            // For each feature, increase the score.
-            // Thus, more features == higher score.
-            .map(|(adapter, pri)| {
-                let features_count = adapter.features().iter().count();
-
-                (adapter, (pri.0, pri.1, pri.2, features_count))
-            })
-            .collect::<Vec<_>>();
-
-        // Sort adapters
-        valid_adapters_ranked.sort_by(|a, b| b.1.cmp(&a.1));
-
-        if valid_adapters_ranked.is_empty() {
-            panic!("No suitable GPU adapters found!");
-        }
-
-        info!("Following GPU adapters found:");
-        valid_adapters_ranked
-            .iter()
-            .enumerate()
-            .for_each(|(i, (adapter, score))| {
-                info!("#{}: {} {:?}", i, adapter.get_info().name, score)
-            });
-
-        valid_adapters_ranked
-    }
-
-    fn make_device_and_queue(adapter: &Adapter) -> (Device, Queue) {
-        let (device, queue) = pollster::block_on(adapter.request_device(&DeviceDescriptor {
-            label: Some("Orbital GPU"),
-            required_features: Features::default() | Features::POLYGON_MODE_LINE,
-            required_limits: Limits::default(),
-            memory_hints: MemoryHints::Performance,
-            trace: Trace::Off,
-        }))
-        .expect("Failed creating device from chosen adapter!");
-        debug!("Device: {device:?}");
-        debug!("Queue: {queue:?}");
-
-        (device, queue)
-    }
-
-    fn make_surface_configuration(
-        surface: &Surface,
-        adapter: &Adapter,
-        window_size: PhysicalSize<u32>,
-        vsync_enabled: bool,
-    ) -> SurfaceConfiguration {
-        let caps = surface.get_capabilities(adapter);
-        let mut surface_configuration = SurfaceConfiguration {
-            usage: TextureUsages::RENDER_ATTACHMENT,
-            format: *caps
-                .formats
-                .first()
-                .expect("Surface is required to have at least one format set!"),
-            width: window_size.width,
-            height: window_size.height,
-            desired_maximum_frame_latency: 2,
-            present_mode: *caps
-                .present_modes
-                .first()
-                .expect("Surface is required to have at least one present mode set!"),
-            alpha_mode: CompositeAlphaMode::Auto,
-            view_formats: vec![],
-        };
-
-        surface_configuration.present_mode = match vsync_enabled {
-            true => PresentMode::AutoVsync,
-            false => PresentMode::AutoNoVsync,
-        };
-
-        // Add SRGB view format
-        surface_configuration
-            .view_formats
-            .push(surface_configuration.format.add_srgb_suffix());
-
-        surface_configuration
-    }
-
     pub fn reconfigure_surface(&mut self)
     where
         Self: Sized + Send,
     {
-        self.surface.as_ref().unwrap().configure(
-            self.device.as_ref().unwrap(),
-            self.surface_configuration.as_ref().unwrap(),
-        );
+        self.connector.as_ref().unwrap().configure_surface();
+        self.notify_resize();
+    }
 
-        let config_ref = self.surface_configuration.as_ref().unwrap();
+    fn notify_resize(&mut self) {
+        let connector = self.connector.as_ref().unwrap();
+        let config_ref = connector.surface_configuration().unwrap();
 
         block_on(self.app.on_resize(
             Vector2 {
                 x: config_ref.width,
                 y: config_ref.height,
             },
-            self.device.as_ref().unwrap(),
-            self.queue.as_ref().unwrap(),
+            connector.device(),
+            connector.queue(),
         ));
     }
 
     pub fn acquire_next_frame(&mut self) -> Result<SurfaceTexture, SurfaceError> {
-        let surface = self.surface.as_ref().unwrap();
+        let surface = self.connector.as_ref().unwrap().surface().unwrap();
 
         surface.get_current_texture()
     }
 
-    pub fn redraw(&mut self) {
+    /// Renders the next frame.
+    /// Returns `true` if the app can't continue rendering and should exit.
+    pub fn redraw(&mut self) -> bool {
         // Check if surface and device are present
-        if self.surface.is_none() || self.device.is_none() {
+        if self
+            .connector
+            .as_ref()
+            .is_none_or(|connector| connector.surface().is_none())
+        {
             warn!("Redraw requested, but runtime is in an incomplete state!");
-            return;
+            return false;
+        }
+
+        if self
+            .connector
+            .as_ref()
+            .is_some_and(GpuConnector::is_device_lost)
+            && !self.recover_lost_device()
+        {
+            return true;
         }
 
         // Get next frame to render on
-        let frame = match self.acquire_next_frame() {
+        let surface = self.connector.as_ref().unwrap().surface().unwrap();
+        let frame = match acquire_frame_with_recovery(
+            || surface.get_current_texture(),
+            || {
+                let connector = self.connector.as_ref().unwrap();
+                connector.configure_surface();
+            },
+        ) {
             Ok(surface_texture) => surface_texture,
-            Err(e) => {
-                warn!("Failed to acquire next frame from surface: {e}");
-
-                warn!("Attempting reconfiguration ...");
-                self.reconfigure_surface();
-
-                match self.acquire_next_frame() {
-                    Ok(surface_texture) => surface_texture,
-                    Err(e) => panic!(
-                        "Failed to acquire next frame from surface after reconfiguration! ({e:?})"
-                    ),
-                }
-            }
+            Err(FrameFailure::Skip) => return false,
+            Err(FrameFailure::Exit) => return true,
         };
 
         let format = match self
-            .surface_configuration
+            .connector
             .as_ref()
+            .and_then(|connector| connector.surface_configuration())
             .unwrap()
             .view_formats
             .first()
         {
-            Some(format) => format,
+            Some(format) => *format,
             None => {
                 warn!("No view formats available for surface!");
 
@@ -333,30 +160,97 @@ impl<AppImpl: App> AppRuntime<AppImpl> {
                 self.reconfigure_surface();
 
                 match self
-                    .surface_configuration
+                    .connector
                     .as_ref()
+                    .and_then(|connector| connector.surface_configuration())
                     .unwrap()
                     .view_formats
                     .first()
                 {
-                    Some(format) => format,
+                    Some(format) => *format,
                     None => panic!("No view formats available for surface after reconfiguration!"),
                 }
             }
         };
 
         let view: wgpu::TextureView = frame.texture.create_view(&TextureViewDescriptor {
-            format: Some(*format),
+            format: Some(format),
             ..TextureViewDescriptor::default()
         });
 
-        block_on(self.app.on_render(
-            &view,
-            self.device.as_ref().unwrap(),
-            self.queue.as_ref().unwrap(),
-        ));
+        let connector = self.connector.as_ref().unwrap();
+        block_on(
+            self.app
+                .on_render(&view, connector.device(), connector.queue()),
+        );
 
         frame.present();
+
+        false
+    }
+
+    /// Creates the [GpuConnector] for the current window.
+    fn connect_gpu(&mut self) -> Result<(), GpuConnectorError> {
+        let window = self.window.as_ref().unwrap();
+
+        let instance = GpuConnector::make_instance();
+        let surface: Surface<'static> = unsafe {
+            transmute(
+                instance
+                    .create_surface(window)
+                    .expect("Failed creating Surface!"),
+            )
+        };
+
+        self.connector = Some(GpuConnector::with_surface(
+            instance,
+            surface,
+            window.inner_size(),
+            self.runtime_settings.vsync_enabled,
+            &self.runtime_settings.gpu,
+        )?);
+
+        Ok(())
+    }
+
+    /// Hands the current [GpuConnector] to [App::on_resume].
+    fn resume_app(&mut self) {
+        let connector = self.connector.as_ref().unwrap();
+        block_on(
+            self.app.on_resume(
+                connector
+                    .surface_configuration()
+                    .expect("SurfaceConfiguration must exist at this point!"),
+                connector.device(),
+                connector.queue(),
+            ),
+        );
+    }
+
+    /// Recreates the [GpuConnector] after the device got lost.  
+    /// The [App] gets suspended and resumed, so that it can recreate its GPU
+    /// resources with the new device.
+    ///
+    /// Returns `false` if no new device could be created.
+    fn recover_lost_device(&mut self) -> bool {
+        warn!("GPU device lost! Attempting to recreate it ...");
+
+        block_on(self.app.on_suspend());
+        if let Some(connector) = self.connector.as_mut() {
+            connector.release_surface();
+        }
+        self.connector = None;
+
+        if let Err(e) = self.connect_gpu() {
+            error!("Failed recreating GPU device: {e}");
+            return false;
+        }
+
+        self.reconfigure_surface();
+        self.resume_app();
+
+        info!("Recovered from device loss.");
+        true
     }
 
     #[cfg(feature = "gamepad_input")]
@@ -391,6 +285,9 @@ impl<AppImpl: App> AppRuntime<AppImpl> {
 
         self.input_state.reset_deltas();
 
+        #[cfg(feature = "gamepad_input")]
+        self.rumble_player.update();
+
         result
     }
 
@@ -437,6 +334,34 @@ impl<AppImpl: App> AppRuntime<AppImpl> {
                         warn!("Change cursor grabbing requested, but window does not exist!");
                     }
                 }
+                AppEvent::ChangeImeAllowed(allowed) => {
+                    // Kept for windows recreated on resume
+                    self.runtime_settings.ime_allowed = allowed;
+                    if let Some(window) = &self.window {
+                        window.set_ime_allowed(allowed);
+                    } else {
+                        warn!("Change of IME allowance requested, but window does not exist!");
+                    }
+                }
+                AppEvent::ChangeMouseCapture(capture) => {
+                    if let Some(window) = &self.window {
+                        let grab_mode = if capture {
+                            CursorGrabMode::Confined
+                        } else {
+                            CursorGrabMode::None
+                        };
+                        if let Err(e) = window.set_cursor_grab(grab_mode) {
+                            error!(
+                                "Failed to change cursor grab! This might not be supported on your platform. Error: {e}"
+                            );
+                        }
+                        window.set_cursor_visible(!capture);
+                    } else {
+                        warn!("Change mouse capture requested, but window does not exist!");
+                    }
+
+                    self.input_state.set_mouse_captured(capture);
+                }
                 AppEvent::RequestAppClosure => {
                     warn!("App closure was requested!");
                     exit_requested = true;
@@ -445,6 +370,18 @@ impl<AppImpl: App> AppRuntime<AppImpl> {
                     warn!("Force app closure was requested with exit code {exit_code}!");
                     std::process::exit(exit_code);
                 }
+                AppEvent::Pause => {
+                    self.paused = true;
+                    if let Some(timer) = &mut self.timer {
+                        timer.pause();
+                    }
+                }
+                AppEvent::Resume => {
+                    self.paused = false;
+                    if let Some(timer) = &mut self.timer {
+                        timer.resume();
+                    }
+                }
                 AppEvent::RequestRedraw => {
                     if let Some(window) = &self.window {
                         window.request_redraw();
@@ -455,6 +392,10 @@ impl<AppImpl: App> AppRuntime<AppImpl> {
                 AppEvent::SendMessage(message) => {
                     self.app_messages.push(message);
                 }
+                #[cfg(feature = "gamepad_input")]
+                AppEvent::Rumble(rumble) => {
+                    self.rumble_player.play(rumble, &mut self.gil);
+                }
             }
         }
 
@@ -469,6 +410,20 @@ impl<AppImpl: App> AppRuntime<AppImpl> {
 }
 
 impl<AppImpl: App> ApplicationHandler for AppRuntime<AppImpl> {
+    fn new_events(&mut self, event_loop: &ActiveEventLoop, cause: StartCause) {
+        let control_flow = self.runtime_settings.control_flow;
+
+        if cause == StartCause::Init {
+            event_loop.set_control_flow(control_flow.control_flow(Instant::now()));
+        }
+
+        if control_flow.redraw_on_wake(cause) {
+            if let Some(window) = &self.window {
+                window.request_redraw();
+            }
+        }
+    }
+
     fn resumed(&mut self, event_loop: &ActiveEventLoop) {
         // Remake all window, device, queue, etc. related structures
 
@@ -482,72 +437,30 @@ impl<AppImpl: App> ApplicationHandler for AppRuntime<AppImpl> {
                 )
                 .unwrap(),
         );
-
-        self.instance = Some(AppRuntime::<AppImpl>::make_instance());
-
-        self.surface = Some(unsafe {
-            transmute(
-                self.instance
-                    .as_ref()
-                    .unwrap()
-                    .create_surface(self.window.as_ref().unwrap())
-                    .expect("Failed creating Surface!"),
-            )
-        });
-
-        let mut adapters_ranked = AppRuntime::<AppImpl>::retrieve_and_rank_adapters(
-            self.instance
-                .as_ref()
-                .expect("Expected an Instance to exist by now!"),
-            self.surface.as_ref(),
-        );
-
-        let (chosen_adapter, chosen_score) = adapters_ranked.swap_remove(adapters_ranked.len() - 1);
-        info!(
-            "Chosen adapter: {} {:?}\n{:?}",
-            chosen_adapter.get_info().name,
-            chosen_score,
-            chosen_adapter.get_info()
-        );
-        self.adapter = Some(chosen_adapter);
+        self.window
+            .as_ref()
+            .unwrap()
+            .set_ime_allowed(self.runtime_settings.ime_allowed);
 
         let window_size = self.window.as_ref().unwrap().inner_size();
-        self.surface_configuration = Some(AppRuntime::<AppImpl>::make_surface_configuration(
-            self.surface
-                .as_ref()
-                .expect("Expected a Surface to exist by now!"),
-            self.adapter
-                .as_ref()
-                .expect("Expected an Adapter to exist by now!"),
-            window_size,
-            self.runtime_settings.vsync_enabled,
-        ));
+        self.input_state
+            .set_scale_factor(self.window.as_ref().unwrap().scale_factor());
+        self.input_state.surface_resize(window_size);
 
-        let (device, queue) = AppRuntime::<AppImpl>::make_device_and_queue(
-            self.adapter
-                .as_ref()
-                .expect("Expected an Adapter to be set by now!"),
-        );
-        self.device = Some(device);
-        self.queue = Some(queue);
+        if let Err(e) = self.connect_gpu() {
+            error!("Failed connecting to GPU: {e}");
+            event_loop.exit();
+            return;
+        }
 
-        self.timer = Some(Timer::new());
+        let mut timer = Timer::with_max_delta_time(self.runtime_settings.max_delta_time);
+        if self.paused {
+            timer.pause();
+        }
+        self.timer = Some(timer);
 
         self.reconfigure_surface();
-
-        block_on(
-            self.app.on_resume(
-                self.surface_configuration
-                    .as_ref()
-                    .expect("SurfaceConfiguration must exist at this point!"),
-                self.device
-                    .as_ref()
-                    .expect("Device must exist at this point!"),
-                self.queue
-                    .as_ref()
-                    .expect("Queue must exist at this point!"),
-            ),
-        );
+        self.resume_app();
     }
 
     fn suspended(&mut self, _event_loop: &ActiveEventLoop) {
@@ -565,18 +478,15 @@ impl<AppImpl: App> ApplicationHandler for AppRuntime<AppImpl> {
         // Invalidate everything related to the window, surface and device.
         // Important: Drop resources in the correct order with delays to prevent segfaults
         debug!("Dropping all resources...");
-        self.surface_configuration = None;
-
         // Drop the surface before the device to prevent Vulkan validation errors
-        self.surface = None;
+        if let Some(connector) = self.connector.as_mut() {
+            connector.release_surface();
+        }
 
         // Add a small delay before dropping device to ensure all GPU operations complete
         thread::sleep(std::time::Duration::from_millis(50));
 
-        self.queue = None;
-        self.device = None;
-        self.adapter = None;
-        self.instance = None;
+        self.connector = None;
         self.window = None;
         self.timer = None;
 
@@ -609,10 +519,20 @@ impl<AppImpl: App> ApplicationHandler for AppRuntime<AppImpl> {
                     self.exit(event_loop);
                     return;
                 }
-                self.redraw();
+                if self.redraw() {
+                    error!("Rendering can't continue, shutting down!");
+
+                    self.exit(event_loop);
+                    return;
+                }
+
+                let control_flow = self.runtime_settings.control_flow;
+                event_loop.set_control_flow(control_flow.control_flow(Instant::now()));
 
                 #[cfg(feature = "auto_request_redraw")]
-                self.window.as_ref().unwrap().request_redraw();
+                if control_flow.redraw_after_frame() {
+                    self.window.as_ref().unwrap().request_redraw();
+                }
 
                 None
             }
@@ -650,21 +570,49 @@ impl<AppImpl: App> ApplicationHandler for AppRuntime<AppImpl> {
                 device_id,
                 position,
             }),
+            WindowEvent::Ime(ime) => match ime {
+                Ime::Enabled => Some(InputEvent::ImeState { enabled: true }),
+                Ime::Preedit(string, _) => Some(InputEvent::TextComposition { string }),
+                Ime::Commit(string) => Some(InputEvent::Text { string }),
+                Ime::Disabled => Some(InputEvent::ImeState { enabled: false }),
+            },
             WindowEvent::Resized(new_size) => {
-                self.surface_configuration =
-                    Some(AppRuntime::<AppImpl>::make_surface_configuration(
-                        self.surface.as_ref().unwrap(),
-                        self.adapter.as_ref().unwrap(),
-                        new_size,
-                        self.runtime_settings.vsync_enabled,
-                    ));
-
-                self.reconfigure_surface();
+                self.connector.as_mut().unwrap().resize(new_size);
+                self.notify_resize();
 
                 self.input_state.surface_resize(new_size);
 
                 None
             }
+            WindowEvent::ScaleFactorChanged {
+                scale_factor,
+                mut inner_size_writer,
+            } => {
+                debug!("Scale factor changed to {scale_factor}");
+
+                if let Some(surface_configuration) = self
+                    .connector
+                    .as_mut()
+                    .and_then(|connector| connector.surface_configuration_mut())
+                {
+                    let new_size = rescale_surface_configuration(
+                        surface_configuration,
+                        self.input_state.scale_factor(),
+                        scale_factor,
+                    );
+
+                    if let Err(e) = inner_size_writer.request_inner_size(new_size) {
+                        warn!("Failed requesting new inner size {new_size:?}: {e}");
+                    }
+
+                    self.reconfigure_surface();
+                    self.input_state.surface_resize(new_size);
+                }
+
+                self.input_state.set_scale_factor(scale_factor);
+
+                None
+            }
             _ => None,
         };
 
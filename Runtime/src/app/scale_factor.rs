@@ -0,0 +1,39 @@
+use wgpu::SurfaceConfiguration;
+use winit::dpi::PhysicalSize;
+
+/// Converts a physical size from one scale factor to another.
+/// The logical size stays the same, only the amount of physical pixels
+/// backing it changes.
+pub fn rescale_physical_size(
+    size: PhysicalSize<u32>,
+    old_scale_factor: f64,
+    new_scale_factor: f64,
+) -> PhysicalSize<u32> {
+    let rescaled = size
+        .to_logical::<f64>(old_scale_factor)
+        .to_physical::<u32>(new_scale_factor);
+
+    // Surfaces can't be zero sized
+    PhysicalSize::new(rescaled.width.max(1), rescaled.height.max(1))
+}
+
+/// Updates the given [SurfaceConfiguration] to cover the same logical area
+/// under a new scale factor.
+/// Returns the new physical size, which the surface has to be reconfigured
+/// with afterwards.
+pub fn rescale_surface_configuration(
+    surface_configuration: &mut SurfaceConfiguration,
+    old_scale_factor: f64,
+    new_scale_factor: f64,
+) -> PhysicalSize<u32> {
+    let new_size = rescale_physical_size(
+        PhysicalSize::new(surface_configuration.width, surface_configuration.height),
+        old_scale_factor,
+        new_scale_factor,
+    );
+
+    surface_configuration.width = new_size.width;
+    surface_configuration.height = new_size.height;
+
+    new_size
+}
@@ -1,10 +1,28 @@
+use std::time::Duration;
+
 use winit::dpi::{PhysicalSize, Size};
 
+use super::{ControlFlowStrategy, GpuConnectorConfig, Timer};
+
 #[derive(Debug, Clone)]
 pub struct AppSettings {
     pub name: String,
     pub size: Size,
     pub vsync_enabled: bool,
+    pub control_flow: ControlFlowStrategy,
+    /// Features and limits requested from the GPU.
+    pub gpu: GpuConnectorConfig,
+    /// Upper bound for the delta time passed to updates.
+    /// Longer frames, e.g. after a breakpoint or a long loading time, are
+    /// clamped to this to prevent objects from jumping.
+    pub max_delta_time: Duration,
+    /// Whether IMEs can compose text, see
+    /// [InputEvent::TextComposition](crate::app::input::InputEvent::TextComposition).  
+    /// Disabled by default, as IMEs capture regular key presses while
+    /// composing, e.g. WASD on CJK setups.
+    /// Can be changed at runtime via [AppEvent::ChangeImeAllowed](crate::app::AppEvent::ChangeImeAllowed),
+    /// e.g. while a text field is focused.
+    pub ime_allowed: bool,
 }
 
 impl Default for AppSettings {
@@ -13,6 +31,10 @@ impl Default for AppSettings {
             name: "Orbital App".into(),
             size: PhysicalSize::new(1280, 720).into(),
             vsync_enabled: true,
+            control_flow: ControlFlowStrategy::default(),
+            gpu: GpuConnectorConfig::default(),
+            max_delta_time: Timer::DEFAULT_MAX_DELTA_TIME,
+            ime_allowed: false,
         }
     }
 }
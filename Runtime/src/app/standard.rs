@@ -1,12 +1,13 @@
 use crate::element::Element;
 use crate::{
     app::{input::InputState, App, AppEvent},
-    cgmath::Vector2,
-    element::{ElementEvent, ElementStore, Event, WorldEvent},
+    audio::AudioBackend,
+    cgmath::{EuclideanSpace, Point3, Vector2},
+    element::{ElementEvent, ElementStore, Event, MessageLimit, WorldEvent},
     logging::warn,
     renderer::Renderer,
     wgpu::{Device, Queue, SurfaceConfiguration, TextureView},
-    world::World,
+    world::{CacheCleanupCadence, World},
 };
 use async_std::task::block_on;
 use std::time::{Duration, Instant};
@@ -17,6 +18,8 @@ pub struct StandardApp {
     pub(crate) world: World,
     pub(crate) queue_events: Vec<Event>,
     pub(crate) renderer: Option<Renderer>,
+    /// Start of the last update, used to measure unclamped frame times.
+    pub(crate) last_update: Option<Instant>,
     #[cfg(feature = "standard_app_detect_no_more_elements")]
     pub(crate) empty_since: Option<Instant>,
 }
@@ -34,6 +37,29 @@ impl StandardApp {
 
         s
     }
+
+    /// Sets the per-element, per-frame [MessageLimit].  
+    /// See [MessageLimit] for the defaults.
+    pub fn with_message_limit(mut self, message_limit: MessageLimit) -> Self {
+        self.element_store.set_message_limit(message_limit);
+
+        self
+    }
+
+    /// Sets the [AudioBackend] playing requested sounds.  
+    /// Defaults to no audio.
+    pub fn with_audio_backend(mut self, audio_backend: impl AudioBackend + 'static) -> Self {
+        self.world.set_audio_backend(Box::new(audio_backend));
+
+        self
+    }
+
+    /// Sets how often the [World] runs cache cleanup.
+    pub fn with_cache_cleanup_cadence(mut self, cadence: CacheCleanupCadence) -> Self {
+        self.world.set_cache_cleanup_cadence(cadence);
+
+        self
+    }
 }
 
 impl App for StandardApp {
@@ -48,6 +74,10 @@ impl App for StandardApp {
     }
 
     async fn on_resume(&mut self, config: &SurfaceConfiguration, device: &Device, queue: &Queue) {
+        // Resources of a previous device can't be used anymore
+        self.world.invalidate_realizations();
+        self.world
+            .set_surface_size(Vector2::new(config.width, config.height));
         self.renderer = Some(Renderer::new(
             config.format,
             Vector2::new(config.width, config.height),
@@ -62,12 +92,16 @@ impl App for StandardApp {
 
     async fn on_suspend(&mut self) {
         self.renderer = None;
+        // Time spent suspended isn't a frame
+        self.last_update = None;
     }
 
     async fn on_resize(&mut self, new_size: Vector2<u32>, device: &Device, queue: &Queue)
     where
         Self: Sized,
     {
+        self.world.set_surface_size(new_size);
+
         if let Some(renderer) = &mut self.renderer {
             renderer.change_resolution(new_size, device, queue);
         } else {
@@ -84,6 +118,14 @@ impl App for StandardApp {
     where
         Self: Sized,
     {
+        // The delta time is clamped and zero while paused, which would hide
+        // exactly the hitches frame stats are meant to show
+        let now = Instant::now();
+        if let Some(last_update) = self.last_update.replace(now) {
+            self.world
+                .record_frame(now.duration_since(last_update).as_secs_f64());
+        }
+
         let mut events = self.element_store.update(delta_time, input_state).await;
         let old_events = self.queue_events.drain(0..self.queue_events.len());
 
@@ -108,7 +150,7 @@ impl App for StandardApp {
         }
 
         // Kick off world future to process world updates while we handle other things
-        let world_future = self.world.update(world_events);
+        let world_future = self.world.update(world_events, delta_time);
 
         let new_events = self.element_store.process_events(element_events).await;
         self.queue_events.extend(new_events);
@@ -132,6 +174,11 @@ impl App for StandardApp {
         // Await world future before we need access to the world again.
         world_future.await;
 
+        // Delivered with the next element update
+        for message in self.world.take_messages() {
+            self.element_store.queue_message(message);
+        }
+
         // Note: Currently **all** models are flagged for realization.
         // Once a system for culling or another way of selecting which models should be realized and what shouldn't be realized is in place, this can be changed.
         let model_ids = self
@@ -155,9 +202,16 @@ impl App for StandardApp {
         if let Some(renderer) = &mut self.renderer {
             self.world
                 .prepare_render(renderer.surface_texture_format(), device, queue);
+            renderer.set_clear_color(self.world.background().clear_color());
 
             let (world_bind_group_option, world_environment_option, models) =
                 self.world.retrieve_render_resources();
+            let camera_position = self
+                .world
+                .camera_store()
+                .active_camera_descriptor()
+                .map(|camera| camera.position)
+                .unwrap_or_else(Point3::origin);
             let world_bind_group = match world_bind_group_option {
                 Some(x) => x,
                 None => {
@@ -172,6 +226,7 @@ impl App for StandardApp {
                     world_bind_group,
                     world_environment_option,
                     models,
+                    camera_position,
                     device,
                     queue,
                 )
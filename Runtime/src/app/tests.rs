@@ -0,0 +1,651 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use async_std::task::block_on;
+use async_trait::async_trait;
+use cgmath::{Point3, Vector3};
+
+use wgpu::{
+    BufferBindingType, BufferDescriptor, BufferUsages, Color, CompositeAlphaMode, Extent3d,
+    Features, Limits, PollType, PresentMode, RequestAdapterError, SurfaceConfiguration,
+    SurfaceError, TextureDimension, TextureFormat, TextureUsages,
+};
+use winit::{
+    dpi::{PhysicalPosition, PhysicalSize},
+    event::{DeviceId, StartCause},
+    event_loop::ControlFlow,
+};
+
+use super::{
+    acquire_frame_with_recovery,
+    input::{InputEvent, InputState},
+    rescale_physical_size, rescale_surface_configuration,
+    standard::StandardApp,
+    App, ComputeBinding, ComputeError, ControlFlowStrategy, FrameFailure, GpuConnector,
+    GpuConnectorConfig, GpuConnectorError, Timer,
+};
+#[cfg(feature = "gamepad_input")]
+use super::{input::Rumble, AppEvent};
+use crate::{
+    element::{CameraEvent, CameraState, Element, ElementRegistration, Event, Message, WorldEvent},
+    resources::{
+        BufferDescriptor as OrbitalBufferDescriptor, CameraDescriptor, ShaderError, ShaderSource,
+    },
+    wgpu_test_adapter,
+    world::Background,
+};
+
+fn surface_configuration(width: u32, height: u32) -> SurfaceConfiguration {
+    SurfaceConfiguration {
+        usage: TextureUsages::RENDER_ATTACHMENT,
+        format: TextureFormat::Bgra8UnormSrgb,
+        width,
+        height,
+        present_mode: PresentMode::AutoVsync,
+        desired_maximum_frame_latency: 2,
+        alpha_mode: CompositeAlphaMode::Auto,
+        view_formats: vec![],
+    }
+}
+
+#[test]
+fn scale_factor_change_reconfigures_surface() {
+    let mut configuration = surface_configuration(1280, 720);
+
+    let new_size = rescale_surface_configuration(&mut configuration, 1.0, 2.0);
+    assert_eq!(new_size, PhysicalSize::new(2560, 1440));
+    assert_eq!(configuration.width, 2560);
+    assert_eq!(configuration.height, 1440);
+
+    let new_size = rescale_surface_configuration(&mut configuration, 2.0, 1.5);
+    assert_eq!(new_size, PhysicalSize::new(1920, 1080));
+    assert_eq!(configuration.width, 1920);
+    assert_eq!(configuration.height, 1080);
+}
+
+#[test]
+fn rescale_never_reaches_zero() {
+    assert_eq!(
+        rescale_physical_size(PhysicalSize::new(1, 1), 2.0, 1.0),
+        PhysicalSize::new(1, 1)
+    );
+}
+
+#[test]
+fn scale_factor_change_rescales_cursor() {
+    let mut state = InputState::new();
+    assert_eq!(state.scale_factor(), 1.0);
+
+    state.handle_event(InputEvent::MouseMovedPosition {
+        device_id: DeviceId::dummy(),
+        position: PhysicalPosition::new(100.0, 50.0),
+    });
+
+    state.set_scale_factor(2.0);
+    assert_eq!(state.scale_factor(), 2.0);
+    assert_eq!(state.mouse_cursor_position_state(), (200.0, 100.0).into());
+    assert_eq!(state.mouse_cursor_position_logical(), (100.0, 50.0).into());
+}
+
+#[test]
+fn control_flow_on_demand_waits() {
+    let strategy = ControlFlowStrategy::OnDemand;
+
+    assert_eq!(strategy.control_flow(Instant::now()), ControlFlow::Wait);
+    // Nothing is drawn until a redraw is requested ...
+    assert!(!strategy.redraw_after_frame());
+    // ... which is what wakes up the event loop
+    assert!(!strategy.redraw_on_wake(StartCause::WaitCancelled {
+        start: Instant::now(),
+        requested_resume: None,
+    }));
+}
+
+#[test]
+fn control_flow_continuous_polls() {
+    let strategy = ControlFlowStrategy::default();
+
+    assert_eq!(strategy, ControlFlowStrategy::Continuous);
+    assert_eq!(strategy.control_flow(Instant::now()), ControlFlow::Poll);
+    assert!(strategy.redraw_after_frame());
+}
+
+#[test]
+fn control_flow_throttled() {
+    let strategy = ControlFlowStrategy::Throttled(50);
+    let last_frame = Instant::now();
+
+    assert_eq!(
+        strategy.control_flow(last_frame),
+        ControlFlow::WaitUntil(last_frame + Duration::from_millis(20))
+    );
+    assert!(!strategy.redraw_after_frame());
+    assert!(strategy.redraw_on_wake(StartCause::ResumeTimeReached {
+        start: last_frame,
+        requested_resume: last_frame + Duration::from_millis(20),
+    }));
+    assert!(!strategy.redraw_on_wake(StartCause::WaitCancelled {
+        start: last_frame,
+        requested_resume: Some(last_frame + Duration::from_millis(20)),
+    }));
+}
+
+#[test]
+fn timer_clamps_stalls() {
+    let max_delta_time = Duration::from_millis(100);
+    let mut timer = Timer::with_max_delta_time(max_delta_time);
+
+    let (delta_time, _) = timer.advance(Duration::from_millis(16));
+    assert_eq!(delta_time, 0.016);
+
+    // E.g. a breakpoint or long loading time
+    let (delta_time, _) = timer.advance(Duration::from_secs(5));
+    assert_eq!(delta_time, max_delta_time.as_secs_f64());
+}
+
+#[test]
+fn timer_pause_emits_zero_deltas() {
+    let mut timer = Timer::new();
+
+    timer.pause();
+    assert!(timer.is_paused());
+    let (delta_time, _) = timer.advance(Duration::from_millis(16));
+    assert_eq!(delta_time, 0.0);
+
+    timer.resume();
+    let (delta_time, _) = timer.advance(Duration::from_millis(16));
+    assert_eq!(delta_time, 0.016);
+}
+
+/// Acquires a frame, failing with the given errors first.
+fn acquire_with_errors(
+    errors: Vec<SurfaceError>,
+) -> (Result<&'static str, FrameFailure>, usize, usize) {
+    let mut errors = errors.into_iter();
+    let mut attempts = 0;
+    let mut reconfigurations = 0;
+
+    let result = acquire_frame_with_recovery(
+        || {
+            attempts += 1;
+            errors.next().map_or(Ok("frame"), Err)
+        },
+        || reconfigurations += 1,
+    );
+
+    (result, attempts, reconfigurations)
+}
+
+#[test]
+fn lost_surface_is_reconfigured_and_retried() {
+    for error in [SurfaceError::Lost, SurfaceError::Outdated] {
+        assert_eq!(acquire_with_errors(vec![error]), (Ok("frame"), 2, 1));
+    }
+
+    // Giving up after one retry, but without crashing
+    assert_eq!(
+        acquire_with_errors(vec![SurfaceError::Lost, SurfaceError::Lost]),
+        (Err(FrameFailure::Skip), 2, 1)
+    );
+}
+
+#[test]
+fn surface_errors_skip_or_exit() {
+    assert_eq!(acquire_with_errors(vec![]), (Ok("frame"), 1, 0));
+    assert_eq!(
+        acquire_with_errors(vec![SurfaceError::Timeout]),
+        (Err(FrameFailure::Skip), 1, 0)
+    );
+    assert_eq!(
+        acquire_with_errors(vec![SurfaceError::OutOfMemory]),
+        (Err(FrameFailure::Exit), 1, 0)
+    );
+}
+
+#[test]
+fn gpu_connector_detects_device_loss() {
+    let connector = wgpu_test_adapter::make_wgpu_connector();
+    assert!(!connector.is_device_lost());
+
+    connector.device().destroy();
+    connector
+        .device()
+        .poll(PollType::Wait)
+        .expect("Failed polling device");
+    assert!(connector.is_device_lost());
+}
+
+#[test]
+fn gpu_connector_without_surface() {
+    let mut connector = wgpu_test_adapter::make_wgpu_connector();
+
+    assert!(connector.surface().is_none());
+    assert!(connector.surface_configuration().is_none());
+    assert!(connector.surface_configuration_mut().is_none());
+
+    // Configuring without a surface must be a no-op
+    connector.configure_surface();
+    connector.resize(PhysicalSize::new(800, 600));
+    connector.release_surface();
+    assert!(connector.surface_configuration().is_none());
+
+    let buffer = connector.device().create_buffer(&BufferDescriptor {
+        label: None,
+        size: 4,
+        usage: BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    connector.queue().write_buffer(&buffer, 0, &[0; 4]);
+    connector.queue().submit([]);
+}
+
+#[test]
+fn gpu_connector_enables_requested_feature() {
+    let (instance, adapter) = wgpu_test_adapter::make_wgpu_adapter();
+    let Some(feature) = adapter.features().iter().next() else {
+        // Nothing to request on this adapter
+        return;
+    };
+
+    let connector = GpuConnector::from_adapter(
+        instance,
+        adapter,
+        &GpuConnectorConfig {
+            required_features: feature,
+            required_limits: Limits::downlevel_defaults(),
+        },
+    )
+    .expect("Requesting a supported feature must succeed");
+
+    assert!(connector.device().features().contains(feature));
+}
+
+#[test]
+fn gpu_connector_reports_missing_features() {
+    let (instance, adapter) = wgpu_test_adapter::make_wgpu_adapter();
+    let unsupported = Features::all() - adapter.features();
+
+    let result = GpuConnector::from_adapter(
+        instance,
+        adapter,
+        &GpuConnectorConfig {
+            required_features: Features::all(),
+            required_limits: Limits::downlevel_defaults(),
+        },
+    );
+
+    match result {
+        Err(GpuConnectorError::MissingFeatures { missing, .. }) => {
+            assert_eq!(missing, unsupported)
+        }
+        other => panic!("Expected missing features, got {other:?}"),
+    }
+}
+
+#[test]
+fn gpu_connector_reports_exceeded_limits() {
+    let (instance, adapter) = wgpu_test_adapter::make_wgpu_adapter();
+    let allowed = adapter.limits().max_bind_groups;
+
+    let result = GpuConnector::from_adapter(
+        instance,
+        adapter,
+        &GpuConnectorConfig {
+            required_features: Features::empty(),
+            required_limits: Limits {
+                max_bind_groups: allowed + 1,
+                ..Limits::downlevel_defaults()
+            },
+        },
+    );
+
+    match result {
+        Err(GpuConnectorError::LimitsExceeded { limits, .. }) => {
+            assert_eq!(
+                limits,
+                vec![("max_bind_groups", allowed as u64 + 1, allowed as u64)]
+            )
+        }
+        other => panic!("Expected exceeded limits, got {other:?}"),
+    }
+}
+
+#[test]
+fn gpu_connector_retries_with_fallback_adapter() {
+    let mut fallback_requested = false;
+
+    let result = GpuConnector::choose_adapter(Vec::new(), || {
+        fallback_requested = true;
+        Err(RequestAdapterError::EnvNotSet)
+    });
+
+    assert!(fallback_requested);
+    assert!(matches!(
+        result,
+        Err(GpuConnectorError::NoAdapters(
+            RequestAdapterError::EnvNotSet
+        ))
+    ));
+}
+
+#[test]
+fn gpu_connector_uses_fallback_adapter() {
+    let (_, adapter) = wgpu_test_adapter::make_wgpu_adapter();
+    let name = adapter.get_info().name;
+
+    let chosen = GpuConnector::choose_adapter(Vec::new(), || Ok(adapter))
+        .expect("Fallback adapter must be chosen");
+
+    assert_eq!(chosen.get_info().name, name);
+}
+
+#[test]
+fn gpu_connector_skips_fallback_if_adapters_found() {
+    let (_, adapter) = wgpu_test_adapter::make_wgpu_adapter();
+
+    let result = GpuConnector::choose_adapter(vec![(adapter, (0, 0, 0, 0))], || {
+        panic!("Fallback must not be requested")
+    });
+
+    assert!(result.is_ok());
+}
+
+const DOUBLE_SHADER: &str = "
+@group(0) @binding(0)
+var<storage, read_write> values: array<f32>;
+
+@compute @workgroup_size(4)
+fn double(@builtin(global_invocation_id) id: vec3<u32>) {
+    values[id.x] = values[id.x] * 2.0;
+}
+";
+
+fn storage_buffer(values: &[f32]) -> OrbitalBufferDescriptor {
+    OrbitalBufferDescriptor {
+        data: values.iter().flat_map(|x| x.to_le_bytes()).collect(),
+        ty: BufferBindingType::Storage { read_only: false },
+        usage: BufferUsages::STORAGE,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn gpu_connector_dispatch_reads_back_buffer() {
+    let connector = wgpu_test_adapter::make_wgpu_connector();
+    let buffer = storage_buffer(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0]);
+
+    let data = connector
+        .dispatch(
+            ShaderSource::String(DOUBLE_SHADER),
+            "double",
+            &[ComputeBinding::Buffer(&buffer)],
+            Vector3::new(2, 1, 1),
+            Some(0),
+        )
+        .expect("Dispatch failed")
+        .expect("Buffer wasn't read back");
+
+    let values = data
+        .chunks_exact(4)
+        .map(|x| f32::from_le_bytes([x[0], x[1], x[2], x[3]]))
+        .collect::<Vec<_>>();
+    assert_eq!(values, [2.0, 4.0, 6.0, 8.0, 10.0, 12.0, 14.0, 16.0]);
+}
+
+#[test]
+fn gpu_connector_dispatch_errors() {
+    let connector = wgpu_test_adapter::make_wgpu_connector();
+    let buffer = storage_buffer(&[1.0; 4]);
+
+    let result = connector.dispatch(
+        ShaderSource::String(DOUBLE_SHADER),
+        "double",
+        &[ComputeBinding::Buffer(&buffer)],
+        Vector3::new(1, 1, 1),
+        Some(1),
+    );
+    assert!(matches!(
+        result,
+        Err(ComputeError::InvalidReadBack { binding: 1 })
+    ));
+
+    let result = connector.dispatch(
+        ShaderSource::String(DOUBLE_SHADER),
+        "triple",
+        &[ComputeBinding::Buffer(&buffer)],
+        Vector3::new(1, 1, 1),
+        None,
+    );
+    assert!(matches!(
+        result,
+        Err(ComputeError::Shader(ShaderError::MissingEntryPoint { .. }))
+    ));
+}
+
+/// Spawns a camera and queries it until its state is received.
+#[derive(Debug)]
+struct CameraQuerier {
+    state: Arc<Mutex<Option<CameraState>>>,
+}
+
+impl CameraQuerier {
+    fn camera() -> CameraDescriptor {
+        CameraDescriptor {
+            label: "Queried".into(),
+            position: Point3::new(1.0, 2.0, 3.0),
+            yaw: 0.5,
+            pitch: -0.25,
+            roll: 0.1,
+            ..Default::default()
+        }
+    }
+}
+
+#[async_trait]
+impl Element for CameraQuerier {
+    fn on_registration(&self) -> ElementRegistration {
+        ElementRegistration::new("querier").with_initial_event(Event::World(WorldEvent::Camera(
+            CameraEvent::Spawn(Self::camera()),
+        )))
+    }
+
+    async fn on_update(
+        &mut self,
+        _delta_time: f64,
+        _input_state: &InputState,
+    ) -> Option<Vec<Event>> {
+        if self.state.lock().unwrap().is_some() {
+            return None;
+        }
+
+        Some(vec![Event::World(WorldEvent::Camera(CameraEvent::Query {
+            label: "Queried".into(),
+            requester: "querier".into(),
+        }))])
+    }
+
+    async fn on_message(&mut self, message: &Arc<Message>) -> Option<Vec<Event>> {
+        *self.state.lock().unwrap() = CameraState::from_message(message);
+        None
+    }
+}
+
+#[test]
+fn camera_query_replies_with_state() {
+    let state = Arc::new(Mutex::new(None));
+    let mut app = StandardApp::with_initial_elements(vec![Box::new(CameraQuerier {
+        state: state.clone(),
+    })]);
+    let input_state = InputState::new();
+
+    // The first query is processed before the camera is spawned
+    block_on(app.on_update(&input_state, 0.0, None));
+    // The second query is answered, the reply gets delivered next frame
+    block_on(app.on_update(&input_state, 0.0, None));
+    assert!(state.lock().unwrap().is_none());
+    block_on(app.on_update(&input_state, 0.0, None));
+
+    let camera = CameraQuerier::camera();
+    let state = state
+        .lock()
+        .unwrap()
+        .clone()
+        .expect("Camera state expected");
+    assert_eq!(state, CameraState::from(&camera));
+    assert_eq!(state.position, camera.position);
+    assert_eq!(state.yaw, camera.yaw);
+    assert_eq!(state.pitch, camera.pitch);
+    assert_eq!(state.roll, camera.roll);
+    assert_eq!(state.forward, camera.forward());
+}
+
+/// Switches the background to a solid color on the first update and back
+/// to the sky box on the second one.
+#[derive(Debug)]
+struct BackgroundSwitcher {
+    updates: u32,
+}
+
+impl BackgroundSwitcher {
+    const COLOR: Color = Color {
+        r: 0.1,
+        g: 0.2,
+        b: 0.3,
+        a: 1.0,
+    };
+}
+
+#[async_trait]
+impl Element for BackgroundSwitcher {
+    fn on_registration(&self) -> ElementRegistration {
+        ElementRegistration::new("background_switcher")
+    }
+
+    async fn on_update(
+        &mut self,
+        _delta_time: f64,
+        _input_state: &InputState,
+    ) -> Option<Vec<Event>> {
+        self.updates += 1;
+
+        let background = match self.updates {
+            1 => Background::Color(Self::COLOR),
+            2 => Background::Skybox(None),
+            _ => return None,
+        };
+        Some(vec![Event::World(WorldEvent::SetBackground(background))])
+    }
+}
+
+#[test]
+fn frame_stats_record_unclamped_frame_time() {
+    let mut app = StandardApp::with_initial_elements(vec![Box::new(CameraQuerier {
+        state: Arc::new(Mutex::new(None)),
+    })]);
+    let input_state = InputState::new();
+
+    // A zero delta time, like while paused, must not hide the frame
+    block_on(app.on_update(&input_state, 0.0, None));
+    std::thread::sleep(Duration::from_millis(150));
+    block_on(app.on_update(&input_state, 0.0, None));
+
+    let stats = app.world.frame_stats().expect("Frame stats expected");
+    // Above the default maximum delta time of 100ms
+    assert!(stats.frame_time >= 0.15, "{stats:?}");
+}
+
+#[test]
+fn set_background_updates_renderer() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+    let config = surface_configuration(64, 32);
+    let target = device.create_texture(&wgpu::TextureDescriptor {
+        label: None,
+        size: Extent3d {
+            width: config.width,
+            height: config.height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: TextureDimension::D2,
+        format: config.format,
+        usage: TextureUsages::RENDER_ATTACHMENT,
+        view_formats: &[],
+    });
+    let target_view = target.create_view(&Default::default());
+
+    let mut app =
+        StandardApp::with_initial_elements(vec![Box::new(BackgroundSwitcher { updates: 0 })]);
+    block_on(app.on_resume(&config, &device, &queue));
+    let input_state = InputState::new();
+    let clear_color = |app: &StandardApp| app.renderer.as_ref().unwrap().clear_color();
+
+    // Sky box by default
+    block_on(app.on_render(&target_view, &device, &queue));
+    assert_eq!(clear_color(&app), None);
+
+    block_on(app.on_update(&input_state, 0.0, None));
+    assert_eq!(
+        *app.world.background(),
+        Background::Color(BackgroundSwitcher::COLOR)
+    );
+    block_on(app.on_render(&target_view, &device, &queue));
+    assert_eq!(clear_color(&app), Some(BackgroundSwitcher::COLOR));
+
+    // Switching back mid-run draws the sky box again
+    block_on(app.on_update(&input_state, 0.0, None));
+    assert_eq!(*app.world.background(), Background::Skybox(None));
+    block_on(app.on_render(&target_view, &device, &queue));
+    assert_eq!(clear_color(&app), None);
+}
+
+/// Requests a rumble on every update.
+#[cfg(feature = "gamepad_input")]
+#[derive(Debug)]
+struct Rumbler;
+
+#[cfg(feature = "gamepad_input")]
+impl Rumbler {
+    const RUMBLE: Rumble = Rumble {
+        gamepad: None,
+        strong: 1.0,
+        weak: 0.25,
+        duration: Duration::from_millis(100),
+    };
+}
+
+#[cfg(feature = "gamepad_input")]
+#[async_trait]
+impl Element for Rumbler {
+    fn on_registration(&self) -> ElementRegistration {
+        ElementRegistration::new("rumbler")
+    }
+
+    async fn on_update(
+        &mut self,
+        _delta_time: f64,
+        _input_state: &InputState,
+    ) -> Option<Vec<Event>> {
+        Some(vec![Event::App(AppEvent::Rumble(Self::RUMBLE))])
+    }
+}
+
+#[cfg(feature = "gamepad_input")]
+#[test]
+fn rumble_is_routed_to_runtime() {
+    let mut app = StandardApp::with_initial_elements(vec![Box::new(Rumbler)]);
+    let input_state = InputState::new();
+
+    let rumbles = (0..2)
+        .flat_map(|_| block_on(app.on_update(&input_state, 0.0, None)).unwrap_or_default())
+        .filter_map(|event| match event {
+            AppEvent::Rumble(rumble) => Some(rumble),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    assert!(!rumbles.is_empty());
+    assert!(rumbles.iter().all(|rumble| *rumble == Rumbler::RUMBLE));
+}
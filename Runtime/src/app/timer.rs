@@ -1,4 +1,4 @@
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 pub struct Timer {
     /// Last time when tick was called.
@@ -13,18 +13,50 @@ pub struct Timer {
     /// The total time that has passed during this whole cycle.
     /// Each cycle should last at least a single second, but it can be longer in case of e.g. lag.
     cycle_delta_time: f64,
+    /// Upper bound for a single delta time.
+    /// Prevents e.g. breakpoints or long loading times from causing huge jumps.
+    max_delta_time: Duration,
+    /// If paused, all delta times are zero.
+    paused: bool,
 }
 
 impl Timer {
+    pub const DEFAULT_MAX_DELTA_TIME: Duration = Duration::from_millis(100);
+
     pub fn new() -> Self {
+        Self::with_max_delta_time(Self::DEFAULT_MAX_DELTA_TIME)
+    }
+
+    pub fn with_max_delta_time(max_delta_time: Duration) -> Self {
         Self {
             last_time: Instant::now(),
             fps: 0u64,
             delta_time: 0f64,
             cycle_delta_time: 0f64,
+            max_delta_time,
+            paused: false,
         }
     }
 
+    pub fn max_delta_time(&self) -> Duration {
+        self.max_delta_time
+    }
+
+    /// Pauses the timer.
+    /// Until [resumed](Self::resume), every tick returns a delta time of zero.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Resumes the timer after [pausing](Self::pause).
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
     /// Will perform a few calculations in succession to accurately calculate a delta time, to be used in e.g. updating, and fps reading.
     /// Must be called each update cycle, otherwise this will be inaccurate.
     ///
@@ -37,6 +69,9 @@ impl Timer {
     /// Thus, delta time should be used to e.g. update between frames.  
     /// Cycle delta time should be used as a metric of FPS stability.
     ///
+    /// The delta time is clamped to the [maximum delta time](Self::max_delta_time)
+    /// and zero while [paused](Self::pause).
+    ///
     /// # Returns
     /// Returns two (*three) things as a tuple:
     /// 1. The current delta time, will always be returned.
@@ -47,9 +82,17 @@ impl Timer {
         let elapsed = now.duration_since(self.last_time);
         self.last_time = now;
 
-        self.delta_time = elapsed.as_secs_f64().clamp(0.0, 1.0);
+        self.advance(elapsed)
+    }
+
+    /// Same as [Self::tick], but with a given elapsed time instead of
+    /// measuring it.
+    pub(crate) fn advance(&mut self, elapsed: Duration) -> (f64, Option<(f64, u64)>) {
+        let clamped = elapsed.min(self.max_delta_time).as_secs_f64();
+        self.delta_time = if self.paused { 0.0 } else { clamped };
 
-        self.cycle_delta_time += self.delta_time;
+        // The cycle keeps running while paused to keep FPS readings accurate.
+        self.cycle_delta_time += clamped;
         self.fps += 1;
 
         let cycle_part = if self.cycle_delta_time >= 1.0 {
@@ -0,0 +1,236 @@
+//! # Asset Module
+//!
+//! The asset module resolves asset paths used by all loaders of the engine, such as the
+//! glTF importer, shader sources and texture files.
+//!
+//! By default, relative paths are resolved against the current working directory of the
+//! process. This breaks as soon as the working directory differs from where the assets are
+//! located, e.g. when launching from inside a macOS app bundle.
+//! To counter this, an [`AssetResolver`] can be configured with an asset root that all
+//! relative paths are resolved against. Additionally, assets can be embedded into the binary
+//! and registered under a path.
+//!
+//! On Android, relative paths are first looked up inside the APK assets.
+//!
+//! On the web, there is no file system to read from and loaders can't wait on a `fetch`.
+//! Instead, the application fetches its assets up front and hands the bytes over via
+//! [`register_bytes`]. Any other path fails with [`ErrorKind::Unsupported`].
+//!
+//! The resolver used by the loaders is set globally via [`set_global_resolver`].
+
+use std::{
+    collections::HashMap,
+    io::{Error, ErrorKind, Result},
+    path::{Path, PathBuf},
+    sync::{Arc, LazyLock, RwLock},
+};
+
+#[cfg(target_os = "android")]
+use std::sync::OnceLock;
+#[cfg(target_os = "android")]
+use winit::platform::android::activity::AndroidApp;
+
+#[cfg(test)]
+mod tests;
+
+static GLOBAL_RESOLVER: LazyLock<RwLock<AssetResolver>> =
+    LazyLock::new(|| RwLock::new(AssetResolver::new()));
+
+#[cfg(target_os = "android")]
+static ANDROID_APP: OnceLock<AndroidApp> = OnceLock::new();
+
+/// Resolves asset paths against a configurable asset root and embedded assets.
+#[derive(Debug, Default, Clone)]
+pub struct AssetResolver {
+    root: Option<PathBuf>,
+    embedded: HashMap<PathBuf, &'static [u8]>,
+    loaded: HashMap<PathBuf, Arc<[u8]>>,
+}
+
+impl AssetResolver {
+    /// Creates a new resolver without an asset root.
+    /// Relative paths will be resolved against the current working directory.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the asset root relative paths are resolved against.
+    pub fn with_root<P: Into<PathBuf>>(mut self, root: P) -> Self {
+        self.root = Some(root.into());
+        self
+    }
+
+    /// Registers an embedded asset under the given path.
+    /// Embedded assets take priority over anything else.
+    ///
+    /// Commonly used together with [`include_bytes!`].
+    pub fn with_embedded<P: Into<PathBuf>>(mut self, path: P, bytes: &'static [u8]) -> Self {
+        self.embedded.insert(path.into(), bytes);
+        self
+    }
+
+    /// Registers an asset loaded at runtime under the given path, replacing any
+    /// previously registered bytes.
+    ///
+    /// On the web, this is how fetched assets are made available to the loaders.
+    pub fn insert_bytes<P: Into<PathBuf>>(&mut self, path: P, bytes: Vec<u8>) {
+        self.loaded.insert(path.into(), bytes.into());
+    }
+
+    pub fn root(&self) -> Option<&Path> {
+        self.root.as_deref()
+    }
+
+    /// Resolves a path against the asset root.
+    /// Absolute paths, or any path if no asset root is set, are returned as-is.
+    pub fn resolve<P: AsRef<Path>>(&self, path: P) -> PathBuf {
+        let path = path.as_ref();
+
+        match &self.root {
+            Some(root) if path.is_relative() => root.join(path),
+            _ => path.to_path_buf(),
+        }
+    }
+
+    /// Returns the embedded asset registered under the given path, if any.
+    pub fn embedded<P: AsRef<Path>>(&self, path: P) -> Option<&'static [u8]> {
+        self.embedded.get(path.as_ref()).copied()
+    }
+
+    /// Reads an asset.
+    ///
+    /// Embedded assets are checked first, followed by assets registered via
+    /// [`Self::insert_bytes`].
+    /// On Android, relative paths are then looked up inside the APK assets.
+    /// Lastly, the path is resolved via [`Self::resolve`] and read from the file system.
+    ///
+    /// On the web, there is no file system and reading an unregistered path fails
+    /// with [`ErrorKind::Unsupported`].
+    pub fn read<P: AsRef<Path>>(&self, path: P) -> Result<Vec<u8>> {
+        let path = path.as_ref();
+
+        if let Some(bytes) = self.embedded(path) {
+            return Ok(bytes.to_vec());
+        }
+
+        if let Some(bytes) = self.loaded.get(path) {
+            return Ok(bytes.to_vec());
+        }
+
+        #[cfg(target_os = "android")]
+        if let Some(bytes) = Self::read_android_asset(path) {
+            return Ok(bytes);
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        return Err(Error::new(
+            ErrorKind::Unsupported,
+            format!(
+                "Asset '{}' isn't registered; on the web, assets have to be fetched and registered via `asset::register_bytes` first",
+                path.display()
+            ),
+        ));
+
+        #[cfg(not(target_arch = "wasm32"))]
+        std::fs::read(self.resolve(path))
+    }
+
+    /// Reads an asset as UTF-8 string.
+    /// Check [`Self::read`] for details.
+    pub fn read_to_string<P: AsRef<Path>>(&self, path: P) -> Result<String> {
+        String::from_utf8(self.read(path)?).map_err(|e| Error::new(ErrorKind::InvalidData, e))
+    }
+
+    /// Returns `true` if the given path is served from memory, rather than the
+    /// file system, i.e. it's embedded, registered or an Android asset.
+    pub fn is_in_memory<P: AsRef<Path>>(&self, path: P) -> bool {
+        let path = path.as_ref();
+
+        #[cfg(target_os = "android")]
+        if path.is_relative() && ANDROID_APP.get().is_some() {
+            return true;
+        }
+
+        self.embedded.contains_key(path) || self.loaded.contains_key(path)
+    }
+
+    #[cfg(target_os = "android")]
+    fn read_android_asset(path: &Path) -> Option<Vec<u8>> {
+        use std::{ffi::CString, io::Read};
+
+        if path.is_absolute() {
+            return None;
+        }
+
+        let app = ANDROID_APP.get()?;
+        let path = CString::new(path.to_str()?).ok()?;
+        let mut asset = app.asset_manager().open(&path)?;
+
+        let mut bytes = Vec::new();
+        asset.read_to_end(&mut bytes).ok()?;
+        Some(bytes)
+    }
+}
+
+/// Replaces the global [`AssetResolver`] used by all loaders.
+pub fn set_global_resolver(resolver: AssetResolver) {
+    *GLOBAL_RESOLVER
+        .write()
+        .expect("Asset resolver lock got poisoned") = resolver;
+}
+
+/// Returns a copy of the global [`AssetResolver`].
+pub fn global_resolver() -> AssetResolver {
+    GLOBAL_RESOLVER
+        .read()
+        .expect("Asset resolver lock got poisoned")
+        .clone()
+}
+
+/// Resolves a path using the global [`AssetResolver`].
+pub fn resolve<P: AsRef<Path>>(path: P) -> PathBuf {
+    GLOBAL_RESOLVER
+        .read()
+        .expect("Asset resolver lock got poisoned")
+        .resolve(path)
+}
+
+/// Reads an asset using the global [`AssetResolver`].
+pub fn read<P: AsRef<Path>>(path: P) -> Result<Vec<u8>> {
+    GLOBAL_RESOLVER
+        .read()
+        .expect("Asset resolver lock got poisoned")
+        .read(path)
+}
+
+/// Reads an asset as UTF-8 string using the global [`AssetResolver`].
+pub fn read_to_string<P: AsRef<Path>>(path: P) -> Result<String> {
+    GLOBAL_RESOLVER
+        .read()
+        .expect("Asset resolver lock got poisoned")
+        .read_to_string(path)
+}
+
+/// Registers an asset loaded at runtime with the global [`AssetResolver`].
+/// Check [`AssetResolver::insert_bytes`] for details.
+pub fn register_bytes<P: Into<PathBuf>>(path: P, bytes: Vec<u8>) {
+    GLOBAL_RESOLVER
+        .write()
+        .expect("Asset resolver lock got poisoned")
+        .insert_bytes(path, bytes);
+}
+
+/// Returns `true` if the global [`AssetResolver`] serves the given path from memory.
+pub fn is_in_memory<P: AsRef<Path>>(path: P) -> bool {
+    GLOBAL_RESOLVER
+        .read()
+        .expect("Asset resolver lock got poisoned")
+        .is_in_memory(path)
+}
+
+/// Registers the [`AndroidApp`] to read APK assets from.
+/// This is done automatically by [`make_android_main`](crate::make_android_main).
+#[cfg(target_os = "android")]
+pub fn set_android_app(app: AndroidApp) {
+    let _ = ANDROID_APP.set(app);
+}
@@ -0,0 +1,76 @@
+use std::path::{Path, PathBuf};
+
+use crate::asset::AssetResolver;
+
+#[test]
+fn relative_path_resolves_against_root() {
+    let resolver = AssetResolver::new().with_root("/opt/game");
+
+    assert_eq!(
+        resolver.resolve("Assets/Models/Cube.glb"),
+        PathBuf::from("/opt/game/Assets/Models/Cube.glb")
+    );
+}
+
+#[test]
+fn absolute_path_ignores_root() {
+    let resolver = AssetResolver::new().with_root("/opt/game");
+
+    assert_eq!(
+        resolver.resolve("/tmp/Cube.glb"),
+        PathBuf::from("/tmp/Cube.glb")
+    );
+}
+
+#[test]
+fn no_root_keeps_path() {
+    let resolver = AssetResolver::new();
+
+    assert_eq!(
+        resolver.resolve("Assets/Cube.glb"),
+        Path::new("Assets/Cube.glb")
+    );
+}
+
+#[test]
+fn read_from_root() {
+    let resolver = AssetResolver::new().with_root(env!("CARGO_MANIFEST_DIR"));
+
+    let content = resolver
+        .read_to_string("Cargo.toml")
+        .expect("Failed reading asset relative to root");
+    assert!(content.contains("name = \"orbital\""));
+}
+
+#[test]
+fn read_embedded() {
+    const DATA: &[u8] = b"embedded";
+    let resolver = AssetResolver::new()
+        .with_root("/does/not/exist")
+        .with_embedded("Assets/Embedded.txt", DATA);
+
+    assert!(resolver.is_in_memory("Assets/Embedded.txt"));
+    assert_eq!(
+        resolver
+            .read("Assets/Embedded.txt")
+            .expect("Embedded asset missing"),
+        DATA
+    );
+    assert!(resolver.read("Assets/Other.txt").is_err());
+}
+
+#[test]
+fn read_registered_bytes() {
+    let mut resolver = AssetResolver::new().with_root("/does/not/exist");
+    resolver.insert_bytes("Assets/Fetched.txt", b"first".to_vec());
+    resolver.insert_bytes("Assets/Fetched.txt", b"fetched".to_vec());
+
+    assert!(resolver.is_in_memory("Assets/Fetched.txt"));
+    assert_eq!(
+        resolver
+            .read_to_string("Assets/Fetched.txt")
+            .expect("Registered asset missing"),
+        "fetched"
+    );
+    assert!(!resolver.is_in_memory("Assets/Other.txt"));
+}
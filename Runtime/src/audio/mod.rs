@@ -0,0 +1,49 @@
+//! # Audio Module
+//!
+//! Orbital doesn't ship an audio engine.
+//! Instead, sound effects are requested through [WorldEvent::PlaySound] and
+//! handed to an [AudioBackend], which can be backed by any audio library.
+//! By default, [NoAudioBackend] is used, which ignores all requests.
+//!
+//! [WorldEvent::PlaySound]: crate::element::WorldEvent::PlaySound
+
+use cgmath::Point3;
+use log::debug;
+
+#[cfg(test)]
+mod tests;
+
+/// A request to play a sound, as received by an [AudioBackend].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoundRequest {
+    /// Identifier of the sound to play.
+    /// What this refers to (e.g. a file path or a name) is up to the
+    /// [AudioBackend].
+    pub id: String,
+    /// Volume, where `1.0` is the original volume of the sound.
+    pub volume: f32,
+    /// World space position of the sound source.
+    /// `None` for non-spatial sounds, like UI or music.
+    pub position: Option<Point3<f32>>,
+    /// World space position of the listener, i.e. the active camera.
+    /// `None` if no camera is active.
+    pub listener: Option<Point3<f32>>,
+}
+
+/// Integration point for audio engines.
+pub trait AudioBackend: Send + Sync {
+    /// Called for every [WorldEvent::PlaySound] processed by the world.
+    ///
+    /// [WorldEvent::PlaySound]: crate::element::WorldEvent::PlaySound
+    fn play_sound(&mut self, request: SoundRequest);
+}
+
+/// [AudioBackend] which ignores any requests.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoAudioBackend;
+
+impl AudioBackend for NoAudioBackend {
+    fn play_sound(&mut self, request: SoundRequest) {
+        debug!("No audio backend set, ignoring sound request: {request:?}");
+    }
+}
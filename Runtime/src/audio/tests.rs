@@ -0,0 +1,82 @@
+use std::sync::{Arc, Mutex};
+
+use cgmath::Point3;
+
+use crate::{
+    element::{CameraEvent, WorldEvent},
+    resources::CameraDescriptor,
+    world::World,
+};
+
+use super::{AudioBackend, SoundRequest};
+
+#[derive(Default, Clone)]
+struct MockAudioBackend {
+    requests: Arc<Mutex<Vec<SoundRequest>>>,
+}
+
+impl AudioBackend for MockAudioBackend {
+    fn play_sound(&mut self, request: SoundRequest) {
+        self.requests.lock().unwrap().push(request);
+    }
+}
+
+#[test]
+fn play_sound_reaches_backend() {
+    let backend = MockAudioBackend::default();
+    let mut world = World::new();
+    world.set_audio_backend(Box::new(backend.clone()));
+
+    world.process_event(WorldEvent::PlaySound {
+        id: "explosion".into(),
+        volume: 0.5,
+        position: Some(Point3::new(1.0, 2.0, 3.0)),
+    });
+    world.process_event(WorldEvent::PlaySound {
+        id: "click".into(),
+        volume: 1.0,
+        position: None,
+    });
+
+    assert_eq!(
+        *backend.requests.lock().unwrap(),
+        vec![
+            SoundRequest {
+                id: "explosion".into(),
+                volume: 0.5,
+                position: Some(Point3::new(1.0, 2.0, 3.0)),
+                listener: None,
+            },
+            SoundRequest {
+                id: "click".into(),
+                volume: 1.0,
+                position: None,
+                listener: None,
+            },
+        ]
+    );
+}
+
+#[test]
+fn play_sound_includes_listener() {
+    let backend = MockAudioBackend::default();
+    let mut world = World::new();
+    world.set_audio_backend(Box::new(backend.clone()));
+
+    world.process_event(WorldEvent::Camera(CameraEvent::Spawn(CameraDescriptor {
+        position: Point3::new(4.0, 5.0, 6.0),
+        ..Default::default()
+    })));
+    world.process_event(WorldEvent::Camera(CameraEvent::Target(
+        CameraDescriptor::DEFAULT_NAME.into(),
+    )));
+    world.process_event(WorldEvent::PlaySound {
+        id: "step".into(),
+        volume: 1.0,
+        position: Some(Point3::new(0.0, 0.0, 0.0)),
+    });
+
+    let requests = backend.requests.lock().unwrap();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].listener, Some(Point3::new(4.0, 5.0, 6.0)));
+}
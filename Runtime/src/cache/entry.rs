@@ -20,10 +20,11 @@ impl<Value> CacheEntry<Value> {
     /// > Note: We assume here, that anyone using the given resources, `clone`s said inner Arc and keeps it stored for as long as needed.
     ///
     /// If the counter is 1, we assume that the value is no longer in use and mark it as unused by setting a timer.
+    /// The timer is only started once, repeated checks won't reset it.
     /// The outer cache will periodically check if the entry is unused and remove it if it passes beyond a threshold.
     pub fn cleanup_check(&mut self) {
         if Arc::<Value>::strong_count(&self.inner) == 1 {
-            self.unused_since = Some(Instant::now());
+            self.unused_since.get_or_insert_with(Instant::now);
         } else {
             self.unused_since = None;
         }
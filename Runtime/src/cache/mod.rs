@@ -1,15 +1,22 @@
 use std::{
+    convert::Infallible,
     hash::Hash,
     ops::{Deref, DerefMut},
     time::Duration,
 };
 
-use hashbrown::HashMap;
+use hashbrown::{hash_map::Entry, HashMap};
 use log::debug;
 
 mod entry;
 pub use entry::*;
 
+mod stats;
+pub use stats::*;
+
+#[cfg(test)]
+mod tests;
+
 #[derive(Debug)]
 pub struct Cache<Key, Value>
 where
@@ -18,6 +25,7 @@ where
 {
     timeout: Duration,
     map: HashMap<Key, CacheEntry<Value>>,
+    stats: CacheStats,
 }
 
 impl<Key, Value> Default for Cache<Key, Value>
@@ -39,25 +47,101 @@ where
         Self {
             timeout,
             map: HashMap::new(),
+            stats: CacheStats::default(),
+        }
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Changes how long an entry may stay unused before it's evicted.
+    /// Applies to the next [Self::cleanup], including entries that are
+    /// already unused.
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// Returns the statistics collected since creation or the last
+    /// [Self::reset_stats].
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.stats = CacheStats::default();
+    }
+
+    /// Looks up an entry, counting it as a hit or miss.
+    pub fn get_tracked(&mut self, key: &Key) -> Option<&CacheEntry<Value>> {
+        let entry = self.map.get(key);
+        match entry {
+            Some(_) => self.stats.hits += 1,
+            None => self.stats.misses += 1,
+        }
+        entry
+    }
+
+    /// Inserts an entry, counting it as an insertion.
+    /// Returns the replaced entry, if any.
+    pub fn insert_tracked(
+        &mut self,
+        key: Key,
+        entry: CacheEntry<Value>,
+    ) -> Option<CacheEntry<Value>> {
+        self.stats.insertions += 1;
+        self.map.insert(key, entry)
+    }
+
+    /// Looks up an entry and creates it if missing.
+    /// Counted as a hit, or as a miss followed by an insertion.
+    pub fn get_or_insert_with<F>(&mut self, key: Key, f: F) -> &CacheEntry<Value>
+    where
+        F: FnOnce() -> Value,
+    {
+        match self.get_or_try_insert_with(key, || Ok::<_, Infallible>(f())) {
+            Ok(entry) => entry,
         }
     }
 
+    /// Same as [Self::get_or_insert_with], but creating the entry may fail.
+    /// Nothing is inserted in that case.
+    pub fn get_or_try_insert_with<F, E>(&mut self, key: Key, f: F) -> Result<&CacheEntry<Value>, E>
+    where
+        F: FnOnce() -> Result<Value, E>,
+    {
+        match self.map.entry(key) {
+            Entry::Occupied(entry) => {
+                self.stats.hits += 1;
+                Ok(entry.into_mut())
+            }
+            Entry::Vacant(entry) => {
+                self.stats.misses += 1;
+                let value = f()?;
+                self.stats.insertions += 1;
+                Ok(entry.insert(CacheEntry::new(value)))
+            }
+        }
+    }
+
+    /// Removes any entry that hasn't been in use for longer than the timeout.
+    /// An entry is _in use_ as long as someone holds a clone of it's inner
+    /// `Arc` (see [CacheEntry::cleanup_check]).
     pub fn cleanup(&mut self) {
         // Perform cleanup check first
         self.map.values_mut().for_each(CacheEntry::cleanup_check);
 
-        #[cfg(debug_assertions)]
         let before = self.map.len();
 
         // Then remove anything past our threshold
         self.map
             .retain(|_, v| v.unused_since().is_none_or(|x| x.elapsed() <= self.timeout));
 
+        let after = self.map.len();
+        self.stats.evictions += (before - after) as u64;
+
         #[cfg(debug_assertions)]
-        {
-            let after = self.map.len();
-            debug!("Cache cleanup: {before} -> {after} entries (before -> after)");
-        }
+        debug!("Cache cleanup: {before} -> {after} entries (before -> after)");
     }
 }
 
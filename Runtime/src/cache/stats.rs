@@ -0,0 +1,25 @@
+/// Snapshot of how effective a [Cache](super::Cache) is.
+///
+/// Only accesses going through the tracked methods of the cache (e.g.
+/// [Cache::get_tracked](super::Cache::get_tracked)) are counted, raw
+/// accesses to the underlying map are not.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Number of lookups that found an entry.
+    pub hits: u64,
+    /// Number of lookups that didn't find an entry.
+    pub misses: u64,
+    /// Number of entries inserted.
+    pub insertions: u64,
+    /// Number of entries removed by [Cache::cleanup](super::Cache::cleanup).
+    pub evictions: u64,
+}
+
+impl CacheStats {
+    /// Ratio of hits to lookups, between `0.0` and `1.0`.
+    /// Returns [None] if there were no lookups yet.
+    pub fn hit_rate(&self) -> Option<f64> {
+        let lookups = self.hits + self.misses;
+        (lookups > 0).then(|| self.hits as f64 / lookups as f64)
+    }
+}
@@ -0,0 +1,119 @@
+use std::{thread::sleep, time::Duration};
+
+use super::{Cache, CacheEntry, CacheStats};
+
+#[test]
+fn cleanup_evicts_unreferenced_entries() {
+    let mut cache = Cache::new(Duration::from_millis(10));
+    cache.insert("referenced", CacheEntry::new(1));
+    cache.insert("unreferenced", CacheEntry::new(2));
+
+    let _handle = cache.get("referenced").unwrap().clone_inner();
+
+    // First cleanup only marks the unreferenced entry as unused
+    cache.cleanup();
+    assert_eq!(cache.len(), 2);
+    assert!(cache.get("referenced").unwrap().unused_since().is_none());
+    assert!(cache.get("unreferenced").unwrap().unused_since().is_some());
+
+    // Repeated cleanups must not reset the timer
+    sleep(Duration::from_millis(15));
+    cache.cleanup();
+
+    assert!(cache.contains_key("referenced"));
+    assert!(!cache.contains_key("unreferenced"));
+}
+
+#[test]
+fn cleanup_resets_timer_when_reused() {
+    let mut cache = Cache::new(Duration::from_millis(10));
+    cache.insert("entry", CacheEntry::new(1));
+
+    cache.cleanup();
+    assert!(cache.get("entry").unwrap().unused_since().is_some());
+
+    let _handle = cache.get("entry").unwrap().clone_inner();
+    sleep(Duration::from_millis(15));
+    cache.cleanup();
+
+    assert!(cache.get("entry").unwrap().unused_since().is_none());
+}
+
+#[test]
+fn set_timeout_applies_to_unused_entries() {
+    let mut cache = Cache::new(Duration::from_secs(60));
+    assert_eq!(cache.timeout(), Duration::from_secs(60));
+    cache.insert("entry", CacheEntry::new(1));
+
+    cache.cleanup();
+    sleep(Duration::from_millis(15));
+
+    // Still within the original timeout
+    cache.cleanup();
+    assert!(cache.contains_key("entry"));
+
+    // The entry is already unused for longer than the new timeout
+    cache.set_timeout(Duration::from_millis(10));
+    assert_eq!(cache.timeout(), Duration::from_millis(10));
+    cache.cleanup();
+    assert!(!cache.contains_key("entry"));
+}
+
+#[test]
+fn entries_are_kept_until_timeout() {
+    let mut cache = Cache::new(Duration::from_millis(50));
+    cache.insert("entry", CacheEntry::new(1));
+
+    cache.cleanup();
+    sleep(Duration::from_millis(10));
+    cache.cleanup();
+    assert!(cache.contains_key("entry"));
+
+    sleep(Duration::from_millis(50));
+    cache.cleanup();
+    assert!(!cache.contains_key("entry"));
+}
+
+#[test]
+fn stats_track_tracked_accesses() {
+    let mut cache = Cache::new(Duration::from_millis(10));
+    assert_eq!(cache.stats(), CacheStats::default());
+    assert_eq!(cache.stats().hit_rate(), None);
+
+    assert!(cache.get_tracked(&"a").is_none());
+    cache.insert_tracked("a", CacheEntry::new(1));
+    assert_eq!(**cache.get_tracked(&"a").unwrap(), 1);
+
+    // Miss + insertion, then a hit
+    assert_eq!(**cache.get_or_insert_with("b", || 2), 2);
+    assert_eq!(**cache.get_or_insert_with("b", || 3), 2);
+
+    // Failing creation is a miss without insertion
+    assert!(cache
+        .get_or_try_insert_with("c", || Err::<i32, _>("failed"))
+        .is_err());
+    assert!(!cache.contains_key("c"));
+
+    // Raw map access isn't tracked
+    assert!(cache.get("a").is_some());
+
+    let _handle = cache.get("a").unwrap().clone_inner();
+    cache.cleanup();
+    sleep(Duration::from_millis(15));
+    cache.cleanup();
+
+    let stats = cache.stats();
+    assert_eq!(
+        stats,
+        CacheStats {
+            hits: 2,
+            misses: 3,
+            insertions: 2,
+            evictions: 1,
+        }
+    );
+    assert_eq!(stats.hit_rate(), Some(0.4));
+
+    cache.reset_stats();
+    assert_eq!(cache.stats(), CacheStats::default());
+}
@@ -0,0 +1,103 @@
+use cgmath::Vector2;
+
+use crate::camera_controller::{DeadZoneMode, ResponseCurve};
+
+/// Maps raw axis values (e.g. gamepad sticks in `-1.0..=1.0`) to the values
+/// driving the camera.
+///
+/// By default, a stick is only cut off while both axes are within the
+/// [dead zone](Self::dead_zone) and passed through unchanged otherwise.
+/// With [DeadZoneMode::PerAxis], each axis is mapped on its own:
+/// Values within the dead zone become zero, while the range between the
+/// dead zone and the [saturation](Self::saturation) is rescaled to
+/// `0.0..=1.0` and shaped by the [curve](Self::curve).
+/// The sign of the value is kept.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AxisResponse {
+    /// For most controllers/gamepads, something around 0.1 should suffice.
+    /// This value depends highly on your controller and how much e.g.
+    /// "stick drift" you have.
+    /// On platforms that manage controller dead zones (e.g. consoles) this
+    /// might not be required and can simply be set to 0.0!
+    pub dead_zone: f64,
+    pub dead_zone_mode: DeadZoneMode,
+    pub curve: ResponseCurve,
+    /// Magnitude at which the output reaches its maximum of `1.0`.
+    /// Usually `1.0`, lower values help worn sticks to reach full output.
+    pub saturation: f64,
+}
+
+impl AxisResponse {
+    pub const DEFAULT_DEAD_ZONE: f64 = 0.1;
+
+    /// Raw values, cut off while both axes are within the given dead zone.
+    /// See [DeadZoneMode::Cutoff].
+    pub fn cutoff(dead_zone: f64) -> Self {
+        Self {
+            dead_zone,
+            dead_zone_mode: DeadZoneMode::Cutoff,
+            curve: ResponseCurve::Linear,
+            saturation: 1.0,
+        }
+    }
+
+    /// Linear per axis response with the given dead zone.
+    pub fn linear(dead_zone: f64) -> Self {
+        Self {
+            dead_zone,
+            dead_zone_mode: DeadZoneMode::PerAxis,
+            curve: ResponseCurve::Linear,
+            saturation: 1.0,
+        }
+    }
+
+    /// Quadratic per axis response with the given dead zone.
+    pub fn quadratic(dead_zone: f64) -> Self {
+        Self {
+            dead_zone,
+            dead_zone_mode: DeadZoneMode::PerAxis,
+            curve: ResponseCurve::Quadratic,
+            saturation: 1.0,
+        }
+    }
+
+    /// Maps a single raw axis value.
+    pub fn apply(&self, value: f64) -> f64 {
+        let magnitude = value.abs();
+        if magnitude <= self.dead_zone {
+            return 0.0;
+        }
+        if self.dead_zone_mode == DeadZoneMode::Cutoff {
+            return value;
+        }
+
+        let range = self.saturation - self.dead_zone;
+        let normalized = if range > 0.0 {
+            ((magnitude - self.dead_zone) / range).min(1.0)
+        } else {
+            1.0
+        };
+
+        value.signum() * self.curve.apply(normalized)
+    }
+
+    /// Maps both axes of a stick.
+    pub fn apply_vector(&self, value: Vector2<f64>) -> Vector2<f64> {
+        match self.dead_zone_mode {
+            DeadZoneMode::Cutoff => {
+                if value.x.abs() > self.dead_zone || value.y.abs() > self.dead_zone {
+                    value
+                } else {
+                    Vector2::new(0.0, 0.0)
+                }
+            }
+            DeadZoneMode::PerAxis => Vector2::new(self.apply(value.x), self.apply(value.y)),
+        }
+    }
+}
+
+impl Default for AxisResponse {
+    fn default() -> Self {
+        Self::cutoff(Self::DEFAULT_DEAD_ZONE)
+    }
+}
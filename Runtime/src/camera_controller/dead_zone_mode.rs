@@ -0,0 +1,15 @@
+/// Defines how the dead zone of an
+/// [AxisResponse](crate::camera_controller::AxisResponse) is applied.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum DeadZoneMode {
+    /// Both axes are passed through unchanged as soon as either axis
+    /// exceeds the dead zone, otherwise both are zero.
+    /// The curve and saturation are ignored.
+    #[default]
+    Cutoff,
+    /// Each axis is mapped on its own.
+    /// Values within the dead zone become zero, while the range between the
+    /// dead zone and the saturation is rescaled to `0.0..=1.0` and shaped by
+    /// the curve.
+    PerAxis,
+}
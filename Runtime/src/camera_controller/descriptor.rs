@@ -1,4 +1,6 @@
-use crate::camera_controller::{CameraControllerMovementType, CameraControllerRotationType};
+use crate::camera_controller::{
+    CameraControllerMovementType, CameraControllerRotationType, CameraControllerZoomMode,
+};
 use crate::resources::CameraDescriptor;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -7,6 +9,9 @@ pub struct CameraControllerDescriptor {
     pub movement_type: CameraControllerMovementType,
     /// Controls how the camera rotates.
     pub rotation_type: CameraControllerRotationType,
+    /// Controls how the camera zooms.
+    /// If `None`, the camera can't be zoomed.
+    pub zoom_mode: Option<CameraControllerZoomMode>,
     /// The actual camera descriptor that is spawned and handles rendering.
     pub camera_descriptor: CameraDescriptor,
 }
@@ -9,6 +9,7 @@
 //! - **Descriptor**: Defines camera controller configuration
 //! - **MovementType**: Different ways the camera can move (e.g., free, orbital, first-person)
 //! - **RotationType**: Different ways the camera can rotate (e.g., mouse look, controller)
+//! - **ZoomMode**: Zooming by changing the field of view (e.g., mouse scroll wheel)
 //! - **Input Types**: Various input methods including mouse, keyboard, and gamepad
 //! - **Realization**: Runtime representation of the camera controller
 //!
@@ -35,11 +36,26 @@ pub use mouse_input::*;
 mod mouse_input_type;
 pub use mouse_input_type::*;
 
+mod zoom_mode;
+pub use zoom_mode::*;
+
 mod axis_input;
 pub use axis_input::*;
 
+mod axis_response;
+pub use axis_response::*;
+
+mod response_curve;
+pub use response_curve::*;
+
+mod dead_zone_mode;
+pub use dead_zone_mode::*;
+
 mod button_input;
 pub use button_input::*;
 
 mod realization;
 pub use realization::*;
+
+#[cfg(test)]
+mod tests;
@@ -1,5 +1,5 @@
 use crate::app::input::{InputAxis, InputButton};
-use crate::camera_controller::ButtonAxis;
+use crate::camera_controller::{AxisResponse, ButtonAxis};
 use cgmath::Vector3;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -25,11 +25,18 @@ pub enum CameraControllerMovementType {
         /// However, there are some exceptions like, for example, _"creative flight"_, _free flight_,
         /// diving/swimming or space.
         ignore_pitch_for_forward_movement: bool,
-        /// For most controllers/gamepads, something around 0.1 should suffice.
-        /// This value depends highly on your controller and how much e.g. "stick drift" you have.
-        /// Some platforms may manage controller dead zones, but settings this regardless should
-        /// not interfere with anything.
-        axis_dead_zone: f64,
+        /// Dead zone and response curve applied to the movement [axis](Self::Input::axis).
+        /// Use [AxisResponse::cutoff] with a dead zone around 0.1 for most
+        /// controllers/gamepads.
+        axis_response: AxisResponse,
+        /// If set, movement eases in and out instead of starting and
+        /// stopping instantly.
+        /// The movement approaches the input exponentially, where the value
+        /// is the time (in seconds) needed to cover ~63% of the difference.
+        /// Values around 0.1 feel responsive, while still being smooth.
+        ///
+        /// If `None`, inputs are applied directly.
+        smoothing: Option<f32>,
     },
     /// Follows an entity with a given offset.
     Following {
@@ -1,26 +1,47 @@
 use crate::app::input::{InputAxis, InputState};
 use crate::app::AppEvent;
 use crate::camera_controller::{
-    ButtonAxis, CameraControllerAxisInputMode, CameraControllerButtonInputMode,
+    AxisResponse, ButtonAxis, CameraControllerAxisInputMode, CameraControllerButtonInputMode,
     CameraControllerDescriptor, CameraControllerMovementType, CameraControllerRotationType,
 };
 use crate::element::{CameraEvent, Element, ElementRegistration, Event, Message, WorldEvent};
 use crate::resources::{CameraTransform, Mode};
 use async_trait::async_trait;
 use cgmath::num_traits::abs;
-use cgmath::{Vector2, Vector3, Zero};
+use cgmath::{InnerSpace, Vector2, Vector3, Zero};
 use std::sync::Arc;
 
 #[derive(Debug)]
 pub struct CameraController {
     descriptor: CameraControllerDescriptor,
+    /// Current, smoothed, movement per update.
+    /// Only used if movement smoothing is enabled.
+    movement_velocity: Vector3<f32>,
+    /// Current, smoothed, pitch (X) and yaw (Y) change per update.
+    /// Only used if rotation smoothing is enabled.
+    rotation_velocity: Vector2<f32>,
+    /// Field of view the camera is zoomed to.
+    /// Zooming is unavailable for orthographic cameras.
+    fovy: Option<f32>,
+    /// Whether mouse inputs are suspended, because the mouse isn't
+    /// [captured](InputState::mouse_captured).
+    suspended: bool,
 }
 
 impl CameraController {
     const AXIS_NORMALIZATION_TO_MATCH_MOUSE_SENSITIVITY: f32 = 0.01;
 
+    /// Smoothed velocities below this are considered to be at rest.
+    const SMOOTHING_REST_THRESHOLD: f32 = 1e-6;
+
     pub fn new(descriptor: CameraControllerDescriptor) -> Self {
-        Self { descriptor }
+        Self {
+            fovy: descriptor.camera_descriptor.projection.fovy(),
+            descriptor,
+            movement_velocity: Vector3::zero(),
+            rotation_velocity: Vector2::zero(),
+            suspended: false,
+        }
     }
 
     pub fn controller_label(&self) -> String {
@@ -34,6 +55,12 @@ impl CameraController {
         self.descriptor.camera_descriptor.label.clone()
     }
 
+    /// Whether mouse inputs are currently ignored, because the mouse got
+    /// released.
+    pub fn is_suspended(&self) -> bool {
+        self.suspended
+    }
+
     fn update_camera(&mut self, delta_time: f64, input_state: &InputState) -> Option<Event> {
         let mut transform = CameraTransform {
             label: self.camera_label(),
@@ -41,10 +68,17 @@ impl CameraController {
             pitch: None,
             yaw: None,
             roll: None,
+            fovy: None,
         };
 
+        // The first update after re-capturing is skipped as well, as the
+        // mouse may still have moved before the capture got applied.
+        let ignore_mouse = self.suspended || !input_state.mouse_captured();
+        self.suspended = !input_state.mouse_captured();
+
         self.handle_movement(delta_time, &mut transform, input_state);
-        self.handle_rotation(delta_time, &mut transform, input_state);
+        self.handle_rotation(delta_time, &mut transform, input_state, ignore_mouse);
+        self.handle_zoom(&mut transform, input_state);
 
         if transform.is_introducing_change() {
             Some(Event::World(WorldEvent::Camera(CameraEvent::Transform(
@@ -55,8 +89,18 @@ impl CameraController {
         }
     }
 
+    /// Returns how far a smoothed value moves towards its target within
+    /// `delta_time`, given the smoothing time constant.
+    fn smoothing_factor(delta_time: f64, smoothing: f32) -> f32 {
+        if smoothing <= 0.0 {
+            return 1.0;
+        }
+
+        1.0 - (-(delta_time as f32) / smoothing).exp()
+    }
+
     fn handle_movement(
-        &self,
+        &mut self,
         delta_time: f64,
         transform: &mut CameraTransform,
         input_state: &InputState,
@@ -69,13 +113,13 @@ impl CameraController {
                 button_down,
                 speed,
                 ignore_pitch_for_forward_movement,
-                axis_dead_zone,
+                axis_response,
+                smoothing,
             } => {
                 let mut movement_vector = Vector3::<f64>::zero();
 
                 if let Some(axis) = axis {
-                    if let Some(delta_vector) = self.read_delta(axis, input_state, *axis_dead_zone)
-                    {
+                    if let Some(delta_vector) = self.read_axis(axis, input_state, axis_response) {
                         movement_vector.x += delta_vector.x;
                         movement_vector.z += delta_vector.y;
                     }
@@ -118,6 +162,17 @@ impl CameraController {
                     movement_vector.cast().expect("Cast must succeed!");
                 output_vector *= *speed;
 
+                if let Some(smoothing) = smoothing {
+                    let factor = Self::smoothing_factor(delta_time, *smoothing);
+                    self.movement_velocity += (output_vector - self.movement_velocity) * factor;
+                    if output_vector.is_zero()
+                        && self.movement_velocity.magnitude2() < Self::SMOOTHING_REST_THRESHOLD
+                    {
+                        self.movement_velocity = Vector3::zero();
+                    }
+                    output_vector = self.movement_velocity;
+                }
+
                 if *ignore_pitch_for_forward_movement {
                     transform.position = Some(Mode::OffsetViewAligned(output_vector));
                 } else {
@@ -192,10 +247,10 @@ impl CameraController {
         mode: &CameraControllerAxisInputMode,
         transform: &mut CameraTransform,
         input_state: &InputState,
-        axis_dead_zone: f64,
+        axis_response: &AxisResponse,
     ) -> bool {
         for axis in &mode.axis {
-            if let Some(delta) = self.read_delta(axis, input_state, axis_dead_zone) {
+            if let Some(delta) = self.read_axis(axis, input_state, axis_response) {
                 return self.apply_delta_to_transform(
                     &delta,
                     transform,
@@ -208,23 +263,63 @@ impl CameraController {
     }
 
     fn handle_rotation(
+        &mut self,
+        delta_time: f64,
+        transform: &mut CameraTransform,
+        input_state: &InputState,
+        ignore_mouse: bool,
+    ) {
+        self.apply_rotation_input(delta_time, transform, input_state, ignore_mouse);
+
+        if let CameraControllerRotationType::Free {
+            smoothing: Some(smoothing),
+            ..
+        } = &self.descriptor.rotation_type
+        {
+            let offset = |mode: &Option<Mode<f32>>| match mode {
+                Some(Mode::Offset(x)) => *x,
+                _ => 0.0,
+            };
+            let target = Vector2::new(offset(&transform.pitch), offset(&transform.yaw));
+
+            let factor = Self::smoothing_factor(delta_time, *smoothing);
+            self.rotation_velocity += (target - self.rotation_velocity) * factor;
+            if target.is_zero()
+                && self.rotation_velocity.magnitude2() < Self::SMOOTHING_REST_THRESHOLD
+            {
+                self.rotation_velocity = Vector2::zero();
+            }
+
+            if self.rotation_velocity.is_zero() {
+                transform.pitch = None;
+                transform.yaw = None;
+            } else {
+                transform.pitch = Some(Mode::Offset(self.rotation_velocity.x));
+                transform.yaw = Some(Mode::Offset(self.rotation_velocity.y));
+            }
+        }
+    }
+
+    fn apply_rotation_input(
         &self,
         delta_time: f64,
         transform: &mut CameraTransform,
         input_state: &InputState,
+        ignore_mouse: bool,
     ) {
         match &self.descriptor.rotation_type {
             CameraControllerRotationType::Free {
                 axis_input,
                 button_input,
                 mouse_input,
-                axis_dead_zone,
+                axis_response,
+                ..
             } => {
                 // Delta inputs (gamepad) first
                 if axis_input
                     .as_ref()
                     .map(|x| {
-                        self.apply_delta_axis_rotation(x, transform, input_state, *axis_dead_zone)
+                        self.apply_delta_axis_rotation(x, transform, input_state, axis_response)
                     })
                     .unwrap_or(false)
                 {
@@ -241,6 +336,9 @@ impl CameraController {
                 }
 
                 // Lastly, mouse inputs
+                if ignore_mouse {
+                    return;
+                }
                 if let Some(x) = mouse_input {
                     x.input_type.is_triggering(input_state).then(|| {
                         self.apply_mouse_view(
@@ -259,6 +357,26 @@ impl CameraController {
         }
     }
 
+    /// Changes the field of view based on scroll wheel inputs.
+    /// The field of view is tracked here and sent as a whole, so it can be
+    /// clamped to the zoom range.
+    fn handle_zoom(&mut self, transform: &mut CameraTransform, input_state: &InputState) {
+        let (Some(zoom_mode), Some(current_fovy)) = (&self.descriptor.zoom_mode, self.fovy) else {
+            return;
+        };
+
+        if let Some(delta) = self.read_delta(&InputAxis::MouseScrollWheel, input_state, 0.0) {
+            // Scrolling up (positive) zooms in
+            let fovy = (current_fovy - delta.x as f32 * zoom_mode.sensitivity)
+                .clamp(zoom_mode.min_fovy, zoom_mode.max_fovy);
+
+            if fovy != current_fovy {
+                self.fovy = Some(fovy);
+                transform.fovy = Some(Mode::Overwrite(fovy));
+            }
+        }
+    }
+
     /// Will read a delta state (axis) and return its value if any input got recorded by the [`InputState`].
     /// Upon receiving a delta state (value), if the given state exceeds the standard range (-1.0 to +1.0),
     /// it will be normalized. A value can only be normalized if a resolution has been set prior.
@@ -279,6 +397,20 @@ impl CameraController {
         })
     }
 
+    /// Same as [`Self::read_delta`], but maps the value through an [`AxisResponse`].
+    /// Returns `None` if both axes end up within the dead zone.
+    fn read_axis(
+        &self,
+        axis: &InputAxis,
+        input_state: &InputState,
+        response: &AxisResponse,
+    ) -> Option<Vector2<f64>> {
+        let (_, delta) = input_state.delta_state_any(axis)?;
+        let delta = response.apply_vector(delta);
+
+        (!delta.is_zero()).then_some(delta)
+    }
+
     /// Returns `true` if mouse movement was detected and got applied.
     /// Returns `false` otherwise.
     fn apply_mouse_view(
@@ -0,0 +1,22 @@
+/// Shapes the output of an [AxisResponse](crate::camera_controller::AxisResponse)
+/// between its dead zone and saturation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ResponseCurve {
+    /// Output grows proportionally with the input.
+    #[default]
+    Linear,
+    /// Output grows with the square of the input.
+    /// Gives finer control for small stick movements, while still reaching
+    /// full output.
+    Quadratic,
+}
+
+impl ResponseCurve {
+    /// Shapes a value in the range of `0.0..=1.0`.
+    pub fn apply(&self, value: f64) -> f64 {
+        match self {
+            ResponseCurve::Linear => value,
+            ResponseCurve::Quadratic => value * value,
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use crate::camera_controller::{
-    CameraControllerAxisInputMode, CameraControllerButtonInputMode, CameraControllerMouseInputMode,
+    AxisResponse, CameraControllerAxisInputMode, CameraControllerButtonInputMode,
+    CameraControllerMouseInputMode,
 };
 
 #[derive(Debug, Clone, PartialEq)]
@@ -13,12 +14,17 @@ pub enum CameraControllerRotationType {
         /// Controls mouse behavior
         mouse_input: Option<CameraControllerMouseInputMode>,
 
-        /// For most controllers/gamepads, something around 0.1 should suffice.
-        /// This value depends highly on your controller and how much e.g. "stick drift" you have.
-        /// On platforms that manage controller dead zones (e.g. consoles) this might not be required
-        /// and can simply be set to 0.0! However, setting this to anything but zero on managed platforms
-        /// shouldn't interfere either.
-        axis_dead_zone: f64,
+        /// Dead zone and response curve applied to the
+        /// [axis input](Self::Free::axis_input).
+        /// Check [AxisResponse] for details.
+        axis_response: AxisResponse,
+        /// If set, rotation eases in and out instead of starting and
+        /// stopping instantly.
+        /// Works the same as the `smoothing` of
+        /// [`CameraControllerMovementType::Input`](crate::camera_controller::CameraControllerMovementType::Input).
+        ///
+        /// If `None`, inputs are applied directly.
+        smoothing: Option<f32>,
     },
     /// A camera controller that is locked and will not be rotated automatically.
     /// A locked camera can still be interacted with and manually rotated!
@@ -0,0 +1,343 @@
+use async_std::task::block_on;
+use cgmath::{Vector2, Vector3, Zero};
+use winit::event::{DeviceId, ElementState, MouseButton, MouseScrollDelta, TouchPhase};
+
+use crate::app::input::{InputAxis, InputButton, InputEvent, InputState};
+use crate::camera_controller::{
+    AxisResponse, ButtonAxis, CameraController, CameraControllerButtonInputMode,
+    CameraControllerDescriptor, CameraControllerMouseInputMode, CameraControllerMouseInputType,
+    CameraControllerMovementType, CameraControllerRotationType, CameraControllerZoomMode,
+    DeadZoneMode, ResponseCurve,
+};
+use crate::element::{CameraEvent, Element, Event, WorldEvent};
+use crate::resources::{CameraDescriptor, CameraTransform, Mode};
+
+const DELTA_TIME: f64 = 1.0 / 60.0;
+
+/// Movement and rotation axis, where "forward" is bound to the left mouse
+/// button.
+fn button_axis() -> ButtonAxis {
+    ButtonAxis {
+        forward: InputButton::Mouse(MouseButton::Left),
+        backward: InputButton::Mouse(MouseButton::Back),
+        left: InputButton::Mouse(MouseButton::Forward),
+        right: InputButton::Mouse(MouseButton::Middle),
+    }
+}
+
+fn controller(smoothing: Option<f32>) -> CameraController {
+    controller_with_zoom(smoothing, None)
+}
+
+fn controller_with_zoom(
+    smoothing: Option<f32>,
+    zoom_mode: Option<CameraControllerZoomMode>,
+) -> CameraController {
+    CameraController::new(CameraControllerDescriptor {
+        movement_type: CameraControllerMovementType::Input {
+            axis: None,
+            button_axis: Some(vec![button_axis()]),
+            button_up: None,
+            button_down: None,
+            speed: 1.0,
+            ignore_pitch_for_forward_movement: true,
+            axis_response: AxisResponse::cutoff(0.1),
+            smoothing,
+        },
+        rotation_type: CameraControllerRotationType::Free {
+            axis_input: None,
+            button_input: Some(CameraControllerButtonInputMode {
+                button_axis: vec![button_axis()],
+                sensitivity: 1.0,
+            }),
+            mouse_input: None,
+            axis_response: AxisResponse::cutoff(0.1),
+            smoothing,
+        },
+        zoom_mode,
+        camera_descriptor: CameraDescriptor::default(),
+    })
+}
+
+fn forward_input(pressed: bool) -> InputState {
+    let mut input_state = InputState::new();
+    input_state.handle_event(InputEvent::MouseButton {
+        device_id: DeviceId::dummy(),
+        state: if pressed {
+            ElementState::Pressed
+        } else {
+            ElementState::Released
+        },
+        button: MouseButton::Left,
+    });
+    input_state
+}
+
+/// Returns the camera transform emitted by the controller, if any.
+fn update(controller: &mut CameraController, input_state: &InputState) -> Option<CameraTransform> {
+    let mut events = block_on(controller.on_update(DELTA_TIME, input_state))?;
+    assert_eq!(events.len(), 1);
+    match events.remove(0) {
+        Event::World(WorldEvent::Camera(CameraEvent::Transform(transform))) => Some(transform),
+        x => panic!("Expected a camera transform, got: {x:?}"),
+    }
+}
+
+fn movement(transform: &CameraTransform) -> Vector3<f32> {
+    match transform.position {
+        Some(Mode::OffsetViewAligned(x)) => x,
+        ref x => panic!("Expected a view aligned offset, got: {x:?}"),
+    }
+}
+
+fn pitch(transform: &CameraTransform) -> f32 {
+    match transform.pitch {
+        Some(Mode::Offset(x)) => x,
+        None => 0.0,
+        ref x => panic!("Expected a pitch offset, got: {x:?}"),
+    }
+}
+
+#[test]
+fn without_smoothing_input_applies_instantly() {
+    let mut controller = controller(None);
+
+    let transform = update(&mut controller, &forward_input(true)).unwrap();
+    let target = movement(&transform);
+    assert!(target.x > 0.0);
+    assert_eq!(pitch(&transform), target.x);
+
+    // Releasing stops instantly as well
+    assert!(update(&mut controller, &forward_input(false)).is_none());
+}
+
+#[test]
+fn smoothing_approaches_target_asymptotically() {
+    let target = movement(&update(&mut controller(None), &forward_input(true)).unwrap());
+    let mut controller = controller(Some(0.1));
+
+    let input_state = forward_input(true);
+    let mut last = 0.0;
+    for _ in 0..30 {
+        let transform = update(&mut controller, &input_state).unwrap();
+        let current = movement(&transform).x;
+
+        assert!(current > last, "Movement must ease in");
+        assert!(current < target.x, "Movement must not overshoot");
+        assert_eq!(pitch(&transform), current);
+        last = current;
+    }
+    // Half a second is five time constants
+    assert!(last > target.x * 0.99);
+
+    // Releasing eases out until the camera comes to rest
+    let input_state = forward_input(false);
+    let mut frames = 0;
+    while let Some(transform) = update(&mut controller, &input_state) {
+        let current = movement(&transform).x;
+
+        assert!(current < last, "Movement must ease out");
+        assert!(current >= 0.0);
+        last = current;
+        frames += 1;
+    }
+    assert!(frames > 1, "Movement must not stop instantly");
+    assert!(frames < 600, "Movement must come to rest");
+}
+
+fn scroll_input(lines: f32) -> InputState {
+    let mut input_state = InputState::new();
+    input_state.handle_event(InputEvent::MouseWheel {
+        device_id: DeviceId::dummy(),
+        delta: MouseScrollDelta::LineDelta(0.0, lines),
+        phase: TouchPhase::Moved,
+    });
+    input_state
+}
+
+fn fovy(transform: &CameraTransform) -> f32 {
+    match transform.fovy {
+        Some(Mode::Overwrite(x)) => x,
+        ref x => panic!("Expected a field of view, got: {x:?}"),
+    }
+}
+
+#[test]
+fn scroll_zooms_within_bounds() {
+    let zoom_mode = CameraControllerZoomMode {
+        min_fovy: 20.0,
+        max_fovy: 60.0,
+        sensitivity: 5.0,
+    };
+    let mut controller = controller_with_zoom(None, Some(zoom_mode));
+    let initial_fovy = CameraDescriptor::default().projection.fovy().unwrap();
+
+    // Scrolling up zooms in
+    let transform = update(&mut controller, &scroll_input(1.0)).unwrap();
+    assert_eq!(fovy(&transform), initial_fovy - 5.0);
+    assert!(transform.position.is_some());
+
+    // Scrolling down zooms out
+    let transform = update(&mut controller, &scroll_input(-2.0)).unwrap();
+    assert_eq!(fovy(&transform), initial_fovy + 5.0);
+
+    // Zooming is clamped
+    let transform = update(&mut controller, &scroll_input(100.0)).unwrap();
+    assert_eq!(fovy(&transform), 20.0);
+    assert!(update(&mut controller, &scroll_input(1.0)).is_none());
+
+    let transform = update(&mut controller, &scroll_input(-100.0)).unwrap();
+    assert_eq!(fovy(&transform), 60.0);
+}
+
+#[test]
+fn scroll_without_zoom_mode() {
+    let mut controller = controller(None);
+
+    assert!(update(&mut controller, &scroll_input(1.0)).is_none());
+}
+
+fn mouse_controller() -> CameraController {
+    CameraController::new(CameraControllerDescriptor {
+        movement_type: CameraControllerMovementType::Static,
+        rotation_type: CameraControllerRotationType::Free {
+            axis_input: None,
+            button_input: None,
+            mouse_input: Some(CameraControllerMouseInputMode {
+                input_type: CameraControllerMouseInputType::Always,
+                sensitivity: 1.0,
+                grab_cursor: true,
+                hide_cursor: true,
+            }),
+            axis_response: AxisResponse::cutoff(0.1),
+            smoothing: None,
+        },
+        zoom_mode: None,
+        camera_descriptor: CameraDescriptor::default(),
+    })
+}
+
+fn mouse_input(input_state: &mut InputState, delta: (f64, f64)) {
+    input_state.handle_event(InputEvent::MouseMovedDelta {
+        device_id: DeviceId::dummy(),
+        delta,
+    });
+}
+
+#[test]
+fn mouse_capture_suspends_controller() {
+    let mut controller = mouse_controller();
+    let mut input_state = InputState::new();
+    assert!(input_state.mouse_captured());
+
+    mouse_input(&mut input_state, (10.0, 10.0));
+    assert!(update(&mut controller, &input_state).is_some());
+    input_state.reset_deltas();
+
+    // Released: mouse movement is ignored
+    input_state.set_mouse_captured(false);
+    mouse_input(&mut input_state, (10.0, 10.0));
+    assert!(update(&mut controller, &input_state).is_none());
+    assert!(controller.is_suspended());
+
+    // Movement accumulated while released is discarded when re-capturing
+    mouse_input(&mut input_state, (500.0, 500.0));
+    input_state.set_mouse_captured(true);
+    assert_eq!(
+        input_state
+            .delta_state_any(&InputAxis::MouseMovement)
+            .map(|(_, delta)| delta),
+        Some(Vector2::zero())
+    );
+
+    // The first update after re-capturing is skipped as well
+    mouse_input(&mut input_state, (500.0, 500.0));
+    assert!(update(&mut controller, &input_state).is_none());
+    assert!(!controller.is_suspended());
+    input_state.reset_deltas();
+
+    mouse_input(&mut input_state, (10.0, 10.0));
+    assert!(update(&mut controller, &input_state).is_some());
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < 1e-9,
+        "Expected {expected}, got {actual}"
+    );
+}
+
+#[test]
+fn axis_response_linear() {
+    let response = AxisResponse::linear(0.2);
+
+    // Within the dead zone
+    assert_eq!(response.apply(0.0), 0.0);
+    assert_eq!(response.apply(0.1), 0.0);
+    assert_eq!(response.apply(-0.2), 0.0);
+
+    // Rescaled between dead zone and saturation
+    assert_close(response.apply(0.6), 0.5);
+    assert_close(response.apply(-0.6), -0.5);
+    assert_close(response.apply(1.0), 1.0);
+
+    // Without a dead zone, values are kept as-is
+    assert_close(AxisResponse::linear(0.0).apply(0.3), 0.3);
+}
+
+#[test]
+fn axis_response_quadratic() {
+    let response = AxisResponse::quadratic(0.2);
+
+    assert_eq!(response.apply(0.15), 0.0);
+    assert_close(response.apply(0.6), 0.25);
+    assert_close(response.apply(-0.6), -0.25);
+    assert_close(response.apply(1.0), 1.0);
+}
+
+#[test]
+fn axis_response_saturation() {
+    let response = AxisResponse {
+        dead_zone: 0.1,
+        dead_zone_mode: DeadZoneMode::PerAxis,
+        curve: ResponseCurve::Linear,
+        saturation: 0.9,
+    };
+
+    assert_close(response.apply(0.5), 0.5);
+    assert_close(response.apply(0.9), 1.0);
+    assert_close(response.apply(-1.0), -1.0);
+}
+
+#[test]
+fn axis_response_cutoff() {
+    let response = AxisResponse::default();
+    assert_eq!(
+        response,
+        AxisResponse::cutoff(AxisResponse::DEFAULT_DEAD_ZONE)
+    );
+
+    // Both axes within the dead zone
+    assert_eq!(
+        response.apply_vector(Vector2::new(0.05, -0.1)),
+        Vector2::new(0.0, 0.0)
+    );
+
+    // Either axis exceeding the dead zone passes the raw vector
+    assert_eq!(
+        response.apply_vector(Vector2::new(0.05, 0.6)),
+        Vector2::new(0.05, 0.6)
+    );
+    assert_close(response.apply(-0.6), -0.6);
+    assert_eq!(response.apply(0.05), 0.0);
+}
+
+#[test]
+fn axis_response_per_axis() {
+    let response = AxisResponse::linear(AxisResponse::DEFAULT_DEAD_ZONE);
+
+    // Drift on one axis doesn't leak into the result
+    let mapped = response.apply_vector(Vector2::new(0.05, 1.0));
+    assert_eq!(mapped.x, 0.0);
+    assert_close(mapped.y, 1.0);
+}
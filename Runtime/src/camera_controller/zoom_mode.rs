@@ -0,0 +1,14 @@
+/// Zooms the camera by changing its vertical field of view with the mouse
+/// scroll wheel.
+///
+/// Scrolling up narrows the field of view (zooms in), scrolling down widens
+/// it (zooms out).
+#[derive(Debug, Clone, PartialEq)]
+pub struct CameraControllerZoomMode {
+    /// Smallest field of view (in degrees) that can be zoomed to.
+    pub min_fovy: f32,
+    /// Largest field of view (in degrees) that can be zoomed to.
+    pub max_fovy: f32,
+    /// Degrees the field of view changes per scrolled line.
+    pub sensitivity: f32,
+}
@@ -0,0 +1,309 @@
+//! # Developer Console Module
+//!
+//! The developer console module provides an in-engine console [`Element`] to run text
+//! commands at runtime, e.g. to despawn models or clear the world while iterating.
+//!
+//! ## Key Components
+//!
+//! - **DevConsole**: The console element, toggled by a key, with input line and history
+//! - **Command Registry**: Apps can extend the console with their own commands
+//!
+//! ## Usage
+//!
+//! Create a [`DevConsole`], register any additional commands and spawn it like any
+//! other [`Element`]:
+//!
+//! ```rust
+//! # use orbital::dev_console::DevConsole;
+//! # use orbital::element::{Event, WorldEvent};
+//! let mut console = DevConsole::new();
+//! console.register("reset", |_arguments| Ok(vec![Event::World(WorldEvent::Clear)]));
+//! ```
+//!
+//! Command lines are split by whitespace into the command name and its arguments.
+//! Arguments containing whitespace can be quoted (`"like this"`).
+//!
+//! There is no text rendering yet, thus the input line and command output are written
+//! to the log.
+
+use std::{collections::HashMap, fmt::Debug, sync::Arc};
+
+use async_trait::async_trait;
+use log::{info, warn};
+use winit::keyboard::{Key, KeyCode, NamedKey, PhysicalKey};
+
+use crate::{
+    app::{input::InputState, AppEvent},
+    element::{Element, ElementEvent, ElementRegistration, Event, Message, ModelEvent, WorldEvent},
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Handler of a console command.
+/// Receives the parsed arguments (excluding the command name) and returns
+/// [`Event`]s to be executed, or an error message to be shown.
+pub type DevConsoleCommand = Box<dyn Fn(&[String]) -> Result<Vec<Event>, String> + Send + Sync>;
+
+/// In-engine developer console.
+/// Check the [module documentation](self) for details.
+pub struct DevConsole {
+    commands: HashMap<String, DevConsoleCommand>,
+    toggle_key: PhysicalKey,
+    open: bool,
+    input: String,
+    history: Vec<String>,
+    history_cursor: Option<usize>,
+}
+
+impl Default for DevConsole {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for DevConsole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DevConsole")
+            .field("commands", &self.commands.keys().collect::<Vec<_>>())
+            .field("toggle_key", &self.toggle_key)
+            .field("open", &self.open)
+            .field("input", &self.input)
+            .field("history", &self.history)
+            .finish()
+    }
+}
+
+impl DevConsole {
+    pub const LABEL: &'static str = "DevConsole";
+
+    /// Creates a new console with the built-in commands registered:
+    /// - `despawn_model <label>`: Despawns a model
+    /// - `despawn_element <label>`: Despawns an element
+    /// - `clear`: Clears the world
+    /// - `quit`: Requests the app to close
+    ///
+    /// The console is toggled with the key left of `1` (`` ` `` on US layouts).
+    pub fn new() -> Self {
+        let mut console = Self {
+            commands: HashMap::new(),
+            toggle_key: PhysicalKey::Code(KeyCode::Backquote),
+            open: false,
+            input: String::new(),
+            history: Vec::new(),
+            history_cursor: None,
+        };
+
+        console.register("despawn_model", |arguments| {
+            let [label] = arguments else {
+                return Err("Usage: despawn_model <label>".to_string());
+            };
+            Ok(vec![Event::World(WorldEvent::Model(ModelEvent::Despawn(
+                label.clone(),
+            )))])
+        });
+        console.register("despawn_element", |arguments| {
+            let [label] = arguments else {
+                return Err("Usage: despawn_element <label>".to_string());
+            };
+            Ok(vec![Event::Element(ElementEvent::Despawn(label.clone()))])
+        });
+        console.register("clear", |_| Ok(vec![Event::World(WorldEvent::Clear)]));
+        console.register("quit", |_| {
+            Ok(vec![Event::App(AppEvent::RequestAppClosure)])
+        });
+
+        console
+    }
+
+    /// Changes the key used to open and close the console.
+    pub fn with_toggle_key(mut self, toggle_key: PhysicalKey) -> Self {
+        self.toggle_key = toggle_key;
+        self
+    }
+
+    /// Registers a command.
+    /// Registering a command with an existing name replaces the previous one.
+    pub fn register<S, F>(&mut self, name: S, handler: F)
+    where
+        S: Into<String>,
+        F: Fn(&[String]) -> Result<Vec<Event>, String> + Send + Sync + 'static,
+    {
+        self.commands.insert(name.into(), Box::new(handler));
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// Previously executed command lines, oldest first.
+    pub fn history(&self) -> &[String] {
+        &self.history
+    }
+
+    /// Executes a command line and returns the resulting [`Event`]s.
+    /// The line gets added to the history, even if the command fails.
+    pub fn execute(&mut self, line: &str) -> Vec<Event> {
+        let line = line.trim();
+        if line.is_empty() {
+            return Vec::new();
+        }
+
+        if self.history.last().is_none_or(|last| last != line) {
+            self.history.push(line.to_string());
+        }
+        self.history_cursor = None;
+
+        let mut arguments = Self::parse_arguments(line);
+        let name = arguments.remove(0);
+
+        if name == "help" {
+            let mut names = self.commands.keys().cloned().collect::<Vec<_>>();
+            names.sort();
+            info!("[Console] Commands: help, {}", names.join(", "));
+            return Vec::new();
+        }
+
+        match self.commands.get(&name) {
+            Some(command) => match command(&arguments) {
+                Ok(events) => events,
+                Err(e) => {
+                    warn!("[Console] {name}: {e}");
+                    Vec::new()
+                }
+            },
+            None => {
+                warn!("[Console] Unknown command '{name}'. Type 'help' for a list of commands.");
+                Vec::new()
+            }
+        }
+    }
+
+    /// Splits a command line by whitespace.
+    /// Double quotes can be used to include whitespace in an argument.
+    pub fn parse_arguments(line: &str) -> Vec<String> {
+        let mut arguments = Vec::new();
+        let mut current = String::new();
+        let mut quoted = false;
+        let mut has_argument = false;
+
+        for c in line.chars() {
+            match c {
+                '"' => {
+                    quoted = !quoted;
+                    has_argument = true;
+                }
+                c if c.is_whitespace() && !quoted => {
+                    if has_argument {
+                        arguments.push(std::mem::take(&mut current));
+                        has_argument = false;
+                    }
+                }
+                c => {
+                    current.push(c);
+                    has_argument = true;
+                }
+            }
+        }
+
+        if has_argument {
+            arguments.push(current);
+        }
+
+        arguments
+    }
+
+    /// Navigates the history, `true` going back to older entries.
+    fn navigate_history(&mut self, older: bool) {
+        if self.history.is_empty() {
+            return;
+        }
+
+        self.history_cursor = match (self.history_cursor, older) {
+            (None, true) => Some(self.history.len() - 1),
+            (None, false) => None,
+            (Some(cursor), true) => Some(cursor.saturating_sub(1)),
+            (Some(cursor), false) if cursor + 1 < self.history.len() => Some(cursor + 1),
+            (Some(_), false) => None,
+        };
+
+        self.input = self
+            .history_cursor
+            .map(|cursor| self.history[cursor].clone())
+            .unwrap_or_default();
+    }
+}
+
+#[async_trait]
+impl Element for DevConsole {
+    fn on_registration(&self) -> ElementRegistration {
+        ElementRegistration::new(Self::LABEL)
+    }
+
+    async fn on_message(&mut self, _message: &Arc<Message>) -> Option<Vec<Event>> {
+        None
+    }
+
+    async fn on_update(
+        &mut self,
+        _delta_time: f64,
+        input_state: &InputState,
+    ) -> Option<Vec<Event>> {
+        let mut events = Vec::new();
+        let mut input_changed = false;
+
+        for key_event in input_state.key_presses() {
+            if key_event.physical_key == self.toggle_key {
+                if !key_event.repeat {
+                    self.open = !self.open;
+                    info!("[Console] {}", if self.open { "Opened" } else { "Closed" });
+                }
+                continue;
+            }
+
+            if !self.open {
+                continue;
+            }
+
+            match &key_event.logical_key {
+                Key::Named(NamedKey::Enter) => {
+                    let line = std::mem::take(&mut self.input);
+                    info!("[Console] > {line}");
+                    events.extend(self.execute(&line));
+                }
+                Key::Named(NamedKey::Escape) => {
+                    self.open = false;
+                    info!("[Console] Closed");
+                }
+                Key::Named(NamedKey::Backspace) => {
+                    self.input.pop();
+                    input_changed = true;
+                }
+                Key::Named(NamedKey::ArrowUp) => {
+                    self.navigate_history(true);
+                    input_changed = true;
+                }
+                Key::Named(NamedKey::ArrowDown) => {
+                    self.navigate_history(false);
+                    input_changed = true;
+                }
+                _ => {
+                    if let Some(text) = &key_event.text {
+                        self.input.extend(text.chars().filter(|c| !c.is_control()));
+                        input_changed = true;
+                    }
+                }
+            }
+        }
+
+        if input_changed {
+            info!("[Console] > {}_", self.input);
+        }
+
+        (!events.is_empty()).then_some(events)
+    }
+}
@@ -0,0 +1,73 @@
+use crate::{
+    dev_console::DevConsole,
+    element::{Event, ModelEvent, WorldEvent},
+};
+
+#[test]
+fn registered_command_produces_events() {
+    let mut console = DevConsole::new();
+    console.register("remove", |arguments| {
+        Ok(arguments
+            .iter()
+            .map(|label| Event::World(WorldEvent::Model(ModelEvent::Despawn(label.clone()))))
+            .collect())
+    });
+
+    let events = console.execute("remove Cube \"Big Sphere\"");
+
+    assert_eq!(events.len(), 2);
+    assert!(matches!(
+        &events[0],
+        Event::World(WorldEvent::Model(ModelEvent::Despawn(label))) if label == "Cube"
+    ));
+    assert!(matches!(
+        &events[1],
+        Event::World(WorldEvent::Model(ModelEvent::Despawn(label))) if label == "Big Sphere"
+    ));
+    assert_eq!(console.history(), ["remove Cube \"Big Sphere\""]);
+}
+
+#[test]
+fn builtin_command() {
+    let mut console = DevConsole::new();
+
+    let events = console.execute("clear");
+
+    assert!(matches!(events[..], [Event::World(WorldEvent::Clear)]));
+}
+
+#[test]
+fn failing_and_unknown_commands() {
+    let mut console = DevConsole::new();
+
+    assert!(console.execute("despawn_model").is_empty());
+    assert!(console.execute("does_not_exist").is_empty());
+    assert!(console.execute("   ").is_empty());
+    assert_eq!(console.history().len(), 2);
+}
+
+#[test]
+fn parse_arguments() {
+    assert_eq!(
+        DevConsole::parse_arguments("  spawn  \"a b\" c \"\" "),
+        ["spawn", "a b", "c", ""]
+    );
+}
+
+#[test]
+fn history_navigation() {
+    let mut console = DevConsole::new();
+    console.execute("first");
+    console.execute("second");
+
+    console.navigate_history(true);
+    assert_eq!(console.input(), "second");
+    console.navigate_history(true);
+    assert_eq!(console.input(), "first");
+    console.navigate_history(true);
+    assert_eq!(console.input(), "first");
+    console.navigate_history(false);
+    assert_eq!(console.input(), "second");
+    console.navigate_history(false);
+    assert_eq!(console.input(), "");
+}
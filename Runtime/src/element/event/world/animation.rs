@@ -0,0 +1,19 @@
+use crate::resources::AnimationDescriptor;
+
+#[derive(Debug)]
+pub enum AnimationEvent {
+    /// Registers an animation, replacing any with the same label.
+    /// Animations don't play until [AnimationEvent::Play] is sent.
+    Spawn(AnimationDescriptor),
+    Despawn(String),
+    /// Plays an animation from the start, restarting it if it's already
+    /// playing.
+    /// Looping animations repeat until stopped, others stop after their
+    /// last keyframe.
+    Play {
+        label: String,
+        looping: bool,
+    },
+    /// Stops an animation, keeping the current pose.
+    Stop(String),
+}
@@ -1,3 +1,7 @@
+use cgmath::{Point3, Vector3};
+use hashbrown::HashMap;
+
+use crate::element::{Message, Origin, Target, Variant};
 use crate::resources::{CameraDescriptor, CameraTransform};
 
 #[derive(Debug)]
@@ -6,4 +10,108 @@ pub enum CameraEvent {
     Despawn(String),
     Target(String),
     Transform(CameraTransform),
+    /// Requests the current state of the camera with the given `label`.
+    ///
+    /// The [World](crate::world::World) replies with a [Message] sent to the
+    /// [Element](crate::element::Element) labeled `requester`, which can be
+    /// read via [CameraState::from_message].
+    /// The reply is delivered with the next update, i.e. one frame later.
+    Query {
+        label: String,
+        requester: String,
+    },
+}
+
+/// State of a camera, sent in reply to [CameraEvent::Query].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CameraState {
+    pub label: String,
+    pub position: Point3<f32>,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub roll: f32,
+    /// Normalized direction the camera is looking at.
+    pub forward: Vector3<f32>,
+}
+
+impl CameraState {
+    /// Content key identifying a [Message] as a [CameraState].
+    pub const MESSAGE_KEY: &'static str = "camera_state";
+
+    /// Creates a message containing this state, sent by the app.
+    pub fn to_message(&self, to: Target) -> Message {
+        let mut state = HashMap::new();
+        state.insert("label".to_string(), Variant::String(self.label.clone()));
+        state.insert(
+            "position".to_string(),
+            Self::vector_to_variant([self.position.x, self.position.y, self.position.z]),
+        );
+        state.insert("yaw".to_string(), Variant::F32(self.yaw));
+        state.insert("pitch".to_string(), Variant::F32(self.pitch));
+        state.insert("roll".to_string(), Variant::F32(self.roll));
+        state.insert(
+            "forward".to_string(),
+            Self::vector_to_variant([self.forward.x, self.forward.y, self.forward.z]),
+        );
+
+        Message::new(Origin::App, to)
+            .add_content(Self::MESSAGE_KEY.to_string(), Variant::Map(state))
+    }
+
+    /// Reads the state of a message created by [Self::to_message].
+    /// Returns `None` for any other message.
+    pub fn from_message(message: &Message) -> Option<Self> {
+        let state = message.get(Self::MESSAGE_KEY)?.as_map()?;
+        let f32_of = |key: &str| match state.get(key)? {
+            Variant::F32(x) => Some(*x),
+            _ => None,
+        };
+
+        let Variant::String(label) = state.get("label")? else {
+            return None;
+        };
+        let [x, y, z] = Self::variant_to_vector(state.get("position")?)?;
+        let forward = Self::variant_to_vector(state.get("forward")?)?;
+
+        Some(Self {
+            label: label.clone(),
+            position: Point3::new(x, y, z),
+            yaw: f32_of("yaw")?,
+            pitch: f32_of("pitch")?,
+            roll: f32_of("roll")?,
+            forward: forward.into(),
+        })
+    }
+
+    fn vector_to_variant(vector: [f32; 3]) -> Variant {
+        Variant::Map(HashMap::from_iter(
+            ["x", "y", "z"]
+                .into_iter()
+                .zip(vector)
+                .map(|(key, value)| (key.to_string(), Variant::F32(value))),
+        ))
+    }
+
+    fn variant_to_vector(variant: &Variant) -> Option<[f32; 3]> {
+        let map = variant.as_map()?;
+        let component = |key: &str| match map.get(key)? {
+            Variant::F32(x) => Some(*x),
+            _ => None,
+        };
+
+        Some([component("x")?, component("y")?, component("z")?])
+    }
+}
+
+impl From<&CameraDescriptor> for CameraState {
+    fn from(descriptor: &CameraDescriptor) -> Self {
+        Self {
+            label: descriptor.label.clone(),
+            position: descriptor.position,
+            yaw: descriptor.yaw,
+            pitch: descriptor.pitch,
+            roll: descriptor.roll,
+            forward: descriptor.forward(),
+        }
+    }
 }
@@ -10,7 +10,12 @@ pub use environment::*;
 mod light;
 pub use light::*;
 
-use crate::importer::ImportTask;
+mod animation;
+pub use animation::*;
+
+use cgmath::Point3;
+
+use crate::{element::Element, importer::ImportTask, world::Background};
 
 #[derive(Debug)]
 pub enum WorldEvent {
@@ -18,6 +23,62 @@ pub enum WorldEvent {
     Camera(CameraEvent),
     Environment(EnvironmentEvent),
     Light(LightEvent),
+    Animation(AnimationEvent),
     Import(ImportTask),
+    /// Changes what is drawn behind all models, see [Background].
+    SetBackground(Background),
+    /// Requests the current [FrameStats](crate::world::FrameStats).
+    ///
+    /// The [World](crate::world::World) replies with a
+    /// [Message](crate::element::Message) sent to the
+    /// [Element](crate::element::Element) labeled `requester`, which can be
+    /// read via [FrameStats::from_message](crate::world::FrameStats::from_message).
+    /// The reply is delivered with the next update, i.e. one frame later.
+    QueryFrameStats {
+        requester: String,
+    },
+    /// Finds all [Element]s tagged with `tag`, see
+    /// [ElementRegistration::with_tags].
+    /// The emitting [Element] receives an [ElementQuery] reply listing
+    /// their main labels in registration order, which can then be messaged.
+    /// If nothing matches, the reply lists no labels.
+    ///
+    /// Answered by the [ElementStore] instead of the
+    /// [World](crate::world::World), thus only [Element]s can query.
+    /// The reply is delivered with the next update, i.e. one frame later.
+    ///
+    /// [Element]: crate::element::Element
+    /// [ElementRegistration::with_tags]: crate::element::ElementRegistration::with_tags
+    /// [ElementQuery]: crate::element::ElementQuery
+    /// [ElementStore]: crate::element::ElementStore
+    QueryElements {
+        tag: String,
+    },
+    /// Hot-swaps the [Element] with the given label by `new_element`,
+    /// e.g. after reloading its implementation.
+    /// The old [Element] is despawned, handing its saved state over to the
+    /// replacement (see [Element::on_save_state]), which is spawned with
+    /// the same labels.
+    ///
+    /// Applied by the [ElementStore] instead of the
+    /// [World](crate::world::World) with the next update.
+    /// Check [ElementStore::replace_element] for details.
+    ///
+    /// [ElementStore]: crate::element::ElementStore
+    /// [ElementStore::replace_element]: crate::element::ElementStore::replace_element
+    ReplaceElement {
+        label: String,
+        new_element: Box<dyn Element + Send + Sync>,
+    },
+    /// Requests a sound to be played by the [AudioBackend].  
+    /// Check [SoundRequest] for the fields.
+    ///
+    /// [AudioBackend]: crate::audio::AudioBackend
+    /// [SoundRequest]: crate::audio::SoundRequest
+    PlaySound {
+        id: String,
+        volume: f32,
+        position: Option<Point3<f32>>,
+    },
     Clear,
 }
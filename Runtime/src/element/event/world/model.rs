@@ -3,8 +3,17 @@ use crate::resources::{Mode, ModelDescriptor, Transform};
 #[derive(Debug)]
 pub enum ModelEvent {
     Spawn(ModelDescriptor),
+    /// Spawns many models at once.
+    /// Prefer this over many individual [ModelEvent::Spawn]s, as the whole
+    /// batch is processed within one update and instances of the same
+    /// base model only cause a single re-realization.
+    SpawnBatch(Vec<ModelDescriptor>),
     Despawn(String),
     Transform(String, Mode<Transform>),
+    /// Replaces _all_ [Transform]s of a base model, e.g. to stream
+    /// instances in and out.
+    /// Instance labels of removed [Transform]s will be forgotten.
+    SetTransforms(String, Vec<Transform>),
     TransformInstance(String, Mode<Transform>, String), // ULID as string
     AddInstance(String, Transform),
     RemoveInstance(String, String), // ULID as string
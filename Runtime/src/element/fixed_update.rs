@@ -0,0 +1,53 @@
+use log::debug;
+
+/// Accumulates frame time of an [Element](super::Element) with a fixed
+/// update rate (see [ElementRegistration::with_fixed_update](super::ElementRegistration::with_fixed_update))
+/// and tells how many fixed steps are due.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct FixedUpdate {
+    step: f64,
+    accumulator: f64,
+}
+
+impl FixedUpdate {
+    /// Upper limit of fixed steps per frame.
+    /// If an update takes longer than the step itself, more and more steps
+    /// would pile up each frame. Any time beyond this limit is dropped
+    /// instead, slowing down the simulation.
+    pub const MAX_STEPS_PER_FRAME: u32 = 16;
+
+    pub fn new(rate_hz: f64) -> Self {
+        Self {
+            step: 1.0 / rate_hz,
+            accumulator: 0.0,
+        }
+    }
+
+    /// Duration of a single fixed step in seconds.
+    pub fn step(&self) -> f64 {
+        self.step
+    }
+
+    /// Changes the rate, keeping any accumulated time.
+    pub fn set_rate(&mut self, rate_hz: f64) {
+        self.step = 1.0 / rate_hz;
+    }
+
+    /// Accumulates `delta_time` and returns the number of fixed steps due.
+    pub fn advance(&mut self, delta_time: f64) -> u32 {
+        self.accumulator += delta_time;
+
+        let steps = (self.accumulator / self.step).floor();
+        if steps > Self::MAX_STEPS_PER_FRAME as f64 {
+            debug!(
+                "Fixed update is {steps} steps behind, dropping all but {}",
+                Self::MAX_STEPS_PER_FRAME
+            );
+            self.accumulator = 0.0;
+            return Self::MAX_STEPS_PER_FRAME;
+        }
+
+        self.accumulator -= steps * self.step;
+        steps as u32
+    }
+}
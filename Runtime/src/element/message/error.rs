@@ -0,0 +1,22 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// Failure parsing a [Message](super::Message) from JSON.
+#[derive(Debug)]
+pub enum MessageJsonError {
+    Json(serde_json::Error),
+    InvalidCorrelationId(String),
+}
+
+impl Display for MessageJsonError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MessageJsonError::Json(error) => write!(f, "Invalid message JSON: {error}"),
+            MessageJsonError::InvalidCorrelationId(id) => {
+                write!(f, "Invalid correlation ID '{id}'")
+            }
+        }
+    }
+}
+
+impl Error for MessageJsonError {}
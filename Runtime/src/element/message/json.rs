@@ -0,0 +1,81 @@
+use std::time::{Duration, Instant};
+
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+use ulid::Ulid;
+
+use super::{Message, MessageJsonError, Origin, Target, Variant};
+
+/// The serialized parts of a [Message].
+/// The creation instant can't be serialized, as [Instant]s are only
+/// meaningful within the running process.
+#[derive(Serialize)]
+struct MessageJsonRef<'a> {
+    from: &'a Origin,
+    to: &'a Target,
+    content: &'a HashMap<String, Variant>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    correlation_id: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    is_reply: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ttl: Option<&'a Duration>,
+}
+
+#[derive(Deserialize)]
+struct MessageJson {
+    from: Origin,
+    to: Target,
+    #[serde(default)]
+    content: HashMap<String, Variant>,
+    #[serde(default)]
+    correlation_id: Option<String>,
+    #[serde(default)]
+    is_reply: bool,
+    #[serde(default)]
+    ttl: Option<Duration>,
+}
+
+impl Message {
+    /// Serializes the message into JSON, e.g. for debugging, save states
+    /// or sending it over the network.  
+    /// The creation instant isn't included, check [Self::from_json].
+    ///
+    /// Non-finite floats can't be represented in JSON and are written as
+    /// `null`, which fails to parse back.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&MessageJsonRef {
+            from: &self.from,
+            to: &self.to,
+            content: &self.content,
+            correlation_id: self.correlation_id.map(|id| id.to_string()),
+            is_reply: self.is_reply,
+            ttl: self.ttl.as_ref(),
+        })
+        .expect("Messages must be serializable to JSON")
+    }
+
+    /// Parses a message serialized via [Self::to_json].  
+    /// The message counts as created while parsing, thus its
+    /// [TTL](Self::ttl) starts anew.
+    pub fn from_json(json: &str) -> Result<Self, MessageJsonError> {
+        let parsed: MessageJson = serde_json::from_str(json).map_err(MessageJsonError::Json)?;
+
+        let correlation_id = parsed
+            .correlation_id
+            .map(|id| {
+                Ulid::from_string(&id).map_err(|_| MessageJsonError::InvalidCorrelationId(id))
+            })
+            .transpose()?;
+
+        Ok(Self {
+            from: parsed.from,
+            to: parsed.to,
+            creation_instant: Instant::now(),
+            content: parsed.content,
+            correlation_id,
+            is_reply: parsed.is_reply,
+            ttl: parsed.ttl,
+        })
+    }
+}
@@ -0,0 +1,61 @@
+/// Caps how many messages a single [Element](crate::element::Element) may
+/// emit per frame.
+///
+/// This is a diagnostics tool to catch runaway messaging (e.g. an
+/// [Element] sending a message to itself every time it receives one).
+/// Whenever an [Element] exceeds [MessageLimit::max_per_frame], a warning
+/// is logged.
+/// If [MessageLimit::drop_excess] is set, any message past the cap is
+/// dropped as well.
+///
+/// By default, debug builds warn (without dropping) after
+/// [MessageLimit::DEFAULT_MAX_PER_FRAME] messages, while release builds
+/// are unlimited.
+///
+/// [Element]: crate::element::Element
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MessageLimit {
+    /// Maximum amount of messages per element per frame.
+    /// `None` disables the limit.
+    pub max_per_frame: Option<usize>,
+    /// If `true`, messages past the limit are dropped.
+    /// Otherwise, they are only reported.
+    pub drop_excess: bool,
+}
+
+impl MessageLimit {
+    pub const DEFAULT_MAX_PER_FRAME: usize = 1024;
+
+    /// No limit, nothing will be reported or dropped.
+    pub const UNLIMITED: Self = Self {
+        max_per_frame: None,
+        drop_excess: false,
+    };
+
+    /// Reports any [Element](crate::element::Element) exceeding `max_per_frame`.
+    pub fn warn_after(max_per_frame: usize) -> Self {
+        Self {
+            max_per_frame: Some(max_per_frame),
+            drop_excess: false,
+        }
+    }
+
+    /// Reports any [Element](crate::element::Element) exceeding
+    /// `max_per_frame` and drops any excess messages.
+    pub fn drop_after(max_per_frame: usize) -> Self {
+        Self {
+            max_per_frame: Some(max_per_frame),
+            drop_excess: true,
+        }
+    }
+}
+
+impl Default for MessageLimit {
+    fn default() -> Self {
+        if cfg!(debug_assertions) {
+            Self::warn_after(Self::DEFAULT_MAX_PER_FRAME)
+        } else {
+            Self::UNLIMITED
+        }
+    }
+}
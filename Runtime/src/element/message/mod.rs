@@ -1,5 +1,9 @@
 use hashbrown::HashMap;
-use std::time::Instant;
+use std::time::{Duration, Instant};
+use ulid::Ulid;
+
+mod limit;
+pub use limit::*;
 
 mod origin;
 pub use origin::*;
@@ -10,12 +14,26 @@ pub use target::*;
 mod variant;
 pub use variant::*;
 
+mod error;
+pub use error::*;
+
+mod json;
+
+#[cfg(test)]
+mod tests;
+
 #[derive(Debug)]
 pub struct Message {
     from: Origin,
     to: Target,
     creation_instant: Instant,
     content: HashMap<String, Variant>,
+    /// Shared by a request and its replies.
+    correlation_id: Option<Ulid>,
+    /// Set on messages created via [Self::reply].
+    is_reply: bool,
+    /// How long after creation the message is still delivered.
+    ttl: Option<Duration>,
 }
 
 impl Message {
@@ -25,9 +43,75 @@ impl Message {
             to,
             creation_instant: Instant::now(),
             content: HashMap::new(),
+            correlation_id: None,
+            is_reply: false,
+            ttl: None,
+        }
+    }
+
+    /// Creates a message expecting a reply.
+    /// Replies can be created with [Self::reply] and matched with
+    /// [Self::is_reply_to].
+    pub fn new_request(from: Origin, to: Target) -> Self {
+        Self {
+            correlation_id: Some(Ulid::new()),
+            ..Self::new(from, to)
         }
     }
 
+    /// Creates a message targeting all elements, see [Target::Broadcast].
+    pub fn new_broadcast(from: Origin) -> Self {
+        Self::new(from, Target::Broadcast)
+    }
+
+    /// Creates a reply to this message, sent back to where this message
+    /// originates from.
+    /// The reply carries the same correlation ID as this message.
+    ///
+    /// Returns `None` if this message originates from the app, as the app
+    /// can't be targeted by messages.
+    pub fn reply(&self, from: Origin) -> Option<Self> {
+        let to = match &self.from {
+            Origin::Element { label } => Target::Element {
+                labels: vec![label.clone()],
+            },
+            Origin::App => return None,
+        };
+
+        Some(Self {
+            correlation_id: self.correlation_id,
+            is_reply: true,
+            ..Self::new(from, to)
+        })
+    }
+
+    /// Checks if this message is a reply to `other`, i.e. it got created
+    /// via [Self::reply] on `other` and is sent back to its origin.
+    /// Only messages created via [Self::new_request] can be replied to.
+    pub fn is_reply_to(&self, other: &Message) -> bool {
+        let Origin::Element { label } = &other.from else {
+            return false;
+        };
+
+        self.is_reply
+            && self.correlation_id.is_some()
+            && self.correlation_id == other.correlation_id
+            && matches!(&self.to, Target::Element { labels } if labels.contains(label))
+    }
+
+    /// Drops the message if it couldn't be delivered within `ttl` after
+    /// its creation, e.g. for time-sensitive signals such as input.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Checks if the message outlived its TTL, if any.
+    pub fn is_expired(&self) -> bool {
+        self.ttl
+            .is_some_and(|ttl| self.creation_instant.elapsed() > ttl)
+    }
+
     pub fn add_content(mut self, key: String, value: Variant) -> Self {
         self.content.insert(key, value);
         self
@@ -52,4 +136,17 @@ impl Message {
     pub fn content(&self) -> &HashMap<String, Variant> {
         &self.content
     }
+
+    pub fn correlation_id(&self) -> Option<&Ulid> {
+        self.correlation_id.as_ref()
+    }
+
+    /// Whether this message got created via [Self::reply].
+    pub fn is_reply(&self) -> bool {
+        self.is_reply
+    }
+
+    pub fn ttl(&self) -> Option<&Duration> {
+        self.ttl.as_ref()
+    }
 }
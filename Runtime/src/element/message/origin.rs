@@ -1,5 +1,7 @@
+use serde::{Deserialize, Serialize};
+
 /// Origin of a message.
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
 pub enum Origin {
     /// Used if a message originates from the app.
     App,
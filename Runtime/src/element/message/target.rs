@@ -1,7 +1,13 @@
+use serde::{Deserialize, Serialize};
+
 /// Target of a message.
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
 pub enum Target {
     /// Used if a message targets all elements.
+    /// Each element receives the message once, regardless of how many
+    /// labels it has.
+    /// If the message originates from an element, said element won't
+    /// receive its own message.
     /// Use only if necessary.
     Broadcast,
     /// Used if a message targets a specific element.
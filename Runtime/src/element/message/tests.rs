@@ -0,0 +1,202 @@
+use hashbrown::HashMap;
+
+use super::{Message, MessageJsonError, Origin, Target, Variant};
+
+fn element(label: &str) -> Origin {
+    Origin::Element {
+        label: label.into(),
+    }
+}
+
+#[test]
+fn request_has_correlation_id() {
+    let message = Message::new(element("a"), Target::Broadcast);
+    assert!(message.correlation_id().is_none());
+
+    let request = Message::new_request(element("a"), Target::Broadcast);
+    assert!(request.correlation_id().is_some());
+
+    let other_request = Message::new_request(element("a"), Target::Broadcast);
+    assert_ne!(request.correlation_id(), other_request.correlation_id());
+}
+
+#[test]
+fn reply_round_trip() {
+    let request = Message::new_request(
+        element("player"),
+        Target::Element {
+            labels: vec!["enemy".into()],
+        },
+    );
+
+    let reply = request.reply(element("enemy")).unwrap();
+    assert_eq!(reply.from(), &element("enemy"));
+    assert_eq!(
+        reply.to(),
+        &Target::Element {
+            labels: vec!["player".into()]
+        }
+    );
+    assert_eq!(reply.correlation_id(), request.correlation_id());
+    assert!(reply.is_reply_to(&request));
+
+    let other_request = Message::new_request(element("player"), Target::Broadcast);
+    assert!(!reply.is_reply_to(&other_request));
+
+    // A request isn't a reply, neither to itself nor the other way around
+    assert!(!request.is_reply_to(&request));
+    assert!(!request.is_reply_to(&reply));
+}
+
+#[test]
+fn reply_must_target_request_origin() {
+    let request = Message::new_request(element("player"), Target::Broadcast);
+    let reply = request.reply(element("enemy")).unwrap();
+    assert!(reply.is_reply());
+
+    // Same correlation ID, but sent by someone else
+    let forwarded = Message::new_request(element("npc"), Target::Broadcast);
+    let forwarded = Message {
+        correlation_id: request.correlation_id,
+        ..forwarded
+    };
+    assert!(!reply.is_reply_to(&forwarded));
+}
+
+#[test]
+fn reply_without_request() {
+    let message = Message::new(element("a"), Target::Broadcast);
+    let reply = message.reply(element("b")).unwrap();
+
+    // Nothing to correlate
+    assert!(!reply.is_reply_to(&message));
+}
+
+#[test]
+fn reply_to_app() {
+    let request = Message::new_request(Origin::App, Target::Broadcast);
+
+    assert!(request.reply(element("a")).is_none());
+}
+
+#[test]
+fn nested_map_round_trip() {
+    let mut inner = HashMap::new();
+    inner.insert("x".to_string(), Variant::F32(1.5));
+    inner.insert("name".to_string(), Variant::String("spawn".into()));
+    let mut outer = HashMap::new();
+    outer.insert("position".to_string(), Variant::Map(inner.clone()));
+
+    let message = Message::new(element("a"), Target::Broadcast)
+        .add_content("command".into(), Variant::Map(outer));
+
+    let command = message.get("command").and_then(Variant::as_map).unwrap();
+    let position = command.get("position").and_then(Variant::as_map).unwrap();
+    assert_eq!(position, &inner);
+    assert_eq!(position.get("x"), Some(&Variant::F32(1.5)));
+    assert!(message.get("command").unwrap().as_bytes().is_none());
+}
+
+#[test]
+fn bytes_round_trip() {
+    let blob = vec![0u8, 1, 2, 254, 255];
+
+    let message = Message::new(element("a"), Target::Broadcast)
+        .add_content("blob".into(), Variant::Bytes(blob.clone()));
+
+    let variant = message.get("blob").unwrap();
+    assert_eq!(variant.as_bytes(), Some(blob.as_slice()));
+    assert!(variant.as_map().is_none());
+    assert_eq!(variant.clone(), Variant::Bytes(blob));
+}
+
+#[test]
+fn variant_ordering() {
+    assert!(Variant::U8(1) < Variant::U8(2));
+    // Different variants are ordered by declaration
+    assert!(Variant::Empty < Variant::Bytes(Vec::new()));
+    assert!(Variant::Bytes(vec![1]) < Variant::Bytes(vec![2]));
+
+    let mut map = HashMap::new();
+    map.insert("a".to_string(), Variant::Empty);
+    assert_eq!(
+        Variant::Map(map.clone()).partial_cmp(&Variant::Map(map)),
+        Some(std::cmp::Ordering::Equal)
+    );
+    assert_eq!(
+        Variant::Map(HashMap::new()).partial_cmp(&Variant::Map(
+            [("a".to_string(), Variant::Empty)].into_iter().collect()
+        )),
+        None
+    );
+}
+
+#[test]
+fn json_round_trip() {
+    let mut nested = HashMap::new();
+    nested.insert("health".to_string(), Variant::U32(100));
+    nested.insert("name".to_string(), Variant::String("Orbital".into()));
+
+    let variants = [
+        Variant::Empty,
+        Variant::String("Hello \"World\"".into()),
+        Variant::Boolean(true),
+        Variant::U8(u8::MAX),
+        Variant::U16(u16::MAX),
+        Variant::U32(u32::MAX),
+        Variant::U64(u64::MAX),
+        Variant::U128(u128::MAX),
+        Variant::I8(i8::MIN),
+        Variant::I16(i16::MIN),
+        Variant::I32(i32::MIN),
+        Variant::I64(i64::MIN),
+        Variant::I128(i128::MIN),
+        Variant::F32(1.5),
+        Variant::F64(-0.1),
+        Variant::Map(nested),
+        Variant::Bytes(vec![0, 1, 2, 254, 255]),
+    ];
+
+    let mut message = Message::new_request(
+        element("saver"),
+        Target::Element {
+            labels: vec!["loader".into()],
+        },
+    )
+    .with_ttl(std::time::Duration::from_secs(5));
+    for (i, variant) in variants.iter().enumerate() {
+        message = message.add_content(format!("{i}"), variant.clone());
+    }
+
+    let json = message.to_json();
+    // Bytes are kept compact
+    assert!(json.contains(r#""Bytes":"AAEC/v8=""#), "{json}");
+
+    let parsed = Message::from_json(&json).expect("Parsing message JSON failed!");
+    assert_eq!(parsed.from(), message.from());
+    assert_eq!(parsed.to(), message.to());
+    assert_eq!(parsed.content(), message.content());
+    assert_eq!(parsed.correlation_id(), message.correlation_id());
+    assert_eq!(parsed.ttl(), message.ttl());
+    assert!(parsed.creation_instant() >= message.creation_instant());
+
+    let reply = message.reply(element("loader")).unwrap();
+    let parsed_reply = Message::from_json(&reply.to_json()).expect("Parsing reply JSON failed!");
+    assert!(parsed_reply.is_reply_to(&message));
+}
+
+#[test]
+fn json_invalid() {
+    assert!(matches!(
+        Message::from_json("{}"),
+        Err(MessageJsonError::Json(_))
+    ));
+    assert!(matches!(
+        Message::from_json(r#"{"from":"App","to":"Broadcast","correlation_id":"nope"}"#),
+        Err(MessageJsonError::InvalidCorrelationId(_))
+    ));
+
+    // Content is optional
+    let message = Message::from_json(r#"{"from":"App","to":"Broadcast"}"#).unwrap();
+    assert!(message.content().is_empty());
+}
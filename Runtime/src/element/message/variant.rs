@@ -1,6 +1,9 @@
+use std::cmp::Ordering;
+
+use hashbrown::HashMap;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Variant {
     Empty,
     // Normal types
@@ -21,4 +24,101 @@ pub enum Variant {
     // Floating point numbers
     F32(f32),
     F64(f64),
+    // Structured types
+    /// A nested map, e.g. for structured payloads.
+    Map(HashMap<String, Variant>),
+    /// Raw binary data, e.g. a serialized command.
+    /// Serialized as base64 string in human readable formats like JSON.
+    Bytes(#[serde(with = "base64_bytes")] Vec<u8>),
+}
+
+impl Variant {
+    pub fn as_map(&self) -> Option<&HashMap<String, Variant>> {
+        match self {
+            Variant::Map(map) => Some(map),
+            _ => None,
+        }
+    }
+
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Variant::Bytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    /// Position of the variant in declaration order.
+    fn index(&self) -> usize {
+        match self {
+            Variant::Empty => 0,
+            Variant::String(_) => 1,
+            Variant::Boolean(_) => 2,
+            Variant::U8(_) => 3,
+            Variant::U16(_) => 4,
+            Variant::U32(_) => 5,
+            Variant::U64(_) => 6,
+            Variant::U128(_) => 7,
+            Variant::I8(_) => 8,
+            Variant::I16(_) => 9,
+            Variant::I32(_) => 10,
+            Variant::I64(_) => 11,
+            Variant::I128(_) => 12,
+            Variant::F32(_) => 13,
+            Variant::F64(_) => 14,
+            Variant::Map(_) => 15,
+            Variant::Bytes(_) => 16,
+        }
+    }
+}
+
+/// Orders like a derived implementation would: by variant first, then by
+/// value.
+/// Maps have no order, thus two different maps are incomparable.
+impl PartialOrd for Variant {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Variant::Empty, Variant::Empty) => Some(Ordering::Equal),
+            (Variant::String(a), Variant::String(b)) => a.partial_cmp(b),
+            (Variant::Boolean(a), Variant::Boolean(b)) => a.partial_cmp(b),
+            (Variant::U8(a), Variant::U8(b)) => a.partial_cmp(b),
+            (Variant::U16(a), Variant::U16(b)) => a.partial_cmp(b),
+            (Variant::U32(a), Variant::U32(b)) => a.partial_cmp(b),
+            (Variant::U64(a), Variant::U64(b)) => a.partial_cmp(b),
+            (Variant::U128(a), Variant::U128(b)) => a.partial_cmp(b),
+            (Variant::I8(a), Variant::I8(b)) => a.partial_cmp(b),
+            (Variant::I16(a), Variant::I16(b)) => a.partial_cmp(b),
+            (Variant::I32(a), Variant::I32(b)) => a.partial_cmp(b),
+            (Variant::I64(a), Variant::I64(b)) => a.partial_cmp(b),
+            (Variant::I128(a), Variant::I128(b)) => a.partial_cmp(b),
+            (Variant::F32(a), Variant::F32(b)) => a.partial_cmp(b),
+            (Variant::F64(a), Variant::F64(b)) => a.partial_cmp(b),
+            (Variant::Map(a), Variant::Map(b)) => (a == b).then_some(Ordering::Equal),
+            (Variant::Bytes(a), Variant::Bytes(b)) => a.partial_cmp(b),
+            _ => self.index().partial_cmp(&other.index()),
+        }
+    }
+}
+
+/// Keeps [Variant::Bytes] compact in human readable formats, instead of
+/// writing every byte as a number.
+/// Binary formats store the bytes as they are.
+mod base64_bytes {
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&base64::encode(bytes))
+        } else {
+            bytes.serialize(serializer)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        if deserializer.is_human_readable() {
+            let encoded = String::deserialize(deserializer)?;
+            base64::decode(encoded).map_err(D::Error::custom)
+        } else {
+            Vec::deserialize(deserializer)
+        }
+    }
 }
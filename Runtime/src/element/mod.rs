@@ -35,6 +35,14 @@ pub use message::*;
 mod event;
 pub use event::*;
 
+mod query;
+pub use query::*;
+
+mod fixed_update;
+
+#[cfg(test)]
+mod tests;
+
 /// An [Element] is a **thing** inside a [World].  
 /// Whenever you need something in your world, be it static or updated,
 /// you are looking for one or multiple [Elements]!
@@ -132,6 +140,27 @@ pub use event::*;
 pub trait Element: Debug + Send {
     fn on_registration(&self) -> ElementRegistration;
 
+    /// Called once after this [Element] got spawned, before its first
+    /// [Element::on_update].  
+    /// Events returned here are processed after the initial events of the
+    /// [ElementRegistration], thus any resources registered there already
+    /// exist.
+    ///
+    /// For replacements (see [WorldEvent::ReplaceElement]), this is called
+    /// after [Element::on_restore_state].
+    async fn on_spawn(&mut self) -> Option<Vec<Event>> {
+        None
+    }
+
+    /// Called once right before this [Element] gets despawned.  
+    /// Use this to clean up after yourself, e.g. by despawning any
+    /// [Models] spawned earlier.
+    ///
+    /// [Models]: crate::resources::Model
+    async fn on_despawn(&mut self) -> Option<Vec<Event>> {
+        None
+    }
+
     async fn on_message(&mut self, message: &Arc<Message>) -> Option<Vec<Event>> {
         if let Target::Element { .. } = message.to() {
             info!("Received message that isn't handled: {message:?}");
@@ -147,4 +176,41 @@ pub trait Element: Debug + Send {
     ) -> Option<Vec<Event>> {
         None
     }
+
+    /// Called with a fixed time step, if enabled via
+    /// [ElementRegistration::with_fixed_update].
+    /// Depending on the frame time, this is called zero or multiple times
+    /// per frame, always before [Element::on_update].
+    ///
+    /// `fixed_delta_time` is the duration of a step in seconds and stays
+    /// the same between calls.
+    async fn on_fixed_update(
+        &mut self,
+        _fixed_delta_time: f64,
+        _input_state: &InputState,
+    ) -> Option<Vec<Event>> {
+        None
+    }
+
+    /// Called right before this [Element] gets replaced through
+    /// [WorldEvent::ReplaceElement], followed by [Element::on_despawn].  
+    /// Whatever is returned here is handed to the replacement
+    /// in [Element::on_restore_state].
+    ///
+    /// Use this to preserve e.g. positions, health or other game state
+    /// when swapping an implementation at runtime.
+    fn on_save_state(&mut self) -> Option<Variant> {
+        None
+    }
+
+    /// Called on a replacement [Element] with the state saved by the
+    /// [Element] it replaces (see [Element::on_save_state]).  
+    /// Only called if the previous [Element] saved any state, right before
+    /// [Element::on_spawn].
+    ///
+    /// The replacement is spawned like any other [Element], thus its initial
+    /// events (see [ElementRegistration]) are processed as well.
+    fn on_restore_state(&mut self, _state: Variant) -> Option<Vec<Event>> {
+        None
+    }
 }
@@ -0,0 +1,63 @@
+use hashbrown::HashMap;
+
+use super::{Message, Origin, Target, Variant};
+
+/// Reply to a [WorldEvent::QueryElements](super::WorldEvent::QueryElements).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElementQuery {
+    /// The tag that got queried.
+    pub tag: String,
+    /// Main labels of the matching [Element](super::Element)s, in
+    /// registration order.
+    /// Empty if nothing matched.
+    pub labels: Vec<String>,
+}
+
+impl ElementQuery {
+    /// Content key identifying a [Message] as [ElementQuery].
+    pub const MESSAGE_KEY: &'static str = "element_query";
+
+    /// Creates a message containing this query result, sent by the app.
+    pub fn to_message(&self, to: Target) -> Message {
+        // Variants can't hold lists, thus labels are keyed by their
+        // position instead
+        let labels = self
+            .labels
+            .iter()
+            .enumerate()
+            .map(|(position, label)| (position.to_string(), Variant::String(label.clone())))
+            .collect();
+
+        let mut query = HashMap::new();
+        query.insert("tag".to_string(), Variant::String(self.tag.clone()));
+        query.insert("labels".to_string(), Variant::Map(labels));
+
+        Message::new(Origin::App, to)
+            .add_content(Self::MESSAGE_KEY.to_string(), Variant::Map(query))
+    }
+
+    /// Reads the query result of a message created by [Self::to_message].
+    /// Returns `None` for any other message.
+    pub fn from_message(message: &Message) -> Option<Self> {
+        let query = message.get(Self::MESSAGE_KEY)?.as_map()?;
+        let Variant::String(tag) = query.get("tag")? else {
+            return None;
+        };
+
+        let mut labels = query
+            .get("labels")?
+            .as_map()?
+            .iter()
+            .map(|(position, label)| match label {
+                Variant::String(label) => Some((position.parse::<usize>().ok()?, label.clone())),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()?;
+        labels.sort();
+
+        Some(Self {
+            tag: tag.clone(),
+            labels: labels.into_iter().map(|(_, label)| label).collect(),
+        })
+    }
+}
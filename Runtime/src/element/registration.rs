@@ -8,6 +8,10 @@ pub struct ElementRegistration {
     /// [Element]s can share _labels_ to
     labels: Vec<String>,
     initial_world_changes: Vec<Event>,
+    fixed_update_rate: Option<f64>,
+    /// `None` means all messages are received.
+    subscriptions: Option<Vec<String>>,
+    tags: Vec<String>,
 }
 
 impl ElementRegistration {
@@ -15,6 +19,9 @@ impl ElementRegistration {
         Self {
             labels: vec![main_label.into()],
             initial_world_changes: Vec::new(),
+            fixed_update_rate: None,
+            subscriptions: None,
+            tags: Vec::new(),
         }
     }
 
@@ -43,6 +50,71 @@ impl ElementRegistration {
         self
     }
 
+    /// Opts into [Element::on_fixed_update](super::Element::on_fixed_update)
+    /// being called `rate_hz` times per second, independent of the frame
+    /// rate.
+    /// Useful for e.g. physics and simulations, which need a fixed time step
+    /// to be deterministic.
+    ///
+    /// Frame time gets accumulated, thus a frame may trigger zero or
+    /// multiple fixed updates.
+    pub fn with_fixed_update(mut self, rate_hz: f64) -> Self {
+        assert!(rate_hz > 0.0, "Fixed update rate must be positive");
+        self.fixed_update_rate = Some(rate_hz);
+
+        self
+    }
+
+    /// The rate set by [Self::with_fixed_update], if any.
+    pub fn fixed_update_rate(&self) -> Option<f64> {
+        self.fixed_update_rate
+    }
+
+    /// Subscribes to the given topics.
+    /// Once subscribed, only [Message](super::Message)s with a
+    /// [content](super::Message::content) key matching any of the topics
+    /// are delivered to the [Element](super::Element).
+    /// This applies to both broadcasted and directly targeted messages.
+    ///
+    /// Can be called multiple times to subscribe to further topics.
+    /// By default, all messages are received.
+    pub fn subscribe_to<S: AsRef<str>>(mut self, topics: &[S]) -> Self {
+        self.subscriptions
+            .get_or_insert_with(Vec::new)
+            .extend(topics.iter().map(|topic| topic.as_ref().to_string()));
+
+        self
+    }
+
+    /// Drops any subscriptions made via [Self::subscribe_to], so all
+    /// messages are received again.
+    pub fn subscribe_to_all(mut self) -> Self {
+        self.subscriptions = None;
+
+        self
+    }
+
+    /// The topics set by [Self::subscribe_to], if any.
+    pub fn subscriptions(&self) -> Option<&[String]> {
+        self.subscriptions.as_deref()
+    }
+
+    /// Tags the [Element](super::Element), e.g. with `enemy`.
+    /// Unlike labels, tags are meant to be shared by many [Element]s and
+    /// allow discovering them via [WorldEvent::QueryElements](super::WorldEvent::QueryElements).
+    ///
+    /// Can be called multiple times to add further tags.
+    pub fn with_tags<S: Into<String>>(mut self, tags: impl IntoIterator<Item = S>) -> Self {
+        self.tags.extend(tags.into_iter().map(Into::into));
+
+        self
+    }
+
+    /// The tags set by [Self::with_tags].
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
     pub fn extract(self) -> (Vec<String>, Vec<Event>) {
         (self.labels, self.initial_world_changes)
     }
@@ -1,6 +1,9 @@
 use std::sync::Arc;
 
-use super::{ElementEvent, Event, Target};
+use super::{
+    fixed_update::FixedUpdate, ElementEvent, ElementQuery, Event, MessageLimit, Origin, Target,
+    WorldEvent,
+};
 use crate::{
     app::input::InputState,
     element::{Element, Message},
@@ -8,7 +11,7 @@ use crate::{
 use futures::future::join_all;
 use futures::StreamExt;
 use hashbrown::HashMap;
-use log::warn;
+use log::{debug, warn};
 
 type ElementIndexType = u64;
 
@@ -21,6 +24,20 @@ where
     cursor_index: ElementIndexType,
     label_map: HashMap<String, ElementIndexType>,
     message_queue: HashMap<ElementIndexType, Vec<Arc<Message>>>,
+    message_limit: MessageLimit,
+    emitted_messages: HashMap<ElementIndexType, usize>,
+    message_limit_violations: usize,
+    fixed_updates: HashMap<ElementIndexType, FixedUpdate>,
+    /// Topics of [Element]s only receiving some messages.
+    /// Missing [Element]s receive all messages.
+    subscriptions: HashMap<ElementIndexType, Vec<String>>,
+    /// First label of each [Element]'s registration, as reported by
+    /// [Self::query_elements].
+    main_labels: HashMap<ElementIndexType, String>,
+    tags: HashMap<ElementIndexType, Vec<String>>,
+    /// Replacements requested via [WorldEvent::ReplaceElement], applied
+    /// with the next [Self::process_events].
+    queue_replacements: Vec<(String, Box<dyn Element + Send + Sync>)>,
 }
 
 impl Default for ElementStore {
@@ -38,21 +55,76 @@ impl ElementStore {
             cursor_index: ElementIndexType::MIN,
             label_map: HashMap::new(),
             message_queue: HashMap::new(),
+            message_limit: MessageLimit::default(),
+            emitted_messages: HashMap::new(),
+            message_limit_violations: 0,
+            fixed_updates: HashMap::new(),
+            subscriptions: HashMap::new(),
+            main_labels: HashMap::new(),
+            tags: HashMap::new(),
+            queue_replacements: Vec::new(),
         }
     }
 
+    pub fn message_limit(&self) -> &MessageLimit {
+        &self.message_limit
+    }
+
+    pub fn set_message_limit(&mut self, message_limit: MessageLimit) {
+        self.message_limit = message_limit;
+    }
+
+    /// Returns how often an [Element] exceeded the [MessageLimit] so far.  
+    /// Each [Element] is counted at most once per frame.
+    pub fn message_limit_violations(&self) -> usize {
+        self.message_limit_violations
+    }
+
     pub fn clear(&mut self) {
         self.element_map.clear();
         self.cursor_index = 0;
         self.label_map.clear();
         self.message_queue.clear();
+        self.emitted_messages.clear();
+        self.fixed_updates.clear();
+        self.subscriptions.clear();
+        self.main_labels.clear();
+        self.tags.clear();
+        self.queue_replacements.clear();
     }
 
-    pub fn store_element(&mut self, element: Box<dyn Element + Send + Sync>, labels: Vec<String>) {
+    /// Stores an [Element] under the given labels.
+    /// If `fixed_update_rate` is set, [Element::on_fixed_update] will be
+    /// called at this rate.
+    /// If `subscriptions` are set, only messages matching any of the topics
+    /// are delivered, see [ElementRegistration::subscribe_to](super::ElementRegistration::subscribe_to).
+    /// `tags` allow finding the [Element] via [Self::query_elements].
+    pub fn store_element(
+        &mut self,
+        element: Box<dyn Element + Send + Sync>,
+        labels: Vec<String>,
+        fixed_update_rate: Option<f64>,
+        subscriptions: Option<Vec<String>>,
+        tags: Vec<String>,
+    ) {
         let next_cursor_index = self.cursor_index + 1;
         self.cursor_index = next_cursor_index;
         self.element_map.insert(next_cursor_index, element);
         self.message_queue.insert(next_cursor_index, Vec::new());
+        if let Some(rate) = fixed_update_rate {
+            self.fixed_updates
+                .insert(next_cursor_index, FixedUpdate::new(rate));
+        }
+        if let Some(topics) = subscriptions {
+            self.subscriptions.insert(next_cursor_index, topics);
+        }
+        if let Some(main_label) = labels.first() {
+            self.main_labels
+                .insert(next_cursor_index, main_label.clone());
+        }
+        if !tags.is_empty() {
+            self.tags.insert(next_cursor_index, tags);
+        }
 
         // Reserve capacity for better performance with large label vectors
         self.label_map.reserve(labels.len());
@@ -65,49 +137,275 @@ impl ElementStore {
         if let Some(element_id) = self.label_map.get(element_label).cloned() {
             self.element_map.remove(&element_id);
             self.message_queue.remove(&element_id);
+            self.fixed_updates.remove(&element_id);
+            self.subscriptions.remove(&element_id);
+            self.main_labels.remove(&element_id);
+            self.tags.remove(&element_id);
 
             self.label_map.retain(|_, v| element_id.eq(v));
         }
     }
 
-    pub fn queue_message(&mut self, message: Message) {
-        let labels = match message.to() {
-            Target::Broadcast => self.label_map.keys().cloned().collect(),
-            Target::Element { labels } => labels.to_owned(),
+    /// Replaces the [Element] with the given label by `new_element`.
+    ///
+    /// The old [Element] is despawned and the replacement spawned into its
+    /// slot:
+    /// Labels (including the ones added later on) and queued messages are
+    /// kept, additional labels of the replacement's registration are added.
+    ///
+    /// State is migrated by calling [Element::on_save_state] on the old
+    /// [Element] and handing the result to [Element::on_restore_state] of
+    /// the new one, before its [Element::on_spawn].
+    /// The returned events are, in order, the ones of the old
+    /// [Element::on_despawn], the replacement's initial events, its
+    /// [Element::on_restore_state] and its [Element::on_spawn].
+    /// As the [World] caches meshes and materials by their descriptors,
+    /// despawning and respawning the same [Model]s keeps their GPU
+    /// resources.
+    ///
+    /// If no [Element] with the given label exists, nothing is replaced.
+    ///
+    /// [World]: crate::world::World
+    /// [Model]: crate::resources::Model
+    pub async fn replace_element(
+        &mut self,
+        element_label: &str,
+        mut new_element: Box<dyn Element + Send + Sync>,
+    ) -> Vec<Event> {
+        let Some(element_id) = self.label_to_index(element_label) else {
+            warn!("Attempting to replace element with label '{element_label}', which cannot be found!");
+            return Vec::new();
         };
 
-        let arc = Arc::new(message);
+        let registration = new_element.on_registration();
+        // Accumulated time is kept, in case the rate stays the same.
+        match registration.fixed_update_rate() {
+            Some(rate) => {
+                self.fixed_updates
+                    .entry(element_id)
+                    .and_modify(|fixed_update| fixed_update.set_rate(rate))
+                    .or_insert_with(|| FixedUpdate::new(rate));
+            }
+            None => {
+                self.fixed_updates.remove(&element_id);
+            }
+        }
+
+        match registration.subscriptions() {
+            Some(topics) => {
+                self.subscriptions.insert(element_id, topics.to_vec());
+            }
+            None => {
+                self.subscriptions.remove(&element_id);
+            }
+        }
+
+        if registration.tags().is_empty() {
+            self.tags.remove(&element_id);
+        } else {
+            self.tags.insert(element_id, registration.tags().to_vec());
+        }
+
+        let (labels, initial_events) = registration.extract();
+        // The new main label only applies if it isn't taken already
+        if let Some(main_label) = labels.first().filter(|label| {
+            self.label_map
+                .get(*label)
+                .is_none_or(|id| *id == element_id)
+        }) {
+            self.main_labels.insert(element_id, main_label.clone());
+        }
         for label in labels {
-            let idx = match self.label_to_index(&label) {
-                None => {
-                    warn!("Trying to queue message {arc:#?} but couldn't find element with label '{label}'!");
-                    continue;
+            self.label_map.entry(label).or_insert(element_id);
+        }
+
+        let mut events = Vec::new();
+        let mut state = None;
+        if let Some(old_element) = self.element_map.get_mut(&element_id) {
+            state = old_element.on_save_state();
+            if let Some(despawn_events) = old_element.on_despawn().await {
+                events.extend(despawn_events);
+            }
+        }
+
+        events.extend(initial_events);
+        if let Some(restore_events) = state.and_then(|state| new_element.on_restore_state(state)) {
+            events.extend(restore_events);
+        }
+        if let Some(spawn_events) = new_element.on_spawn().await {
+            events.extend(spawn_events);
+        }
+
+        self.element_map.insert(element_id, new_element);
+
+        self.handle_emitted_events(element_id, events)
+    }
+
+    /// Returns the main labels of all [Element]s tagged with `tag`, in
+    /// registration order.
+    /// The main label is the first label of an [Element]'s registration.
+    pub fn query_elements(&self, tag: &str) -> Vec<&str> {
+        let mut matches: Vec<_> = self
+            .tags
+            .iter()
+            .filter(|(_, tags)| tags.iter().any(|x| x == tag))
+            .filter_map(|(element_id, _)| {
+                Some((*element_id, self.main_labels.get(element_id)?.as_str()))
+            })
+            .collect();
+        // Indices are handed out in increasing order
+        matches.sort_unstable_by_key(|(element_id, _)| *element_id);
+
+        matches.into_iter().map(|(_, label)| label).collect()
+    }
+
+    /// Handles [WorldEvent]s emitted by the given [Element] that concern
+    /// other [Element]s, rather than the [World].
+    /// Returns all remaining events.
+    ///
+    /// [World]: crate::world::World
+    fn handle_emitted_events(
+        &mut self,
+        element_id: ElementIndexType,
+        events: Vec<Event>,
+    ) -> Vec<Event> {
+        events
+            .into_iter()
+            .filter_map(|event| match event {
+                Event::World(WorldEvent::QueryElements { tag }) => {
+                    self.answer_query(element_id, tag);
+                    None
+                }
+                Event::World(WorldEvent::ReplaceElement { label, new_element }) => {
+                    self.queue_replacements.push((label, new_element));
+                    None
                 }
-                Some(label) => label,
-            };
+                event => Some(event),
+            })
+            .collect()
+    }
+
+    /// Queues an [ElementQuery] reply for the querying [Element].
+    /// The reply bypasses subscriptions, as it got explicitly requested.
+    fn answer_query(&mut self, element_id: ElementIndexType, tag: String) {
+        let query = ElementQuery {
+            labels: self
+                .query_elements(&tag)
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+            tag,
+        };
+        let message = query.to_message(Target::Element {
+            labels: self
+                .main_labels
+                .get(&element_id)
+                .cloned()
+                .into_iter()
+                .collect(),
+        });
+
+        match self.message_queue.get_mut(&element_id) {
+            Some(messages) => messages.push(Arc::new(message)),
+            None => warn!("Element #{element_id} queried elements tagged '{}', but has no message queue! The reply will be dropped.", query.tag),
+        }
+    }
+
+    /// Queues a message for its target [Element]s.
+    ///
+    /// A [Target::Broadcast] is delivered once to every [Element], except
+    /// the sending [Element] itself.
+    /// [Element]s with subscriptions only get the message if it matches
+    /// any of their topics.
+    pub fn queue_message(&mut self, message: Message) {
+        let arc = Arc::new(message);
+
+        let recipients: Vec<ElementIndexType> = match arc.to() {
+            Target::Broadcast => {
+                let sender = match arc.from() {
+                    Origin::Element { label } => self.label_to_index(label),
+                    Origin::App => None,
+                };
+
+                self.element_map
+                    .keys()
+                    .copied()
+                    .filter(|idx| Some(*idx) != sender)
+                    .collect()
+            }
+            Target::Element { labels } => labels
+                .iter()
+                .filter_map(|label| {
+                    let idx = self.label_to_index(label);
+                    if idx.is_none() {
+                        warn!("Trying to queue message {arc:#?} but couldn't find element with label '{label}'!");
+                    }
+                    idx
+                })
+                .collect(),
+        };
+
+        for idx in recipients {
+            if !self.is_subscribed(idx, &arc) {
+                continue;
+            }
 
             if let Some(messages) = self.message_queue.get_mut(&idx) {
                 messages.push(arc.clone());
             } else {
-                warn!("Failed sending message to element: No message queue found associated with element label '{label}'! The message will be dropped.");
+                warn!("Failed sending message to element: No message queue found associated with element #{idx}! The message will be dropped.");
             }
         }
     }
 
+    /// Checks if the message contains any topic the [Element] is
+    /// subscribed to.
+    /// Always true if the [Element] has no subscriptions.
+    fn is_subscribed(&self, element_id: ElementIndexType, message: &Message) -> bool {
+        self.subscriptions.get(&element_id).is_none_or(|topics| {
+            topics
+                .iter()
+                .any(|topic| message.content().contains_key(topic))
+        })
+    }
+
     pub async fn process_events(&mut self, events: Vec<ElementEvent>) -> Vec<Event> {
         let mut result_events = Vec::new();
 
+        for (label, new_element) in std::mem::take(&mut self.queue_replacements) {
+            result_events.extend(self.replace_element(&label, new_element).await);
+        }
+
         for event in events {
             match event {
-                ElementEvent::Spawn(element) => {
+                ElementEvent::Spawn(mut element) => {
                     let registration = element.on_registration();
-                    let (labels, new_events) = registration.extract();
+                    let fixed_update_rate = registration.fixed_update_rate();
+                    let subscriptions = registration.subscriptions().map(<[String]>::to_vec);
+                    let tags = registration.tags().to_vec();
+                    let (labels, mut new_events) = registration.extract();
 
-                    self.store_element(element, labels);
+                    if let Some(spawn_events) = element.on_spawn().await {
+                        new_events.extend(spawn_events);
+                    }
+
+                    self.store_element(element, labels, fixed_update_rate, subscriptions, tags);
+                    result_events.extend(self.handle_emitted_events(self.cursor_index, new_events));
+                }
+                ElementEvent::Despawn(label) => {
+                    let element = self.label_to_index(&label).and_then(|element_id| {
+                        Some((element_id, self.element_map.get_mut(&element_id)?))
+                    });
+                    if let Some((element_id, element)) = element {
+                        if let Some(despawn_events) = element.on_despawn().await {
+                            let despawn_events =
+                                self.handle_emitted_events(element_id, despawn_events);
+                            result_events.extend(despawn_events);
+                        }
+                    }
 
-                    result_events.extend(new_events);
+                    self.remove_element(&label);
                 }
-                ElementEvent::Despawn(label) => self.remove_element(&label),
                 ElementEvent::AddLabels {
                     element_label,
                     new_labels,
@@ -124,10 +422,18 @@ impl ElementStore {
     }
 
     async fn send_messages(&mut self) -> Vec<Event> {
-        let messages = std::mem::take(&mut self.message_queue);
+        // Only take the queued messages, the queues themselves have to stay
+        // around for the next messages.
+        let messages: Vec<_> = self
+            .message_queue
+            .iter_mut()
+            .filter(|(_, queue)| !queue.is_empty())
+            .map(|(element_id, queue)| (*element_id, std::mem::take(queue)))
+            .collect();
         let mut events = Vec::new();
 
         for (element_id, messages) in messages {
+            let mut element_events = Vec::new();
             match self.element_map.get_mut(&element_id) {
                 None => {
                     warn!("Got a message in queue that is supposed to be send to element with ID #{element_id}, but element does not exist! Messages to be dropped: {messages:#?}");
@@ -135,33 +441,127 @@ impl ElementStore {
                 }
                 Some(element) => {
                     for message in messages {
+                        if message.is_expired() {
+                            debug!("Dropping expired message: {message:?}");
+                            continue;
+                        }
+
                         if let Some(new_events) = element.on_message(&message).await {
-                            events.extend(new_events);
+                            element_events.extend(new_events);
                         }
                     }
                 }
             }
+
+            let element_events = self.handle_emitted_events(element_id, element_events);
+            events.extend(self.enforce_message_limit(element_id, element_events));
         }
 
         events
     }
 
     pub async fn update(&mut self, delta_time: f64, input_state: &InputState) -> Vec<Event> {
+        self.emitted_messages.clear();
+
         let mut events = self.send_messages().await;
+        events.extend(self.fixed_update(delta_time, input_state).await);
 
-        let futures: Vec<_> = self
+        let (element_ids, futures): (Vec<_>, Vec<_>) = self
             .element_map
             .iter_mut()
-            .map(|(_, x)| x.on_update(delta_time, input_state))
-            .collect();
+            .map(|(id, x)| (*id, x.on_update(delta_time, input_state)))
+            .unzip();
 
         let future_results = join_all(futures).await;
-        let new_events: Vec<Event> = future_results.into_iter().flatten().flatten().collect();
-        events.extend(new_events);
+        for (element_id, new_events) in element_ids.into_iter().zip(future_results) {
+            if let Some(new_events) = new_events {
+                let new_events = self.handle_emitted_events(element_id, new_events);
+                events.extend(self.enforce_message_limit(element_id, new_events));
+            }
+        }
+
+        events
+    }
+
+    /// Calls [Element::on_fixed_update] of all [Element]s with a fixed
+    /// update rate as often as due after `delta_time`.
+    async fn fixed_update(&mut self, delta_time: f64, input_state: &InputState) -> Vec<Event> {
+        let (element_ids, futures): (Vec<_>, Vec<_>) = self
+            .element_map
+            .iter_mut()
+            .filter_map(|(id, element)| {
+                let fixed_update = self.fixed_updates.get_mut(id)?;
+                let steps = fixed_update.advance(delta_time);
+                let step = fixed_update.step();
+
+                Some((*id, async move {
+                    let mut events = Vec::new();
+                    for _ in 0..steps {
+                        if let Some(new_events) = element.on_fixed_update(step, input_state).await {
+                            events.extend(new_events);
+                        }
+                    }
+                    events
+                }))
+            })
+            .unzip();
+
+        let mut events = Vec::new();
+        for (element_id, new_events) in element_ids.into_iter().zip(join_all(futures).await) {
+            let new_events = self.handle_emitted_events(element_id, new_events);
+            events.extend(self.enforce_message_limit(element_id, new_events));
+        }
 
         events
     }
 
+    /// Counts messages emitted by the given element this frame and applies
+    /// the [MessageLimit].
+    fn enforce_message_limit(
+        &mut self,
+        element_id: ElementIndexType,
+        events: Vec<Event>,
+    ) -> Vec<Event> {
+        let Some(max_per_frame) = self.message_limit.max_per_frame else {
+            return events;
+        };
+
+        let emitted = self.emitted_messages.entry(element_id).or_insert(0);
+        let mut result = Vec::with_capacity(events.len());
+        for event in events {
+            if let Event::Element(ElementEvent::SendMessage(_)) = &event {
+                *emitted += 1;
+
+                if *emitted == max_per_frame + 1 {
+                    self.message_limit_violations += 1;
+
+                    let labels: Vec<&String> = self
+                        .label_map
+                        .iter()
+                        .filter(|(_, id)| **id == element_id)
+                        .map(|(label, _)| label)
+                        .collect();
+                    warn!(
+                        "Element {labels:?} emitted more than {max_per_frame} messages this frame!{}",
+                        if self.message_limit.drop_excess {
+                            " Excess messages will be dropped."
+                        } else {
+                            ""
+                        }
+                    );
+                }
+
+                if self.message_limit.drop_excess && *emitted > max_per_frame {
+                    continue;
+                }
+            }
+
+            result.push(event);
+        }
+
+        result
+    }
+
     pub fn add_label(&mut self, element_label: &str, new_labels: Vec<String>) {
         if let Some(element_id) = self.label_map.get(element_label).cloned() {
             new_labels.into_iter().for_each(|label| {
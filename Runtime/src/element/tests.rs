@@ -0,0 +1,706 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use async_std::task::block_on;
+use async_trait::async_trait;
+
+use crate::app::input::InputState;
+
+use super::{
+    fixed_update::FixedUpdate, Element, ElementEvent, ElementQuery, ElementRegistration,
+    ElementStore, Event, Message, MessageLimit, ModelEvent, Origin, Target, Variant, WorldEvent,
+};
+
+#[derive(Debug)]
+struct Spammer {
+    messages_per_update: usize,
+}
+
+#[async_trait]
+impl Element for Spammer {
+    fn on_registration(&self) -> ElementRegistration {
+        ElementRegistration::new("spammer")
+    }
+
+    async fn on_update(
+        &mut self,
+        _delta_time: f64,
+        _input_state: &InputState,
+    ) -> Option<Vec<Event>> {
+        Some(
+            (0..self.messages_per_update)
+                .map(|_| {
+                    Event::Element(ElementEvent::SendMessage(Message::new(
+                        Origin::Element {
+                            label: "spammer".into(),
+                        },
+                        Target::Broadcast,
+                    )))
+                })
+                .collect(),
+        )
+    }
+}
+
+fn spawn_spammer(store: &mut ElementStore, messages_per_update: usize) {
+    block_on(
+        store.process_events(vec![ElementEvent::Spawn(Box::new(Spammer {
+            messages_per_update,
+        }))]),
+    );
+}
+
+#[test]
+fn message_limit_warns() {
+    let mut store = ElementStore::new();
+    store.set_message_limit(MessageLimit::warn_after(10));
+    spawn_spammer(&mut store, 25);
+
+    let events = block_on(store.update(0.0, &InputState::new()));
+
+    assert_eq!(events.len(), 25);
+    assert_eq!(store.message_limit_violations(), 1);
+}
+
+#[test]
+fn message_limit_drops() {
+    let mut store = ElementStore::new();
+    store.set_message_limit(MessageLimit::drop_after(10));
+    spawn_spammer(&mut store, 25);
+
+    let events = block_on(store.update(0.0, &InputState::new()));
+    assert_eq!(events.len(), 10);
+    assert_eq!(store.message_limit_violations(), 1);
+
+    // The count resets every frame
+    let events = block_on(store.update(0.0, &InputState::new()));
+    assert_eq!(events.len(), 10);
+    assert_eq!(store.message_limit_violations(), 2);
+}
+
+#[test]
+fn message_limit_within_cap() {
+    let mut store = ElementStore::new();
+    store.set_message_limit(MessageLimit::drop_after(10));
+    spawn_spammer(&mut store, 10);
+
+    let events = block_on(store.update(0.0, &InputState::new()));
+
+    assert_eq!(events.len(), 10);
+    assert_eq!(store.message_limit_violations(), 0);
+}
+
+#[test]
+fn message_limit_unlimited() {
+    let mut store = ElementStore::new();
+    store.set_message_limit(MessageLimit::UNLIMITED);
+    spawn_spammer(&mut store, 5000);
+
+    let events = block_on(store.update(0.0, &InputState::new()));
+
+    assert_eq!(events.len(), 5000);
+    assert_eq!(store.message_limit_violations(), 0);
+}
+
+#[derive(Debug)]
+struct Counter {
+    count: u32,
+}
+
+#[async_trait]
+impl Element for Counter {
+    fn on_registration(&self) -> ElementRegistration {
+        ElementRegistration::new("counter")
+    }
+
+    async fn on_update(
+        &mut self,
+        _delta_time: f64,
+        _input_state: &InputState,
+    ) -> Option<Vec<Event>> {
+        self.count += 1;
+        None
+    }
+
+    fn on_save_state(&mut self) -> Option<Variant> {
+        Some(Variant::U32(self.count))
+    }
+}
+
+#[derive(Debug)]
+struct ReplacedCounter {
+    restored: Arc<Mutex<Option<Variant>>>,
+}
+
+#[async_trait]
+impl Element for ReplacedCounter {
+    fn on_registration(&self) -> ElementRegistration {
+        ElementRegistration::new("counter").with_additional_label("counter_v2")
+    }
+
+    fn on_restore_state(&mut self, state: Variant) -> Option<Vec<Event>> {
+        *self.restored.lock().unwrap() = Some(state);
+        None
+    }
+}
+
+#[test]
+fn replace_element_hands_over_state() {
+    let mut store = ElementStore::new();
+    block_on(store.process_events(vec![ElementEvent::Spawn(Box::new(Counter { count: 0 }))]));
+
+    for _ in 0..3 {
+        block_on(store.update(0.0, &InputState::new()));
+    }
+
+    let restored = Arc::new(Mutex::new(None));
+    let events = block_on(store.replace_element(
+        "counter",
+        Box::new(ReplacedCounter {
+            restored: restored.clone(),
+        }),
+    ));
+
+    assert!(events.is_empty());
+    assert_eq!(*restored.lock().unwrap(), Some(Variant::U32(3)));
+    assert_eq!(store.element_count(), 1);
+    assert_eq!(
+        store.label_to_index("counter"),
+        store.label_to_index("counter_v2")
+    );
+}
+
+#[test]
+fn replace_unknown_element() {
+    let mut store = ElementStore::new();
+
+    let restored = Arc::new(Mutex::new(None));
+    block_on(store.replace_element(
+        "missing",
+        Box::new(ReplacedCounter {
+            restored: restored.clone(),
+        }),
+    ));
+
+    assert_eq!(store.element_count(), 0);
+    assert!(restored.lock().unwrap().is_none());
+}
+
+#[derive(Debug)]
+struct Listener {
+    label: &'static str,
+    received: Arc<Mutex<Vec<&'static str>>>,
+    broadcast: bool,
+}
+
+#[async_trait]
+impl Element for Listener {
+    fn on_registration(&self) -> ElementRegistration {
+        ElementRegistration::new(self.label).with_additional_label(format!("{}_alias", self.label))
+    }
+
+    async fn on_update(
+        &mut self,
+        _delta_time: f64,
+        _input_state: &InputState,
+    ) -> Option<Vec<Event>> {
+        std::mem::take(&mut self.broadcast).then(|| {
+            vec![Event::Element(ElementEvent::SendMessage(
+                Message::new_broadcast(Origin::Element {
+                    label: self.label.into(),
+                }),
+            ))]
+        })
+    }
+
+    async fn on_message(&mut self, _message: &Arc<Message>) -> Option<Vec<Event>> {
+        self.received.lock().unwrap().push(self.label);
+        None
+    }
+}
+
+#[test]
+fn broadcast_skips_sender() {
+    let mut store = ElementStore::new();
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let spawn = ["a", "b", "c"]
+        .into_iter()
+        .map(|label| {
+            ElementEvent::Spawn(Box::new(Listener {
+                label,
+                received: received.clone(),
+                broadcast: label == "a",
+            }))
+        })
+        .collect();
+    block_on(store.process_events(spawn));
+
+    let events = block_on(store.update(0.0, &InputState::new()));
+    assert_eq!(events.len(), 1);
+    let element_events = events
+        .into_iter()
+        .map(|event| match event {
+            Event::Element(event) => event,
+            x => panic!("Unexpected event: {x:?}"),
+        })
+        .collect();
+    block_on(store.process_events(element_events));
+
+    // Delivered on the next update, once per element
+    block_on(store.update(0.0, &InputState::new()));
+    let mut received = received.lock().unwrap().clone();
+    received.sort();
+    assert_eq!(received, vec!["b", "c"]);
+}
+
+#[test]
+fn broadcast_from_app_reaches_everyone() {
+    let mut store = ElementStore::new();
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let spawn = ["a", "b"]
+        .into_iter()
+        .map(|label| {
+            ElementEvent::Spawn(Box::new(Listener {
+                label,
+                received: received.clone(),
+                broadcast: false,
+            }))
+        })
+        .collect();
+    block_on(store.process_events(spawn));
+
+    store.queue_message(Message::new_broadcast(Origin::App));
+    block_on(store.update(0.0, &InputState::new()));
+
+    let mut received = received.lock().unwrap().clone();
+    received.sort();
+    assert_eq!(received, vec!["a", "b"]);
+}
+
+#[test]
+fn expired_message_is_dropped() {
+    let mut store = ElementStore::new();
+    let received = Arc::new(Mutex::new(Vec::new()));
+    block_on(
+        store.process_events(vec![ElementEvent::Spawn(Box::new(Listener {
+            label: "a",
+            received: received.clone(),
+            broadcast: false,
+        }))]),
+    );
+
+    let expiring = Message::new_broadcast(Origin::App).with_ttl(Duration::ZERO);
+    std::thread::sleep(Duration::from_millis(5));
+    assert!(expiring.is_expired());
+    store.queue_message(expiring);
+
+    block_on(store.update(0.0, &InputState::new()));
+    assert!(received.lock().unwrap().is_empty());
+
+    // Messages within their TTL are still delivered
+    let fresh = Message::new_broadcast(Origin::App).with_ttl(Duration::from_secs(60));
+    assert!(!fresh.is_expired());
+    store.queue_message(fresh);
+
+    block_on(store.update(0.0, &InputState::new()));
+    assert_eq!(*received.lock().unwrap(), vec!["a"]);
+}
+
+/// Records the content keys of all received messages.
+#[derive(Debug)]
+struct Subscriber {
+    label: &'static str,
+    topics: Option<&'static [&'static str]>,
+    received: Arc<Mutex<Vec<(&'static str, String)>>>,
+}
+
+#[async_trait]
+impl Element for Subscriber {
+    fn on_registration(&self) -> ElementRegistration {
+        let registration = ElementRegistration::new(self.label);
+        match self.topics {
+            Some(topics) => registration.subscribe_to(topics),
+            None => registration,
+        }
+    }
+
+    async fn on_message(&mut self, message: &Arc<Message>) -> Option<Vec<Event>> {
+        let mut received = self.received.lock().unwrap();
+        for key in message.content().keys() {
+            received.push((self.label, key.clone()));
+        }
+        None
+    }
+}
+
+#[test]
+fn subscriptions_filter_messages() {
+    let mut store = ElementStore::new();
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let spawn = [
+        ("health", Some(&["damage"][..])),
+        ("hud", Some(&["score"][..])),
+        ("logger", None),
+    ]
+    .into_iter()
+    .map(|(label, topics)| {
+        ElementEvent::Spawn(Box::new(Subscriber {
+            label,
+            topics,
+            received: received.clone(),
+        }) as Box<dyn Element + Send + Sync>)
+    })
+    .collect();
+    block_on(store.process_events(spawn));
+
+    for topic in ["damage", "score", "unrelated"] {
+        store.queue_message(
+            Message::new_broadcast(Origin::App).add_content(topic.into(), Variant::Boolean(true)),
+        );
+    }
+    // Direct messages are filtered as well
+    store.queue_message(
+        Message::new(
+            Origin::App,
+            Target::Element {
+                labels: vec!["health".into()],
+            },
+        )
+        .add_content("unrelated".into(), Variant::Boolean(true)),
+    );
+    block_on(store.update(0.0, &InputState::new()));
+
+    let mut received = received.lock().unwrap().clone();
+    received.sort();
+    assert_eq!(
+        received,
+        vec![
+            ("health", "damage".to_string()),
+            ("hud", "score".to_string()),
+            ("logger", "damage".to_string()),
+            ("logger", "score".to_string()),
+            ("logger", "unrelated".to_string()),
+        ]
+    );
+}
+
+/// Counts its fixed updates.
+#[derive(Debug)]
+struct Simulation {
+    fixed_updates: Arc<Mutex<Vec<f64>>>,
+}
+
+#[async_trait]
+impl Element for Simulation {
+    fn on_registration(&self) -> ElementRegistration {
+        ElementRegistration::new("simulation").with_fixed_update(60.0)
+    }
+
+    async fn on_fixed_update(
+        &mut self,
+        fixed_delta_time: f64,
+        _input_state: &InputState,
+    ) -> Option<Vec<Event>> {
+        self.fixed_updates.lock().unwrap().push(fixed_delta_time);
+        None
+    }
+}
+
+#[test]
+fn fixed_update_is_independent_of_frame_rate() {
+    let fixed_updates = Arc::new(Mutex::new(Vec::new()));
+    let mut store = ElementStore::new();
+    block_on(
+        store.process_events(vec![ElementEvent::Spawn(Box::new(Simulation {
+            fixed_updates: fixed_updates.clone(),
+        }))]),
+    );
+
+    // Irregular frame times with the expected total of fixed updates at 60Hz
+    let frames = [
+        (0.005, 0),
+        (0.03, 2),
+        (0.001, 2),
+        (0.1, 8),
+        (0.0164, 9),
+        (0.0, 9),
+    ];
+    for (delta_time, expected) in frames {
+        block_on(store.update(delta_time, &InputState::new()));
+        assert_eq!(fixed_updates.lock().unwrap().len(), expected);
+    }
+    assert!(fixed_updates
+        .lock()
+        .unwrap()
+        .iter()
+        .all(|step| *step == 1.0 / 60.0));
+
+    // Long frames are capped instead of piling up
+    block_on(store.update(10.0, &InputState::new()));
+    assert_eq!(
+        fixed_updates.lock().unwrap().len(),
+        9 + FixedUpdate::MAX_STEPS_PER_FRAME as usize
+    );
+}
+
+/// Records the order its lifecycle hooks are called in.
+#[derive(Debug)]
+struct Lifecycle {
+    calls: Arc<Mutex<Vec<&'static str>>>,
+}
+
+fn despawn_model_event(label: &str) -> Event {
+    Event::World(WorldEvent::Model(ModelEvent::Despawn(label.into())))
+}
+
+#[async_trait]
+impl Element for Lifecycle {
+    fn on_registration(&self) -> ElementRegistration {
+        ElementRegistration::new("lifecycle").with_initial_event(despawn_model_event("initial"))
+    }
+
+    async fn on_spawn(&mut self) -> Option<Vec<Event>> {
+        self.calls.lock().unwrap().push("spawn");
+        Some(vec![despawn_model_event("spawn")])
+    }
+
+    async fn on_update(
+        &mut self,
+        _delta_time: f64,
+        _input_state: &InputState,
+    ) -> Option<Vec<Event>> {
+        self.calls.lock().unwrap().push("update");
+        None
+    }
+
+    async fn on_despawn(&mut self) -> Option<Vec<Event>> {
+        self.calls.lock().unwrap().push("despawn");
+        Some(vec![despawn_model_event("despawn")])
+    }
+}
+
+fn despawned_model_labels(events: &[Event]) -> Vec<&str> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            Event::World(WorldEvent::Model(ModelEvent::Despawn(label))) => Some(label.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn lifecycle_hooks_order() {
+    let mut store = ElementStore::new();
+    let calls = Arc::new(Mutex::new(Vec::new()));
+
+    let events = block_on(
+        store.process_events(vec![ElementEvent::Spawn(Box::new(Lifecycle {
+            calls: calls.clone(),
+        }))]),
+    );
+    // Events of the spawn hook follow the initial events
+    assert_eq!(despawned_model_labels(&events), ["initial", "spawn"]);
+
+    block_on(store.update(0.0, &InputState::new()));
+    block_on(store.update(0.0, &InputState::new()));
+
+    let events = block_on(store.process_events(vec![ElementEvent::Despawn("lifecycle".into())]));
+    assert_eq!(despawned_model_labels(&events), ["despawn"]);
+    assert_eq!(store.element_count(), 0);
+
+    // The hook isn't called again for an already despawned element
+    let events = block_on(store.process_events(vec![ElementEvent::Despawn("lifecycle".into())]));
+    assert!(events.is_empty());
+
+    assert_eq!(
+        *calls.lock().unwrap(),
+        ["spawn", "update", "update", "despawn"]
+    );
+}
+
+/// Requests its replacement on the first update.
+#[derive(Debug)]
+struct Replacer {
+    label: &'static str,
+    replacement: Option<Box<dyn Element + Send + Sync>>,
+}
+
+#[async_trait]
+impl Element for Replacer {
+    fn on_registration(&self) -> ElementRegistration {
+        ElementRegistration::new("replacer")
+    }
+
+    async fn on_update(
+        &mut self,
+        _delta_time: f64,
+        _input_state: &InputState,
+    ) -> Option<Vec<Event>> {
+        let new_element = self.replacement.take()?;
+        Some(vec![Event::World(WorldEvent::ReplaceElement {
+            label: self.label.into(),
+            new_element,
+        })])
+    }
+}
+
+#[test]
+fn replace_element_event_despawns_old_element() {
+    let mut store = ElementStore::new();
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    block_on(store.process_events(vec![
+        ElementEvent::Spawn(Box::new(Lifecycle {
+            calls: calls.clone(),
+        })),
+        ElementEvent::Spawn(Box::new(Replacer {
+            label: "lifecycle",
+            replacement: Some(Box::new(Lifecycle {
+                calls: calls.clone(),
+            })),
+        })),
+    ]));
+
+    // The replacement is handled by the store, not passed on to the world
+    let events = block_on(store.update(0.0, &InputState::new()));
+    assert!(events.is_empty());
+
+    let events = block_on(store.process_events(Vec::new()));
+    assert_eq!(
+        despawned_model_labels(&events),
+        ["despawn", "initial", "spawn"]
+    );
+    assert_eq!(store.element_count(), 2);
+    assert_eq!(
+        *calls.lock().unwrap(),
+        ["spawn", "update", "despawn", "spawn"]
+    );
+}
+
+#[derive(Debug)]
+struct Tagged {
+    label: &'static str,
+    tags: &'static [&'static str],
+    /// Tags queried on the next update.
+    pending_queries: Vec<&'static str>,
+    queries: Arc<Mutex<Vec<ElementQuery>>>,
+}
+
+#[async_trait]
+impl Element for Tagged {
+    fn on_registration(&self) -> ElementRegistration {
+        ElementRegistration::new(self.label)
+            .with_additional_label(format!("{}_alias", self.label))
+            .with_tags(self.tags.iter().copied())
+    }
+
+    async fn on_update(
+        &mut self,
+        _delta_time: f64,
+        _input_state: &InputState,
+    ) -> Option<Vec<Event>> {
+        let queries = std::mem::take(&mut self.pending_queries);
+        (!queries.is_empty()).then(|| {
+            queries
+                .into_iter()
+                .map(|tag| Event::World(WorldEvent::QueryElements { tag: tag.into() }))
+                .collect()
+        })
+    }
+
+    async fn on_message(&mut self, message: &Arc<Message>) -> Option<Vec<Event>> {
+        if let Some(query) = ElementQuery::from_message(message) {
+            self.queries.lock().unwrap().push(query);
+        }
+        None
+    }
+}
+
+#[test]
+fn query_elements_by_tag() {
+    let mut store = ElementStore::new();
+    let queries = Arc::new(Mutex::new(Vec::new()));
+    let spawn = [
+        ("orc", &["enemy", "boss"][..], Vec::new()),
+        ("villager", &["npc"][..], Vec::new()),
+        ("goblin", &["enemy"][..], Vec::new()),
+        ("player", &[][..], vec!["enemy", "dragon"]),
+    ]
+    .into_iter()
+    .map(|(label, tags, pending_queries)| {
+        ElementEvent::Spawn(Box::new(Tagged {
+            label,
+            tags,
+            pending_queries,
+            queries: queries.clone(),
+        }) as Box<dyn Element + Send + Sync>)
+    })
+    .collect();
+    block_on(store.process_events(spawn));
+
+    // Main labels in registration order
+    assert_eq!(store.query_elements("enemy"), vec!["orc", "goblin"]);
+    assert_eq!(store.query_elements("boss"), vec!["orc"]);
+    assert!(store.query_elements("dragon").is_empty());
+
+    // Queries are answered by the store, not passed on to the world
+    let events = block_on(store.update(0.0, &InputState::new()));
+    assert!(events.is_empty());
+    assert!(queries.lock().unwrap().is_empty());
+    // Replies are delivered with the next update
+    block_on(store.update(0.0, &InputState::new()));
+
+    let queries = queries.lock().unwrap();
+    assert_eq!(
+        *queries,
+        vec![
+            ElementQuery {
+                tag: "enemy".into(),
+                labels: vec!["orc".into(), "goblin".into()],
+            },
+            // No matches still get a reply
+            ElementQuery {
+                tag: "dragon".into(),
+                labels: Vec::new(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn element_query_message_keeps_duplicate_labels() {
+    let query = ElementQuery {
+        tag: "enemy".into(),
+        labels: vec!["orc".into(), "goblin".into(), "orc".into()],
+    };
+
+    let message = query.to_message(Target::Broadcast);
+    assert_eq!(ElementQuery::from_message(&message), Some(query));
+}
+
+#[test]
+fn replacement_updates_main_label() {
+    let mut store = ElementStore::new();
+    block_on(
+        store.process_events(vec![ElementEvent::Spawn(Box::new(Tagged {
+            label: "orc",
+            tags: &["enemy"],
+            pending_queries: Vec::new(),
+            queries: Arc::new(Mutex::new(Vec::new())),
+        }))]),
+    );
+
+    block_on(store.replace_element(
+        "orc",
+        Box::new(Tagged {
+            label: "orc_v2",
+            tags: &["enemy"],
+            pending_queries: Vec::new(),
+            queries: Arc::new(Mutex::new(Vec::new())),
+        }),
+    ));
+
+    assert_eq!(store.query_elements("enemy"), vec!["orc_v2"]);
+}
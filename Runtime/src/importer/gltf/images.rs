@@ -0,0 +1,177 @@
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    ops::Index,
+    path::Path,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use gltf::{image::Format, Document, Texture};
+use hashbrown::HashMap;
+
+use crate::{cache::Cache, resources::TextureDescriptor};
+
+/// Name of the glTF extension referencing KTX2 images.
+pub const KHR_TEXTURE_BASISU: &str = "KHR_texture_basisu";
+
+/// Images of a glTF file.
+///
+/// Images referenced by [`KHR_TEXTURE_BASISU`] can't be decoded by the
+/// `gltf` crate and are kept as raw KTX2 bytes instead.
+/// Their slot in `decoded` holds a white 1x1 placeholder so image indices
+/// stay valid.
+///
+/// Textures created from the images are shared through a cache keyed by
+/// the image contents, so materials referencing the same image (even
+/// through different glTF images or textures) share one
+/// [TextureDescriptor].
+#[derive(Debug, Default)]
+pub struct GltfImages {
+    pub decoded: Vec<gltf::image::Data>,
+    pub ktx2: HashMap<usize, Vec<u8>>,
+    /// Content hash of each image, see [Self::content_hash].
+    hashes: Vec<u64>,
+    texture_cache: Mutex<Cache<TextureKey, TextureDescriptor>>,
+}
+
+/// How a texture interprets the image it's created from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextureRole {
+    Srgb,
+    Linear,
+    /// Blue channel of a metallic-roughness image.
+    Metallic,
+    /// Green channel of a metallic-roughness image.
+    Roughness,
+}
+
+/// Identifies a texture created from an image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TextureKey {
+    pub image_hash: u64,
+    pub role: TextureRole,
+    /// Index of the glTF sampler applied to the texture.
+    pub sampler: Option<usize>,
+}
+
+impl GltfImages {
+    pub fn import(
+        document: &Document,
+        base: Option<&Path>,
+        buffers: &[gltf::buffer::Data],
+    ) -> Result<Self, gltf::Error> {
+        let ktx2_sources = document
+            .textures()
+            .filter_map(|texture| Self::ktx2_source(&texture))
+            .collect::<Vec<_>>();
+
+        let mut images = Self::default();
+        for image in document.images() {
+            if ktx2_sources.contains(&image.index()) {
+                let bytes = Self::read_raw(image.source(), base, buffers)?;
+                images.hashes.push(Self::content_hash(&bytes, None));
+                images.ktx2.insert(image.index(), bytes);
+                images.decoded.push(gltf::image::Data {
+                    pixels: vec![255; 4],
+                    format: Format::R8G8B8A8,
+                    width: 1,
+                    height: 1,
+                });
+            } else {
+                let data = gltf::image::Data::from_source(image.source(), base, buffers)?;
+                images
+                    .hashes
+                    .push(Self::content_hash(&data.pixels, Some(&data)));
+                images.decoded.push(data);
+            }
+        }
+
+        Ok(images)
+    }
+
+    /// Returns the image index of the KTX2 image a texture references
+    /// through [`KHR_TEXTURE_BASISU`], if any.
+    pub fn ktx2_source(texture: &Texture) -> Option<usize> {
+        texture
+            .extension_value(KHR_TEXTURE_BASISU)?
+            .get("source")?
+            .as_u64()
+            .map(|x| x as usize)
+    }
+
+    /// Returns the content hash of the image with the given index.
+    pub fn hash_of(&self, image: usize) -> u64 {
+        self.hashes[image]
+    }
+
+    /// Returns the texture for `key`, creating it via `parse` if no texture
+    /// with the same key was created before.
+    pub fn shared_texture(
+        &self,
+        key: TextureKey,
+        parse: impl FnOnce() -> TextureDescriptor,
+    ) -> Arc<TextureDescriptor> {
+        if let Some(entry) = self.lock_texture_cache().get(&key) {
+            return entry.clone_inner();
+        }
+
+        // Parsing happens outside the lock, so nodes imported in parallel
+        // don't wait on each other. If another node was faster, its
+        // texture is kept.
+        let descriptor = parse();
+        self.lock_texture_cache()
+            .get_or_insert_with(key, || descriptor)
+            .clone_inner()
+    }
+
+    /// Returns the number of distinct textures created so far.
+    pub fn texture_count(&self) -> usize {
+        self.lock_texture_cache().len()
+    }
+
+    fn lock_texture_cache(&self) -> MutexGuard<'_, Cache<TextureKey, TextureDescriptor>> {
+        // The cache stays consistent even if a parser panicked
+        self.texture_cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Hashes the bytes of an image.
+    /// For decoded images the format and dimensions are included, as the
+    /// same bytes can be interpreted differently.
+    fn content_hash(bytes: &[u8], decoded: Option<&gltf::image::Data>) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        bytes.hash(&mut hasher);
+        if let Some(data) = decoded {
+            (data.format as u8).hash(&mut hasher);
+            data.width.hash(&mut hasher);
+            data.height.hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    fn read_raw(
+        source: gltf::image::Source,
+        base: Option<&Path>,
+        buffers: &[gltf::buffer::Data],
+    ) -> Result<Vec<u8>, gltf::Error> {
+        match source {
+            gltf::image::Source::View { view, .. } => {
+                let buffer = &buffers[view.buffer().index()];
+                Ok(buffer[view.offset()..view.offset() + view.length()].to_vec())
+            }
+            gltf::image::Source::Uri { uri, .. } => {
+                // Buffers are read as-is, which is exactly what we need
+                // here. Any padding added is ignored by the KTX2 parser.
+                gltf::buffer::Data::from_source(gltf::buffer::Source::Uri(uri), base).map(|x| x.0)
+            }
+        }
+    }
+}
+
+impl Index<usize> for GltfImages {
+    type Output = gltf::image::Data;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.decoded[index]
+    }
+}
@@ -5,4 +5,6 @@ pub enum GltfImportType {
     Model,
     Camera,
     Light,
+    /// Animations are matched by their own name.
+    Animation,
 }
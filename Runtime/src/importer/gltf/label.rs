@@ -0,0 +1,81 @@
+/// Defines how the name of a "thing" inside a glTF file is matched.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GltfLabel {
+    /// Matches names equal to the given one.
+    Exact(String),
+    /// Matches names against a wildcard pattern:
+    /// - `*` matches any sequence of characters, including none.
+    /// - `?` matches exactly one character.
+    /// - Anything else has to match exactly.
+    ///
+    /// Matching is case-sensitive, e.g. `Level_*` matches `Level_1` and
+    /// `Level_Forest`, but not `level_1`.
+    Pattern(String),
+}
+
+impl GltfLabel {
+    /// Whether `name` is matched by this label.
+    pub fn matches(&self, name: &str) -> bool {
+        match self {
+            Self::Exact(label) => label == name,
+            Self::Pattern(pattern) => wildcard_match(pattern, name),
+        }
+    }
+
+    /// Whether the optional `name` is matched by this label.
+    /// Unnamed "things" are never matched.
+    pub fn matches_option(&self, name: Option<&str>) -> bool {
+        name.is_some_and(|name| self.matches(name))
+    }
+
+    /// Whether this label can match multiple "things".
+    pub fn is_pattern(&self) -> bool {
+        matches!(self, Self::Pattern(_))
+    }
+}
+
+impl From<&str> for GltfLabel {
+    fn from(value: &str) -> Self {
+        Self::Exact(value.to_string())
+    }
+}
+
+impl From<String> for GltfLabel {
+    fn from(value: String) -> Self {
+        Self::Exact(value)
+    }
+}
+
+/// Matches `text` against `pattern` as described in [GltfLabel::Pattern].
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let text = text.chars().collect::<Vec<_>>();
+
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text position it got tried at,
+    // to backtrack to if the remaining pattern doesn't match.
+    let mut backtrack = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(c) if *c == '?' || *c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                // Let the last `*` consume one more character
+                Some((star, star_t)) => {
+                    backtrack = Some((star, star_t + 1));
+                    p = star + 1;
+                    t = star_t + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
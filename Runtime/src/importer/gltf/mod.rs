@@ -1,19 +1,32 @@
+use crate::asset;
 use crate::resources::{
-    CameraDescriptor, FilterMode, LightDescriptor, MaterialDescriptor, MeshDescriptor,
-    ModelDescriptor, PBRMaterialDescriptor, TextureDescriptor, TextureSize, Transform, Vertex,
+    AddressMode, AlphaMode, AnimationChannel, AnimationDescriptor, AnimationValues,
+    CameraDescriptor, FilterMode, Instance, Interpolation, Ktx2Texture, LightDescriptor,
+    MaterialDescriptor, MeshDescriptor, ModelDescriptor, NormalMapSpace, PBRMaterialDescriptor,
+    Projection, RenderLayer, SkinDescriptor, SkinJoint, TextureDescriptor, TextureSize,
+    TextureUsageIntent, Transform, Vertex, VertexSkin,
 };
-use cgmath::{InnerSpace, Point3, Quaternion, Vector2, Vector3, Zero};
-use gltf::camera::Projection;
+use cgmath::{
+    InnerSpace, Matrix4, Point3, Quaternion, SquareMatrix, Vector2, Vector3, Vector4, Zero,
+};
+use gltf::animation::util::ReadOutputs;
+use gltf::camera::Projection as GltfProjection;
 use gltf::image::Format;
 use gltf::khr_lights_punctual;
-use gltf::{Camera, Document, Material, Mesh, Node, Scene, Semantic};
+use gltf::texture::{MagFilter, MinFilter, WrappingMode};
+use gltf::{Animation, Camera, Document, Material, Mesh, Node, Scene, Semantic, Skin};
 use hashbrown::HashMap;
 use log::{debug, trace, warn};
+use rayon::prelude::*;
 use std::error::Error;
+use std::f32::consts::PI;
 use std::sync::Arc;
 use ulid::Ulid;
 use wgpu::TextureFormat::R32Float;
-use wgpu::{Color, TextureDimension, TextureFormat, TextureUsages, TextureViewDimension};
+use wgpu::{
+    AddressMode as WAddressMode, Color, FilterMode as WFilterMode, TextureDimension, TextureFormat,
+    TextureViewDimension,
+};
 
 mod import;
 pub use import::*;
@@ -21,6 +34,9 @@ pub use import::*;
 mod specific_import;
 pub use specific_import::*;
 
+mod label;
+pub use label::*;
+
 mod task;
 pub use task::*;
 
@@ -30,10 +46,19 @@ pub use import_type::*;
 mod result;
 pub use result::*;
 
+mod settings;
+pub use settings::*;
+
+mod images;
+use images::{GltfImages, TextureKey, TextureRole};
+
 mod error;
 use crate::quaternion::quaternion_to_pitch_yaw;
 pub use error::*;
 
+mod warning;
+pub use warning::*;
+
 #[cfg(test)]
 mod tests;
 
@@ -44,12 +69,29 @@ mod tests;
 /// for labels! Labels is an _optional feature_ in glTF files. Most applications export glTF files
 /// directly with the label without any modification being necessary, other apps might have a toggle.
 ///
+/// File paths are resolved via [`crate::asset`].
+///
+/// Images can either be external files, data URIs or be embedded inside a buffer view
+/// (e.g. inside a `.glb` file). PNG and JPEG images are supported.
+/// KTX2 images referenced through `KHR_texture_basisu` are preferred if they
+/// aren't supercompressed, otherwise the texture's regular source is used.
+///
 /// # Known unsupported behaviors:
 /// - URL references to websites, e.g. to download an image, are not supported.
 ///   Any resources are required to be local and accessible.
 #[derive(Debug)]
 pub struct GltfImporter;
 
+/// Result of converting a single [`Node`].
+enum NodeImport {
+    Models {
+        models: Vec<ModelDescriptor>,
+        warnings: Vec<GltfImportWarning>,
+    },
+    Camera(CameraDescriptor),
+    Light(LightDescriptor),
+}
+
 impl GltfImporter {
     /// Starts the import of a glTF file given settings defined in [`GltfImportTask`].
     ///
@@ -66,30 +108,69 @@ impl GltfImporter {
     /// materials, it will be automatically instanced by the World system.
     /// Each instance gets a unique transform that preserves the original positioning.
     pub async fn import(import_task: GltfImportTask) -> GltfImportResult {
-        let (document, buffers, textures) = match gltf::import(&import_task.file) {
-            Ok(x) => x,
-            Err(e) => {
-                return GltfImportResult {
-                    errors: vec![Box::new(e)],
-                    ..Default::default()
+        let (document, buffers, textures) =
+            match Self::load(&import_task.file, import_task.settings.geometry_only) {
+                Ok(x) => x,
+                Err(e) => {
+                    return GltfImportResult {
+                        errors: vec![Box::new(e)],
+                        ..Default::default()
+                    }
                 }
-            }
-        };
+            };
 
-        match import_task.import {
-            GltfImport::WholeFile => Self::import_whole_file(&document, &buffers, &textures),
+        let mut result = match import_task.import {
+            GltfImport::WholeFile => {
+                Self::import_whole_file(&document, &buffers, &textures, &import_task.settings)
+            }
             GltfImport::Specific(specific_gltf_imports) => {
                 let mut result = GltfImportResult::empty();
 
                 for specific_import in specific_gltf_imports {
-                    let import_result =
-                        Self::import_specific(specific_import, &document, &buffers, &textures);
+                    let import_result = Self::import_specific(
+                        specific_import,
+                        &document,
+                        &buffers,
+                        &textures,
+                        &import_task.settings,
+                    );
                     result.extend(import_result);
                 }
 
                 result
             }
-        }
+        };
+
+        result.decoded_images = textures.decoded.len();
+        result.unique_textures = textures.texture_count();
+        result
+    }
+
+    /// Loads a glTF file including its buffers.
+    /// Images are only decoded if `skip_images` is `false`, otherwise no
+    /// images are returned.
+    fn load(
+        file: &str,
+        skip_images: bool,
+    ) -> Result<(Document, Vec<gltf::buffer::Data>, GltfImages), gltf::Error> {
+        // Assets served from memory can't reference external files, thus only
+        // self-contained files (e.g. `.glb`) are supported for them.
+        let in_memory = asset::is_in_memory(file);
+
+        let bytes = asset::read(file).map_err(gltf::Error::Io)?;
+        let gltf::Gltf { document, blob } = gltf::Gltf::from_slice(&bytes)?;
+
+        let path = asset::resolve(file);
+        let base = (!in_memory).then(|| path.parent()).flatten();
+        let buffers = gltf::import_buffers(&document, base, blob)?;
+
+        let images = if skip_images {
+            GltfImages::default()
+        } else {
+            GltfImages::import(&document, base, &buffers)?
+        };
+
+        Ok((document, buffers, images))
     }
 
     /// Handles importing from a glTF [`Document`] given a [`SpecificGltfImport`].
@@ -97,81 +178,141 @@ impl GltfImporter {
         specific_import: SpecificGltfImport,
         document: &Document,
         buffers: &Vec<gltf::buffer::Data>,
-        textures: &Vec<gltf::image::Data>,
+        textures: &GltfImages,
+        settings: &GltfImportSettings,
     ) -> GltfImportResult {
         let mut result = GltfImportResult::empty();
 
-        match specific_import.import_type {
+        let label = &specific_import.label;
+        // An exact label only imports the first match, a pattern all of them.
+        let limit = if label.is_pattern() { usize::MAX } else { 1 };
+
+        let found = match specific_import.import_type {
             GltfImportType::Scene => {
-                if let Some(scene) = document
+                let scenes = document
                     .scenes()
-                    .find(|scene| scene.name().is_some_and(|x| x == specific_import.label))
-                {
-                    let import_result =
-                        Self::import_whole_scene(scene, document, buffers, textures);
+                    .filter(|scene| label.matches_option(scene.name()))
+                    .take(limit)
+                    .collect::<Vec<_>>();
+
+                for scene in &scenes {
+                    let import_result = Self::import_whole_scene(
+                        scene.clone(),
+                        document,
+                        buffers,
+                        textures,
+                        settings,
+                    );
                     result.extend(import_result);
-                } else {
-                    result
-                        .errors
-                        .push(Box::new(GltfError::NotFound(specific_import)));
                 }
+
+                !scenes.is_empty()
             }
             GltfImportType::Model | GltfImportType::Camera => {
-                if let Some(node) = document.scenes().find_map(|scene| {
-                    scene.nodes().find(|node| {
-                        node.name()
-                            .is_some_and(|name| name == specific_import.label)
-                    })
-                }) {
-                    let import_result = Self::import_nodes(vec![node], buffers, textures);
+                let nodes = Self::find_root_nodes(document, limit, |node| {
+                    label.matches_option(node.name())
+                });
+                let found = !nodes.is_empty();
+
+                if found {
+                    let import_result =
+                        Self::import_nodes(nodes, document, buffers, textures, settings);
                     result.extend(import_result);
-                } else {
-                    result
-                        .errors
-                        .push(Box::new(GltfError::NotFound(specific_import)));
                 }
+
+                found
             }
             GltfImportType::Light => {
-                if let Some(node) = document.scenes().find_map(|scene| {
-                    scene.nodes().find(|node| {
-                        node.name()
-                            .is_some_and(|name| name == specific_import.label)
-                    })
-                }) {
-                    if let Some(light) = node.light() {
-                        match Self::parse_light(&node, &light, buffers) {
-                            Ok(light_desc) => result.lights.push(light_desc),
-                            Err(e) => result.errors.push(e),
-                        }
-                    } else {
-                        result
-                            .errors
-                            .push(Box::new(GltfError::NotFound(specific_import)));
+                // Lights can be matched by the name of their node or by
+                // their own name.
+                let nodes = Self::find_root_nodes(document, limit, |node| {
+                    node.light().is_some()
+                        && (label.matches_option(node.name())
+                            || node
+                                .light()
+                                .is_some_and(|light| label.matches_option(light.name())))
+                });
+
+                for node in &nodes {
+                    let light = node.light().expect("Only nodes with a light are matched");
+                    match Self::parse_light(node, &light, buffers, settings) {
+                        Ok(light_desc) => result.lights.push(light_desc),
+                        Err(e) => result.errors.push(e),
+                    }
+                }
+
+                !nodes.is_empty()
+            }
+            GltfImportType::Animation => {
+                let animations = document
+                    .animations()
+                    .filter(|animation| label.matches_option(animation.name()))
+                    .take(limit)
+                    .collect::<Vec<_>>();
+
+                for animation in &animations {
+                    match Self::parse_animation(animation, buffers) {
+                        Ok(animation) => result.animations.push(animation),
+                        Err(e) => result.errors.push(e),
                     }
-                } else {
-                    result
-                        .errors
-                        .push(Box::new(GltfError::NotFound(specific_import)));
                 }
+
+                !animations.is_empty()
             }
+        };
+
+        if !found {
+            result
+                .errors
+                .push(Box::new(GltfError::NotFound(specific_import)));
         }
 
         result
     }
 
+    /// Finds up to `limit` root nodes of all scenes accepted by `filter`.
+    /// Nodes used by multiple scenes are only returned once.
+    fn find_root_nodes<'a>(
+        document: &'a Document,
+        limit: usize,
+        filter: impl Fn(&Node) -> bool,
+    ) -> Vec<Node<'a>> {
+        let mut nodes: Vec<Node> = Vec::new();
+        for node in document.scenes().flat_map(|scene| scene.nodes()) {
+            if nodes.len() == limit {
+                break;
+            }
+
+            if filter(&node) && nodes.iter().all(|x| x.index() != node.index()) {
+                nodes.push(node);
+            }
+        }
+
+        nodes
+    }
+
     /// Handles importing a whole glTF file
     fn import_whole_file(
         document: &Document,
         buffers: &Vec<gltf::buffer::Data>,
-        textures: &Vec<gltf::image::Data>,
+        textures: &GltfImages,
+        settings: &GltfImportSettings,
     ) -> GltfImportResult {
         let mut result = GltfImportResult::empty();
 
         for scene in document.scenes() {
-            let import_result = Self::import_whole_scene(scene, document, buffers, textures);
+            let import_result =
+                Self::import_whole_scene(scene, document, buffers, textures, settings);
             result.extend(import_result);
         }
 
+        for animation in document.animations() {
+            match Self::parse_animation(&animation, buffers) {
+                Ok(animation) => result.animations.push(animation),
+                Err(e) => result.errors.push(e),
+            }
+        }
+
         result
     }
 
@@ -180,50 +321,74 @@ impl GltfImporter {
         scene: Scene,
         document: &Document,
         buffers: &Vec<gltf::buffer::Data>,
-        textures: &Vec<gltf::image::Data>,
+        textures: &GltfImages,
+        settings: &GltfImportSettings,
     ) -> GltfImportResult {
         let nodes: Vec<_> = scene.nodes().collect();
 
-        Self::import_nodes(nodes, buffers, textures)
+        Self::import_nodes(nodes, document, buffers, textures, settings)
     }
 
     /// Handles importing a specific set of [`Node`]s from a glTF [`Document`].
     fn import_nodes(
         nodes: Vec<Node>,
+        document: &Document,
         buffers: &Vec<gltf::buffer::Data>,
-        textures: &Vec<gltf::image::Data>,
+        textures: &GltfImages,
+        settings: &GltfImportSettings,
     ) -> GltfImportResult {
-        let mut model_descriptors = Vec::new();
-        let mut camera_descriptors = Vec::new();
-        let mut light_descriptors = Vec::new();
-        let mut errors = Vec::new();
-
-        for node in nodes {
-            if let Some(mesh) = node.mesh() {
-                match Self::parse_models(&node, &mesh, buffers, textures) {
-                    Ok(models) => model_descriptors.extend(models),
-                    Err(e) => errors.push(e),
-                }
-            } else if let Some(camera) = node.camera() {
-                match Self::parse_camera(&node, &camera, buffers) {
-                    Ok(camera) => camera_descriptors.push(camera),
-                    Err(e) => errors.push(e),
-                }
-            } else if let Some(light) = node.light() {
-                match Self::parse_light(&node, &light, buffers) {
-                    Ok(light_desc) => light_descriptors.push(light_desc),
-                    Err(e) => errors.push(e),
+        // Nodes are converted independently of each other.
+        // Both paths keep the node order, thus the result is identical.
+        let node_results: Vec<_> = if settings.single_threaded {
+            nodes
+                .iter()
+                .map(|node| Self::import_node(node, document, buffers, textures, settings))
+                .collect()
+        } else {
+            nodes
+                .par_iter()
+                .map(|node| Self::import_node(node, document, buffers, textures, settings))
+                .collect()
+        };
+
+        let mut result = GltfImportResult::empty();
+        for node_result in node_results {
+            match node_result {
+                Some(Ok(NodeImport::Models { models, warnings })) => {
+                    result.models.extend(models);
+                    result.warnings.extend(warnings);
                 }
-            } else {
-                warn!("Unknown node type: {node:?}");
+                Some(Ok(NodeImport::Camera(camera))) => result.cameras.push(camera),
+                Some(Ok(NodeImport::Light(light))) => result.lights.push(light),
+                Some(Err(e)) => result.errors.push(e),
+                None => (),
             }
         }
 
-        GltfImportResult {
-            models: model_descriptors,
-            cameras: camera_descriptors,
-            lights: light_descriptors,
-            errors,
+        result
+    }
+
+    /// Converts a single [`Node`].
+    /// Returns [`None`] if the node type isn't supported.
+    fn import_node(
+        node: &Node,
+        document: &Document,
+        buffers: &Vec<gltf::buffer::Data>,
+        textures: &GltfImages,
+        settings: &GltfImportSettings,
+    ) -> Option<Result<NodeImport, Box<dyn Error + Send + Sync>>> {
+        if let Some(mesh) = node.mesh() {
+            Some(
+                Self::parse_models(node, &mesh, document, buffers, textures, settings)
+                    .map(|(models, warnings)| NodeImport::Models { models, warnings }),
+            )
+        } else if let Some(camera) = node.camera() {
+            Some(Self::parse_camera(node, &camera, buffers, settings).map(NodeImport::Camera))
+        } else if let Some(light) = node.light() {
+            Some(Self::parse_light(node, &light, buffers, settings).map(NodeImport::Light))
+        } else {
+            warn!("Unknown node type: {node:?}");
+            None
         }
     }
 
@@ -354,14 +519,14 @@ impl GltfImporter {
                 base_mip: 0,
                 mip_levels: 1, // glTF image data is typically just the base mip level
             },
-            usages: TextureUsages::TEXTURE_BINDING
-                | TextureUsages::COPY_DST
-                | TextureUsages::RENDER_ATTACHMENT, // Ensure required usages for textures
+            usages: TextureUsageIntent::SAMPLED.usages(),
             format,
             // Determine dimension based on data. For glTF images, D2 is standard.
             texture_dimension: TextureDimension::D2,
             texture_view_dimension: TextureViewDimension::D2,
             filter_mode: FilterMode::linear(),
+            address_mode: AddressMode::default(),
+            generate_mips: true,
         }
     }
 
@@ -377,6 +542,154 @@ impl GltfImporter {
         Self::parse_texture(data, false)
     }
 
+    /// Returns the KTX2 image (index and bytes) of a texture referenced
+    /// through `KHR_texture_basisu` if it can be uploaded as-is.
+    /// Basis Universal supercompressed images would need transcoding, in
+    /// which case `None` is returned and the regular source should be used.
+    ///
+    /// Whether the device supports the compressed format is only known
+    /// once realized, thus the regular source is kept as fallback.
+    fn usable_ktx2<'a>(
+        texture: &gltf::Texture,
+        textures: &'a GltfImages,
+    ) -> Option<(usize, &'a Vec<u8>)> {
+        let image = GltfImages::ktx2_source(texture)?;
+        let bytes = textures.ktx2.get(&image)?;
+
+        if let Err(e) = Ktx2Texture::parse(bytes) {
+            warn!(
+                "KTX2 image of texture #{} can't be used, falling back to its regular source: {}",
+                texture.index(),
+                e
+            );
+            return None;
+        }
+
+        Some((image, bytes))
+    }
+
+    /// Parses the texture a material references.
+    /// The result is shared with any other material referencing an image
+    /// with the same contents, using the same role and sampler.
+    fn shared_texture(
+        texture: &gltf::Texture,
+        textures: &GltfImages,
+        role: TextureRole,
+    ) -> Arc<TextureDescriptor> {
+        let sampler = texture.sampler();
+        let source = texture.source().index();
+        let (image, ktx2) = match Self::usable_ktx2(texture, textures) {
+            Some((image, bytes)) => (image, Some(bytes)),
+            None => (source, None),
+        };
+        let key = TextureKey {
+            image_hash: textures.hash_of(image),
+            role,
+            sampler: sampler.index(),
+        };
+
+        textures.shared_texture(key, || {
+            let parse_source = || match role {
+                TextureRole::Srgb => Self::parse_texture_srgb(&textures[source]),
+                _ => Self::parse_texture_linear(&textures[source]),
+            };
+            let descriptor = match ktx2 {
+                Some(bytes) => TextureDescriptor::Ktx2 {
+                    bytes: bytes.clone(),
+                    usages: TextureUsageIntent::SAMPLED.usages(),
+                    filter_mode: FilterMode::linear(),
+                    address_mode: AddressMode::default(),
+                    fallback: Some(Box::new(Self::apply_sampler(parse_source(), &sampler))),
+                },
+                None => parse_source(),
+            };
+
+            Self::apply_sampler(descriptor, &sampler)
+        })
+    }
+
+    /// Same as [`Self::shared_texture`], but splits a metallic-roughness
+    /// texture via [`Self::parse_dual_texture`].
+    fn shared_dual_texture(
+        texture: &gltf::Texture,
+        textures: &GltfImages,
+    ) -> (Arc<TextureDescriptor>, Arc<TextureDescriptor>) {
+        let sampler = texture.sampler();
+        let image = texture.source().index();
+        let key = |role| TextureKey {
+            image_hash: textures.hash_of(image),
+            role,
+            sampler: sampler.index(),
+        };
+
+        // Both halves are parsed at once, keep the roughness around in case
+        // the metallic half was missing
+        let mut roughness = None;
+        let metallic = textures.shared_texture(key(TextureRole::Metallic), || {
+            let (metallic, parsed_roughness) = Self::parse_dual_texture(&textures[image]);
+            roughness = Some(Self::apply_sampler(parsed_roughness, &sampler));
+            Self::apply_sampler(metallic, &sampler)
+        });
+        let roughness = textures.shared_texture(key(TextureRole::Roughness), || {
+            roughness.unwrap_or_else(|| {
+                Self::apply_sampler(Self::parse_dual_texture(&textures[image]).1, &sampler)
+            })
+        });
+
+        (metallic, roughness)
+    }
+
+    /// Applies the wrap and filter modes of a glTF [`Sampler`](gltf::texture::Sampler)
+    /// to a parsed [`TextureDescriptor`].
+    /// Filters the sampler doesn't define are kept.
+    fn apply_sampler(
+        mut descriptor: TextureDescriptor,
+        sampler: &gltf::texture::Sampler,
+    ) -> TextureDescriptor {
+        let (TextureDescriptor::Data {
+            filter_mode,
+            address_mode,
+            ..
+        }
+        | TextureDescriptor::Ktx2 {
+            filter_mode,
+            address_mode,
+            ..
+        }) = &mut descriptor
+        else {
+            return descriptor;
+        };
+
+        let wrap = |mode: WrappingMode| match mode {
+            WrappingMode::ClampToEdge => WAddressMode::ClampToEdge,
+            WrappingMode::MirroredRepeat => WAddressMode::MirrorRepeat,
+            WrappingMode::Repeat => WAddressMode::Repeat,
+        };
+        address_mode.u = wrap(sampler.wrap_s());
+        address_mode.v = wrap(sampler.wrap_t());
+
+        if let Some(mag_filter) = sampler.mag_filter() {
+            filter_mode.mag = match mag_filter {
+                MagFilter::Nearest => WFilterMode::Nearest,
+                MagFilter::Linear => WFilterMode::Linear,
+            };
+        }
+        if let Some(min_filter) = sampler.min_filter() {
+            let (min, mipmap) = match min_filter {
+                MinFilter::Nearest => (WFilterMode::Nearest, filter_mode.mipmap),
+                MinFilter::Linear => (WFilterMode::Linear, filter_mode.mipmap),
+                MinFilter::NearestMipmapNearest => (WFilterMode::Nearest, WFilterMode::Nearest),
+                MinFilter::LinearMipmapNearest => (WFilterMode::Linear, WFilterMode::Nearest),
+                MinFilter::NearestMipmapLinear => (WFilterMode::Nearest, WFilterMode::Linear),
+                MinFilter::LinearMipmapLinear => (WFilterMode::Linear, WFilterMode::Linear),
+            };
+            filter_mode.min = min;
+            filter_mode.mipmap = mipmap;
+        }
+
+        descriptor
+    }
+
     /// Handles parsing a "dual" texture.
     /// Same as [`Self::parse_texture`], but splits the B(lue) and G(reen) channel into two separate
     /// textures according to the glTF specification for metallic-roughness textures.
@@ -438,13 +751,13 @@ impl GltfImporter {
                 base_mip: 0,
                 mip_levels: 1,
             },
-            usages: TextureUsages::TEXTURE_BINDING
-                | TextureUsages::COPY_DST
-                | TextureUsages::RENDER_ATTACHMENT,
+            usages: TextureUsageIntent::SAMPLED.usages(),
             format: actual_format,
             texture_dimension: TextureDimension::D2,
             texture_view_dimension: TextureViewDimension::D2,
             filter_mode: FilterMode::linear(),
+            address_mode: AddressMode::default(),
+            generate_mips: true,
         };
         let texture_1 = TextureDescriptor::Data {
             pixels: pixels_1,
@@ -455,50 +768,54 @@ impl GltfImporter {
                 base_mip: 0,
                 mip_levels: 1,
             },
-            usages: TextureUsages::TEXTURE_BINDING
-                | TextureUsages::COPY_DST
-                | TextureUsages::RENDER_ATTACHMENT,
+            usages: TextureUsageIntent::SAMPLED.usages(),
             format: actual_format,
             texture_dimension: TextureDimension::D2,
             texture_view_dimension: TextureViewDimension::D2,
             filter_mode: FilterMode::linear(),
+            address_mode: AddressMode::default(),
+            generate_mips: true,
         };
 
         (texture_0, texture_1)
     }
 
     /// Handles parsing a glTF [`Material`] into an Orbital [`MaterialDescriptor`].
-    fn parse_materials(
-        material: &Material,
-        textures: &Vec<gltf::image::Data>,
-    ) -> MaterialDescriptor {
-        let normal = if let Some(normal_info) = material.normal_texture() {
-            Self::parse_texture_linear(&textures[normal_info.texture().source().index()])
+    fn parse_materials(material: &Material, textures: &GltfImages) -> MaterialDescriptor {
+        let (normal, normal_scale) = if let Some(normal_info) = material.normal_texture() {
+            (
+                Self::shared_texture(&normal_info.texture(), textures, TextureRole::Linear),
+                normal_info.scale(),
+            )
         } else {
             // Default normal map value: (0.5, 0.5, 1.0, 1.0) maps to (0, 0, 1) in tangent space after 2*x-1
             // Use linear format for normal maps (no sRGB conversion)
-            TextureDescriptor::uniform_rgba_value(0.5, 0.5, 1.0, 1.0, false)
+            (
+                Arc::new(TextureDescriptor::uniform_rgba_value(
+                    0.5, 0.5, 1.0, 1.0, false,
+                )),
+                1.0,
+            )
         };
 
-        // NOTE: 'W' (Opacity / Transparency) is skipped here!
-        let (albedo, albedo_factor) =
-            if let Some(albedo_info) = material.pbr_metallic_roughness().base_color_texture() {
-                let texture =
-                    Self::parse_texture_srgb(&textures[albedo_info.texture().source().index()]);
-                let factor = material.pbr_metallic_roughness().base_color_factor();
-                (texture, Vector3::new(factor[0], factor[1], factor[2]))
-            } else {
-                let factor = material.pbr_metallic_roughness().base_color_factor();
-                let texture = TextureDescriptor::uniform_rgba_value(
-                    factor[0] as f64,
-                    factor[1] as f64,
-                    factor[2] as f64,
-                    factor[3] as f64,
-                    true,
-                );
+        let (albedo, albedo_factor) = if let Some(albedo_info) =
+            material.pbr_metallic_roughness().base_color_texture()
+        {
+            let texture = Self::shared_texture(&albedo_info.texture(), textures, TextureRole::Srgb);
+            let factor = material.pbr_metallic_roughness().base_color_factor();
+            (texture, Vector4::from(factor))
+        } else {
+            let factor = material.pbr_metallic_roughness().base_color_factor();
+            let texture = TextureDescriptor::uniform_rgba_value(
+                factor[0] as f64,
+                factor[1] as f64,
+                factor[2] as f64,
+                factor[3] as f64,
+                true,
+            );
 
-                (texture, Vector3::new(1.0, 1.0, 1.0))
-            };
+            (Arc::new(texture), Vector4::new(1.0, 1.0, 1.0, 1.0))
+        };
 
         let (metallic, roughness, metallic_factor, roughness_factor) =
             if let Some(metallic_and_roughness_info) = material
@@ -508,9 +825,7 @@ impl GltfImporter {
                 // If a metallic & roughness texture is set, the factors will be needed to multiplied with the texture.
 
                 let (texture_descriptor_metallic, texture_descriptor_roughness) =
-                    Self::parse_dual_texture(
-                        &textures[metallic_and_roughness_info.texture().source().index()],
-                    );
+                    Self::shared_dual_texture(&metallic_and_roughness_info.texture(), textures);
 
                 let factor_metallic = material.pbr_metallic_roughness().metallic_factor();
                 let factor_roughness = material.pbr_metallic_roughness().roughness_factor();
@@ -543,23 +858,32 @@ impl GltfImporter {
                 );
 
                 (
-                    texture_descriptor_metallic,
-                    texture_descriptor_roughness,
+                    Arc::new(texture_descriptor_metallic),
+                    Arc::new(texture_descriptor_roughness),
                     1.0,
                     1.0,
                 )
             };
 
-        let occlusion = if let Some(occlusion_info) = material.occlusion_texture() {
-            Self::parse_texture_linear(&textures[occlusion_info.texture().source().index()])
-        } else {
-            TextureDescriptor::uniform_rgba_white(false)
-        };
-        let emissive = if let Some(emissive_info) = material.emissive_texture() {
-            Self::parse_texture_srgb(&textures[emissive_info.texture().source().index()])
+        let (occlusion, occlusion_strength) =
+            if let Some(occlusion_info) = material.occlusion_texture() {
+                (
+                    Self::shared_texture(&occlusion_info.texture(), textures, TextureRole::Linear),
+                    occlusion_info.strength(),
+                )
+            } else {
+                (Arc::new(TextureDescriptor::uniform_rgba_white(false)), 1.0)
+            };
+        let (emissive, emissive_factor) = if let Some(emissive_info) = material.emissive_texture() {
+            // If an emissive texture is set, the factor will be needed to multiplied with the texture.
+            let texture =
+                Self::shared_texture(&emissive_info.texture(), textures, TextureRole::Srgb);
+
+            (texture, Vector3::from(material.emissive_factor()))
         } else {
+            // If no emissive texture is set, the factor acts as a global texture.
             let emissive_color = material.emissive_factor();
-            TextureDescriptor::uniform_rgba_color(
+            let texture = TextureDescriptor::uniform_rgba_color(
                 Color {
                     r: emissive_color[0] as f64,
                     g: emissive_color[1] as f64,
@@ -567,12 +891,19 @@ impl GltfImporter {
                     a: 1.0,
                 },
                 true,
-            )
+            );
+
+            (Arc::new(texture), Vector3::new(1.0, 1.0, 1.0))
         };
+        // KHR_materials_emissive_strength
+        let emissive_strength = material.emissive_strength().unwrap_or(1.0);
 
         let pbr_material = PBRMaterialDescriptor {
             name: material.name().map(|x| x.to_string()),
             normal,
+            // glTF normal maps are always in tangent space
+            normal_map_space: NormalMapSpace::TangentSpace,
+            normal_scale,
             albedo,
             albedo_factor,
             metallic,
@@ -580,7 +911,25 @@ impl GltfImporter {
             roughness,
             roughness_factor,
             occlusion,
+            occlusion_strength,
             emissive,
+            emissive_factor,
+            emissive_strength,
+            // KHR_materials_clearcoat and KHR_materials_sheen aren't
+            // exposed by the gltf crate yet
+            clearcoat_factor: None,
+            clearcoat_roughness: 0.0,
+            clearcoat_normal: None,
+            sheen_color: None,
+            sheen_roughness: 0.0,
+            alpha_mode: match material.alpha_mode() {
+                gltf::material::AlphaMode::Opaque => AlphaMode::Opaque,
+                gltf::material::AlphaMode::Mask => AlphaMode::Mask {
+                    cutoff: material.alpha_cutoff().unwrap_or(0.5),
+                },
+                gltf::material::AlphaMode::Blend => AlphaMode::Blend,
+            },
+            double_sided: material.double_sided(),
             custom_material_shader: None,
         };
 
@@ -590,14 +939,36 @@ impl GltfImporter {
     /// Handles parsing of a glTF [`Mesh`] into multiple [`ModelDescriptor`]s.
     /// A _glTF Primitive_ is what Orbital considers a [`Model`].
     /// A _glTF Attribute_ is, in some sense, what Orbital considers a [`Mesh`] and [`Vertex`]
+    ///
+    /// Primitives which can't be imported are skipped and reported as
+    /// [`GltfImportWarning`]s.
     fn parse_models(
         node: &Node,
         mesh: &Mesh,
+        document: &Document,
         buffers: &Vec<gltf::buffer::Data>,
-        textures: &Vec<gltf::image::Data>,
-    ) -> Result<Vec<ModelDescriptor>, Box<dyn Error>> {
+        textures: &GltfImages,
+        settings: &GltfImportSettings,
+    ) -> Result<(Vec<ModelDescriptor>, Vec<GltfImportWarning>), Box<dyn Error + Send + Sync>> {
         let primitives = mesh.primitives();
         let mut results = Vec::new();
+        let mut warnings = Vec::new();
+        let label = node
+            .name()
+            .map(|x| x.to_string())
+            .unwrap_or("Unnamed".to_string());
+
+        // Animations replace the node transform, which includes the offset
+        // of recentered meshes
+        let animated = document
+            .animations()
+            .flat_map(|animation| animation.channels())
+            .any(|channel| channel.target().node().index() == node.index());
+
+        // Joints and weights are only imported if there is a skeleton
+        let skin = node
+            .skin()
+            .map(|skin| Arc::new(Self::parse_skin(&skin, buffers)));
 
         // glTF Primitive == Orbital Model
         for primitive in primitives {
@@ -605,15 +976,38 @@ impl GltfImporter {
 
             let Some(positions) = reader.read_positions() else {
                 warn!("Primitive has no positions. Skipping mesh primitive.");
+                warnings.push(GltfImportWarning {
+                    model: label.clone(),
+                    reason: GltfImportWarningReason::MissingPositions {
+                        primitive: primitive.index(),
+                    },
+                });
                 continue;
             };
             let Some(indices) = reader.read_indices().map(|x| x.into_u32()) else {
                 warn!("Primitive has no indices. Skipping mesh primitive.");
+                warnings.push(GltfImportWarning {
+                    model: label.clone(),
+                    reason: GltfImportWarningReason::MissingIndices {
+                        primitive: primitive.index(),
+                    },
+                });
                 continue;
             };
             let normals = reader.read_normals();
             let tangents = reader.read_tangents();
             let uvs = reader.read_tex_coords(0).map(|x| x.into_f32());
+            let colors_vec: Option<Vec<Vector4<f32>>> = reader
+                .read_colors(0)
+                .map(|x| x.into_rgba_f32().map(Vector4::from).collect());
+            let joints_vec: Option<Vec<[u16; 4]>> = skin
+                .as_ref()
+                .and(reader.read_joints(0))
+                .map(|x| x.into_u16().collect());
+            let weights_vec: Option<Vec<[f32; 4]>> = skin
+                .as_ref()
+                .and(reader.read_weights(0))
+                .map(|x| x.into_f32().collect());
             primitive.attributes().for_each(|x| {
                 if let Semantic::TexCoords(indices) = x.0 {
                     if indices > 1 {
@@ -632,7 +1026,7 @@ impl GltfImporter {
                 .unwrap_or_default(); // Get indices_vec here
 
             // --- Normal Calculation Logic Start ---
-            let normals_vec = if let Some(normals_iter) = normals {
+            let normals_vec: Vec<Vector3<f32>> = if let Some(normals_iter) = normals {
                 // If normals are provided in the glTF file, collect and convert them as before
                 normals_iter
                     .map(|n| Vector3::new(n[0], n[1], n[2]))
@@ -641,68 +1035,27 @@ impl GltfImporter {
                 // If normals are *not* provided, calculate them generically
                 warn!("Primitive has no normals. Calculating them based on triangle geometry.");
 
-                // Ensure we have positions and indices to work with for normal calculation
-                if positions_vec.is_empty() || indices_vec.is_empty() {
-                    warn!("Cannot calculate normals: Missing positions or indices.");
-                    (0..positions_vec.len()).map(|_| Vector3::zero()).collect()
-                } else {
-                    // Allocate a vector to accumulate normals for each vertex
-                    let mut calculated_normals: Vec<Vector3<f32>> =
-                        vec![Vector3::new(0.0, 0.0, 0.0); positions_vec.len()];
-
-                    // Iterate through triangles using indices to calculate face normals
-                    // Use indices_vec for calculating normals based on triangle connectivity.
-                    for i in (0..indices_vec.len()).step_by(3) {
-                        if i + 2 < indices_vec.len() {
-                            let idx_a = indices_vec[i] as usize;
-                            let idx_b = indices_vec[i + 1] as usize;
-                            let idx_c = indices_vec[i + 2] as usize;
-
-                            // Bounds check for indices
-                            if idx_a < positions_vec.len()
-                                && idx_b < positions_vec.len()
-                                && idx_c < positions_vec.len()
-                            {
-                                let p_a = positions_vec[idx_a];
-                                let p_b = positions_vec[idx_b];
-                                let p_c = positions_vec[idx_c];
-
-                                // Calculate face normal using the cross product
-                                // This assumes a right-handed coordinate system and standard CCW winding for front faces.
-                                // The vector order is important for correct normal direction.
-                                let edge_ab = p_b - p_a;
-                                let edge_ac = p_c - p_a;
-                                let face_normal = edge_ab.cross(edge_ac); // Not normalized yet, magnitude is proportional to area
-
-                                // Accumulate the un-normalized face normal for all three vertices
-                                // This weights the vertex normal by the area of the contributing face.
-                                calculated_normals[idx_a] += face_normal;
-                                calculated_normals[idx_b] += face_normal;
-                                calculated_normals[idx_c] += face_normal;
-                            } else {
-                                warn!("Index out of bounds during normal calculation at triangle starting index {}", i);
-                            }
-                        }
-                    }
-
-                    // Normalize the accumulated vertex normals to get the final unit vectors
-                    for normal in calculated_normals.iter_mut() {
-                        // Handle potential zero vectors (e.g., isolated vertex, degenerate faces)
-                        let norm_magnitude = normal.magnitude();
-                        if norm_magnitude > f32::EPSILON {
-                            // Check for non-zero length before normalizing
-                            *normal /= norm_magnitude;
-                        } else {
-                            // If the normal remains zero, a default is needed.
-                            // Keeping as zero for consistency with previous behavior,
-                            // though a context-specific default (e.g., 'up') might be better.
-                            // Log a trace message as it's usually not critical but good to know.
-                            trace!("Calculated normal resulted in zero vector, keeping as zero.");
-                        }
-                    }
-
-                    calculated_normals
-                }
+                let mut normal_descriptor = MeshDescriptor {
+                    vertices: positions_vec
+                        .iter()
+                        .map(|position| {
+                            Vertex::new(
+                                *position,
+                                Vector3::zero(),
+                                Vector3::zero(),
+                                Vector2::zero(),
+                            )
+                        })
+                        .collect(),
+                    indices: indices_vec.clone(),
+                    ..Default::default()
+                };
+                normal_descriptor.compute_normals(true);
+                normal_descriptor
+                    .vertices
+                    .into_iter()
+                    .map(|vertex| vertex.normal)
+                    .collect()
             };
             // --- Normal Calculation Logic End ---
 
@@ -767,7 +1120,20 @@ impl GltfImporter {
                 };
 
                 // Create vertex with the calculated or provided normal, tangent, and bitangent
-                let vertex = Vertex::new_with_bitangent(position, normal, tangent, bitangent, uv);
+                let mut vertex =
+                    Vertex::new_with_bitangent(position, normal, tangent, bitangent, uv);
+                if let Some(color) = colors_vec.as_ref().and_then(|x| x.get(i)) {
+                    vertex = vertex.with_color(*color);
+                }
+                if let (Some(joints), Some(weights)) = (
+                    joints_vec.as_ref().and_then(|x| x.get(i)),
+                    weights_vec.as_ref().and_then(|x| x.get(i)),
+                ) {
+                    vertex = vertex.with_skin(VertexSkin {
+                        joints: *joints,
+                        weights: *weights,
+                    });
+                }
                 vertices.push(vertex);
             }
 
@@ -785,14 +1151,45 @@ impl GltfImporter {
                 }
             }
 
-            let mesh_descriptor = MeshDescriptor {
+            let mut mesh_descriptor = MeshDescriptor {
                 vertices,
                 indices: indices_flipped,
+                ..Default::default()
+            };
+
+            if settings.repair_winding {
+                let flipped = mesh_descriptor.repair_winding();
+                if flipped > 0 {
+                    debug!("Repaired winding of {flipped} triangles");
+                }
+
+                if mesh_descriptor.is_uv_mirrored() {
+                    debug!("Mesh primitive has mirrored UVs, flipping them");
+                    mesh_descriptor.mirror_uvs();
+                }
+            }
+
+            // Normal maps need tangents matching the UV mapping
+            if tangents_vec.is_none()
+                && uvs_vec.is_some()
+                && !settings.geometry_only
+                && primitive.material().normal_texture().is_some()
+            {
+                debug!("Mesh primitive has a normal map, but no tangents. Generating them");
+                mesh_descriptor.compute_tangents();
+            }
+            let mut material = if settings.geometry_only {
+                PBRMaterialDescriptor::default().into()
+            } else {
+                Self::parse_materials(&primitive.material(), textures)
             };
-            let material = Self::parse_materials(&primitive.material(), textures);
+            let model_skin = mesh_descriptor.is_skinned().then(|| skin.clone()).flatten();
+            if model_skin.is_some() {
+                material = material.skinned();
+            }
 
             let decomposed = node.transform().decomposed();
-            let transform = Transform {
+            let mut transform = Transform {
                 position: Vector3 {
                     x: decomposed.0[0],
                     y: decomposed.0[1],
@@ -813,24 +1210,174 @@ impl GltfImporter {
                 },
             };
 
+            // Skinned meshes are placed by their joints alone, the transform
+            // of their node is ignored as required by the glTF spec
+            if model_skin.is_some() {
+                transform = Transform::default();
+            }
+
+            mesh_descriptor.compute_bounding_box();
+
+            // Keep vertex coordinates small by moving the mesh to the origin
+            // and compensating with the transform instead.
+            // Skinned meshes are left as-is, as their joints are defined
+            // relative to the original vertex positions.
+            // Animated meshes are left as-is, too, as animations would
+            // overwrite the compensating transform.
+            if model_skin.is_none() && !animated {
+                let offset = mesh_descriptor.recenter();
+                let model_space_matrix = Instance::from(&transform).make_model_space_matrix();
+                transform.position = (model_space_matrix * offset.extend(1.0)).truncate();
+            }
+
             let mut transforms = HashMap::new();
             let ulid = Ulid::new();
             transforms.insert(ulid, transform);
 
             let model = ModelDescriptor {
-                label: node
-                    .name()
-                    .map(|x| x.to_string())
-                    .unwrap_or("Unnamed".to_string()),
+                label: label.clone(),
                 mesh: Arc::new(mesh_descriptor),
                 materials: vec![Arc::new(material)],
                 transforms,
+                skin: model_skin,
+                // Blended materials need to be drawn after opaque geometry
+                render_layer: match primitive.material().alpha_mode() {
+                    gltf::material::AlphaMode::Blend if !settings.geometry_only => {
+                        RenderLayer::TRANSPARENT
+                    }
+                    _ => RenderLayer::default(),
+                },
             };
 
             results.push(model);
         }
 
-        Ok(results)
+        Ok((results, warnings))
+    }
+
+    /// Handles parsing of a glTF [`Skin`] and turns it into an Orbital [`SkinDescriptor`].
+    /// Joints are defined in glTF space (Y-up), thus the joint matrices get
+    /// converted into the Z-up space imported vertices use.
+    fn parse_skin(skin: &Skin, buffers: &[gltf::buffer::Data]) -> SkinDescriptor {
+        let joint_nodes: Vec<_> = skin.joints().collect();
+        let reader = skin.reader(|buffer| Some(&buffers[buffer.index()]));
+        let inverse_bind_matrices: Vec<Matrix4<f32>> = reader
+            .read_inverse_bind_matrices()
+            .map(|matrices| matrices.map(Matrix4::from).collect())
+            .unwrap_or_default();
+
+        let joints = joint_nodes
+            .iter()
+            .enumerate()
+            .map(|(i, joint)| {
+                let parent = joint_nodes.iter().position(|candidate| {
+                    candidate
+                        .children()
+                        .any(|child| child.index() == joint.index())
+                });
+
+                let (translation, [x, y, z, w], scale) = joint.transform().decomposed();
+
+                SkinJoint {
+                    label: joint
+                        .name()
+                        .map(|x| x.to_string())
+                        .unwrap_or("Unnamed".to_string()),
+                    parent,
+                    transform: Transform {
+                        position: Vector3::from(translation),
+                        rotation: Quaternion::new(w, x, y, z),
+                        scale: Vector3::from(scale),
+                    },
+                    inverse_bind_matrix: inverse_bind_matrices
+                        .get(i)
+                        .copied()
+                        .unwrap_or(Matrix4::identity()),
+                }
+            })
+            .collect();
+
+        SkinDescriptor {
+            label: skin
+                .name()
+                .map(|x| x.to_string())
+                .unwrap_or("Unnamed".to_string()),
+            joints,
+            // (x, y, z) -> (x, z, -y), same as the vertex conversion
+            space_conversion: Matrix4::new(
+                1.0, 0.0, 0.0, 0.0, //
+                0.0, 0.0, -1.0, 0.0, //
+                0.0, 1.0, 0.0, 0.0, //
+                0.0, 0.0, 0.0, 1.0,
+            ),
+        }
+    }
+
+    /// Handles parsing of a glTF [`Animation`] and turns it into an Orbital [`AnimationDescriptor`].
+    /// Channels target models by the name of the animated node.
+    /// Morph target weights aren't supported and are skipped.
+    fn parse_animation(
+        animation: &Animation,
+        buffers: &[gltf::buffer::Data],
+    ) -> Result<AnimationDescriptor, Box<dyn Error + Send + Sync>> {
+        let mut channels = Vec::new();
+
+        for channel in animation.channels() {
+            let reader = channel.reader(|buffer| Some(&buffers[buffer.index()]));
+
+            let Some(times) = reader.read_inputs() else {
+                warn!("Animation channel has no keyframe times. Skipping channel.");
+                continue;
+            };
+            let values = match reader.read_outputs() {
+                Some(ReadOutputs::Translations(translations)) => {
+                    AnimationValues::Translation(translations.map(Vector3::from).collect())
+                }
+                Some(ReadOutputs::Rotations(rotations)) => AnimationValues::Rotation(
+                    rotations
+                        .into_f32()
+                        .map(|[x, y, z, w]| Quaternion::new(w, x, y, z))
+                        .collect(),
+                ),
+                Some(ReadOutputs::Scales(scales)) => {
+                    AnimationValues::Scale(scales.map(Vector3::from).collect())
+                }
+                Some(ReadOutputs::MorphTargetWeights(_)) => {
+                    warn!("Morph target animations are not supported. Skipping channel.");
+                    continue;
+                }
+                None => {
+                    warn!("Animation channel has no keyframe values. Skipping channel.");
+                    continue;
+                }
+            };
+
+            let interpolation = match channel.sampler().interpolation() {
+                gltf::animation::Interpolation::Step => Interpolation::Step,
+                gltf::animation::Interpolation::Linear => Interpolation::Linear,
+                gltf::animation::Interpolation::CubicSpline => Interpolation::CubicSpline,
+            };
+
+            channels.push(AnimationChannel {
+                target: channel
+                    .target()
+                    .node()
+                    .name()
+                    .map(|x| x.to_string())
+                    .unwrap_or("Unnamed".to_string()),
+                interpolation,
+                times: times.collect(),
+                values,
+            });
+        }
+
+        Ok(AnimationDescriptor {
+            label: animation
+                .name()
+                .map(|x| x.to_string())
+                .unwrap_or("Unnamed".to_string()),
+            channels,
+        })
     }
 
     /// Handles parsing of glTF [`Camera`] and turns it into an Orbital [`CameraDescriptor`].
@@ -838,14 +1385,8 @@ impl GltfImporter {
         node: &Node,
         camera: &Camera,
         buffers: &Vec<gltf::buffer::Data>,
-    ) -> Result<CameraDescriptor, Box<dyn Error>> {
-        let perspective = match camera.projection() {
-            Projection::Orthographic(_) => {
-                return Err(Box::new(GltfError::Unsupported));
-            }
-            Projection::Perspective(perspective) => perspective,
-        };
-
+        settings: &GltfImportSettings,
+    ) -> Result<CameraDescriptor, Box<dyn Error + Send + Sync>> {
         let transform = node.transform();
         let decomposed = transform.decomposed();
 
@@ -857,20 +1398,52 @@ impl GltfImporter {
         );
         let (pitch, yaw) = quaternion_to_pitch_yaw(&quaternion);
 
-        let camera_descriptor = CameraDescriptor {
-            label: node
-                .name()
-                .map(|x| x.to_string())
-                .unwrap_or("Unnamed".to_string()),
-            position: Point3::new(decomposed.0[0], decomposed.0[1], decomposed.0[2]),
-            yaw,
-            pitch,
-            roll: 0.0,
-            aspect: perspective.aspect_ratio().unwrap_or(16.0 / 9.0),
-            fovy: perspective.yfov(),
-            near: perspective.znear(),
-            far: perspective.znear(),
-            global_gamma: CameraDescriptor::DEFAULT_GAMMA,
+        let label = node
+            .name()
+            .map(|x| x.to_string())
+            .unwrap_or("Unnamed".to_string());
+        let position = Point3::new(decomposed.0[0], decomposed.0[1], decomposed.0[2]);
+
+        let camera_descriptor = match camera.projection() {
+            GltfProjection::Orthographic(orthographic) => CameraDescriptor {
+                label,
+                position,
+                yaw,
+                pitch,
+                roll: 0.0,
+                projection: Projection::orthographic_centered(
+                    orthographic.ymag(),
+                    orthographic.xmag() / orthographic.ymag(),
+                ),
+                auto_aspect: settings.auto_aspect,
+                near: orthographic.znear(),
+                far: orthographic.zfar(),
+                ..Default::default()
+            },
+            GltfProjection::Perspective(perspective) => CameraDescriptor {
+                label,
+                position,
+                yaw,
+                pitch,
+                roll: 0.0,
+                projection: Projection::Perspective {
+                    fovy: perspective.yfov(),
+                    // Replaced with the surface aspect ratio once spawned
+                    aspect: perspective
+                        .aspect_ratio()
+                        .unwrap_or(Projection::default().aspect()),
+                },
+                auto_aspect: settings.auto_aspect || perspective.aspect_ratio().is_none(),
+                near: perspective.znear(),
+                // glTF cameras without a far plane use an infinite
+                // projection, which fitting the depth range to the scene
+                // approximates best
+                far: perspective
+                    .zfar()
+                    .unwrap_or(CameraDescriptor::default().far),
+                auto_depth_range: perspective.zfar().is_none(),
+                global_gamma: CameraDescriptor::DEFAULT_GAMMA,
+            },
         };
 
         Ok(camera_descriptor)
@@ -913,7 +1486,8 @@ impl GltfImporter {
         node: &Node,
         light: &khr_lights_punctual::Light,
         buffers: &Vec<gltf::buffer::Data>,
-    ) -> Result<LightDescriptor, Box<dyn Error>> {
+        settings: &GltfImportSettings,
+    ) -> Result<LightDescriptor, Box<dyn Error + Send + Sync>> {
         let transform = node.transform();
         let decomposed = transform.decomposed();
 
@@ -950,21 +1524,28 @@ impl GltfImporter {
         // Get the light properties from the glTF light
         let light_type = light.kind();
         let color = light.color();
-        let intensity = light.intensity();
+        let intensity = if settings.convert_light_units {
+            Self::convert_light_intensity(&light_type, light.intensity())
+        } else {
+            light.intensity()
+        };
+
+        // Lights are labelled by their node, or by themselves if unnamed
+        let name = node.name().or(light.name());
 
         // Convert RGB color to Vector3
         let color_vector = Vector3::new(color[0], color[1], color[2]);
 
         // Create the appropriate light descriptor based on the light type
-        let light_descriptor = match light_type {
+        let mut light_descriptor = match light_type {
             khr_lights_punctual::Kind::Directional => LightDescriptor::new_directional(
-                node.name().unwrap_or("Directional Light").to_string(),
+                name.unwrap_or("Directional Light").to_string(),
                 direction,
                 color_vector,
                 intensity,
             ),
             khr_lights_punctual::Kind::Point => LightDescriptor::new_point(
-                node.name().unwrap_or("Point Light").to_string(),
+                name.unwrap_or("Point Light").to_string(),
                 position,
                 color_vector,
                 intensity,
@@ -975,7 +1556,7 @@ impl GltfImporter {
             } => {
                 // Apply coordinate system conversion to direction for spot lights too
                 LightDescriptor::new_spot(
-                    node.name().unwrap_or("Spot Light").to_string(),
+                    name.unwrap_or("Spot Light").to_string(),
                     position,
                     direction,
                     color_vector,
@@ -986,6 +1567,29 @@ impl GltfImporter {
             }
         };
 
+        if let Some(range) = light.range() {
+            light_descriptor = light_descriptor.with_range(range);
+        }
+
         Ok(light_descriptor)
     }
+
+    /// Luminous efficacy (lm/W) of an ideal 555nm light source, used to
+    /// convert between photometric and radiometric units.
+    const LUMENS_PER_WATT: f32 = 683.0;
+
+    /// Converts a glTF light intensity from photometric units into
+    /// radiometric units.
+    ///
+    /// - Point and spot lights: candela (lm/sr) into watts, assuming the
+    ///   light emits into the full sphere
+    /// - Directional lights: lux (lm/m²) into W/m²
+    fn convert_light_intensity(kind: &khr_lights_punctual::Kind, intensity: f32) -> f32 {
+        match kind {
+            khr_lights_punctual::Kind::Directional => intensity / Self::LUMENS_PER_WATT,
+            khr_lights_punctual::Kind::Point | khr_lights_punctual::Kind::Spot { .. } => {
+                intensity * 4.0 * PI / Self::LUMENS_PER_WATT
+            }
+        }
+    }
 }
@@ -1,4 +1,5 @@
-use crate::resources::{CameraDescriptor, LightDescriptor, ModelDescriptor};
+use crate::importer::gltf::GltfImportWarning;
+use crate::resources::{AnimationDescriptor, CameraDescriptor, LightDescriptor, ModelDescriptor};
 use std::error::Error;
 
 /// Contains the results of a glTF Import.
@@ -7,7 +8,16 @@ pub struct GltfImportResult {
     pub models: Vec<ModelDescriptor>,
     pub cameras: Vec<CameraDescriptor>,
     pub lights: Vec<LightDescriptor>,
+    pub animations: Vec<AnimationDescriptor>,
     pub errors: Vec<Box<dyn Error>>,
+    /// Non-fatal issues, the affected parts got skipped.
+    pub warnings: Vec<GltfImportWarning>,
+    /// Number of images that got decoded during the import.
+    pub decoded_images: usize,
+    /// Number of distinct textures created from the images.
+    /// Materials referencing images with the same contents share a
+    /// texture, which is only counted once.
+    pub unique_textures: usize,
 }
 
 impl GltfImportResult {
@@ -19,6 +29,10 @@ impl GltfImportResult {
         self.models.extend(other.models);
         self.cameras.extend(other.cameras);
         self.lights.extend(other.lights);
+        self.animations.extend(other.animations);
         self.errors.extend(other.errors);
+        self.warnings.extend(other.warnings);
+        self.decoded_images += other.decoded_images;
+        self.unique_textures += other.unique_textures;
     }
 }
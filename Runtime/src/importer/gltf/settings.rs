@@ -0,0 +1,35 @@
+/// Optional processing steps applied while importing a glTF file.
+///
+/// Everything is disabled by default, meaning the file is imported as-is.
+#[derive(Debug, Default, Clone)]
+pub struct GltfImportSettings {
+    /// If enabled, the triangle winding of each imported mesh is unified.
+    /// Meshes with a mirrored UV mapping get their UVs flipped as well.
+    ///
+    /// Check [`MeshDescriptor::repair_winding`] for details.
+    ///
+    /// [`MeshDescriptor::repair_winding`]: crate::resources::MeshDescriptor::repair_winding
+    pub repair_winding: bool,
+    /// If enabled, only geometry is imported.
+    /// Materials and textures are skipped entirely and a default PBR material
+    /// is attached instead.
+    /// Images aren't decoded in this mode, which speeds up imports
+    /// significantly, e.g. when loading collision meshes.
+    pub geometry_only: bool,
+    /// If enabled, aspect ratios defined by glTF cameras are ignored and
+    /// the surface aspect ratio is used instead.
+    /// Cameras without an aspect ratio always use the surface aspect ratio.
+    pub auto_aspect: bool,
+    /// If enabled, light intensities are converted from glTF's photometric
+    /// units (candela for point and spot lights, lux for directional
+    /// lights) into radiometric units (watts and watts per square meter).
+    /// This matches the values used in e.g. Blender.
+    /// Otherwise, intensities are used as-is.
+    pub convert_light_units: bool,
+    /// If enabled, nodes are converted one after another on the calling
+    /// thread.
+    /// Otherwise, nodes are converted in parallel, which speeds up imports
+    /// of scenes with many meshes significantly.
+    /// The result is the same either way.
+    pub single_threaded: bool,
+}
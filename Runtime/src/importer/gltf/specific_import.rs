@@ -1,9 +1,11 @@
-use crate::importer::gltf::GltfImportType;
+use crate::importer::gltf::{GltfImportType, GltfLabel};
 
 #[derive(Debug)]
 pub struct SpecificGltfImport {
     /// The type of "thing" to import
     pub import_type: GltfImportType,
-    /// The label of the "thing" to import
-    pub label: String,
+    /// The label of the "thing" to import.
+    /// Either an exact name or a pattern matching multiple "things".
+    /// Plain strings convert into [GltfLabel::Exact].
+    pub label: GltfLabel,
 }
@@ -1,8 +1,9 @@
-use crate::importer::gltf::GltfImport;
+use crate::importer::gltf::{GltfImport, GltfImportSettings};
 
 /// Defines how a given glTF file is being imported.
 #[derive(Debug)]
 pub struct GltfImportTask {
     pub file: String,
     pub import: GltfImport,
+    pub settings: GltfImportSettings,
 }
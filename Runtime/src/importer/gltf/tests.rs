@@ -1,10 +1,26 @@
+use crate::element::CameraEvent;
+use crate::importer::gltf::GltfImportSettings;
 use crate::importer::gltf::{
-    GltfImport, GltfImportResult, GltfImportTask, GltfImportType, GltfImporter, SpecificGltfImport,
+    GltfImport, GltfImportResult, GltfImportTask, GltfImportType, GltfImportWarning,
+    GltfImportWarningReason, GltfImporter, GltfLabel, SpecificGltfImport,
 };
+use crate::importer::{ImportProgress, ImportTask, Importer};
 use crate::logging;
+use crate::resources::{
+    AnimationValue, CameraDescriptor, Instance, Interpolation, LightType, MaterialDescriptor,
+    ModelDescriptor, PBRMaterialDescriptor, PBRMaterialShaderDescriptor, Projection,
+    TextureDescriptor, Transform, VariableType, VertexLayout,
+};
+use crate::world::CameraStore;
 use async_std::task::block_on;
-use cgmath::{Point3, Quaternion, Vector3};
+use cgmath::{
+    EuclideanSpace, InnerSpace, Matrix4, Point3, Quaternion, SquareMatrix, Vector3, Vector4, Zero,
+};
+use image::{ImageFormat, RgbaImage};
 use log::debug;
+use std::io::Cursor;
+use std::sync::Arc;
+use wgpu::{AddressMode, BlendState, FilterMode};
 
 #[test]
 fn load_gltf() {
@@ -13,6 +29,7 @@ fn load_gltf() {
     let task = GltfImportTask {
         file: "../Assets/Models/TestScene.gltf".to_string(),
         import: GltfImport::WholeFile,
+        settings: Default::default(),
     };
 
     let x = GltfImporter::import(task);
@@ -29,6 +46,7 @@ fn load_glb() {
     let task = GltfImportTask {
         file: "../Assets/Models/TestScene.glb".to_string(),
         import: GltfImport::WholeFile,
+        settings: Default::default(),
     };
 
     let x = GltfImporter::import(task);
@@ -44,6 +62,7 @@ fn query(import: SpecificGltfImport) -> GltfImportResult {
     let task = GltfImportTask {
         file: "../Assets/Models/TestScene.gltf".to_string(),
         import: GltfImport::Specific(vec![import]),
+        settings: Default::default(),
     };
 
     let x = GltfImporter::import(task);
@@ -58,7 +77,7 @@ fn query(import: SpecificGltfImport) -> GltfImportResult {
 fn check_top_camera_existing() {
     let result = query(SpecificGltfImport {
         import_type: GltfImportType::Camera,
-        label: "Top Camera".to_string(),
+        label: "Top Camera".into(),
     });
     assert_eq!(result.cameras.len(), 1);
 }
@@ -67,7 +86,7 @@ fn check_top_camera_existing() {
 fn check_default_camera_existing() {
     let result = query(SpecificGltfImport {
         import_type: GltfImportType::Camera,
-        label: "Default Camera".to_string(),
+        label: "Default Camera".into(),
     });
     assert_eq!(result.cameras.len(), 1);
 }
@@ -76,7 +95,7 @@ fn check_default_camera_existing() {
 fn check_red_cube_existing() {
     let result = query(SpecificGltfImport {
         import_type: GltfImportType::Model,
-        label: "Red Cube".to_string(),
+        label: "Red Cube".into(),
     });
     assert_eq!(result.models.len(), 1);
 }
@@ -85,7 +104,7 @@ fn check_red_cube_existing() {
 fn check_blue_cube_existing() {
     let result = query(SpecificGltfImport {
         import_type: GltfImportType::Model,
-        label: "Blue Cube".to_string(),
+        label: "Blue Cube".into(),
     });
     assert_eq!(result.models.len(), 1);
 }
@@ -94,7 +113,7 @@ fn check_blue_cube_existing() {
 fn check_green_cube_existing() {
     let result = query(SpecificGltfImport {
         import_type: GltfImportType::Model,
-        label: "Green Cube".to_string(),
+        label: "Green Cube".into(),
     });
     assert_eq!(result.models.len(), 1);
 }
@@ -103,7 +122,7 @@ fn check_green_cube_existing() {
 fn check_top_camera_position_matches() {
     let result = query(SpecificGltfImport {
         import_type: GltfImportType::Camera,
-        label: "Top Camera".to_string(),
+        label: "Top Camera".into(),
     });
     assert_eq!(result.cameras.len(), 1);
 
@@ -115,7 +134,7 @@ fn check_top_camera_position_matches() {
 fn check_top_camera_rotation_matches() {
     let result = query(SpecificGltfImport {
         import_type: GltfImportType::Camera,
-        label: "Top Camera".to_string(),
+        label: "Top Camera".into(),
     });
     assert_eq!(result.cameras.len(), 1);
 
@@ -132,7 +151,7 @@ fn check_top_camera_rotation_matches() {
 fn check_red_cube_position_matches() {
     let result = query(SpecificGltfImport {
         import_type: GltfImportType::Model,
-        label: "Red Cube".to_string(),
+        label: "Red Cube".into(),
     });
     assert_eq!(result.models.len(), 1);
 
@@ -148,7 +167,7 @@ fn check_red_cube_position_matches() {
 fn check_red_cube_rotation_matches() {
     let result = query(SpecificGltfImport {
         import_type: GltfImportType::Model,
-        label: "Red Cube".to_string(),
+        label: "Red Cube".into(),
     });
     assert_eq!(result.models.len(), 1);
 
@@ -164,7 +183,7 @@ fn check_red_cube_rotation_matches() {
 fn check_red_cube_scale_matches() {
     let result = query(SpecificGltfImport {
         import_type: GltfImportType::Model,
-        label: "Red Cube".to_string(),
+        label: "Red Cube".into(),
     });
     assert_eq!(result.models.len(), 1);
 
@@ -180,7 +199,7 @@ fn check_red_cube_scale_matches() {
 fn check_blue_cube_position_matches() {
     let result = query(SpecificGltfImport {
         import_type: GltfImportType::Model,
-        label: "Blue Cube".to_string(),
+        label: "Blue Cube".into(),
     });
     assert_eq!(result.models.len(), 1);
 
@@ -196,7 +215,7 @@ fn check_blue_cube_position_matches() {
 fn check_blue_cube_rotation_matches() {
     let result = query(SpecificGltfImport {
         import_type: GltfImportType::Model,
-        label: "Blue Cube".to_string(),
+        label: "Blue Cube".into(),
     });
     assert_eq!(result.models.len(), 1);
 
@@ -212,7 +231,7 @@ fn check_blue_cube_rotation_matches() {
 fn check_blue_cube_scale_matches() {
     let result = query(SpecificGltfImport {
         import_type: GltfImportType::Model,
-        label: "Blue Cube".to_string(),
+        label: "Blue Cube".into(),
     });
     assert_eq!(result.models.len(), 1);
 
@@ -228,7 +247,7 @@ fn check_blue_cube_scale_matches() {
 fn check_green_cube_position_matches() {
     let result = query(SpecificGltfImport {
         import_type: GltfImportType::Model,
-        label: "Green Cube".to_string(),
+        label: "Green Cube".into(),
     });
     assert_eq!(result.models.len(), 1);
 
@@ -244,7 +263,7 @@ fn check_green_cube_position_matches() {
 fn check_green_cube_rotation_matches() {
     let result = query(SpecificGltfImport {
         import_type: GltfImportType::Model,
-        label: "Green Cube".to_string(),
+        label: "Green Cube".into(),
     });
     assert_eq!(result.models.len(), 1);
 
@@ -260,7 +279,7 @@ fn check_green_cube_rotation_matches() {
 fn check_green_cube_scale_matches() {
     let result = query(SpecificGltfImport {
         import_type: GltfImportType::Model,
-        label: "Green Cube".to_string(),
+        label: "Green Cube".into(),
     });
     assert_eq!(result.models.len(), 1);
 
@@ -283,8 +302,9 @@ fn check_light_import_specific() {
         file: "../Assets/Models/TestScene.gltf".to_string(),
         import: GltfImport::Specific(vec![SpecificGltfImport {
             import_type: GltfImportType::Light,
-            label: "SomeLight".to_string(), // This likely doesn't exist in the test file
+            label: "SomeLight".into(), // This likely doesn't exist in the test file
         }]),
+        settings: Default::default(),
     };
 
     let x = GltfImporter::import(task);
@@ -303,6 +323,7 @@ fn check_whole_file_light_import() {
     let task = GltfImportTask {
         file: "../Assets/Models/TestScene.gltf".to_string(),
         import: GltfImport::WholeFile,
+        settings: Default::default(),
     };
 
     let x = GltfImporter::import(task);
@@ -312,3 +333,1612 @@ fn check_whole_file_light_import() {
     // The import should succeed without errors, even if there are no lights
     assert!(result.errors.is_empty());
 }
+
+/// Writes a `.glb` file into the temporary directory and returns its path.
+/// The JSON chunk is padded with spaces and the binary chunk with zeros, as
+/// required by the specification.
+fn write_glb(file_name: &str, json: &str, bin: &[u8]) -> String {
+    let mut json = json.as_bytes().to_vec();
+    json.resize(json.len().next_multiple_of(4), b' ');
+    let mut bin = bin.to_vec();
+    bin.resize(bin.len().next_multiple_of(4), 0);
+
+    let length = 12 + 8 + json.len() + 8 + bin.len();
+
+    let mut glb = Vec::new();
+    glb.extend_from_slice(b"glTF");
+    glb.extend_from_slice(&2u32.to_le_bytes());
+    glb.extend_from_slice(&(length as u32).to_le_bytes());
+    glb.extend_from_slice(&(json.len() as u32).to_le_bytes());
+    glb.extend_from_slice(b"JSON");
+    glb.extend_from_slice(&json);
+    glb.extend_from_slice(&(bin.len() as u32).to_le_bytes());
+    glb.extend_from_slice(b"BIN\0");
+    glb.extend_from_slice(&bin);
+
+    let path = std::env::temp_dir().join(file_name);
+    std::fs::write(&path, glb).expect("Failed writing test glb");
+    path.to_string_lossy().to_string()
+}
+
+/// Binary data of a single triangle: positions (36 bytes) followed by `u16`
+/// indices (6 bytes + 2 bytes padding).
+fn triangle_bin() -> Vec<u8> {
+    let mut bin = Vec::new();
+    for position in [[0.0f32, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]] {
+        for x in position {
+            bin.extend_from_slice(&x.to_le_bytes());
+        }
+    }
+    for index in [0u16, 1, 2] {
+        bin.extend_from_slice(&index.to_le_bytes());
+    }
+    bin.extend_from_slice(&[0, 0]);
+    bin
+}
+
+/// Accessors and buffer views of [`triangle_bin`].
+const TRIANGLE_ACCESSORS: &str = r#"
+    "accessors": [
+        { "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3", "min": [0, 0, 0], "max": [1, 1, 0] },
+        { "bufferView": 1, "componentType": 5123, "count": 3, "type": "SCALAR" }
+    ]"#;
+
+/// Writes a `.glb` file containing a single triangle named `Triangle` with a
+/// 4x2 albedo texture embedded via a buffer view.
+fn write_textured_triangle_glb(file_name: &str) -> String {
+    let mut png = Vec::new();
+    RgbaImage::from_pixel(4, 2, image::Rgba([255, 0, 0, 255]))
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .expect("Failed encoding test image");
+
+    let mut bin = triangle_bin();
+    let image_offset = bin.len();
+    bin.extend_from_slice(&png);
+
+    let json = format!(
+        r#"{{
+            "asset": {{ "version": "2.0" }},
+            "scene": 0,
+            "scenes": [{{ "nodes": [0] }}],
+            "nodes": [{{ "name": "Triangle", "mesh": 0 }}],
+            "meshes": [{{ "primitives": [{{ "attributes": {{ "POSITION": 0 }}, "indices": 1, "material": 0 }}] }}],
+            "materials": [{{ "pbrMetallicRoughness": {{ "baseColorTexture": {{ "index": 0 }} }} }}],
+            "textures": [{{ "source": 0 }}],
+            "images": [{{ "bufferView": 2, "mimeType": "image/png" }}],
+            {TRIANGLE_ACCESSORS},
+            "bufferViews": [
+                {{ "buffer": 0, "byteOffset": 0, "byteLength": 36 }},
+                {{ "buffer": 0, "byteOffset": 36, "byteLength": 6 }},
+                {{ "buffer": 0, "byteOffset": {image_offset}, "byteLength": {} }}
+            ],
+            "buffers": [{{ "byteLength": {} }}]
+        }}"#,
+        png.len(),
+        bin.len().next_multiple_of(4),
+    );
+
+    write_glb(file_name, &json, &bin)
+}
+
+/// Returns the albedo texture of the first material of a model.
+fn albedo_of(model: &ModelDescriptor) -> &Arc<TextureDescriptor> {
+    // Albedo is the second variable of a PBR material
+    let VariableType::Texture { descriptor, .. } = &model.materials[0].variables[1] else {
+        panic!("Expected albedo to be a texture!");
+    };
+    descriptor
+}
+
+/// Returns the emissive factor and strength of a PBR material.
+fn emissive_factors(material: &MaterialDescriptor) -> ([f32; 3], f32) {
+    let Some(VariableType::Buffer(factors)) = material.variables.last() else {
+        panic!("Factor buffer expected as last variable");
+    };
+    let read_f32 =
+        |offset: usize| f32::from_le_bytes(factors.data[offset..offset + 4].try_into().unwrap());
+
+    let offset = PBRMaterialShaderDescriptor::EMISSIVE_FACTOR_OFFSET;
+    (
+        [read_f32(offset), read_f32(offset + 4), read_f32(offset + 8)],
+        read_f32(PBRMaterialShaderDescriptor::EMISSIVE_STRENGTH_OFFSET),
+    )
+}
+
+#[test]
+fn emissive_strength_import() {
+    logging::test_init();
+
+    let mut png = Vec::new();
+    RgbaImage::from_pixel(1, 1, image::Rgba([255, 255, 255, 255]))
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .expect("Failed encoding test image");
+
+    let mut bin = triangle_bin();
+    let image_offset = bin.len();
+    bin.extend_from_slice(&png);
+
+    let json = format!(
+        r#"{{
+            "asset": {{ "version": "2.0" }},
+            "extensionsUsed": ["KHR_materials_emissive_strength"],
+            "scene": 0,
+            "scenes": [{{ "nodes": [0, 1] }}],
+            "nodes": [
+                {{ "name": "Textured", "mesh": 0 }},
+                {{ "name": "Untextured", "mesh": 1 }}
+            ],
+            "meshes": [
+                {{ "primitives": [{{ "attributes": {{ "POSITION": 0 }}, "indices": 1, "material": 0 }}] }},
+                {{ "primitives": [{{ "attributes": {{ "POSITION": 0 }}, "indices": 1, "material": 1 }}] }}
+            ],
+            "materials": [
+                {{
+                    "name": "Textured",
+                    "emissiveTexture": {{ "index": 0 }},
+                    "emissiveFactor": [0.5, 0.25, 1.0],
+                    "extensions": {{ "KHR_materials_emissive_strength": {{ "emissiveStrength": 4.0 }} }}
+                }},
+                {{ "name": "Untextured", "emissiveFactor": [1.0, 0.0, 0.0] }}
+            ],
+            "textures": [{{ "source": 0 }}],
+            "images": [{{ "bufferView": 2, "mimeType": "image/png" }}],
+            {TRIANGLE_ACCESSORS},
+            "bufferViews": [
+                {{ "buffer": 0, "byteOffset": 0, "byteLength": 36 }},
+                {{ "buffer": 0, "byteOffset": 36, "byteLength": 6 }},
+                {{ "buffer": 0, "byteOffset": {image_offset}, "byteLength": {} }}
+            ],
+            "buffers": [{{ "byteLength": {} }}]
+        }}"#,
+        png.len(),
+        bin.len().next_multiple_of(4),
+    );
+
+    let task = GltfImportTask {
+        file: write_glb("orbital_emissive_strength.glb", &json, &bin),
+        import: GltfImport::WholeFile,
+        settings: Default::default(),
+    };
+    let result = block_on(GltfImporter::import(task));
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+
+    let material_of = |name: &str| {
+        result
+            .models
+            .iter()
+            .flat_map(|x| &x.materials)
+            .find(|x| x.name.as_deref() == Some(name))
+            .expect("Material must be imported")
+            .clone()
+    };
+
+    assert_eq!(
+        emissive_factors(&material_of("Textured")),
+        ([0.5, 0.25, 1.0], 4.0)
+    );
+    // Without a texture, the factor is baked into the texture instead
+    assert_eq!(
+        emissive_factors(&material_of("Untextured")),
+        ([1.0, 1.0, 1.0], 1.0)
+    );
+}
+
+#[test]
+fn normal_scale_and_occlusion_strength_import() {
+    logging::test_init();
+
+    let mut png = Vec::new();
+    RgbaImage::from_pixel(1, 1, image::Rgba([128, 128, 255, 255]))
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .expect("Failed encoding test image");
+
+    let mut bin = triangle_bin();
+    let image_offset = bin.len();
+    bin.extend_from_slice(&png);
+
+    let json = format!(
+        r#"{{
+            "asset": {{ "version": "2.0" }},
+            "scene": 0,
+            "scenes": [{{ "nodes": [0, 1] }}],
+            "nodes": [
+                {{ "name": "Scaled", "mesh": 0 }},
+                {{ "name": "Plain", "mesh": 1 }}
+            ],
+            "meshes": [
+                {{ "primitives": [{{ "attributes": {{ "POSITION": 0 }}, "indices": 1, "material": 0 }}] }},
+                {{ "primitives": [{{ "attributes": {{ "POSITION": 0 }}, "indices": 1, "material": 1 }}] }}
+            ],
+            "materials": [
+                {{
+                    "name": "Scaled",
+                    "normalTexture": {{ "index": 0, "scale": 2.0 }},
+                    "occlusionTexture": {{ "index": 0, "strength": 0.25 }}
+                }},
+                {{ "name": "Plain" }}
+            ],
+            "textures": [{{ "source": 0 }}],
+            "images": [{{ "bufferView": 2, "mimeType": "image/png" }}],
+            {TRIANGLE_ACCESSORS},
+            "bufferViews": [
+                {{ "buffer": 0, "byteOffset": 0, "byteLength": 36 }},
+                {{ "buffer": 0, "byteOffset": 36, "byteLength": 6 }},
+                {{ "buffer": 0, "byteOffset": {image_offset}, "byteLength": {} }}
+            ],
+            "buffers": [{{ "byteLength": {} }}]
+        }}"#,
+        png.len(),
+        bin.len().next_multiple_of(4),
+    );
+
+    let task = GltfImportTask {
+        file: write_glb("orbital_normal_occlusion_factors.glb", &json, &bin),
+        import: GltfImport::WholeFile,
+        settings: Default::default(),
+    };
+    let result = block_on(GltfImporter::import(task));
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+
+    let factors_of = |name: &str| {
+        let material = result
+            .models
+            .iter()
+            .flat_map(|x| &x.materials)
+            .find(|x| x.name.as_deref() == Some(name))
+            .expect("Material must be imported");
+        let Some(VariableType::Buffer(factors)) = material.variables.last() else {
+            panic!("Factor buffer expected as last variable");
+        };
+        let read_f32 = |offset: usize| {
+            f32::from_le_bytes(factors.data[offset..offset + 4].try_into().unwrap())
+        };
+
+        (
+            read_f32(PBRMaterialShaderDescriptor::NORMAL_SCALE_OFFSET),
+            read_f32(PBRMaterialShaderDescriptor::OCCLUSION_STRENGTH_OFFSET),
+        )
+    };
+
+    assert_eq!(factors_of("Scaled"), (2.0, 0.25));
+    assert_eq!(factors_of("Plain"), (1.0, 1.0));
+}
+
+#[test]
+fn embedded_buffer_view_texture() {
+    logging::test_init();
+
+    let task = GltfImportTask {
+        file: write_textured_triangle_glb("orbital_embedded_texture.glb"),
+        import: GltfImport::WholeFile,
+        settings: Default::default(),
+    };
+    let result = block_on(GltfImporter::import(task));
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    assert_eq!(result.models.len(), 1);
+    assert_eq!(result.decoded_images, 1);
+
+    // Albedo is the second variable of a PBR material
+    let TextureDescriptor::Data { size, .. } = albedo_of(&result.models[0]).as_ref() else {
+        panic!("Expected albedo to be a data texture!");
+    };
+    assert_eq!(size.width, 4);
+    assert_eq!(size.height, 2);
+}
+
+#[test]
+fn texture_sampler_import() {
+    logging::test_init();
+
+    let mut png = Vec::new();
+    RgbaImage::from_pixel(2, 2, image::Rgba([255, 0, 0, 255]))
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .expect("Failed encoding test image");
+
+    let mut bin = triangle_bin();
+    let image_offset = bin.len();
+    bin.extend_from_slice(&png);
+
+    // CLAMP_TO_EDGE, MIRRORED_REPEAT, NEAREST and LINEAR_MIPMAP_NEAREST
+    let json = format!(
+        r#"{{
+            "asset": {{ "version": "2.0" }},
+            "scene": 0,
+            "scenes": [{{ "nodes": [0] }}],
+            "nodes": [{{ "name": "Triangle", "mesh": 0 }}],
+            "meshes": [{{ "primitives": [{{ "attributes": {{ "POSITION": 0 }}, "indices": 1, "material": 0 }}] }}],
+            "materials": [{{ "pbrMetallicRoughness": {{ "baseColorTexture": {{ "index": 0 }} }} }}],
+            "samplers": [{{ "wrapS": 33071, "wrapT": 33648, "magFilter": 9728, "minFilter": 9985 }}],
+            "textures": [{{ "source": 0, "sampler": 0 }}],
+            "images": [{{ "bufferView": 2, "mimeType": "image/png" }}],
+            {TRIANGLE_ACCESSORS},
+            "bufferViews": [
+                {{ "buffer": 0, "byteOffset": 0, "byteLength": 36 }},
+                {{ "buffer": 0, "byteOffset": 36, "byteLength": 6 }},
+                {{ "buffer": 0, "byteOffset": {image_offset}, "byteLength": {} }}
+            ],
+            "buffers": [{{ "byteLength": {} }}]
+        }}"#,
+        png.len(),
+        bin.len().next_multiple_of(4),
+    );
+
+    let task = GltfImportTask {
+        file: write_glb("orbital_texture_sampler.glb", &json, &bin),
+        import: GltfImport::WholeFile,
+        settings: Default::default(),
+    };
+    let result = block_on(GltfImporter::import(task));
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+
+    let TextureDescriptor::Data {
+        filter_mode,
+        address_mode,
+        ..
+    } = albedo_of(&result.models[0]).as_ref()
+    else {
+        panic!("Expected albedo to be a data texture!");
+    };
+    assert_eq!(address_mode.u, AddressMode::ClampToEdge);
+    assert_eq!(address_mode.v, AddressMode::MirrorRepeat);
+    assert_eq!(filter_mode.mag, FilterMode::Nearest);
+    assert_eq!(filter_mode.min, FilterMode::Linear);
+    assert_eq!(filter_mode.mipmap, FilterMode::Nearest);
+
+    // Without a sampler, textures repeat
+    let task = GltfImportTask {
+        file: write_textured_triangle_glb("orbital_texture_default_sampler.glb"),
+        import: GltfImport::WholeFile,
+        settings: Default::default(),
+    };
+    let result = block_on(GltfImporter::import(task));
+    let TextureDescriptor::Data { address_mode, .. } = albedo_of(&result.models[0]).as_ref() else {
+        panic!("Expected albedo to be a data texture!");
+    };
+    assert_eq!(address_mode.u, AddressMode::Repeat);
+    assert_eq!(address_mode.v, AddressMode::Repeat);
+}
+
+#[test]
+fn shared_images_are_deduplicated() {
+    logging::test_init();
+
+    let mut png = Vec::new();
+    RgbaImage::from_pixel(4, 2, image::Rgba([255, 0, 0, 255]))
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .expect("Failed encoding test image");
+
+    // Both images have the same contents, but are stored separately
+    let mut bin = triangle_bin();
+    let first_offset = bin.len();
+    bin.extend_from_slice(&png);
+    let second_offset = bin.len();
+    bin.extend_from_slice(&png);
+
+    let json = format!(
+        r#"{{
+            "asset": {{ "version": "2.0" }},
+            "scene": 0,
+            "scenes": [{{ "nodes": [0, 1] }}],
+            "nodes": [
+                {{ "name": "Red", "mesh": 0 }},
+                {{ "name": "Dark", "mesh": 1 }}
+            ],
+            "meshes": [
+                {{ "primitives": [{{ "attributes": {{ "POSITION": 0 }}, "indices": 1, "material": 0 }}] }},
+                {{ "primitives": [{{ "attributes": {{ "POSITION": 0 }}, "indices": 1, "material": 1 }}] }}
+            ],
+            "materials": [
+                {{ "pbrMetallicRoughness": {{ "baseColorTexture": {{ "index": 0 }} }} }},
+                {{ "pbrMetallicRoughness": {{ "baseColorTexture": {{ "index": 1 }}, "baseColorFactor": [0.5, 0.5, 0.5, 1.0] }} }}
+            ],
+            "textures": [{{ "source": 0 }}, {{ "source": 1 }}],
+            "images": [
+                {{ "bufferView": 2, "mimeType": "image/png" }},
+                {{ "bufferView": 3, "mimeType": "image/png" }}
+            ],
+            {TRIANGLE_ACCESSORS},
+            "bufferViews": [
+                {{ "buffer": 0, "byteOffset": 0, "byteLength": 36 }},
+                {{ "buffer": 0, "byteOffset": 36, "byteLength": 6 }},
+                {{ "buffer": 0, "byteOffset": {first_offset}, "byteLength": {} }},
+                {{ "buffer": 0, "byteOffset": {second_offset}, "byteLength": {} }}
+            ],
+            "buffers": [{{ "byteLength": {} }}]
+        }}"#,
+        png.len(),
+        png.len(),
+        bin.len().next_multiple_of(4),
+    );
+
+    let task = GltfImportTask {
+        file: write_glb("orbital_shared_images.glb", &json, &bin),
+        import: GltfImport::WholeFile,
+        settings: Default::default(),
+    };
+    let result = block_on(GltfImporter::import(task));
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    assert_eq!(result.models.len(), 2);
+    assert_eq!(result.decoded_images, 2);
+
+    // Different materials, but a single albedo texture
+    assert_ne!(result.models[0].materials, result.models[1].materials);
+    assert!(Arc::ptr_eq(
+        albedo_of(&result.models[0]),
+        albedo_of(&result.models[1])
+    ));
+    assert_eq!(result.unique_textures, 1);
+}
+
+/// Builds a minimal 2x2 RGBA8 KTX2 container with a single mip level.
+fn rgba8_ktx2(supercompression: u32) -> Vec<u8> {
+    let mut bytes = crate::resources::KTX2_IDENTIFIER.to_vec();
+    for value in [37u32, 1, 2, 2, 0, 0, 1, 1, supercompression] {
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    bytes.extend_from_slice(&[0u8; 32]);
+    for value in [104u64, 16, 16] {
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    bytes.extend_from_slice(&[255u8; 16]);
+    bytes
+}
+
+/// Writes a `.glb` file containing a single triangle whose albedo texture
+/// references a KTX2 image through `KHR_texture_basisu`, with a 4x2 PNG as
+/// regular source.
+fn write_basisu_triangle_glb(file_name: &str, ktx2: &[u8]) -> String {
+    let mut png = Vec::new();
+    RgbaImage::from_pixel(4, 2, image::Rgba([255, 0, 0, 255]))
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .expect("Failed encoding test image");
+
+    let mut bin = triangle_bin();
+    let png_offset = bin.len();
+    bin.extend_from_slice(&png);
+    bin.resize(bin.len().next_multiple_of(4), 0);
+    let ktx2_offset = bin.len();
+    bin.extend_from_slice(ktx2);
+
+    let json = format!(
+        r#"{{
+            "asset": {{ "version": "2.0" }},
+            "extensionsUsed": ["KHR_texture_basisu"],
+            "scene": 0,
+            "scenes": [{{ "nodes": [0] }}],
+            "nodes": [{{ "name": "Triangle", "mesh": 0 }}],
+            "meshes": [{{ "primitives": [{{ "attributes": {{ "POSITION": 0 }}, "indices": 1, "material": 0 }}] }}],
+            "materials": [{{ "pbrMetallicRoughness": {{ "baseColorTexture": {{ "index": 0 }} }} }}],
+            "textures": [{{ "source": 0, "extensions": {{ "KHR_texture_basisu": {{ "source": 1 }} }} }}],
+            "images": [
+                {{ "bufferView": 2, "mimeType": "image/png" }},
+                {{ "bufferView": 3, "mimeType": "image/ktx2" }}
+            ],
+            {TRIANGLE_ACCESSORS},
+            "bufferViews": [
+                {{ "buffer": 0, "byteOffset": 0, "byteLength": 36 }},
+                {{ "buffer": 0, "byteOffset": 36, "byteLength": 6 }},
+                {{ "buffer": 0, "byteOffset": {png_offset}, "byteLength": {} }},
+                {{ "buffer": 0, "byteOffset": {ktx2_offset}, "byteLength": {} }}
+            ],
+            "buffers": [{{ "byteLength": {} }}]
+        }}"#,
+        png.len(),
+        ktx2.len(),
+        bin.len().next_multiple_of(4),
+    );
+
+    write_glb(file_name, &json, &bin)
+}
+
+#[test]
+fn basisu_ktx2_texture_is_preferred() {
+    logging::test_init();
+
+    let ktx2 = rgba8_ktx2(0);
+    let task = GltfImportTask {
+        file: write_basisu_triangle_glb("orbital_basisu_texture.glb", &ktx2),
+        import: GltfImport::WholeFile,
+        settings: Default::default(),
+    };
+    let result = block_on(GltfImporter::import(task));
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    assert_eq!(result.models.len(), 1);
+
+    let TextureDescriptor::Ktx2 {
+        bytes, fallback, ..
+    } = albedo_of(&result.models[0]).as_ref()
+    else {
+        panic!("Expected albedo to be a KTX2 texture!");
+    };
+    assert_eq!(*bytes, ktx2);
+
+    // The PNG source is kept for devices lacking the KTX2 format
+    let Some(TextureDescriptor::Data { size, .. }) = fallback.as_deref() else {
+        panic!("Expected the PNG source as fallback!");
+    };
+    assert_eq!((size.width, size.height), (4, 2));
+}
+
+#[test]
+fn supercompressed_basisu_texture_falls_back() {
+    logging::test_init();
+
+    // BasisLZ supercompressed images would need transcoding
+    let task = GltfImportTask {
+        file: write_basisu_triangle_glb("orbital_basisu_fallback.glb", &rgba8_ktx2(1)),
+        import: GltfImport::WholeFile,
+        settings: Default::default(),
+    };
+    let result = block_on(GltfImporter::import(task));
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+
+    let TextureDescriptor::Data { size, .. } = albedo_of(&result.models[0]).as_ref() else {
+        panic!("Expected albedo to fall back to the PNG source!");
+    };
+    assert_eq!(size.width, 4);
+    assert_eq!(size.height, 2);
+}
+
+#[test]
+fn blocking_import() {
+    logging::test_init();
+
+    let mut importer = Importer::new(1);
+    importer.register_task(ImportTask::Gltf {
+        file_path: write_textured_triangle_glb("orbital_blocking_import.glb"),
+        task: GltfImport::WholeFile,
+        settings: GltfImportSettings::default(),
+    });
+
+    // Results are available right away, without polling `update`
+    let results = importer.import_blocking();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].models.len(), 1);
+    assert_eq!(results[0].models[0].mesh.vertices.len(), 3);
+
+    // Queued tasks are consumed
+    assert!(importer.import_blocking().is_empty());
+}
+
+#[test]
+fn importer_caps_parallel_tasks() {
+    logging::test_init();
+
+    let mut importer = Importer::new(2);
+    for i in 0..3 {
+        importer.register_task(ImportTask::Gltf {
+            file_path: write_textured_triangle_glb(&format!("orbital_parallel_import_{i}.glb")),
+            task: GltfImport::WholeFile,
+            settings: GltfImportSettings::default(),
+        });
+    }
+    assert_eq!(
+        importer.progress(),
+        ImportProgress {
+            finished: 0,
+            total: 3
+        }
+    );
+
+    let mut results = Vec::new();
+    let started = std::time::Instant::now();
+    while !importer.progress().is_done() {
+        assert!(
+            started.elapsed() < std::time::Duration::from_secs(60),
+            "Imports didn't finish in time"
+        );
+
+        results.extend(block_on(importer.update()));
+        assert!(importer.running_task_count() <= 2);
+        std::thread::sleep(std::time::Duration::from_millis(5));
+    }
+
+    assert_eq!(
+        importer.progress(),
+        ImportProgress {
+            finished: 3,
+            total: 3
+        }
+    );
+    assert_eq!(importer.running_task_count(), 0);
+    assert_eq!(results.len(), 3);
+    assert!(results.iter().all(|result| result.models.len() == 1));
+
+    // Registering after everything finished starts counting anew
+    importer.register_task(ImportTask::Gltf {
+        file_path: write_textured_triangle_glb("orbital_parallel_import_3.glb"),
+        task: GltfImport::WholeFile,
+        settings: GltfImportSettings::default(),
+    });
+    assert_eq!(
+        importer.progress(),
+        ImportProgress {
+            finished: 0,
+            total: 1
+        }
+    );
+}
+
+#[test]
+fn geometry_only_import() {
+    logging::test_init();
+
+    let task = GltfImportTask {
+        file: write_textured_triangle_glb("orbital_geometry_only.glb"),
+        import: GltfImport::WholeFile,
+        settings: GltfImportSettings {
+            geometry_only: true,
+            ..Default::default()
+        },
+    };
+    let result = block_on(GltfImporter::import(task));
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    assert_eq!(result.decoded_images, 0);
+
+    assert_eq!(result.models.len(), 1);
+    let model = &result.models[0];
+    assert_eq!(model.mesh.vertices.len(), 3);
+    assert_eq!(model.mesh.indices.len(), 3);
+
+    let default_material: MaterialDescriptor = PBRMaterialDescriptor::default().into();
+    assert_eq!(*model.materials[0], default_material);
+}
+
+#[test]
+fn vertex_color_import() {
+    logging::test_init();
+
+    let colors = [
+        [1.0f32, 0.0, 0.0, 1.0],
+        [0.0, 1.0, 0.0, 0.5],
+        [0.0, 0.0, 1.0, 1.0],
+    ];
+    let mut bin = triangle_bin();
+    let color_offset = bin.len();
+    for color in colors {
+        for x in color {
+            bin.extend_from_slice(&x.to_le_bytes());
+        }
+    }
+
+    let json = format!(
+        r#"{{
+            "asset": {{ "version": "2.0" }},
+            "scene": 0,
+            "scenes": [{{ "nodes": [0] }}],
+            "nodes": [{{ "name": "Triangle", "mesh": 0 }}],
+            "meshes": [{{ "primitives": [{{ "attributes": {{ "POSITION": 0, "COLOR_0": 2 }}, "indices": 1 }}] }}],
+            "accessors": [
+                {{ "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3", "min": [0, 0, 0], "max": [1, 1, 0] }},
+                {{ "bufferView": 1, "componentType": 5123, "count": 3, "type": "SCALAR" }},
+                {{ "bufferView": 2, "componentType": 5126, "count": 3, "type": "VEC4" }}
+            ],
+            "bufferViews": [
+                {{ "buffer": 0, "byteOffset": 0, "byteLength": 36 }},
+                {{ "buffer": 0, "byteOffset": 36, "byteLength": 6 }},
+                {{ "buffer": 0, "byteOffset": {color_offset}, "byteLength": 48 }}
+            ],
+            "buffers": [{{ "byteLength": {} }}]
+        }}"#,
+        bin.len(),
+    );
+
+    let task = GltfImportTask {
+        file: write_glb("orbital_vertex_color.glb", &json, &bin),
+        import: GltfImport::WholeFile,
+        settings: Default::default(),
+    };
+    let result = block_on(GltfImporter::import(task));
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    assert_eq!(result.models.len(), 1);
+
+    let vertices = &result.models[0].mesh.vertices;
+    assert_eq!(vertices.len(), 3);
+    for (vertex, color) in vertices.iter().zip(colors) {
+        assert_eq!(vertex.color, Some(Vector4::from(color)));
+
+        // Color follows position, normal, tangent, bitangent and UV
+        let bytes = vertex.to_bytes();
+        assert_eq!(bytes.len() as u64, VertexLayout::standard().array_stride);
+        let color_bytes: Vec<u8> = color.iter().flat_map(|x| x.to_le_bytes()).collect();
+        assert_eq!(bytes[56..72], color_bytes);
+    }
+}
+
+#[test]
+fn vertex_color_defaults_to_white() {
+    logging::test_init();
+
+    let task = GltfImportTask {
+        file: write_textured_triangle_glb("orbital_vertex_color_default.glb"),
+        import: GltfImport::WholeFile,
+        settings: Default::default(),
+    };
+    let result = block_on(GltfImporter::import(task));
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+
+    let vertex = &result.models[0].mesh.vertices[0];
+    assert_eq!(vertex.color, None);
+
+    let white: Vec<u8> = [1.0f32; 4].iter().flat_map(|x| x.to_le_bytes()).collect();
+    assert_eq!(vertex.to_bytes()[56..72], white);
+}
+
+#[test]
+fn material_double_sided_and_alpha_mode() {
+    logging::test_init();
+
+    let json = format!(
+        r#"{{
+            "asset": {{ "version": "2.0" }},
+            "scene": 0,
+            "scenes": [{{ "nodes": [0] }}],
+            "nodes": [{{ "name": "Triangle", "mesh": 0 }}],
+            "meshes": [{{ "primitives": [{{ "attributes": {{ "POSITION": 0 }}, "indices": 1, "material": 0 }}] }}],
+            "materials": [{{ "doubleSided": true, "alphaMode": "BLEND" }}],
+            {TRIANGLE_ACCESSORS},
+            "bufferViews": [
+                {{ "buffer": 0, "byteOffset": 0, "byteLength": 36 }},
+                {{ "buffer": 0, "byteOffset": 36, "byteLength": 6 }}
+            ],
+            "buffers": [{{ "byteLength": 44 }}]
+        }}"#
+    );
+
+    let task = GltfImportTask {
+        file: write_glb("orbital_double_sided.glb", &json, &triangle_bin()),
+        import: GltfImport::WholeFile,
+        settings: Default::default(),
+    };
+    let result = block_on(GltfImporter::import(task));
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+
+    let material = &result.models[0].materials[0];
+    assert_eq!(material.cull_mode, None);
+    assert_eq!(material.blend, Some(BlendState::ALPHA_BLENDING));
+}
+
+/// Writes a `.glb` file containing `count` triangle nodes named
+/// `Triangle<i>`, each moved along the X axis by its index.
+fn write_multi_mesh_glb(file_name: &str, count: usize) -> String {
+    let nodes = (0..count)
+        .map(|i| format!(r#"{{ "name": "Triangle{i}", "mesh": 0, "translation": [{i}, 0, 0] }}"#))
+        .collect::<Vec<_>>()
+        .join(",");
+    let node_indices = (0..count)
+        .map(|i| i.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let json = format!(
+        r#"{{
+            "asset": {{ "version": "2.0" }},
+            "scene": 0,
+            "scenes": [{{ "nodes": [{node_indices}] }}],
+            "nodes": [{nodes}],
+            "meshes": [{{ "primitives": [{{ "attributes": {{ "POSITION": 0 }}, "indices": 1 }}] }}],
+            {TRIANGLE_ACCESSORS},
+            "bufferViews": [
+                {{ "buffer": 0, "byteOffset": 0, "byteLength": 36 }},
+                {{ "buffer": 0, "byteOffset": 36, "byteLength": 6 }}
+            ],
+            "buffers": [{{ "byteLength": 44 }}]
+        }}"#
+    );
+
+    write_glb(file_name, &json, &triangle_bin())
+}
+
+#[test]
+fn parallel_import_matches_single_threaded() {
+    logging::test_init();
+
+    let file = write_multi_mesh_glb("orbital_multi_mesh.glb", 128);
+    let import = |single_threaded| {
+        block_on(GltfImporter::import(GltfImportTask {
+            file: file.clone(),
+            import: GltfImport::WholeFile,
+            settings: GltfImportSettings {
+                single_threaded,
+                ..Default::default()
+            },
+        }))
+    };
+
+    let parallel = import(false);
+    let serial = import(true);
+    assert!(parallel.errors.is_empty(), "{:?}", parallel.errors);
+    assert!(serial.errors.is_empty(), "{:?}", serial.errors);
+    assert_eq!(parallel.models.len(), 128);
+    assert_eq!(parallel.models.len(), serial.models.len());
+
+    for (i, (parallel, serial)) in parallel.models.iter().zip(&serial.models).enumerate() {
+        // Scene order must be kept
+        assert_eq!(parallel.label, format!("Triangle{i}"));
+        assert_eq!(parallel.label, serial.label);
+        assert_eq!(parallel.mesh, serial.mesh);
+        assert_eq!(parallel.materials, serial.materials);
+
+        let parallel_transforms: Vec<_> = parallel.transforms.values().collect();
+        let serial_transforms: Vec<_> = serial.transforms.values().collect();
+        assert_eq!(parallel_transforms, serial_transforms);
+        // Node translation plus the offset of the recentered triangle
+        assert_eq!(
+            parallel_transforms[0].position,
+            Vector3::new(i as f32 - 0.5, 0.0, -0.5)
+        );
+    }
+}
+
+/// Writes a `.glb` file containing a single perspective camera named
+/// `Camera`, optionally with an aspect ratio.
+fn write_camera_glb(file_name: &str, aspect_ratio: Option<f32>) -> String {
+    let aspect_ratio = aspect_ratio
+        .map(|x| format!(r#""aspectRatio": {x},"#))
+        .unwrap_or_default();
+
+    let json = format!(
+        r#"{{
+            "asset": {{ "version": "2.0" }},
+            "scene": 0,
+            "scenes": [{{ "nodes": [0] }}],
+            "nodes": [{{ "name": "Camera", "camera": 0 }}],
+            "cameras": [{{
+                "type": "perspective",
+                "perspective": {{ {aspect_ratio} "yfov": 0.8, "znear": 0.1, "zfar": 100.0 }}
+            }}]
+        }}"#
+    );
+
+    write_glb(file_name, &json, &[])
+}
+
+fn import_camera(file: String, settings: GltfImportSettings) -> CameraDescriptor {
+    logging::test_init();
+
+    let task = GltfImportTask {
+        file,
+        import: GltfImport::WholeFile,
+        settings,
+    };
+    let mut result = block_on(GltfImporter::import(task));
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    assert_eq!(result.cameras.len(), 1);
+
+    result.cameras.remove(0)
+}
+
+#[test]
+fn camera_without_aspect_adopts_surface_aspect() {
+    let camera = import_camera(
+        write_camera_glb("orbital_camera_no_aspect.glb", None),
+        Default::default(),
+    );
+    assert!(camera.auto_aspect);
+
+    let mut store = CameraStore::new();
+    store.set_surface_aspect(4.0 / 3.0);
+    store.handle_event(CameraEvent::Spawn(camera));
+
+    let spawned = store.descriptor_by_label("Camera").unwrap();
+    assert_eq!(spawned.projection.aspect(), 4.0 / 3.0);
+}
+
+#[test]
+fn camera_aspect_is_honored() {
+    let camera = import_camera(
+        write_camera_glb("orbital_camera_aspect.glb", Some(2.0)),
+        Default::default(),
+    );
+    assert!(!camera.auto_aspect);
+    assert_eq!(camera.near, 0.1);
+    assert_eq!(camera.far, 100.0);
+    assert!(!camera.auto_depth_range);
+
+    let mut store = CameraStore::new();
+    store.set_surface_aspect(4.0 / 3.0);
+    store.handle_event(CameraEvent::Spawn(camera));
+    assert_eq!(
+        store
+            .descriptor_by_label("Camera")
+            .unwrap()
+            .projection
+            .aspect(),
+        2.0
+    );
+
+    // Unless told otherwise
+    let camera = import_camera(
+        write_camera_glb("orbital_camera_auto_aspect.glb", Some(2.0)),
+        GltfImportSettings {
+            auto_aspect: true,
+            ..Default::default()
+        },
+    );
+    assert!(camera.auto_aspect);
+}
+
+/// Writes a `.glb` with a directional, point and spot light.
+/// The spot light is unnamed on the node level, but named itself.
+fn write_lights_glb(file_name: &str) -> String {
+    let json = r#"{
+        "asset": { "version": "2.0" },
+        "extensionsUsed": ["KHR_lights_punctual"],
+        "extensions": {
+            "KHR_lights_punctual": {
+                "lights": [
+                    { "name": "SunLight", "type": "directional", "color": [1.0, 0.9, 0.8], "intensity": 683.0 },
+                    { "name": "LampLight", "type": "point", "color": [1.0, 0.0, 0.0], "intensity": 10.0, "range": 5.0 },
+                    {
+                        "name": "SpotLight",
+                        "type": "spot",
+                        "intensity": 20.0,
+                        "spot": { "innerConeAngle": 0.2, "outerConeAngle": 0.6 }
+                    }
+                ]
+            }
+        },
+        "scene": 0,
+        "scenes": [{ "nodes": [0, 1, 2] }],
+        "nodes": [
+            { "name": "Sun", "extensions": { "KHR_lights_punctual": { "light": 0 } } },
+            { "name": "Lamp", "translation": [1.0, 2.0, 3.0], "extensions": { "KHR_lights_punctual": { "light": 1 } } },
+            { "extensions": { "KHR_lights_punctual": { "light": 2 } } }
+        ]
+    }"#;
+
+    write_glb(file_name, json, &[])
+}
+
+fn import_lights(import: GltfImport, settings: GltfImportSettings) -> GltfImportResult {
+    logging::test_init();
+
+    let task = GltfImportTask {
+        file: write_lights_glb("orbital_lights.glb"),
+        import,
+        settings,
+    };
+    let result = block_on(GltfImporter::import(task));
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+
+    result
+}
+
+#[test]
+fn light_import() {
+    let result = import_lights(GltfImport::WholeFile, Default::default());
+    assert_eq!(result.lights.len(), 3);
+
+    let sun = &result.lights[0];
+    assert_eq!(sun.label(), "Sun");
+    assert_eq!(sun.light_type, LightType::Directional { intensity: 683.0 });
+    assert_eq!(sun.color, Vector3::new(1.0, 0.9, 0.8));
+    // glTF lights point along -Z, which maps to -Y in Orbital
+    assert!((sun.direction - Vector3::new(0.0, -1.0, 0.0)).magnitude() < 1e-5);
+
+    let lamp = &result.lights[1];
+    assert_eq!(lamp.label(), "Lamp");
+    assert_eq!(
+        lamp.light_type,
+        LightType::Point {
+            intensity: 10.0,
+            range: Some(5.0)
+        }
+    );
+    assert_eq!(lamp.position, Vector3::new(1.0, 3.0, -2.0));
+
+    let spot = &result.lights[2];
+    assert_eq!(spot.label(), "SpotLight");
+    assert_eq!(
+        spot.light_type,
+        LightType::Spot {
+            intensity: 20.0,
+            inner_cone_angle: 0.2,
+            outer_cone_angle: 0.6,
+            range: None
+        }
+    );
+    assert_eq!(spot.color, Vector3::new(1.0, 1.0, 1.0));
+}
+
+#[test]
+fn light_import_converts_units() {
+    let result = import_lights(
+        GltfImport::WholeFile,
+        GltfImportSettings {
+            convert_light_units: true,
+            ..Default::default()
+        },
+    );
+    assert_eq!(result.lights.len(), 3);
+
+    // 683 lux == 1 W/m²
+    assert!((result.lights[0].intensity() - 1.0).abs() < 1e-5);
+    // 10 cd over the full sphere
+    assert!(
+        (result.lights[1].intensity() - 10.0 * 4.0 * std::f32::consts::PI / 683.0).abs() < 1e-5
+    );
+}
+
+#[test]
+fn light_import_specific_by_light_name() {
+    let result = import_lights(
+        GltfImport::Specific(vec![
+            SpecificGltfImport {
+                import_type: GltfImportType::Light,
+                label: "SpotLight".into(),
+            },
+            SpecificGltfImport {
+                import_type: GltfImportType::Light,
+                label: "Lamp".into(),
+            },
+        ]),
+        Default::default(),
+    );
+
+    let labels = result
+        .lights
+        .iter()
+        .map(|light| light.label())
+        .collect::<Vec<_>>();
+    assert_eq!(labels, vec!["SpotLight", "Lamp"]);
+}
+
+#[test]
+fn orthographic_camera_import() {
+    let json = r#"{
+        "asset": { "version": "2.0" },
+        "scene": 0,
+        "scenes": [{ "nodes": [0] }],
+        "nodes": [{ "name": "Ortho", "camera": 0, "translation": [1.0, 2.0, 3.0] }],
+        "cameras": [{
+            "type": "orthographic",
+            "orthographic": { "xmag": 4.0, "ymag": 2.0, "znear": 0.5, "zfar": 50.0 }
+        }]
+    }"#;
+
+    let camera = import_camera(
+        write_glb("orbital_camera_ortho.glb", json, &[]),
+        Default::default(),
+    );
+
+    assert_eq!(camera.label, "Ortho");
+    assert_eq!(camera.position, Point3::new(1.0, 2.0, 3.0));
+    assert_eq!(
+        camera.projection,
+        Projection::Orthographic {
+            left: -4.0,
+            right: 4.0,
+            bottom: -2.0,
+            top: 2.0,
+        }
+    );
+    assert!(!camera.auto_aspect);
+    assert_eq!(camera.near, 0.5);
+    assert_eq!(camera.far, 50.0);
+
+    // Spawning it must work like any other camera
+    let mut store = CameraStore::new();
+    store.handle_event(CameraEvent::Spawn(camera));
+    assert!(store.descriptor_by_label("Ortho").is_some());
+}
+
+/// Writes a `.glb` file containing an animation named `Move`, moving the
+/// node `Mover` from the origin to `(2, 4, 6)` within one second.
+fn write_animation_glb(file_name: &str, interpolation: &str) -> String {
+    let mut bin = Vec::new();
+    for time in [0.0f32, 1.0] {
+        bin.extend_from_slice(&time.to_le_bytes());
+    }
+    for x in [0.0f32, 0.0, 0.0, 2.0, 4.0, 6.0] {
+        bin.extend_from_slice(&x.to_le_bytes());
+    }
+
+    let json = format!(
+        r#"{{
+            "asset": {{ "version": "2.0" }},
+            "scene": 0,
+            "scenes": [{{ "nodes": [0] }}],
+            "nodes": [{{ "name": "Mover" }}],
+            "animations": [{{
+                "name": "Move",
+                "channels": [{{ "sampler": 0, "target": {{ "node": 0, "path": "translation" }} }}],
+                "samplers": [{{ "input": 0, "output": 1, "interpolation": "{interpolation}" }}]
+            }}],
+            "accessors": [
+                {{ "bufferView": 0, "componentType": 5126, "count": 2, "type": "SCALAR", "min": [0], "max": [1] }},
+                {{ "bufferView": 1, "componentType": 5126, "count": 2, "type": "VEC3" }}
+            ],
+            "bufferViews": [
+                {{ "buffer": 0, "byteOffset": 0, "byteLength": 8 }},
+                {{ "buffer": 0, "byteOffset": 8, "byteLength": 24 }}
+            ],
+            "buffers": [{{ "byteLength": {} }}]
+        }}"#,
+        bin.len()
+    );
+
+    write_glb(file_name, &json, &bin)
+}
+
+#[test]
+fn animation_import() {
+    logging::test_init();
+
+    let task = GltfImportTask {
+        file: write_animation_glb("orbital_animation.glb", "LINEAR"),
+        import: GltfImport::WholeFile,
+        settings: Default::default(),
+    };
+    let result = block_on(GltfImporter::import(task));
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    assert_eq!(result.animations.len(), 1);
+
+    let animation = &result.animations[0];
+    assert_eq!(animation.label, "Move");
+    assert_eq!(animation.duration(), 1.0);
+    assert_eq!(animation.channels[0].target, "Mover");
+    assert_eq!(animation.channels[0].interpolation, Interpolation::Linear);
+
+    let mut transform = Transform::default();
+    animation.apply("Mover", 0.5, &mut transform);
+    assert_eq!(transform.position, Vector3::new(1.0, 2.0, 3.0));
+}
+
+#[test]
+fn animation_import_specific_step() {
+    logging::test_init();
+
+    let task = GltfImportTask {
+        file: write_animation_glb("orbital_animation_step.glb", "STEP"),
+        import: GltfImport::Specific(vec![SpecificGltfImport {
+            import_type: GltfImportType::Animation,
+            label: "Move".into(),
+        }]),
+        settings: Default::default(),
+    };
+    let result = block_on(GltfImporter::import(task));
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    assert_eq!(result.animations.len(), 1);
+
+    match result.animations[0].channels[0].sample(0.5) {
+        Some(AnimationValue::Translation(position)) => {
+            assert_eq!(position, Vector3::new(0.0, 0.0, 0.0))
+        }
+        other => panic!("Expected translation, got {other:?}"),
+    }
+}
+
+fn write_skinned_glb(file_name: &str) -> String {
+    let mut bin = Vec::new();
+    for x in [0.0f32, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0] {
+        bin.extend_from_slice(&x.to_le_bytes());
+    }
+    for joint in [0u16, 0, 0, 0, 0, 1, 0, 0, 1, 0, 0, 0] {
+        bin.extend_from_slice(&joint.to_le_bytes());
+    }
+    for weight in [
+        1.0f32, 0.0, 0.0, 0.0, 0.5, 0.5, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0,
+    ] {
+        bin.extend_from_slice(&weight.to_le_bytes());
+    }
+    // Column-major, the second joint sits one unit up
+    #[rustfmt::skip]
+    let inverse_bind_matrices = [
+        1.0f32, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0,
+        1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, -1.0, 0.0, 1.0,
+    ];
+    for x in inverse_bind_matrices {
+        bin.extend_from_slice(&x.to_le_bytes());
+    }
+    for index in [0u16, 1, 2] {
+        bin.extend_from_slice(&index.to_le_bytes());
+    }
+    bin.extend_from_slice(&[0, 0]);
+
+    let json = format!(
+        r#"{{
+            "asset": {{ "version": "2.0" }},
+            "scene": 0,
+            "scenes": [{{ "nodes": [0, 1] }}],
+            "nodes": [
+                {{ "name": "Skinned", "mesh": 0, "skin": 0, "translation": [5, 0, 0] }},
+                {{ "name": "Root", "children": [2] }},
+                {{ "name": "Tip", "translation": [0, 1, 0] }}
+            ],
+            "meshes": [{{
+                "name": "Skinned",
+                "primitives": [{{
+                    "attributes": {{ "POSITION": 0, "JOINTS_0": 1, "WEIGHTS_0": 2 }},
+                    "indices": 4
+                }}]
+            }}],
+            "skins": [{{ "name": "Arm", "joints": [1, 2], "inverseBindMatrices": 3 }}],
+            "accessors": [
+                {{ "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3", "min": [0, 0, 0], "max": [1, 1, 0] }},
+                {{ "bufferView": 1, "componentType": 5123, "count": 3, "type": "VEC4" }},
+                {{ "bufferView": 2, "componentType": 5126, "count": 3, "type": "VEC4" }},
+                {{ "bufferView": 3, "componentType": 5126, "count": 2, "type": "MAT4" }},
+                {{ "bufferView": 4, "componentType": 5123, "count": 3, "type": "SCALAR" }}
+            ],
+            "bufferViews": [
+                {{ "buffer": 0, "byteOffset": 0, "byteLength": 36 }},
+                {{ "buffer": 0, "byteOffset": 36, "byteLength": 24 }},
+                {{ "buffer": 0, "byteOffset": 60, "byteLength": 48 }},
+                {{ "buffer": 0, "byteOffset": 108, "byteLength": 128 }},
+                {{ "buffer": 0, "byteOffset": 236, "byteLength": 6 }}
+            ],
+            "buffers": [{{ "byteLength": {} }}]
+        }}"#,
+        bin.len()
+    );
+
+    write_glb(file_name, &json, &bin)
+}
+
+#[test]
+fn skinned_model_import() {
+    logging::test_init();
+
+    let task = GltfImportTask {
+        file: write_skinned_glb("orbital_skinned.glb"),
+        import: GltfImport::WholeFile,
+        settings: Default::default(),
+    };
+    let result = block_on(GltfImporter::import(task));
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    assert_eq!(result.models.len(), 1);
+
+    let model = &result.models[0];
+    assert!(model.mesh.is_skinned());
+    assert!(model.materials.iter().all(|x| x.is_skinned()));
+
+    let skin = model.skin.as_ref().expect("Model must be skinned");
+    assert_eq!(skin.label, "Arm");
+    assert_eq!(skin.joints.len(), 2);
+    assert_eq!(skin.joints[0].label, "Root");
+    assert_eq!(skin.joints[0].parent, None);
+    assert_eq!(skin.joints[1].label, "Tip");
+    assert_eq!(skin.joints[1].parent, Some(0));
+    // Bind pose doesn't deform the mesh
+    for matrix in skin.joint_palette() {
+        assert_eq!(matrix, Matrix4::identity());
+    }
+
+    let vertex_skin = model.mesh.vertices[1].skin.expect("Vertex must be skinned");
+    assert_eq!(vertex_skin.joints, [0, 1, 0, 0]);
+    assert_eq!(vertex_skin.weights, [0.5, 0.5, 0.0, 0.0]);
+    // The node transform is ignored for skinned meshes
+    assert_eq!(model.transforms.len(), 1);
+    for transform in model.transforms.values() {
+        assert_eq!(*transform, Transform::default());
+    }
+}
+
+#[test]
+fn far_away_model_is_recentered() {
+    logging::test_init();
+
+    const OFFSET: [f32; 3] = [1000.0, 2000.0, -3000.0];
+
+    let mut bin = Vec::new();
+    for position in [[0.0f32, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]] {
+        for (x, offset) in position.into_iter().zip(OFFSET) {
+            bin.extend_from_slice(&(x + offset).to_le_bytes());
+        }
+    }
+    for index in [0u16, 1, 2] {
+        bin.extend_from_slice(&index.to_le_bytes());
+    }
+    bin.extend_from_slice(&[0, 0]);
+
+    let json = format!(
+        r#"{{
+            "asset": {{ "version": "2.0" }},
+            "scene": 0,
+            "scenes": [{{ "nodes": [0] }}],
+            "nodes": [{{ "name": "Far", "mesh": 0 }}],
+            "meshes": [{{ "primitives": [{{ "attributes": {{ "POSITION": 0 }}, "indices": 1 }}] }}],
+            "accessors": [
+                {{ "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3", "min": [1000, 2000, -3000], "max": [1001, 2001, -3000] }},
+                {{ "bufferView": 1, "componentType": 5123, "count": 3, "type": "SCALAR" }}
+            ],
+            "bufferViews": [
+                {{ "buffer": 0, "byteOffset": 0, "byteLength": 36 }},
+                {{ "buffer": 0, "byteOffset": 36, "byteLength": 6 }}
+            ],
+            "buffers": [{{ "byteLength": {} }}]
+        }}"#,
+        bin.len()
+    );
+
+    let task = GltfImportTask {
+        file: write_glb("orbital_far_away.glb", &json, &bin),
+        import: GltfImport::WholeFile,
+        settings: Default::default(),
+    };
+    let result = block_on(GltfImporter::import(task));
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+
+    let model = &result.models[0];
+    let transform = *model.transforms.values().next().unwrap();
+
+    // Vertices are centered around the origin
+    let bounding_box = model.mesh.find_bounding_box();
+    assert_eq!(
+        bounding_box.min.to_vec() + bounding_box.max.to_vec(),
+        Vector3::zero()
+    );
+
+    // The transform places them where they were authored
+    let recentered = Instance::from(&transform).make_model_space_matrix();
+    let original = Instance::from(&Transform {
+        position: Vector3::zero(),
+        ..transform
+    })
+    .make_model_space_matrix();
+    let authored = [
+        [1000.0, 2000.0, -3000.0],
+        [1001.0, 2000.0, -3000.0],
+        [1000.0, 2001.0, -3000.0],
+    ]
+    .map(|[x, y, z]| Vector3::new(x, z, -y));
+    for (vertex, authored) in model.mesh.vertices.iter().zip(authored) {
+        assert_eq!(
+            recentered * vertex.position.extend(1.0),
+            original * authored.extend(1.0)
+        );
+    }
+}
+
+#[test]
+fn far_away_animated_model_is_not_recentered() {
+    logging::test_init();
+
+    const OFFSET: [f32; 3] = [1000.0, 2000.0, -3000.0];
+
+    let mut bin = Vec::new();
+    for position in [[0.0f32, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]] {
+        for (x, offset) in position.into_iter().zip(OFFSET) {
+            bin.extend_from_slice(&(x + offset).to_le_bytes());
+        }
+    }
+    for index in [0u16, 1, 2] {
+        bin.extend_from_slice(&index.to_le_bytes());
+    }
+    bin.extend_from_slice(&[0, 0]);
+    for time in [0.0f32, 1.0] {
+        bin.extend_from_slice(&time.to_le_bytes());
+    }
+    for x in [0.0f32, 0.0, 0.0, 2.0, 4.0, 6.0] {
+        bin.extend_from_slice(&x.to_le_bytes());
+    }
+
+    let json = format!(
+        r#"{{
+            "asset": {{ "version": "2.0" }},
+            "scene": 0,
+            "scenes": [{{ "nodes": [0] }}],
+            "nodes": [{{ "name": "Far", "mesh": 0 }}],
+            "meshes": [{{ "primitives": [{{ "attributes": {{ "POSITION": 0 }}, "indices": 1 }}] }}],
+            "animations": [{{
+                "name": "Move",
+                "channels": [{{ "sampler": 0, "target": {{ "node": 0, "path": "translation" }} }}],
+                "samplers": [{{ "input": 2, "output": 3 }}]
+            }}],
+            "accessors": [
+                {{ "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3", "min": [1000, 2000, -3000], "max": [1001, 2001, -3000] }},
+                {{ "bufferView": 1, "componentType": 5123, "count": 3, "type": "SCALAR" }},
+                {{ "bufferView": 2, "componentType": 5126, "count": 2, "type": "SCALAR", "min": [0], "max": [1] }},
+                {{ "bufferView": 3, "componentType": 5126, "count": 2, "type": "VEC3" }}
+            ],
+            "bufferViews": [
+                {{ "buffer": 0, "byteOffset": 0, "byteLength": 36 }},
+                {{ "buffer": 0, "byteOffset": 36, "byteLength": 6 }},
+                {{ "buffer": 0, "byteOffset": 44, "byteLength": 8 }},
+                {{ "buffer": 0, "byteOffset": 52, "byteLength": 24 }}
+            ],
+            "buffers": [{{ "byteLength": {} }}]
+        }}"#,
+        bin.len()
+    );
+
+    let task = GltfImportTask {
+        file: write_glb("orbital_far_away_animated.glb", &json, &bin),
+        import: GltfImport::WholeFile,
+        settings: Default::default(),
+    };
+    let result = block_on(GltfImporter::import(task));
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+
+    // The animation replaces the position, thus nothing may depend on it
+    let model = &result.models[0];
+    let transform = *model.transforms.values().next().unwrap();
+    assert_eq!(transform.position, Vector3::zero());
+
+    let bounding_box = model.mesh.find_bounding_box();
+    assert_ne!(
+        bounding_box.min.to_vec() + bounding_box.max.to_vec(),
+        Vector3::zero()
+    );
+}
+
+#[test]
+fn non_indexed_primitive_is_reported() {
+    logging::test_init();
+
+    // Second primitive lacks indices, the first one is still imported
+    let json = format!(
+        r#"{{
+            "asset": {{ "version": "2.0" }},
+            "scene": 0,
+            "scenes": [{{ "nodes": [0] }}],
+            "nodes": [{{ "name": "Triangle", "mesh": 0 }}],
+            "meshes": [{{ "primitives": [
+                {{ "attributes": {{ "POSITION": 0 }}, "indices": 1 }},
+                {{ "attributes": {{ "POSITION": 0 }} }}
+            ] }}],
+            {TRIANGLE_ACCESSORS},
+            "bufferViews": [
+                {{ "buffer": 0, "byteOffset": 0, "byteLength": 36 }},
+                {{ "buffer": 0, "byteOffset": 36, "byteLength": 6 }}
+            ],
+            "buffers": [{{ "byteLength": 44 }}]
+        }}"#
+    );
+    let task = GltfImportTask {
+        file: write_glb("orbital_non_indexed.glb", &json, &triangle_bin()),
+        import: GltfImport::WholeFile,
+        settings: GltfImportSettings::default(),
+    };
+
+    let result = block_on(GltfImporter::import(task));
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    assert_eq!(result.models.len(), 1);
+    assert_eq!(
+        result.warnings,
+        vec![GltfImportWarning {
+            model: "Triangle".to_string(),
+            reason: GltfImportWarningReason::MissingIndices { primitive: 1 },
+        }]
+    );
+}
+
+#[test]
+fn label_pattern_matching() {
+    let pattern = GltfLabel::Pattern("Level_*".to_string());
+    assert!(pattern.matches("Level_1"));
+    assert!(pattern.matches("Level_"));
+    assert!(pattern.matches("Level_Forest_2"));
+    assert!(!pattern.matches("level_1"));
+    assert!(!pattern.matches("Menu_Level_1"));
+
+    let pattern = GltfLabel::Pattern("Enemy_?".to_string());
+    assert!(pattern.matches("Enemy_1"));
+    assert!(!pattern.matches("Enemy_"));
+    assert!(!pattern.matches("Enemy_10"));
+
+    let pattern = GltfLabel::Pattern("*_Floor*".to_string());
+    assert!(pattern.matches("Level_1_Floor"));
+    assert!(pattern.matches("_Floor_Tile"));
+    assert!(!pattern.matches("Floor"));
+
+    // Without wildcards, patterns match exactly
+    assert!(GltfLabel::Pattern("Enemy".to_string()).matches("Enemy"));
+    assert!(!GltfLabel::Exact("Enemy_*".to_string()).matches("Enemy_1"));
+    assert!(!pattern.matches_option(None));
+}
+
+/// Writes a `.glb` file with the scenes `Level_1`, `Level_2` and `Menu`,
+/// each containing differently named triangles.
+fn write_multi_scene_glb(file_name: &str) -> String {
+    let json = format!(
+        r#"{{
+            "asset": {{ "version": "2.0" }},
+            "scene": 0,
+            "scenes": [
+                {{ "name": "Level_1", "nodes": [0, 3] }},
+                {{ "name": "Level_2", "nodes": [1, 4, 5, 6] }},
+                {{ "name": "Menu", "nodes": [2] }}
+            ],
+            "nodes": [
+                {{ "name": "Level_1_Floor", "mesh": 0 }},
+                {{ "name": "Level_2_Floor", "mesh": 0 }},
+                {{ "name": "Menu_Background", "mesh": 0 }},
+                {{ "name": "Enemy_1", "mesh": 0 }},
+                {{ "name": "Enemy_2", "mesh": 0 }},
+                {{ "name": "Enemy_10", "mesh": 0 }},
+                {{ "name": "enemy_3", "mesh": 0 }}
+            ],
+            "meshes": [{{ "primitives": [{{ "attributes": {{ "POSITION": 0 }}, "indices": 1 }}] }}],
+            {TRIANGLE_ACCESSORS},
+            "bufferViews": [
+                {{ "buffer": 0, "byteOffset": 0, "byteLength": 36 }},
+                {{ "buffer": 0, "byteOffset": 36, "byteLength": 6 }}
+            ],
+            "buffers": [{{ "byteLength": 44 }}]
+        }}"#
+    );
+
+    write_glb(file_name, &json, &triangle_bin())
+}
+
+fn import_labels(file: &str, import_type: GltfImportType, label: GltfLabel) -> GltfImportResult {
+    block_on(GltfImporter::import(GltfImportTask {
+        file: file.to_string(),
+        import: GltfImport::Specific(vec![SpecificGltfImport { import_type, label }]),
+        settings: GltfImportSettings {
+            geometry_only: true,
+            ..Default::default()
+        },
+    }))
+}
+
+fn model_labels(result: &GltfImportResult) -> Vec<&str> {
+    result
+        .models
+        .iter()
+        .map(|model| model.label.as_str())
+        .collect()
+}
+
+#[test]
+fn pattern_selects_scenes() {
+    logging::test_init();
+
+    let file = write_multi_scene_glb("orbital_multi_scene_scenes.glb");
+    let result = import_labels(
+        &file,
+        GltfImportType::Scene,
+        GltfLabel::Pattern("Level_*".to_string()),
+    );
+
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    assert_eq!(
+        model_labels(&result),
+        vec![
+            "Level_1_Floor",
+            "Enemy_1",
+            "Level_2_Floor",
+            "Enemy_2",
+            "Enemy_10",
+            "enemy_3"
+        ]
+    );
+}
+
+#[test]
+fn pattern_selects_models() {
+    logging::test_init();
+
+    let file = write_multi_scene_glb("orbital_multi_scene_models.glb");
+
+    let result = import_labels(
+        &file,
+        GltfImportType::Model,
+        GltfLabel::Pattern("Enemy_?".to_string()),
+    );
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    assert_eq!(model_labels(&result), vec!["Enemy_1", "Enemy_2"]);
+
+    let result = import_labels(
+        &file,
+        GltfImportType::Model,
+        GltfLabel::Pattern("*_Floor".to_string()),
+    );
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    assert_eq!(
+        model_labels(&result),
+        vec!["Level_1_Floor", "Level_2_Floor"]
+    );
+
+    // A pattern without any match is reported
+    let result = import_labels(
+        &file,
+        GltfImportType::Model,
+        GltfLabel::Pattern("Boss_*".to_string()),
+    );
+    assert!(result.models.is_empty());
+    assert_eq!(result.errors.len(), 1);
+}
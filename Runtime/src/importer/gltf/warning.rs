@@ -0,0 +1,36 @@
+use std::fmt::{Display, Formatter};
+
+/// A non-fatal issue found during a glTF import.
+/// Unlike errors, the import continues, but the affected part is skipped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GltfImportWarning {
+    /// Label of the affected model, i.e. the name of its node.
+    pub model: String,
+    pub reason: GltfImportWarningReason,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GltfImportWarningReason {
+    /// A mesh primitive without positions was skipped.
+    MissingPositions { primitive: usize },
+    /// A mesh primitive without indices was skipped.
+    /// Non-indexed geometry isn't supported.
+    MissingIndices { primitive: usize },
+}
+
+impl Display for GltfImportWarning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.reason {
+            GltfImportWarningReason::MissingPositions { primitive } => write!(
+                f,
+                "Skipped primitive #{primitive} of model '{}': No positions",
+                self.model
+            ),
+            GltfImportWarningReason::MissingIndices { primitive } => write!(
+                f,
+                "Skipped primitive #{primitive} of model '{}': No indices",
+                self.model
+            ),
+        }
+    }
+}
@@ -2,42 +2,124 @@
 //!
 //! The importer module handles asynchronous asset loading and processing for the Orbital engine.
 //! It provides a system for importing various asset formats (primarily GLTF) and converting
-//! them into engine resources like models and cameras.
+//! them into engine resources like models, cameras and lights.
 //!
 //! ## Key Components
 //!
 //! - **Importer**: Manages the import task queue and runs import operations asynchronously
 //! - **ImportTask**: Represents different types of import operations that can be queued
-//! - **ImportResult**: Contains the results of an import operation (models, cameras, etc.)
+//! - **ImportResult**: Contains the results of an import operation (models, cameras, animations, etc.)
+//! - **ImportProgress**: How many of the registered tasks are finished, e.g. for loading screens
 //! - **GLTF Import**: Specialized support for GLTF format assets with materials and scenes
+//! - **OBJ Import**: Wavefront OBJ geometry with materials of `.mtl` libraries
 //!
 //! ## Asynchronous Processing
 //!
 //! The importer processes tasks asynchronously with configurable parallelism, allowing
 //! multiple assets to be loaded simultaneously without blocking the main application thread.
+//! If blocking is fine, e.g. in tests, tools or at startup, queued tasks can also be run
+//! on the calling thread via [`Importer::import_blocking`].
 
 use crate::{
-    importer::gltf::{GltfImport, GltfImportTask, GltfImporter},
-    resources::{CameraDescriptor, ModelDescriptor},
+    importer::gltf::{
+        GltfImport, GltfImportSettings, GltfImportTask, GltfImportWarning, GltfImporter,
+    },
+    importer::obj::{ObjImportWarning, ObjImporter},
+    resources::{AnimationDescriptor, CameraDescriptor, LightDescriptor, ModelDescriptor},
 };
 use async_std::task;
-use futures::stream::{FuturesUnordered, StreamExt};
+use futures::{
+    stream::{FuturesUnordered, StreamExt},
+    FutureExt,
+};
+use log::error;
+use std::{
+    error::Error,
+    fmt::{Display, Formatter},
+};
 
 pub mod gltf;
+pub mod obj;
 
 /// Represents different types of import operations that can be queued.
-/// Currently supports GLTF format assets, but designed to support additional formats.
+/// Currently supports GLTF and Wavefront OBJ format assets.
 #[derive(Debug)]
 pub enum ImportTask {
-    Gltf { file_path: String, task: GltfImport },
+    Gltf {
+        file_path: String,
+        task: GltfImport,
+        settings: GltfImportSettings,
+    },
+    /// Imports all models of an OBJ file, see [ObjImporter].
+    Obj { file_path: String },
 }
 
-/// Contains the results of an import operation, including any models and cameras
-/// that were created during the import process.
+/// Contains the results of an import operation, including any models, cameras,
+/// lights and animations that were created during the import process.
 #[derive(Default)]
 pub struct ImportResult {
     pub models: Vec<ModelDescriptor>,
     pub cameras: Vec<CameraDescriptor>,
+    pub lights: Vec<LightDescriptor>,
+    pub animations: Vec<AnimationDescriptor>,
+    /// Parts of the import (or the whole file) that failed.
+    pub errors: Vec<ImportError>,
+    /// Non-fatal issues of the import, the affected parts got skipped.
+    pub warnings: Vec<ImportWarning>,
+}
+
+/// A failure of an import operation.
+/// Only the message is kept, as the underlying errors can't be sent
+/// between threads.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportError {
+    Gltf { file_path: String, message: String },
+    Obj { file_path: String, message: String },
+}
+
+impl Display for ImportError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImportError::Gltf { file_path, message } => {
+                write!(f, "glTF '{file_path}': {message}")
+            }
+            ImportError::Obj { file_path, message } => write!(f, "OBJ '{file_path}': {message}"),
+        }
+    }
+}
+
+impl Error for ImportError {}
+
+/// A non-fatal issue of an import operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportWarning {
+    Gltf(GltfImportWarning),
+    Obj(ObjImportWarning),
+}
+
+impl Display for ImportWarning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImportWarning::Gltf(warning) => write!(f, "glTF: {warning}"),
+            ImportWarning::Obj(warning) => write!(f, "OBJ: {warning}"),
+        }
+    }
+}
+
+/// Progress of the tasks registered with an [Importer] since it was last
+/// idle, e.g. for loading screens.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ImportProgress {
+    pub finished: usize,
+    pub total: usize,
+}
+
+impl ImportProgress {
+    /// Whether all tasks are finished.
+    /// Also true if there never were any tasks.
+    pub fn is_done(&self) -> bool {
+        self.finished >= self.total
+    }
 }
 
 /// The main importer that manages the import task queue and runs import operations
@@ -46,6 +128,7 @@ pub struct Importer {
     queued_tasks: Vec<ImportTask>,
     running_tasks: FuturesUnordered<task::JoinHandle<ImportResult>>,
     allowed_parallel_tasks: u8,
+    progress: ImportProgress,
 }
 
 impl Importer {
@@ -54,21 +137,55 @@ impl Importer {
             queued_tasks: Vec::new(),
             running_tasks: FuturesUnordered::new(),
             allowed_parallel_tasks,
+            progress: ImportProgress::default(),
         }
     }
 
     pub fn register_task(&mut self, task: ImportTask) {
+        // Start counting anew once everything registered earlier is done
+        if self.progress.is_done() {
+            self.progress = ImportProgress::default();
+        }
+        self.progress.total += 1;
+
         self.queued_tasks.push(task);
     }
 
+    /// Maximum number of tasks running at the same time.
+    pub fn allowed_parallel_tasks(&self) -> u8 {
+        self.allowed_parallel_tasks
+    }
+
+    /// Changes the maximum number of tasks running at the same time.
+    /// Already running tasks aren't affected, but no new tasks are started
+    /// until the running ones drop below the new limit.
+    pub fn set_allowed_parallel_tasks(&mut self, allowed_parallel_tasks: u8) {
+        self.allowed_parallel_tasks = allowed_parallel_tasks;
+    }
+
+    /// Number of tasks currently running.
+    pub fn running_task_count(&self) -> usize {
+        self.running_tasks.len()
+    }
+
+    pub fn progress(&self) -> ImportProgress {
+        self.progress
+    }
+
+    /// Returns the results of all tasks finished since the last call and
+    /// starts queued tasks, as far as [Self::allowed_parallel_tasks] permits.
+    ///
+    /// Never waits for running tasks, thus is meant to be called
+    /// repeatedly, e.g. once per frame.
     pub async fn update(&mut self) -> Vec<ImportResult> {
         let mut results = Vec::new();
 
-        // Poll the set of running tasks to drain any that have completed.
-        // This is non-blocking and will only process futures that are already ready.
-        while let Some(result) = self.running_tasks.next().await {
+        // Drain the tasks that already completed, without waiting for the
+        // ones still running.
+        while let Some(Some(result)) = self.running_tasks.next().now_or_never() {
             results.push(result);
         }
+        self.progress.finished += results.len();
 
         // Check if we can start new tasks.
         while self.running_tasks.len() < self.allowed_parallel_tasks as usize
@@ -76,26 +193,89 @@ impl Importer {
         {
             let task_desc = self.queued_tasks.remove(0);
 
-            let handle = task::spawn(async move {
-                match task_desc {
-                    ImportTask::Gltf { file_path, task } => {
-                        let gltf_result = GltfImporter::import(GltfImportTask {
-                            file: file_path,
-                            import: task,
-                        })
-                        .await;
-
-                        ImportResult {
-                            models: gltf_result.models,
-                            cameras: gltf_result.cameras,
-                        }
-                    }
-                }
-            });
-
+            let handle = task::spawn(Self::run_task(task_desc));
             self.running_tasks.push(handle);
         }
 
         results
     }
+
+    /// Runs all queued tasks one after another on the calling thread and
+    /// returns their results once all are done.
+    ///
+    /// Unlike [Self::update], no polling is needed, which is handy for tests,
+    /// tools or loading everything needed at startup.
+    pub fn import_blocking(&mut self) -> Vec<ImportResult> {
+        let results: Vec<_> = self
+            .queued_tasks
+            .drain(..)
+            .map(|task_desc| task::block_on(Self::run_task(task_desc)))
+            .collect();
+        self.progress.finished += results.len();
+
+        results
+    }
+
+    /// Does the actual work of an [ImportTask].
+    /// Shared by [Self::update] and [Self::import_blocking].
+    async fn run_task(task_desc: ImportTask) -> ImportResult {
+        match task_desc {
+            ImportTask::Gltf {
+                file_path,
+                task,
+                settings,
+            } => {
+                let gltf_result = GltfImporter::import(GltfImportTask {
+                    file: file_path.clone(),
+                    import: task,
+                    settings,
+                })
+                .await;
+
+                ImportResult {
+                    models: gltf_result.models,
+                    cameras: gltf_result.cameras,
+                    lights: gltf_result.lights,
+                    animations: gltf_result.animations,
+                    errors: gltf_result
+                        .errors
+                        .into_iter()
+                        .map(|e| {
+                            error!("Failed importing from glTF file '{file_path}': {e}");
+                            ImportError::Gltf {
+                                file_path: file_path.clone(),
+                                message: e.to_string(),
+                            }
+                        })
+                        .collect(),
+                    warnings: gltf_result
+                        .warnings
+                        .into_iter()
+                        .map(ImportWarning::Gltf)
+                        .collect(),
+                }
+            }
+            ImportTask::Obj { file_path } => match ObjImporter::import(&file_path) {
+                Ok(obj_result) => ImportResult {
+                    models: obj_result.models,
+                    warnings: obj_result
+                        .warnings
+                        .into_iter()
+                        .map(ImportWarning::Obj)
+                        .collect(),
+                    ..Default::default()
+                },
+                Err(e) => {
+                    error!("Failed importing OBJ file '{file_path}': {e}");
+                    ImportResult {
+                        errors: vec![ImportError::Obj {
+                            file_path,
+                            message: e.to_string(),
+                        }],
+                        ..Default::default()
+                    }
+                }
+            },
+        }
+    }
 }
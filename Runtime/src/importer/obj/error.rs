@@ -0,0 +1,24 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug)]
+pub enum ObjError {
+    IOError(std::io::Error),
+    /// A statement couldn't be parsed.
+    /// Lines are counted starting at 1.
+    Parse {
+        line: usize,
+        message: String,
+    },
+}
+
+impl Display for ObjError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ObjError::IOError(error) => write!(f, "IO error: {error}"),
+            ObjError::Parse { line, message } => write!(f, "Line {line}: {message}"),
+        }
+    }
+}
+
+impl Error for ObjError {}
@@ -0,0 +1,292 @@
+use crate::importer::obj::{ObjError, ObjImportWarning};
+use crate::resources::{
+    AlphaMode, MaterialDescriptor, NormalMapSpace, PBRMaterialDescriptor, TextureDescriptor,
+};
+use cgmath::{Vector3, Vector4};
+use hashbrown::HashMap;
+use log::warn;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use wgpu::Color;
+
+/// A material as defined by a `.mtl` material library.
+/// Only the parts mapping onto PBR materials are kept.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjMaterial {
+    /// `Kd`
+    pub diffuse: Vector3<f32>,
+    /// `map_Kd`
+    pub diffuse_map: Option<PathBuf>,
+    /// `d`, or `1 - Tr`
+    pub dissolve: f32,
+    /// `Ke`
+    pub emissive: Vector3<f32>,
+    /// `map_Ke`
+    pub emissive_map: Option<PathBuf>,
+    /// `norm`, `map_Bump` or `bump`
+    pub normal_map: Option<PathBuf>,
+    /// `Pr`, or derived from the specular exponent `Ns`
+    pub roughness: f32,
+    /// `Pm`
+    pub metallic: f32,
+}
+
+impl Default for ObjMaterial {
+    fn default() -> Self {
+        Self {
+            diffuse: Vector3::new(0.8, 0.8, 0.8),
+            diffuse_map: None,
+            dissolve: 1.0,
+            emissive: Vector3::new(0.0, 0.0, 0.0),
+            emissive_map: None,
+            normal_map: None,
+            roughness: 1.0,
+            metallic: 0.0,
+        }
+    }
+}
+
+/// Textures loaded so far, keyed by path and whether they are sRGB.
+/// Materials referencing the same file share the texture.
+/// Failed loads are kept too, so they are only attempted and reported once.
+pub type ObjTextureCache = HashMap<(PathBuf, bool), Option<Arc<TextureDescriptor>>>;
+
+impl ObjMaterial {
+    /// Parses a `.mtl` material library.
+    /// Texture paths are resolved relative to `base`.
+    /// Unsupported statements are ignored.
+    pub fn parse_library(source: &str, base: &Path) -> Result<HashMap<String, Self>, ObjError> {
+        let mut materials = HashMap::new();
+        let mut current: Option<(String, Self)> = None;
+        // An explicit PBR roughness takes precedence over the specular exponent
+        let mut explicit_roughness = false;
+
+        for (i, line) in source.lines().enumerate() {
+            let line_number = i + 1;
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let mut tokens = line.split_whitespace();
+            let Some(keyword) = tokens.next() else {
+                continue;
+            };
+            let args: Vec<&str> = tokens.collect();
+
+            if keyword == "newmtl" {
+                if let Some((name, material)) = current.take() {
+                    materials.insert(name, material);
+                }
+                current = Some((args.join(" "), Self::default()));
+                explicit_roughness = false;
+                continue;
+            }
+
+            let Some((_, material)) = current.as_mut() else {
+                return Err(ObjError::Parse {
+                    line: line_number,
+                    message: format!("'{keyword}' outside of any material"),
+                });
+            };
+
+            match keyword {
+                "Kd" => material.diffuse = parse_color(&args, line_number)?,
+                "Ke" => material.emissive = parse_color(&args, line_number)?,
+                "d" => material.dissolve = parse_float(&args, line_number)?,
+                "Tr" => material.dissolve = 1.0 - parse_float(&args, line_number)?,
+                "Ns" if !explicit_roughness => {
+                    // Approximation of Blinn-Phong exponents as GGX roughness
+                    let exponent = parse_float(&args, line_number)?.max(0.0);
+                    material.roughness = (2.0 / (exponent + 2.0)).sqrt();
+                }
+                "Pr" => {
+                    material.roughness = parse_float(&args, line_number)?;
+                    explicit_roughness = true;
+                }
+                "Pm" => material.metallic = parse_float(&args, line_number)?,
+                "map_Kd" => material.diffuse_map = Some(parse_map(&args, base, line_number)?),
+                "map_Ke" => material.emissive_map = Some(parse_map(&args, base, line_number)?),
+                "norm" | "map_Bump" | "map_bump" | "bump" => {
+                    material.normal_map = Some(parse_map(&args, base, line_number)?)
+                }
+                _ => (),
+            }
+        }
+
+        if let Some((name, material)) = current {
+            materials.insert(name, material);
+        }
+
+        Ok(materials)
+    }
+
+    /// Turns the material into an Orbital [`MaterialDescriptor`].
+    /// Textures failing to load are reported as warnings, in which case
+    /// the material's factor is used on its own.
+    pub fn to_descriptor(
+        &self,
+        name: Option<&str>,
+        textures: &mut ObjTextureCache,
+        warnings: &mut Vec<ObjImportWarning>,
+    ) -> MaterialDescriptor {
+        let alpha = self.dissolve.clamp(0.0, 1.0);
+
+        let normal = self
+            .normal_map
+            .as_ref()
+            .and_then(|path| load_texture(path, false, textures, warnings))
+            .unwrap_or_else(|| {
+                // (0.5, 0.5, 1.0) maps to (0, 0, 1) in tangent space
+                Arc::new(TextureDescriptor::uniform_rgba_value(
+                    0.5, 0.5, 1.0, 1.0, false,
+                ))
+            });
+
+        // With a texture, the color acts as a factor.
+        // Otherwise, the color acts as a global texture.
+        let (albedo, albedo_factor) = match self
+            .diffuse_map
+            .as_ref()
+            .and_then(|path| load_texture(path, true, textures, warnings))
+        {
+            Some(texture) => (texture, self.diffuse.extend(alpha)),
+            None => (
+                Arc::new(TextureDescriptor::uniform_rgba_value(
+                    self.diffuse.x as f64,
+                    self.diffuse.y as f64,
+                    self.diffuse.z as f64,
+                    alpha as f64,
+                    true,
+                )),
+                Vector4::new(1.0, 1.0, 1.0, 1.0),
+            ),
+        };
+
+        let (emissive, emissive_factor) = match self
+            .emissive_map
+            .as_ref()
+            .and_then(|path| load_texture(path, true, textures, warnings))
+        {
+            Some(texture) => (texture, self.emissive),
+            None => (
+                Arc::new(TextureDescriptor::uniform_rgba_color(
+                    Color {
+                        r: self.emissive.x as f64,
+                        g: self.emissive.y as f64,
+                        b: self.emissive.z as f64,
+                        a: 1.0,
+                    },
+                    true,
+                )),
+                Vector3::new(1.0, 1.0, 1.0),
+            ),
+        };
+
+        PBRMaterialDescriptor {
+            name: name.map(|x| x.to_string()),
+            normal,
+            normal_map_space: NormalMapSpace::TangentSpace,
+            normal_scale: 1.0,
+            albedo,
+            albedo_factor,
+            metallic: Arc::new(TextureDescriptor::uniform_rgba_value(
+                self.metallic as f64,
+                0.0,
+                0.0,
+                1.0,
+                true,
+            )),
+            metallic_factor: 1.0,
+            roughness: Arc::new(TextureDescriptor::uniform_rgba_value(
+                self.roughness as f64,
+                0.0,
+                0.0,
+                1.0,
+                true,
+            )),
+            roughness_factor: 1.0,
+            occlusion: Arc::new(TextureDescriptor::uniform_rgba_white(false)),
+            occlusion_strength: 1.0,
+            emissive,
+            emissive_factor,
+            emissive_strength: 1.0,
+            alpha_mode: if alpha < 1.0 {
+                AlphaMode::Blend
+            } else {
+                AlphaMode::Opaque
+            },
+            double_sided: false,
+            ..Default::default()
+        }
+        .into()
+    }
+
+    /// Whether the material needs to be drawn after opaque geometry.
+    pub fn is_transparent(&self) -> bool {
+        self.dissolve < 1.0
+    }
+}
+
+fn load_texture(
+    path: &Path,
+    srgb: bool,
+    textures: &mut ObjTextureCache,
+    warnings: &mut Vec<ObjImportWarning>,
+) -> Option<Arc<TextureDescriptor>> {
+    textures
+        .entry((path.to_path_buf(), srgb))
+        .or_insert_with(|| {
+            let texture = if srgb {
+                TextureDescriptor::from_path(path)
+            } else {
+                TextureDescriptor::from_path_linear(path)
+            };
+
+            match texture {
+                Ok(texture) => Some(Arc::new(texture)),
+                Err(e) => {
+                    warn!("Failed loading OBJ texture '{}': {e:?}", path.display());
+                    warnings.push(ObjImportWarning::Texture {
+                        path: path.display().to_string(),
+                        reason: format!("{e:?}"),
+                    });
+                    None
+                }
+            }
+        })
+        .clone()
+}
+
+pub(super) fn parse_float(args: &[&str], line: usize) -> Result<f32, ObjError> {
+    let value = args.first().ok_or_else(|| ObjError::Parse {
+        line,
+        message: "Missing value".into(),
+    })?;
+
+    value.parse().map_err(|_| ObjError::Parse {
+        line,
+        message: format!("Invalid number '{value}'"),
+    })
+}
+
+fn parse_color(args: &[&str], line: usize) -> Result<Vector3<f32>, ObjError> {
+    let r = parse_float(args, line)?;
+    // A single value is a gray scale color
+    if args.len() < 3 {
+        return Ok(Vector3::new(r, r, r));
+    }
+
+    Ok(Vector3::new(
+        r,
+        parse_float(&args[1..], line)?,
+        parse_float(&args[2..], line)?,
+    ))
+}
+
+/// Texture statements may carry options before the path, e.g. `-bm 1.0`,
+/// thus the last argument is taken as path.
+fn parse_map(args: &[&str], base: &Path, line: usize) -> Result<PathBuf, ObjError> {
+    args.last()
+        .map(|path| base.join(path))
+        .ok_or_else(|| ObjError::Parse {
+            line,
+            message: "Missing texture path".into(),
+        })
+}
@@ -0,0 +1,372 @@
+//! # OBJ Importer
+//!
+//! Imports Wavefront OBJ files, including materials of referenced `.mtl`
+//! material libraries.
+//!
+//! Every combination of group (`o`/`g`) and material (`usemtl`) becomes its
+//! own [`ModelDescriptor`].
+//! Polygonal faces are triangulated and normals are computed if the file
+//! doesn't provide them.
+//!
+//! OBJ files are usually Y-up, thus positions and normals get converted into
+//! Orbital's Z-up space, just like glTF imports.
+
+use crate::asset;
+use crate::resources::{MeshDescriptor, ModelDescriptor, RenderLayer, Transform, Vertex};
+use cgmath::{InnerSpace, Vector2, Vector3, Zero};
+use hashbrown::HashMap;
+use log::{debug, warn};
+use std::path::Path;
+use std::sync::Arc;
+use ulid::Ulid;
+
+mod error;
+pub use error::*;
+
+mod warning;
+pub use warning::*;
+
+mod result;
+pub use result::*;
+
+mod material;
+use material::*;
+
+#[cfg(test)]
+mod tests;
+
+/// A face corner, indexing into positions, UVs and normals.
+type Corner = (usize, Option<usize>, Option<usize>);
+
+/// Triangles sharing a group and material.
+struct Submesh {
+    group: String,
+    material: Option<String>,
+    /// Three corners per triangle.
+    corners: Vec<Corner>,
+}
+
+pub struct ObjImporter;
+
+impl ObjImporter {
+    /// Imports an OBJ file.
+    /// Material libraries and textures are resolved relative to the file.
+    pub fn import(file: &str) -> Result<ObjImportResult, ObjError> {
+        let source = asset::read_to_string(file).map_err(ObjError::IOError)?;
+
+        let path = Path::new(file);
+        let base = path.parent().unwrap_or(Path::new(""));
+        let name = path
+            .file_stem()
+            .map(|x| x.to_string_lossy().to_string())
+            .unwrap_or_else(|| "OBJ".into());
+
+        Self::import_source(&source, base, &name)
+    }
+
+    /// Same as [`Self::import`], but parses already loaded OBJ source.
+    /// Material libraries and textures are resolved relative to `base`.
+    /// Faces outside of any group are labeled `name`.
+    pub fn import_source(
+        source: &str,
+        base: &Path,
+        name: &str,
+    ) -> Result<ObjImportResult, ObjError> {
+        let mut positions: Vec<Vector3<f32>> = Vec::new();
+        let mut uvs: Vec<Vector2<f32>> = Vec::new();
+        let mut normals: Vec<Vector3<f32>> = Vec::new();
+        let mut materials = HashMap::new();
+        let mut submeshes: Vec<Submesh> = Vec::new();
+        let mut warnings = Vec::new();
+
+        let mut group = name.to_string();
+        let mut material: Option<String> = None;
+
+        for (i, line) in source.lines().enumerate() {
+            let line_number = i + 1;
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let mut tokens = line.split_whitespace();
+            let Some(keyword) = tokens.next() else {
+                continue;
+            };
+            let args: Vec<&str> = tokens.collect();
+
+            match keyword {
+                "v" => positions.push(Self::parse_vector3(&args, line_number)?),
+                "vn" => normals.push(Self::parse_vector3(&args, line_number)?),
+                "vt" => {
+                    let u = parse_float(&args, line_number)?;
+                    let v = if args.len() > 1 {
+                        parse_float(&args[1..], line_number)?
+                    } else {
+                        0.0
+                    };
+                    // OBJ UVs start at the bottom, Orbital's at the top
+                    uvs.push(Vector2::new(u, 1.0 - v));
+                }
+                "f" => {
+                    if args.len() < 3 {
+                        return Err(ObjError::Parse {
+                            line: line_number,
+                            message: "Faces need at least three vertices".into(),
+                        });
+                    }
+
+                    let corners = args
+                        .iter()
+                        .map(|x| {
+                            Self::parse_corner(
+                                x,
+                                [positions.len(), uvs.len(), normals.len()],
+                                line_number,
+                            )
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+
+                    let submesh = match submeshes.last_mut() {
+                        Some(x) if x.group == group && x.material == material => x,
+                        _ => {
+                            submeshes.push(Submesh {
+                                group: group.clone(),
+                                material: material.clone(),
+                                corners: Vec::new(),
+                            });
+                            submeshes.last_mut().unwrap()
+                        }
+                    };
+
+                    // Polygons are expected to be convex, thus a fan suffices
+                    for i in 1..corners.len() - 1 {
+                        submesh
+                            .corners
+                            .extend([corners[0], corners[i], corners[i + 1]]);
+                    }
+                }
+                "o" | "g" => {
+                    group = if args.is_empty() {
+                        name.to_string()
+                    } else {
+                        args.join(" ")
+                    }
+                }
+                "usemtl" => material = Some(args.join(" ")),
+                "mtllib" => {
+                    for library in args {
+                        let path = base.join(library);
+                        let result = asset::read_to_string(&path)
+                            .map_err(ObjError::IOError)
+                            .and_then(|source| {
+                                ObjMaterial::parse_library(&source, path.parent().unwrap_or(base))
+                            });
+
+                        match result {
+                            Ok(library) => materials.extend(library),
+                            Err(e) => {
+                                warn!("Failed loading material library '{}': {e}", path.display());
+                                warnings.push(ObjImportWarning::MaterialLibrary {
+                                    path: path.display().to_string(),
+                                    reason: e.to_string(),
+                                });
+                            }
+                        }
+                    }
+                }
+                // Smoothing groups, lines, points and free-form geometry
+                _ => debug!("Skipping unsupported OBJ statement '{keyword}' on line {line_number}"),
+            }
+        }
+
+        // Groups spanning multiple materials need distinct labels
+        let mut material_counts: HashMap<&str, usize> = HashMap::new();
+        for submesh in &submeshes {
+            *material_counts.entry(&submesh.group).or_default() += 1;
+        }
+
+        let mut textures = ObjTextureCache::new();
+        let mut parsed_materials = HashMap::new();
+        let mut models = Vec::with_capacity(submeshes.len());
+        for submesh in &submeshes {
+            let label = match &submesh.material {
+                Some(material) if material_counts[submesh.group.as_str()] > 1 => {
+                    format!("{}.{material}", submesh.group)
+                }
+                _ => submesh.group.clone(),
+            };
+
+            let obj_material = match &submesh.material {
+                Some(name) => materials.get(name).cloned().unwrap_or_else(|| {
+                    let warning = ObjImportWarning::UnknownMaterial {
+                        material: name.clone(),
+                    };
+                    if !warnings.contains(&warning) {
+                        warn!("{warning}");
+                        warnings.push(warning);
+                    }
+                    ObjMaterial::default()
+                }),
+                None => ObjMaterial::default(),
+            };
+            let material = parsed_materials
+                .entry(submesh.material.clone())
+                .or_insert_with(|| {
+                    Arc::new(obj_material.to_descriptor(
+                        submesh.material.as_deref(),
+                        &mut textures,
+                        &mut warnings,
+                    ))
+                })
+                .clone();
+
+            let mut mesh = Self::build_mesh(submesh, &positions, &uvs, &normals);
+
+            // Keep vertex coordinates small by moving the mesh to the origin
+            // and compensating with the transform instead.
+            mesh.compute_bounding_box();
+            let offset = mesh.recenter();
+
+            let mut transforms = HashMap::new();
+            transforms.insert(
+                Ulid::new(),
+                Transform {
+                    position: offset,
+                    ..Default::default()
+                },
+            );
+
+            models.push(ModelDescriptor {
+                label,
+                mesh: Arc::new(mesh),
+                materials: vec![material],
+                transforms,
+                skin: None,
+                // Blended materials need to be drawn after opaque geometry
+                render_layer: if obj_material.is_transparent() {
+                    RenderLayer::TRANSPARENT
+                } else {
+                    RenderLayer::default()
+                },
+            });
+        }
+
+        Ok(ObjImportResult { models, warnings })
+    }
+
+    /// Turns the triangles of a [`Submesh`] into an indexed mesh, sharing
+    /// vertices between identical corners.
+    fn build_mesh(
+        submesh: &Submesh,
+        positions: &[Vector3<f32>],
+        uvs: &[Vector2<f32>],
+        normals: &[Vector3<f32>],
+    ) -> MeshDescriptor {
+        let has_normals = submesh
+            .corners
+            .iter()
+            .all(|(_, _, normal)| normal.is_some());
+        let has_uvs = submesh.corners.iter().any(|(_, uv, _)| uv.is_some());
+
+        let mut lookup: HashMap<Corner, u32> = HashMap::new();
+        let mut vertices = Vec::new();
+        let mut indices = Vec::with_capacity(submesh.corners.len());
+        for &(position, uv, normal) in &submesh.corners {
+            // Normals get computed later on, in which case corners only
+            // differing by normal can share a vertex
+            let normal = normal.filter(|_| has_normals);
+
+            let index = *lookup.entry((position, uv, normal)).or_insert_with(|| {
+                let position = positions[position];
+                let normal = normal.map(|x| normals[x]).unwrap_or(Vector3::zero());
+                vertices.push(Vertex::new(
+                    Vector3::new(position.x, position.z, -position.y),
+                    Vector3::new(normal.x, normal.z, -normal.y),
+                    Vector3::zero(),
+                    uv.map(|x| uvs[x]).unwrap_or(Vector2::zero()),
+                ));
+                (vertices.len() - 1) as u32
+            });
+            indices.push(index);
+        }
+
+        let mut mesh = MeshDescriptor::new(vertices, indices);
+        if !has_normals {
+            mesh.compute_normals(true);
+        }
+
+        if has_uvs {
+            mesh.compute_tangents();
+        } else {
+            // Without UVs there is no meaningful tangent space,
+            // any tangent orthogonal to the normal will do
+            for vertex in &mut mesh.vertices {
+                let helper = if vertex.normal.z.abs() < 0.99 {
+                    Vector3::unit_z()
+                } else {
+                    Vector3::unit_x()
+                };
+                let tangent = helper.cross(vertex.normal).normalize();
+                *vertex = Vertex::new(vertex.position, vertex.normal, tangent, vertex.uv);
+            }
+        }
+
+        mesh
+    }
+
+    fn parse_vector3(args: &[&str], line: usize) -> Result<Vector3<f32>, ObjError> {
+        if args.len() < 3 {
+            return Err(ObjError::Parse {
+                line,
+                message: "Expected three components".into(),
+            });
+        }
+
+        Ok(Vector3::new(
+            parse_float(args, line)?,
+            parse_float(&args[1..], line)?,
+            parse_float(&args[2..], line)?,
+        ))
+    }
+
+    /// Parses a face corner in any of the forms `v`, `v/vt`, `v//vn` and
+    /// `v/vt/vn`.
+    /// `counts` are the number of positions, UVs and normals defined so
+    /// far, which negative (relative) indices refer back from.
+    fn parse_corner(corner: &str, counts: [usize; 3], line: usize) -> Result<Corner, ObjError> {
+        let mut parts = corner.split('/');
+        let mut next = |count: usize| -> Result<Option<usize>, ObjError> {
+            match parts.next() {
+                None | Some("") => Ok(None),
+                Some(index) => Self::resolve_index(index, count, line).map(Some),
+            }
+        };
+
+        let position = next(counts[0])?.ok_or_else(|| ObjError::Parse {
+            line,
+            message: format!("Face corner '{corner}' lacks a position"),
+        })?;
+        let uv = next(counts[1])?;
+        let normal = next(counts[2])?;
+
+        Ok((position, uv, normal))
+    }
+
+    /// OBJ indices start at 1, negative ones count back from the end.
+    fn resolve_index(index: &str, count: usize, line: usize) -> Result<usize, ObjError> {
+        let invalid = || ObjError::Parse {
+            line,
+            message: format!("Invalid index '{index}'"),
+        };
+
+        let index: i64 = index.parse().map_err(|_| invalid())?;
+        let resolved = if index > 0 {
+            index - 1
+        } else {
+            count as i64 + index
+        };
+
+        if index == 0 || resolved < 0 || resolved >= count as i64 {
+            return Err(invalid());
+        }
+
+        Ok(resolved as usize)
+    }
+}
@@ -0,0 +1,10 @@
+use crate::importer::obj::ObjImportWarning;
+use crate::resources::ModelDescriptor;
+
+/// Contains the results of an OBJ import.
+#[derive(Debug, Default)]
+pub struct ObjImportResult {
+    pub models: Vec<ModelDescriptor>,
+    /// Non-fatal issues of the import.
+    pub warnings: Vec<ObjImportWarning>,
+}
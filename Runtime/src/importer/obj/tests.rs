@@ -0,0 +1,213 @@
+use crate::element::{ModelEvent, WorldEvent};
+use crate::importer::obj::{ObjError, ObjImportWarning, ObjImporter};
+use crate::importer::{ImportError, ImportTask, Importer};
+use crate::logging;
+use crate::resources::{ModelDescriptor, RenderLayer, TextureDescriptor, VariableType};
+use crate::world::World;
+use cgmath::{InnerSpace, Vector3};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const CUBE_OBJ: &str = "# Unit cube without normals or UVs
+mtllib cube.mtl
+o Cube
+v -1 -1 -1
+v  1 -1 -1
+v  1  1 -1
+v -1  1 -1
+v -1 -1  1
+v  1 -1  1
+v  1  1  1
+v -1  1  1
+usemtl Red
+f 1 4 3 2
+f 5 6 7 8
+f 1 2 6 5
+f 2 3 7 6
+f 3 4 8 7
+f 4 1 5 8
+";
+
+const CUBE_MTL: &str = "newmtl Red
+Kd 1.0 0.0 0.0
+Ns 250
+";
+
+fn albedo_of(model: &ModelDescriptor) -> &Arc<TextureDescriptor> {
+    // Albedo is the second variable of a PBR material
+    let VariableType::Texture { descriptor, .. } = &model.materials[0].variables[1] else {
+        panic!("Expected albedo to be a texture!");
+    };
+    descriptor
+}
+
+fn write_cube(directory: &str) -> PathBuf {
+    let directory = std::env::temp_dir().join(directory);
+    std::fs::create_dir_all(&directory).unwrap();
+    std::fs::write(directory.join("cube.obj"), CUBE_OBJ).unwrap();
+    std::fs::write(directory.join("cube.mtl"), CUBE_MTL).unwrap();
+
+    directory.join("cube.obj")
+}
+
+#[test]
+fn import_cube() {
+    logging::test_init();
+
+    let path = write_cube("orbital_obj_cube");
+    let mut importer = Importer::new(1);
+    importer.register_task(ImportTask::Obj {
+        file_path: path.to_string_lossy().to_string(),
+    });
+
+    let results = importer.import_blocking();
+    assert_eq!(results.len(), 1);
+    assert!(results[0].warnings.is_empty());
+
+    let models = &results[0].models;
+    assert_eq!(models.len(), 1);
+    assert_eq!(models[0].label, "Cube");
+    // Six quads, two triangles each
+    assert_eq!(models[0].mesh.indices.len(), 36);
+    // Without normals in the file, corners are shared and smooth normals computed
+    assert_eq!(models[0].mesh.vertices.len(), 8);
+    for vertex in &models[0].mesh.vertices {
+        assert!((vertex.normal.magnitude() - 1.0).abs() < 1e-4);
+        // Smooth normals of a cube's corner point away from the center
+        assert!(vertex.normal.dot(vertex.position) > 0.0);
+    }
+
+    assert_eq!(models[0].render_layer, RenderLayer::default());
+    assert_eq!(
+        albedo_of(&models[0]).as_ref(),
+        &TextureDescriptor::uniform_rgba_value(1.0, 0.0, 0.0, 1.0, true)
+    );
+
+    let mut world = World::new();
+    world.process_event(WorldEvent::Model(ModelEvent::SpawnBatch(models.clone())));
+    assert!(world.model_store().label_to_id("Cube").is_some());
+}
+
+#[test]
+fn missing_file_is_reported() {
+    logging::test_init();
+
+    let mut importer = Importer::new(1);
+    importer.register_task(ImportTask::Obj {
+        file_path: "does/not/exist.obj".into(),
+    });
+
+    let results = importer.import_blocking();
+    assert_eq!(results.len(), 1);
+    assert!(results[0].models.is_empty());
+    assert!(matches!(
+        results[0].errors.as_slice(),
+        [ImportError::Obj { file_path, .. }] if file_path == "does/not/exist.obj"
+    ));
+}
+
+#[test]
+fn import_face_formats() {
+    logging::test_init();
+
+    // Y-up triangle and quad using `v//vn`, `v/vt/vn` and relative indices
+    let source = "v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vt 0 0
+vt 1 0
+vt 1 1
+vt 0 1
+vn 0 0 1
+f 1//1 2//1 3//1
+g Quad
+f -4/-4/-1 -3/-3/-1 -2/-2/-1 -1/-1/-1
+";
+
+    let result = ObjImporter::import_source(source, Path::new(""), "Shapes").unwrap();
+    assert_eq!(result.models.len(), 2);
+
+    let triangle = &result.models[0];
+    assert_eq!(triangle.label, "Shapes");
+    assert_eq!(triangle.mesh.indices.len(), 3);
+    assert_eq!(triangle.mesh.vertices.len(), 3);
+
+    let quad = &result.models[1];
+    assert_eq!(quad.label, "Quad");
+    assert_eq!(quad.mesh.indices.len(), 6);
+    assert_eq!(quad.mesh.vertices.len(), 4);
+    for vertex in &quad.mesh.vertices {
+        // Converted from Y-up (0, 0, 1) into Z-up space
+        assert!((vertex.normal - Vector3::new(0.0, 1.0, 0.0)).magnitude() < 1e-6);
+        assert!(vertex.tangent.dot(vertex.normal).abs() < 1e-4);
+    }
+    // OBJ UVs start at the bottom
+    let uvs: Vec<_> = quad
+        .mesh
+        .vertices
+        .iter()
+        .map(|x| (x.uv.x, x.uv.y))
+        .collect();
+    assert!(uvs.contains(&(0.0, 1.0)));
+    assert!(uvs.contains(&(1.0, 0.0)));
+}
+
+#[test]
+fn import_transparent_material() {
+    logging::test_init();
+
+    let directory = std::env::temp_dir().join("orbital_obj_transparent");
+    std::fs::create_dir_all(&directory).unwrap();
+    std::fs::write(
+        directory.join("glass.mtl"),
+        "newmtl Glass\nKd 0.5 0.5 1.0\nd 0.25\nPr 0.1\nPm 0.0\n",
+    )
+    .unwrap();
+
+    let source = "mtllib glass.mtl\nv 0 0 0\nv 1 0 0\nv 0 1 0\nusemtl Glass\nf 1 2 3\n";
+    let result = ObjImporter::import_source(source, &directory, "Pane").unwrap();
+    assert!(result.warnings.is_empty());
+    assert_eq!(result.models[0].render_layer, RenderLayer::TRANSPARENT);
+    // Without a diffuse map, the dissolve ends up in the albedo's alpha
+    assert_eq!(
+        albedo_of(&result.models[0]).as_ref(),
+        &TextureDescriptor::uniform_rgba_value(0.5, 0.5, 1.0, 0.25, true)
+    );
+}
+
+#[test]
+fn import_missing_material_parts() {
+    logging::test_init();
+
+    let source = "mtllib missing.mtl\nv 0 0 0\nv 1 0 0\nv 0 1 0\nusemtl Nope\nf 1 2 3\nf 1 3 2\n";
+    let result = ObjImporter::import_source(source, Path::new("/nonexistent"), "Tri").unwrap();
+
+    // The model is still imported with a default material
+    assert_eq!(result.models.len(), 1);
+    assert_eq!(result.warnings.len(), 2);
+    assert!(matches!(
+        result.warnings[0],
+        ObjImportWarning::MaterialLibrary { .. }
+    ));
+    assert_eq!(
+        result.warnings[1],
+        ObjImportWarning::UnknownMaterial {
+            material: "Nope".into()
+        }
+    );
+}
+
+#[test]
+fn import_invalid_source() {
+    logging::test_init();
+
+    let result = ObjImporter::import_source("v 0 0 0\nf 1 2 3\n", Path::new(""), "Broken");
+    assert!(matches!(result, Err(ObjError::Parse { line: 2, .. })));
+
+    let result = ObjImporter::import_source("v 0 zero 0\n", Path::new(""), "Broken");
+    assert!(matches!(result, Err(ObjError::Parse { line: 1, .. })));
+
+    let result = ObjImporter::import("/nonexistent/orbital.obj");
+    assert!(matches!(result, Err(ObjError::IOError(_))));
+}
@@ -0,0 +1,33 @@
+use std::fmt::{Display, Formatter};
+
+/// A non-fatal issue found during an OBJ import.
+/// Unlike errors, the import continues, but the affected part falls back to
+/// defaults.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ObjImportWarning {
+    /// A material library couldn't be loaded.
+    /// Faces using its materials get a default material.
+    MaterialLibrary { path: String, reason: String },
+    /// Faces use a material no loaded library defines.
+    /// A default material is used instead.
+    UnknownMaterial { material: String },
+    /// A texture referenced by a material couldn't be loaded.
+    /// The material's factor is used on its own instead.
+    Texture { path: String, reason: String },
+}
+
+impl Display for ObjImportWarning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ObjImportWarning::MaterialLibrary { path, reason } => {
+                write!(f, "Couldn't load material library '{path}': {reason}")
+            }
+            ObjImportWarning::UnknownMaterial { material } => {
+                write!(f, "Unknown material '{material}', using a default material")
+            }
+            ObjImportWarning::Texture { path, reason } => {
+                write!(f, "Couldn't load texture '{path}': {reason}")
+            }
+        }
+    }
+}
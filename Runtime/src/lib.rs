@@ -15,7 +15,11 @@
 //! - [**resources**](resources): Core resource types (models, cameras, textures, materials)
 //! - [**renderer**](renderer): Rendering pipeline and draw commands
 //! - [**importer**](importer): Asset import functionality, primarily GLTF
+//! - [**asset**](asset): Asset path resolution against a configurable asset root
+//! - [**audio**](audio): Integration point for audio engines
 //! - [**camera_controller**](camera_controller): Camera control system with various movement types
+//! - [**dev_console**](dev_console): In-engine developer console for runtime commands
+//! - [**util**](util): Small helpers, such as a deterministic random number generator
 //!
 //! ## Key Concepts
 //!
@@ -35,8 +39,11 @@
 //! management automatically.
 
 pub mod app;
+pub mod asset;
+pub mod audio;
 pub mod cache;
 pub mod camera_controller;
+pub mod dev_console;
 pub mod element;
 pub mod importer;
 pub mod logging;
@@ -47,6 +54,7 @@ pub mod quaternion;
 pub mod renderer;
 pub mod resources;
 pub mod shader_preprocessor;
+pub mod util;
 pub mod world;
 
 #[cfg(test)]
@@ -0,0 +1,40 @@
+use hashbrown::HashMap;
+use log::LevelFilter;
+
+/// Level filters used by [init_with](super::init_with).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogConfig {
+    /// Level accepted for any target without an override.
+    pub level: LevelFilter,
+    /// Per-target overrides of [Self::level], keyed by the target
+    /// (commonly the crate or module path, e.g. `my_game::physics`).
+    pub targets: HashMap<String, LevelFilter>,
+}
+
+impl LogConfig {
+    /// Overrides the level of `target`.
+    pub fn with_target(mut self, target: impl Into<String>, level: LevelFilter) -> Self {
+        self.targets.insert(target.into(), level);
+        self
+    }
+}
+
+impl Default for LogConfig {
+    /// `Debug` for debug builds, `Info` otherwise.
+    /// WGPU and Naga are limited to `Warn` as they are very verbose.
+    fn default() -> Self {
+        let level = if cfg!(debug_assertions) {
+            LevelFilter::Debug
+        } else {
+            LevelFilter::Info
+        };
+
+        Self {
+            level,
+            targets: HashMap::new(),
+        }
+        .with_target("wgpu_core", LevelFilter::Warn)
+        .with_target("wgpu_hal", LevelFilter::Warn)
+        .with_target("naga", LevelFilter::Warn)
+    }
+}
@@ -1,41 +1,80 @@
 pub use log::*;
 use std::sync::Once;
-use std::{fs, path::Path, time::SystemTime};
+use std::{fs, io, path::Path, time::SystemTime};
 
-#[cfg(target_os = "android")]
-pub fn init() {
-    android_logger::init_once(
-        android_logger::Config::default().with_max_level(log::LevelFilter::Debug),
-    );
+mod config;
+pub use config::*;
+
+#[cfg(all(test, not(target_os = "android")))]
+mod tests;
+
+/// Index of the log file the current run writes to.
+#[cfg(not(target_os = "android"))]
+const LOG_FILE_FIRST_INDEX: u32 = 0;
+/// Index of the oldest log file kept around.
+#[cfg(not(target_os = "android"))]
+const LOG_FILE_LAST_INDEX: u32 = 4;
+
+#[cfg(not(target_os = "android"))]
+fn log_file_name(index: u32) -> String {
+    format!("game-{index}.log")
 }
 
+/// Shifts all log files inside `directory` up by one index, starting with
+/// the highest one, so `game-3.log` becomes `game-4.log` and so on.
+/// The oldest log file gets removed, which leaves the first index free for
+/// the current run.
 #[cfg(not(target_os = "android"))]
-pub fn init() {
-    static ONCE: Once = Once::new();
-    ONCE.call_once(|| {
-        let default_log_level = if cfg!(debug_assertions) {
-            LevelFilter::Debug
+pub(crate) fn rotate_log_files(directory: &Path) -> io::Result<()> {
+    for i in (LOG_FILE_FIRST_INDEX..=LOG_FILE_LAST_INDEX).rev() {
+        let path = directory.join(log_file_name(i));
+        if !path.exists() {
+            continue;
+        }
+
+        if i == LOG_FILE_LAST_INDEX {
+            fs::remove_file(path)?;
         } else {
-            LevelFilter::Info
-        };
+            fs::rename(path, directory.join(log_file_name(i + 1)))?;
+        }
+    }
 
-        const START: u32 = 0;
-        const END: u32 = 4;
+    Ok(())
+}
 
-        for i in (START..=END).rev() {
-            let log_file = format!("game-{i}.log");
-            let path = Path::new(&log_file);
+/// Initializes logging with the default [LogConfig].
+#[cfg(target_os = "android")]
+pub fn init() {
+    init_with(LogConfig {
+        level: LevelFilter::Debug,
+        ..Default::default()
+    });
+}
 
-            if path.exists() {
-                if i == END {
-                    fs::remove_file(path).expect("failed removing last index log file");
-                } else {
-                    let next_log_file = format!("game-{}.log", i + 1);
+/// Initializes logging with the given [LogConfig].
+/// Per-target overrides aren't supported on Android, only
+/// [LogConfig::level] is applied.
+#[cfg(target_os = "android")]
+pub fn init_with(config: LogConfig) {
+    android_logger::init_once(android_logger::Config::default().with_max_level(config.level));
+}
 
-                    fs::rename(path, next_log_file).expect("failed renaming log file to next index");
-                }
-            }
-        }
+/// Initializes logging with the default [LogConfig].
+#[cfg(not(target_os = "android"))]
+pub fn init() {
+    init_with(LogConfig::default());
+}
+
+/// Initializes logging with the given [LogConfig].
+/// Logs are written to StdOut and `game-0.log`, previous log files get
+/// rotated.
+///
+/// Only the first call initializes the logger, further calls are ignored.
+#[cfg(not(target_os = "android"))]
+pub fn init_with(config: LogConfig) {
+    static ONCE: Once = Once::new();
+    ONCE.call_once(|| {
+        rotate_log_files(Path::new(".")).expect("failed rotating log files");
 
         if let Err(e) = fern::Dispatch::new()
             // Setup formation
@@ -49,17 +88,11 @@ pub fn init() {
                 ))
             })
             .chain(
-                fern::Dispatch::new()
-                    // Default level to accept
-                    .level(default_log_level)
-                    // WGPU Overwrite
-                    .level_for("wgpu_core", LevelFilter::Warn)
-                    .level_for("wgpu_hal", LevelFilter::Warn)
-                    .level_for("naga", LevelFilter::Warn)
+                filter_dispatch(&config)
                     // Write to StdOut
                     .chain(std::io::stdout())
                     .chain(
-                        fern::log_file(format!("game-{START}.log"))
+                        fern::log_file(log_file_name(LOG_FILE_FIRST_INDEX))
                             .expect("failed building file log"),
                     ),
             )
@@ -75,6 +108,15 @@ pub fn init() {
     });
 }
 
+/// A [fern::Dispatch] accepting records based on the levels of `config`.
+#[cfg(not(target_os = "android"))]
+fn filter_dispatch(config: &LogConfig) -> fern::Dispatch {
+    config.targets.iter().fold(
+        fern::Dispatch::new().level(config.level),
+        |dispatch, (target, level)| dispatch.level_for(target.clone(), *level),
+    )
+}
+
 #[cfg(not(target_os = "android"))]
 pub fn test_init() {
     if let Err(e) = fern::Dispatch::new()
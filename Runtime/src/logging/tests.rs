@@ -0,0 +1,84 @@
+use std::{fs, sync::mpsc};
+
+use log::{Level, LevelFilter, Record};
+
+use super::{
+    filter_dispatch, log_file_name, rotate_log_files, LogConfig, LOG_FILE_FIRST_INDEX,
+    LOG_FILE_LAST_INDEX,
+};
+
+#[test]
+fn log_files_are_rotated() {
+    let directory = std::env::temp_dir().join("orbital_log_rotation");
+    let _ = fs::remove_dir_all(&directory);
+    fs::create_dir_all(&directory).unwrap();
+
+    // Simulate more runs than log files are kept, each rotating and then
+    // writing its run number into the first log file.
+    let runs = LOG_FILE_LAST_INDEX + 3;
+    for run in 0..runs {
+        rotate_log_files(&directory).expect("Rotation failed!");
+        fs::write(
+            directory.join(log_file_name(LOG_FILE_FIRST_INDEX)),
+            run.to_string(),
+        )
+        .unwrap();
+    }
+
+    for i in LOG_FILE_FIRST_INDEX..=LOG_FILE_LAST_INDEX {
+        let content = fs::read_to_string(directory.join(log_file_name(i)))
+            .expect("Rotated log file missing!");
+        assert_eq!(content, (runs - 1 - i).to_string());
+    }
+    assert_eq!(
+        fs::read_dir(&directory).unwrap().count() as u32,
+        LOG_FILE_LAST_INDEX - LOG_FILE_FIRST_INDEX + 1
+    );
+
+    fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn target_overrides_are_applied() {
+    let config = LogConfig {
+        level: LevelFilter::Warn,
+        ..Default::default()
+    }
+    .with_target("my_game", LevelFilter::Trace)
+    .with_target("orbital", LevelFilter::Off);
+
+    let (sender, receiver) = mpsc::channel();
+    let (_, logger) = filter_dispatch(&config)
+        .format(|out, message, record| out.finish(format_args!("{} {}", record.target(), message)))
+        .chain(sender)
+        .into_log();
+
+    for (target, level) in [
+        ("my_game", Level::Trace),
+        ("my_game::physics", Level::Debug),
+        ("orbital", Level::Error),
+        ("naga", Level::Info),
+        ("naga", Level::Warn),
+        ("other", Level::Info),
+        ("other", Level::Error),
+    ] {
+        logger.log(
+            &Record::builder()
+                .target(target)
+                .level(level)
+                .args(format_args!("{level}"))
+                .build(),
+        );
+    }
+
+    let received = receiver.try_iter().collect::<Vec<_>>();
+    assert_eq!(
+        received,
+        vec![
+            "my_game TRACE\n",
+            "my_game::physics DEBUG\n",
+            "naga WARN\n",
+            "other ERROR\n",
+        ]
+    );
+}
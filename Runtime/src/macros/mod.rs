@@ -38,6 +38,7 @@ macro_rules! make_android_main {
                 event_loop::EventLoop,
                 platform::android::{activity::AndroidApp, EventLoopBuilderExtAndroid},
             };
+            orbital::asset::set_android_app(app.clone());
             let event_loop = EventLoop::builder().with_android_app(app).build();
 
             entrypoint(event_loop);
@@ -2,3 +2,9 @@
 pub fn max_mip_level(size: u32) -> u32 {
     size.ilog2() + 1
 }
+
+/// Given a [`width`] and [`height`], will return the amount of mip levels
+/// needed for a full mip chain down to 1x1.
+pub fn full_mip_level_count(width: u32, height: u32) -> u32 {
+    max_mip_level(width.max(height).max(1))
+}
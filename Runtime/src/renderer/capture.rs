@@ -0,0 +1,290 @@
+use std::{error::Error, fmt::Display, io::Cursor};
+
+use cgmath::Vector2;
+use image::{ImageError, ImageFormat, RgbaImage};
+use wgpu::{
+    BufferDescriptor, BufferUsages, CommandEncoderDescriptor, Device, Extent3d, Origin3d, PollType,
+    Queue, TexelCopyBufferInfo, TexelCopyBufferLayout, TexelCopyTextureInfo, TextureAspect,
+    TextureDescriptor, TextureDimension, TextureFormat, TextureUsages, TextureViewDescriptor,
+    COPY_BYTES_PER_ROW_ALIGNMENT,
+};
+
+use crate::{
+    element::{CameraEvent, WorldEvent},
+    resources::CameraDescriptor,
+    world::World,
+};
+
+use super::Renderer;
+
+/// Errors that can occur while capturing a frame with
+/// [render_camera_to_png] or [render_camera_to_rgba].
+#[derive(Debug)]
+pub enum CaptureError {
+    /// Only 8-bit RGBA/BGRA and 16-bit float RGBA targets can be converted.
+    UnsupportedFormat(TextureFormat),
+    /// The world didn't produce a bind group to render with.
+    MissingWorldBindGroup,
+    /// Mapping the read back buffer failed.
+    Readback(String),
+    /// Encoding the PNG failed.
+    Encoding(ImageError),
+}
+
+impl Display for CaptureError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnsupportedFormat(format) => {
+                write!(f, "Cannot capture render target with format {format:?}")
+            }
+            Self::MissingWorldBindGroup => write!(f, "World bind group is missing"),
+            Self::Readback(e) => write!(f, "Failed reading back render target: {e}"),
+            Self::Encoding(e) => write!(f, "Failed encoding PNG: {e}"),
+        }
+    }
+}
+
+impl Error for CaptureError {}
+
+/// Renders the given [World] from the point of view of `camera` into an
+/// offscreen texture and returns it encoded as sRGB PNG.
+///
+/// Check [render_camera_to_rgba] for details.
+pub async fn render_camera_to_png(
+    world: &mut World,
+    camera: CameraDescriptor,
+    resolution: Vector2<u32>,
+    format: TextureFormat,
+    device: &Device,
+    queue: &Queue,
+) -> Result<Vec<u8>, CaptureError> {
+    let data = render_camera_to_rgba(world, camera, resolution, format, device, queue).await?;
+    let image = RgbaImage::from_raw(resolution.x, resolution.y, data)
+        .expect("Read back data must match the resolution");
+
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(CaptureError::Encoding)?;
+
+    Ok(png)
+}
+
+/// Renders the given [World] from the point of view of `camera` into an
+/// offscreen texture and reads it back as tightly packed sRGB RGBA8 pixels,
+/// row by row starting at the top left.
+/// No window or surface is needed, making this usable for headless
+/// rendering, e.g. in tests.
+///
+/// `format` must match the format the [World]'s models are realized with
+/// (usually the surface format), as material pipelines are cached per
+/// descriptor.
+/// Supported formats are [TextureFormat::Rgba8Unorm],
+/// [TextureFormat::Rgba8UnormSrgb], [TextureFormat::Bgra8Unorm],
+/// [TextureFormat::Bgra8UnormSrgb] and [TextureFormat::Rgba16Float].
+/// 8-bit targets already contain the values as they would be displayed and
+/// are copied as-is, while linear float targets are sRGB encoded.
+///
+/// The camera is only used for this capture, the previously active camera
+/// will be restored afterwards.
+pub async fn render_camera_to_rgba(
+    world: &mut World,
+    camera: CameraDescriptor,
+    resolution: Vector2<u32>,
+    format: TextureFormat,
+    device: &Device,
+    queue: &Queue,
+) -> Result<Vec<u8>, CaptureError> {
+    if !matches!(
+        format,
+        TextureFormat::Rgba8Unorm
+            | TextureFormat::Rgba8UnormSrgb
+            | TextureFormat::Bgra8Unorm
+            | TextureFormat::Bgra8UnormSrgb
+            | TextureFormat::Rgba16Float
+    ) {
+        return Err(CaptureError::UnsupportedFormat(format));
+    }
+
+    // Swap in the capture camera
+    let camera_position = camera.position;
+    let previous_camera = world.camera_store().active_camera();
+    let camera_label = format!("Capture Camera {}", ulid::Ulid::new());
+    world.process_event(WorldEvent::Camera(CameraEvent::Spawn(CameraDescriptor {
+        label: camera_label.clone(),
+        ..camera
+    })));
+    world.process_event(WorldEvent::Camera(CameraEvent::Target(
+        camera_label.clone(),
+    )));
+
+    // Make sure every model is realized for this frame
+    world.model_store_mut().process_bounding_boxes(device);
+    let model_ids = world
+        .model_store()
+        .get_bounding_boxes()
+        .keys()
+        .copied()
+        .collect();
+    world.model_store_mut().flag_realization(model_ids, false);
+    world.prepare_render(&format, device, queue);
+
+    let target = device.create_texture(&TextureDescriptor {
+        label: Some("Capture Render Target"),
+        size: Extent3d {
+            width: resolution.x,
+            height: resolution.y,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: TextureDimension::D2,
+        format,
+        usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+    let target_view = target.create_view(&TextureViewDescriptor::default());
+
+    let render_result = match world.retrieve_render_resources() {
+        (Some(world_bind_group), world_environment, models) => {
+            let mut renderer = Renderer::new(format, resolution, device, queue);
+            renderer.set_clear_color(world.background().clear_color());
+            renderer
+                .render(
+                    &target_view,
+                    world_bind_group,
+                    world_environment,
+                    models,
+                    camera_position,
+                    device,
+                    queue,
+                )
+                .await;
+            Ok(())
+        }
+        (None, _, _) => Err(CaptureError::MissingWorldBindGroup),
+    };
+
+    // Restore the previous camera
+    if let Some(previous_camera) = previous_camera {
+        world.camera_store_mut().target_camera(previous_camera);
+    } else {
+        world.camera_store_mut().untarget_camera();
+    }
+    world.process_event(WorldEvent::Camera(CameraEvent::Despawn(camera_label)));
+
+    render_result?;
+
+    read_texture(&target, format, device, queue)
+}
+
+/// Reads back a 2D texture and converts it into tightly packed sRGB RGBA8.
+fn read_texture(
+    texture: &wgpu::Texture,
+    format: TextureFormat,
+    device: &Device,
+    queue: &Queue,
+) -> Result<Vec<u8>, CaptureError> {
+    let bytes_per_pixel = format
+        .block_copy_size(None)
+        .ok_or(CaptureError::UnsupportedFormat(format))?;
+    let (width, height) = (texture.width(), texture.height());
+
+    let bytes_per_row = bytes_per_pixel * width;
+    let aligned_bytes_per_row = bytes_per_row.next_multiple_of(COPY_BYTES_PER_ROW_ALIGNMENT);
+
+    let buffer = device.create_buffer(&BufferDescriptor {
+        label: Some("Capture Read Buffer"),
+        size: (aligned_bytes_per_row * height) as u64,
+        usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+        label: Some("Capture Encoder"),
+    });
+    encoder.copy_texture_to_buffer(
+        TexelCopyTextureInfo {
+            texture,
+            mip_level: 0,
+            origin: Origin3d::ZERO,
+            aspect: TextureAspect::All,
+        },
+        TexelCopyBufferInfo {
+            buffer: &buffer,
+            layout: TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(aligned_bytes_per_row),
+                rows_per_image: Some(height),
+            },
+        },
+        texture.size(),
+    );
+    queue.submit([encoder.finish()]);
+
+    let (sender, receiver) = std::sync::mpsc::channel();
+    buffer
+        .slice(..)
+        .map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+    device
+        .poll(PollType::Wait)
+        .map_err(|e| CaptureError::Readback(e.to_string()))?;
+    receiver
+        .recv()
+        .map_err(|e| CaptureError::Readback(e.to_string()))?
+        .map_err(|e| CaptureError::Readback(e.to_string()))?;
+
+    let mapped = buffer.slice(..).get_mapped_range();
+    let mut rgba = Vec::with_capacity((width * height * 4) as usize);
+    for row in mapped.chunks(aligned_bytes_per_row as usize) {
+        for pixel in row[..bytes_per_row as usize].chunks(bytes_per_pixel as usize) {
+            match format {
+                TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb => {
+                    rgba.extend_from_slice(&[pixel[2], pixel[1], pixel[0], pixel[3]]);
+                }
+                TextureFormat::Rgba16Float => {
+                    let channel =
+                        |i: usize| f16_to_f32(u16::from_le_bytes([pixel[i], pixel[i + 1]]));
+
+                    rgba.extend_from_slice(&[
+                        linear_to_srgb(channel(0)),
+                        linear_to_srgb(channel(2)),
+                        linear_to_srgb(channel(4)),
+                        (channel(6).clamp(0.0, 1.0) * 255.0).round() as u8,
+                    ]);
+                }
+                _ => rgba.extend_from_slice(pixel),
+            }
+        }
+    }
+
+    Ok(rgba)
+}
+
+/// Encodes a linear color channel with the sRGB transfer function.
+pub(crate) fn linear_to_srgb(linear: f32) -> u8 {
+    let linear = linear.clamp(0.0, 1.0);
+    let srgb = if linear <= 0.003_130_8 {
+        linear * 12.92
+    } else {
+        1.055 * linear.powf(1.0 / 2.4) - 0.055
+    };
+
+    (srgb * 255.0).round() as u8
+}
+
+/// Converts an IEEE 754 half precision float into a single precision float.
+pub(crate) fn f16_to_f32(bits: u16) -> f32 {
+    let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = ((bits >> 10) & 0x1f) as i32;
+    let mantissa = (bits & 0x3ff) as f32;
+
+    match exponent {
+        0 => sign * mantissa * 2f32.powi(-24),
+        0x1f if mantissa == 0.0 => sign * f32::INFINITY,
+        0x1f => f32::NAN,
+        _ => sign * (1.0 + mantissa / 1024.0) * 2f32.powi(exponent - 15),
+    }
+}
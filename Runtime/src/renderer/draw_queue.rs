@@ -0,0 +1,142 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use cgmath::{MetricSpace, Point3};
+use wgpu::{BindGroup, RenderPipeline};
+
+use crate::resources::{MaterialShader, Model};
+
+/// A single draw of a [`Model`] using one of its materials.
+#[derive(Debug, Clone, Copy)]
+pub struct DrawCall<'a> {
+    pub model: &'a Model,
+    pub material: &'a MaterialShader,
+}
+
+/// Collects all [`DrawCall`]s of a frame.
+///
+/// By default, draw calls are in the order the models are given.
+/// Use [`DrawQueue::group`] to reorder them to minimize pipeline and bind
+/// group changes, or [`DrawQueue::sort`] to additionally respect the
+/// [`RenderLayer`](crate::resources::RenderLayer) of each model.
+#[derive(Debug, Default)]
+pub struct DrawQueue<'a> {
+    draw_calls: Vec<DrawCall<'a>>,
+}
+
+impl<'a> DrawQueue<'a> {
+    pub fn new(models: &[&'a Model]) -> Self {
+        let draw_calls = models
+            .iter()
+            .flat_map(|model| {
+                model
+                    .materials()
+                    .iter()
+                    .map(|material| DrawCall { model, material })
+            })
+            .collect();
+
+        Self { draw_calls }
+    }
+
+    /// Orders the draw calls by pipeline first and bind group second.
+    /// Draw calls sharing both keep their relative order.
+    pub fn group(&mut self) {
+        self.draw_calls.sort_by_cached_key(|draw_call| {
+            (
+                Self::hash_of(draw_call.material.pipeline()),
+                Self::hash_of(&draw_call.material.bind_group()),
+            )
+        });
+    }
+
+    /// Orders the draw calls by [`RenderLayer`](crate::resources::RenderLayer)
+    /// first.
+    /// Inside an opaque layer, draw calls are grouped like with
+    /// [`DrawQueue::group`] and only ordered front-to-back within a group,
+    /// based on the distance of the model to `camera_position`.
+    /// Transparent layers are ordered back-to-front only, as blending
+    /// depends on the draw order.
+    pub fn sort(&mut self, camera_position: Point3<f32>) {
+        self.draw_calls.sort_by_cached_key(|draw_call| {
+            let render_layer = draw_call.model.render_layer();
+            // Squared distances are never negative, thus their bits are
+            // ordered like the floats themselves
+            let distance = draw_call
+                .model
+                .center()
+                .distance2(camera_position)
+                .to_bits();
+
+            if render_layer.is_transparent() {
+                (render_layer, 0, 0, u32::MAX - distance)
+            } else {
+                (
+                    render_layer,
+                    Self::hash_of(draw_call.material.pipeline()),
+                    Self::hash_of(&draw_call.material.bind_group()),
+                    distance,
+                )
+            }
+        });
+    }
+
+    pub fn draw_calls(&self) -> &[DrawCall<'a>] {
+        &self.draw_calls
+    }
+
+    /// Counts how often the pipeline has to be changed when drawing this queue
+    /// in order, including the initial set.
+    pub fn pipeline_switches(&self) -> usize {
+        self.count_switches(|draw_call| draw_call.material.pipeline())
+    }
+
+    /// Counts how often the material bind group has to be changed when drawing
+    /// this queue in order, including the initial set.
+    pub fn bind_group_switches(&self) -> usize {
+        self.count_switches(|draw_call| draw_call.material.bind_group())
+    }
+
+    fn count_switches<T: PartialEq>(&self, f: impl Fn(&DrawCall<'a>) -> T) -> usize {
+        let mut switches = 0;
+        let mut last = None;
+
+        for draw_call in &self.draw_calls {
+            let current = Some(f(draw_call));
+            if current != last {
+                switches += 1;
+                last = current;
+            }
+        }
+
+        switches
+    }
+
+    fn hash_of<T: Hash>(value: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+/// Used to skip redundant state changes while recording a render pass.
+#[derive(Debug, Default)]
+pub(crate) struct BoundState<'a> {
+    pipeline: Option<&'a RenderPipeline>,
+    bind_group: Option<Option<&'a BindGroup>>,
+}
+
+impl<'a> BoundState<'a> {
+    /// Returns `true` if the pipeline changed.
+    pub(crate) fn set_pipeline(&mut self, pipeline: &'a RenderPipeline) -> bool {
+        let changed = self.pipeline != Some(pipeline);
+        self.pipeline = Some(pipeline);
+        changed
+    }
+
+    /// Returns `true` if the bind group changed.
+    pub(crate) fn set_bind_group(&mut self, bind_group: Option<&'a BindGroup>) -> bool {
+        let changed = self.bind_group != Some(bind_group);
+        self.bind_group = Some(bind_group);
+        changed
+    }
+}
@@ -14,17 +14,34 @@
 //! ## Rendering Pipeline
 //!
 //! The renderer follows a two-stage process:
-//! 1. Sky box rendering (if environment is present)
+//! 1. Sky box rendering (if environment is present and no clear color is set)
 //! 2. Model rendering with depth testing and proper material handling
+//!
+//! Model draws are ordered by [`RenderLayer`](crate::resources::RenderLayer)
+//! and distance to the camera, then grouped by pipeline and bind group via a
+//! [`DrawQueue`] to minimize state changes.
+//!
+//! For thumbnails or golden images, [`render_camera_to_png`] renders a
+//! [`World`](crate::world::World) offscreen into an encoded PNG.
+//! [`render_camera_to_rgba`] does the same, but returns the raw pixels.
 
-use cgmath::Vector2;
+use cgmath::{Point3, Vector2};
 use wgpu::{
-    BindGroup, Color, CommandEncoder, CommandEncoderDescriptor, Device, IndexFormat, LoadOp,
-    Operations, Queue, RenderPassColorAttachment, RenderPassDepthStencilAttachment,
-    RenderPassDescriptor, StoreOp, TextureFormat, TextureView,
+    BindGroup, Color, CommandEncoder, CommandEncoderDescriptor, Device, LoadOp, Operations, Queue,
+    RenderPassColorAttachment, RenderPassDepthStencilAttachment, RenderPassDescriptor, StoreOp,
+    TextureFormat, TextureView,
 };
 
-use crate::resources::{MaterialShader, Model, Texture, WorldEnvironment};
+use crate::resources::{MaterialShader, Model, Skin, Texture, WorldEnvironment};
+
+mod draw_queue;
+pub use draw_queue::*;
+
+mod capture;
+pub use capture::*;
+
+#[cfg(test)]
+mod tests;
 
 /// The main renderer that manages the rendering state and executes the rendering pipeline.
 /// It handles both sky box rendering for environment maps and model rendering with
@@ -32,6 +49,7 @@ use crate::resources::{MaterialShader, Model, Texture, WorldEnvironment};
 pub struct Renderer {
     surface_texture_format: TextureFormat,
     depth_texture: Texture,
+    clear_color: Option<Color>,
 }
 
 impl Renderer {
@@ -52,6 +70,7 @@ impl Renderer {
         Self {
             surface_texture_format,
             depth_texture,
+            clear_color: None,
         }
     }
 
@@ -64,16 +83,31 @@ impl Renderer {
         self.surface_texture_format = surface_texture_format;
     }
 
+    /// The color the screen is cleared with.  
+    /// If `None`, the sky box of the world environment is drawn instead,
+    /// if there is any.
+    pub fn clear_color(&self) -> Option<Color> {
+        self.clear_color
+    }
+
+    /// Changes what is drawn behind all models, see [Self::clear_color].
+    /// Usually set from [World::background](crate::world::World::background).
+    pub fn set_clear_color(&mut self, clear_color: Option<Color>) {
+        self.clear_color = clear_color;
+    }
+
     pub fn change_resolution(&mut self, resolution: Vector2<u32>, device: &Device, queue: &Queue) {
         self.depth_texture = Texture::depth_texture(&resolution, device, queue);
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn render(
         &mut self,
         target_view: &TextureView,
         world_bind_group: &BindGroup,
         world_environment_option: Option<&WorldEnvironment>,
         models: Vec<&Model>,
+        camera_position: Point3<f32>,
         device: &Device,
         queue: &Queue,
     ) {
@@ -81,7 +115,9 @@ impl Renderer {
             label: Some("Orbital::Render::Encoder"),
         });
 
-        if let Some(world_environment) = world_environment_option {
+        // A solid color replaces the sky box
+        let sky_box_environment = world_environment_option.filter(|_| self.clear_color.is_none());
+        if let Some(world_environment) = sky_box_environment {
             let sky_box_shader = world_environment.material_shader();
             self.render_sky_box(
                 target_view,
@@ -91,7 +127,13 @@ impl Renderer {
             );
         }
 
-        self.render_models(models, target_view, world_bind_group, &mut command_encoder);
+        self.render_models(
+            models,
+            camera_position,
+            target_view,
+            world_bind_group,
+            &mut command_encoder,
+        );
 
         queue.submit(vec![command_encoder.finish()]);
     }
@@ -129,6 +171,7 @@ impl Renderer {
     fn render_models(
         &self,
         models: Vec<&Model>,
+        camera_position: Point3<f32>,
         target_view: &TextureView,
         world_bind_group: &BindGroup,
         command_encoder: &mut CommandEncoder,
@@ -139,7 +182,10 @@ impl Renderer {
                 view: target_view,
                 resolve_target: None,
                 ops: Operations {
-                    load: LoadOp::Load,
+                    load: match self.clear_color {
+                        Some(color) => LoadOp::Clear(color),
+                        None => LoadOp::Load,
+                    },
                     store: StoreOp::Store,
                 },
                 depth_slice: None,
@@ -156,24 +202,32 @@ impl Renderer {
             occlusion_query_set: None,
         });
 
-        for model in models {
-            for material in model.materials() {
-                render_pass.set_pipeline(material.pipeline());
+        let mut draw_queue = DrawQueue::new(&models);
+        draw_queue.sort(camera_position);
 
+        let mut bound_state = BoundState::default();
+        for DrawCall { model, material } in draw_queue.draw_calls() {
+            let pipeline_changed = bound_state.set_pipeline(material.pipeline());
+            if pipeline_changed {
+                render_pass.set_pipeline(material.pipeline());
                 render_pass.set_bind_group(0, world_bind_group, &[]);
+            }
+            if bound_state.set_bind_group(material.bind_group()) || pipeline_changed {
                 render_pass.set_bind_group(1, material.bind_group(), &[]);
+            }
 
-                render_pass.set_vertex_buffer(0, model.mesh().vertex_buffer().slice(..));
-                render_pass.set_vertex_buffer(1, model.instance_buffer().slice(..));
-                render_pass
-                    .set_index_buffer(model.mesh().index_buffer().slice(..), IndexFormat::Uint32);
-
-                render_pass.draw_indexed(
-                    0..model.mesh().index_count(),
-                    0,
-                    0..model.instance_count(),
-                );
+            if let Some(skin) = model.skin() {
+                render_pass.set_bind_group(Skin::BIND_GROUP_INDEX, skin.bind_group(), &[]);
             }
+
+            render_pass.set_vertex_buffer(0, model.mesh().vertex_buffer().slice(..));
+            render_pass.set_vertex_buffer(1, model.instance_buffer().slice(..));
+            render_pass.set_index_buffer(
+                model.mesh().index_buffer().slice(..),
+                model.mesh().index_format(),
+            );
+
+            render_pass.draw_indexed(0..model.mesh().index_count(), 0, 0..model.instance_count());
         }
     }
 }
@@ -0,0 +1,426 @@
+use std::{
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use cgmath::{Point3, Vector2, Vector3};
+use hashbrown::HashMap;
+use ulid::Ulid;
+use wgpu::TextureFormat;
+
+use async_std::task::block_on;
+
+use crate::{
+    cache::Cache,
+    element::{ModelEvent, WorldEvent},
+    renderer::{
+        f16_to_f32, linear_to_srgb, render_camera_to_png, render_camera_to_rgba, CaptureError,
+        DrawQueue,
+    },
+    resources::{
+        CameraDescriptor, MaterialDescriptor, MeshDescriptor, Model, ModelDescriptor, RenderLayer,
+        ShaderSource, Transform, Vertex,
+    },
+    wgpu_test_adapter,
+    world::World,
+};
+
+#[test]
+fn grouping_reduces_pipeline_switches() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+
+    let cache_mesh = RwLock::new(Cache::new(Duration::from_secs(5)));
+    let cache_material = RwLock::new(Cache::new(Duration::from_secs(5)));
+
+    let mesh = Arc::new(MeshDescriptor {
+        vertices: vec![Vertex {
+            position: Vector3::new(1.0, 2.0, 3.0),
+            normal: Vector3::new(1.0, 2.0, 3.0),
+            tangent: Vector3::new(1.0, 2.0, 3.0),
+            bitangent: Vector3::new(1.0, 2.0, 3.0),
+            uv: Vector2::new(1.0, 2.0),
+            color: None,
+            skin: None,
+        }],
+        indices: vec![0],
+        ..Default::default()
+    });
+    let materials = ["A", "B"].map(|name| {
+        Arc::new(MaterialDescriptor {
+            name: Some(name.to_string()),
+            ..Default::default()
+        })
+    });
+
+    // Alternate between both materials: A, B, A, B, A, B
+    let models = (0..6)
+        .map(|i| {
+            let mut transforms = HashMap::new();
+            transforms.insert(Ulid::new(), Transform::default());
+
+            let descriptor = ModelDescriptor {
+                label: format!("Model {i}"),
+                mesh: mesh.clone(),
+                materials: vec![materials[i % 2].clone()],
+                transforms,
+                skin: None,
+                render_layer: RenderLayer::default(),
+            };
+
+            Model::from_descriptor(
+                &descriptor,
+                &TextureFormat::Rgba16Float,
+                &device,
+                &queue,
+                &cache_mesh,
+                &cache_material,
+            )
+            .expect("Failure realizing test model")
+        })
+        .collect::<Vec<_>>();
+    let model_refs = models.iter().collect::<Vec<_>>();
+
+    let mut draw_queue = DrawQueue::new(&model_refs);
+    assert_eq!(draw_queue.draw_calls().len(), 6);
+    assert_eq!(draw_queue.pipeline_switches(), 6);
+
+    draw_queue.group();
+    assert_eq!(draw_queue.draw_calls().len(), 6);
+    assert_eq!(draw_queue.pipeline_switches(), 2);
+}
+
+#[test]
+fn sort_keeps_grouping_of_opaque_draw_calls() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+
+    let cache_mesh = RwLock::new(Cache::new(Duration::from_secs(5)));
+    let cache_material = RwLock::new(Cache::new(Duration::from_secs(5)));
+
+    let mesh = Arc::new(MeshDescriptor {
+        vertices: vec![Vertex {
+            position: Vector3::new(0.0, 0.0, 0.0),
+            normal: Vector3::new(0.0, 0.0, 1.0),
+            tangent: Vector3::new(1.0, 0.0, 0.0),
+            bitangent: Vector3::new(0.0, 1.0, 0.0),
+            uv: Vector2::new(0.0, 0.0),
+            color: None,
+            skin: None,
+        }],
+        indices: vec![0],
+        ..Default::default()
+    });
+    let materials = ["A", "B"].map(|name| {
+        Arc::new(MaterialDescriptor {
+            name: Some(name.to_string()),
+            ..Default::default()
+        })
+    });
+
+    // Alternate between both materials with increasing distance
+    let models = (0..6)
+        .map(|i| {
+            let mut transforms = HashMap::new();
+            transforms.insert(
+                Ulid::new(),
+                Transform {
+                    position: Vector3::new(i as f32 + 1.0, 0.0, 0.0),
+                    ..Default::default()
+                },
+            );
+
+            let descriptor = ModelDescriptor {
+                label: format!("Model {i}"),
+                mesh: mesh.clone(),
+                materials: vec![materials[i % 2].clone()],
+                transforms,
+                skin: None,
+                render_layer: RenderLayer::WORLD,
+            };
+
+            Model::from_descriptor(
+                &descriptor,
+                &TextureFormat::Rgba16Float,
+                &device,
+                &queue,
+                &cache_mesh,
+                &cache_material,
+            )
+            .expect("Failure realizing test model")
+        })
+        .collect::<Vec<_>>();
+    let model_refs = models.iter().collect::<Vec<_>>();
+
+    let mut grouped = DrawQueue::new(&model_refs);
+    grouped.group();
+    let mut sorted = DrawQueue::new(&model_refs);
+    sorted.sort(Point3::new(0.0, 0.0, 0.0));
+
+    assert_eq!(sorted.pipeline_switches(), grouped.pipeline_switches());
+    assert_eq!(sorted.bind_group_switches(), grouped.bind_group_switches());
+
+    // Front-to-back inside each group
+    for group in sorted
+        .draw_calls()
+        .chunk_by(|a, b| a.material.pipeline() == b.material.pipeline())
+    {
+        let distances = group
+            .iter()
+            .map(|draw_call| draw_call.model.center().x)
+            .collect::<Vec<_>>();
+        assert!(distances.is_sorted(), "{distances:?}");
+    }
+}
+
+#[test]
+fn sort_respects_render_layers() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+
+    let cache_mesh = RwLock::new(Cache::new(Duration::from_secs(5)));
+    let cache_material = RwLock::new(Cache::new(Duration::from_secs(5)));
+
+    let mesh = Arc::new(MeshDescriptor {
+        vertices: vec![Vertex {
+            position: Vector3::new(0.0, 0.0, 0.0),
+            normal: Vector3::new(0.0, 0.0, 1.0),
+            tangent: Vector3::new(1.0, 0.0, 0.0),
+            bitangent: Vector3::new(0.0, 1.0, 0.0),
+            uv: Vector2::new(0.0, 0.0),
+            color: None,
+            skin: None,
+        }],
+        indices: vec![0],
+        ..Default::default()
+    });
+    let material = Arc::new(MaterialDescriptor::default());
+
+    // Spawned in an order not matching any policy
+    let setups = [
+        (RenderLayer::OVERLAY, 1.0),
+        (RenderLayer::TRANSPARENT, 2.0),
+        (RenderLayer::WORLD, 5.0),
+        (RenderLayer::TRANSPARENT, 8.0),
+        (RenderLayer::BACKGROUND, 50.0),
+        (RenderLayer::WORLD, 1.0),
+        (RenderLayer::TRANSPARENT, 4.0),
+        (RenderLayer::WORLD, 3.0),
+    ];
+    let models = setups
+        .iter()
+        .map(|(render_layer, distance)| {
+            let mut transforms = HashMap::new();
+            transforms.insert(
+                Ulid::new(),
+                Transform {
+                    position: Vector3::new(*distance, 0.0, 0.0),
+                    ..Default::default()
+                },
+            );
+
+            let descriptor = ModelDescriptor {
+                label: format!("{render_layer:?} at {distance}"),
+                mesh: mesh.clone(),
+                materials: vec![material.clone()],
+                transforms,
+                skin: None,
+                render_layer: *render_layer,
+            };
+
+            Model::from_descriptor(
+                &descriptor,
+                &TextureFormat::Rgba16Float,
+                &device,
+                &queue,
+                &cache_mesh,
+                &cache_material,
+            )
+            .expect("Failure realizing test model")
+        })
+        .collect::<Vec<_>>();
+    let model_refs = models.iter().collect::<Vec<_>>();
+
+    let mut draw_queue = DrawQueue::new(&model_refs);
+    draw_queue.sort(Point3::new(0.0, 0.0, 0.0));
+
+    let order = draw_queue
+        .draw_calls()
+        .iter()
+        .map(|draw_call| (draw_call.model.render_layer(), draw_call.model.center().x))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        order,
+        [
+            (RenderLayer::BACKGROUND, 50.0),
+            // Opaque: front-to-back
+            (RenderLayer::WORLD, 1.0),
+            (RenderLayer::WORLD, 3.0),
+            (RenderLayer::WORLD, 5.0),
+            // Transparent: back-to-front
+            (RenderLayer::TRANSPARENT, 8.0),
+            (RenderLayer::TRANSPARENT, 4.0),
+            (RenderLayer::TRANSPARENT, 2.0),
+            (RenderLayer::OVERLAY, 1.0),
+        ]
+    );
+}
+
+/// Covers the whole screen with a constant color of linear `(1.0, 0.5, 0.0)`.
+const SOLID_COLOR_SHADER: &str = "
+@vertex
+fn entrypoint_vertex(@builtin(vertex_index) vertex_index: u32) -> @builtin(position) vec4<f32> {
+    let uv = vec2<f32>(f32(vertex_index & 1u), f32((vertex_index >> 1u) & 1u));
+    return vec4(uv * 4.0 - 1.0, 0.5, 1.0);
+}
+
+@fragment
+fn entrypoint_fragment() -> @location(0) vec4<f32> {
+    return vec4(1.0, 0.5, 0.0, 1.0);
+}
+";
+
+/// Covers the lower left half of the screen with a constant color of linear
+/// `(0.0, 1.0, 0.5)`.
+const TRIANGLE_SHADER: &str = "
+@vertex
+fn entrypoint_vertex(@builtin(vertex_index) vertex_index: u32) -> @builtin(position) vec4<f32> {
+    var positions = array(vec2(-1.0, -1.0), vec2(1.0, -1.0), vec2(-1.0, 1.0));
+    return vec4(positions[vertex_index], 0.5, 1.0);
+}
+
+@fragment
+fn entrypoint_fragment() -> @location(0) vec4<f32> {
+    return vec4(0.0, 1.0, 0.5, 1.0);
+}
+";
+
+fn solid_color_world() -> World {
+    world_with_shader(SOLID_COLOR_SHADER)
+}
+
+fn world_with_shader(shader: &'static str) -> World {
+    // The shader ignores the position, but it must be in front of the
+    // default camera to not be frustum culled.
+    let vertex = Vertex {
+        position: Vector3::new(1.0, 0.0, 0.0),
+        normal: Vector3::new(0.0, 0.0, 1.0),
+        tangent: Vector3::new(1.0, 0.0, 0.0),
+        bitangent: Vector3::new(0.0, 1.0, 0.0),
+        uv: Vector2::new(0.0, 0.0),
+        color: None,
+        skin: None,
+    };
+    let mut transforms = HashMap::new();
+    transforms.insert(Ulid::new(), Transform::default());
+
+    let mut world = World::new();
+    world.process_event(WorldEvent::Model(ModelEvent::Spawn(ModelDescriptor {
+        label: "Solid".to_string(),
+        mesh: Arc::new(MeshDescriptor {
+            vertices: vec![vertex; 3],
+            indices: vec![0, 1, 2],
+            ..Default::default()
+        }),
+        materials: vec![Arc::new(MaterialDescriptor {
+            name: Some("Solid Color".to_string()),
+            shader_source: ShaderSource::String(shader),
+            cull_mode: None,
+            ..Default::default()
+        })],
+        transforms,
+        skin: None,
+        render_layer: RenderLayer::default(),
+    })));
+
+    world
+}
+
+fn capture(format: TextureFormat) -> Result<image::RgbaImage, CaptureError> {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+    let mut world = solid_color_world();
+
+    let png = block_on(render_camera_to_png(
+        &mut world,
+        CameraDescriptor::default(),
+        Vector2::new(64, 32),
+        format,
+        &device,
+        &queue,
+    ))?;
+
+    Ok(
+        image::load_from_memory_with_format(&png, image::ImageFormat::Png)
+            .expect("Capture must be a valid PNG")
+            .to_rgba8(),
+    )
+}
+
+/// GPUs may round sRGB conversions differently, allow an error of one.
+fn assert_pixel(actual: [u8; 4], expected: [u8; 4]) {
+    assert!(
+        actual.iter().zip(expected).all(|(a, e)| a.abs_diff(e) <= 1),
+        "{actual:?} != {expected:?}"
+    );
+}
+
+#[test]
+fn capture_srgb_target() {
+    let image = capture(TextureFormat::Rgba8UnormSrgb).unwrap();
+
+    assert_eq!(image.dimensions(), (64, 32));
+    assert_pixel(image.get_pixel(32, 16).0, [255, 188, 0, 255]);
+}
+
+#[test]
+fn capture_float_target() {
+    let image = capture(TextureFormat::Rgba16Float).unwrap();
+
+    assert_eq!(image.dimensions(), (64, 32));
+    // Linear values must be encoded to sRGB
+    assert_pixel(image.get_pixel(10, 5).0, [255, 188, 0, 255]);
+}
+
+#[test]
+fn capture_unsupported_format() {
+    assert!(matches!(
+        capture(TextureFormat::R32Float),
+        Err(CaptureError::UnsupportedFormat(TextureFormat::R32Float))
+    ));
+}
+
+#[test]
+fn capture_color_conversion() {
+    assert_eq!(linear_to_srgb(0.0), 0);
+    assert_eq!(linear_to_srgb(0.5), 188);
+    assert_eq!(linear_to_srgb(1.0), 255);
+    assert_eq!(linear_to_srgb(2.0), 255);
+
+    assert_eq!(f16_to_f32(0x3c00), 1.0);
+    assert_eq!(f16_to_f32(0x3800), 0.5);
+    assert_eq!(f16_to_f32(0xc000), -2.0);
+    assert_eq!(f16_to_f32(0x0000), 0.0);
+}
+
+#[test]
+fn render_triangle_to_rgba() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+    let mut world = world_with_shader(TRIANGLE_SHADER);
+
+    let data = block_on(render_camera_to_rgba(
+        &mut world,
+        CameraDescriptor::default(),
+        Vector2::new(64, 32),
+        TextureFormat::Rgba8UnormSrgb,
+        &device,
+        &queue,
+    ))
+    .unwrap();
+    assert_eq!(data.len(), 64 * 32 * 4);
+
+    let pixel = |x: usize, y: usize| -> [u8; 4] {
+        let offset = (y * 64 + x) * 4;
+        data[offset..offset + 4].try_into().unwrap()
+    };
+    // Inside the triangle
+    assert_pixel(pixel(4, 28), [0, 255, 188, 255]);
+    // Outside the triangle, the target stays zero initialized
+    assert_eq!(pixel(60, 2), [0, 0, 0, 0]);
+}
@@ -0,0 +1,125 @@
+use cgmath::{InnerSpace, Quaternion, Vector3, VectorSpace};
+
+use crate::resources::Transform;
+
+use super::Interpolation;
+
+/// Keyframe values of an [AnimationChannel].
+/// The variant defines which property of a [Transform] gets animated.
+///
+/// For [Interpolation::CubicSpline] each keyframe consists of three
+/// consecutive values: in-tangent, value and out-tangent.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnimationValues {
+    Translation(Vec<Vector3<f32>>),
+    Rotation(Vec<Quaternion<f32>>),
+    Scale(Vec<Vector3<f32>>),
+}
+
+/// A single value sampled from an [AnimationChannel].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AnimationValue {
+    Translation(Vector3<f32>),
+    Rotation(Quaternion<f32>),
+    Scale(Vector3<f32>),
+}
+
+impl AnimationValue {
+    /// Replaces the animated property of `transform` with this value.
+    pub fn apply(self, transform: &mut Transform) {
+        match self {
+            AnimationValue::Translation(position) => transform.set_position(position),
+            AnimationValue::Rotation(rotation) => transform.set_rotation(rotation),
+            AnimationValue::Scale(scale) => transform.set_scale(scale),
+        }
+    }
+}
+
+/// Animates a single property of a target.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimationChannel {
+    /// Label of the animated target, e.g. a model.
+    pub target: String,
+    pub interpolation: Interpolation,
+    /// Keyframe times in seconds, in ascending order.
+    pub times: Vec<f32>,
+    pub values: AnimationValues,
+}
+
+impl AnimationChannel {
+    /// Time of the last keyframe in seconds.
+    pub fn duration(&self) -> f32 {
+        self.times.last().copied().unwrap_or(0.0)
+    }
+
+    /// Evaluates the channel at `time` in seconds.
+    /// Times outside of the keyframes are clamped to the first or last
+    /// keyframe.
+    ///
+    /// Returns [None] if there are no keyframes or not enough values.
+    pub fn sample(&self, time: f32) -> Option<AnimationValue> {
+        match &self.values {
+            AnimationValues::Translation(values) => {
+                sample_keyframes(&self.times, values, self.interpolation, time, Vector3::lerp)
+                    .map(AnimationValue::Translation)
+            }
+            AnimationValues::Rotation(values) => {
+                sample_keyframes(&self.times, values, self.interpolation, time, |a, b, t| {
+                    a.slerp(b, t)
+                })
+                .map(|rotation| AnimationValue::Rotation(rotation.normalize()))
+            }
+            AnimationValues::Scale(values) => {
+                sample_keyframes(&self.times, values, self.interpolation, time, Vector3::lerp)
+                    .map(AnimationValue::Scale)
+            }
+        }
+    }
+}
+
+fn sample_keyframes<T: VectorSpace<Scalar = f32>>(
+    times: &[f32],
+    values: &[T],
+    interpolation: Interpolation,
+    time: f32,
+    lerp: impl Fn(T, T, f32) -> T,
+) -> Option<T> {
+    let value_at = |keyframe: usize| match interpolation {
+        Interpolation::CubicSpline => values.get(keyframe * 3 + 1).copied(),
+        _ => values.get(keyframe).copied(),
+    };
+
+    let last = times.len().checked_sub(1)?;
+    if time <= times[0] {
+        return value_at(0);
+    }
+    if time >= times[last] {
+        return value_at(last);
+    }
+
+    let previous = times.partition_point(|x| *x <= time) - 1;
+    let next = previous + 1;
+    let delta = times[next] - times[previous];
+    let t = (time - times[previous]) / delta;
+
+    match interpolation {
+        Interpolation::Step => value_at(previous),
+        Interpolation::Linear => Some(lerp(value_at(previous)?, value_at(next)?, t)),
+        Interpolation::CubicSpline => {
+            let start = value_at(previous)?;
+            let start_tangent = *values.get(previous * 3 + 2)? * delta;
+            let end = value_at(next)?;
+            let end_tangent = *values.get(next * 3)? * delta;
+
+            let t2 = t * t;
+            let t3 = t2 * t;
+
+            Some(
+                start * (2.0 * t3 - 3.0 * t2 + 1.0)
+                    + start_tangent * (t3 - 2.0 * t2 + t)
+                    + end * (-2.0 * t3 + 3.0 * t2)
+                    + end_tangent * (t3 - t2),
+            )
+        }
+    }
+}
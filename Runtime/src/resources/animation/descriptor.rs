@@ -0,0 +1,32 @@
+use crate::resources::Transform;
+
+use super::AnimationChannel;
+
+/// An animation, made of one or multiple [AnimationChannel]s.
+/// Each channel animates one property of one target.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimationDescriptor {
+    pub label: String,
+    pub channels: Vec<AnimationChannel>,
+}
+
+impl AnimationDescriptor {
+    /// Time of the last keyframe of all channels in seconds.
+    pub fn duration(&self) -> f32 {
+        self.channels
+            .iter()
+            .map(|channel| channel.duration())
+            .fold(0.0, f32::max)
+    }
+
+    /// Evaluates all channels animating `target` at `time` in seconds and
+    /// applies them to `transform`.
+    /// Properties not animated are left untouched.
+    pub fn apply(&self, target: &str, time: f32, transform: &mut Transform) {
+        self.channels
+            .iter()
+            .filter(|channel| channel.target == target)
+            .filter_map(|channel| channel.sample(time))
+            .for_each(|value| value.apply(transform));
+    }
+}
@@ -0,0 +1,13 @@
+/// Defines how values in between two keyframes are computed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Interpolation {
+    /// The value of the previous keyframe is held until the next keyframe.
+    Step,
+    /// Values are interpolated linearly.
+    /// Rotations are spherically interpolated.
+    #[default]
+    Linear,
+    /// Values are interpolated with a cubic Hermite spline.
+    /// Each keyframe stores an in-tangent, the value and an out-tangent.
+    CubicSpline,
+}
@@ -0,0 +1,11 @@
+mod interpolation;
+pub use interpolation::*;
+
+mod channel;
+pub use channel::*;
+
+mod descriptor;
+pub use descriptor::*;
+
+#[cfg(test)]
+mod tests;
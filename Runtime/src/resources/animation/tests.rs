@@ -0,0 +1,152 @@
+use cgmath::{Deg, InnerSpace, Quaternion, Rotation3, Vector3};
+
+use crate::resources::Transform;
+
+use super::{
+    AnimationChannel, AnimationDescriptor, AnimationValue, AnimationValues, Interpolation,
+};
+
+fn translation_channel(
+    interpolation: Interpolation,
+    values: Vec<Vector3<f32>>,
+) -> AnimationChannel {
+    AnimationChannel {
+        target: "Cube".into(),
+        interpolation,
+        times: vec![0.0, 1.0],
+        values: AnimationValues::Translation(values),
+    }
+}
+
+fn translation(value: Option<AnimationValue>) -> Vector3<f32> {
+    match value {
+        Some(AnimationValue::Translation(x)) => x,
+        other => panic!("Expected translation, got {other:?}"),
+    }
+}
+
+#[test]
+fn step_holds_previous_keyframe() {
+    let channel = translation_channel(
+        Interpolation::Step,
+        vec![Vector3::new(0.0, 0.0, 0.0), Vector3::new(2.0, 0.0, 0.0)],
+    );
+
+    assert_eq!(
+        translation(channel.sample(0.99)),
+        Vector3::new(0.0, 0.0, 0.0)
+    );
+    assert_eq!(
+        translation(channel.sample(1.0)),
+        Vector3::new(2.0, 0.0, 0.0)
+    );
+}
+
+#[test]
+fn linear_interpolates() {
+    let channel = translation_channel(
+        Interpolation::Linear,
+        vec![Vector3::new(0.0, 0.0, 0.0), Vector3::new(2.0, 4.0, -2.0)],
+    );
+
+    assert_eq!(
+        translation(channel.sample(0.5)),
+        Vector3::new(1.0, 2.0, -1.0)
+    );
+}
+
+#[test]
+fn sampling_is_clamped() {
+    let channel = translation_channel(
+        Interpolation::Linear,
+        vec![Vector3::new(1.0, 0.0, 0.0), Vector3::new(2.0, 0.0, 0.0)],
+    );
+
+    assert_eq!(
+        translation(channel.sample(-1.0)),
+        Vector3::new(1.0, 0.0, 0.0)
+    );
+    assert_eq!(
+        translation(channel.sample(5.0)),
+        Vector3::new(2.0, 0.0, 0.0)
+    );
+    assert_eq!(channel.duration(), 1.0);
+}
+
+#[test]
+fn cubic_spline_interpolates() {
+    let zero = Vector3::new(0.0, 0.0, 0.0);
+    // in-tangent, value, out-tangent per keyframe
+    let channel = translation_channel(
+        Interpolation::CubicSpline,
+        vec![zero, zero, zero, zero, Vector3::new(2.0, 0.0, 0.0), zero],
+    );
+
+    // With flat tangents the spline is a smoothstep
+    assert_eq!(
+        translation(channel.sample(0.5)),
+        Vector3::new(1.0, 0.0, 0.0)
+    );
+    let quarter = translation(channel.sample(0.25));
+    assert!((quarter.x - 2.0 * 0.15625).abs() < 1e-6);
+    assert_eq!(
+        translation(channel.sample(1.0)),
+        Vector3::new(2.0, 0.0, 0.0)
+    );
+}
+
+#[test]
+fn rotation_is_slerped() {
+    let channel = AnimationChannel {
+        target: "Cube".into(),
+        interpolation: Interpolation::Linear,
+        times: vec![0.0, 2.0],
+        values: AnimationValues::Rotation(vec![
+            Quaternion::from_angle_z(Deg(0.0)),
+            Quaternion::from_angle_z(Deg(90.0)),
+        ]),
+    };
+
+    let Some(AnimationValue::Rotation(rotation)) = channel.sample(1.0) else {
+        panic!("Expected rotation");
+    };
+    let expected = Quaternion::from_angle_z(Deg(45.0));
+    assert!((rotation - expected).magnitude() < 1e-5);
+}
+
+#[test]
+fn empty_channel_samples_nothing() {
+    let channel = translation_channel(Interpolation::Linear, vec![]);
+
+    assert_eq!(channel.sample(0.0), None);
+    assert_eq!(channel.duration(), 1.0);
+}
+
+#[test]
+fn descriptor_applies_matching_channels() {
+    let descriptor = AnimationDescriptor {
+        label: "Move".into(),
+        channels: vec![
+            translation_channel(
+                Interpolation::Linear,
+                vec![Vector3::new(0.0, 0.0, 0.0), Vector3::new(2.0, 0.0, 0.0)],
+            ),
+            AnimationChannel {
+                target: "Other".into(),
+                interpolation: Interpolation::Linear,
+                times: vec![0.0, 3.0],
+                values: AnimationValues::Scale(vec![
+                    Vector3::new(1.0, 1.0, 1.0),
+                    Vector3::new(2.0, 2.0, 2.0),
+                ]),
+            },
+        ],
+    };
+    assert_eq!(descriptor.duration(), 3.0);
+
+    let mut transform = Transform::default();
+    descriptor.apply("Cube", 0.5, &mut transform);
+
+    assert_eq!(transform.position, Vector3::new(1.0, 0.0, 0.0));
+    assert_eq!(transform.scale, Transform::default().scale);
+}
@@ -1,11 +1,13 @@
 use std::{f32, hash::Hash};
 
-use cgmath::{num_traits::Float, Point3};
+use cgmath::{num_traits::Float, InnerSpace, Matrix4, Point3, Transform, Vector3};
 use wgpu::{
     util::{BufferInitDescriptor, DeviceExt},
     Buffer, BufferUsages, Device,
 };
 
+use crate::resources::Frustum;
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct BoundingBoxDescriptor {
     pub min: Point3<f32>,
@@ -17,6 +19,92 @@ impl BoundingBoxDescriptor {
         Self { min, max }
     }
 
+    /// Returns the eight corners of this box.
+    pub fn corners(&self) -> [Point3<f32>; 8] {
+        let (min, max) = (self.min, self.max);
+
+        [
+            Point3::new(min.x, min.y, min.z),
+            Point3::new(max.x, min.y, min.z),
+            Point3::new(max.x, max.y, min.z),
+            Point3::new(min.x, max.y, min.z),
+            Point3::new(min.x, min.y, max.z),
+            Point3::new(max.x, min.y, max.z),
+            Point3::new(max.x, max.y, max.z),
+            Point3::new(min.x, max.y, max.z),
+        ]
+    }
+
+    /// Returns the axis-aligned box enclosing this box after transforming
+    /// it by `matrix`, e.g. a model space matrix.
+    pub fn transformed(&self, matrix: &Matrix4<f32>) -> Self {
+        let mut min = Point3::new(f32::INFINITY, f32::INFINITY, f32::INFINITY);
+        let mut max = Point3::new(f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY);
+
+        for corner in self.corners() {
+            let corner = matrix.transform_point(corner);
+            min = Point3::new(
+                min.x.min(corner.x),
+                min.y.min(corner.y),
+                min.z.min(corner.z),
+            );
+            max = Point3::new(
+                max.x.max(corner.x),
+                max.y.max(corner.y),
+                max.z.max(corner.z),
+            );
+        }
+
+        Self { min, max }
+    }
+
+    /// Returns the smallest box enclosing both, this and `other` box.
+    pub fn union(&self, other: &Self) -> Self {
+        Self {
+            min: Point3::new(
+                self.min.x.min(other.min.x),
+                self.min.y.min(other.min.y),
+                self.min.z.min(other.min.z),
+            ),
+            max: Point3::new(
+                self.max.x.max(other.max.x),
+                self.max.y.max(other.max.y),
+                self.max.z.max(other.max.z),
+            ),
+        }
+    }
+
+    /// Checks whether any part of this box is inside the [Frustum].
+    ///
+    /// Boxes are only rejected if they are fully outside of at least one
+    /// frustum plane.
+    /// Boxes near a frustum corner may thus be reported as intersecting,
+    /// even though they are not, which is fine for culling.
+    pub fn intersects_frustum(&self, frustum: &Frustum) -> bool {
+        frustum.planes.iter().all(|plane| {
+            // The corner furthest along the plane normal
+            let positive = Vector3::new(
+                if plane.x >= 0.0 {
+                    self.max.x
+                } else {
+                    self.min.x
+                },
+                if plane.y >= 0.0 {
+                    self.max.y
+                } else {
+                    self.min.y
+                },
+                if plane.z >= 0.0 {
+                    self.max.z
+                } else {
+                    self.min.z
+                },
+            );
+
+            plane.truncate().dot(positive) + plane.w >= 0.0
+        })
+    }
+
     pub fn to_binary_data(&self) -> Vec<u8> {
         [
             // Min
@@ -3,6 +3,8 @@ use wgpu::{
     Buffer, BufferUsages, Device,
 };
 
+use crate::resources::Frustum;
+
 mod descriptor;
 pub use descriptor::*;
 
@@ -11,6 +13,7 @@ mod tests;
 
 #[derive(Debug)]
 pub struct BoundingBox {
+    descriptor: BoundingBoxDescriptor,
     buffer: Buffer,
 }
 
@@ -22,7 +25,20 @@ impl BoundingBox {
             usage: BufferUsages::UNIFORM,
         });
 
-        Self { buffer }
+        Self {
+            descriptor: *descriptor,
+            buffer,
+        }
+    }
+
+    pub fn descriptor(&self) -> &BoundingBoxDescriptor {
+        &self.descriptor
+    }
+
+    /// Checks whether any part of this box is inside the [Frustum].
+    /// See [BoundingBoxDescriptor::intersects_frustum].
+    pub fn intersects_frustum(&self, frustum: &Frustum) -> bool {
+        self.descriptor.intersects_frustum(frustum)
     }
 
     pub fn buffer(&self) -> &Buffer {
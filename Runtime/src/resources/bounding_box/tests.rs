@@ -1,6 +1,9 @@
-use cgmath::Point3;
+use cgmath::{Matrix4, Point3, Vector3};
 
-use crate::wgpu_test_adapter;
+use crate::{
+    resources::{CameraDescriptor, Frustum},
+    wgpu_test_adapter,
+};
 
 use super::{BoundingBox, BoundingBoxDescriptor};
 
@@ -23,3 +26,72 @@ fn realization() {
 
     let _realization = BoundingBox::new(&descriptor, &device);
 }
+
+/// Default camera at the origin, looking along +X.
+fn frustum() -> Frustum {
+    CameraDescriptor::default().frustum()
+}
+
+fn bounding_box(min: [f32; 3], max: [f32; 3]) -> BoundingBoxDescriptor {
+    BoundingBoxDescriptor::new(min.into(), max.into())
+}
+
+#[test]
+fn frustum_contains_point_in_view() {
+    let frustum = frustum();
+
+    assert!(frustum.contains_point(Vector3::new(5.0, 0.0, 0.0)));
+    assert!(!frustum.contains_point(Vector3::new(-5.0, 0.0, 0.0)));
+}
+
+#[test]
+fn intersects_frustum_inside() {
+    let descriptor = bounding_box([4.0, -1.0, -1.0], [6.0, 1.0, 1.0]);
+
+    assert!(descriptor.intersects_frustum(&frustum()));
+}
+
+#[test]
+fn intersects_frustum_outside() {
+    let frustum = frustum();
+
+    // Behind the camera
+    assert!(!bounding_box([-6.0, -1.0, -1.0], [-4.0, 1.0, 1.0]).intersects_frustum(&frustum));
+    // Off to the side
+    assert!(!bounding_box([4.0, -1.0, 40.0], [6.0, 1.0, 50.0]).intersects_frustum(&frustum));
+    // Beyond the far plane
+    assert!(!bounding_box([20000.0, -1.0, -1.0], [20002.0, 1.0, 1.0]).intersects_frustum(&frustum));
+}
+
+#[test]
+fn intersects_frustum_straddling() {
+    let frustum = frustum();
+
+    // Crossing a side plane
+    assert!(bounding_box([4.0, -1.0, 2.0], [6.0, 1.0, 50.0]).intersects_frustum(&frustum));
+    // Crossing the near plane, enclosing the camera
+    assert!(bounding_box([-1.0, -1.0, -1.0], [1.0, 1.0, 1.0]).intersects_frustum(&frustum));
+}
+
+#[test]
+fn transformed_encloses_corners() {
+    let descriptor = bounding_box([-1.0, -1.0, -1.0], [1.0, 1.0, 1.0]);
+
+    let moved = descriptor.transformed(&Matrix4::from_translation(Vector3::new(-5.0, 0.0, 0.0)));
+    assert_eq!(moved, bounding_box([-6.0, -1.0, -1.0], [-4.0, 1.0, 1.0]));
+    assert!(!moved.intersects_frustum(&frustum()));
+
+    let scaled = descriptor.transformed(&Matrix4::from_nonuniform_scale(2.0, 1.0, 3.0));
+    assert_eq!(scaled, bounding_box([-2.0, -1.0, -3.0], [2.0, 1.0, 3.0]));
+}
+
+#[test]
+fn realization_intersects_frustum() {
+    let (_, device, _) = wgpu_test_adapter::make_wgpu_connection();
+
+    let descriptor = bounding_box([4.0, -1.0, -1.0], [6.0, 1.0, 1.0]);
+    let realization = BoundingBox::new(&descriptor, &device);
+
+    assert_eq!(realization.descriptor(), &descriptor);
+    assert!(realization.intersects_frustum(&frustum()));
+}
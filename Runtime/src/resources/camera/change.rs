@@ -18,6 +18,13 @@ pub struct CameraTransform {
     /// If `Some`, will change the camera's roll.
     /// If `None`, will be ignored.
     pub roll: Option<Mode<f32>>,
+    /// If `Some`, will change the camera's vertical field of view (in
+    /// degrees).
+    /// If `None`, will be ignored.
+    ///
+    /// View aligned offsets behave like [`Mode::Offset`].
+    /// Ignored for orthographic projections.
+    pub fovy: Option<Mode<f32>>,
 }
 
 impl CameraTransform {
@@ -64,6 +71,15 @@ impl CameraTransform {
             return true;
         }
 
+        if self.fovy.as_ref().is_some_and(|fovy| match fovy {
+            Mode::Overwrite(_) => true,
+            Mode::Offset(v) | Mode::OffsetViewAligned(v) | Mode::OffsetViewAlignedWithY(v) => {
+                v.abs() >= 0.0001
+            }
+        }) {
+            return true;
+        }
+
         false
     }
 }
@@ -1,7 +1,8 @@
 use std::f32::consts::FRAC_PI_2;
 
-use super::{CameraTransform, Mode};
-use cgmath::{InnerSpace, Point3, Vector3};
+use super::{CameraTransform, Frustum, Mode, Projection, Ray};
+use crate::resources::BoundingBoxDescriptor;
+use cgmath::{EuclideanSpace, InnerSpace, Matrix4, Point3, SquareMatrix, Vector3, Vector4};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct CameraDescriptor {
@@ -10,10 +11,23 @@ pub struct CameraDescriptor {
     pub yaw: f32,
     pub pitch: f32,
     pub roll: f32,
-    pub aspect: f32,
-    pub fovy: f32,
+    pub projection: Projection,
+    /// If enabled, the aspect ratio of the [projection](Self::projection)
+    /// is replaced with the aspect ratio of the surface once the camera is
+    /// spawned and again whenever the surface gets resized.  
+    /// Disable this to keep a fixed aspect ratio, e.g. for letterboxing.
+    ///
+    /// Check [Projection::set_aspect] for details.
+    pub auto_aspect: bool,
     pub near: f32,
     pub far: f32,
+    /// If enabled, [near](Self::near) and [far](Self::far) get fitted to the
+    /// bounds of all models each frame while this camera is active.
+    /// This avoids clipping large scenes while keeping depth precision
+    /// high for small ones.
+    ///
+    /// Check [Self::fit_depth_range] for details.
+    pub auto_depth_range: bool,
     pub global_gamma: f32,
 }
 
@@ -21,6 +35,143 @@ impl CameraDescriptor {
     pub const DEFAULT_NAME: &'static str = "Default";
     pub const SAFE_FRAC_PI_2: f32 = FRAC_PI_2 - 0.0001;
     pub const DEFAULT_GAMMA: f32 = 2.2;
+    /// Smallest vertical field of view (in degrees) a change can apply.
+    pub const MIN_FOVY: f32 = 0.1;
+    /// Largest vertical field of view (in degrees) a change can apply.
+    pub const MAX_FOVY: f32 = 179.9;
+    /// Smallest near plane [Self::fit_depth_range] sets.
+    pub const MIN_AUTO_NEAR: f32 = 0.01;
+    /// Largest ratio of far to near plane [Self::fit_depth_range] sets.
+    /// Bigger ratios spread the depth precision too thin, causing
+    /// z-fighting.
+    pub const MAX_AUTO_DEPTH_RATIO: f32 = 100_000.0;
+    /// Relative margin [Self::fit_depth_range] adds around the scene, so
+    /// geometry touching its bounds isn't clipped.
+    const AUTO_DEPTH_MARGIN: f32 = 0.01;
+
+    /// Converts cgmath's OpenGL style clip space (`z` in `-1..1`) into
+    /// wgpu's clip space (`z` in `0..1`).
+    #[rustfmt::skip]
+    const OPENGL_TO_WGPU_MATRIX: Matrix4<f32> = Matrix4::new(
+        1.0, 0.0, 0.0, 0.0,
+        0.0, 1.0, 0.0, 0.0,
+        0.0, 0.0, 0.5, 0.0,
+        0.0, 0.0, 0.5, 1.0,
+    );
+
+    /// Returns the normalized direction the camera is looking at.
+    pub fn forward(&self) -> Vector3<f32> {
+        let (pitch_sin, pitch_cos) = self.pitch.sin_cos();
+        let (yaw_sin, yaw_cos) = self.yaw.sin_cos();
+
+        Vector3::new(pitch_cos * yaw_cos, pitch_sin, pitch_cos * yaw_sin).normalize()
+    }
+
+    /// Calculates the view matrix based on position, yaw, pitch and roll.
+    pub fn view_matrix(&self) -> Matrix4<f32> {
+        let (yaw_sin, yaw_cos) = self.yaw.sin_cos();
+        let (roll_sin, roll_cos) = self.roll.sin_cos();
+
+        // Calculate the forward, right, and up vectors
+        let forward = self.forward();
+        let right = Vector3::new(-yaw_sin, 0.0, yaw_cos).normalize();
+        let up = right.cross(forward).normalize();
+
+        // Apply roll rotation to the up vector
+        let rotated_up = -right * roll_sin + up * roll_cos;
+
+        Matrix4::look_to_rh(self.position, forward, rotated_up)
+    }
+
+    /// Calculates the projection matrix as uploaded to the GPU,
+    /// based on the [projection](Self::projection).
+    pub fn projection_matrix(&self) -> Matrix4<f32> {
+        self.projection.matrix(self.near, self.far)
+    }
+
+    /// Fits [near](Self::near) and [far](Self::far) tightly around
+    /// `scene_bounds`, based on the depth of its corners along
+    /// [Self::forward].  
+    /// The near plane is kept at or above [Self::MIN_AUTO_NEAR] and
+    /// `far / MAX_AUTO_DEPTH_RATIO`, thus scenes surrounding the camera
+    /// are clipped close to it instead of losing all depth precision.
+    /// Scenes entirely behind the camera leave the depth range untouched.
+    ///
+    /// Returns whether the depth range changed.
+    pub fn fit_depth_range(&mut self, scene_bounds: &BoundingBoxDescriptor) -> bool {
+        let forward = self.forward();
+        let (min_depth, max_depth) = scene_bounds
+            .corners()
+            .iter()
+            .map(|corner| (corner - self.position).dot(forward))
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), depth| {
+                (min.min(depth), max.max(depth))
+            });
+
+        if !max_depth.is_finite() || max_depth <= 0.0 {
+            return false;
+        }
+
+        let far = max_depth * (1.0 + Self::AUTO_DEPTH_MARGIN);
+        let near = (min_depth * (1.0 - Self::AUTO_DEPTH_MARGIN))
+            .max(Self::MIN_AUTO_NEAR)
+            .max(far / Self::MAX_AUTO_DEPTH_RATIO);
+        // Tiny scenes right in front of the camera may end up in front of
+        // the clamped near plane
+        let far = far.max(near + Self::MIN_AUTO_NEAR);
+
+        if near == self.near && far == self.far {
+            return false;
+        }
+
+        self.near = near;
+        self.far = far;
+        true
+    }
+
+    /// Calculates the [Frustum] this camera currently sees.
+    pub fn frustum(&self) -> Frustum {
+        Frustum::from_view_projection(self.projection_matrix() * self.view_matrix())
+    }
+
+    /// Converts a pixel position (e.g. the cursor position) into a world
+    /// space [Ray].
+    ///
+    /// `pixel` is measured from the top-left corner of the viewport, which
+    /// is `viewport` pixels wide and high.
+    ///
+    /// The pixel is unprojected on the near (`z = 0`) and far (`z = 1`)
+    /// plane of wgpu's clip space through the inverse view-projection.
+    /// The resulting ray starts on the near plane.
+    /// Since only the projection matrix is inverted, this works for
+    /// perspective projections (rays diverge from the camera position) as
+    /// well as orthographic projections (rays are parallel to
+    /// [Self::forward]).
+    pub fn screen_to_ray(&self, pixel: (f32, f32), viewport: (u32, u32)) -> Ray {
+        let ndc_x = (2.0 * pixel.0) / viewport.0.max(1) as f32 - 1.0;
+        let ndc_y = 1.0 - (2.0 * pixel.1) / viewport.1.max(1) as f32;
+
+        let view_projection =
+            Self::OPENGL_TO_WGPU_MATRIX * self.projection_matrix() * self.view_matrix();
+        let Some(inverse) = view_projection.invert() else {
+            return Ray {
+                origin: self.position,
+                direction: self.forward(),
+            };
+        };
+
+        let unproject = |ndc_z: f32| {
+            let world = inverse * Vector4::new(ndc_x, ndc_y, ndc_z, 1.0);
+            Point3::from_vec(world.truncate() / world.w)
+        };
+        let near = unproject(0.0);
+        let far = unproject(1.0);
+
+        Ray {
+            origin: near,
+            direction: (far - near).normalize(),
+        }
+    }
 
     pub fn apply_change(&mut self, change: CameraTransform) {
         if let Some(mode) = change.pitch {
@@ -56,6 +207,21 @@ impl CameraDescriptor {
             }
         }
 
+        // Orthographic projections don't have a field of view
+        if let (Some(mode), Projection::Perspective { fovy, .. }) =
+            (change.fovy, &mut self.projection)
+        {
+            match mode {
+                Mode::Overwrite(new_fovy) => *fovy = new_fovy,
+                Mode::Offset(offset)
+                | Mode::OffsetViewAligned(offset)
+                | Mode::OffsetViewAlignedWithY(offset) => *fovy += offset,
+            }
+
+            // A field of view outside of (0, 180) degrees can't be projected
+            *fovy = fovy.clamp(Self::MIN_FOVY, Self::MAX_FOVY);
+        }
+
         if let Some(mode) = change.position {
             match mode {
                 Mode::Overwrite(position) => {
@@ -109,10 +275,11 @@ impl Default for CameraDescriptor {
             yaw: 0f32,
             pitch: 0f32,
             roll: 0f32,
-            aspect: 16.0 / 9.0,
-            fovy: 45.0,
+            projection: Projection::default(),
+            auto_aspect: true,
             near: 0.1,
             far: 10000.0,
+            auto_depth_range: false,
             global_gamma: Self::DEFAULT_GAMMA,
         }
     }
@@ -0,0 +1,46 @@
+use cgmath::{InnerSpace, Matrix, Matrix4, Vector3, Vector4};
+
+/// A view frustum described by six inward facing planes.
+///
+/// Each plane is stored as `(normal, distance)` in a [Vector4], such that a
+/// point `p` lies inside the plane if `normal.dot(p) + distance >= 0`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frustum {
+    /// Left, right, bottom, top, near and far plane, in that order.
+    pub planes: [Vector4<f32>; 6],
+}
+
+impl Frustum {
+    /// Extracts the frustum planes from a view-projection matrix as
+    /// produced by [CameraDescriptor::projection_matrix](super::CameraDescriptor::projection_matrix)
+    /// times [CameraDescriptor::view_matrix](super::CameraDescriptor::view_matrix).
+    ///
+    /// The matrix is expected to map into OpenGL style clip space
+    /// (`z` in `-1..1`), which is what cgmath produces.
+    pub fn from_view_projection(view_projection: Matrix4<f32>) -> Self {
+        let x = view_projection.row(0);
+        let y = view_projection.row(1);
+        let z = view_projection.row(2);
+        let w = view_projection.row(3);
+
+        Self {
+            planes: [w + x, w - x, w + y, w - y, w + z, w - z].map(Self::normalize_plane),
+        }
+    }
+
+    /// Checks whether the point is inside, or on the border of, the frustum.
+    pub fn contains_point(&self, point: Vector3<f32>) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.truncate().dot(point) + plane.w >= 0.0)
+    }
+
+    fn normalize_plane(plane: Vector4<f32>) -> Vector4<f32> {
+        let length = plane.truncate().magnitude();
+        if length > 0.0 {
+            plane / length
+        } else {
+            plane
+        }
+    }
+}
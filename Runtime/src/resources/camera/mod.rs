@@ -1,6 +1,6 @@
 use std::mem;
 
-use cgmath::{perspective, Deg, InnerSpace, Matrix, Matrix4, SquareMatrix, Vector3};
+use cgmath::{Matrix, Matrix4, SquareMatrix};
 use wgpu::{Buffer, BufferDescriptor, BufferUsages, Device, Queue};
 
 mod change;
@@ -12,6 +12,15 @@ pub use mode::*;
 mod descriptor;
 pub use descriptor::*;
 
+mod ray;
+pub use ray::*;
+
+mod frustum;
+pub use frustum::*;
+
+mod projection;
+pub use projection::*;
+
 #[cfg(test)]
 mod tests;
 
@@ -292,34 +301,14 @@ impl Camera {
     // }
 
     pub fn calculate_view_projection_matrix(&self, descriptor: &CameraDescriptor) -> Matrix4<f32> {
-        // Takes yaw and pitch values and converts them into a target vector for our camera.
-        let (pitch_sin, pitch_cos) = descriptor.pitch.sin_cos();
-        let (yaw_sin, yaw_cos) = descriptor.yaw.sin_cos();
-        let (roll_sin, roll_cos) = descriptor.roll.sin_cos();
-
-        // Calculate the forward, right, and up vectors
-        let forward = Vector3::new(pitch_cos * yaw_cos, pitch_sin, pitch_cos * yaw_sin).normalize();
-        let right = Vector3::new(-yaw_sin, 0.0, yaw_cos).normalize();
-        let up = right.cross(forward).normalize();
-
-        // Apply roll rotation to the up and right vectors
-        let rotated_right = right * roll_cos + up * roll_sin;
-        let rotated_up = -right * roll_sin + up * roll_cos;
-
-        // Calculates the view project matrix
-        Matrix4::look_to_rh(descriptor.position, forward, rotated_up)
+        descriptor.view_matrix()
     }
 
     pub fn calculate_perspective_projection_matrix(
         &self,
         descriptor: &CameraDescriptor,
     ) -> Matrix4<f32> {
-        perspective(
-            Deg(descriptor.fovy),
-            descriptor.aspect,
-            descriptor.near,
-            descriptor.far,
-        )
+        descriptor.projection_matrix()
     }
 
     pub fn camera_buffer(&self) -> &Buffer {
@@ -0,0 +1,103 @@
+use cgmath::{ortho, perspective, Deg, Matrix4};
+
+/// Defines how a camera projects the world onto the surface.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Projection {
+    /// Objects further away appear smaller, like with the human eye.
+    Perspective {
+        /// Vertical field of view in degrees.
+        fovy: f32,
+        /// Width divided by height of the view.
+        aspect: f32,
+    },
+    /// Objects keep their size regardless of their distance, e.g. for 2D
+    /// scenes, UI overlays or technical views.  
+    /// The bounds define the view volume in view space, i.e. relative to the
+    /// camera position.
+    Orthographic {
+        left: f32,
+        right: f32,
+        bottom: f32,
+        top: f32,
+    },
+}
+
+impl Projection {
+    /// An [Projection::Orthographic] projection centered on the camera,
+    /// `half_height` up and down and `half_height * aspect` left and right.
+    pub fn orthographic_centered(half_height: f32, aspect: f32) -> Self {
+        let half_width = half_height * aspect;
+
+        Self::Orthographic {
+            left: -half_width,
+            right: half_width,
+            bottom: -half_height,
+            top: half_height,
+        }
+    }
+
+    /// Calculates the projection matrix given the near and far plane.  
+    /// Note that this is in OpenGL style clip space (`z` in `-1..1`).
+    pub fn matrix(&self, near: f32, far: f32) -> Matrix4<f32> {
+        match *self {
+            Self::Perspective { fovy, aspect } => perspective(Deg(fovy), aspect, near, far),
+            Self::Orthographic {
+                left,
+                right,
+                bottom,
+                top,
+            } => ortho(left, right, bottom, top, near, far),
+        }
+    }
+
+    /// Width divided by height of the view.
+    pub fn aspect(&self) -> f32 {
+        match *self {
+            Self::Perspective { aspect, .. } => aspect,
+            Self::Orthographic {
+                left,
+                right,
+                bottom,
+                top,
+            } => (right - left) / (top - bottom),
+        }
+    }
+
+    /// Changes the aspect ratio.  
+    /// Orthographic projections keep their vertical bounds and horizontal
+    /// center, only the width changes.
+    pub fn set_aspect(&mut self, new_aspect: f32) {
+        match self {
+            Self::Perspective { aspect, .. } => *aspect = new_aspect,
+            Self::Orthographic {
+                left,
+                right,
+                bottom,
+                top,
+            } => {
+                let center = (*left + *right) / 2.0;
+                let half_width = (*top - *bottom) * new_aspect / 2.0;
+                *left = center - half_width;
+                *right = center + half_width;
+            }
+        }
+    }
+
+    /// Vertical field of view in degrees, if this is a perspective
+    /// projection.
+    pub fn fovy(&self) -> Option<f32> {
+        match *self {
+            Self::Perspective { fovy, .. } => Some(fovy),
+            Self::Orthographic { .. } => None,
+        }
+    }
+}
+
+impl Default for Projection {
+    fn default() -> Self {
+        Self::Perspective {
+            fovy: 45.0,
+            aspect: 16.0 / 9.0,
+        }
+    }
+}
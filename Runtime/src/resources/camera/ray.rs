@@ -0,0 +1,20 @@
+use cgmath::{Point3, Vector3};
+
+/// A half-line in world space, starting at [Ray::origin] and extending
+/// infinitely along [Ray::direction].
+///
+/// Usually created by [CameraDescriptor::screen_to_ray](super::CameraDescriptor::screen_to_ray)
+/// to pick objects under the cursor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ray {
+    pub origin: Point3<f32>,
+    /// Normalized direction of the ray.
+    pub direction: Vector3<f32>,
+}
+
+impl Ray {
+    /// Returns the point at distance `t` along the ray.
+    pub fn at(&self, t: f32) -> Point3<f32> {
+        self.origin + self.direction * t
+    }
+}
@@ -1,8 +1,8 @@
-use cgmath::{Point3, Vector3};
+use cgmath::{InnerSpace, Matrix4, Point3, Vector3, Vector4};
 
-use crate::{logging, wgpu_test_adapter};
+use crate::{logging, resources::BoundingBoxDescriptor, wgpu_test_adapter};
 
-use super::{Camera, CameraDescriptor, CameraTransform, Mode};
+use super::{Camera, CameraDescriptor, CameraTransform, Mode, Projection};
 
 #[test]
 fn realization_default() {
@@ -23,8 +23,13 @@ fn defaults() {
     assert_eq!(descriptor.position, Point3::new(0.0, 0.0, 0.0));
     assert_eq!(descriptor.yaw, 0f32);
     assert_eq!(descriptor.pitch, 0f32);
-    assert_eq!(descriptor.aspect, 16.0 / 9.0);
-    assert_eq!(descriptor.fovy, 45.0);
+    assert_eq!(
+        descriptor.projection,
+        Projection::Perspective {
+            fovy: 45.0,
+            aspect: 16.0 / 9.0
+        }
+    );
     assert_eq!(descriptor.near, 0.1);
     assert_eq!(descriptor.far, 10000.0);
     assert_eq!(descriptor.global_gamma, 2.2);
@@ -262,3 +267,204 @@ fn realization_change_roll_offset() {
     assert_eq!(to_be_changed_descriptor.yaw, original_descriptor.yaw);
     assert_eq!(to_be_changed_descriptor.roll, ROLL_OFFSET); // Should be the offset value since default is 0
 }
+
+#[test]
+fn screen_to_ray_center() {
+    let descriptor = CameraDescriptor {
+        position: Point3::new(1.0, 2.0, 3.0),
+        yaw: 0.7,
+        pitch: -0.3,
+        ..Default::default()
+    };
+
+    let ray = descriptor.screen_to_ray((960.0, 540.0), (1920, 1080));
+
+    assert!((ray.direction - descriptor.forward()).magnitude() < 1e-3);
+    // Origin is on the near plane in front of the camera
+    let offset = ray.origin - descriptor.position;
+    assert!((offset.magnitude() - descriptor.near).abs() < 1e-3);
+}
+
+#[test]
+fn screen_to_ray_corner() {
+    let descriptor = CameraDescriptor {
+        projection: Projection::Perspective {
+            fovy: 90.0,
+            aspect: 2.0,
+        },
+        ..Default::default()
+    };
+
+    // Default camera looks along +X with +Z to the right and +Y up.
+    // Top-left corner with a 90° vertical FOV and an aspect of 2 is one
+    // unit up and two units left per unit forward.
+    let ray = descriptor.screen_to_ray((0.0, 0.0), (200, 100));
+    let expected = Vector3::new(1.0, 1.0, -2.0).normalize();
+
+    assert!((ray.direction - expected).magnitude() < 1e-3);
+}
+
+#[test]
+fn orthographic_projection() {
+    let descriptor = CameraDescriptor {
+        projection: Projection::orthographic_centered(3.0, 2.0),
+        near: 1.0,
+        far: 11.0,
+        ..Default::default()
+    };
+
+    let projection = descriptor.projection_matrix();
+
+    // Corners of the view volume end up on the edges of clip space
+    let corner = projection * Vector4::new(6.0, 3.0, -1.0, 1.0);
+    assert!((corner - Vector4::new(1.0, 1.0, -1.0, 1.0)).magnitude() < 1e-5);
+    let far_corner = projection * Vector4::new(-6.0, -3.0, -11.0, 1.0);
+    assert!((far_corner - Vector4::new(-1.0, -1.0, 1.0, 1.0)).magnitude() < 1e-5);
+
+    // Rays are parallel to the view direction
+    let ray = descriptor.screen_to_ray((0.0, 0.0), (200, 100));
+    assert!((ray.direction - descriptor.forward()).magnitude() < 1e-3);
+}
+
+#[test]
+fn fovy_change() {
+    let mut descriptor = CameraDescriptor::default();
+
+    let change = CameraTransform {
+        fovy: Some(Mode::Offset(-15.0)),
+        ..Default::default()
+    };
+    assert!(change.is_introducing_change());
+    descriptor.apply_change(change);
+    assert_eq!(descriptor.projection.fovy(), Some(45.0 - 15.0));
+
+    descriptor.apply_change(CameraTransform {
+        fovy: Some(Mode::Overwrite(500.0)),
+        ..Default::default()
+    });
+    assert_eq!(
+        descriptor.projection.fovy(),
+        Some(CameraDescriptor::MAX_FOVY)
+    );
+
+    // Orthographic projections have no field of view to change
+    let mut descriptor = CameraDescriptor {
+        projection: Projection::orthographic_centered(1.0, 1.0),
+        ..Default::default()
+    };
+    descriptor.apply_change(CameraTransform {
+        fovy: Some(Mode::Overwrite(60.0)),
+        ..Default::default()
+    });
+    assert_eq!(
+        descriptor.projection,
+        Projection::orthographic_centered(1.0, 1.0)
+    );
+}
+
+/// Compares two matrices component-wise.
+fn assert_matrix_eq(actual: Matrix4<f32>, expected: Matrix4<f32>) {
+    let actual: &[f32; 16] = actual.as_ref();
+    let expected: &[f32; 16] = expected.as_ref();
+    for (a, e) in actual.iter().zip(expected) {
+        assert!((a - e).abs() < 1e-5, "{actual:?} != {expected:?}");
+    }
+}
+
+#[test]
+fn perspective_projection_matrix() {
+    let projection = Projection::Perspective {
+        fovy: 90.0,
+        aspect: 2.0,
+    };
+
+    // f = 1 / tan(fovy / 2) = 1 for 90°, near 1, far 3
+    #[rustfmt::skip]
+    let expected = Matrix4::new(
+        0.5, 0.0, 0.0, 0.0,
+        0.0, 1.0, 0.0, 0.0,
+        0.0, 0.0, -2.0, -1.0,
+        0.0, 0.0, -3.0, 0.0,
+    );
+    assert_matrix_eq(projection.matrix(1.0, 3.0), expected);
+}
+
+#[test]
+fn orthographic_projection_matrix() {
+    let projection = Projection::Orthographic {
+        left: -1.0,
+        right: 3.0,
+        bottom: -2.0,
+        top: 2.0,
+    };
+
+    // Width 4, height 4, depth 2 (near 1, far 3), shifted 1 to the right
+    #[rustfmt::skip]
+    let expected = Matrix4::new(
+        0.5, 0.0, 0.0, 0.0,
+        0.0, 0.5, 0.0, 0.0,
+        0.0, 0.0, -1.0, 0.0,
+        -0.5, 0.0, -2.0, 1.0,
+    );
+    assert_matrix_eq(projection.matrix(1.0, 3.0), expected);
+}
+
+#[test]
+fn projection_aspect() {
+    let mut perspective = Projection::default();
+    perspective.set_aspect(2.0);
+    assert_eq!(perspective.aspect(), 2.0);
+
+    // Orthographic keeps its height and horizontal center
+    let mut orthographic = Projection::Orthographic {
+        left: 0.0,
+        right: 2.0,
+        bottom: -1.0,
+        top: 1.0,
+    };
+    assert_eq!(orthographic.aspect(), 1.0);
+    orthographic.set_aspect(2.0);
+    assert_eq!(
+        orthographic,
+        Projection::Orthographic {
+            left: -1.0,
+            right: 3.0,
+            bottom: -1.0,
+            top: 1.0,
+        }
+    );
+}
+
+#[test]
+fn fit_depth_range() {
+    // Looking along +X
+    let mut camera = CameraDescriptor::default();
+    let scene =
+        BoundingBoxDescriptor::new(Point3::new(10.0, -1.0, -1.0), Point3::new(50.0, 1.0, 1.0));
+
+    assert!(camera.fit_depth_range(&scene));
+    assert!(camera.near <= 10.0 && camera.near > 9.0);
+    assert!(camera.far >= 50.0 && camera.far < 51.0);
+    // Fitting again changes nothing
+    assert!(!camera.fit_depth_range(&scene));
+
+    // Surrounding the camera, the near plane is clamped
+    let surrounding =
+        BoundingBoxDescriptor::new(Point3::new(-5.0, -5.0, -5.0), Point3::new(5.0, 5.0, 5.0));
+    assert!(camera.fit_depth_range(&surrounding));
+    assert_eq!(camera.near, CameraDescriptor::MIN_AUTO_NEAR);
+    assert!(camera.far >= 5.0);
+
+    // Huge scenes are limited to a sane depth ratio
+    let huge =
+        BoundingBoxDescriptor::new(Point3::new(-1.0, -1.0, -1.0), Point3::new(1.0e7, 1.0, 1.0));
+    assert!(camera.fit_depth_range(&huge));
+    assert!(camera.far / camera.near <= CameraDescriptor::MAX_AUTO_DEPTH_RATIO * 1.001);
+
+    // Scenes behind the camera keep the depth range as-is
+    let (near, far) = (camera.near, camera.far);
+    let behind =
+        BoundingBoxDescriptor::new(Point3::new(-50.0, -1.0, -1.0), Point3::new(-10.0, 1.0, 1.0));
+    assert!(!camera.fit_depth_range(&behind));
+    assert_eq!((camera.near, camera.far), (near, far));
+}
@@ -1,4 +1,4 @@
-use wgpu::{PolygonMode, PrimitiveTopology};
+use wgpu::{BlendState, CompareFunction, DepthBiasState, PolygonMode, PrimitiveTopology};
 
 use crate::resources::{MaterialShaderDescriptor, ShaderSource, VertexStageLayout};
 
@@ -20,7 +20,13 @@ impl From<DebugMaterialShader> for MaterialShaderDescriptor {
             front_face_order: wgpu::FrontFace::Ccw,
             cull_mode: None,
             polygon_mode: PolygonMode::Line,
+            blend: Some(BlendState::REPLACE),
             depth_stencil: true,
+            depth_bias: DepthBiasState::default(),
+            depth_compare: CompareFunction::Less,
+            depth_write_enabled: true,
+            output_transform: None,
+            defines: Vec::new(),
         }
     }
 }
@@ -4,6 +4,9 @@ use cgmath::{Vector3, Zero};
 pub enum LightType {
     Point {
         intensity: f32,
+        /// Distance after which the light has no effect anymore.
+        /// If `None`, the light reaches infinitely far.
+        range: Option<f32>,
     },
     Directional {
         intensity: f32,
@@ -12,6 +15,9 @@ pub enum LightType {
         intensity: f32,
         inner_cone_angle: f32,
         outer_cone_angle: f32,
+        /// Distance after which the light has no effect anymore.
+        /// If `None`, the light reaches infinitely far.
+        range: Option<f32>,
     },
 }
 
@@ -33,7 +39,10 @@ impl LightDescriptor {
     ) -> Self {
         Self {
             label,
-            light_type: LightType::Point { intensity },
+            light_type: LightType::Point {
+                intensity,
+                range: None,
+            },
             color,
             position,
             direction: Vector3::zero(),
@@ -70,6 +79,7 @@ impl LightDescriptor {
                 intensity,
                 inner_cone_angle,
                 outer_cone_angle,
+                range: None,
             },
             color,
             position,
@@ -77,10 +87,66 @@ impl LightDescriptor {
         }
     }
 
+    /// Sets the range of a point or spot light.
+    /// Directional lights are unaffected.
+    pub fn with_range(mut self, range: f32) -> Self {
+        match &mut self.light_type {
+            LightType::Point { range: x, .. } | LightType::Spot { range: x, .. } => {
+                *x = Some(range)
+            }
+            LightType::Directional { .. } => (),
+        }
+        self
+    }
+
     pub fn label(&self) -> &str {
         &self.label
     }
 
+    pub fn intensity(&self) -> f32 {
+        match &self.light_type {
+            LightType::Point { intensity, .. }
+            | LightType::Directional { intensity }
+            | LightType::Spot { intensity, .. } => *intensity,
+        }
+    }
+
+    /// The perceived brightness of this light, i.e. the luminance of its
+    /// color scaled by its intensity.
+    pub fn brightness(&self) -> f32 {
+        (0.2126 * self.color.x + 0.7152 * self.color.y + 0.0722 * self.color.z) * self.intensity()
+    }
+
+    pub fn range(&self) -> Option<f32> {
+        match &self.light_type {
+            LightType::Point { range, .. } | LightType::Spot { range, .. } => *range,
+            LightType::Directional { .. } => None,
+        }
+    }
+
+    /// Calculates the distance attenuation of this light.
+    /// Mirrors the attenuation applied in the PBR shader.
+    ///
+    /// Point and spot lights fall off by the inverse-square law.
+    /// If a range is set, the falloff is smoothly windowed to reach zero at
+    /// the range, as recommended by the glTF `KHR_lights_punctual` extension.
+    /// Directional lights aren't attenuated.
+    pub fn attenuation(&self, distance: f32) -> f32 {
+        if let LightType::Directional { .. } = self.light_type {
+            return 1.0;
+        }
+
+        let inverse_square = 1.0 / distance.powi(2).max(0.0001);
+
+        match self.range() {
+            Some(range) if range > 0.0 => {
+                let window = (1.0 - (distance / range).powi(4)).clamp(0.0, 1.0);
+                inverse_square * window.powi(2)
+            }
+            _ => inverse_square,
+        }
+    }
+
     pub fn to_buffer_data(&self) -> Vec<u8> {
         let mut data = Vec::new();
 
@@ -96,12 +162,7 @@ impl LightDescriptor {
         data.extend_from_slice(&self.color.x.to_le_bytes());
         data.extend_from_slice(&self.color.y.to_le_bytes());
         data.extend_from_slice(&self.color.z.to_le_bytes());
-        let intensity = match &self.light_type {
-            LightType::Point { intensity } => *intensity,
-            LightType::Directional { intensity } => *intensity,
-            LightType::Spot { intensity, .. } => *intensity,
-        };
-        data.extend_from_slice(&intensity.to_le_bytes()); // Intensity
+        data.extend_from_slice(&self.intensity().to_le_bytes()); // Intensity
 
         // Direction (vec4) - 16 bytes
         // xyz: direction, w: type
@@ -116,12 +177,13 @@ impl LightDescriptor {
         data.extend_from_slice(&light_type_value.to_le_bytes()); // Light type
 
         // Params (vec4) - 16 bytes
-        // x: inner cone angle, y: outer cone angle, zw: padding
+        // x: inner cone angle, y: outer cone angle, z: range (0 = infinite), w: padding
+        let range = self.range().unwrap_or(0.0);
         match &self.light_type {
             LightType::Point { .. } => {
                 data.extend_from_slice(&0f32.to_le_bytes()); // Inner cone angle
                 data.extend_from_slice(&0f32.to_le_bytes()); // Outer cone angle
-                data.extend_from_slice(&0f32.to_le_bytes()); // Padding
+                data.extend_from_slice(&range.to_le_bytes()); // Range
                 data.extend_from_slice(&0f32.to_le_bytes()); // Padding
             }
             LightType::Directional { .. } => {
@@ -137,7 +199,7 @@ impl LightDescriptor {
             } => {
                 data.extend_from_slice(&inner_cone_angle.to_le_bytes()); // Inner cone angle
                 data.extend_from_slice(&outer_cone_angle.to_le_bytes()); // Outer cone angle
-                data.extend_from_slice(&0f32.to_le_bytes()); // Padding
+                data.extend_from_slice(&range.to_le_bytes()); // Range
                 data.extend_from_slice(&0f32.to_le_bytes()); // Padding
             }
         }
@@ -150,7 +212,10 @@ impl Default for LightDescriptor {
     fn default() -> Self {
         Self {
             label: "Default Light".to_string(),
-            light_type: LightType::Point { intensity: 1.0 },
+            light_type: LightType::Point {
+                intensity: 1.0,
+                range: None,
+            },
             color: Vector3::new(1.0, 1.0, 1.0),
             position: Vector3::zero(),
             direction: Vector3::new(0.0, -1.0, 0.0),
@@ -0,0 +1,51 @@
+use cgmath::Vector3;
+
+use crate::resources::LightDescriptor;
+
+fn point_light() -> LightDescriptor {
+    LightDescriptor::new_point(
+        "Point".to_string(),
+        Vector3::new(0.0, 0.0, 0.0),
+        Vector3::new(1.0, 1.0, 1.0),
+        1.0,
+    )
+}
+
+#[test]
+fn attenuation_is_zero_beyond_range() {
+    let light = point_light().with_range(10.0);
+
+    assert_eq!(light.attenuation(10.0), 0.0);
+    assert_eq!(light.attenuation(10.5), 0.0);
+    assert_eq!(light.attenuation(100.0), 0.0);
+    assert!(light.attenuation(9.0) > 0.0);
+}
+
+#[test]
+fn attenuation_follows_inverse_square_within_range() {
+    let light = point_light().with_range(1000.0);
+
+    let near = light.attenuation(1.0);
+    let far = light.attenuation(2.0);
+
+    assert!((near - 1.0).abs() < 0.001);
+    assert!((near / far - 4.0).abs() < 0.001);
+}
+
+#[test]
+fn attenuation_without_range_is_inverse_square() {
+    let light = point_light();
+
+    assert_eq!(light.range(), None);
+    assert_eq!(light.attenuation(2.0), 0.25);
+    assert_eq!(light.attenuation(100.0), 0.0001);
+}
+
+#[test]
+fn range_is_written_to_buffer() {
+    let data = point_light().with_range(7.5).to_buffer_data();
+
+    assert_eq!(data.len(), 64);
+    // Params.z
+    assert_eq!(&data[56..60], &7.5f32.to_le_bytes());
+}
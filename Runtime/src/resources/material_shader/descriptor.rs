@@ -1,6 +1,11 @@
-use wgpu::{Face, FrontFace, PolygonMode, PrimitiveTopology, ShaderStages};
+use wgpu::{
+    BlendState, CompareFunction, DepthBiasState, Face, FrontFace, PolygonMode, PrimitiveTopology,
+    ShaderStages, TextureFormat,
+};
 
-use crate::resources::{ShaderDescriptor, ShaderSource, VariableType, VertexStageLayout};
+use crate::resources::{
+    OutputTransform, ShaderDescriptor, ShaderError, ShaderSource, VariableType, VertexStageLayout,
+};
 
 pub type MaterialDescriptor = MaterialShaderDescriptor;
 
@@ -16,7 +21,97 @@ pub struct MaterialShaderDescriptor {
     pub front_face_order: FrontFace,
     pub cull_mode: Option<Face>,
     pub polygon_mode: PolygonMode,
+    /// Blending of the fragment output with the render target.
+    /// `None` overwrites the target, same as [`BlendState::REPLACE`].
+    pub blend: Option<BlendState>,
     pub depth_stencil: bool,
+    /// Offsets the depth of each fragment, e.g. to pull decals towards the
+    /// camera and prevent z-fighting with coplanar geometry.
+    /// Only has an effect if `depth_stencil` is enabled.
+    ///
+    /// Check [`DepthBiasState`] for details.
+    /// Note that a non-zero `clamp` requires the `DEPTH_BIAS_CLAMP` downlevel
+    /// flag, which isn't supported on all backends (e.g. OpenGL).
+    pub depth_bias: DepthBiasState,
+    /// Comparison of a fragment's depth against the depth buffer.
+    /// Only has an effect if `depth_stencil` is enabled.
+    ///
+    /// Defaults to [`CompareFunction::Less`].
+    /// Skyboxes, which are drawn at the far plane, may want
+    /// [`CompareFunction::LessEqual`].
+    pub depth_compare: CompareFunction,
+    /// Whether fragments passing the depth test write their depth.
+    /// Only has an effect if `depth_stencil` is enabled.
+    ///
+    /// Disable this for transparent geometry, skyboxes or overlays that
+    /// shouldn't occlude anything drawn after them.
+    pub depth_write_enabled: bool,
+    /// Overrides the [OutputTransform] shaders importing
+    /// [OutputTransform::IMPORT_DIRECTIVE] apply.
+    /// If `None`, the transform is picked based on the surface format.
+    ///
+    /// Check [OutputTransform::for_format] for details.
+    pub output_transform: Option<OutputTransform>,
+    /// Names defined when preprocessing the shader, enabling the matching
+    /// `#ifdef` blocks, e.g. for optional features of a shader.
+    pub defines: Vec<&'static str>,
+}
+
+impl MaterialShaderDescriptor {
+    /// Name defined for shaders of skinned materials.
+    pub const SKINNED_DEFINE: &'static str = "SKINNED";
+
+    /// Whether this material uses [VertexStageLayout::SkinnedVertexData].
+    pub fn is_skinned(&self) -> bool {
+        self.vertex_stage_layouts
+            .as_ref()
+            .is_some_and(|layouts| layouts.contains(&VertexStageLayout::SkinnedVertexData))
+    }
+
+    /// Turns this material into a skinned material by replacing
+    /// [VertexStageLayout::ComplexVertexData] with
+    /// [VertexStageLayout::SkinnedVertexData].
+    ///
+    /// Skinned materials have [Self::SKINNED_DEFINE] defined and expect the
+    /// joint palette of a [Skin](crate::resources::Skin) bound.
+    pub fn skinned(mut self) -> Self {
+        if let Some(layouts) = self.vertex_stage_layouts.as_mut() {
+            for layout in layouts {
+                if *layout == VertexStageLayout::ComplexVertexData {
+                    *layout = VertexStageLayout::SkinnedVertexData;
+                }
+            }
+        }
+
+        self
+    }
+
+    /// The [OutputTransform] applied when rendering to `surface_format`.
+    pub fn output_transform_for_format(&self, surface_format: TextureFormat) -> OutputTransform {
+        self.output_transform
+            .unwrap_or_else(|| OutputTransform::for_format(surface_format))
+    }
+
+    /// Preprocesses the shader source with the [OutputTransform] matching
+    /// `surface_format` available as import.
+    pub fn preprocessed_source_for_format(
+        &self,
+        surface_format: TextureFormat,
+    ) -> Result<String, ShaderError> {
+        let mut preprocessor = self.preprocessor()?;
+        preprocessor.add_import(
+            OutputTransform::IMPORT_DIRECTIVE,
+            self.output_transform_for_format(surface_format).wgsl(),
+        );
+        if self.is_skinned() {
+            preprocessor.add_define(Self::SKINNED_DEFINE);
+        }
+        for define in &self.defines {
+            preprocessor.add_define(*define);
+        }
+
+        self.preprocessed_source_with(&preprocessor)
+    }
 }
 
 impl ShaderDescriptor for MaterialShaderDescriptor {
@@ -53,7 +148,13 @@ impl Default for MaterialShaderDescriptor {
             front_face_order: FrontFace::Ccw,
             cull_mode: Some(Face::Front),
             polygon_mode: PolygonMode::Fill,
+            blend: Some(BlendState::REPLACE),
             depth_stencil: true,
+            depth_bias: DepthBiasState::default(),
+            depth_compare: CompareFunction::Less,
+            depth_write_enabled: true,
+            output_transform: None,
+            defines: Vec::new(),
         }
     }
 }
@@ -2,12 +2,14 @@ use std::error::Error;
 use std::sync::OnceLock;
 
 use wgpu::{
-    BindGroup, BlendState, ColorTargetState, ColorWrites, CompareFunction, DepthStencilState,
-    Device, FragmentState, PipelineLayoutDescriptor, PrimitiveState, Queue, RenderPipeline,
-    RenderPipelineDescriptor, TextureFormat, VertexState,
+    naga::ShaderStage, BindGroup, BindGroupDescriptor, BindGroupLayout, BindGroupLayoutDescriptor,
+    ColorTargetState, ColorWrites, DepthStencilState, Device, FragmentState,
+    PipelineLayoutDescriptor, PrimitiveState, Queue, RenderPipeline, RenderPipelineDescriptor,
+    TextureFormat, VertexState,
 };
 
 pub use crate::resources::shader::{ShaderDescriptor, ShaderError, Variables};
+use crate::resources::{validate_shader_bindings, validate_shader_entry_points, Skin};
 use crate::world::World;
 
 mod descriptor;
@@ -16,6 +18,12 @@ pub use descriptor::*;
 mod vertex_stage_layout;
 pub use vertex_stage_layout::*;
 
+mod output_transform;
+pub use output_transform::*;
+
+mod pipeline_info;
+pub use pipeline_info::*;
+
 #[cfg(test)]
 mod tests;
 
@@ -24,6 +32,8 @@ pub struct MaterialShader {
     pipeline: RenderPipeline,
     bind_group: Option<BindGroup>,
     variables: Option<Variables>,
+    depth_stencil: Option<DepthStencilState>,
+    info: PipelineInfo,
 }
 
 impl MaterialShader {
@@ -38,19 +48,57 @@ impl MaterialShader {
         device: &Device,
         queue: &Queue,
     ) -> Result<Self, Box<dyn Error>> {
-        let shader_module = descriptor.shader_module(device)?;
+        let surface_format = surface_format.unwrap_or(TextureFormat::Rgba8UnormSrgb);
+        let preprocessed_source = descriptor.preprocessed_source_for_format(surface_format)?;
+
+        validate_shader_entry_points(
+            &preprocessed_source,
+            &[
+                (descriptor.entrypoint_vertex, ShaderStage::Vertex),
+                (descriptor.entrypoint_fragment, ShaderStage::Fragment),
+            ],
+        )?;
+
+        // Check the material bindings up-front, as a mismatch would otherwise
+        // only surface as an opaque pipeline creation error.
+        // Skinned materials additionally get the joint palette bound.
+        let highest_group = if descriptor.is_skinned() {
+            Skin::BIND_GROUP_INDEX
+        } else {
+            1
+        };
+        validate_shader_bindings(
+            &preprocessed_source,
+            1,
+            highest_group,
+            &descriptor.variables,
+        )?;
+        let shader_module =
+            descriptor.shader_module_from_source(device, preprocessed_source.clone());
 
         let engine_bind_group_layout_once = OnceLock::new();
         let engine_bind_group_layout = engine_bind_group_layout_once
             .get_or_init(|| World::make_world_bind_group_layout(device));
 
         // Create a pipeline layout and bind group
-        let bind_group_option = descriptor.bind_group(device, queue)?;
+        let mut bind_group_option = descriptor.bind_group(device, queue)?;
+
+        // Skinned materials expect the joint palette after the material
+        // bind group, thus an empty one is needed if there are no variables.
+        let skin_bind_group_layout = descriptor
+            .is_skinned()
+            .then(|| Skin::make_bind_group_layout(device));
+        if skin_bind_group_layout.is_some() && bind_group_option.is_none() {
+            bind_group_option = Some(Self::make_empty_bind_group(device));
+        }
 
         let mut bind_group_layouts = vec![engine_bind_group_layout];
         if let Some((_, layout, _)) = bind_group_option.as_ref() {
             bind_group_layouts.push(layout);
         }
+        if let Some(layout) = skin_bind_group_layout.as_ref() {
+            bind_group_layouts.push(layout);
+        }
 
         let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
             label: descriptor.name.as_deref(),
@@ -73,18 +121,18 @@ impl MaterialShader {
         let depth_stencil = if descriptor.depth_stencil {
             Some(DepthStencilState {
                 format: TextureFormat::Depth32Float,
-                depth_write_enabled: true,
-                depth_compare: CompareFunction::Less,
+                depth_write_enabled: descriptor.depth_write_enabled,
+                depth_compare: descriptor.depth_compare,
                 stencil: Default::default(),
-                bias: Default::default(),
+                bias: descriptor.depth_bias,
             })
         } else {
             None
         };
 
         let targets = [Some(ColorTargetState {
-            format: surface_format.unwrap_or(TextureFormat::Rgba8UnormSrgb),
-            blend: Some(BlendState::REPLACE),
+            format: surface_format,
+            blend: descriptor.blend,
             write_mask: ColorWrites::ALL,
         })];
 
@@ -105,7 +153,7 @@ impl MaterialShader {
                 targets: &targets,
                 compilation_options: Default::default(),
             }),
-            depth_stencil,
+            depth_stencil: depth_stencil.clone(),
             primitive: PrimitiveState {
                 topology: descriptor.primitive_topology,
                 strip_index_format: None,
@@ -125,14 +173,41 @@ impl MaterialShader {
             None => (None, None),
         };
 
+        let info = PipelineInfo {
+            label: descriptor.name(),
+            source: preprocessed_source,
+            surface_format,
+            bind_group_layout: variables
+                .as_ref()
+                .map(|variables| descriptor.bind_group_layout_entries(variables))
+                .transpose()?
+                .unwrap_or_default(),
+        };
+
         let pipeline = device.create_render_pipeline(&pipeline_desc);
         Ok(Self {
             pipeline,
             bind_group,
             variables,
+            depth_stencil,
+            info,
         })
     }
 
+    fn make_empty_bind_group(device: &Device) -> (BindGroup, BindGroupLayout, Variables) {
+        let layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("Empty Material BindGroup Layout"),
+            entries: &[],
+        });
+        let bind_group = device.create_bind_group(&BindGroupDescriptor {
+            label: Some("Empty Material BindGroup"),
+            layout: &layout,
+            entries: &[],
+        });
+
+        (bind_group, layout, Variables::new())
+    }
+
     pub fn pipeline(&self) -> &RenderPipeline {
         &self.pipeline
     }
@@ -144,4 +219,15 @@ impl MaterialShader {
     pub fn variables(&self) -> Option<&Variables> {
         self.variables.as_ref()
     }
+
+    /// Metadata about this pipeline, e.g. its preprocessed source.
+    pub fn info(&self) -> &PipelineInfo {
+        &self.info
+    }
+
+    /// The depth stencil state the pipeline got created with.
+    /// `None` if depth testing is disabled.
+    pub fn depth_stencil(&self) -> Option<&DepthStencilState> {
+        self.depth_stencil.as_ref()
+    }
 }
@@ -0,0 +1,75 @@
+use std::hash::{Hash, Hasher};
+
+use wgpu::TextureFormat;
+
+/// Transform applied to the final color of a material before it's written
+/// to the surface.
+///
+/// Shaders opt into it by importing [OutputTransform::IMPORT_DIRECTIVE] and
+/// passing their final color through `output_transform(color)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputTransform {
+    /// The color is written as-is.
+    /// Correct for sRGB surfaces, which encode on write, and float (HDR)
+    /// surfaces, which expect linear colors.
+    None,
+    /// The color is encoded with the sRGB transfer function.
+    /// Correct for linear (non-sRGB) 8/10-bit surfaces.
+    SrgbEncode,
+    /// The color is encoded with a plain `1.0 / gamma` power curve.
+    /// Meant as a manual override for displays or surfaces that don't behave
+    /// as their format suggests.
+    Gamma(f32),
+}
+
+impl OutputTransform {
+    /// Name of the shader import providing `output_transform`.
+    /// Use it as `#import <output_transform>`.
+    pub const IMPORT_DIRECTIVE: &'static str = "output_transform";
+
+    /// Picks the correct transform for a surface format.
+    /// Linear formats that have an sRGB counterpart (e.g.
+    /// [TextureFormat::Bgra8Unorm]) and [TextureFormat::Rgb10a2Unorm] need a
+    /// manual sRGB encode. Everything else is left untouched.
+    pub fn for_format(format: TextureFormat) -> Self {
+        if format.is_srgb() {
+            return Self::None;
+        }
+
+        if format.add_srgb_suffix() != format || format == TextureFormat::Rgb10a2Unorm {
+            Self::SrgbEncode
+        } else {
+            Self::None
+        }
+    }
+
+    /// Generates the WGSL source of the `output_transform` function.
+    pub fn wgsl(&self) -> String {
+        let body = match self {
+            OutputTransform::None => "    return color;".to_string(),
+            OutputTransform::SrgbEncode => "    // sRGB encode
+    let c = clamp(color, vec3<f32>(0.0), vec3<f32>(1.0));
+    let low = c * 12.92;
+    let high = 1.055 * pow(c, vec3<f32>(1.0 / 2.4)) - vec3<f32>(0.055);
+    return select(high, low, c <= vec3<f32>(0.0031308));"
+                .to_string(),
+            OutputTransform::Gamma(gamma) => format!(
+                "    // Gamma encode
+    return pow(max(color, vec3<f32>(0.0)), vec3<f32>(1.0 / {gamma:?}));"
+            ),
+        };
+
+        format!("fn output_transform(color: vec3<f32>) -> vec3<f32> {{\n{body}\n}}\n")
+    }
+}
+
+impl Eq for OutputTransform {}
+
+impl Hash for OutputTransform {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        core::mem::discriminant(self).hash(state);
+        if let OutputTransform::Gamma(gamma) = self {
+            gamma.to_bits().hash(state);
+        }
+    }
+}
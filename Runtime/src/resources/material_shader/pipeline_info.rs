@@ -0,0 +1,25 @@
+use wgpu::{BindGroupLayoutEntry, TextureFormat};
+
+/// Read-only metadata of a realized [MaterialShader](super::MaterialShader),
+/// meant for debugging and inspection tools.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PipelineInfo {
+    /// Label of the pipeline, taken from the descriptor's name.
+    pub label: Option<String>,
+    /// The preprocessed WGSL source the pipeline got compiled from.
+    pub source: String,
+    /// The surface format the pipeline renders to.
+    pub surface_format: TextureFormat,
+    /// The layout of the material's bind group (group 1).
+    /// Empty if the material has no variables.
+    pub bind_group_layout: Vec<BindGroupLayoutEntry>,
+}
+
+impl PipelineInfo {
+    /// Whether both pipelines got compiled from the same source and share
+    /// the same bindings, i.e. are likely duplicates only differing in
+    /// pipeline state or variable contents.
+    pub fn is_similar_to(&self, other: &PipelineInfo) -> bool {
+        self.source == other.source && self.bind_group_layout == other.bind_group_layout
+    }
+}
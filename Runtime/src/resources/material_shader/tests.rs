@@ -1,10 +1,57 @@
-use wgpu::TextureFormat;
+use std::sync::Arc;
+
+use wgpu::{
+    naga::ShaderStage, CompareFunction, DepthBiasState, SamplerBindingType, TextureFormat,
+    TextureSampleType,
+};
 
 use crate::{
-    resources::{MaterialDescriptor, MaterialShader, MaterialShaderDescriptor},
+    resources::{
+        BufferDescriptor, MaterialDescriptor, MaterialShader, MaterialShaderDescriptor,
+        OutputTransform, ShaderError, ShaderSource, TextureDescriptor, VariableType,
+    },
     wgpu_test_adapter,
 };
 
+const TEXTURED_SHADER: &str = "
+@group(1) @binding(0) var albedo_texture: texture_2d<f32>;
+@group(1) @binding(1) var albedo_sampler: sampler;
+
+@vertex
+fn entrypoint_vertex(@builtin(vertex_index) vertex_index: u32) -> @builtin(position) vec4<f32> {
+    return vec4<f32>(f32(vertex_index), 0.0, 0.0, 1.0);
+}
+
+@fragment
+fn entrypoint_fragment() -> @location(0) vec4<f32> {
+    return textureSample(albedo_texture, albedo_sampler, vec2<f32>(0.0));
+}
+";
+
+fn texture_variable() -> VariableType {
+    VariableType::Texture {
+        descriptor: Arc::new(TextureDescriptor::uniform_luma_black()),
+        sample_type: TextureSampleType::Float { filterable: true },
+        sampler_binding_type: SamplerBindingType::Filtering,
+    }
+}
+
+fn realize_textured(variables: Vec<VariableType>) -> Result<MaterialShader, ShaderError> {
+    let (_adapter, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+
+    let descriptor = MaterialShaderDescriptor {
+        shader_source: ShaderSource::String(TEXTURED_SHADER),
+        variables,
+        vertex_stage_layouts: None,
+        ..Default::default()
+    };
+
+    MaterialShader::from_descriptor(&descriptor, None, &device, &queue).map_err(|e| {
+        *e.downcast::<ShaderError>()
+            .expect("Expected realization to fail with a shader error!")
+    })
+}
+
 #[test]
 fn default_realization() {
     let (_adapter, device, queue) = wgpu_test_adapter::make_wgpu_connection();
@@ -28,3 +75,256 @@ fn realization_custom_texture_format() {
 fn alias_material_descriptor() {
     let _ = MaterialDescriptor::default();
 }
+
+#[test]
+fn realization_depth_bias() {
+    let (_adapter, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+
+    let depth_bias = DepthBiasState {
+        constant: -2,
+        slope_scale: -1.5,
+        // A non-zero clamp isn't supported by all backends
+        clamp: 0.0,
+    };
+    let descriptor = MaterialShaderDescriptor {
+        depth_bias,
+        ..Default::default()
+    };
+
+    let material_shader = MaterialShader::from_descriptor(&descriptor, None, &device, &queue)
+        .expect("Failed turning material shader descriptor into render pipeline!");
+
+    let depth_stencil = material_shader
+        .depth_stencil()
+        .expect("Depth stencil expected to be enabled");
+    assert_eq!(depth_stencil.bias, depth_bias);
+}
+
+#[test]
+fn realization_depth_compare_and_write() {
+    let (_adapter, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+
+    let descriptor = MaterialShaderDescriptor {
+        depth_compare: CompareFunction::LessEqual,
+        depth_write_enabled: false,
+        ..Default::default()
+    };
+
+    let material_shader = MaterialShader::from_descriptor(&descriptor, None, &device, &queue)
+        .expect("Failed turning material shader descriptor into render pipeline!");
+
+    let depth_stencil = material_shader
+        .depth_stencil()
+        .expect("Depth stencil expected to be enabled");
+    assert_eq!(depth_stencil.depth_compare, CompareFunction::LessEqual);
+    assert!(!depth_stencil.depth_write_enabled);
+}
+
+#[test]
+fn realization_matching_bindings() {
+    let material_shader = realize_textured(vec![texture_variable()])
+        .expect("Failed realizing material shader with matching bindings!");
+
+    // Texture + Sampler
+    assert_eq!(material_shader.info().bind_group_layout.len(), 2);
+}
+
+#[test]
+fn realization_binding_missing_in_shader() {
+    let result = realize_textured(vec![
+        texture_variable(),
+        VariableType::Buffer(BufferDescriptor::default()),
+    ]);
+
+    match result {
+        Err(ShaderError::BindingMismatch {
+            group,
+            binding,
+            reason,
+        }) => {
+            assert_eq!(group, 1);
+            assert_eq!(binding, 2);
+            assert!(reason.contains("uniform buffer"));
+        }
+        x => panic!("Expected a binding mismatch, got: {x:?}"),
+    }
+}
+
+#[test]
+fn realization_binding_missing_in_variables() {
+    match realize_textured(Vec::new()) {
+        Err(ShaderError::BindingMismatch { group, binding, .. }) => {
+            assert_eq!(group, 1);
+            assert_eq!(binding, 0);
+        }
+        x => panic!("Expected a binding mismatch, got: {x:?}"),
+    }
+}
+
+#[test]
+fn realization_binding_kind_mismatch() {
+    match realize_textured(vec![VariableType::Buffer(BufferDescriptor::default())]) {
+        Err(ShaderError::BindingMismatch {
+            group,
+            binding,
+            reason,
+        }) => {
+            assert_eq!(group, 1);
+            assert_eq!(binding, 0);
+            assert!(reason.contains("texture"));
+        }
+        x => panic!("Expected a binding mismatch, got: {x:?}"),
+    }
+}
+
+const VERTEX_ONLY_SHADER: &str = "
+@vertex
+fn entrypoint_vertex(@builtin(vertex_index) vertex_index: u32) -> @builtin(position) vec4<f32> {
+    return vec4<f32>(f32(vertex_index), 0.0, 0.0, 1.0);
+}
+";
+
+fn realize_entry_points(
+    shader: &'static str,
+    entrypoint_vertex: &'static str,
+    entrypoint_fragment: &'static str,
+) -> Result<MaterialShader, ShaderError> {
+    let (_adapter, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+
+    let descriptor = MaterialShaderDescriptor {
+        shader_source: ShaderSource::String(shader),
+        entrypoint_vertex,
+        entrypoint_fragment,
+        vertex_stage_layouts: None,
+        ..Default::default()
+    };
+
+    MaterialShader::from_descriptor(&descriptor, None, &device, &queue).map_err(|e| {
+        *e.downcast::<ShaderError>()
+            .expect("Expected realization to fail with a shader error!")
+    })
+}
+
+#[test]
+fn realization_missing_fragment_entry_point() {
+    match realize_entry_points(
+        VERTEX_ONLY_SHADER,
+        "entrypoint_vertex",
+        "entrypoint_fragment",
+    ) {
+        Err(ShaderError::MissingEntryPoint { name, stage }) => {
+            assert_eq!(name, "entrypoint_fragment");
+            assert_eq!(stage, ShaderStage::Fragment);
+        }
+        x => panic!("Expected a missing entry point, got: {x:?}"),
+    }
+}
+
+#[test]
+fn realization_entry_point_typo_and_wrong_stage() {
+    match realize_entry_points(TEXTURED_SHADER, "entrypoint_vertx", "entrypoint_fragment") {
+        Err(ShaderError::MissingEntryPoint { name, stage }) => {
+            assert_eq!(name, "entrypoint_vertx");
+            assert_eq!(stage, ShaderStage::Vertex);
+        }
+        x => panic!("Expected a missing entry point, got: {x:?}"),
+    }
+
+    // Exists, but as a vertex entry point
+    match realize_entry_points(TEXTURED_SHADER, "entrypoint_vertex", "entrypoint_vertex") {
+        Err(ShaderError::MissingEntryPoint { name, stage }) => {
+            assert_eq!(name, "entrypoint_vertex");
+            assert_eq!(stage, ShaderStage::Fragment);
+        }
+        x => panic!("Expected a missing entry point, got: {x:?}"),
+    }
+}
+
+const OUTPUT_TRANSFORM_SHADER: &str = "
+#import <output_transform>
+
+@vertex
+fn entrypoint_vertex(@builtin(vertex_index) vertex_index: u32) -> @builtin(position) vec4<f32> {
+    return vec4<f32>(f32(vertex_index), 0.0, 0.0, 1.0);
+}
+
+@fragment
+fn entrypoint_fragment() -> @location(0) vec4<f32> {
+    return vec4<f32>(output_transform(vec3<f32>(0.5)), 1.0);
+}
+";
+
+fn output_transform_descriptor() -> MaterialShaderDescriptor {
+    MaterialShaderDescriptor {
+        shader_source: ShaderSource::String(OUTPUT_TRANSFORM_SHADER),
+        vertex_stage_layouts: None,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn output_transform_for_format() {
+    assert_eq!(
+        OutputTransform::for_format(TextureFormat::Bgra8Unorm),
+        OutputTransform::SrgbEncode
+    );
+    assert_eq!(
+        OutputTransform::for_format(TextureFormat::Rgba8Unorm),
+        OutputTransform::SrgbEncode
+    );
+    assert_eq!(
+        OutputTransform::for_format(TextureFormat::Bgra8UnormSrgb),
+        OutputTransform::None
+    );
+    assert_eq!(
+        OutputTransform::for_format(TextureFormat::Rgba16Float),
+        OutputTransform::None
+    );
+}
+
+#[test]
+fn output_transform_linear_surface() {
+    let descriptor = output_transform_descriptor();
+
+    let source = descriptor
+        .preprocessed_source_for_format(TextureFormat::Bgra8Unorm)
+        .expect("Preprocessing failed!");
+    assert!(source.contains(&OutputTransform::SrgbEncode.wgsl()));
+
+    let (_adapter, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+    MaterialShader::from_descriptor(
+        &descriptor,
+        Some(TextureFormat::Rgba8Unorm),
+        &device,
+        &queue,
+    )
+    .expect("Failed realizing material shader for a linear surface!");
+}
+
+#[test]
+fn output_transform_srgb_surface() {
+    let descriptor = output_transform_descriptor();
+
+    let source = descriptor
+        .preprocessed_source_for_format(TextureFormat::Bgra8UnormSrgb)
+        .expect("Preprocessing failed!");
+    assert!(source.contains(&OutputTransform::None.wgsl()));
+    assert!(!source.contains(&OutputTransform::SrgbEncode.wgsl()));
+}
+
+#[test]
+fn output_transform_gamma_override() {
+    let descriptor = MaterialShaderDescriptor {
+        output_transform: Some(OutputTransform::Gamma(2.2)),
+        ..output_transform_descriptor()
+    };
+
+    let source = descriptor
+        .preprocessed_source_for_format(TextureFormat::Bgra8UnormSrgb)
+        .expect("Preprocessing failed!");
+    assert!(source.contains(&OutputTransform::Gamma(2.2).wgsl()));
+
+    let (_adapter, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+    MaterialShader::from_descriptor(&descriptor, None, &device, &queue)
+        .expect("Failed realizing material shader with a gamma override!");
+}
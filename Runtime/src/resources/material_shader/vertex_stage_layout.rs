@@ -6,6 +6,10 @@ use crate::resources::{Instance, Vertex};
 pub enum VertexStageLayout {
     SimpleVertexData,
     ComplexVertexData,
+    /// [ComplexVertexData](VertexStageLayout::ComplexVertexData) with
+    /// additional joints and weights for skinning.
+    /// Shaders get `SKINNED` defined for this layout.
+    SkinnedVertexData,
     InstanceData,
     Custom(VertexBufferLayout<'static>),
 }
@@ -17,6 +21,9 @@ impl VertexStageLayout {
             VertexStageLayout::ComplexVertexData => {
                 Vertex::complex_vertex_buffer_layout_descriptor()
             }
+            VertexStageLayout::SkinnedVertexData => {
+                Vertex::skinned_vertex_buffer_layout_descriptor()
+            }
             VertexStageLayout::InstanceData => Instance::vertex_buffer_layout_descriptor(),
             VertexStageLayout::Custom(vertex_buffer_layout) => vertex_buffer_layout,
         }
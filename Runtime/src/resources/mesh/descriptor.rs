@@ -1,21 +1,78 @@
-use std::hash::Hash;
+use std::{
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+};
 
-use cgmath::Point3;
+use cgmath::{EuclideanSpace, InnerSpace, Point3, Vector3, Zero};
+use log::warn;
+use wgpu::{IndexFormat, PrimitiveTopology};
 
-use crate::resources::{BoundingBoxDescriptor, Vertex};
+use crate::resources::{BoundingBoxDescriptor, Vertex, VertexLayout};
+
+/// Vertex data in a custom format, structured as described by its
+/// [VertexLayout].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RawVertexData {
+    pub data: Vec<u8>,
+    pub layout: VertexLayout,
+}
 
 #[derive(Debug, Clone, Eq)]
 pub struct MeshDescriptor {
     pub vertices: Vec<Vertex>,
     pub indices: Vec<u32>,
+    /// How [Self::indices] are assembled into primitives.  
+    /// Materials rendering this mesh are realized with this topology,
+    /// replacing their own
+    /// [primitive_topology](crate::resources::MaterialShaderDescriptor::primitive_topology).
+    pub primitive_topology: PrimitiveTopology,
+    /// Format the [Self::indices] are stored in on the GPU.  
+    /// [IndexFormat::Uint16] halves the index buffer size, but only works
+    /// if no index exceeds [u16::MAX].
+    pub index_format: IndexFormat,
+    /// Bounding box of the vertices, as set by [Self::compute_bounding_box].  
+    /// If unset, [Self::bounding_box] derives it from the vertices on demand.
+    pub bounding_box: Option<BoundingBoxDescriptor>,
+    /// Whether the realized [Mesh](crate::resources::Mesh) can be updated
+    /// in-place, e.g. via [Mesh::update_vertices](crate::resources::Mesh::update_vertices).  
+    /// Use this for meshes changing often, like procedural terrain or debug
+    /// lines, to avoid reallocating GPU buffers on each change.
+    pub dynamic: bool,
+    /// Custom vertex data, realized via
+    /// [Mesh::from_raw_data](crate::resources::Mesh::from_raw_data) instead
+    /// of [Self::vertices].  
+    /// Use this for custom vertex formats, e.g. with additional attributes
+    /// for custom shaders.
+    /// The layout is validated against the
+    /// [vertex_stage_layouts](crate::resources::MaterialShaderDescriptor::vertex_stage_layouts)
+    /// of each material once the model gets realized.
+    ///
+    /// Positions can't be read from raw data, thus [Self::bounding_box]
+    /// should be set explicitly.
+    pub raw_vertices: Option<RawVertexData>,
 }
 
 impl MeshDescriptor {
     pub fn new(vertices: Vec<Vertex>, indices: Vec<u32>) -> Self {
-        Self { vertices, indices }
+        Self {
+            vertices,
+            indices,
+            ..Default::default()
+        }
     }
 
+    /// Whether any vertex carries skinning data.
+    pub fn is_skinned(&self) -> bool {
+        self.vertices.iter().any(|vertex| vertex.skin.is_some())
+    }
+
+    /// Derives the bounding box from the vertex positions.
+    /// Empty meshes result in a zero-volume box at the origin.
     pub fn find_bounding_box(&self) -> BoundingBoxDescriptor {
+        if self.vertices.is_empty() {
+            return BoundingBoxDescriptor::new(Point3::origin(), Point3::origin());
+        }
+
         let mut min = Point3::new(f32::MAX, f32::MAX, f32::MAX);
         let mut max = Point3::new(f32::MIN, f32::MIN, f32::MIN);
         for vertex in &self.vertices {
@@ -33,6 +90,381 @@ impl MeshDescriptor {
         }
         BoundingBoxDescriptor { min, max }
     }
+
+    /// Derives the bounding box from the vertex positions and stores it in
+    /// [Self::bounding_box](field@Self::bounding_box).  
+    /// Needs to be called again after changing vertex positions.
+    pub fn compute_bounding_box(&mut self) {
+        self.bounding_box = Some(self.find_bounding_box());
+    }
+
+    /// Returns the stored bounding box or, if unset, derives it from the
+    /// vertex positions.
+    pub fn bounding_box(&self) -> BoundingBoxDescriptor {
+        self.bounding_box
+            .unwrap_or_else(|| self.find_bounding_box())
+    }
+
+    /// Moves the vertices such that the center of the bounding box ends up
+    /// at the origin.
+    ///
+    /// Returns the offset that got subtracted from every vertex position.
+    /// Translating the mesh by it restores the original placement.
+    pub fn recenter(&mut self) -> Vector3<f32> {
+        if self.vertices.is_empty() {
+            return Vector3::zero();
+        }
+
+        let bounding_box = self.find_bounding_box();
+        let center = (bounding_box.min.to_vec() + bounding_box.max.to_vec()) / 2.0;
+        for vertex in &mut self.vertices {
+            vertex.position -= center;
+        }
+        if let Some(bounding_box) = self.bounding_box.as_mut() {
+            bounding_box.min -= center;
+            bounding_box.max -= center;
+        }
+
+        center
+    }
+
+    /// Unifies the triangle winding of this mesh.
+    ///
+    /// Triangles sharing an edge are made to agree with each other, meaning
+    /// the shared edge has to be walked in opposite directions by both.
+    /// Vertices are matched by position, thus triangles are also connected
+    /// across UV seams.
+    /// Each connected set of triangles is then oriented to match the winding
+    /// most triangles of the whole mesh have in relation to their vertex
+    /// normals.
+    ///
+    /// Returns the number of flipped triangles.
+    pub fn repair_winding(&mut self) -> usize {
+        if self
+            .indices
+            .iter()
+            .any(|index| *index as usize >= self.vertices.len())
+        {
+            warn!("Mesh has out of bounds indices, skipping winding repair!");
+            return 0;
+        }
+
+        let triangle_count = self.indices.len() / 3;
+
+        let position_key = |index: u32| {
+            let position = self.vertices[index as usize].position;
+            [
+                position.x.to_bits(),
+                position.y.to_bits(),
+                position.z.to_bits(),
+            ]
+        };
+
+        // Collect every edge with the triangles using it and the direction
+        // they are walking it in.
+        type Edge = ([u32; 3], [u32; 3]);
+        let mut edges: HashMap<Edge, Vec<(usize, bool)>> = HashMap::new();
+        for triangle in 0..triangle_count {
+            let corners = [0, 1, 2].map(|corner| position_key(self.indices[triangle * 3 + corner]));
+
+            for corner in 0..3 {
+                let from = corners[corner];
+                let to = corners[(corner + 1) % 3];
+                if from == to {
+                    continue;
+                }
+
+                let forward = from < to;
+                let key = if forward { (from, to) } else { (to, from) };
+                edges.entry(key).or_default().push((triangle, forward));
+            }
+        }
+
+        // Non-manifold edges can't be oriented consistently and are ignored.
+        let mut neighbours: Vec<Vec<(usize, bool, bool)>> = vec![Vec::new(); triangle_count];
+        for occurrences in edges.values() {
+            if let [(a, a_forward), (b, b_forward)] = occurrences[..] {
+                if a != b {
+                    neighbours[a].push((b, a_forward, b_forward));
+                    neighbours[b].push((a, b_forward, a_forward));
+                }
+            }
+        }
+
+        let agreements: Vec<Option<bool>> = (0..triangle_count)
+            .map(|triangle| self.triangle_agrees_with_normals(triangle))
+            .collect();
+        let agreeing = agreements.iter().filter(|x| **x == Some(true)).count();
+        let disagreeing = agreements.iter().filter(|x| **x == Some(false)).count();
+        let reference = agreeing >= disagreeing;
+
+        let mut flipped: Vec<Option<bool>> = vec![None; triangle_count];
+        for seed in 0..triangle_count {
+            if flipped[seed].is_some() {
+                continue;
+            }
+
+            flipped[seed] = Some(false);
+            let mut component = vec![seed];
+            let mut queue = VecDeque::from([seed]);
+            while let Some(triangle) = queue.pop_front() {
+                let triangle_flipped = flipped[triangle].unwrap();
+
+                for &(neighbour, own_forward, neighbour_forward) in &neighbours[triangle] {
+                    if flipped[neighbour].is_some() {
+                        continue;
+                    }
+
+                    flipped[neighbour] =
+                        Some(neighbour_forward == (own_forward != triangle_flipped));
+                    component.push(neighbour);
+                    queue.push_back(neighbour);
+                }
+            }
+
+            let (matching, opposing) =
+                component
+                    .iter()
+                    .fold((0, 0), |(matching, opposing), &triangle| {
+                        match agreements[triangle] {
+                            Some(agrees) if (agrees != flipped[triangle].unwrap()) == reference => {
+                                (matching + 1, opposing)
+                            }
+                            Some(_) => (matching, opposing + 1),
+                            None => (matching, opposing),
+                        }
+                    });
+            if opposing > matching {
+                for triangle in component {
+                    flipped[triangle] = flipped[triangle].map(|x| !x);
+                }
+            }
+        }
+
+        let mut flip_count = 0;
+        for (triangle, triangle_flipped) in flipped.into_iter().enumerate() {
+            if triangle_flipped == Some(true) {
+                self.indices.swap(triangle * 3 + 1, triangle * 3 + 2);
+                flip_count += 1;
+            }
+        }
+
+        flip_count
+    }
+
+    /// Checks whether the UV mapping of this mesh is mirrored.
+    ///
+    /// UVs are expected to follow the glTF convention, i.e. the origin is at
+    /// the top-left and V points down.
+    /// A mesh is considered mirrored if most triangles have their UVs wound
+    /// the opposite way of what this convention results in.
+    pub fn is_uv_mirrored(&self) -> bool {
+        let mut mirrored = 0;
+        let mut regular = 0;
+
+        for triangle in 0..self.indices.len() / 3 {
+            let Some(agrees) = self.triangle_agrees_with_normals(triangle) else {
+                continue;
+            };
+
+            let [a, b, c] = [0, 1, 2]
+                .map(|corner| self.vertices[self.indices[triangle * 3 + corner] as usize].uv);
+            let uv_area = (b - a).perp_dot(c - a);
+            if uv_area.abs() <= f32::EPSILON {
+                continue;
+            }
+
+            // With V pointing down, a regular mapping has its UVs wound
+            // opposite to the positions.
+            if (uv_area > 0.0) == agrees {
+                mirrored += 1;
+            } else {
+                regular += 1;
+            }
+        }
+
+        mirrored > regular
+    }
+
+    /// Mirrors the UVs of this mesh horizontally.
+    /// Tangents are flipped accordingly.
+    pub fn mirror_uvs(&mut self) {
+        for vertex in &mut self.vertices {
+            vertex.uv.x = 1.0 - vertex.uv.x;
+            vertex.tangent = -vertex.tangent;
+        }
+    }
+
+    /// Derives tangents and bitangents of all vertices from their positions,
+    /// UVs and normals (Lengyel's method).
+    ///
+    /// Tangents point towards increasing U.
+    /// UVs are expected to follow the glTF convention (V pointing down),
+    /// thus bitangents point towards decreasing V, like they would with
+    /// tangents provided by a glTF file.
+    ///
+    /// Vertices that aren't part of any triangle with a valid UV mapping get
+    /// an arbitrary tangent orthogonal to their normal.
+    /// Vertices with a zero-length normal get an arbitrary basis around
+    /// [Vector3::unit_z], their normal is kept as-is.
+    pub fn compute_tangents(&mut self) {
+        let mut tangents = vec![Vector3::zero(); self.vertices.len()];
+        let mut bitangents = vec![Vector3::zero(); self.vertices.len()];
+
+        for corners in self.indices.chunks_exact(3) {
+            let [a, b, c] = [corners[0], corners[1], corners[2]].map(|x| x as usize);
+            if [a, b, c].iter().any(|x| *x >= self.vertices.len()) {
+                warn!("Mesh has out of bounds indices, skipping triangle for tangent generation!");
+                continue;
+            }
+
+            let edge_1 = self.vertices[b].position - self.vertices[a].position;
+            let edge_2 = self.vertices[c].position - self.vertices[a].position;
+            let uv_1 = self.vertices[b].uv - self.vertices[a].uv;
+            let uv_2 = self.vertices[c].uv - self.vertices[a].uv;
+
+            let determinant = uv_1.x * uv_2.y - uv_2.x * uv_1.y;
+            if determinant.abs() <= f32::EPSILON {
+                continue;
+            }
+            let r = 1.0 / determinant;
+
+            let tangent = (edge_1 * uv_2.y - edge_2 * uv_1.y) * r;
+            let bitangent = (edge_2 * uv_1.x - edge_1 * uv_2.x) * r;
+
+            for index in [a, b, c] {
+                tangents[index] += tangent;
+                bitangents[index] += bitangent;
+            }
+        }
+
+        for (vertex, (tangent, bitangent)) in self
+            .vertices
+            .iter_mut()
+            .zip(tangents.into_iter().zip(bitangents))
+        {
+            let normal = if vertex.normal.magnitude2() > f32::EPSILON {
+                vertex.normal.normalize()
+            } else {
+                Vector3::unit_z()
+            };
+
+            // Gram-Schmidt orthogonalize
+            let tangent = tangent - normal * normal.dot(tangent);
+            let tangent = if tangent.magnitude2() > f32::EPSILON {
+                tangent.normalize()
+            } else {
+                let arbitrary = if normal.x.abs() > 0.9 {
+                    Vector3::unit_y()
+                } else {
+                    Vector3::unit_x()
+                };
+                arbitrary.cross(normal).normalize()
+            };
+
+            // The accumulated bitangent points towards increasing V
+            let handedness = if normal.cross(tangent).dot(bitangent) > 0.0 {
+                -1.0
+            } else {
+                1.0
+            };
+
+            vertex.tangent = tangent;
+            vertex.bitangent = normal.cross(tangent) * handedness;
+        }
+    }
+
+    /// Computes vertex normals from the triangle geometry, assuming
+    /// counter-clockwise winding for front faces.
+    ///
+    /// If `smooth` is set, face normals are averaged per shared vertex,
+    /// weighted by triangle area.
+    /// Otherwise, every triangle gets its own vertices carrying its face
+    /// normal, thus the vertex count will match the index count afterwards.
+    ///
+    /// Degenerate triangles don't contribute to any normal.
+    /// Vertices without any valid normal keep their previous normal, if it
+    /// is usable, or fall back to [Vector3::unit_z].
+    pub fn compute_normals(&mut self, smooth: bool) {
+        let face_normals: Vec<Option<Vector3<f32>>> = self
+            .indices
+            .chunks_exact(3)
+            .map(|corners| {
+                let [a, b, c] = [corners[0], corners[1], corners[2]].map(|x| x as usize);
+                if [a, b, c].iter().any(|x| *x >= self.vertices.len()) {
+                    warn!(
+                        "Mesh has out of bounds indices, skipping triangle for normal generation!"
+                    );
+                    return None;
+                }
+
+                // Not normalized, magnitude is proportional to the triangle area
+                Some(
+                    (self.vertices[b].position - self.vertices[a].position)
+                        .cross(self.vertices[c].position - self.vertices[a].position),
+                )
+            })
+            .collect();
+
+        if smooth {
+            let mut normals = vec![Vector3::zero(); self.vertices.len()];
+            for (corners, face_normal) in self.indices.chunks_exact(3).zip(&face_normals) {
+                let Some(face_normal) = face_normal else {
+                    continue;
+                };
+
+                for index in corners {
+                    normals[*index as usize] += *face_normal;
+                }
+            }
+
+            for (vertex, normal) in self.vertices.iter_mut().zip(normals) {
+                vertex.normal = Self::normalize_or_fallback(normal, vertex.normal);
+            }
+        } else {
+            let mut vertices = Vec::with_capacity(self.indices.len());
+            for (corners, face_normal) in self.indices.chunks_exact(3).zip(face_normals) {
+                let Some(face_normal) = face_normal else {
+                    continue;
+                };
+
+                for index in corners {
+                    let mut vertex = self.vertices[*index as usize].clone();
+                    vertex.normal = Self::normalize_or_fallback(face_normal, vertex.normal);
+                    vertices.push(vertex);
+                }
+            }
+
+            self.indices = (0..vertices.len() as u32).collect();
+            self.vertices = vertices;
+        }
+    }
+
+    fn normalize_or_fallback(normal: Vector3<f32>, previous: Vector3<f32>) -> Vector3<f32> {
+        if normal.magnitude2() > f32::EPSILON * f32::EPSILON {
+            normal.normalize()
+        } else if previous.magnitude2() > f32::EPSILON {
+            previous.normalize()
+        } else {
+            Vector3::unit_z()
+        }
+    }
+
+    /// Returns whether the winding of a triangle agrees with the normals of
+    /// its vertices, i.e. the triangle is counter-clockwise when viewed from
+    /// the side its normals are pointing to.
+    /// `None` is returned if this can't be decided, e.g. for degenerate
+    /// triangles or missing normals.
+    fn triangle_agrees_with_normals(&self, triangle: usize) -> Option<bool> {
+        let corners = self.indices.get(triangle * 3..triangle * 3 + 3)?;
+        let [a, b, c] = [0, 1, 2].map(|corner| self.vertices.get(corners[corner] as usize));
+        let (a, b, c) = (a?, b?, c?);
+        let face_normal = (b.position - a.position).cross(c.position - a.position);
+        let vertex_normal = a.normal + b.normal + c.normal;
+
+        let alignment = face_normal.dot(vertex_normal);
+        (alignment.abs() > f32::EPSILON).then_some(alignment > 0.0)
+    }
 }
 
 impl PartialEq for MeshDescriptor {
@@ -43,8 +475,14 @@ impl PartialEq for MeshDescriptor {
             return false;
         }
 
-        // Then compare the actual data
-        self.vertices == other.vertices && self.indices == other.indices
+        // Then compare the actual data.
+        // The bounding box is derived from the vertices, thus not compared.
+        self.vertices == other.vertices
+            && self.indices == other.indices
+            && self.primitive_topology == other.primitive_topology
+            && self.index_format == other.index_format
+            && self.dynamic == other.dynamic
+            && self.raw_vertices == other.raw_vertices
     }
 }
 
@@ -52,5 +490,23 @@ impl Hash for MeshDescriptor {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         self.vertices.hash(state);
         self.indices.hash(state);
+        self.primitive_topology.hash(state);
+        self.index_format.hash(state);
+        self.dynamic.hash(state);
+        self.raw_vertices.hash(state);
+    }
+}
+
+impl Default for MeshDescriptor {
+    fn default() -> Self {
+        Self {
+            vertices: Vec::new(),
+            indices: Vec::new(),
+            primitive_topology: PrimitiveTopology::TriangleList,
+            index_format: IndexFormat::Uint32,
+            bounding_box: None,
+            dynamic: false,
+            raw_vertices: None,
+        }
     }
 }
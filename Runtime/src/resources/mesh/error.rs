@@ -0,0 +1,81 @@
+use std::{error::Error, fmt::Display};
+
+use wgpu::{IndexFormat, VertexFormat};
+
+#[derive(Debug, PartialEq)]
+pub enum MeshError {
+    /// The vertex data isn't a multiple of the layout's stride.
+    InvalidVertexDataLength { length: usize, array_stride: u64 },
+    /// An attribute of the layout doesn't fit into the stride.
+    AttributeOutOfBounds { shader_location: u32 },
+    /// An index points past the last vertex.
+    IndexOutOfBounds { index: u32, vertex_count: u32 },
+    /// The material requires an attribute the mesh doesn't provide.
+    MissingAttribute { shader_location: u32 },
+    /// The mesh provides the attribute in a different way than the material
+    /// requires.
+    AttributeMismatch {
+        shader_location: u32,
+        expected: (VertexFormat, u64),
+        found: (VertexFormat, u64),
+    },
+    /// The mesh wasn't created as a dynamic mesh and can't be updated.
+    NotDynamic,
+    /// The mesh uses a custom [VertexLayout](super::VertexLayout), which
+    /// [Vertex](super::Vertex)es can't be converted into.
+    CustomVertexLayout,
+    /// An index doesn't fit into the index format of the mesh.
+    IndexFormatOverflow {
+        index: u32,
+        index_format: IndexFormat,
+    },
+}
+
+impl Display for MeshError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MeshError::InvalidVertexDataLength {
+                length,
+                array_stride,
+            } => write!(
+                f,
+                "Vertex data length {length} isn't a multiple of the array stride {array_stride}"
+            ),
+            MeshError::AttributeOutOfBounds { shader_location } => write!(
+                f,
+                "Vertex attribute @location({shader_location}) exceeds the array stride"
+            ),
+            MeshError::IndexOutOfBounds {
+                index,
+                vertex_count,
+            } => write!(
+                f,
+                "Index {index} is out of bounds for {vertex_count} vertices"
+            ),
+            MeshError::MissingAttribute { shader_location } => write!(
+                f,
+                "Mesh is missing vertex attribute @location({shader_location})"
+            ),
+            MeshError::AttributeMismatch {
+                shader_location,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Vertex attribute @location({shader_location}) mismatch: expected {:?} at offset {}, found {:?} at offset {}",
+                expected.0, expected.1, found.0, found.1
+            ),
+            MeshError::NotDynamic => write!(f, "Mesh isn't dynamic and can't be updated"),
+            MeshError::CustomVertexLayout => write!(
+                f,
+                "Mesh uses a custom vertex layout, vertices have to be updated as raw data"
+            ),
+            MeshError::IndexFormatOverflow {
+                index,
+                index_format,
+            } => write!(f, "Index {index} doesn't fit into {index_format:?}"),
+        }
+    }
+}
+
+impl Error for MeshError {}
@@ -0,0 +1,64 @@
+use wgpu::{VertexAttribute, VertexBufferLayout, VertexStepMode};
+
+use crate::resources::Vertex;
+
+/// Describes how vertex data of a [Mesh](super::Mesh) is structured.  
+/// An owned version of [VertexBufferLayout], so that custom layouts can be
+/// created at runtime.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VertexLayout {
+    /// Size of a single vertex in bytes.
+    pub array_stride: u64,
+    pub attributes: Vec<VertexAttribute>,
+}
+
+impl VertexLayout {
+    pub fn new(array_stride: u64, attributes: Vec<VertexAttribute>) -> Self {
+        Self {
+            array_stride,
+            attributes,
+        }
+    }
+
+    /// The layout of the standard [Vertex].
+    pub fn standard() -> Self {
+        Vertex::complex_vertex_buffer_layout_descriptor().into()
+    }
+
+    /// The layout of the standard [Vertex] including its
+    /// [VertexSkin](crate::resources::VertexSkin).
+    pub fn skinned() -> Self {
+        Vertex::skinned_vertex_buffer_layout_descriptor().into()
+    }
+
+    /// Returns a [VertexBufferLayout] borrowing from this layout.
+    pub fn vertex_buffer_layout(&self) -> VertexBufferLayout<'_> {
+        VertexBufferLayout {
+            array_stride: self.array_stride,
+            step_mode: VertexStepMode::Vertex,
+            attributes: &self.attributes,
+        }
+    }
+
+    /// Returns the attribute bound to the given shader location, if any.
+    pub fn attribute(&self, shader_location: u32) -> Option<&VertexAttribute> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.shader_location == shader_location)
+    }
+}
+
+impl Default for VertexLayout {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+impl From<VertexBufferLayout<'_>> for VertexLayout {
+    fn from(value: VertexBufferLayout<'_>) -> Self {
+        Self {
+            array_stride: value.array_stride,
+            attributes: value.attributes.to_vec(),
+        }
+    }
+}
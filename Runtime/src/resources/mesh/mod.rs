@@ -1,63 +1,498 @@
 use wgpu::{
     util::{BufferInitDescriptor, DeviceExt},
-    Buffer, BufferUsages, Device, Queue,
+    Buffer, BufferDescriptor, BufferUsages, Device, IndexFormat, Queue, VertexStepMode,
+    COPY_BUFFER_ALIGNMENT,
 };
 
 pub use crate::resources::Vertex;
+use crate::resources::VertexStageLayout;
 
 mod descriptor;
 pub use descriptor::*;
 
+mod layout;
+pub use layout::*;
+
+mod error;
+pub use error::*;
+
 #[cfg(test)]
 mod tests;
 
 #[derive(Debug)]
 pub struct Mesh {
     vertex_buffer: Buffer,
+    vertex_layout: VertexLayout,
     index_buffer: Buffer,
     index_count: u32,
+    /// Highest index, checked when only vertices are updated.
+    max_index: Option<u32>,
+    index_format: IndexFormat,
+    dynamic: bool,
 }
 
 impl Mesh {
-    pub fn from_descriptor(descriptor: &MeshDescriptor, device: &Device, _queue: &Queue) -> Self {
-        Self::from_data(&descriptor.vertices, &descriptor.indices, device)
+    /// Descriptors with [raw vertices](MeshDescriptor::raw_vertices) use
+    /// their custom [VertexLayout], skinned descriptors
+    /// [VertexLayout::skinned] and anything else [VertexLayout::standard].
+    /// [Dynamic](MeshDescriptor::dynamic) descriptors result in a dynamic
+    /// [Mesh].
+    ///
+    /// Fails if an index doesn't fit into the
+    /// [index_format](MeshDescriptor::index_format) of the descriptor, or
+    /// raw vertices don't match their layout.
+    pub fn from_descriptor(
+        descriptor: &MeshDescriptor,
+        device: &Device,
+        _queue: &Queue,
+    ) -> Result<Self, MeshError> {
+        let (vertex_data, vertex_layout) = if let Some(raw) = &descriptor.raw_vertices {
+            Self::validate_raw_data(&raw.data, &raw.layout, &descriptor.indices)?;
+            (raw.data.clone(), raw.layout.clone())
+        } else if descriptor.is_skinned() {
+            (
+                Self::vertices_to_skinned_bytes(&descriptor.vertices),
+                VertexLayout::skinned(),
+            )
+        } else {
+            (
+                Self::vertices_to_bytes(&descriptor.vertices),
+                VertexLayout::standard(),
+            )
+        };
+
+        Self::create(
+            &vertex_data,
+            vertex_layout,
+            &descriptor.indices,
+            descriptor.index_format,
+            descriptor.dynamic,
+            device,
+        )
     }
 
     pub fn from_data(vertices: &[Vertex], indices: &[u32], device: &Device) -> Self {
+        Self::create_u32(
+            &Self::vertices_to_bytes(vertices),
+            VertexLayout::standard(),
+            indices,
+            false,
+            device,
+        )
+    }
+
+    /// Same as [Mesh::from_data], but stores the indices in the given
+    /// [IndexFormat].  
+    /// [IndexFormat::Uint16] halves the size of the index buffer, but fails
+    /// with [MeshError::IndexFormatOverflow] if any index exceeds
+    /// [u16::MAX].
+    pub fn from_data_with_index_format(
+        vertices: &[Vertex],
+        indices: &[u32],
+        index_format: IndexFormat,
+        device: &Device,
+    ) -> Result<Self, MeshError> {
+        Self::create(
+            &Self::vertices_to_bytes(vertices),
+            VertexLayout::standard(),
+            indices,
+            index_format,
+            false,
+            device,
+        )
+    }
+
+    /// Creates a [Mesh] including the [VertexSkin](crate::resources::VertexSkin)
+    /// of each vertex, structured as described by [VertexLayout::skinned].
+    pub fn from_data_skinned(vertices: &[Vertex], indices: &[u32], device: &Device) -> Self {
+        Self::create_u32(
+            &Self::vertices_to_skinned_bytes(vertices),
+            VertexLayout::skinned(),
+            indices,
+            false,
+            device,
+        )
+    }
+
+    /// Creates a dynamic [Mesh], which can be updated in-place via
+    /// [Mesh::update].  
+    /// Use this for procedural meshes that change often, e.g. terrain chunks
+    /// or deformable surfaces, to avoid recreating the buffers each time.
+    pub fn from_data_dynamic(vertices: &[Vertex], indices: &[u32], device: &Device) -> Self {
+        Self::create_u32(
+            &Self::vertices_to_bytes(vertices),
+            VertexLayout::standard(),
+            indices,
+            true,
+            device,
+        )
+    }
+
+    /// Creates a [Mesh] from raw vertex data structured as described by
+    /// `vertex_layout`.  
+    /// Use this for custom vertex formats, e.g. with additional attributes
+    /// for custom shaders.
+    /// The layout can be checked against a material with
+    /// [Mesh::validate_vertex_stage_layouts].
+    pub fn from_raw_data(
+        vertex_data: &[u8],
+        vertex_layout: VertexLayout,
+        indices: &[u32],
+        device: &Device,
+    ) -> Result<Self, MeshError> {
+        Self::validate_raw_data(vertex_data, &vertex_layout, indices)?;
+
+        Ok(Self::create_u32(
+            vertex_data,
+            vertex_layout,
+            indices,
+            false,
+            device,
+        ))
+    }
+
+    /// Updates a dynamic [Mesh] with new vertices and indices.  
+    /// The new data is written into the existing buffers if it fits.
+    /// Otherwise, the affected buffer is reallocated with room to grow.
+    ///
+    /// Only works on dynamic meshes.
+    /// Vertices are converted into [VertexLayout::standard] or
+    /// [VertexLayout::skinned], depending on [Mesh::vertex_layout].
+    /// Meshes with a custom layout fail with [MeshError::CustomVertexLayout],
+    /// use [Mesh::update_raw] for them instead.
+    pub fn update(
+        &mut self,
+        vertices: &[Vertex],
+        indices: &[u32],
+        device: &Device,
+        queue: &Queue,
+    ) -> Result<(), MeshError> {
+        let vertex_data = self.vertices_to_layout_bytes(vertices)?;
+        self.update_raw(&vertex_data, indices, device, queue)
+    }
+
+    /// Updates only the vertices of a dynamic [Mesh], keeping its indices.  
+    /// Meant for meshes whose topology stays the same while vertices move,
+    /// e.g. deforming surfaces.
+    /// Fails if an existing index is out of bounds of the new vertices.
+    ///
+    /// Like [Mesh::update], the vertex buffer is only reallocated if the new
+    /// vertices don't fit, otherwise they are written into it.
+    /// Works on both, [VertexLayout::standard] and [VertexLayout::skinned]
+    /// meshes, custom layouts fail with [MeshError::CustomVertexLayout].
+    pub fn update_vertices(
+        &mut self,
+        vertices: &[Vertex],
+        device: &Device,
+        queue: &Queue,
+    ) -> Result<(), MeshError> {
+        if !self.dynamic {
+            return Err(MeshError::NotDynamic);
+        }
+
+        let vertex_data = self.vertices_to_layout_bytes(vertices)?;
+        Self::validate_raw_data(&vertex_data, &self.vertex_layout, &[])?;
+
+        let vertex_count = vertices.len() as u32;
+        if let Some(index) = self.max_index.filter(|index| *index >= vertex_count) {
+            return Err(MeshError::IndexOutOfBounds {
+                index,
+                vertex_count,
+            });
+        }
+
+        Self::write_or_grow(
+            &mut self.vertex_buffer,
+            &vertex_data,
+            "Mesh Vertex Buffer",
+            BufferUsages::VERTEX,
+            device,
+            queue,
+        );
+
+        Ok(())
+    }
+
+    /// Updates a dynamic [Mesh] with raw vertex data structured as described
+    /// by [Mesh::vertex_layout].  
+    /// Check [Mesh::update] for details.
+    pub fn update_raw(
+        &mut self,
+        vertex_data: &[u8],
+        indices: &[u32],
+        device: &Device,
+        queue: &Queue,
+    ) -> Result<(), MeshError> {
+        if !self.dynamic {
+            return Err(MeshError::NotDynamic);
+        }
+
+        Self::validate_raw_data(vertex_data, &self.vertex_layout, indices)?;
+        let index_data = Self::indices_to_bytes(indices, self.index_format)?;
+
+        Self::write_or_grow(
+            &mut self.vertex_buffer,
+            vertex_data,
+            "Mesh Vertex Buffer",
+            BufferUsages::VERTEX,
+            device,
+            queue,
+        );
+        Self::write_or_grow(
+            &mut self.index_buffer,
+            &index_data,
+            "Mesh Index Buffer",
+            BufferUsages::INDEX,
+            device,
+            queue,
+        );
+        self.index_count = indices.len() as u32;
+        self.max_index = indices.iter().max().copied();
+
+        Ok(())
+    }
+
+    /// Converts vertices into the structure described by [Mesh::vertex_layout].
+    fn vertices_to_layout_bytes(&self, vertices: &[Vertex]) -> Result<Vec<u8>, MeshError> {
+        if self.vertex_layout == VertexLayout::standard() {
+            Ok(Self::vertices_to_bytes(vertices))
+        } else if self.vertex_layout == VertexLayout::skinned() {
+            Ok(Self::vertices_to_skinned_bytes(vertices))
+        } else {
+            Err(MeshError::CustomVertexLayout)
+        }
+    }
+
+    /// Writes `data` into `buffer`, if it fits.
+    /// Otherwise, `buffer` is replaced by a bigger one.
+    fn write_or_grow(
+        buffer: &mut Buffer,
+        data: &[u8],
+        label: &str,
+        usage: BufferUsages,
+        device: &Device,
+        queue: &Queue,
+    ) {
+        if data.is_empty() {
+            return;
+        }
+
+        let size = (data.len() as u64).next_multiple_of(COPY_BUFFER_ALIGNMENT);
+        if size > buffer.size() {
+            *buffer = device.create_buffer(&BufferDescriptor {
+                label: Some(label),
+                size: size.next_power_of_two(),
+                usage: usage | Self::dynamic_usages(),
+                mapped_at_creation: false,
+            });
+        }
+
+        if size == data.len() as u64 {
+            queue.write_buffer(buffer, 0, data);
+        } else {
+            let mut padded = data.to_vec();
+            padded.resize(size as usize, 0);
+            queue.write_buffer(buffer, 0, &padded);
+        }
+    }
+
+    fn validate_raw_data(
+        vertex_data: &[u8],
+        vertex_layout: &VertexLayout,
+        indices: &[u32],
+    ) -> Result<(), MeshError> {
+        let array_stride = vertex_layout.array_stride;
+
+        if array_stride == 0 || !(vertex_data.len() as u64).is_multiple_of(array_stride) {
+            return Err(MeshError::InvalidVertexDataLength {
+                length: vertex_data.len(),
+                array_stride,
+            });
+        }
+
+        if let Some(attribute) = vertex_layout
+            .attributes
+            .iter()
+            .find(|attribute| attribute.offset + attribute.format.size() > array_stride)
+        {
+            return Err(MeshError::AttributeOutOfBounds {
+                shader_location: attribute.shader_location,
+            });
+        }
+
+        let vertex_count = (vertex_data.len() as u64 / array_stride) as u32;
+        if let Some(&index) = indices.iter().find(|index| **index >= vertex_count) {
+            return Err(MeshError::IndexOutOfBounds {
+                index,
+                vertex_count,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Same as [Mesh::create] with [IndexFormat::Uint32], which fits any
+    /// index.
+    fn create_u32(
+        vertex_data: &[u8],
+        vertex_layout: VertexLayout,
+        indices: &[u32],
+        dynamic: bool,
+        device: &Device,
+    ) -> Self {
+        Self::create(
+            vertex_data,
+            vertex_layout,
+            indices,
+            IndexFormat::Uint32,
+            dynamic,
+            device,
+        )
+        .expect("u32 indices can't overflow")
+    }
+
+    fn create(
+        vertex_data: &[u8],
+        vertex_layout: VertexLayout,
+        indices: &[u32],
+        index_format: IndexFormat,
+        dynamic: bool,
+        device: &Device,
+    ) -> Result<Self, MeshError> {
+        let index_data = Self::indices_to_bytes(indices, index_format)?;
+
+        let extra_usages = if dynamic {
+            Self::dynamic_usages()
+        } else {
+            BufferUsages::COPY_SRC
+        };
+
         let vertex_buffer = device.create_buffer_init(&BufferInitDescriptor {
             label: Some("Mesh Vertex Buffer"),
-            contents: &vertices
-                .iter()
-                .flat_map(|x| x.to_bytes())
-                .collect::<Vec<u8>>(),
-            usage: BufferUsages::VERTEX,
+            contents: vertex_data,
+            usage: BufferUsages::VERTEX | extra_usages,
         });
 
         let index_buffer = device.create_buffer_init(&BufferInitDescriptor {
             label: Some("Mesh Index Buffer"),
-            contents: &indices
-                .iter()
-                .flat_map(|x| x.to_le_bytes())
-                .collect::<Vec<u8>>(),
-            usage: BufferUsages::INDEX,
+            contents: &index_data,
+            usage: BufferUsages::INDEX | extra_usages,
         });
 
-        Self {
+        Ok(Self {
             vertex_buffer,
+            vertex_layout,
             index_buffer,
             index_count: indices.len() as u32,
+            max_index: indices.iter().max().copied(),
+            index_format,
+            dynamic,
+        })
+    }
+
+    fn dynamic_usages() -> BufferUsages {
+        BufferUsages::COPY_SRC | BufferUsages::COPY_DST
+    }
+
+    fn vertices_to_bytes(vertices: &[Vertex]) -> Vec<u8> {
+        vertices.iter().flat_map(|x| x.to_bytes()).collect()
+    }
+
+    fn vertices_to_skinned_bytes(vertices: &[Vertex]) -> Vec<u8> {
+        vertices.iter().flat_map(|x| x.to_skinned_bytes()).collect()
+    }
+
+    fn indices_to_bytes(indices: &[u32], index_format: IndexFormat) -> Result<Vec<u8>, MeshError> {
+        match index_format {
+            IndexFormat::Uint16 => indices
+                .iter()
+                .map(|&index| {
+                    u16::try_from(index).map(u16::to_le_bytes).map_err(|_| {
+                        MeshError::IndexFormatOverflow {
+                            index,
+                            index_format,
+                        }
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map(|x| x.concat()),
+            IndexFormat::Uint32 => Ok(indices.iter().flat_map(|x| x.to_le_bytes()).collect()),
         }
     }
 
+    /// Checks whether this [Mesh] provides every vertex attribute a
+    /// material requires.  
+    /// The per-vertex layout of `vertex_stage_layouts` is compared against
+    /// [Mesh::vertex_layout]; each attribute must exist at the same shader
+    /// location with the same format and offset.
+    /// Per-instance layouts are ignored.
+    pub fn validate_vertex_stage_layouts(
+        &self,
+        vertex_stage_layouts: &[VertexStageLayout],
+    ) -> Result<(), MeshError> {
+        let Some(required) = vertex_stage_layouts
+            .iter()
+            .map(|layout| layout.clone().vertex_buffer_layout())
+            .find(|layout| layout.step_mode == VertexStepMode::Vertex)
+        else {
+            return Ok(());
+        };
+
+        for attribute in required.attributes {
+            let shader_location = attribute.shader_location;
+            let provided = self
+                .vertex_layout
+                .attribute(shader_location)
+                .ok_or(MeshError::MissingAttribute { shader_location })?;
+
+            if provided.format != attribute.format || provided.offset != attribute.offset {
+                return Err(MeshError::AttributeMismatch {
+                    shader_location,
+                    expected: (attribute.format, attribute.offset),
+                    found: (provided.format, provided.offset),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The vertex buffer of this mesh.  
+    /// Created with [BufferUsages::VERTEX] and [BufferUsages::COPY_SRC],
+    /// meaning it can be copied into e.g. a storage buffer for compute work.
+    /// Dynamic meshes additionally use [BufferUsages::COPY_DST].
+    /// Dynamic meshes might be bigger than the actual vertex data.
     pub fn vertex_buffer(&self) -> &Buffer {
         &self.vertex_buffer
     }
 
+    /// The layout of [Mesh::vertex_buffer].  
+    /// [VertexLayout::standard] unless created via [Mesh::from_raw_data].
+    pub fn vertex_layout(&self) -> &VertexLayout {
+        &self.vertex_layout
+    }
+
+    /// The index buffer of this mesh, stored as [Mesh::index_format].  
+    /// Created with [BufferUsages::INDEX] and [BufferUsages::COPY_SRC].
+    /// Only the first [Mesh::index_count] indices are valid.
     pub fn index_buffer(&self) -> &Buffer {
         &self.index_buffer
     }
 
+    /// Format of the indices in [Mesh::index_buffer].  
+    /// [IndexFormat::Uint32] unless created from a [MeshDescriptor] or via
+    /// [Mesh::from_data_with_index_format].
+    pub fn index_format(&self) -> IndexFormat {
+        self.index_format
+    }
+
     pub fn index_count(&self) -> u32 {
         self.index_count
     }
+
+    /// Whether this mesh can be updated via [Mesh::update] or
+    /// [Mesh::update_vertices].
+    pub fn is_dynamic(&self) -> bool {
+        self.dynamic
+    }
 }
@@ -1,7 +1,14 @@
-use cgmath::{Point3, Vector2, Vector3};
+use cgmath::{InnerSpace, Point3, Vector2, Vector3, Zero};
+use wgpu::{
+    Buffer, BufferDescriptor, BufferUsages, CommandEncoderDescriptor, Device, IndexFormat, MapMode,
+    PollType, Queue, VertexAttribute, VertexBufferLayout, VertexFormat, VertexStepMode,
+};
 
 use crate::{
-    resources::{Mesh, MeshDescriptor, Vertex},
+    resources::{
+        Mesh, MeshDescriptor, MeshError, RawVertexData, Vertex, VertexLayout, VertexSkin,
+        VertexStageLayout,
+    },
     wgpu_test_adapter,
 };
 
@@ -16,11 +23,15 @@ fn realization() {
             tangent: Vector3::new(1.0, 2.0, 3.0),
             bitangent: Vector3::new(1.0, 2.0, 3.0),
             uv: Vector2::new(1.0, 2.0),
+            color: None,
+            skin: None,
         }],
         indices: vec![0],
+        ..Default::default()
     };
 
-    let _realization = Mesh::from_descriptor(&descriptor, &device, &queue);
+    let _realization =
+        Mesh::from_descriptor(&descriptor, &device, &queue).expect("Failure creating mesh");
 }
 
 #[test]
@@ -33,6 +44,8 @@ fn bounding_box() {
                 tangent: Vector3::new(0.0, 0.0, 0.0),
                 bitangent: Vector3::new(0.0, 0.0, 0.0),
                 uv: Vector2::new(0.0, 0.0),
+                color: None,
+                skin: None,
             },
             Vertex {
                 position: Vector3::new(5.0, 5.0, 5.0),
@@ -40,9 +53,12 @@ fn bounding_box() {
                 tangent: Vector3::new(0.0, 0.0, 0.0),
                 bitangent: Vector3::new(0.0, 0.0, 0.0),
                 uv: Vector2::new(0.0, 0.0),
+                color: None,
+                skin: None,
             },
         ],
         indices: vec![0],
+        ..Default::default()
     };
 
     let bounding_box = descriptor.find_bounding_box();
@@ -50,3 +66,756 @@ fn bounding_box() {
     assert_eq!(bounding_box.min, Point3::new(-5.0, -5.0, -5.0));
     assert_eq!(bounding_box.max, Point3::new(5.0, 5.0, 5.0));
 }
+
+#[test]
+fn compute_bounding_box() {
+    let positions = [
+        Vector3::new(1.0, -2.0, 3.0),
+        Vector3::new(-4.0, 5.0, 0.5),
+        Vector3::new(2.5, 0.0, -6.0),
+    ];
+    let mut descriptor = MeshDescriptor::new(
+        positions
+            .into_iter()
+            .map(|position| {
+                Vertex::new(position, Vector3::zero(), Vector3::zero(), Vector2::zero())
+            })
+            .collect(),
+        vec![0, 1, 2],
+    );
+    assert!(descriptor.bounding_box.is_none());
+
+    descriptor.compute_bounding_box();
+
+    let bounding_box = descriptor.bounding_box.expect("Bounding box wasn't stored");
+    assert_eq!(bounding_box.min, Point3::new(-4.0, -2.0, -6.0));
+    assert_eq!(bounding_box.max, Point3::new(2.5, 5.0, 3.0));
+
+    // Recentering keeps the stored box in sync
+    descriptor.recenter();
+    assert_eq!(descriptor.bounding_box(), descriptor.find_bounding_box());
+}
+
+#[test]
+fn compute_bounding_box_empty_mesh() {
+    let mut descriptor = MeshDescriptor::default();
+    descriptor.compute_bounding_box();
+
+    let bounding_box = descriptor.bounding_box();
+    assert_eq!(bounding_box.min, Point3::new(0.0, 0.0, 0.0));
+    assert_eq!(bounding_box.max, Point3::new(0.0, 0.0, 0.0));
+}
+
+/// Creates a flat 3x3 vertex grid facing +Z, made out of 8 counter-clockwise
+/// triangles. UVs follow the glTF convention.
+fn grid_mesh() -> MeshDescriptor {
+    let mut vertices = Vec::new();
+    for y in 0..3 {
+        for x in 0..3 {
+            vertices.push(Vertex {
+                position: Vector3::new(x as f32, y as f32, 0.0),
+                normal: Vector3::new(0.0, 0.0, 1.0),
+                tangent: Vector3::new(1.0, 0.0, 0.0),
+                bitangent: Vector3::new(0.0, 1.0, 0.0),
+                uv: Vector2::new(x as f32 / 2.0, 1.0 - y as f32 / 2.0),
+                color: None,
+                skin: None,
+            });
+        }
+    }
+
+    let mut indices = Vec::new();
+    for y in 0..2 {
+        for x in 0..2 {
+            let bottom_left = y * 3 + x;
+            let bottom_right = bottom_left + 1;
+            let top_left = bottom_left + 3;
+            let top_right = top_left + 1;
+
+            indices.extend([bottom_left, bottom_right, top_right]);
+            indices.extend([bottom_left, top_right, top_left]);
+        }
+    }
+
+    MeshDescriptor::new(vertices, indices)
+}
+
+fn face_normals_z(descriptor: &MeshDescriptor) -> Vec<f32> {
+    descriptor
+        .indices
+        .chunks(3)
+        .map(|triangle| {
+            let a = descriptor.vertices[triangle[0] as usize].position;
+            let b = descriptor.vertices[triangle[1] as usize].position;
+            let c = descriptor.vertices[triangle[2] as usize].position;
+            (b - a).cross(c - a).z
+        })
+        .collect()
+}
+
+#[test]
+fn repair_winding() {
+    let mut descriptor = grid_mesh();
+    // Mis-wind a single triangle
+    descriptor.indices.swap(10, 11);
+    assert!(face_normals_z(&descriptor)[3] < 0.0);
+
+    let flipped = descriptor.repair_winding();
+
+    assert_eq!(flipped, 1);
+    assert!(face_normals_z(&descriptor).iter().all(|z| *z > 0.0));
+}
+
+#[test]
+fn repair_winding_consistent_mesh() {
+    let mut descriptor = grid_mesh();
+    let original = descriptor.clone();
+
+    assert_eq!(descriptor.repair_winding(), 0);
+    assert_eq!(descriptor, original);
+    assert!(!descriptor.is_uv_mirrored());
+}
+
+#[test]
+fn mirrored_uvs() {
+    let mut descriptor = grid_mesh();
+    descriptor.mirror_uvs();
+    assert!(descriptor.is_uv_mirrored());
+
+    descriptor.mirror_uvs();
+    assert!(!descriptor.is_uv_mirrored());
+}
+
+fn assert_vector_eq(a: Vector3<f32>, b: Vector3<f32>) {
+    assert!((a - b).magnitude() < 1e-5, "{a:?} != {b:?}");
+}
+
+#[test]
+fn compute_tangents() {
+    let expected = grid_mesh();
+    let mut descriptor = grid_mesh();
+    for vertex in &mut descriptor.vertices {
+        vertex.tangent = Vector3::zero();
+        vertex.bitangent = Vector3::zero();
+    }
+
+    descriptor.compute_tangents();
+
+    for (vertex, expected) in descriptor.vertices.iter().zip(&expected.vertices) {
+        assert!(vertex.tangent.dot(vertex.normal).abs() < 1e-5);
+        assert!(vertex.bitangent.dot(vertex.normal).abs() < 1e-5);
+        assert_vector_eq(vertex.tangent, expected.tangent);
+        assert_vector_eq(vertex.bitangent, expected.bitangent);
+    }
+}
+
+#[test]
+fn compute_tangents_mirrored_uvs() {
+    let mut descriptor = grid_mesh();
+    for vertex in &mut descriptor.vertices {
+        vertex.uv.x = 1.0 - vertex.uv.x;
+    }
+
+    descriptor.compute_tangents();
+
+    // Tangents follow U, bitangents still follow V
+    for vertex in &descriptor.vertices {
+        assert_vector_eq(vertex.tangent, Vector3::new(-1.0, 0.0, 0.0));
+        assert_vector_eq(vertex.bitangent, Vector3::new(0.0, 1.0, 0.0));
+    }
+}
+
+#[test]
+fn compute_tangents_without_uvs() {
+    let mut descriptor = grid_mesh();
+    for vertex in &mut descriptor.vertices {
+        vertex.uv = Vector2::new(0.0, 0.0);
+    }
+
+    descriptor.compute_tangents();
+
+    for vertex in &descriptor.vertices {
+        assert!((vertex.tangent.magnitude() - 1.0).abs() < 1e-5);
+        assert!(vertex.tangent.dot(vertex.normal).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn compute_tangents_without_normals() {
+    let mut descriptor = grid_mesh();
+    for vertex in &mut descriptor.vertices {
+        vertex.normal = Vector3::zero();
+    }
+
+    descriptor.compute_tangents();
+
+    for vertex in &descriptor.vertices {
+        assert!(!vertex.tangent.x.is_nan() && !vertex.bitangent.x.is_nan());
+        assert!((vertex.tangent.magnitude() - 1.0).abs() < 1e-5);
+        assert!((vertex.bitangent.magnitude() - 1.0).abs() < 1e-5);
+        assert!(vertex.tangent.dot(vertex.bitangent).abs() < 1e-5);
+        assert_eq!(vertex.normal, Vector3::zero());
+    }
+}
+
+/// Unit cube with eight shared corners and counter-clockwise faces,
+/// viewed from the outside.
+fn cube_mesh() -> MeshDescriptor {
+    let vertices = (0..8)
+        .map(|corner| {
+            let position = Vector3::new(
+                (corner & 1) as f32 - 0.5,
+                ((corner >> 1) & 1) as f32 - 0.5,
+                ((corner >> 2) & 1) as f32 - 0.5,
+            );
+            Vertex::new(position, Vector3::zero(), Vector3::zero(), Vector2::zero())
+        })
+        .collect();
+    let indices = vec![
+        0, 2, 3, 0, 3, 1, // -Z
+        4, 5, 7, 4, 7, 6, // +Z
+        0, 1, 5, 0, 5, 4, // -Y
+        2, 6, 7, 2, 7, 3, // +Y
+        0, 4, 6, 0, 6, 2, // -X
+        1, 3, 7, 1, 7, 5, // +X
+    ];
+
+    MeshDescriptor::new(vertices, indices)
+}
+
+#[test]
+fn compute_normals_smooth() {
+    let mut descriptor = cube_mesh();
+    descriptor.compute_normals(true);
+
+    assert_eq!(descriptor.vertices.len(), 8);
+    for vertex in &descriptor.vertices {
+        assert!((vertex.normal.magnitude() - 1.0).abs() < 1e-5);
+        // Corners point diagonally outwards
+        assert!(vertex.normal.dot(vertex.position.normalize()) > 0.9);
+    }
+}
+
+#[test]
+fn compute_normals_flat() {
+    let mut descriptor = cube_mesh();
+    descriptor.compute_normals(false);
+
+    assert_eq!(descriptor.vertices.len(), 36);
+    assert_eq!(descriptor.indices, (0..36).collect::<Vec<u32>>());
+    for vertex in &descriptor.vertices {
+        assert!((vertex.normal.magnitude() - 1.0).abs() < 1e-5);
+        // Face normals are axis aligned and point outwards
+        let axis_aligned = [vertex.normal.x, vertex.normal.y, vertex.normal.z]
+            .iter()
+            .filter(|x| x.abs() > 1e-5)
+            .count();
+        assert_eq!(axis_aligned, 1);
+        assert!(vertex.normal.dot(vertex.position) > 0.0);
+    }
+}
+
+#[test]
+fn compute_normals_degenerate_triangle() {
+    let position = Vector3::new(1.0, 2.0, 3.0);
+    let vertices = (0..3)
+        .map(|_| Vertex::new(position, Vector3::zero(), Vector3::zero(), Vector2::zero()))
+        .collect::<Vec<_>>();
+
+    for smooth in [true, false] {
+        let mut descriptor = MeshDescriptor::new(vertices.clone(), vec![0, 1, 2]);
+        descriptor.compute_normals(smooth);
+
+        for vertex in &descriptor.vertices {
+            assert!(!vertex.normal.x.is_nan());
+            assert_vector_eq(vertex.normal, Vector3::unit_z());
+        }
+    }
+}
+
+/// Position (`Float32x3`) followed by a custom `Float32` attribute.
+fn custom_layout() -> VertexLayout {
+    VertexLayout::new(
+        size_of::<[f32; 4]>() as u64,
+        vec![
+            VertexAttribute {
+                offset: 0,
+                shader_location: 0,
+                format: VertexFormat::Float32x3,
+            },
+            VertexAttribute {
+                offset: size_of::<[f32; 3]>() as u64,
+                shader_location: 5,
+                format: VertexFormat::Float32,
+            },
+        ],
+    )
+}
+
+#[test]
+fn custom_vertex_layout() {
+    let (_, device, _) = wgpu_test_adapter::make_wgpu_connection();
+
+    let vertex_data: Vec<u8> = [
+        [0.0f32, 0.0, 0.0, 0.25],
+        [1.0, 0.0, 0.0, 0.5],
+        [0.0, 1.0, 0.0, 0.75],
+    ]
+    .iter()
+    .flatten()
+    .flat_map(|x| x.to_le_bytes())
+    .collect();
+
+    let mesh = Mesh::from_raw_data(&vertex_data, custom_layout(), &[0, 1, 2], &device)
+        .expect("Failed creating custom mesh");
+
+    assert_eq!(mesh.vertex_buffer().size(), 3 * 16);
+    assert_eq!(*mesh.vertex_layout(), custom_layout());
+    assert_eq!(mesh.vertex_layout().vertex_buffer_layout().array_stride, 16);
+    assert_eq!(mesh.index_count(), 3);
+
+    let custom_material = VertexStageLayout::Custom(VertexBufferLayout {
+        array_stride: 16,
+        step_mode: VertexStepMode::Vertex,
+        attributes: &[
+            VertexAttribute {
+                offset: 0,
+                shader_location: 0,
+                format: VertexFormat::Float32x3,
+            },
+            VertexAttribute {
+                offset: 12,
+                shader_location: 5,
+                format: VertexFormat::Float32,
+            },
+        ],
+    });
+    assert_eq!(
+        mesh.validate_vertex_stage_layouts(&[custom_material, VertexStageLayout::InstanceData]),
+        Ok(())
+    );
+
+    // The standard layout requires normals, which aren't provided
+    assert_eq!(
+        mesh.validate_vertex_stage_layouts(&[VertexStageLayout::ComplexVertexData]),
+        Err(MeshError::MissingAttribute { shader_location: 1 })
+    );
+}
+
+#[test]
+fn custom_vertex_layout_invalid_data() {
+    let (_, device, _) = wgpu_test_adapter::make_wgpu_connection();
+
+    assert_eq!(
+        Mesh::from_raw_data(&[0u8; 20], custom_layout(), &[0], &device).unwrap_err(),
+        MeshError::InvalidVertexDataLength {
+            length: 20,
+            array_stride: 16
+        }
+    );
+    assert_eq!(
+        Mesh::from_raw_data(&[0u8; 32], custom_layout(), &[0, 2], &device).unwrap_err(),
+        MeshError::IndexOutOfBounds {
+            index: 2,
+            vertex_count: 2
+        }
+    );
+}
+
+#[test]
+fn descriptor_with_raw_vertices() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+
+    let descriptor = MeshDescriptor {
+        indices: vec![0, 1],
+        raw_vertices: Some(RawVertexData {
+            data: vec![0; 32],
+            layout: custom_layout(),
+        }),
+        ..Default::default()
+    };
+    let mesh = Mesh::from_descriptor(&descriptor, &device, &queue)
+        .expect("Failed creating mesh from raw vertices");
+    assert_eq!(*mesh.vertex_layout(), custom_layout());
+    assert_eq!(mesh.vertex_buffer().size(), 32);
+
+    let invalid = MeshDescriptor {
+        indices: vec![0, 2],
+        ..descriptor
+    };
+    assert_eq!(
+        Mesh::from_descriptor(&invalid, &device, &queue).unwrap_err(),
+        MeshError::IndexOutOfBounds {
+            index: 2,
+            vertex_count: 2
+        }
+    );
+}
+
+#[test]
+fn standard_vertex_layout() {
+    let (_, device, _) = wgpu_test_adapter::make_wgpu_connection();
+
+    let mesh = Mesh::from_data(&[], &[], &device);
+    assert_eq!(*mesh.vertex_layout(), VertexLayout::standard());
+    assert_eq!(
+        mesh.validate_vertex_stage_layouts(&[
+            VertexStageLayout::ComplexVertexData,
+            VertexStageLayout::InstanceData
+        ]),
+        Ok(())
+    );
+}
+
+#[test]
+fn skinned_vertex_layout() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+
+    let skin = VertexSkin {
+        joints: [0, 1, 0, 0],
+        weights: [0.75, 0.25, 0.0, 0.0],
+    };
+    let vertex = Vertex::new(
+        Vector3::zero(),
+        Vector3::unit_z(),
+        Vector3::unit_x(),
+        Vector2::zero(),
+    )
+    .with_skin(skin);
+    assert_eq!(
+        vertex.to_skinned_bytes().len() as u64,
+        VertexLayout::skinned().array_stride
+    );
+
+    let descriptor = MeshDescriptor::new(vec![vertex], vec![0]);
+    assert!(descriptor.is_skinned());
+
+    let mesh = Mesh::from_descriptor(&descriptor, &device, &queue).expect("Failure creating mesh");
+    assert_eq!(*mesh.vertex_layout(), VertexLayout::skinned());
+    assert_eq!(
+        mesh.validate_vertex_stage_layouts(&[
+            VertexStageLayout::SkinnedVertexData,
+            VertexStageLayout::InstanceData
+        ]),
+        Ok(())
+    );
+}
+
+#[test]
+fn unskinned_descriptor_keeps_standard_layout() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+
+    let descriptor = MeshDescriptor::new(dynamic_vertices(3, 0.0), vec![0, 1, 2]);
+    assert!(!descriptor.is_skinned());
+
+    let mesh = Mesh::from_descriptor(&descriptor, &device, &queue).expect("Failure creating mesh");
+    assert_eq!(*mesh.vertex_layout(), VertexLayout::standard());
+}
+
+#[test]
+fn recenter_moves_bounding_box_to_origin() {
+    // x: 100..102, y: 100..100
+    let mut descriptor = MeshDescriptor::new(dynamic_vertices(3, 100.0), vec![0, 1, 2]);
+
+    let offset = descriptor.recenter();
+    assert_eq!(offset, Vector3::new(101.0, 100.0, 0.0));
+
+    let bounding_box = descriptor.find_bounding_box();
+    assert_eq!(bounding_box.min, Point3::new(-1.0, 0.0, 0.0));
+    assert_eq!(bounding_box.max, Point3::new(1.0, 0.0, 0.0));
+}
+
+#[test]
+fn recenter_empty_mesh() {
+    let mut descriptor = MeshDescriptor::new(Vec::new(), Vec::new());
+
+    assert_eq!(descriptor.recenter(), Vector3::zero());
+}
+
+fn dynamic_vertices(count: usize, offset: f32) -> Vec<Vertex> {
+    (0..count)
+        .map(|i| Vertex {
+            position: Vector3::new(i as f32 + offset, offset, 0.0),
+            normal: Vector3::new(0.0, 0.0, 1.0),
+            tangent: Vector3::new(1.0, 0.0, 0.0),
+            bitangent: Vector3::new(0.0, 1.0, 0.0),
+            uv: Vector2::new(0.0, 0.0),
+            color: None,
+            skin: None,
+        })
+        .collect()
+}
+
+fn read_buffer(buffer: &Buffer, size: u64, device: &Device, queue: &Queue) -> Vec<u8> {
+    let staging = device.create_buffer(&BufferDescriptor {
+        label: None,
+        size,
+        usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor::default());
+    encoder.copy_buffer_to_buffer(buffer, 0, &staging, 0, size);
+    queue.submit([encoder.finish()]);
+
+    staging.slice(..).map_async(MapMode::Read, |_| {});
+    device
+        .poll(PollType::Wait)
+        .expect("Waiting for buffer mapping failed!");
+
+    let data = staging.slice(..).get_mapped_range().to_vec();
+    data
+}
+
+#[test]
+fn dynamic_mesh_update() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+
+    let mut mesh = Mesh::from_data_dynamic(&dynamic_vertices(3, 0.0), &[0, 1, 2], &device);
+    assert!(mesh.is_dynamic());
+    let vertex_buffer = mesh.vertex_buffer().clone();
+    let index_buffer = mesh.index_buffer().clone();
+
+    let vertices = dynamic_vertices(3, 10.0);
+    mesh.update(&vertices, &[2, 1], &device, &queue)
+        .expect("Updating dynamic mesh failed!");
+
+    // Data fits, so no new buffers must have been allocated
+    assert_eq!(mesh.vertex_buffer(), &vertex_buffer);
+    assert_eq!(mesh.index_buffer(), &index_buffer);
+    assert_eq!(mesh.index_count(), 2);
+
+    let expected_vertex_data = vertices
+        .iter()
+        .flat_map(|x| x.to_bytes())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        read_buffer(
+            mesh.vertex_buffer(),
+            expected_vertex_data.len() as u64,
+            &device,
+            &queue
+        ),
+        expected_vertex_data
+    );
+    assert_eq!(
+        read_buffer(mesh.index_buffer(), 8, &device, &queue),
+        [2u32.to_le_bytes(), 1u32.to_le_bytes()].concat()
+    );
+}
+
+#[test]
+fn dynamic_mesh_update_grows() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+
+    let mut mesh = Mesh::from_data_dynamic(&dynamic_vertices(3, 0.0), &[0, 1, 2], &device);
+    let vertex_buffer = mesh.vertex_buffer().clone();
+
+    let vertices = dynamic_vertices(6, 0.0);
+    mesh.update(&vertices, &[0, 1, 2, 3, 4, 5], &device, &queue)
+        .expect("Updating dynamic mesh failed!");
+
+    assert_ne!(mesh.vertex_buffer(), &vertex_buffer);
+    assert!(mesh.vertex_buffer().size() >= 6 * VertexLayout::standard().array_stride);
+    assert_eq!(mesh.index_count(), 6);
+}
+
+#[test]
+fn dynamic_descriptor_update_vertices() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+
+    let descriptor = MeshDescriptor {
+        dynamic: true,
+        ..MeshDescriptor::new(dynamic_vertices(3, 0.0), vec![0, 1, 2])
+    };
+    let mut mesh =
+        Mesh::from_descriptor(&descriptor, &device, &queue).expect("Failure creating mesh");
+    assert!(mesh.is_dynamic());
+    let initial_buffer = mesh.vertex_buffer().clone();
+
+    // More vertices than fit, thus the buffer must grow
+    mesh.update_vertices(&dynamic_vertices(8, 0.0), &device, &queue)
+        .expect("Updating dynamic mesh failed!");
+    assert_ne!(mesh.vertex_buffer(), &initial_buffer);
+    assert!(mesh.vertex_buffer().size() >= 8 * VertexLayout::standard().array_stride);
+    let grown_buffer = mesh.vertex_buffer().clone();
+
+    // Fewer vertices fit, thus the grown buffer must be reused
+    let vertices = dynamic_vertices(4, 5.0);
+    mesh.update_vertices(&vertices, &device, &queue)
+        .expect("Updating dynamic mesh failed!");
+    assert_eq!(mesh.vertex_buffer(), &grown_buffer);
+    // Indices are kept as they are
+    assert_eq!(mesh.index_count(), 3);
+
+    let expected_vertex_data = vertices
+        .iter()
+        .flat_map(|x| x.to_bytes())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        read_buffer(
+            mesh.vertex_buffer(),
+            expected_vertex_data.len() as u64,
+            &device,
+            &queue
+        ),
+        expected_vertex_data
+    );
+
+    // Index 2 needs at least three vertices
+    assert_eq!(
+        mesh.update_vertices(&dynamic_vertices(2, 0.0), &device, &queue),
+        Err(MeshError::IndexOutOfBounds {
+            index: 2,
+            vertex_count: 2,
+        })
+    );
+}
+
+#[test]
+fn dynamic_skinned_mesh_update() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+
+    let skinned_vertices = |count, offset| {
+        dynamic_vertices(count, offset)
+            .into_iter()
+            .map(|vertex| {
+                vertex.with_skin(VertexSkin {
+                    joints: [0, 1, 0, 0],
+                    weights: [0.5, 0.5, 0.0, 0.0],
+                })
+            })
+            .collect::<Vec<_>>()
+    };
+    let descriptor = MeshDescriptor {
+        dynamic: true,
+        ..MeshDescriptor::new(skinned_vertices(3, 0.0), vec![0, 1, 2])
+    };
+    let mut mesh =
+        Mesh::from_descriptor(&descriptor, &device, &queue).expect("Failure creating mesh");
+    assert_eq!(*mesh.vertex_layout(), VertexLayout::skinned());
+
+    // Must keep the skinned structure, rather than writing standard vertices
+    let vertices = skinned_vertices(3, 10.0);
+    mesh.update(&vertices, &[0, 1, 2], &device, &queue)
+        .expect("Updating dynamic mesh failed!");
+
+    let expected_vertex_data = vertices
+        .iter()
+        .flat_map(|x| x.to_skinned_bytes())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        read_buffer(
+            mesh.vertex_buffer(),
+            expected_vertex_data.len() as u64,
+            &device,
+            &queue
+        ),
+        expected_vertex_data
+    );
+}
+
+#[test]
+fn dynamic_custom_mesh_update() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+
+    let descriptor = MeshDescriptor {
+        indices: vec![0, 1],
+        dynamic: true,
+        raw_vertices: Some(RawVertexData {
+            data: vec![0; 32],
+            layout: custom_layout(),
+        }),
+        ..Default::default()
+    };
+    let mut mesh =
+        Mesh::from_descriptor(&descriptor, &device, &queue).expect("Failure creating mesh");
+
+    assert_eq!(
+        mesh.update(&dynamic_vertices(2, 0.0), &[0, 1], &device, &queue),
+        Err(MeshError::CustomVertexLayout)
+    );
+    assert_eq!(
+        mesh.update_vertices(&dynamic_vertices(2, 0.0), &device, &queue),
+        Err(MeshError::CustomVertexLayout)
+    );
+    mesh.update_raw(&[0; 48], &[0, 1, 2], &device, &queue)
+        .expect("Updating custom mesh with raw data failed!");
+}
+
+#[test]
+fn static_mesh_update() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+
+    let mut mesh = Mesh::from_data(&dynamic_vertices(3, 0.0), &[0, 1, 2], &device);
+    assert_eq!(
+        mesh.update(&dynamic_vertices(3, 1.0), &[0, 1, 2], &device, &queue),
+        Err(MeshError::NotDynamic)
+    );
+    assert_eq!(
+        mesh.update_vertices(&dynamic_vertices(3, 1.0), &device, &queue),
+        Err(MeshError::NotDynamic)
+    );
+}
+
+#[test]
+fn index_formats() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+
+    let vertices = dynamic_vertices(3, 0.0);
+    let indices = [0, 1, 2];
+
+    let mesh = Mesh::from_data_with_index_format(&vertices, &indices, IndexFormat::Uint16, &device)
+        .expect("Failure creating mesh");
+    assert_eq!(mesh.index_format(), IndexFormat::Uint16);
+    assert_eq!(mesh.index_count(), 3);
+    // Padded to the copy alignment
+    assert_eq!(mesh.index_buffer().size(), 8);
+    assert_eq!(
+        read_buffer(mesh.index_buffer(), 8, &device, &queue),
+        [0u16, 1, 2, 0].map(u16::to_le_bytes).concat()
+    );
+
+    let mesh = Mesh::from_data_with_index_format(&vertices, &indices, IndexFormat::Uint32, &device)
+        .expect("Failure creating mesh");
+    assert_eq!(mesh.index_format(), IndexFormat::Uint32);
+    assert_eq!(mesh.index_buffer().size(), 12);
+    assert_eq!(
+        read_buffer(mesh.index_buffer(), 12, &device, &queue),
+        [0u32, 1, 2].map(u32::to_le_bytes).concat()
+    );
+
+    // Descriptors carry their index format
+    let descriptor = MeshDescriptor {
+        index_format: IndexFormat::Uint16,
+        ..MeshDescriptor::new(vertices, indices.to_vec())
+    };
+    let mesh = Mesh::from_descriptor(&descriptor, &device, &queue).expect("Failure creating mesh");
+    assert_eq!(mesh.index_format(), IndexFormat::Uint16);
+}
+
+#[test]
+fn index_format_overflow() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+
+    let vertices = dynamic_vertices(3, 0.0);
+    let indices = [0, 65_535, 65_536, 2];
+
+    assert_eq!(
+        Mesh::from_data_with_index_format(&vertices, &indices, IndexFormat::Uint16, &device)
+            .map(|_| ()),
+        Err(MeshError::IndexFormatOverflow {
+            index: 65_536,
+            index_format: IndexFormat::Uint16
+        })
+    );
+
+    let descriptor = MeshDescriptor {
+        index_format: IndexFormat::Uint16,
+        ..MeshDescriptor::new(vertices, indices.to_vec())
+    };
+    assert!(matches!(
+        Mesh::from_descriptor(&descriptor, &device, &queue),
+        Err(MeshError::IndexFormatOverflow { index: 65_536, .. })
+    ));
+}
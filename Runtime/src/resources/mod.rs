@@ -7,6 +7,7 @@
 //! ## Key Resource Types
 //!
 //! - **Model**: Represents 3D models with meshes, materials, and instances
+//! - **Animation**: Keyframed transform animations, e.g. imported from glTF
 //! - **Camera**: Manages view and projection matrices for rendering
 //! - **Texture**: Handles image data for materials and environment mapping
 //! - **Light**: Represents different types of lighting in the scene
@@ -19,6 +20,7 @@
 //! and cleanup. The engine manages resource lifecycles automatically through
 //! the various stores in the world module.
 
+pub mod animation;
 pub mod bounding_box;
 pub mod buffer;
 pub mod camera;
@@ -31,11 +33,13 @@ pub mod mesh;
 pub mod model;
 pub mod pbr_material_shader;
 pub mod shader;
+pub mod skin;
 pub mod texture;
 pub mod transform;
 pub mod vertex;
 pub mod world_environment;
 
+pub use animation::*;
 pub use bounding_box::*;
 pub use buffer::*;
 pub use camera::*;
@@ -48,6 +52,7 @@ pub use mesh::*;
 pub use model::*;
 pub use pbr_material_shader::*;
 pub use shader::*;
+pub use skin::*;
 pub use texture::*;
 pub use transform::*;
 pub use vertex::*;
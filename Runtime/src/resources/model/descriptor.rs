@@ -7,9 +7,9 @@ use std::{
 use hashbrown::HashMap;
 use ulid::Ulid;
 
-use crate::resources::{MaterialShaderDescriptor, Mode, Transform};
+use crate::resources::{MaterialShaderDescriptor, Mode, SkinDescriptor, Transform};
 
-use super::MeshDescriptor;
+use super::{MeshDescriptor, RenderLayer};
 
 /// Descriptor for a model
 #[derive(Debug, Clone)]
@@ -21,6 +21,12 @@ pub struct ModelDescriptor {
     pub materials: Vec<Arc<MaterialShaderDescriptor>>,
     /// Multiple -> Multiple instances of the same model
     pub transforms: HashMap<Ulid, Transform>,
+    /// Skeleton of a skinned mesh.
+    /// Required if the mesh has skinning data and the materials use
+    /// [VertexStageLayout::SkinnedVertexData](crate::resources::VertexStageLayout::SkinnedVertexData).
+    pub skin: Option<Arc<SkinDescriptor>>,
+    /// When this model is drawn relative to others.
+    pub render_layer: RenderLayer,
 }
 
 impl ModelDescriptor {
@@ -86,7 +92,8 @@ impl ModelDescriptor {
         }
     }
 
-    /// Computes a hash for instance detection based on mesh and materials.
+    /// Computes a hash for instance detection based on mesh, materials and
+    /// render layer.
     pub fn instance_hash(&self) -> Ulid {
         let mut hasher = DefaultHasher::new();
 
@@ -99,6 +106,9 @@ impl ModelDescriptor {
             material.hash(&mut hasher);
         }
 
+        // Models on different layers are drawn separately
+        self.render_layer.hash(&mut hasher);
+
         let hash_u64 = hasher.finish();
         // Convert u64 hash to Ulid by using it as the lower 64 bits
         // and setting timestamp to 0 (deterministic for same inputs)
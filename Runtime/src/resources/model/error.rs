@@ -0,0 +1,26 @@
+use std::{error::Error, fmt::Display};
+
+use crate::resources::MeshError;
+
+#[derive(Debug, PartialEq)]
+pub enum ModelError {
+    /// The mesh doesn't provide the vertex attributes a material requires.
+    VertexLayoutMismatch {
+        material: Option<String>,
+        error: MeshError,
+    },
+}
+
+impl Display for ModelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ModelError::VertexLayoutMismatch { material, error } => write!(
+                f,
+                "Mesh doesn't match the vertex layout of material '{}': {error}",
+                material.as_deref().unwrap_or("unnamed")
+            ),
+        }
+    }
+}
+
+impl Error for ModelError {}
@@ -3,6 +3,7 @@ use std::{
     sync::{Arc, RwLock},
 };
 
+use cgmath::{EuclideanSpace, Point3, Vector3};
 use wgpu::{
     util::{BufferInitDescriptor, DeviceExt},
     Buffer, BufferUsages, Device, Queue, TextureFormat,
@@ -10,13 +11,19 @@ use wgpu::{
 
 pub use super::{Mesh, MeshDescriptor};
 use crate::{
-    cache::{Cache, CacheEntry},
-    resources::{Instance, MaterialShader, MaterialShaderDescriptor},
+    cache::Cache,
+    resources::{Instance, MaterialShader, MaterialShaderDescriptor, Skin},
 };
 
 mod descriptor;
 pub use descriptor::*;
 
+mod error;
+pub use error::*;
+
+mod render_layer;
+pub use render_layer::*;
+
 #[cfg(test)]
 mod tests;
 
@@ -26,6 +33,9 @@ pub struct Model {
     materials: Vec<Arc<MaterialShader>>,
     instance_count: u32,
     instance_buffer: Buffer,
+    skin: Option<Skin>,
+    render_layer: RenderLayer,
+    center: Point3<f32>,
 }
 
 impl Model {
@@ -38,14 +48,13 @@ impl Model {
         material_cache: &'cache RwLock<Cache<Arc<MaterialShaderDescriptor>, MaterialShader>>,
     ) -> Result<Self, Box<dyn Error + 'cache>> {
         // --- Mesh ---
+        // Only realize on cache misses, so models sharing descriptors
+        // (e.g. a batch of imported models) share their GPU resources.
         let mesh = match mesh_cache.write() {
             Ok(mut lock) => lock
-                .entry(descriptor.mesh.clone())
-                .or_insert(CacheEntry::new(Mesh::from_descriptor(
-                    &descriptor.mesh,
-                    device,
-                    queue,
-                )))
+                .get_or_try_insert_with(descriptor.mesh.clone(), || {
+                    Mesh::from_descriptor(&descriptor.mesh, device, queue)
+                })?
                 .clone_inner(),
             Err(e) => return Err(Box::new(e)),
         };
@@ -53,15 +62,35 @@ impl Model {
         // --- Material ---
         let mut materials = Vec::new();
         for material_descriptor in &descriptor.materials {
+            // The mesh decides how its indices are assembled
+            let material_descriptor =
+                if material_descriptor.primitive_topology != descriptor.mesh.primitive_topology {
+                    Arc::new(MaterialShaderDescriptor {
+                        primitive_topology: descriptor.mesh.primitive_topology,
+                        ..(**material_descriptor).clone()
+                    })
+                } else {
+                    material_descriptor.clone()
+                };
+
+            if let Some(vertex_stage_layouts) = &material_descriptor.vertex_stage_layouts {
+                mesh.validate_vertex_stage_layouts(vertex_stage_layouts)
+                    .map_err(|error| ModelError::VertexLayoutMismatch {
+                        material: material_descriptor.name.clone(),
+                        error,
+                    })?;
+            }
+
             materials.push(match material_cache.write() {
                 Ok(mut lock) => lock
-                    .entry(material_descriptor.clone())
-                    .or_insert(CacheEntry::new(MaterialShader::from_descriptor(
-                        material_descriptor,
-                        Some(*surface_format),
-                        device,
-                        queue,
-                    )?))
+                    .get_or_try_insert_with(material_descriptor.clone(), || {
+                        MaterialShader::from_descriptor(
+                            &material_descriptor,
+                            Some(*surface_format),
+                            device,
+                            queue,
+                        )
+                    })?
                     .clone_inner(),
                 Err(e) => return Err(Box::new(e)),
             });
@@ -89,11 +118,40 @@ impl Model {
             usage: BufferUsages::VERTEX,
         });
 
+        // --- Draw order ---
+        // Averaged over all instances, as they are drawn together
+        let mesh_center = {
+            let bounding_box = descriptor.mesh.bounding_box();
+            Point3::from_vec((bounding_box.min.to_vec() + bounding_box.max.to_vec()) / 2.0)
+        };
+        let center = match instances.len() {
+            0 => mesh_center,
+            count => Point3::from_vec(
+                instances
+                    .iter()
+                    .map(|instance| {
+                        (instance.make_model_space_matrix() * mesh_center.to_homogeneous())
+                            .truncate()
+                    })
+                    .sum::<Vector3<f32>>()
+                    / count as f32,
+            ),
+        };
+
+        // --- Skin ---
+        let skin = descriptor
+            .skin
+            .as_ref()
+            .map(|skin| Skin::from_descriptor(skin, device));
+
         Ok(Self {
             mesh,
             materials,
             instance_count,
             instance_buffer,
+            skin,
+            render_layer: descriptor.render_layer,
+            center,
         })
     }
 
@@ -101,6 +159,13 @@ impl Model {
         &self.mesh
     }
 
+    /// Returns a shared handle to the [Mesh] of this model.  
+    /// The [Mesh] may be shared with other models using the same
+    /// [MeshDescriptor].
+    pub fn shared_mesh(&self) -> Arc<Mesh> {
+        self.mesh.clone()
+    }
+
     pub fn materials(&self) -> &Vec<Arc<MaterialShader>> {
         &self.materials
     }
@@ -112,4 +177,20 @@ impl Model {
     pub fn instance_buffer(&self) -> &Buffer {
         &self.instance_buffer
     }
+
+    /// The joint palette of skinned models.
+    pub fn skin(&self) -> Option<&Skin> {
+        self.skin.as_ref()
+    }
+
+    pub fn render_layer(&self) -> RenderLayer {
+        self.render_layer
+    }
+
+    /// Center of the mesh bounds in world space, averaged over all
+    /// instances.
+    /// Used to sort draws by distance to the camera.
+    pub fn center(&self) -> Point3<f32> {
+        self.center
+    }
 }
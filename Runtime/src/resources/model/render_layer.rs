@@ -0,0 +1,35 @@
+/// Decides when a [Model](super::Model) is drawn relative to others.
+///
+/// Layers are drawn in ascending order.
+/// Inside a layer, opaque models are drawn front-to-back to reduce
+/// overdraw, while transparent models (see [RenderLayer::is_transparent])
+/// are drawn back-to-front to blend correctly.
+///
+/// Any value can be used, the constants are just common choices with room
+/// in between for custom layers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RenderLayer(pub u8);
+
+impl RenderLayer {
+    /// Drawn first, e.g. distant scenery.
+    pub const BACKGROUND: Self = Self(0);
+    /// Regular opaque geometry.
+    pub const WORLD: Self = Self(64);
+    /// Transparent geometry, drawn after all opaque geometry.
+    pub const TRANSPARENT: Self = Self(128);
+    /// Drawn last, e.g. UI or gizmos.
+    pub const OVERLAY: Self = Self(192);
+
+    /// Whether models on this layer are sorted back-to-front.  
+    /// This is the case for [RenderLayer::TRANSPARENT] and any layer above,
+    /// including [RenderLayer::OVERLAY].
+    pub fn is_transparent(&self) -> bool {
+        *self >= Self::TRANSPARENT
+    }
+}
+
+impl Default for RenderLayer {
+    fn default() -> Self {
+        Self::WORLD
+    }
+}
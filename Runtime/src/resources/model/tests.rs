@@ -6,15 +6,21 @@ use std::{
 use cgmath::{Vector2, Vector3};
 use hashbrown::HashMap;
 use ulid::Ulid;
-use wgpu::TextureFormat;
+use wgpu::{
+    IndexFormat, PrimitiveTopology, TextureFormat, VertexAttribute, VertexBufferLayout,
+    VertexFormat, VertexStepMode,
+};
 
 use crate::{
     cache::Cache,
-    resources::{MaterialDescriptor, MeshDescriptor, Transform, Vertex},
+    resources::{
+        MaterialDescriptor, MeshDescriptor, MeshError, RawVertexData, RenderLayer, Transform,
+        Vertex, VertexLayout, VertexStageLayout,
+    },
     wgpu_test_adapter,
 };
 
-use super::{Model, ModelDescriptor};
+use super::{Model, ModelDescriptor, ModelError};
 
 #[test]
 fn realization() {
@@ -32,11 +38,16 @@ fn realization() {
                 tangent: Vector3::new(1.0, 2.0, 3.0),
                 bitangent: Vector3::new(1.0, 2.0, 3.0),
                 uv: Vector2::new(1.0, 2.0),
+                color: None,
+                skin: None,
             }],
             indices: vec![0],
+            ..Default::default()
         }),
         materials: vec![Arc::new(MaterialDescriptor::default())],
         transforms,
+        skin: None,
+        render_layer: RenderLayer::default(),
     };
 
     let cache_mesh = RwLock::new(Cache::new(Duration::from_secs(5)));
@@ -52,3 +63,177 @@ fn realization() {
     )
     .expect("Failure realizing test model");
 }
+
+#[test]
+fn mesh_topology_replaces_material_topology() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+
+    let mut transforms = HashMap::new();
+    transforms.insert(Ulid::new(), Transform::default());
+
+    let vertex = Vertex {
+        position: Vector3::new(1.0, 2.0, 3.0),
+        normal: Vector3::new(1.0, 2.0, 3.0),
+        tangent: Vector3::new(1.0, 2.0, 3.0),
+        bitangent: Vector3::new(1.0, 2.0, 3.0),
+        uv: Vector2::new(1.0, 2.0),
+        color: None,
+        skin: None,
+    };
+    let descriptor = ModelDescriptor {
+        label: "Lines".to_string(),
+        mesh: Arc::new(MeshDescriptor {
+            vertices: vec![vertex; 2],
+            indices: vec![0, 1],
+            primitive_topology: PrimitiveTopology::LineList,
+            index_format: IndexFormat::Uint16,
+            ..Default::default()
+        }),
+        materials: vec![Arc::new(MaterialDescriptor::default())],
+        transforms,
+        skin: None,
+        render_layer: RenderLayer::default(),
+    };
+
+    let cache_mesh = RwLock::new(Cache::new(Duration::from_secs(5)));
+    let cache_material = RwLock::new(Cache::new(Duration::from_secs(5)));
+
+    let model = Model::from_descriptor(
+        &descriptor,
+        &TextureFormat::Rgba16Float,
+        &device,
+        &queue,
+        &cache_mesh,
+        &cache_material,
+    )
+    .expect("Failure realizing line model");
+    assert_eq!(model.mesh().index_format(), IndexFormat::Uint16);
+
+    let cache_material = cache_material.read().unwrap();
+    let topologies: Vec<_> = cache_material
+        .keys()
+        .map(|material| material.primitive_topology)
+        .collect();
+    assert_eq!(topologies, vec![PrimitiveTopology::LineList]);
+}
+
+#[test]
+fn vertex_layout_mismatch() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+
+    let mut transforms = HashMap::new();
+    transforms.insert(Ulid::new(), Transform::default());
+
+    // Requires an attribute the standard vertex layout doesn't provide
+    let material = MaterialDescriptor {
+        name: Some("Custom".to_string()),
+        vertex_stage_layouts: Some(vec![
+            VertexStageLayout::Custom(VertexBufferLayout {
+                array_stride: 4,
+                step_mode: VertexStepMode::Vertex,
+                attributes: &[VertexAttribute {
+                    offset: 0,
+                    shader_location: 15,
+                    format: VertexFormat::Float32,
+                }],
+            }),
+            VertexStageLayout::InstanceData,
+        ]),
+        ..Default::default()
+    };
+    let descriptor = ModelDescriptor {
+        label: "Mismatch".to_string(),
+        mesh: Arc::new(MeshDescriptor::default()),
+        materials: vec![Arc::new(material)],
+        transforms,
+        skin: None,
+        render_layer: RenderLayer::default(),
+    };
+
+    let cache_mesh = RwLock::new(Cache::new(Duration::from_secs(5)));
+    let cache_material = RwLock::new(Cache::new(Duration::from_secs(5)));
+
+    let error = Model::from_descriptor(
+        &descriptor,
+        &TextureFormat::Rgba16Float,
+        &device,
+        &queue,
+        &cache_mesh,
+        &cache_material,
+    )
+    .expect_err("Mismatching vertex layout must fail");
+
+    assert_eq!(
+        error.to_string(),
+        ModelError::VertexLayoutMismatch {
+            material: Some("Custom".to_string()),
+            error: MeshError::MissingAttribute {
+                shader_location: 15
+            },
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn custom_vertex_layout_mismatch() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+
+    let mut transforms = HashMap::new();
+    transforms.insert(Ulid::new(), Transform::default());
+
+    // Positions only, while the PBR layout requires normals and more
+    let layout = VertexLayout::new(
+        size_of::<[f32; 3]>() as u64,
+        vec![VertexAttribute {
+            offset: 0,
+            shader_location: 0,
+            format: VertexFormat::Float32x3,
+        }],
+    );
+    let material = MaterialDescriptor {
+        name: Some("Standard".to_string()),
+        vertex_stage_layouts: Some(vec![
+            VertexStageLayout::ComplexVertexData,
+            VertexStageLayout::InstanceData,
+        ]),
+        ..Default::default()
+    };
+    let descriptor = ModelDescriptor {
+        label: "Custom".to_string(),
+        mesh: Arc::new(MeshDescriptor {
+            indices: vec![0, 1, 2],
+            raw_vertices: Some(RawVertexData {
+                data: vec![0; 3 * size_of::<[f32; 3]>()],
+                layout,
+            }),
+            ..Default::default()
+        }),
+        materials: vec![Arc::new(material)],
+        transforms,
+        skin: None,
+        render_layer: RenderLayer::default(),
+    };
+
+    let cache_mesh = RwLock::new(Cache::new(Duration::from_secs(5)));
+    let cache_material = RwLock::new(Cache::new(Duration::from_secs(5)));
+
+    let error = Model::from_descriptor(
+        &descriptor,
+        &TextureFormat::Rgba16Float,
+        &device,
+        &queue,
+        &cache_mesh,
+        &cache_material,
+    )
+    .expect_err("Mismatching custom vertex layout must fail");
+
+    assert_eq!(
+        error.to_string(),
+        ModelError::VertexLayoutMismatch {
+            material: Some("Standard".to_string()),
+            error: MeshError::MissingAttribute { shader_location: 1 },
+        }
+        .to_string()
+    );
+}
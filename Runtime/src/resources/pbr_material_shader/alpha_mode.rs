@@ -0,0 +1,45 @@
+use wgpu::BlendState;
+
+/// Defines how the alpha channel of the albedo is interpreted.
+/// Mirrors glTF's `alphaMode`.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum AlphaMode {
+    /// Alpha is ignored and the material is fully opaque.
+    #[default]
+    Opaque,
+    /// Fragments with an alpha below `cutoff` are discarded, all others are
+    /// fully opaque.
+    /// glTF defaults the cutoff to `0.5`.
+    Mask { cutoff: f32 },
+    /// Alpha is used to blend the material with what is behind it.
+    Blend,
+}
+
+impl AlphaMode {
+    /// Value the PBR shader uses to select the alpha code path.
+    /// Must match the `ALPHA_MODE_*` constants in `pbr.wgsl`.
+    pub fn shader_value(&self) -> u32 {
+        match self {
+            Self::Opaque => 0,
+            Self::Mask { .. } => 1,
+            Self::Blend => 2,
+        }
+    }
+
+    /// Alpha cutoff passed to the PBR shader.
+    /// Only used by [`AlphaMode::Mask`].
+    pub fn cutoff(&self) -> f32 {
+        match self {
+            Self::Mask { cutoff } => *cutoff,
+            _ => 0.0,
+        }
+    }
+
+    /// The blend state a pipeline rendering this mode needs.
+    pub fn blend_state(&self) -> BlendState {
+        match self {
+            Self::Opaque | Self::Mask { .. } => BlendState::REPLACE,
+            Self::Blend => BlendState::ALPHA_BLENDING,
+        }
+    }
+}
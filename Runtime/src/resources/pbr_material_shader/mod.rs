@@ -1,14 +1,22 @@
-use cgmath::{Vector3, Zero};
+use std::sync::Arc;
+
+use cgmath::{Vector3, Vector4, Zero};
 use wgpu::{
-    Face, SamplerBindingType, TextureDimension, TextureFormat, TextureSampleType, TextureUsages,
+    Face, SamplerBindingType, TextureDimension, TextureFormat, TextureSampleType,
     TextureViewDimension,
 };
 
 use crate::resources::{
-    BufferDescriptor, FilterMode, MaterialShaderDescriptor, ShaderSource, TextureDescriptor,
-    TextureSize, VariableType, VertexStageLayout,
+    AddressMode, BufferDescriptor, FilterMode, MaterialShaderDescriptor, ShaderSource,
+    TextureDescriptor, TextureSize, TextureUsageIntent, VariableType, VertexStageLayout,
 };
 
+mod normal_map_space;
+pub use normal_map_space::*;
+
+mod alpha_mode;
+pub use alpha_mode::*;
+
 #[cfg(test)]
 mod tests;
 
@@ -19,15 +27,55 @@ pub struct PBRMaterialShaderDescriptor {
     // --- General ---
     pub name: Option<String>,
     // --- PBR specific ---
-    pub normal: TextureDescriptor,
-    pub albedo: TextureDescriptor,
-    pub albedo_factor: Vector3<f32>,
-    pub metallic: TextureDescriptor,
+    pub normal: Arc<TextureDescriptor>,
+    /// Defines how the [Self::normal] map is interpreted.
+    pub normal_map_space: NormalMapSpace,
+    /// Scales the X and Y components of the sampled [Self::normal],
+    /// flattening (`< 1.0`) or exaggerating (`> 1.0`) the surface detail.
+    pub normal_scale: f32,
+    pub albedo: Arc<TextureDescriptor>,
+    /// Multiplied with [Self::albedo], including its alpha channel.
+    pub albedo_factor: Vector4<f32>,
+    pub metallic: Arc<TextureDescriptor>,
     pub metallic_factor: f32,
-    pub roughness: TextureDescriptor,
+    pub roughness: Arc<TextureDescriptor>,
     pub roughness_factor: f32,
-    pub occlusion: TextureDescriptor,
-    pub emissive: TextureDescriptor,
+    pub occlusion: Arc<TextureDescriptor>,
+    /// Blends between no occlusion (`0.0`) and the full [Self::occlusion]
+    /// (`1.0`).
+    pub occlusion_strength: f32,
+    pub emissive: Arc<TextureDescriptor>,
+    /// Multiplied with [Self::emissive].
+    pub emissive_factor: Vector3<f32>,
+    /// Scales the emission beyond the `0..1` range of [Self::emissive] and
+    /// [Self::emissive_factor], e.g. for HDR light sources.
+    pub emissive_strength: f32,
+    /// Strength of a clear, reflective layer on top of the material, e.g.
+    /// for car paint (`KHR_materials_clearcoat`).
+    /// If set to `None`, the clearcoat lobe is disabled.
+    pub clearcoat_factor: Option<f32>,
+    /// Roughness of the clearcoat layer.
+    /// Only used if [Self::clearcoat_factor] is set.
+    pub clearcoat_roughness: f32,
+    /// Tangent-space normal map of the clearcoat layer.
+    /// If set to `None`, the geometry normal is used.
+    /// Only used if [Self::clearcoat_factor] is set.
+    pub clearcoat_normal: Option<Arc<TextureDescriptor>>,
+    /// Color of the back-scattering of fabric-like materials
+    /// (`KHR_materials_sheen`).
+    /// If set to `None`, the sheen lobe is disabled.
+    pub sheen_color: Option<Vector3<f32>>,
+    /// Roughness of the sheen.
+    /// Only used if [Self::sheen_color] is set.
+    pub sheen_roughness: f32,
+    /// Defines how the alpha channel of [Self::albedo] is used.
+    /// Anything but [AlphaMode::Opaque] replaces the blend state of the
+    /// resulting `MaterialShaderDescriptor`.
+    pub alpha_mode: AlphaMode,
+    /// Renders both sides of each face by disabling culling on the
+    /// resulting `MaterialShaderDescriptor`.
+    /// Otherwise, the culling of the base descriptor is kept.
+    pub double_sided: bool,
     // --- Material specific ---
     /// This field serves as a configuration base for creating a `MaterialShaderDescriptor`.
     /// If set to `Some(...)`, its contents will be used as the base configuration.
@@ -45,11 +93,51 @@ pub struct PBRMaterialShaderDescriptor {
     pub custom_material_shader: Option<MaterialShaderDescriptor>,
 }
 
+impl PBRMaterialShaderDescriptor {
+    /// Name defined for the PBR shader if [Self::clearcoat_factor] is set.
+    pub const CLEARCOAT_DEFINE: &'static str = "CLEARCOAT";
+    /// Name defined for the PBR shader if [Self::sheen_color] is set.
+    pub const SHEEN_DEFINE: &'static str = "SHEEN";
+
+    /// Size of the factor buffer, the last variable without extensions.
+    pub const FACTOR_BUFFER_SIZE: usize = 80;
+    /// Byte offsets of the factors inside the factor buffer.
+    pub const ALBEDO_FACTOR_OFFSET: usize = 0;
+    pub const METALLIC_FACTOR_OFFSET: usize = 16;
+    pub const ROUGHNESS_FACTOR_OFFSET: usize = 20;
+    pub const NORMAL_MAP_SPACE_OFFSET: usize = 24;
+    pub const ALPHA_CUTOFF_OFFSET: usize = 28;
+    pub const ALPHA_MODE_OFFSET: usize = 32;
+    pub const EMISSIVE_FACTOR_OFFSET: usize = 48;
+    pub const EMISSIVE_STRENGTH_OFFSET: usize = 60;
+    pub const NORMAL_SCALE_OFFSET: usize = 64;
+    pub const OCCLUSION_STRENGTH_OFFSET: usize = 68;
+
+    /// A flat tangent-space normal map, i.e. keeping the geometry normal.
+    fn flat_normal() -> TextureDescriptor {
+        TextureDescriptor::Data {
+            pixels: vec![128, 128, 255, 255],
+            size: TextureSize {
+                width: 1,
+                height: 1,
+                ..Default::default()
+            },
+            format: TextureFormat::Rgba8Unorm,
+            usages: TextureUsageIntent::SAMPLED.usages(),
+            texture_dimension: TextureDimension::D2,
+            texture_view_dimension: TextureViewDimension::D2,
+            filter_mode: FilterMode::default(),
+            address_mode: AddressMode::default(),
+            generate_mips: false,
+        }
+    }
+}
+
 impl Default for PBRMaterialShaderDescriptor {
     fn default() -> Self {
         Self {
             name: Some("Default PBR Material Shader".into()),
-            normal: TextureDescriptor::Data {
+            normal: Arc::new(TextureDescriptor::Data {
                 pixels: vec![0, 0, 0, 0],
                 size: TextureSize {
                     width: 1,
@@ -57,13 +145,17 @@ impl Default for PBRMaterialShaderDescriptor {
                     ..Default::default()
                 },
                 format: TextureFormat::Rgba8UnormSrgb,
-                usages: TextureUsages::all(),
+                usages: TextureUsageIntent::SAMPLED.usages(),
 
                 texture_dimension: TextureDimension::D2,
                 texture_view_dimension: TextureViewDimension::D2,
                 filter_mode: FilterMode::default(),
-            },
-            albedo: TextureDescriptor::Data {
+                address_mode: AddressMode::default(),
+                generate_mips: false,
+            }),
+            normal_map_space: NormalMapSpace::default(),
+            normal_scale: 1.0,
+            albedo: Arc::new(TextureDescriptor::Data {
                 pixels: vec![0, 0, 0, 0],
                 size: TextureSize {
                     width: 1,
@@ -71,13 +163,15 @@ impl Default for PBRMaterialShaderDescriptor {
                     ..Default::default()
                 },
                 format: TextureFormat::Rgba8UnormSrgb,
-                usages: TextureUsages::all(),
+                usages: TextureUsageIntent::SAMPLED.usages(),
                 texture_dimension: TextureDimension::D2,
                 texture_view_dimension: TextureViewDimension::D2,
                 filter_mode: FilterMode::default(),
-            },
-            albedo_factor: Vector3::zero(),
-            metallic: TextureDescriptor::Data {
+                address_mode: AddressMode::default(),
+                generate_mips: false,
+            }),
+            albedo_factor: Vector4::zero(),
+            metallic: Arc::new(TextureDescriptor::Data {
                 pixels: vec![0],
                 size: TextureSize {
                     width: 1,
@@ -85,13 +179,15 @@ impl Default for PBRMaterialShaderDescriptor {
                     ..Default::default()
                 },
                 format: TextureFormat::R8Unorm,
-                usages: TextureUsages::all(),
+                usages: TextureUsageIntent::SAMPLED.usages(),
                 texture_dimension: TextureDimension::D2,
                 texture_view_dimension: TextureViewDimension::D2,
                 filter_mode: FilterMode::default(),
-            },
+                address_mode: AddressMode::default(),
+                generate_mips: false,
+            }),
             metallic_factor: 0.0,
-            roughness: TextureDescriptor::Data {
+            roughness: Arc::new(TextureDescriptor::Data {
                 pixels: vec![0],
                 size: TextureSize {
                     width: 1,
@@ -99,13 +195,15 @@ impl Default for PBRMaterialShaderDescriptor {
                     ..Default::default()
                 },
                 format: TextureFormat::R8Unorm,
-                usages: TextureUsages::all(),
+                usages: TextureUsageIntent::SAMPLED.usages(),
                 texture_dimension: TextureDimension::D2,
                 texture_view_dimension: TextureViewDimension::D2,
                 filter_mode: FilterMode::default(),
-            },
+                address_mode: AddressMode::default(),
+                generate_mips: false,
+            }),
             roughness_factor: 0.0,
-            occlusion: TextureDescriptor::Data {
+            occlusion: Arc::new(TextureDescriptor::Data {
                 pixels: vec![0],
                 size: TextureSize {
                     width: 1,
@@ -113,12 +211,15 @@ impl Default for PBRMaterialShaderDescriptor {
                     ..Default::default()
                 },
                 format: TextureFormat::R8Unorm,
-                usages: TextureUsages::all(),
+                usages: TextureUsageIntent::SAMPLED.usages(),
                 texture_dimension: TextureDimension::D2,
                 texture_view_dimension: TextureViewDimension::D2,
                 filter_mode: FilterMode::default(),
-            },
-            emissive: TextureDescriptor::Data {
+                address_mode: AddressMode::default(),
+                generate_mips: false,
+            }),
+            occlusion_strength: 1.0,
+            emissive: Arc::new(TextureDescriptor::Data {
                 pixels: vec![0],
                 size: TextureSize {
                     width: 1,
@@ -126,11 +227,22 @@ impl Default for PBRMaterialShaderDescriptor {
                     ..Default::default()
                 },
                 format: TextureFormat::R8Unorm,
-                usages: TextureUsages::all(),
+                usages: TextureUsageIntent::SAMPLED.usages(),
                 texture_dimension: TextureDimension::D2,
                 texture_view_dimension: TextureViewDimension::D2,
                 filter_mode: FilterMode::default(),
-            },
+                address_mode: AddressMode::default(),
+                generate_mips: false,
+            }),
+            emissive_factor: Vector3::new(1.0, 1.0, 1.0),
+            emissive_strength: 1.0,
+            clearcoat_factor: None,
+            clearcoat_roughness: 0.0,
+            clearcoat_normal: None,
+            sheen_color: None,
+            sheen_roughness: 0.0,
+            alpha_mode: AlphaMode::default(),
+            double_sided: false,
             custom_material_shader: Default::default(),
         }
     }
@@ -153,6 +265,12 @@ impl From<PBRMaterialShaderDescriptor> for MaterialShaderDescriptor {
         };
 
         base.name = val.name;
+        if val.alpha_mode != AlphaMode::Opaque {
+            base.blend = Some(val.alpha_mode.blend_state());
+        }
+        if val.double_sided {
+            base.cull_mode = None;
+        }
         base.variables = vec![
             // Normal
             VariableType::Texture {
@@ -198,13 +316,33 @@ impl From<PBRMaterialShaderDescriptor> for MaterialShaderDescriptor {
                     val.albedo_factor.x.to_le_bytes(), // R
                     val.albedo_factor.y.to_le_bytes(), // G
                     val.albedo_factor.z.to_le_bytes(), // B
+                    val.albedo_factor.w.to_le_bytes(), // A
                     // Metallic Factor
                     val.metallic_factor.to_le_bytes(), // LUMA
                     // Roughness Factor
                     val.roughness_factor.to_le_bytes(), // LUMA
-                    // Padding to reach 32
+                    // Normal Map Space
+                    val.normal_map_space.shader_value().to_le_bytes(),
+                    // Alpha Cutoff
+                    val.alpha_mode.cutoff().to_le_bytes(),
+                    // Alpha Mode
+                    val.alpha_mode.shader_value().to_le_bytes(),
+                    // Padding, vec3 are aligned to 16 bytes
+                    [0; 4],
                     [0; 4],
                     [0; 4],
+                    // Emissive Factor
+                    val.emissive_factor.x.to_le_bytes(), // R
+                    val.emissive_factor.y.to_le_bytes(), // G
+                    val.emissive_factor.z.to_le_bytes(), // B
+                    // Emissive Strength
+                    val.emissive_strength.to_le_bytes(),
+                    // Normal Scale
+                    val.normal_scale.to_le_bytes(),
+                    // Occlusion Strength
+                    val.occlusion_strength.to_le_bytes(),
+                    // Padding to 16 bytes
+                    [0; 4],
                     [0; 4],
                 ]
                 .as_flattened()
@@ -213,6 +351,48 @@ impl From<PBRMaterialShaderDescriptor> for MaterialShaderDescriptor {
             }),
         ];
 
+        // Optional extensions are appended after the factors, the shader
+        // expects their bindings based on which of them are defined.
+        if let Some(clearcoat_factor) = val.clearcoat_factor {
+            base.defines
+                .push(PBRMaterialShaderDescriptor::CLEARCOAT_DEFINE);
+            base.variables.extend([
+                // Clearcoat Normal
+                VariableType::Texture {
+                    descriptor: val
+                        .clearcoat_normal
+                        .unwrap_or_else(|| Arc::new(PBRMaterialShaderDescriptor::flat_normal())),
+                    sample_type: TextureSampleType::Float { filterable: true },
+                    sampler_binding_type: SamplerBindingType::Filtering,
+                },
+                // Clearcoat Factors
+                VariableType::Buffer(BufferDescriptor {
+                    data: [
+                        clearcoat_factor.to_le_bytes(),
+                        val.clearcoat_roughness.to_le_bytes(),
+                    ]
+                    .as_flattened()
+                    .to_vec(),
+                    ..Default::default()
+                }),
+            ]);
+        }
+        if let Some(sheen_color) = val.sheen_color {
+            base.defines.push(PBRMaterialShaderDescriptor::SHEEN_DEFINE);
+            // Sheen Factors
+            base.variables.push(VariableType::Buffer(BufferDescriptor {
+                data: [
+                    sheen_color.x.to_le_bytes(), // R
+                    sheen_color.y.to_le_bytes(), // G
+                    sheen_color.z.to_le_bytes(), // B
+                    val.sheen_roughness.to_le_bytes(),
+                ]
+                .as_flattened()
+                .to_vec(),
+                ..Default::default()
+            }));
+        }
+
         // Add light buffer if it doesn't exist yet
         // For now, we'll rely on the World bind group for lights
 
@@ -0,0 +1,24 @@
+/// Defines which space the normals of a normal map are stored in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NormalMapSpace {
+    /// Normals are relative to the surface, i.e. they get transformed by the
+    /// tangent, bitangent and normal (TBN) of each vertex.
+    /// This is the most common format and the only one allowed by glTF.
+    #[default]
+    TangentSpace,
+    /// Normals are relative to the model itself and only get rotated by the
+    /// model transform.
+    /// Tangents and bitangents are ignored.
+    ObjectSpace,
+}
+
+impl NormalMapSpace {
+    /// Value the PBR shader uses to select the normal map code path.
+    /// Must match the `NORMAL_MAP_SPACE_*` constants in `pbr.wgsl`.
+    pub fn shader_value(&self) -> u32 {
+        match self {
+            Self::TangentSpace => 0,
+            Self::ObjectSpace => 1,
+        }
+    }
+}
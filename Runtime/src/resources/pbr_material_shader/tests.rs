@@ -1,9 +1,12 @@
-use wgpu::{Face, FrontFace, PolygonMode, PrimitiveTopology};
+use cgmath::{Vector3, Vector4};
+use wgpu::{BlendState, Face, FrontFace, PolygonMode, PrimitiveTopology};
 
 use crate::resources::{
-    MaterialShaderDescriptor, PBRMaterial, PBRMaterialDescriptor, PBRMaterialShaderDescriptor,
-    ShaderSource, VertexStageLayout,
+    AlphaMode, MaterialShader, MaterialShaderDescriptor, NormalMapSpace, PBRMaterial,
+    PBRMaterialDescriptor, PBRMaterialShaderDescriptor, ShaderSource, TextureDescriptor,
+    TextureUsageIntent, VariableType, VertexStageLayout,
 };
+use crate::wgpu_test_adapter;
 
 #[test]
 fn default() {
@@ -188,3 +191,406 @@ fn default_conversion_to_material_shader_check_depth_stencil_persistence() {
     let material_shader_descriptor: MaterialShaderDescriptor = pbr_material.into();
     assert_eq!(DEPTH_STENCIL, material_shader_descriptor.depth_stencil);
 }
+
+fn normal_map_space_factor(normal_map_space: NormalMapSpace) -> u32 {
+    let pbr_material = PBRMaterial {
+        normal_map_space,
+        ..Default::default()
+    };
+    let material_shader: MaterialShaderDescriptor = pbr_material.into();
+
+    let Some(VariableType::Buffer(factors)) = material_shader.variables.last() else {
+        panic!("Factor buffer expected as last variable");
+    };
+    assert_eq!(
+        factors.data.len(),
+        PBRMaterialShaderDescriptor::FACTOR_BUFFER_SIZE
+    );
+
+    u32::from_le_bytes(
+        factors.data[PBRMaterialShaderDescriptor::NORMAL_MAP_SPACE_OFFSET..][..4]
+            .try_into()
+            .unwrap(),
+    )
+}
+
+#[test]
+fn normal_map_space_default() {
+    assert_eq!(
+        PBRMaterial::default().normal_map_space,
+        NormalMapSpace::TangentSpace
+    );
+}
+
+#[test]
+fn normal_map_space_reaches_factors() {
+    assert_eq!(
+        normal_map_space_factor(NormalMapSpace::TangentSpace),
+        NormalMapSpace::TangentSpace.shader_value()
+    );
+    assert_eq!(
+        normal_map_space_factor(NormalMapSpace::ObjectSpace),
+        NormalMapSpace::ObjectSpace.shader_value()
+    );
+}
+
+#[test]
+fn normal_map_space_matches_shader() {
+    const SHADER: &str = include_str!("../../../../Assets/Shaders/pbr.wgsl");
+
+    for (constant, normal_map_space) in [
+        ("NORMAL_MAP_SPACE_TANGENT", NormalMapSpace::TangentSpace),
+        ("NORMAL_MAP_SPACE_OBJECT", NormalMapSpace::ObjectSpace),
+    ] {
+        let declaration = format!(
+            "const {constant}: u32 = {}u;",
+            normal_map_space.shader_value()
+        );
+        assert!(SHADER.contains(&declaration), "Missing '{declaration}'");
+    }
+}
+
+#[test]
+fn default_textures_use_minimal_usages() {
+    let pbr_material = PBRMaterial::default();
+
+    for texture in [
+        &pbr_material.normal,
+        &pbr_material.albedo,
+        &pbr_material.metallic,
+        &pbr_material.roughness,
+        &pbr_material.occlusion,
+        &pbr_material.emissive,
+    ] {
+        match &**texture {
+            TextureDescriptor::Data { usages, .. } => {
+                assert_eq!(*usages, TextureUsageIntent::SAMPLED.usages())
+            }
+            _ => panic!("Expected default PBR textures to be data textures!"),
+        }
+    }
+}
+
+fn alpha_mode_descriptor(alpha_mode: AlphaMode) -> MaterialShaderDescriptor {
+    PBRMaterial {
+        alpha_mode,
+        ..Default::default()
+    }
+    .into()
+}
+
+/// Returns the alpha cutoff and alpha mode from the factor buffer.
+fn alpha_factors(material_shader: &MaterialShaderDescriptor) -> (f32, u32) {
+    let Some(VariableType::Buffer(factors)) = material_shader.variables.last() else {
+        panic!("Factor buffer expected as last variable");
+    };
+
+    (
+        f32::from_le_bytes(
+            factors.data[PBRMaterialShaderDescriptor::ALPHA_CUTOFF_OFFSET..][..4]
+                .try_into()
+                .unwrap(),
+        ),
+        u32::from_le_bytes(
+            factors.data[PBRMaterialShaderDescriptor::ALPHA_MODE_OFFSET..][..4]
+                .try_into()
+                .unwrap(),
+        ),
+    )
+}
+
+#[test]
+fn alpha_mode_opaque() {
+    let material_shader = alpha_mode_descriptor(AlphaMode::Opaque);
+    assert_eq!(material_shader.blend, Some(BlendState::REPLACE));
+    assert_eq!(alpha_factors(&material_shader), (0.0, 0));
+}
+
+#[test]
+fn alpha_mode_mask() {
+    let material_shader = alpha_mode_descriptor(AlphaMode::Mask { cutoff: 0.25 });
+    assert_eq!(material_shader.blend, Some(BlendState::REPLACE));
+    assert_eq!(alpha_factors(&material_shader), (0.25, 1));
+}
+
+#[test]
+fn alpha_mode_blend() {
+    let material_shader = alpha_mode_descriptor(AlphaMode::Blend);
+    assert_eq!(material_shader.blend, Some(BlendState::ALPHA_BLENDING));
+    assert_eq!(alpha_factors(&material_shader), (0.0, 2));
+}
+
+#[test]
+fn alpha_mode_blend_overrides_custom_material_shader() {
+    let mut pbr_material = PBRMaterial {
+        alpha_mode: AlphaMode::Blend,
+        ..Default::default()
+    };
+    pbr_material.custom_material_shader = Some(MaterialShaderDescriptor {
+        blend: None,
+        ..Default::default()
+    });
+
+    let material_shader: MaterialShaderDescriptor = pbr_material.into();
+    assert_eq!(material_shader.blend, Some(BlendState::ALPHA_BLENDING));
+}
+
+#[test]
+fn alpha_mode_matches_shader() {
+    const SHADER: &str = include_str!("../../../../Assets/Shaders/pbr.wgsl");
+
+    for (constant, alpha_mode) in [
+        ("ALPHA_MODE_OPAQUE", AlphaMode::Opaque),
+        ("ALPHA_MODE_MASK", AlphaMode::Mask { cutoff: 0.5 }),
+        ("ALPHA_MODE_BLEND", AlphaMode::Blend),
+    ] {
+        let declaration = format!("const {constant}: u32 = {}u;", alpha_mode.shader_value());
+        assert!(SHADER.contains(&declaration), "Missing '{declaration}'");
+    }
+}
+
+#[test]
+fn single_sided_keeps_cull_mode() {
+    let material_shader: MaterialShaderDescriptor = PBRMaterial {
+        double_sided: false,
+        ..Default::default()
+    }
+    .into();
+    assert_eq!(material_shader.cull_mode, Some(Face::Front));
+}
+
+#[test]
+fn double_sided_disables_culling() {
+    let material_shader: MaterialShaderDescriptor = PBRMaterial {
+        double_sided: true,
+        ..Default::default()
+    }
+    .into();
+    assert_eq!(material_shader.cull_mode, None);
+}
+
+#[test]
+fn double_sided_realization() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+
+    const SHADER: &str = include_str!("../../../../Assets/Shaders/pbr.wgsl");
+
+    let mut material_shader = MaterialShaderDescriptor::from(PBRMaterial {
+        double_sided: true,
+        ..Default::default()
+    });
+    material_shader.shader_source = ShaderSource::String(SHADER);
+    let realization = MaterialShader::from_descriptor(&material_shader, None, &device, &queue)
+        .expect("Double sided PBR material must be realizable");
+
+    // Back faces need their normal frame flipped
+    assert!(realization.info().source.contains("@builtin(front_facing)"));
+}
+
+#[test]
+fn skinned_replaces_vertex_layout() {
+    let material_shader: MaterialShaderDescriptor = PBRMaterial::default().into();
+    assert!(!material_shader.is_skinned());
+
+    let skinned = material_shader.skinned();
+    assert!(skinned.is_skinned());
+    assert_eq!(
+        skinned.vertex_stage_layouts,
+        Some(vec![
+            VertexStageLayout::SkinnedVertexData,
+            VertexStageLayout::InstanceData
+        ])
+    );
+}
+
+#[test]
+fn skinned_realization() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+
+    const SHADER: &str = include_str!("../../../../Assets/Shaders/pbr.wgsl");
+
+    let mut material_shader = MaterialShaderDescriptor::from(PBRMaterial::default()).skinned();
+    material_shader.shader_source = ShaderSource::String(SHADER);
+    let realization = MaterialShader::from_descriptor(&material_shader, None, &device, &queue)
+        .expect("Skinned PBR material must be realizable");
+
+    assert!(realization.info().source.contains("joint_palette"));
+}
+
+#[test]
+fn albedo_factor_in_factor_buffer() {
+    let material_shader: MaterialShaderDescriptor = PBRMaterial {
+        albedo_factor: Vector4::new(0.25, 0.5, 0.75, 0.5),
+        ..Default::default()
+    }
+    .into();
+
+    let Some(VariableType::Buffer(factors)) = material_shader.variables.last() else {
+        panic!("Factor buffer expected as last variable");
+    };
+    let read_f32 =
+        |offset: usize| f32::from_le_bytes(factors.data[offset..offset + 4].try_into().unwrap());
+
+    let offset = PBRMaterialShaderDescriptor::ALBEDO_FACTOR_OFFSET;
+    assert_eq!(read_f32(offset), 0.25);
+    assert_eq!(read_f32(offset + 4), 0.5);
+    assert_eq!(read_f32(offset + 8), 0.75);
+    assert_eq!(read_f32(offset + 12), 0.5);
+}
+
+#[test]
+fn emissive_factors_in_factor_buffer() {
+    let material_shader: MaterialShaderDescriptor = PBRMaterial {
+        emissive_factor: Vector3::new(0.25, 0.5, 0.75),
+        emissive_strength: 8.0,
+        ..Default::default()
+    }
+    .into();
+
+    let Some(VariableType::Buffer(factors)) = material_shader.variables.last() else {
+        panic!("Factor buffer expected as last variable");
+    };
+    let read_f32 =
+        |offset: usize| f32::from_le_bytes(factors.data[offset..offset + 4].try_into().unwrap());
+
+    // Emissive factor is a vec3 following the alpha mode, aligned to 16 bytes
+    let offset = PBRMaterialShaderDescriptor::EMISSIVE_FACTOR_OFFSET;
+    assert_eq!(offset % 16, 0);
+    assert_eq!(read_f32(offset), 0.25);
+    assert_eq!(read_f32(offset + 4), 0.5);
+    assert_eq!(read_f32(offset + 8), 0.75);
+    assert_eq!(
+        read_f32(PBRMaterialShaderDescriptor::EMISSIVE_STRENGTH_OFFSET),
+        8.0
+    );
+}
+
+#[test]
+fn emissive_default_is_neutral() {
+    let pbr_material = PBRMaterial::default();
+
+    assert_eq!(pbr_material.emissive_factor, Vector3::new(1.0, 1.0, 1.0));
+    assert_eq!(pbr_material.emissive_strength, 1.0);
+}
+
+#[test]
+fn normal_scale_and_occlusion_strength_in_factor_buffer() {
+    let default = PBRMaterial::default();
+    assert_eq!(default.normal_scale, 1.0);
+    assert_eq!(default.occlusion_strength, 1.0);
+
+    let material_shader: MaterialShaderDescriptor = PBRMaterial {
+        normal_scale: 2.0,
+        occlusion_strength: 0.5,
+        ..Default::default()
+    }
+    .into();
+
+    let Some(VariableType::Buffer(factors)) = material_shader.variables.last() else {
+        panic!("Factor buffer expected as last variable");
+    };
+    let read_f32 =
+        |offset: usize| f32::from_le_bytes(factors.data[offset..offset + 4].try_into().unwrap());
+
+    assert_eq!(
+        read_f32(PBRMaterialShaderDescriptor::NORMAL_SCALE_OFFSET),
+        2.0
+    );
+    assert_eq!(
+        read_f32(PBRMaterialShaderDescriptor::OCCLUSION_STRENGTH_OFFSET),
+        0.5
+    );
+}
+
+#[test]
+fn extensions_grow_variables_only_if_set() {
+    let base: MaterialShaderDescriptor = PBRMaterial::default().into();
+    assert_eq!(7, base.variables.len());
+    assert!(base.defines.is_empty());
+
+    let clearcoat: MaterialShaderDescriptor = PBRMaterial {
+        clearcoat_factor: Some(1.0),
+        ..Default::default()
+    }
+    .into();
+    assert_eq!(9, clearcoat.variables.len());
+    assert_eq!(
+        clearcoat.defines,
+        vec![PBRMaterialShaderDescriptor::CLEARCOAT_DEFINE]
+    );
+
+    let sheen: MaterialShaderDescriptor = PBRMaterial {
+        sheen_color: Some(Vector3::new(1.0, 0.0, 0.0)),
+        ..Default::default()
+    }
+    .into();
+    assert_eq!(8, sheen.variables.len());
+    assert_eq!(
+        sheen.defines,
+        vec![PBRMaterialShaderDescriptor::SHEEN_DEFINE]
+    );
+
+    // Roughness and normal alone don't enable the extensions
+    let unused: MaterialShaderDescriptor = PBRMaterial {
+        clearcoat_roughness: 0.5,
+        clearcoat_normal: Some(PBRMaterial::default().normal),
+        sheen_roughness: 0.5,
+        ..Default::default()
+    }
+    .into();
+    assert_eq!(base.variables, unused.variables);
+    assert!(unused.defines.is_empty());
+}
+
+#[test]
+fn sheen_factors_in_buffer() {
+    let material_shader: MaterialShaderDescriptor = PBRMaterial {
+        sheen_color: Some(Vector3::new(0.25, 0.5, 0.75)),
+        sheen_roughness: 0.3,
+        ..Default::default()
+    }
+    .into();
+
+    let Some(VariableType::Buffer(factors)) = material_shader.variables.last() else {
+        panic!("Sheen buffer expected as last variable");
+    };
+    let read_f32 =
+        |offset: usize| f32::from_le_bytes(factors.data[offset..offset + 4].try_into().unwrap());
+
+    assert_eq!(read_f32(0), 0.25);
+    assert_eq!(read_f32(4), 0.5);
+    assert_eq!(read_f32(8), 0.75);
+    assert_eq!(read_f32(12), 0.3);
+}
+
+#[test]
+fn extensions_realization() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+
+    const SHADER: &str = include_str!("../../../../Assets/Shaders/pbr.wgsl");
+
+    let materials = [
+        PBRMaterial {
+            clearcoat_factor: Some(1.0),
+            ..Default::default()
+        },
+        PBRMaterial {
+            sheen_color: Some(Vector3::new(1.0, 1.0, 1.0)),
+            ..Default::default()
+        },
+        PBRMaterial {
+            clearcoat_factor: Some(0.5),
+            clearcoat_roughness: 0.2,
+            sheen_color: Some(Vector3::new(1.0, 1.0, 1.0)),
+            sheen_roughness: 0.8,
+            ..Default::default()
+        },
+    ];
+
+    for pbr_material in materials {
+        let mut material_shader = MaterialShaderDescriptor::from(pbr_material);
+        material_shader.shader_source = ShaderSource::String(SHADER);
+        MaterialShader::from_descriptor(&material_shader, None, &device, &queue)
+            .expect("PBR material with extensions must be realizable");
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::BTreeMap;
+
+use wgpu::{
+    naga::{front::wgsl, AddressSpace, TypeInner},
+    BufferBindingType,
+};
+
+use super::{ShaderError, VariableType};
+
+/// The kind of resource a single binding slot holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BindingKind {
+    UniformBuffer,
+    StorageBuffer,
+    Texture,
+    Sampler,
+    Other,
+}
+
+impl BindingKind {
+    fn name(self) -> &'static str {
+        match self {
+            BindingKind::UniformBuffer => "uniform buffer",
+            BindingKind::StorageBuffer => "storage buffer",
+            BindingKind::Texture => "texture",
+            BindingKind::Sampler => "sampler",
+            BindingKind::Other => "unsupported resource",
+        }
+    }
+}
+
+/// Checks that the bindings a WGSL shader declares in `group` line up with
+/// the bindings produced by `variables`.
+///
+/// Bindings are assigned the same way [`super::ShaderDescriptor::bind_group_layout`]
+/// does it: a buffer takes one binding, a texture takes two (texture followed
+/// by its sampler).
+/// Any binding the shader declares in a group above `highest_group` is
+/// reported as well, as there is no bind group to provide it.
+///
+/// If the shader can't be parsed, no validation happens and the error is
+/// left to the shader module creation to report.
+pub fn validate_shader_bindings(
+    source: &str,
+    group: u32,
+    highest_group: u32,
+    variables: &[VariableType],
+) -> Result<(), ShaderError> {
+    let module = match wgsl::parse_str(source) {
+        Ok(module) => module,
+        Err(_) => return Ok(()),
+    };
+
+    let mut declared = BTreeMap::new();
+    for (_, global) in module.global_variables.iter() {
+        let Some(resource_binding) = &global.binding else {
+            continue;
+        };
+
+        if resource_binding.group > highest_group {
+            return Err(ShaderError::BindingMismatch {
+                group: resource_binding.group,
+                binding: resource_binding.binding,
+                reason: format!(
+                    "shader declares a binding in group {}, but only groups up to {highest_group} are provided",
+                    resource_binding.group
+                ),
+            });
+        }
+
+        if resource_binding.group != group {
+            continue;
+        }
+
+        let kind = match (&global.space, &module.types[global.ty].inner) {
+            (AddressSpace::Uniform, _) => BindingKind::UniformBuffer,
+            (AddressSpace::Storage { .. }, _) => BindingKind::StorageBuffer,
+            (_, TypeInner::Image { .. }) => BindingKind::Texture,
+            (_, TypeInner::Sampler { .. }) => BindingKind::Sampler,
+            _ => BindingKind::Other,
+        };
+        declared.insert(resource_binding.binding, kind);
+    }
+
+    let mut expected = BTreeMap::new();
+    let mut binding_count = 0;
+    for variable in variables {
+        match variable {
+            VariableType::Buffer(buffer_descriptor) => {
+                let kind = match buffer_descriptor.ty {
+                    BufferBindingType::Uniform => BindingKind::UniformBuffer,
+                    BufferBindingType::Storage { .. } => BindingKind::StorageBuffer,
+                };
+                expected.insert(binding_count, kind);
+                binding_count += 1;
+            }
+            VariableType::Texture { .. } => {
+                expected.insert(binding_count, BindingKind::Texture);
+                expected.insert(binding_count + 1, BindingKind::Sampler);
+                binding_count += 2;
+            }
+        }
+    }
+
+    for (binding, declared_kind) in &declared {
+        match expected.get(binding) {
+            None => {
+                return Err(ShaderError::BindingMismatch {
+                    group,
+                    binding: *binding,
+                    reason: format!(
+                        "shader declares a {}, but no variable provides this binding",
+                        declared_kind.name()
+                    ),
+                });
+            }
+            Some(expected_kind) if expected_kind != declared_kind => {
+                return Err(ShaderError::BindingMismatch {
+                    group,
+                    binding: *binding,
+                    reason: format!(
+                        "shader declares a {}, but the variables provide a {}",
+                        declared_kind.name(),
+                        expected_kind.name()
+                    ),
+                });
+            }
+            Some(_) => (),
+        }
+    }
+
+    if let Some((binding, expected_kind)) = expected
+        .iter()
+        .find(|(binding, _)| !declared.contains_key(*binding))
+    {
+        return Err(ShaderError::BindingMismatch {
+            group,
+            binding: *binding,
+            reason: format!(
+                "variables provide a {}, but the shader doesn't declare this binding",
+                expected_kind.name()
+            ),
+        });
+    }
+
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use wgpu::naga::{front::wgsl, ShaderStage};
+
+use super::ShaderError;
+
+/// Checks that a WGSL shader declares each of the given entry points with
+/// the expected stage.
+///
+/// A mistyped entry point name would otherwise only surface as an opaque
+/// error during pipeline creation.
+///
+/// If the shader can't be parsed, no validation happens and the error is
+/// left to the shader module creation to report.
+pub fn validate_shader_entry_points(
+    source: &str,
+    entry_points: &[(&str, ShaderStage)],
+) -> Result<(), ShaderError> {
+    let module = match wgsl::parse_str(source) {
+        Ok(module) => module,
+        Err(_) => return Ok(()),
+    };
+
+    for (name, stage) in entry_points {
+        let declared = module
+            .entry_points
+            .iter()
+            .any(|entry_point| entry_point.name == *name && entry_point.stage == *stage);
+
+        if !declared {
+            return Err(ShaderError::MissingEntryPoint {
+                name: name.to_string(),
+                stage: *stage,
+            });
+        }
+    }
+
+    Ok(())
+}
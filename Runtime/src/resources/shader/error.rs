@@ -4,6 +4,8 @@ use std::{
     io::Error as IOError,
 };
 
+use wgpu::naga::ShaderStage;
+
 use crate::{resources::TextureError, shader_preprocessor::ShaderPreprocessorError};
 
 #[derive(Debug)]
@@ -11,11 +13,47 @@ pub enum ShaderError {
     ShaderPreprocessor(ShaderPreprocessorError),
     Texture(TextureError),
     IO(IOError),
+    /// The bindings declared in the shader don't match the bindings provided
+    /// by the shader's variables.
+    BindingMismatch {
+        group: u32,
+        binding: u32,
+        reason: String,
+    },
+    /// The shader doesn't declare an entry point with the given name for
+    /// the given stage.
+    MissingEntryPoint {
+        name: String,
+        stage: ShaderStage,
+    },
+    /// The realized variable at the given binding doesn't match its
+    /// [VariableType](crate::resources::VariableType).
+    VariableMismatch {
+        binding: u32,
+    },
 }
 
 impl Display for ShaderError {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        write!(f, "{self:?}")
+        match self {
+            ShaderError::BindingMismatch {
+                group,
+                binding,
+                reason,
+            } => write!(
+                f,
+                "Binding mismatch at @group({group}) @binding({binding}): {reason}"
+            ),
+            ShaderError::MissingEntryPoint { name, stage } => {
+                write!(f, "Shader has no {stage:?} entry point named '{name}'")
+            }
+            ShaderError::VariableMismatch { binding } => write!(
+                f,
+                "Realized variable at binding {binding} doesn't match its variable type"
+            ),
+            ShaderError::ShaderPreprocessor(e) => write!(f, "Shader preprocessing failed: {e}"),
+            _ => write!(f, "{self:?}"),
+        }
     }
 }
 
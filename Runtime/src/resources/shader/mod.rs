@@ -22,6 +22,12 @@ pub use variable_type::*;
 mod source;
 pub use source::*;
 
+mod binding_validation;
+pub use binding_validation::*;
+
+mod entry_point_validation;
+pub use entry_point_validation::*;
+
 #[cfg(test)]
 mod tests;
 
@@ -44,21 +50,47 @@ pub trait ShaderDescriptor {
         None
     }
 
-    fn shader_module(&self, device: &Device) -> Result<ShaderModule, ShaderError> {
-        let preprocessor = self.shader_preprocessor().unwrap_or(
-            ShaderPreprocessor::new_with_defaults().map_err(ShaderError::ShaderPreprocessor)?,
-        );
+    /// Returns [ShaderDescriptor::shader_preprocessor] or, if unset, a copy
+    /// of the [ShaderPreprocessor::shared] one.
+    fn preprocessor(&self) -> Result<ShaderPreprocessor, ShaderError> {
+        match self.shader_preprocessor() {
+            Some(preprocessor) => Ok(preprocessor),
+            None => ShaderPreprocessor::shared().map_err(ShaderError::ShaderPreprocessor),
+        }
+    }
 
+    fn preprocessed_source(&self) -> Result<String, ShaderError> {
+        self.preprocessed_source_with(&self.preprocessor()?)
+    }
+
+    /// Preprocesses the source with the given preprocessor, e.g. one with
+    /// additional imports.
+    fn preprocessed_source_with(
+        &self,
+        preprocessor: &ShaderPreprocessor,
+    ) -> Result<String, ShaderError> {
         let shader_source = self.source().read_as_string()?;
 
-        let preprocessed_source = preprocessor
+        preprocessor
             .parse_shader(shader_source)
-            .map_err(ShaderError::ShaderPreprocessor)?;
+            .map_err(ShaderError::ShaderPreprocessor)
+    }
 
-        Ok(device.create_shader_module(ShaderModuleDescriptor {
+    fn shader_module(&self, device: &Device) -> Result<ShaderModule, ShaderError> {
+        let preprocessed_source = self.preprocessed_source()?;
+        Ok(self.shader_module_from_source(device, preprocessed_source))
+    }
+
+    /// Creates the shader module from an already preprocessed source.
+    fn shader_module_from_source(
+        &self,
+        device: &Device,
+        preprocessed_source: String,
+    ) -> ShaderModule {
+        device.create_shader_module(ShaderModuleDescriptor {
             label: self.name().as_deref(),
             source: wgpu::ShaderSource::Wgsl(preprocessed_source.into()),
-        }))
+        })
     }
 
     fn bind_group_layout(
@@ -70,7 +102,6 @@ pub trait ShaderDescriptor {
             return Ok(None);
         }
 
-        let mut entries = Vec::new();
         let mut variables: Variables = Variables::new();
 
         let mut binding_count = 0;
@@ -85,7 +116,50 @@ pub trait ShaderDescriptor {
                         });
                         variables.insert(binding_count, Variable::Buffer(buffer));
 
-                        let entry = BindGroupLayoutEntry {
+                        binding_count += 1;
+                    }
+                    VariableType::Texture { descriptor, .. } => {
+                        // Note:
+                        // We are skipping over the sampler binding as it is already contained inside the `Texture` realization!
+                        // WGPU handles them as two separate resources (thus two binding indices), but we are treating it as **one**.
+                        // Regardless, we still need to skip over the binding index of the sampler, as later we will do the same in reverse: 1x `Texture` == 1x Texture binding + 1x Sampler binding.
+
+                        let texture = Texture::from_descriptor(descriptor, device, queue)
+                            .map_err(ShaderError::Texture)?;
+                        variables.insert(binding_count, Variable::Texture(texture));
+
+                        binding_count += 2;
+                    }
+                }
+            }
+        }
+
+        let entries = self.bind_group_layout_entries(&variables)?;
+
+        Ok(Some((
+            device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+                label: self.name().as_deref(),
+                entries: &entries,
+            }),
+            variables,
+        )))
+    }
+
+    /// Describes the bind group layout of the realized `variables`.  
+    /// Check [ShaderDescriptor::bind_group_layout] for how variables are
+    /// mapped to bindings.
+    fn bind_group_layout_entries(
+        &self,
+        variables: &Variables,
+    ) -> Result<Vec<BindGroupLayoutEntry>, ShaderError> {
+        let mut entries = Vec::new();
+
+        let mut binding_count = 0;
+        if let Some(variable_types) = self.variables() {
+            for variable_type in variable_types {
+                match variable_type {
+                    VariableType::Buffer(buffer_descriptor) => {
+                        entries.push(BindGroupLayoutEntry {
                             binding: binding_count,
                             visibility: self.stages(),
                             ty: BindingType::Buffer {
@@ -94,60 +168,48 @@ pub trait ShaderDescriptor {
                                 min_binding_size: buffer_descriptor.min_binding_size,
                             },
                             count: buffer_descriptor.count,
-                        };
-                        entries.push(entry);
-
+                        });
                         binding_count += 1;
                     }
                     VariableType::Texture {
-                        descriptor,
+                        descriptor: _,
                         sample_type,
                         sampler_binding_type,
                     } => {
-                        // Note:
-                        // We are skipping over the sampler binding as it is already contained inside the `Texture` realization!
-                        // WGPU handles them as two separate resources (thus two binding indices), but we are treating it as **one**.
-                        // Regardless, we still need to skip over the binding index of the sampler, as later we will do the same in reverse: 1x `Texture` == 1x Texture binding + 1x Sampler binding.
-
-                        let insert_index = binding_count;
-                        let texture = Texture::from_descriptor(descriptor, device, queue)
-                            .map_err(ShaderError::Texture)?;
+                        let view_dimension = match variables.get(&binding_count) {
+                            Some(Variable::Texture(texture)) => *texture.view_dimension(),
+                            _ => {
+                                return Err(ShaderError::VariableMismatch {
+                                    binding: binding_count,
+                                })
+                            }
+                        };
 
-                        let texture_binding = BindGroupLayoutEntry {
+                        entries.push(BindGroupLayoutEntry {
                             binding: binding_count,
                             visibility: self.stages(),
                             ty: BindingType::Texture {
                                 sample_type: *sample_type,
-                                view_dimension: *texture.view_dimension(),
+                                view_dimension,
                                 multisampled: false,
                             },
                             count: None,
-                        };
-                        entries.push(texture_binding);
+                        });
                         binding_count += 1;
 
-                        let sampler_binding = BindGroupLayoutEntry {
+                        entries.push(BindGroupLayoutEntry {
                             binding: binding_count,
                             visibility: self.stages(),
                             ty: BindingType::Sampler(*sampler_binding_type),
                             count: None,
-                        };
-                        entries.push(sampler_binding);
+                        });
                         binding_count += 1;
-
-                        variables.insert(insert_index, Variable::Texture(texture));
                     }
                 }
             }
         }
 
-        Ok(Some((
-            device.create_bind_group_layout(&BindGroupLayoutDescriptor {
-                label: self.name().as_deref(),
-                entries: &entries,
-            }),
-            variables,
-        )))
+        Ok(entries)
     }
 
     fn bind_group(
@@ -1,4 +1,4 @@
-use std::fs::read_to_string;
+use crate::asset;
 
 use super::ShaderError;
 
@@ -17,7 +17,7 @@ impl Default for ShaderSource {
 impl ShaderSource {
     pub fn read_as_string(self) -> Result<String, ShaderError> {
         match self {
-            ShaderSource::Path(path) => read_to_string(path).map_err(ShaderError::IO),
+            ShaderSource::Path(path) => asset::read_to_string(path).map_err(ShaderError::IO),
             ShaderSource::String(string) => Ok(string.to_string()),
         }
     }
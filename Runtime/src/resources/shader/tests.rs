@@ -1,10 +1,13 @@
+use std::sync::Arc;
+
 use log::warn;
 use rand::{rng, Rng};
 use wgpu::{SamplerBindingType, TextureSampleType};
 
 use crate::{
     resources::{
-        BufferDescriptor, ShaderDescriptor, ShaderSource, TextureDescriptor, VariableType,
+        BufferDescriptor, ShaderDescriptor, ShaderError, ShaderSource, TextureDescriptor,
+        VariableType, Variables,
     },
     wgpu_test_adapter,
 };
@@ -30,7 +33,7 @@ impl TestImplementation {
 
             for _ in 0..self.texture_count {
                 self.variables.push(VariableType::Texture {
-                    descriptor: TextureDescriptor::uniform_luma_black(),
+                    descriptor: Arc::new(TextureDescriptor::uniform_luma_black()),
                     sample_type: TextureSampleType::Float { filterable: false },
                     sampler_binding_type: SamplerBindingType::Filtering,
                 });
@@ -107,3 +110,19 @@ fn test_buffer_and_texture_count_random() {
     let texture_count = rng.random_range(1..=12);
     test(buffer_count, texture_count);
 }
+
+#[test]
+fn test_layout_entries_variable_mismatch() {
+    let mut test_impl = TestImplementation {
+        variables: Vec::new(),
+        buffer_count: 0,
+        texture_count: 1,
+    };
+    test_impl.do_work();
+
+    // The texture variable was never realized
+    assert!(matches!(
+        test_impl.bind_group_layout_entries(&Variables::new()),
+        Err(ShaderError::VariableMismatch { binding: 0 })
+    ));
+}
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use wgpu::{SamplerBindingType, TextureSampleType};
 
 use crate::resources::{BufferDescriptor, TextureDescriptor};
@@ -6,7 +8,9 @@ use crate::resources::{BufferDescriptor, TextureDescriptor};
 pub enum VariableType {
     Buffer(BufferDescriptor),
     Texture {
-        descriptor: TextureDescriptor,
+        /// Shared, so textures used by multiple materials don't have to
+        /// be copied.
+        descriptor: Arc<TextureDescriptor>,
         sample_type: TextureSampleType,
         sampler_binding_type: SamplerBindingType,
     },
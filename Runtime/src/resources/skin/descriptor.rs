@@ -0,0 +1,119 @@
+use cgmath::{Matrix4, SquareMatrix};
+
+use crate::resources::{AnimationDescriptor, Instance, Transform};
+
+/// A single joint (bone) of a [SkinDescriptor].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkinJoint {
+    pub label: String,
+    /// Index of the parent joint, if any.
+    pub parent: Option<usize>,
+    /// Transform relative to the parent joint.
+    pub transform: Transform,
+    /// Transforms from mesh space into the local space of this joint in
+    /// bind pose.
+    pub inverse_bind_matrix: Matrix4<f32>,
+}
+
+/// Describes the skeleton of a skinned mesh.
+/// Vertices reference joints by their index in [SkinDescriptor::joints].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkinDescriptor {
+    pub label: String,
+    pub joints: Vec<SkinJoint>,
+    /// Converts from the space the joints are defined in into mesh space.
+    /// Each joint matrix is wrapped by this conversion and its inverse.
+    /// Usually the identity, unless e.g. the mesh got converted to a
+    /// different up-axis on import.
+    pub space_conversion: Matrix4<f32>,
+}
+
+impl SkinDescriptor {
+    /// Computes the transform of each joint relative to the skin root by
+    /// walking up the joint hierarchy.
+    pub fn global_joint_transforms(&self) -> Vec<Matrix4<f32>> {
+        let local: Vec<_> = self
+            .joints
+            .iter()
+            .map(|joint| Instance::from(&joint.transform).make_model_space_matrix())
+            .collect();
+
+        (0..self.joints.len())
+            .map(|index| {
+                let mut matrix = local[index];
+                let mut current = index;
+
+                // Bounded by the joint count to not loop forever on broken
+                // hierarchies.
+                for _ in 0..self.joints.len() {
+                    let Some(parent) = self.joints[current].parent else {
+                        break;
+                    };
+                    let Some(parent_matrix) = local.get(parent) else {
+                        break;
+                    };
+
+                    matrix = parent_matrix * matrix;
+                    current = parent;
+                }
+
+                matrix
+            })
+            .collect()
+    }
+
+    /// Computes the joint matrices (the _joint palette_) skinned vertices
+    /// are transformed by.
+    /// In bind pose, each matrix is the identity.
+    pub fn joint_palette(&self) -> Vec<Matrix4<f32>> {
+        let conversion_inverse = self
+            .space_conversion
+            .invert()
+            .unwrap_or(Matrix4::identity());
+
+        self.global_joint_transforms()
+            .into_iter()
+            .zip(&self.joints)
+            .map(|(global, joint)| {
+                self.space_conversion * global * joint.inverse_bind_matrix * conversion_inverse
+            })
+            .collect()
+    }
+
+    /// Poses the skeleton with all channels of `animation` targeting a
+    /// joint of this skin, evaluated at `time` in seconds.
+    pub fn apply_animation(&mut self, animation: &AnimationDescriptor, time: f32) {
+        for joint in &mut self.joints {
+            animation.apply(&joint.label, time, &mut joint.transform);
+        }
+    }
+
+    /// Returns the [SkinDescriptor::joint_palette] as bytes, ready to be
+    /// uploaded into a storage buffer.
+    /// Contains at least one matrix, as empty buffers can't be bound.
+    pub fn to_binary_data(&self) -> Vec<u8> {
+        let mut palette = self.joint_palette();
+        if palette.is_empty() {
+            palette.push(Matrix4::identity());
+        }
+
+        palette
+            .iter()
+            .flat_map(|matrix| {
+                let columns: &[[f32; 4]; 4] = matrix.as_ref();
+                columns.concat()
+            })
+            .flat_map(|x| x.to_le_bytes())
+            .collect()
+    }
+}
+
+impl Default for SkinDescriptor {
+    fn default() -> Self {
+        Self {
+            label: "Unnamed".to_string(),
+            joints: Vec::new(),
+            space_conversion: Matrix4::identity(),
+        }
+    }
+}
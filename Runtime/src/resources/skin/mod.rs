@@ -0,0 +1,79 @@
+use wgpu::{
+    util::{BufferInitDescriptor, DeviceExt},
+    BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
+    BindGroupLayoutEntry, BindingType, Buffer, BufferBindingType, BufferUsages, Device, Queue,
+    ShaderStages,
+};
+
+mod descriptor;
+pub use descriptor::*;
+
+#[cfg(test)]
+mod tests;
+
+/// Realization of a [SkinDescriptor].
+/// Holds the joint palette, bound as `@group(2) @binding(0)` by skinned
+/// materials.
+#[derive(Debug)]
+pub struct Skin {
+    buffer: Buffer,
+    bind_group: BindGroup,
+}
+
+impl Skin {
+    /// Bind group index skinned materials expect the joint palette at.
+    pub const BIND_GROUP_INDEX: u32 = 2;
+
+    pub fn make_bind_group_layout(device: &Device) -> BindGroupLayout {
+        device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("Skin BindGroup Layout"),
+            entries: &[
+                // Joint Palette
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::VERTEX,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        })
+    }
+
+    pub fn from_descriptor(descriptor: &SkinDescriptor, device: &Device) -> Self {
+        let buffer = device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("Skin Joint Palette Buffer"),
+            contents: &descriptor.to_binary_data(),
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_DST,
+        });
+
+        let bind_group = device.create_bind_group(&BindGroupDescriptor {
+            label: Some("Skin BindGroup"),
+            layout: &Self::make_bind_group_layout(device),
+            entries: &[BindGroupEntry {
+                binding: 0,
+                resource: buffer.as_entire_binding(),
+            }],
+        });
+
+        Self { buffer, bind_group }
+    }
+
+    /// Uploads the joint palette of `descriptor`, e.g. after posing it.
+    /// The joint count must not change, otherwise the skin has to be
+    /// recreated.
+    pub fn update(&self, descriptor: &SkinDescriptor, queue: &Queue) {
+        queue.write_buffer(&self.buffer, 0, &descriptor.to_binary_data());
+    }
+
+    pub fn buffer(&self) -> &Buffer {
+        &self.buffer
+    }
+
+    pub fn bind_group(&self) -> &BindGroup {
+        &self.bind_group
+    }
+}
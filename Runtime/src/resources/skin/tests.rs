@@ -0,0 +1,121 @@
+use cgmath::{Matrix4, SquareMatrix, Vector3, Vector4};
+
+use crate::{
+    resources::{AnimationChannel, AnimationDescriptor, AnimationValues, Interpolation, Transform},
+    wgpu_test_adapter,
+};
+
+use super::{Skin, SkinDescriptor, SkinJoint};
+
+/// Two joints, the child sitting one unit above its parent, both in bind
+/// pose.
+fn two_joint_skin() -> SkinDescriptor {
+    SkinDescriptor {
+        label: "Arm".into(),
+        joints: vec![
+            SkinJoint {
+                label: "Shoulder".into(),
+                parent: None,
+                transform: Transform::default(),
+                inverse_bind_matrix: Matrix4::identity(),
+            },
+            SkinJoint {
+                label: "Elbow".into(),
+                parent: Some(0),
+                transform: Transform {
+                    position: Vector3::new(0.0, 1.0, 0.0),
+                    ..Default::default()
+                },
+                inverse_bind_matrix: Matrix4::from_translation(Vector3::new(0.0, -1.0, 0.0)),
+            },
+        ],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn bind_pose_palette_is_identity() {
+    let skin = two_joint_skin();
+
+    for matrix in skin.joint_palette() {
+        assert_eq!(matrix, Matrix4::identity());
+    }
+}
+
+#[test]
+fn global_transforms_follow_hierarchy() {
+    let mut skin = two_joint_skin();
+    skin.joints[0].transform.position = Vector3::new(2.0, 0.0, 0.0);
+
+    let global = skin.global_joint_transforms();
+    assert_eq!(
+        global[1] * Vector4::new(0.0, 0.0, 0.0, 1.0),
+        Vector4::new(2.0, 1.0, 0.0, 1.0)
+    );
+}
+
+#[test]
+fn space_conversion_wraps_palette() {
+    let mut skin = two_joint_skin();
+    skin.joints[0].transform.position = Vector3::new(0.0, 3.0, 0.0);
+    // (x, y, z) -> (x, z, -y)
+    skin.space_conversion = Matrix4::new(
+        1.0, 0.0, 0.0, 0.0, //
+        0.0, 0.0, -1.0, 0.0, //
+        0.0, 1.0, 0.0, 0.0, //
+        0.0, 0.0, 0.0, 1.0,
+    );
+
+    // Moving along +Y in joint space is moving along -Z in mesh space
+    let palette = skin.joint_palette();
+    assert_eq!(
+        palette[0] * Vector4::new(0.0, 0.0, 0.0, 1.0),
+        Vector4::new(0.0, 0.0, -3.0, 1.0)
+    );
+}
+
+#[test]
+fn animation_poses_joints() {
+    let mut skin = two_joint_skin();
+    let animation = AnimationDescriptor {
+        label: "Raise".into(),
+        channels: vec![AnimationChannel {
+            target: "Shoulder".into(),
+            interpolation: Interpolation::Linear,
+            times: vec![0.0, 1.0],
+            values: AnimationValues::Translation(vec![
+                Vector3::new(0.0, 0.0, 0.0),
+                Vector3::new(0.0, 0.0, 4.0),
+            ]),
+        }],
+    };
+
+    skin.apply_animation(&animation, 0.5);
+
+    assert_eq!(
+        skin.joints[0].transform.position,
+        Vector3::new(0.0, 0.0, 2.0)
+    );
+    // Children move along with their parent
+    assert_eq!(
+        skin.joint_palette()[1] * Vector4::new(0.0, 1.0, 0.0, 1.0),
+        Vector4::new(0.0, 1.0, 2.0, 1.0)
+    );
+}
+
+#[test]
+fn binary_data_is_never_empty() {
+    assert_eq!(SkinDescriptor::default().to_binary_data().len(), 64);
+    assert_eq!(two_joint_skin().to_binary_data().len(), 2 * 64);
+}
+
+#[test]
+fn realization() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+
+    let descriptor = two_joint_skin();
+    let skin = Skin::from_descriptor(&descriptor, &device);
+    assert_eq!(skin.buffer().size(), 2 * 64);
+
+    skin.update(&descriptor, &queue);
+}
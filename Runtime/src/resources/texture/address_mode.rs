@@ -0,0 +1,37 @@
+use wgpu::AddressMode as WAddressMode;
+
+/// Defines how texture coordinates outside of `0..1` are sampled, per axis.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct AddressMode {
+    pub u: WAddressMode,
+    pub v: WAddressMode,
+    pub w: WAddressMode,
+}
+
+impl AddressMode {
+    pub fn repeat() -> Self {
+        Self::all(WAddressMode::Repeat)
+    }
+
+    pub fn clamp_to_edge() -> Self {
+        Self::all(WAddressMode::ClampToEdge)
+    }
+
+    pub fn mirror_repeat() -> Self {
+        Self::all(WAddressMode::MirrorRepeat)
+    }
+
+    pub fn all(address_mode: WAddressMode) -> Self {
+        Self {
+            u: address_mode,
+            v: address_mode,
+            w: address_mode,
+        }
+    }
+}
+
+impl Default for AddressMode {
+    fn default() -> Self {
+        Self::repeat()
+    }
+}
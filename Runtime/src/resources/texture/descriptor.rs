@@ -1,7 +1,15 @@
-use std::{ffi::OsString, hash::Hash};
+use std::{ffi::OsString, hash::Hash, path::PathBuf};
 
-use crate::resources::texture::filter_mode::FilterMode;
-use crate::resources::TextureSize;
+#[cfg(not(target_arch = "wasm32"))]
+use std::io::Cursor;
+
+#[cfg(not(target_arch = "wasm32"))]
+use image::{ColorType, ImageReader};
+
+use crate::resources::texture::{address_mode::AddressMode, filter_mode::FilterMode};
+use crate::resources::{TextureError, TextureSize, TextureUsageIntent};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{asset, util::f32_to_f16};
 use wgpu::{Color, Extent3d, TextureDimension, TextureFormat, TextureUsages, TextureViewDimension};
 
 #[derive(Debug, Clone, PartialEq)]
@@ -28,6 +36,33 @@ pub enum TextureDescriptor {
         texture_dimension: TextureDimension,
         texture_view_dimension: TextureViewDimension,
         filter_mode: FilterMode,
+        /// Defines how coordinates outside of `0..1` are sampled.
+        address_mode: AddressMode,
+        /// Generates a full mip chain from the given pixels (the first
+        /// level) after uploading.
+        /// Replaces `size.mip_levels` and adds
+        /// [`TextureUsages::RENDER_ATTACHMENT`] to the usages.
+        ///
+        /// Only applies to 2D textures with renderable float formats, others
+        /// keep a single level.
+        generate_mips: bool,
+    },
+    /// Defines a texture from a KTX2 container.
+    /// The stored format and all mip levels are uploaded as-is, which
+    /// allows for block compressed (BC, ETC2, ASTC) textures.
+    /// The device must have the matching texture compression feature
+    /// enabled, otherwise the fallback is realized instead.
+    /// Without a fallback, realization fails.
+    ///
+    /// Supercompressed (e.g. Basis Universal) containers aren't supported.
+    Ktx2 {
+        bytes: Vec<u8>,
+        usages: TextureUsages,
+        filter_mode: FilterMode,
+        address_mode: AddressMode,
+        /// Used if the device doesn't support the container's format, e.g.
+        /// an uncompressed source of the same image.
+        fallback: Option<Box<TextureDescriptor>>,
     },
     /// In case you need a custom set of descriptors.
     Custom {
@@ -46,6 +81,71 @@ pub enum TextureDescriptor {
 }
 
 impl TextureDescriptor {
+    /// Loads and decodes an image file into a [TextureDescriptor::Data].  
+    /// The format is picked based on the image:
+    ///
+    /// - HDR (floating point) images use [TextureFormat::Rgba16Float],
+    ///   which, unlike 32-bit floats, can be filtered on any device.
+    /// - Any other image is converted into [TextureFormat::Rgba8UnormSrgb].
+    ///
+    /// Data maps, like normal or metallic-roughness maps, must not be
+    /// treated as sRGB. Use [Self::from_path_linear] for those.
+    ///
+    /// ⚠️ Not available on Web, as there is no file system access.
+    pub fn from_path<P: Into<PathBuf>>(path: P) -> Result<Self, TextureError> {
+        Self::decode_path(path.into(), true)
+    }
+
+    /// Same as [Self::from_path], but 8-bit images use
+    /// [TextureFormat::Rgba8Unorm], i.e. they are treated as linear data.
+    pub fn from_path_linear<P: Into<PathBuf>>(path: P) -> Result<Self, TextureError> {
+        Self::decode_path(path.into(), false)
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn decode_path(path: PathBuf, _srgb: bool) -> Result<Self, TextureError> {
+        Err(TextureError::FileSystemUnavailable(path))
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn decode_path(path: PathBuf, srgb: bool) -> Result<Self, TextureError> {
+        let bytes = asset::read(&path).map_err(TextureError::IOError)?;
+        let image = ImageReader::new(Cursor::new(bytes))
+            .with_guessed_format()
+            .map_err(TextureError::IOError)?
+            .decode()
+            .map_err(TextureError::ImageError)?;
+
+        let (pixels, format) = match image.color() {
+            ColorType::Rgb32F | ColorType::Rgba32F => (
+                image
+                    .to_rgba32f()
+                    .iter()
+                    .flat_map(|x| f32_to_f16(*x).to_le_bytes())
+                    .collect(),
+                TextureFormat::Rgba16Float,
+            ),
+            _ if srgb => (image.to_rgba8().into_raw(), TextureFormat::Rgba8UnormSrgb),
+            _ => (image.to_rgba8().into_raw(), TextureFormat::Rgba8Unorm),
+        };
+
+        Ok(Self::Data {
+            pixels,
+            size: TextureSize {
+                width: image.width(),
+                height: image.height(),
+                ..Default::default()
+            },
+            usages: TextureUsageIntent::SAMPLED.usages(),
+            format,
+            texture_dimension: TextureDimension::D2,
+            texture_view_dimension: TextureViewDimension::D2,
+            filter_mode: FilterMode::default(),
+            address_mode: AddressMode::default(),
+            generate_mips: false,
+        })
+    }
+
     pub fn uniform_rgba_white(srgb: bool) -> Self {
         Self::uniform_rgba_color(
             Color {
@@ -86,7 +186,7 @@ impl TextureDescriptor {
                 ((color.r.clamp(0.0, 1.0)) * 255.0) as u8,
                 ((color.g.clamp(0.0, 1.0)) * 255.0) as u8,
                 ((color.b.clamp(0.0, 1.0)) * 255.0) as u8,
-                255u8,
+                ((color.a.clamp(0.0, 1.0)) * 255.0) as u8,
             ],
             size: TextureSize {
                 width: 1,
@@ -95,7 +195,7 @@ impl TextureDescriptor {
                 base_mip: 0,
                 mip_levels: 1,
             },
-            usages: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+            usages: TextureUsageIntent::SAMPLED.usages(),
             format: if srgb {
                 TextureFormat::Rgba8UnormSrgb
             } else {
@@ -104,6 +204,8 @@ impl TextureDescriptor {
             texture_dimension: TextureDimension::D2,
             texture_view_dimension: TextureViewDimension::D2,
             filter_mode: FilterMode::default(),
+            address_mode: AddressMode::default(),
+            generate_mips: false,
         }
     }
 
@@ -120,10 +222,12 @@ impl TextureDescriptor {
                 ..Default::default()
             },
             format: TextureFormat::R8Unorm,
-            usages: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+            usages: TextureUsageIntent::SAMPLED.usages(),
             texture_dimension: TextureDimension::D2,
             texture_view_dimension: TextureViewDimension::D2,
             filter_mode: FilterMode::default(),
+            address_mode: AddressMode::default(),
+            generate_mips: false,
         }
     }
 
@@ -136,10 +240,12 @@ impl TextureDescriptor {
                 ..Default::default()
             },
             format: TextureFormat::R8Unorm,
-            usages: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+            usages: TextureUsageIntent::SAMPLED.usages(),
             texture_dimension: TextureDimension::D2,
             texture_view_dimension: TextureViewDimension::D2,
             filter_mode: FilterMode::default(),
+            address_mode: AddressMode::default(),
+            generate_mips: false,
         }
     }
 
@@ -152,11 +258,13 @@ impl TextureDescriptor {
                 ..Default::default()
             },
             format: TextureFormat::R8Unorm,
-            usages: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+            usages: TextureUsageIntent::SAMPLED.usages(),
 
             texture_dimension: TextureDimension::D2,
             texture_view_dimension: TextureViewDimension::D2,
             filter_mode: FilterMode::default(),
+            address_mode: AddressMode::default(),
+            generate_mips: false,
         }
     }
 
@@ -169,10 +277,12 @@ impl TextureDescriptor {
                 ..Default::default()
             },
             format: TextureFormat::R8Unorm,
-            usages: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+            usages: TextureUsageIntent::SAMPLED.usages(),
             texture_dimension: TextureDimension::D2,
             texture_view_dimension: TextureViewDimension::D2,
             filter_mode: FilterMode::default(),
+            address_mode: AddressMode::default(),
+            generate_mips: false,
         }
     }
 }
@@ -194,6 +304,8 @@ impl Hash for TextureDescriptor {
                 texture_dimension,
                 texture_view_dimension,
                 filter_mode,
+                address_mode,
+                generate_mips,
             } => {
                 pixels.hash(state);
                 size.hash(state);
@@ -202,6 +314,21 @@ impl Hash for TextureDescriptor {
                 texture_dimension.hash(state);
                 texture_view_dimension.hash(state);
                 filter_mode.hash(state);
+                address_mode.hash(state);
+                generate_mips.hash(state);
+            }
+            TextureDescriptor::Ktx2 {
+                bytes,
+                usages,
+                filter_mode,
+                address_mode,
+                fallback,
+            } => {
+                bytes.hash(state);
+                usages.hash(state);
+                filter_mode.hash(state);
+                address_mode.hash(state);
+                fallback.hash(state);
             }
             TextureDescriptor::Custom {
                 texture_descriptor,
@@ -1,5 +1,23 @@
+use crate::resources::Ktx2Error;
+use wgpu::{Features, TextureFormat, TextureUsages};
+
 #[derive(Debug)]
 pub enum TextureError {
     ImageError(image::ImageError),
     IOError(std::io::Error),
+    Ktx2Error(Ktx2Error),
+    /// The format requires device features which aren't enabled.
+    UnsupportedFormat {
+        format: TextureFormat,
+        missing_features: Features,
+    },
+    /// The format doesn't support (some of) the requested usages.
+    UnsupportedUsages {
+        format: TextureFormat,
+        requested: TextureUsages,
+        supported: TextureUsages,
+    },
+    /// Textures can't be loaded from the given path, as the platform has
+    /// no file system access (e.g. Web).
+    FileSystemUnavailable(std::path::PathBuf),
 }
@@ -5,6 +5,11 @@ pub struct FilterMode {
     pub mag: WFilterMode,
     pub min: WFilterMode,
     pub mipmap: WFilterMode,
+    /// Maximum anisotropy used for sampling, `1` disables anisotropic
+    /// filtering.
+    /// Only applies if all filters are [WFilterMode::Linear], otherwise
+    /// it's ignored.
+    pub anisotropy_clamp: u16,
 }
 
 impl FilterMode {
@@ -13,6 +18,7 @@ impl FilterMode {
             mag: WFilterMode::Linear,
             min: WFilterMode::Linear,
             mipmap: WFilterMode::Linear,
+            anisotropy_clamp: 1,
         }
     }
 
@@ -21,6 +27,29 @@ impl FilterMode {
             mag: WFilterMode::Nearest,
             min: WFilterMode::Nearest,
             mipmap: WFilterMode::Nearest,
+            anisotropy_clamp: 1,
+        }
+    }
+
+    /// Linear filtering with up to `anisotropy_clamp` samples.
+    /// Most devices support up to `16`.
+    pub fn anisotropic(anisotropy_clamp: u16) -> Self {
+        Self {
+            anisotropy_clamp,
+            ..Self::linear()
+        }
+    }
+
+    /// Returns the [FilterMode::anisotropy_clamp] if it can be applied.
+    pub fn effective_anisotropy_clamp(&self) -> u16 {
+        let all_linear = self.mag == WFilterMode::Linear
+            && self.min == WFilterMode::Linear
+            && self.mipmap == WFilterMode::Linear;
+
+        if all_linear {
+            self.anisotropy_clamp.max(1)
+        } else {
+            1
         }
     }
 }
@@ -31,6 +60,7 @@ impl Default for FilterMode {
             mag: WFilterMode::Linear,
             min: WFilterMode::Linear,
             mipmap: WFilterMode::Nearest,
+            anisotropy_clamp: 1,
         }
     }
 }
@@ -0,0 +1,26 @@
+// Downsamples the previous mip level into the current one.
+// A 2x2 box filter is used via `textureLoad`, which works for filterable and
+// non-filterable float formats alike.
+
+@group(0) @binding(0)
+var source: texture_2d<f32>;
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+    // Single triangle covering the whole target
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+
+@fragment
+fn fs_main(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    let max_coord = vec2<i32>(textureDimensions(source)) - 1;
+    let base = vec2<i32>(position.xy) * 2;
+
+    var sum = vec4<f32>(0.0);
+    sum += textureLoad(source, min(base, max_coord), 0);
+    sum += textureLoad(source, min(base + vec2<i32>(1, 0), max_coord), 0);
+    sum += textureLoad(source, min(base + vec2<i32>(0, 1), max_coord), 0);
+    sum += textureLoad(source, min(base + vec2<i32>(1, 1), max_coord), 0);
+    return sum * 0.25;
+}
@@ -0,0 +1,270 @@
+use std::{
+    error::Error,
+    fmt::{Display, Formatter},
+    ops::Range,
+};
+
+use wgpu::{AstcBlock, AstcChannel, TextureFormat};
+
+/// File identifier every KTX2 container starts with.
+pub const KTX2_IDENTIFIER: [u8; 12] = [
+    0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A,
+];
+
+const HEADER_LENGTH: usize = 80;
+const LEVEL_INDEX_ENTRY_LENGTH: usize = 24;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Ktx2Error {
+    /// The data doesn't start with [KTX2_IDENTIFIER].
+    InvalidIdentifier,
+    /// The header or level index point outside of the data.
+    Truncated,
+    /// The `vkFormat` of the container has no matching [TextureFormat].
+    /// A format of `0` (`VK_FORMAT_UNDEFINED`) usually means the data is
+    /// Basis Universal encoded and has to be transcoded first.
+    UnsupportedVkFormat(u32),
+    /// The mip levels are supercompressed (e.g. BasisLZ or Zstandard).
+    UnsupportedSupercompression(u32),
+    /// Volume (3D) textures aren't supported.
+    UnsupportedVolumeTexture,
+    /// The size of a block compressed texture isn't a multiple of the
+    /// format's block dimensions.
+    UnalignedDimensions {
+        width: u32,
+        height: u32,
+        block_width: u32,
+        block_height: u32,
+    },
+    /// A mip level doesn't hold exactly the bytes its size, format and
+    /// layer count require.
+    InvalidLevelLength {
+        level: usize,
+        expected: usize,
+        length: usize,
+    },
+}
+
+impl Display for Ktx2Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Ktx2Error::InvalidIdentifier => write!(f, "Data is not a KTX2 container"),
+            Ktx2Error::Truncated => write!(f, "KTX2 container is truncated"),
+            Ktx2Error::UnsupportedVkFormat(vk_format) => {
+                write!(f, "Unsupported KTX2 vkFormat {vk_format}")
+            }
+            Ktx2Error::UnsupportedSupercompression(scheme) => {
+                write!(f, "Unsupported KTX2 supercompression scheme {scheme}")
+            }
+            Ktx2Error::UnsupportedVolumeTexture => {
+                write!(f, "KTX2 volume textures are not supported")
+            }
+            Ktx2Error::UnalignedDimensions {
+                width,
+                height,
+                block_width,
+                block_height,
+            } => write!(
+                f,
+                "KTX2 texture size {width}x{height} is not a multiple of the {block_width}x{block_height} block size"
+            ),
+            Ktx2Error::InvalidLevelLength {
+                level,
+                expected,
+                length,
+            } => write!(
+                f,
+                "KTX2 mip level {level} has {length} bytes, but {expected} bytes are required"
+            ),
+        }
+    }
+}
+
+impl Error for Ktx2Error {}
+
+/// A parsed KTX2 container.
+/// Mip level data is kept as ranges into the original bytes, starting
+/// with the largest level.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ktx2Texture {
+    pub format: TextureFormat,
+    pub width: u32,
+    pub height: u32,
+    /// Array layers times faces.
+    pub depth_or_array_layers: u32,
+    pub layer_count: u32,
+    pub face_count: u32,
+    pub levels: Vec<Range<usize>>,
+}
+
+impl Ktx2Texture {
+    pub fn parse(bytes: &[u8]) -> Result<Self, Ktx2Error> {
+        if !Self::is_ktx2(bytes) {
+            return Err(Ktx2Error::InvalidIdentifier);
+        }
+        if bytes.len() < HEADER_LENGTH {
+            return Err(Ktx2Error::Truncated);
+        }
+
+        let vk_format = read_u32(bytes, 12);
+        let width = read_u32(bytes, 20);
+        let height = read_u32(bytes, 24);
+        let depth = read_u32(bytes, 28);
+        let layer_count = read_u32(bytes, 32);
+        let face_count = read_u32(bytes, 36);
+        let level_count = read_u32(bytes, 40);
+        let supercompression = read_u32(bytes, 44);
+
+        if supercompression != 0 {
+            return Err(Ktx2Error::UnsupportedSupercompression(supercompression));
+        }
+        if depth > 1 {
+            return Err(Ktx2Error::UnsupportedVolumeTexture);
+        }
+        let format = vk_format_to_texture_format(vk_format)
+            .ok_or(Ktx2Error::UnsupportedVkFormat(vk_format))?;
+
+        // A level count of zero asks the loader to generate mip maps.
+        // We only upload what is stored.
+        let level_count = level_count.max(1) as usize;
+        let level_index_end = HEADER_LENGTH + level_count * LEVEL_INDEX_ENTRY_LENGTH;
+        if bytes.len() < level_index_end {
+            return Err(Ktx2Error::Truncated);
+        }
+
+        let layer_count = layer_count.max(1);
+        let face_count = face_count.max(1);
+        let height = height.max(1);
+
+        let (block_width, block_height) = format.block_dimensions();
+        if !width.is_multiple_of(block_width) || !height.is_multiple_of(block_height) {
+            return Err(Ktx2Error::UnalignedDimensions {
+                width,
+                height,
+                block_width,
+                block_height,
+            });
+        }
+        let block_size = format
+            .block_copy_size(None)
+            .expect("KTX2 formats always have a block copy size") as usize;
+
+        let levels = (0..level_count)
+            .map(|level| {
+                let entry = HEADER_LENGTH + level * LEVEL_INDEX_ENTRY_LENGTH;
+                let offset = read_u64(bytes, entry) as usize;
+                let length = read_u64(bytes, entry + 8) as usize;
+
+                let end = offset.checked_add(length).ok_or(Ktx2Error::Truncated)?;
+                if end > bytes.len() {
+                    return Err(Ktx2Error::Truncated);
+                }
+
+                // Each level stores all layers and faces, made of whole blocks
+                let blocks_wide = (width >> level).max(1).div_ceil(block_width) as usize;
+                let blocks_high = (height >> level).max(1).div_ceil(block_height) as usize;
+                let expected =
+                    blocks_wide * blocks_high * block_size * (layer_count * face_count) as usize;
+                if length != expected {
+                    return Err(Ktx2Error::InvalidLevelLength {
+                        level,
+                        expected,
+                        length,
+                    });
+                }
+
+                Ok(offset..end)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            format,
+            width,
+            height,
+            depth_or_array_layers: layer_count * face_count,
+            layer_count,
+            face_count,
+            levels,
+        })
+    }
+
+    pub fn is_ktx2(bytes: &[u8]) -> bool {
+        bytes.starts_with(&KTX2_IDENTIFIER)
+    }
+
+    pub fn mip_level_count(&self) -> u32 {
+        self.levels.len() as u32
+    }
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
+
+/// Maps a Vulkan `VkFormat` as used by KTX2 to a [TextureFormat].
+pub fn vk_format_to_texture_format(vk_format: u32) -> Option<TextureFormat> {
+    let format = match vk_format {
+        9 => TextureFormat::R8Unorm,
+        16 => TextureFormat::Rg8Unorm,
+        37 => TextureFormat::Rgba8Unorm,
+        43 => TextureFormat::Rgba8UnormSrgb,
+        97 => TextureFormat::Rgba16Float,
+        109 => TextureFormat::Rgba32Float,
+        131 | 133 => TextureFormat::Bc1RgbaUnorm,
+        132 | 134 => TextureFormat::Bc1RgbaUnormSrgb,
+        135 => TextureFormat::Bc2RgbaUnorm,
+        136 => TextureFormat::Bc2RgbaUnormSrgb,
+        137 => TextureFormat::Bc3RgbaUnorm,
+        138 => TextureFormat::Bc3RgbaUnormSrgb,
+        139 => TextureFormat::Bc4RUnorm,
+        140 => TextureFormat::Bc4RSnorm,
+        141 => TextureFormat::Bc5RgUnorm,
+        142 => TextureFormat::Bc5RgSnorm,
+        143 => TextureFormat::Bc6hRgbUfloat,
+        144 => TextureFormat::Bc6hRgbFloat,
+        145 => TextureFormat::Bc7RgbaUnorm,
+        146 => TextureFormat::Bc7RgbaUnormSrgb,
+        147 => TextureFormat::Etc2Rgb8Unorm,
+        148 => TextureFormat::Etc2Rgb8UnormSrgb,
+        149 => TextureFormat::Etc2Rgb8A1Unorm,
+        150 => TextureFormat::Etc2Rgb8A1UnormSrgb,
+        151 => TextureFormat::Etc2Rgba8Unorm,
+        152 => TextureFormat::Etc2Rgba8UnormSrgb,
+        153 => TextureFormat::EacR11Unorm,
+        154 => TextureFormat::EacR11Snorm,
+        155 => TextureFormat::EacRg11Unorm,
+        156 => TextureFormat::EacRg11Snorm,
+        157..=184 => {
+            let block = match (vk_format - 157) / 2 {
+                0 => AstcBlock::B4x4,
+                1 => AstcBlock::B5x4,
+                2 => AstcBlock::B5x5,
+                3 => AstcBlock::B6x5,
+                4 => AstcBlock::B6x6,
+                5 => AstcBlock::B8x5,
+                6 => AstcBlock::B8x6,
+                7 => AstcBlock::B8x8,
+                8 => AstcBlock::B10x5,
+                9 => AstcBlock::B10x6,
+                10 => AstcBlock::B10x8,
+                11 => AstcBlock::B10x10,
+                12 => AstcBlock::B12x10,
+                _ => AstcBlock::B12x12,
+            };
+            let channel = if (vk_format - 157).is_multiple_of(2) {
+                AstcChannel::Unorm
+            } else {
+                AstcChannel::UnormSrgb
+            };
+
+            TextureFormat::Astc { block, channel }
+        }
+        _ => return None,
+    };
+
+    Some(format)
+}
@@ -0,0 +1,95 @@
+use std::sync::{LazyLock, Mutex};
+
+use hashbrown::HashMap;
+use wgpu::{
+    include_wgsl, BindGroupLayout, BindGroupLayoutDescriptor, BindGroupLayoutEntry, BindingType,
+    Device, FragmentState, MultisampleState, PipelineLayoutDescriptor, PrimitiveState,
+    RenderPipeline, RenderPipelineDescriptor, ShaderStages, TextureFormat, TextureSampleType,
+    TextureViewDimension, VertexState,
+};
+
+type DevicePipelines = (Device, HashMap<TextureFormat, MipMapPipeline>);
+
+/// Pipelines of the most recently used [Device], one per [TextureFormat].
+/// Only a single device is kept, so a recreated device (e.g. after a device
+/// loss) replaces the pipelines of the old one.
+static MIP_MAP_PIPELINES: LazyLock<Mutex<Option<DevicePipelines>>> =
+    LazyLock::new(|| Mutex::new(None));
+
+/// Render pipeline downsampling a mip level into the next one.
+#[derive(Debug, Clone)]
+pub(crate) struct MipMapPipeline {
+    pub bind_group_layout: BindGroupLayout,
+    pub pipeline: RenderPipeline,
+}
+
+impl MipMapPipeline {
+    /// Returns the cached pipeline for `format` on `device`, creating it on
+    /// the first use.
+    pub fn get(format: TextureFormat, device: &Device) -> Self {
+        let mut lock = MIP_MAP_PIPELINES
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if lock
+            .as_ref()
+            .is_none_or(|(cached_device, _)| cached_device != device)
+        {
+            *lock = Some((device.clone(), HashMap::new()));
+        }
+
+        let (_, pipelines) = lock.as_mut().expect("Cache was initialized above");
+        pipelines
+            .entry(format)
+            .or_insert_with(|| Self::new(format, device))
+            .clone()
+    }
+
+    fn new(format: TextureFormat, device: &Device) -> Self {
+        let shader = device.create_shader_module(include_wgsl!("generate_mip_maps.wgsl"));
+        let bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("Mip Map Generation"),
+            entries: &[BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStages::FRAGMENT,
+                ty: BindingType::Texture {
+                    sample_type: TextureSampleType::Float { filterable: false },
+                    view_dimension: TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            }],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("Mip Map Generation"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
+            label: Some("Mip Map Generation"),
+            layout: Some(&pipeline_layout),
+            vertex: VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                compilation_options: Default::default(),
+                buffers: &[],
+            },
+            fragment: Some(FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                compilation_options: Default::default(),
+                targets: &[Some(format.into())],
+            }),
+            primitive: PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        Self {
+            bind_group_layout,
+            pipeline,
+        }
+    }
+}
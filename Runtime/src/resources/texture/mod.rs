@@ -1,14 +1,19 @@
-use std::ffi::OsString;
+use std::{ffi::OsString, io::Cursor};
 
 use cgmath::{Vector2, Vector4};
 use image::ImageReader;
+
+use crate::asset;
+use crate::mip_level::full_mip_level_count;
 use wgpu::wgt::PollType;
 use wgpu::{
-    AddressMode, BufferDescriptor, BufferUsages, CommandEncoderDescriptor, Device, Extent3d,
-    FilterMode as WFilterMode, Origin3d, Queue, Sampler, SamplerDescriptor, TexelCopyBufferInfo,
+    AddressMode as WAddressMode, BindGroupDescriptor, BindGroupEntry, BindingResource,
+    BufferDescriptor, BufferUsages, Color, CommandEncoderDescriptor, Device, Extent3d,
+    FilterMode as WFilterMode, LoadOp, Operations, Origin3d, Queue, RenderPassColorAttachment,
+    RenderPassDescriptor, Sampler, SamplerDescriptor, StoreOp, TexelCopyBufferInfo,
     TexelCopyBufferLayout, TexelCopyTextureInfo, Texture as WTexture, TextureAspect,
-    TextureDescriptor as WTextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
-    TextureView, TextureViewDescriptor, TextureViewDimension,
+    TextureDescriptor as WTextureDescriptor, TextureDimension, TextureFormat, TextureSampleType,
+    TextureUsages, TextureView, TextureViewDescriptor, TextureViewDimension,
 };
 
 mod size;
@@ -23,6 +28,21 @@ pub use descriptor::*;
 mod filter_mode;
 pub use filter_mode::*;
 
+mod address_mode;
+pub use address_mode::*;
+
+mod sampling;
+pub use sampling::*;
+
+mod usage;
+pub use usage::*;
+
+mod ktx2;
+pub use ktx2::*;
+
+mod mip_map_pipeline;
+use mip_map_pipeline::*;
+
 #[cfg(test)]
 mod tests;
 
@@ -42,6 +62,8 @@ impl Texture {
     ) -> Result<Self, TextureError> {
         match descriptor {
             TextureDescriptor::File { path, usages } => {
+                validate_texture_usages(TextureFormat::Rgba8UnormSrgb, *usages, device)?;
+
                 Self::from_path_srgb(path, *usages, device, queue)
             }
             TextureDescriptor::Data {
@@ -53,31 +75,111 @@ impl Texture {
                 texture_dimension,
                 texture_view_dimension,
                 filter_mode,
-            } => Ok(Self::from_data(
-                pixels,
-                size,
-                *usages,
-                *format,
-                *texture_dimension,
-                *texture_view_dimension,
-                *filter_mode,
-                device,
-                queue,
-            )),
+                address_mode,
+                generate_mips,
+            } => {
+                let mut generate_mips = *generate_mips;
+                if generate_mips
+                    && (*texture_dimension != TextureDimension::D2
+                        || !Self::supports_mip_map_generation(*format, device))
+                {
+                    log::warn!(
+                        "Can't generate mip maps for {texture_dimension:?} texture with format {format:?}, using a single level instead!"
+                    );
+                    generate_mips = false;
+                }
+
+                if !generate_mips {
+                    validate_texture_usages(*format, *usages, device)?;
+
+                    return Ok(Self::from_data(
+                        pixels,
+                        size,
+                        *usages,
+                        *format,
+                        *texture_dimension,
+                        *texture_view_dimension,
+                        Sampling {
+                            filter_mode: *filter_mode,
+                            address_mode: *address_mode,
+                        },
+                        device,
+                        queue,
+                    ));
+                }
+
+                // Mip levels are rendered into, one after another
+                let usages = *usages | TextureUsages::RENDER_ATTACHMENT;
+                validate_texture_usages(*format, usages, device)?;
+
+                let texture = Self::from_data(
+                    pixels,
+                    &TextureSize {
+                        base_mip: 0,
+                        mip_levels: full_mip_level_count(size.width, size.height),
+                        ..*size
+                    },
+                    usages,
+                    *format,
+                    *texture_dimension,
+                    *texture_view_dimension,
+                    Sampling {
+                        filter_mode: *filter_mode,
+                        address_mode: *address_mode,
+                    },
+                    device,
+                    queue,
+                );
+                texture.generate_mip_maps(device, queue);
+
+                Ok(texture)
+            }
+            TextureDescriptor::Ktx2 {
+                bytes,
+                usages,
+                filter_mode,
+                address_mode,
+                fallback,
+            } => match (
+                Self::from_ktx2(bytes, *usages, *filter_mode, *address_mode, device, queue),
+                fallback,
+            ) {
+                (
+                    Err(TextureError::UnsupportedFormat {
+                        format,
+                        missing_features,
+                    }),
+                    Some(fallback),
+                ) => {
+                    log::warn!(
+                        "KTX2 texture format {format:?} requires missing features {missing_features:?}, using fallback instead!"
+                    );
+                    Self::from_descriptor(fallback, device, queue)
+                }
+                (result, _) => result,
+            },
             TextureDescriptor::Custom {
                 texture_descriptor,
                 view_descriptor,
                 sampler_descriptor,
                 size,
                 data,
-            } => Ok(Self::from_descriptors_and_data(
-                texture_descriptor,
-                view_descriptor,
-                sampler_descriptor,
-                Some((data, *size)),
-                device,
-                queue,
-            )),
+            } => {
+                validate_texture_usages(
+                    texture_descriptor.format,
+                    texture_descriptor.usage,
+                    device,
+                )?;
+
+                Ok(Self::from_descriptors_and_data(
+                    texture_descriptor,
+                    view_descriptor,
+                    sampler_descriptor,
+                    Some((data, *size)),
+                    device,
+                    queue,
+                ))
+            }
         }
     }
 
@@ -115,9 +217,9 @@ impl Texture {
 
         let sampler = device.create_sampler(&SamplerDescriptor {
             label,
-            address_mode_u: AddressMode::ClampToEdge,
-            address_mode_v: AddressMode::ClampToEdge,
-            address_mode_w: AddressMode::ClampToEdge,
+            address_mode_u: WAddressMode::ClampToEdge,
+            address_mode_v: WAddressMode::ClampToEdge,
+            address_mode_w: WAddressMode::ClampToEdge,
             mag_filter: WFilterMode::Linear,
             min_filter: WFilterMode::Linear,
             mipmap_filter: WFilterMode::Linear,
@@ -204,7 +306,9 @@ impl Texture {
         device: &Device,
         queue: &Queue,
     ) -> Result<Self, TextureError> {
-        let img = ImageReader::open(file_path)
+        let bytes = asset::read(file_path).map_err(TextureError::IOError)?;
+        let img = ImageReader::new(Cursor::new(bytes))
+            .with_guessed_format()
             .map_err(TextureError::IOError)?
             .decode()
             .map_err(TextureError::ImageError)?;
@@ -230,6 +334,8 @@ impl Texture {
                 texture_dimension: TextureDimension::D2,
                 texture_view_dimension: TextureViewDimension::D2,
                 filter_mode: FilterMode::default(),
+                address_mode: AddressMode::default(),
+                generate_mips: false,
             },
             device,
             queue,
@@ -275,7 +381,7 @@ impl Texture {
             format,
             TextureDimension::D2,
             TextureViewDimension::D2,
-            FilterMode::default(),
+            Sampling::default(),
             device,
             queue,
         )
@@ -288,7 +394,7 @@ impl Texture {
         format: TextureFormat,
         texture_dimension: TextureDimension,
         texture_view_dimension: TextureViewDimension,
-        filter_mode: FilterMode,
+        sampling: Sampling,
         device: &Device,
         queue: &Queue,
     ) -> Self {
@@ -319,18 +425,10 @@ impl Texture {
         };
         let texture_view = texture.create_view(&texture_view_descriptor);
 
-        let texture_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
-            label: None,
-            address_mode_u: AddressMode::Repeat,
-            address_mode_v: AddressMode::Repeat,
-            address_mode_w: AddressMode::Repeat,
-            mag_filter: filter_mode.mag,
-            min_filter: filter_mode.min,
-            mipmap_filter: filter_mode.mipmap,
-            lod_min_clamp: 0.0,
-            lod_max_clamp: 100.0,
-            ..Default::default()
-        });
+        let texture_sampler = device.create_sampler(&Self::sampler_descriptor(
+            sampling.filter_mode,
+            sampling.address_mode,
+        ));
 
         let texture = Self::from_existing(
             texture,
@@ -423,6 +521,188 @@ impl Texture {
         texture
     }
 
+    /// Creates a texture from a KTX2 container, uploading every stored
+    /// mip level in the container's format.
+    /// Fails if the format isn't supported by the device, e.g. when the
+    /// required texture compression feature isn't enabled, or if a block
+    /// compressed texture isn't made of whole blocks.
+    pub fn from_ktx2(
+        bytes: &[u8],
+        usages: TextureUsages,
+        filter_mode: FilterMode,
+        address_mode: AddressMode,
+        device: &Device,
+        queue: &Queue,
+    ) -> Result<Self, TextureError> {
+        let ktx2 = Ktx2Texture::parse(bytes).map_err(TextureError::Ktx2Error)?;
+        validate_texture_usages(ktx2.format, usages, device)?;
+
+        let view_dimension = match (ktx2.layer_count, ktx2.face_count) {
+            (1, 6) => TextureViewDimension::Cube,
+            (_, 6) => TextureViewDimension::CubeArray,
+            (1, _) => TextureViewDimension::D2,
+            _ => TextureViewDimension::D2Array,
+        };
+
+        let texture = device.create_texture(&WTextureDescriptor {
+            label: Some("KTX2 Texture"),
+            size: Extent3d {
+                width: ktx2.width,
+                height: ktx2.height,
+                depth_or_array_layers: ktx2.depth_or_array_layers,
+            },
+            mip_level_count: ktx2.mip_level_count(),
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: ktx2.format,
+            usage: usages,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&TextureViewDescriptor {
+            dimension: Some(view_dimension),
+            ..Default::default()
+        });
+        let sampler = device.create_sampler(&Self::sampler_descriptor(filter_mode, address_mode));
+
+        let (block_width, block_height) = ktx2.format.block_dimensions();
+        let block_size = ktx2
+            .format
+            .block_copy_size(None)
+            .expect("KTX2 formats always have a block copy size");
+
+        for (mip_level, range) in ktx2.levels.iter().enumerate() {
+            let blocks_wide = (ktx2.width >> mip_level).max(1).div_ceil(block_width);
+            let blocks_high = (ktx2.height >> mip_level).max(1).div_ceil(block_height);
+
+            queue.write_texture(
+                TexelCopyTextureInfo {
+                    texture: &texture,
+                    aspect: TextureAspect::All,
+                    origin: Origin3d::ZERO,
+                    mip_level: mip_level as u32,
+                },
+                &bytes[range.clone()],
+                TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(blocks_wide * block_size),
+                    rows_per_image: Some(blocks_high),
+                },
+                // Copies of block compressed formats have to cover whole
+                // blocks, even if the mip level itself is smaller.
+                texture
+                    .size()
+                    .mip_level_size(mip_level as u32, TextureDimension::D2)
+                    .physical_size(ktx2.format),
+            );
+        }
+
+        Ok(Self::from_existing(texture, view, sampler, view_dimension))
+    }
+
+    /// Creates the [`SamplerDescriptor`] used for textures realized from a
+    /// [`TextureDescriptor`].
+    pub fn sampler_descriptor(
+        filter_mode: FilterMode,
+        address_mode: AddressMode,
+    ) -> SamplerDescriptor<'static> {
+        SamplerDescriptor {
+            label: None,
+            address_mode_u: address_mode.u,
+            address_mode_v: address_mode.v,
+            address_mode_w: address_mode.w,
+            mag_filter: filter_mode.mag,
+            min_filter: filter_mode.min,
+            mipmap_filter: filter_mode.mipmap,
+            lod_min_clamp: 0.0,
+            lod_max_clamp: 100.0,
+            anisotropy_clamp: filter_mode.effective_anisotropy_clamp(),
+            ..Default::default()
+        }
+    }
+
+    /// Checks whether [`Self::generate_mip_maps`] can be used for the
+    /// given format.
+    /// Mip levels are rendered to, thus the format has to be renderable and
+    /// readable as float.
+    pub fn supports_mip_map_generation(format: TextureFormat, device: &Device) -> bool {
+        let renderable = format
+            .guaranteed_format_features(device.features())
+            .allowed_usages
+            .contains(TextureUsages::RENDER_ATTACHMENT);
+        let float = matches!(
+            format.sample_type(None, Some(device.features())),
+            Some(TextureSampleType::Float { .. })
+        );
+
+        renderable && float
+    }
+
+    /// Fills mip levels `1..` of each layer by downsampling the previous
+    /// level, starting with level 0.
+    /// The texture must be created with [`TextureUsages::RENDER_ATTACHMENT`]
+    /// and a format supported by [`Self::supports_mip_map_generation`].
+    pub fn generate_mip_maps(&self, device: &Device, queue: &Queue) {
+        let mip_level_count = self.texture.mip_level_count();
+        if mip_level_count <= 1 {
+            return;
+        }
+
+        let MipMapPipeline {
+            bind_group_layout,
+            pipeline,
+        } = MipMapPipeline::get(self.texture.format(), device);
+
+        let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+            label: Some("Mip Map Generation"),
+        });
+        for layer in 0..self.texture.depth_or_array_layers() {
+            let level_view = |mip_level| {
+                self.texture.create_view(&TextureViewDescriptor {
+                    label: Some("Mip Map Generation"),
+                    dimension: Some(TextureViewDimension::D2),
+                    base_mip_level: mip_level,
+                    mip_level_count: Some(1),
+                    base_array_layer: layer,
+                    array_layer_count: Some(1),
+                    ..Default::default()
+                })
+            };
+
+            for mip_level in 1..mip_level_count {
+                let source = level_view(mip_level - 1);
+                let target = level_view(mip_level);
+
+                let bind_group = device.create_bind_group(&BindGroupDescriptor {
+                    label: Some("Mip Map Generation"),
+                    layout: &bind_group_layout,
+                    entries: &[BindGroupEntry {
+                        binding: 0,
+                        resource: BindingResource::TextureView(&source),
+                    }],
+                });
+
+                let mut render_pass = encoder.begin_render_pass(&RenderPassDescriptor {
+                    label: Some("Mip Map Generation"),
+                    color_attachments: &[Some(RenderPassColorAttachment {
+                        view: &target,
+                        depth_slice: None,
+                        resolve_target: None,
+                        ops: Operations {
+                            load: LoadOp::Clear(Color::TRANSPARENT),
+                            store: StoreOp::Store,
+                        },
+                    })],
+                    ..Default::default()
+                });
+                render_pass.set_pipeline(&pipeline);
+                render_pass.set_bind_group(0, &bind_group, &[]);
+                render_pass.draw(0..3, 0..1);
+            }
+        }
+
+        queue.submit(Some(encoder.finish()));
+    }
+
     pub fn depth_texture(size: &Vector2<u32>, device: &Device, queue: &Queue) -> Texture {
         Self::from_descriptors_and_data(
             &WTextureDescriptor {
@@ -441,9 +721,9 @@ impl Texture {
             },
             &TextureViewDescriptor::default(),
             &SamplerDescriptor {
-                address_mode_u: AddressMode::Repeat,
-                address_mode_v: AddressMode::Repeat,
-                address_mode_w: AddressMode::Repeat,
+                address_mode_u: WAddressMode::Repeat,
+                address_mode_v: WAddressMode::Repeat,
+                address_mode_w: WAddressMode::Repeat,
                 mag_filter: WFilterMode::Linear,
                 min_filter: WFilterMode::Linear,
                 mipmap_filter: WFilterMode::Nearest,
@@ -0,0 +1,8 @@
+use crate::resources::texture::{address_mode::AddressMode, filter_mode::FilterMode};
+
+/// Defines how a texture is sampled.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub struct Sampling {
+    pub filter_mode: FilterMode,
+    pub address_mode: AddressMode,
+}
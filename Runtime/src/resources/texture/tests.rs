@@ -1,8 +1,12 @@
-use wgpu::{Extent3d, TextureDimension, TextureFormat, TextureUsages, TextureViewDimension};
+use wgpu::{
+    AddressMode as WAddressMode, Extent3d, Features, TextureDimension, TextureFormat,
+    TextureFormatFeatureFlags, TextureUsages, TextureViewDimension,
+};
 
-use crate::resources::FilterMode;
+use crate::renderer::f16_to_f32;
+use crate::resources::{AddressMode, FilterMode, Ktx2Error, Ktx2Texture};
 use crate::{
-    resources::{Texture, TextureDescriptor, TextureSize},
+    resources::{Texture, TextureDescriptor, TextureError, TextureSize, TextureUsageIntent},
     wgpu_test_adapter,
 };
 
@@ -24,6 +28,8 @@ fn test_data_descriptor_realization() {
         texture_dimension: TextureDimension::D2,
         texture_view_dimension: TextureViewDimension::D2,
         filter_mode: FilterMode::default(),
+        address_mode: AddressMode::default(),
+        generate_mips: false,
     };
 
     let _texture =
@@ -71,3 +77,459 @@ fn test_custom_descriptor_realization() {
     let _texture =
         Texture::from_descriptor(&descriptor, &device, &queue).expect("Failure creating texture");
 }
+
+#[test]
+fn test_sampled_texture_usages_are_minimal() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+
+    let descriptor = TextureDescriptor::Data {
+        pixels: vec![255u8; 4],
+        size: TextureSize {
+            width: 1,
+            height: 1,
+            ..Default::default()
+        },
+        format: TextureFormat::Rgba8UnormSrgb,
+        usages: TextureUsageIntent::SAMPLED.usages(),
+        texture_dimension: TextureDimension::D2,
+        texture_view_dimension: TextureViewDimension::D2,
+        filter_mode: FilterMode::default(),
+        address_mode: AddressMode::default(),
+        generate_mips: false,
+    };
+
+    let texture =
+        Texture::from_descriptor(&descriptor, &device, &queue).expect("Failure creating texture");
+    let usage = texture.texture().usage();
+    assert!(usage.contains(TextureUsages::TEXTURE_BINDING));
+    assert!(!usage.contains(TextureUsages::RENDER_ATTACHMENT));
+    assert!(!usage.contains(TextureUsages::STORAGE_BINDING));
+}
+
+#[test]
+fn test_usage_intent() {
+    assert_eq!(
+        TextureUsageIntent::SAMPLED.usages(),
+        TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST
+    );
+    assert_eq!(
+        TextureUsageIntent::RENDER_TARGET.usages(),
+        TextureUsages::TEXTURE_BINDING | TextureUsages::RENDER_ATTACHMENT
+    );
+    assert_eq!(
+        TextureUsages::from(TextureUsageIntent {
+            copy_source: true,
+            ..TextureUsageIntent::SAMPLED
+        }),
+        TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST | TextureUsages::COPY_SRC
+    );
+}
+
+#[test]
+fn test_unsupported_usages_are_rejected() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+
+    // sRGB formats can't be used as storage textures
+    let descriptor = TextureDescriptor::Data {
+        pixels: vec![255u8; 4],
+        size: TextureSize {
+            width: 1,
+            height: 1,
+            ..Default::default()
+        },
+        format: TextureFormat::Rgba8UnormSrgb,
+        usages: TextureUsages::TEXTURE_BINDING | TextureUsages::STORAGE_BINDING,
+        texture_dimension: TextureDimension::D2,
+        texture_view_dimension: TextureViewDimension::D2,
+        filter_mode: FilterMode::default(),
+        address_mode: AddressMode::default(),
+        generate_mips: false,
+    };
+
+    let result = Texture::from_descriptor(&descriptor, &device, &queue);
+    assert!(matches!(
+        result,
+        Err(TextureError::UnsupportedUsages {
+            format: TextureFormat::Rgba8UnormSrgb,
+            ..
+        })
+    ));
+}
+
+const VK_FORMAT_R8G8B8A8_UNORM: u32 = 37;
+const VK_FORMAT_BC7_UNORM_BLOCK: u32 = 145;
+
+/// Builds a minimal KTX2 container.
+/// `levels` starts with the largest mip level.
+fn make_ktx2(
+    vk_format: u32,
+    width: u32,
+    height: u32,
+    supercompression: u32,
+    levels: &[Vec<u8>],
+) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(&crate::resources::KTX2_IDENTIFIER);
+    for value in [vk_format, 1, width, height, 0, 0, 1, levels.len() as u32] {
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    bytes.extend_from_slice(&supercompression.to_le_bytes());
+    // No data format descriptor, key/value data or supercompression data
+    bytes.extend_from_slice(&[0u8; 32]);
+
+    let mut offset = 80 + levels.len() * 24;
+    for level in levels {
+        bytes.extend_from_slice(&(offset as u64).to_le_bytes());
+        bytes.extend_from_slice(&(level.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&(level.len() as u64).to_le_bytes());
+        offset += level.len();
+    }
+    for level in levels {
+        bytes.extend_from_slice(level);
+    }
+
+    bytes
+}
+
+/// 8x8 BC7 texture with two mip levels, 16 bytes per 4x4 block.
+fn make_bc7_ktx2() -> Vec<u8> {
+    make_ktx2(
+        VK_FORMAT_BC7_UNORM_BLOCK,
+        8,
+        8,
+        0,
+        &[vec![0u8; 4 * 16], vec![0u8; 16]],
+    )
+}
+
+#[test]
+fn test_ktx2_parse_bc7() {
+    let bytes = make_bc7_ktx2();
+    let ktx2 = Ktx2Texture::parse(&bytes).expect("Failure parsing KTX2");
+
+    assert_eq!(ktx2.format, TextureFormat::Bc7RgbaUnorm);
+    assert_eq!(ktx2.mip_level_count(), 2);
+    assert_eq!((ktx2.width, ktx2.height), (8, 8));
+    assert_eq!(ktx2.depth_or_array_layers, 1);
+    assert_eq!(bytes[ktx2.levels[0].clone()].len(), 64);
+    assert_eq!(bytes[ktx2.levels[1].clone()].len(), 16);
+}
+
+#[test]
+fn test_ktx2_parse_errors() {
+    assert_eq!(
+        Ktx2Texture::parse(b"not a ktx2 file"),
+        Err(Ktx2Error::InvalidIdentifier)
+    );
+
+    let bytes = make_bc7_ktx2();
+    assert_eq!(
+        Ktx2Texture::parse(&bytes[..bytes.len() - 1]),
+        Err(Ktx2Error::Truncated)
+    );
+
+    // Second level is missing a block
+    let short_level = make_ktx2(
+        VK_FORMAT_BC7_UNORM_BLOCK,
+        8,
+        8,
+        0,
+        &[vec![0u8; 3 * 16], vec![0u8; 16]],
+    );
+    assert_eq!(
+        Ktx2Texture::parse(&short_level),
+        Err(Ktx2Error::InvalidLevelLength {
+            level: 0,
+            expected: 64,
+            length: 48,
+        })
+    );
+
+    // 6x6 isn't made of whole 4x4 blocks
+    let unaligned = make_ktx2(VK_FORMAT_BC7_UNORM_BLOCK, 6, 6, 0, &[vec![0u8; 4 * 16]]);
+    assert_eq!(
+        Ktx2Texture::parse(&unaligned),
+        Err(Ktx2Error::UnalignedDimensions {
+            width: 6,
+            height: 6,
+            block_width: 4,
+            block_height: 4,
+        })
+    );
+
+    // Basis Universal: undefined format, BasisLZ supercompression
+    let basis = make_ktx2(0, 8, 8, 1, &[vec![0u8; 16]]);
+    assert_eq!(
+        Ktx2Texture::parse(&basis),
+        Err(Ktx2Error::UnsupportedSupercompression(1))
+    );
+    let uastc = make_ktx2(0, 8, 8, 0, &[vec![0u8; 16]]);
+    assert_eq!(
+        Ktx2Texture::parse(&uastc),
+        Err(Ktx2Error::UnsupportedVkFormat(0))
+    );
+}
+
+#[test]
+fn test_ktx2_descriptor_realization() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+
+    let descriptor = TextureDescriptor::Ktx2 {
+        bytes: make_ktx2(
+            VK_FORMAT_R8G8B8A8_UNORM,
+            4,
+            4,
+            0,
+            &[
+                vec![255u8; 4 * 4 * 4],
+                vec![255u8; 2 * 2 * 4],
+                vec![255u8; 4],
+            ],
+        ),
+        usages: TextureUsageIntent::SAMPLED.usages(),
+        filter_mode: FilterMode::linear(),
+        address_mode: AddressMode::default(),
+        fallback: None,
+    };
+
+    let texture =
+        Texture::from_descriptor(&descriptor, &device, &queue).expect("Failure creating texture");
+    assert_eq!(texture.texture().format(), TextureFormat::Rgba8Unorm);
+    assert_eq!(texture.texture().mip_level_count(), 3);
+}
+
+#[test]
+fn test_ktx2_bc7_realization() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+
+    let descriptor = TextureDescriptor::Ktx2 {
+        bytes: make_bc7_ktx2(),
+        usages: TextureUsageIntent::SAMPLED.usages(),
+        filter_mode: FilterMode::linear(),
+        address_mode: AddressMode::default(),
+        fallback: None,
+    };
+
+    let result = Texture::from_descriptor(&descriptor, &device, &queue);
+    if device.features().contains(Features::TEXTURE_COMPRESSION_BC) {
+        let texture = result.expect("Failure creating texture");
+        assert_eq!(texture.texture().format(), TextureFormat::Bc7RgbaUnorm);
+        assert_eq!(texture.texture().mip_level_count(), 2);
+    } else {
+        assert!(matches!(
+            result,
+            Err(TextureError::UnsupportedFormat {
+                format: TextureFormat::Bc7RgbaUnorm,
+                missing_features,
+            }) if missing_features == Features::TEXTURE_COMPRESSION_BC
+        ));
+    }
+}
+
+#[test]
+fn test_ktx2_fallback_realization() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+
+    let descriptor = TextureDescriptor::Ktx2 {
+        bytes: make_bc7_ktx2(),
+        usages: TextureUsageIntent::SAMPLED.usages(),
+        filter_mode: FilterMode::linear(),
+        address_mode: AddressMode::default(),
+        fallback: Some(Box::new(TextureDescriptor::uniform_rgba_white(false))),
+    };
+
+    let texture =
+        Texture::from_descriptor(&descriptor, &device, &queue).expect("Failure creating texture");
+    if device.features().contains(Features::TEXTURE_COMPRESSION_BC) {
+        assert_eq!(texture.texture().format(), TextureFormat::Bc7RgbaUnorm);
+    } else {
+        assert_ne!(texture.texture().format(), TextureFormat::Bc7RgbaUnorm);
+    }
+}
+
+#[test]
+fn test_generate_mips() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+
+    let descriptor = TextureDescriptor::Data {
+        pixels: vec![255u8; 4 * 4 * 4],
+        size: TextureSize {
+            width: 4,
+            height: 4,
+            ..Default::default()
+        },
+        format: TextureFormat::Rgba8UnormSrgb,
+        usages: TextureUsageIntent::SAMPLED.usages(),
+        texture_dimension: TextureDimension::D2,
+        texture_view_dimension: TextureViewDimension::D2,
+        filter_mode: FilterMode::linear(),
+        address_mode: AddressMode::default(),
+        generate_mips: true,
+    };
+
+    let texture =
+        Texture::from_descriptor(&descriptor, &device, &queue).expect("Failure creating texture");
+    // 4x4, 2x2, 1x1
+    assert_eq!(texture.texture().mip_level_count(), 3);
+    assert!(texture
+        .texture()
+        .usage()
+        .contains(TextureUsages::RENDER_ATTACHMENT));
+}
+
+#[test]
+fn test_generate_mips_disabled_keeps_single_level() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+
+    let descriptor = TextureDescriptor::Data {
+        pixels: vec![255u8; 4 * 4 * 4],
+        size: TextureSize {
+            width: 4,
+            height: 4,
+            ..Default::default()
+        },
+        format: TextureFormat::Rgba8UnormSrgb,
+        usages: TextureUsageIntent::SAMPLED.usages(),
+        texture_dimension: TextureDimension::D2,
+        texture_view_dimension: TextureViewDimension::D2,
+        filter_mode: FilterMode::linear(),
+        address_mode: AddressMode::default(),
+        generate_mips: false,
+    };
+
+    let texture =
+        Texture::from_descriptor(&descriptor, &device, &queue).expect("Failure creating texture");
+    assert_eq!(texture.texture().mip_level_count(), 1);
+}
+
+#[test]
+fn test_sampler_descriptor_address_modes() {
+    let sampler = Texture::sampler_descriptor(FilterMode::default(), AddressMode::default());
+    assert_eq!(sampler.address_mode_u, WAddressMode::Repeat);
+    assert_eq!(sampler.address_mode_v, WAddressMode::Repeat);
+    assert_eq!(sampler.address_mode_w, WAddressMode::Repeat);
+    assert_eq!(sampler.anisotropy_clamp, 1);
+
+    let address_mode = AddressMode {
+        u: WAddressMode::Repeat,
+        v: WAddressMode::MirrorRepeat,
+        w: WAddressMode::ClampToEdge,
+    };
+    let sampler = Texture::sampler_descriptor(FilterMode::anisotropic(16), address_mode);
+    assert_eq!(sampler.address_mode_u, WAddressMode::Repeat);
+    assert_eq!(sampler.address_mode_v, WAddressMode::MirrorRepeat);
+    assert_eq!(sampler.address_mode_w, WAddressMode::ClampToEdge);
+    assert_eq!(sampler.anisotropy_clamp, 16);
+
+    // Anisotropy requires linear filtering everywhere
+    let sampler = Texture::sampler_descriptor(
+        FilterMode {
+            anisotropy_clamp: 16,
+            ..FilterMode::nearest()
+        },
+        address_mode,
+    );
+    assert_eq!(sampler.anisotropy_clamp, 1);
+}
+
+#[test]
+fn test_sampler_configuration_realization() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+
+    let descriptor = |address_mode| TextureDescriptor::Data {
+        pixels: vec![255u8; 4 * 4 * 4],
+        size: TextureSize {
+            width: 4,
+            height: 4,
+            ..Default::default()
+        },
+        usages: TextureUsageIntent::SAMPLED.usages(),
+        format: TextureFormat::Rgba8Unorm,
+        texture_dimension: TextureDimension::D2,
+        texture_view_dimension: TextureViewDimension::D2,
+        filter_mode: FilterMode::anisotropic(16),
+        address_mode,
+        generate_mips: false,
+    };
+
+    // Differently wrapped textures must not be shared
+    let repeat = descriptor(AddressMode::repeat());
+    let clamped = descriptor(AddressMode::clamp_to_edge());
+    assert_ne!(repeat, clamped);
+
+    for descriptor in [repeat, clamped] {
+        Texture::from_descriptor(&descriptor, &device, &queue).expect("Failure creating texture");
+    }
+}
+
+fn descriptor_format_and_size(descriptor: &TextureDescriptor) -> (TextureFormat, TextureSize) {
+    match descriptor {
+        TextureDescriptor::Data { format, size, .. } => (*format, *size),
+        _ => panic!("Expected a data descriptor, got: {descriptor:?}"),
+    }
+}
+
+#[test]
+fn test_from_path_png() {
+    let path = std::env::temp_dir().join("orbital_from_path.png");
+    image::RgbaImage::from_pixel(3, 2, image::Rgba([255, 128, 0, 255]))
+        .save(&path)
+        .expect("Failed writing PNG");
+
+    let descriptor = TextureDescriptor::from_path(&path).expect("Failure loading PNG");
+    let (format, size) = descriptor_format_and_size(&descriptor);
+    assert_eq!(format, TextureFormat::Rgba8UnormSrgb);
+    assert_eq!((size.width, size.height), (3, 2));
+
+    let linear = TextureDescriptor::from_path_linear(&path).expect("Failure loading PNG");
+    assert_eq!(
+        descriptor_format_and_size(&linear).0,
+        TextureFormat::Rgba8Unorm
+    );
+
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+    Texture::from_descriptor(&descriptor, &device, &queue).expect("Failure creating texture");
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_from_path_hdr() {
+    let path = std::env::temp_dir().join("orbital_from_path.hdr");
+    image::DynamicImage::ImageRgb32F(image::Rgb32FImage::from_pixel(
+        4,
+        2,
+        image::Rgb([2.5, 1.0, 0.25]),
+    ))
+    .save(&path)
+    .expect("Failed writing HDR");
+
+    let descriptor = TextureDescriptor::from_path(&path).expect("Failure loading HDR");
+    let (format, size) = descriptor_format_and_size(&descriptor);
+    assert_eq!(format, TextureFormat::Rgba16Float);
+    assert_eq!((size.width, size.height), (4, 2));
+    // Filtering samplers must work without FLOAT32_FILTERABLE
+    assert!(format
+        .guaranteed_format_features(Features::empty())
+        .flags
+        .contains(TextureFormatFeatureFlags::FILTERABLE));
+
+    let TextureDescriptor::Data { pixels, .. } = descriptor else {
+        unreachable!()
+    };
+    assert_eq!(pixels.len(), 4 * 2 * 4 * size_of::<u16>());
+    assert_eq!(
+        f16_to_f32(u16::from_le_bytes(pixels[0..2].try_into().unwrap())),
+        2.5
+    );
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_from_path_missing_file() {
+    assert!(matches!(
+        TextureDescriptor::from_path("orbital_missing_texture.png"),
+        Err(TextureError::IOError(_))
+    ));
+}
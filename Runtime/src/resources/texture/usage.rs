@@ -0,0 +1,116 @@
+use wgpu::{Device, Features, TextureFormat, TextureUsages};
+
+use super::TextureError;
+
+/// Describes how a texture is going to be used.
+/// Turns into the minimal set of [TextureUsages] required for that,
+/// as requesting more than needed (e.g. [TextureUsages::all]) can reduce
+/// performance, especially on tiled GPUs.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct TextureUsageIntent {
+    /// Sampled/bound inside a shader.
+    pub sampled: bool,
+    /// Rendered into as a color or depth attachment.
+    pub render_target: bool,
+    /// Bound as a storage texture.
+    pub storage: bool,
+    /// Copied from, e.g. for read backs.
+    pub copy_source: bool,
+    /// Copied into, e.g. when uploading pixel data.
+    pub copy_destination: bool,
+}
+
+impl TextureUsageIntent {
+    /// A texture that gets uploaded once and is sampled afterwards.
+    /// This is what most material textures need.
+    pub const SAMPLED: Self = Self {
+        sampled: true,
+        render_target: false,
+        storage: false,
+        copy_source: false,
+        copy_destination: true,
+    };
+
+    /// A texture that gets rendered into and is sampled afterwards.
+    pub const RENDER_TARGET: Self = Self {
+        sampled: true,
+        render_target: true,
+        storage: false,
+        copy_source: false,
+        copy_destination: false,
+    };
+
+    /// Returns the minimal [TextureUsages] for this intent.
+    pub const fn usages(&self) -> TextureUsages {
+        let mut usages = TextureUsages::empty();
+
+        if self.sampled {
+            usages = usages.union(TextureUsages::TEXTURE_BINDING);
+        }
+        if self.render_target {
+            usages = usages.union(TextureUsages::RENDER_ATTACHMENT);
+        }
+        if self.storage {
+            usages = usages.union(TextureUsages::STORAGE_BINDING);
+        }
+        if self.copy_source {
+            usages = usages.union(TextureUsages::COPY_SRC);
+        }
+        if self.copy_destination {
+            usages = usages.union(TextureUsages::COPY_DST);
+        }
+
+        usages
+    }
+}
+
+impl Default for TextureUsageIntent {
+    fn default() -> Self {
+        Self::SAMPLED
+    }
+}
+
+impl From<TextureUsageIntent> for TextureUsages {
+    fn from(value: TextureUsageIntent) -> Self {
+        value.usages()
+    }
+}
+
+/// Checks whether the given `usages` are supported for `format` on `device`.
+///
+/// If the device has [Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES]
+/// enabled, formats may support more than what is guaranteed, which can
+/// only be queried from the adapter. In that case only the features
+/// required by the format itself are checked.
+pub fn validate_texture_usages(
+    format: TextureFormat,
+    usages: TextureUsages,
+    device: &Device,
+) -> Result<(), TextureError> {
+    let device_features = device.features();
+
+    let missing_features = format.required_features() - device_features;
+    if !missing_features.is_empty() {
+        return Err(TextureError::UnsupportedFormat {
+            format,
+            missing_features,
+        });
+    }
+
+    if device_features.contains(Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES) {
+        return Ok(());
+    }
+
+    let supported = format
+        .guaranteed_format_features(device_features)
+        .allowed_usages;
+    if !supported.contains(usages) {
+        return Err(TextureError::UnsupportedUsages {
+            format,
+            requested: usages,
+            supported,
+        });
+    }
+
+    Ok(())
+}
@@ -1,4 +1,7 @@
-use cgmath::{Quaternion, Vector3, Zero};
+use cgmath::{InnerSpace, Matrix3, Matrix4, Quaternion, SquareMatrix, Vector3, Zero};
+
+#[cfg(test)]
+mod tests;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Transform {
@@ -40,6 +43,91 @@ impl Transform {
         }
     }
 
+    /// A [Transform] at the given position, without any rotation and a
+    /// scale of one.
+    /// Unlike [Self::only_position], which zeroes the scale, this is meant
+    /// as the start of a builder chain.
+    pub fn from_position(position: Vector3<f32>) -> Self {
+        Self {
+            position,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_position(mut self, position: Vector3<f32>) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub fn with_rotation(mut self, rotation: Quaternion<f32>) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn with_scale(mut self, scale: Vector3<f32>) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Composes this transform into a matrix, applying scale first, then
+    /// rotation and lastly the position.
+    /// This is the same matrix instances of this transform get rendered with.
+    pub fn to_matrix(&self) -> Matrix4<f32> {
+        Matrix4::from_translation(self.position)
+            * Matrix4::from(self.rotation)
+            * Matrix4::from_nonuniform_scale(self.scale.x, self.scale.y, self.scale.z)
+    }
+
+    /// Decomposes a matrix into position, rotation and scale.
+    /// The inverse of [Self::to_matrix], as long as the matrix doesn't
+    /// contain any shearing or projection, which can't be represented.
+    ///
+    /// Mirroring is expressed by a negative X scale.
+    /// The rotation is always a unit quaternion.
+    pub fn from_matrix(matrix: Matrix4<f32>) -> Self {
+        let position = matrix.w.truncate();
+
+        let mut basis = Matrix3::from_cols(
+            matrix.x.truncate(),
+            matrix.y.truncate(),
+            matrix.z.truncate(),
+        );
+        let mut scale = Vector3::new(
+            basis.x.magnitude(),
+            basis.y.magnitude(),
+            basis.z.magnitude(),
+        );
+        if basis.determinant() < 0.0 {
+            scale.x = -scale.x;
+        }
+
+        // Remove the scale, leaving the pure rotation
+        for (axis, scale) in [
+            (&mut basis.x, scale.x),
+            (&mut basis.y, scale.y),
+            (&mut basis.z, scale.z),
+        ] {
+            if scale != 0.0 {
+                *axis /= scale;
+            }
+        }
+        let rotation = Quaternion::from(basis).normalize();
+
+        Self {
+            position,
+            rotation,
+            scale,
+        }
+    }
+
+    /// Applies `child` relative to this transform, e.g. for a node inside a
+    /// hierarchy.
+    /// Equals multiplying both matrices, which is only exact for
+    /// combinations without shearing, e.g. uniform scales.
+    pub fn compose(&self, child: &Transform) -> Transform {
+        Self::from_matrix(self.to_matrix() * child.to_matrix())
+    }
+
     pub fn zero() -> Self {
         Self {
             position: Vector3::zero(),
@@ -0,0 +1,102 @@
+use cgmath::{AbsDiffEq, Deg, Euler, InnerSpace, Matrix4, Quaternion, Vector3};
+
+use super::Transform;
+
+const EPSILON: f32 = 1e-5;
+
+fn assert_matrix_eq(actual: Matrix4<f32>, expected: Matrix4<f32>) {
+    assert!(
+        actual.abs_diff_eq(&expected, EPSILON),
+        "{actual:?} != {expected:?}"
+    );
+}
+
+fn assert_transform_eq(actual: Transform, expected: Transform) {
+    assert!(actual.position.abs_diff_eq(&expected.position, EPSILON));
+    assert!(actual.scale.abs_diff_eq(&expected.scale, EPSILON));
+    // `q` and `-q` describe the same rotation
+    assert!(
+        (actual.rotation.dot(expected.rotation).abs() - 1.0).abs() < EPSILON,
+        "{:?} != {:?}",
+        actual.rotation,
+        expected.rotation
+    );
+}
+
+fn rotation(x: f32, y: f32, z: f32) -> Quaternion<f32> {
+    Quaternion::from(Euler::new(Deg(x), Deg(y), Deg(z)))
+}
+
+#[test]
+fn builder() {
+    let transform = Transform::from_position(Vector3::new(1.0, 2.0, 3.0))
+        .with_rotation(rotation(0.0, 90.0, 0.0))
+        .with_scale(Vector3::new(2.0, 2.0, 2.0));
+
+    assert_eq!(transform.position, Vector3::new(1.0, 2.0, 3.0));
+    assert_eq!(transform.rotation, rotation(0.0, 90.0, 0.0));
+    assert_eq!(transform.scale, Vector3::new(2.0, 2.0, 2.0));
+
+    assert_eq!(
+        Transform::from_position(Vector3::new(1.0, 2.0, 3.0)).scale,
+        Vector3::new(1.0, 1.0, 1.0)
+    );
+}
+
+#[test]
+fn matrix_round_trip() {
+    let transforms = [
+        Transform::default(),
+        Transform::from_position(Vector3::new(-4.0, 0.5, 12.0)),
+        Transform::default().with_rotation(rotation(30.0, -45.0, 120.0)),
+        Transform::default().with_scale(Vector3::new(0.5, 3.0, 1.5)),
+        Transform::from_position(Vector3::new(100.0, -250.0, 3.5))
+            .with_rotation(rotation(-170.0, 10.0, 80.0))
+            .with_scale(Vector3::new(0.01, 0.02, 0.03)),
+        // Mirrored
+        Transform::from_position(Vector3::new(1.0, 2.0, 3.0))
+            .with_rotation(rotation(0.0, 0.0, 45.0))
+            .with_scale(Vector3::new(-2.0, 1.0, 1.0)),
+    ];
+
+    for transform in transforms {
+        let decomposed = Transform::from_matrix(transform.to_matrix());
+        assert_matrix_eq(decomposed.to_matrix(), transform.to_matrix());
+
+        if transform.rotation != Quaternion::new(0.0, 0.0, 0.0, 0.0) {
+            assert_transform_eq(decomposed, transform);
+        }
+    }
+}
+
+#[test]
+fn identity_rotation_decomposes_to_unit_quaternion() {
+    // A zero quaternion renders as identity, but decomposes into the unit one
+    let decomposed = Transform::from_matrix(Transform::default().to_matrix());
+
+    assert_transform_eq(
+        decomposed,
+        Transform::default().with_rotation(Quaternion::new(1.0, 0.0, 0.0, 0.0)),
+    );
+}
+
+#[test]
+fn compose_matches_matrix_multiplication() {
+    let parent = Transform::from_position(Vector3::new(0.0, 5.0, 0.0))
+        .with_rotation(rotation(0.0, 90.0, 0.0))
+        .with_scale(Vector3::new(2.0, 2.0, 2.0));
+    let child = Transform::from_position(Vector3::new(1.0, 0.0, 0.0))
+        .with_rotation(rotation(45.0, 0.0, 0.0))
+        .with_scale(Vector3::new(0.5, 1.0, 3.0));
+
+    let composed = parent.compose(&child);
+    assert_matrix_eq(composed.to_matrix(), parent.to_matrix() * child.to_matrix());
+
+    // The child's position is rotated and scaled by its parent
+    assert!(composed
+        .position
+        .abs_diff_eq(&Vector3::new(0.0, 5.0, -2.0), EPSILON));
+    assert!(composed
+        .scale
+        .abs_diff_eq(&Vector3::new(1.0, 2.0, 6.0), EPSILON));
+}
@@ -1,8 +1,16 @@
 use std::{hash::Hash, mem::size_of};
 
-use cgmath::{num_traits::Float, Vector2, Vector3};
+use cgmath::{num_traits::Float, Vector2, Vector3, Vector4};
 use wgpu::{VertexAttribute, VertexBufferLayout, VertexFormat, VertexStepMode};
 
+/// Joints influencing a skinned [Vertex] and their weights.
+/// Joint indices refer to the joints of a [SkinDescriptor](crate::resources::SkinDescriptor).
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct VertexSkin {
+    pub joints: [u16; 4],
+    pub weights: [f32; 4],
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Vertex {
     pub position: Vector3<f32>,
@@ -10,12 +18,27 @@ pub struct Vertex {
     pub tangent: Vector3<f32>,
     pub bitangent: Vector3<f32>,
     pub uv: Vector2<f32>,
+    /// Linear RGBA vertex color, multiplied into the base color.
+    /// Treated as [white](Vertex::DEFAULT_COLOR) if not set.
+    pub color: Option<Vector4<f32>>,
+    /// Only set for skinned meshes.
+    /// Unskinned vertices keep using the smaller
+    /// [complex layout](Vertex::complex_vertex_buffer_layout_descriptor).
+    pub skin: Option<VertexSkin>,
 }
 
 impl Vertex {
+    /// Color used for vertices without a [color](Vertex::color).
+    /// White, leaving the base color unchanged.
+    pub const DEFAULT_COLOR: Vector4<f32> = Vector4::new(1.0, 1.0, 1.0, 1.0);
+
+    /// Position, normal, tangent, bitangent and UV (locations 0 to 4),
+    /// followed by the color (location 11).
+    /// Locations 5 to 10 are taken by the instance data and
+    /// [skinning](Vertex::skinned_vertex_buffer_layout_descriptor).
     pub fn complex_vertex_buffer_layout_descriptor() -> VertexBufferLayout<'static> {
         VertexBufferLayout {
-            array_stride: size_of::<[f32; 3 * 4 + 2]>() as u64,
+            array_stride: size_of::<[f32; 3 * 4 + 2 + 4]>() as u64,
             step_mode: VertexStepMode::Vertex,
             attributes: &[
                 // Position
@@ -48,6 +71,77 @@ impl Vertex {
                     shader_location: 4,
                     format: VertexFormat::Float32x2,
                 },
+                // Color
+                VertexAttribute {
+                    offset: size_of::<[f32; 3 * 4 + 2]>() as u64,
+                    shader_location: 11,
+                    format: VertexFormat::Float32x4,
+                },
+            ],
+        }
+    }
+
+    /// Same as [Vertex::complex_vertex_buffer_layout_descriptor], followed
+    /// by the joint indices (location 9) and weights (location 10) of
+    /// [VertexSkin].
+    /// Locations 5 to 8 are taken by the instance data.
+    pub fn skinned_vertex_buffer_layout_descriptor() -> VertexBufferLayout<'static> {
+        const COMPLEX_SIZE: u64 = size_of::<[f32; 3 * 4 + 2 + 4]>() as u64;
+
+        VertexBufferLayout {
+            array_stride: COMPLEX_SIZE
+                + size_of::<[u16; 4]>() as u64
+                + size_of::<[f32; 4]>() as u64,
+            step_mode: VertexStepMode::Vertex,
+            attributes: &[
+                // Position
+                VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: VertexFormat::Float32x3,
+                },
+                // Normal
+                VertexAttribute {
+                    offset: size_of::<[f32; 3]>() as u64,
+                    shader_location: 1,
+                    format: VertexFormat::Float32x3,
+                },
+                // Tangent
+                VertexAttribute {
+                    offset: size_of::<[f32; 3 * 2]>() as u64,
+                    shader_location: 2,
+                    format: VertexFormat::Float32x3,
+                },
+                // Bitangent
+                VertexAttribute {
+                    offset: size_of::<[f32; 3 * 3]>() as u64,
+                    shader_location: 3,
+                    format: VertexFormat::Float32x3,
+                },
+                // UV
+                VertexAttribute {
+                    offset: size_of::<[f32; 3 * 4]>() as u64,
+                    shader_location: 4,
+                    format: VertexFormat::Float32x2,
+                },
+                // Color
+                VertexAttribute {
+                    offset: size_of::<[f32; 3 * 4 + 2]>() as u64,
+                    shader_location: 11,
+                    format: VertexFormat::Float32x4,
+                },
+                // Joints
+                VertexAttribute {
+                    offset: COMPLEX_SIZE,
+                    shader_location: 9,
+                    format: VertexFormat::Uint16x4,
+                },
+                // Weights
+                VertexAttribute {
+                    offset: COMPLEX_SIZE + size_of::<[u16; 4]>() as u64,
+                    shader_location: 10,
+                    format: VertexFormat::Float32x4,
+                },
             ],
         }
     }
@@ -79,6 +173,8 @@ impl Vertex {
             tangent,
             bitangent: Self::calculate_binormal(tangent, normal),
             uv,
+            color: None,
+            skin: None,
         }
     }
 
@@ -95,14 +191,28 @@ impl Vertex {
             tangent,
             bitangent,
             uv,
+            color: None,
+            skin: None,
         }
     }
 
+    pub fn with_color(mut self, color: Vector4<f32>) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn with_skin(mut self, skin: VertexSkin) -> Self {
+        self.skin = Some(skin);
+        self
+    }
+
     pub fn calculate_binormal(tangent: Vector3<f32>, normal: Vector3<f32>) -> Vector3<f32> {
         tangent.cross(normal)
     }
 
     pub fn to_bytes(&self) -> Vec<u8> {
+        let color = self.color.unwrap_or(Self::DEFAULT_COLOR);
+
         [
             self.position.x.to_le_bytes(),
             self.position.y.to_le_bytes(),
@@ -118,9 +228,25 @@ impl Vertex {
             self.bitangent.z.to_le_bytes(),
             self.uv.x.to_le_bytes(),
             self.uv.y.to_le_bytes(),
+            color.x.to_le_bytes(),
+            color.y.to_le_bytes(),
+            color.z.to_le_bytes(),
+            color.w.to_le_bytes(),
         ]
         .concat()
     }
+
+    /// Like [Vertex::to_bytes], followed by the [VertexSkin] data as
+    /// described by [Vertex::skinned_vertex_buffer_layout_descriptor].
+    /// Vertices without skin are bound to the first joint with no weight.
+    pub fn to_skinned_bytes(&self) -> Vec<u8> {
+        let skin = self.skin.unwrap_or_default();
+
+        let mut bytes = self.to_bytes();
+        bytes.extend(skin.joints.iter().flat_map(|x| x.to_le_bytes()));
+        bytes.extend(skin.weights.iter().flat_map(|x| x.to_le_bytes()));
+        bytes
+    }
 }
 
 /// Note: This ignores that f32 can't be Eq'd by default due to NaN.
@@ -147,6 +273,20 @@ impl Hash for Vertex {
 
         self.uv.x.integer_decode().hash(state);
         self.uv.y.integer_decode().hash(state);
+
+        if let Some(color) = &self.color {
+            color.x.integer_decode().hash(state);
+            color.y.integer_decode().hash(state);
+            color.z.integer_decode().hash(state);
+            color.w.integer_decode().hash(state);
+        }
+
+        if let Some(skin) = &self.skin {
+            skin.joints.hash(state);
+            for weight in skin.weights {
+                weight.integer_decode().hash(state);
+            }
+        }
     }
 }
 
@@ -110,10 +110,7 @@ impl CacheFile {
         device: &Device,
         queue: &Queue,
     ) -> (OrbitalTexture, OrbitalTexture) {
-        let cube_face_size = match world_environment_descriptor {
-            WorldEnvironmentDescriptor::FromFile { cube_face_size, .. } => *cube_face_size,
-            WorldEnvironmentDescriptor::FromData { cube_face_size, .. } => *cube_face_size,
-        };
+        let cube_face_size = world_environment_descriptor.cube_face_size();
 
         // Use the cached mip level count for the specular texture to ensure
         // consistency between generation and loading.
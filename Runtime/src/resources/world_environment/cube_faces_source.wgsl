@@ -0,0 +1,54 @@
+// Cube faces source, one layer per face: +X, -X, +Y, -Y, +Z, -Z
+@group(0) @binding(0) var src: texture_2d_array<f32>;
+
+struct SourceFace {
+    forward: vec3<f32>,
+    up: vec3<f32>,
+    right: vec3<f32>,
+}
+
+// Same orientation as the destination faces of the specular IBL
+fn source_face(index: u32) -> SourceFace {
+    switch index {
+        case 0u: { // +X
+            return SourceFace(vec3(1.0, 0.0, 0.0), vec3(0.0, 1.0, 0.0), vec3(0.0, 0.0, -1.0));
+        }
+        case 1u: { // -X
+            return SourceFace(vec3(-1.0, 0.0, 0.0), vec3(0.0, 1.0, 0.0), vec3(0.0, 0.0, 1.0));
+        }
+        case 2u: { // +Y
+            return SourceFace(vec3(0.0, -1.0, 0.0), vec3(0.0, 0.0, 1.0), vec3(1.0, 0.0, 0.0));
+        }
+        case 3u: { // -Y
+            return SourceFace(vec3(0.0, 1.0, 0.0), vec3(0.0, 0.0, -1.0), vec3(1.0, 0.0, 0.0));
+        }
+        case 4u: { // +Z
+            return SourceFace(vec3(0.0, 0.0, 1.0), vec3(0.0, 1.0, 0.0), vec3(1.0, 0.0, 0.0));
+        }
+        default { // -Z
+            return SourceFace(vec3(0.0, 0.0, -1.0), vec3(0.0, 1.0, 0.0), vec3(-1.0, 0.0, 0.0));
+        }
+    }
+}
+
+// Loads the source texel the given direction points at
+fn load_source(direction: vec3<f32>) -> vec4<f32> {
+    // The face pointing the most into the direction is hit
+    var face_index = 0u;
+    var face = source_face(0u);
+    for (var i = 1u; i < 6u; i++) {
+        let candidate = source_face(i);
+        if dot(direction, candidate.forward) > dot(direction, face.forward) {
+            face_index = i;
+            face = candidate;
+        }
+    }
+
+    // Project onto the face plane, giving coordinates in [-1; 1]
+    let projected = direction / dot(direction, face.forward);
+    let cube_uv = vec2(dot(projected, face.right), dot(projected, face.up));
+
+    let src_size = i32(textureDimensions(src).x);
+    let pixel = vec2<i32>((cube_uv + 1.0) * 0.5 * f32(src_size));
+    return textureLoad(src, clamp(pixel, vec2(0), vec2(src_size - 1)), face_index, 0);
+}
@@ -6,7 +6,7 @@ use super::SamplingType;
 
 #[derive(Debug, Clone, Eq)]
 pub enum WorldEnvironmentDescriptor {
-    /// Loading an HDRI from file.  
+    /// Loading an HDRI from file, e.g. `.hdr` or `.exr`.  
     /// First of all, will convert the HDRI _equirectangular_ image
     /// into a _cube texture_.
     /// Secondly, will transform the _cube texture_ into a diffuse
@@ -73,11 +73,36 @@ pub enum WorldEnvironmentDescriptor {
         /// Note: The maximum mip level count is determined by the texture size (log2(size) + 1).
         specular_mip_level_count: Option<u32>,
     },
+    /// Loading the six faces of a cube texture from files.
+    /// The faces are sampled directly, instead of an _equirectangular_
+    /// image, and otherwise processed the same way as
+    /// [WorldEnvironmentDescriptor::FromFile].
+    ///
+    /// All faces must be square and of the same size, which doesn't need to
+    /// match `cube_face_size`.
+    FromCubeFaces {
+        cube_face_size: u32,
+        /// Paths to the faces, ordered like the cube texture layers:
+        /// +X, -X, +Y, -Y, +Z, -Z.
+        faces: [String; 6],
+        sampling_type: SamplingType,
+        /// Check [WorldEnvironmentDescriptor::FromFile] for details.
+        specular_mip_level_count: Option<u32>,
+    },
 }
 
 impl WorldEnvironmentDescriptor {
     pub const DEFAULT_SIZE: u32 = 2048;
     pub const DEFAULT_SAMPLING_TYPE: SamplingType = SamplingType::ImportanceSampling;
+
+    /// Size of each face of the resulting cube textures.
+    pub fn cube_face_size(&self) -> u32 {
+        match self {
+            Self::FromFile { cube_face_size, .. }
+            | Self::FromData { cube_face_size, .. }
+            | Self::FromCubeFaces { cube_face_size, .. } => *cube_face_size,
+        }
+    }
 }
 
 impl PartialEq for WorldEnvironmentDescriptor {
@@ -130,6 +155,25 @@ impl PartialEq for WorldEnvironmentDescriptor {
                 // Then, compare byte-by-byte with fail-fast.
                 l_data.iter().zip(r_data.iter()).any(|(l, r)| l.eq(r))
             }
+            (
+                Self::FromCubeFaces {
+                    cube_face_size: l_cube_face_size,
+                    faces: l_faces,
+                    sampling_type: l_sampling_type,
+                    specular_mip_level_count: l_specular_mip_level_count,
+                },
+                Self::FromCubeFaces {
+                    cube_face_size: r_cube_face_size,
+                    faces: r_faces,
+                    sampling_type: r_sampling_type,
+                    specular_mip_level_count: r_specular_mip_level_count,
+                },
+            ) => {
+                l_cube_face_size == r_cube_face_size
+                    && l_faces == r_faces
+                    && l_sampling_type == r_sampling_type
+                    && l_specular_mip_level_count == r_specular_mip_level_count
+            }
             _ => false,
         }
     }
@@ -162,6 +206,17 @@ impl Hash for WorldEnvironmentDescriptor {
                 sampling_type.hash(state);
                 specular_mip_level_count.hash(state);
             }
+            WorldEnvironmentDescriptor::FromCubeFaces {
+                cube_face_size,
+                faces,
+                sampling_type,
+                specular_mip_level_count,
+            } => {
+                cube_face_size.hash(state);
+                faces.hash(state);
+                sampling_type.hash(state);
+                specular_mip_level_count.hash(state);
+            }
         }
     }
 }
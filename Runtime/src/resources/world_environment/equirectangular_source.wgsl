@@ -0,0 +1,12 @@
+const INV_ATAN = vec2<f32>(0.1591, 0.3183); // 1/(2*PI), 1/PI
+
+// Equirectangular source
+@group(0) @binding(0) var src: texture_2d<f32>;
+
+// Loads the source texel the given direction points at
+fn load_source(direction: vec3<f32>) -> vec4<f32> {
+    let eq_uv = vec2(atan2(direction.z, direction.x), asin(direction.y)) * INV_ATAN + 0.5;
+    let src_dims = vec2<i32>(textureDimensions(src));
+    let eq_pixel = vec2<i32>(eq_uv * vec2<f32>(src_dims));
+    return textureLoad(src, clamp(eq_pixel, vec2(0), src_dims - 1), 0);
+}
@@ -4,6 +4,12 @@ use std::fmt::Display;
 pub enum WorldEnvironmentError {
     IO(std::io::Error),
     Image(image::ImageError),
+    /// A cube face isn't square or its size differs from the other faces.
+    CubeFaceSize {
+        path: String,
+        width: u32,
+        height: u32,
+    },
 }
 
 impl std::error::Error for WorldEnvironmentError {}
@@ -0,0 +1,66 @@
+use wgpu::{
+    BindGroupLayoutEntry, BindingType, ShaderStages, StorageTextureAccess, TextureFormat,
+    TextureSampleType, TextureViewDimension,
+};
+
+/// Layout of the environment the IBL textures get generated from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum IblSource {
+    /// A single equirectangular image.
+    Equirectangular,
+    /// Six cube faces as layers of a 2D array texture, ordered
+    /// +X, -X, +Y, -Y, +Z, -Z.
+    CubeFaces,
+}
+
+impl IblSource {
+    /// WGSL declaring the source binding and `load_source`, which the IBL
+    /// shaders sample the environment with.
+    pub fn shader_source(self) -> &'static str {
+        match self {
+            IblSource::Equirectangular => include_str!("equirectangular_source.wgsl"),
+            IblSource::CubeFaces => include_str!("cube_faces_source.wgsl"),
+        }
+    }
+
+    pub fn bind_group_layout_entries(self) -> &'static [BindGroupLayoutEntry] {
+        const EQUIRECTANGULAR: [BindGroupLayoutEntry; 2] =
+            bind_group_layout_entries(TextureViewDimension::D2);
+        const CUBE_FACES: [BindGroupLayoutEntry; 2] =
+            bind_group_layout_entries(TextureViewDimension::D2Array);
+
+        match self {
+            IblSource::Equirectangular => &EQUIRECTANGULAR,
+            IblSource::CubeFaces => &CUBE_FACES,
+        }
+    }
+}
+
+const fn bind_group_layout_entries(
+    src_view_dimension: TextureViewDimension,
+) -> [BindGroupLayoutEntry; 2] {
+    [
+        // Input: Environment as source
+        BindGroupLayoutEntry {
+            binding: 0,
+            visibility: ShaderStages::COMPUTE,
+            ty: BindingType::Texture {
+                sample_type: TextureSampleType::Float { filterable: false },
+                view_dimension: src_view_dimension,
+                multisampled: false,
+            },
+            count: None,
+        },
+        // Output
+        BindGroupLayoutEntry {
+            binding: 1,
+            visibility: ShaderStages::COMPUTE,
+            ty: BindingType::StorageTexture {
+                access: StorageTextureAccess::WriteOnly,
+                format: TextureFormat::Rgba16Float,
+                view_dimension: TextureViewDimension::D2Array,
+            },
+            count: None,
+        },
+    ]
+}
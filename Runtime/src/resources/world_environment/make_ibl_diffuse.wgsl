@@ -1,9 +1,7 @@
 const PI: f32 = 3.14159265359;
-const INV_ATAN: vec2<f32> = vec2(0.1591, 0.3183); // 1/(2*PI), 1/PI
 const TWO_PI: f32 = 6.28318530718;
 
-// Bindings for textures
-@group(0) @binding(0) var src: texture_2d<f32>; // Equirectangular source
+// Source bindings and `load_source` are prepended, see `IblSource`
 @group(0) @binding(1) var dst: texture_storage_2d_array<rgba16float, write>; // Cubemap destination
 
 // Structure to define a cubemap face
@@ -105,10 +103,7 @@ fn calculate_pbr_ibl_diffuse(N: vec3<f32>, gid: vec3<u32>) {
         );
         
         // Sample environment map
-        let eq_uv = vec2(atan2(L.z, L.x), asin(L.y)) * INV_ATAN + 0.5;
-        let src_dims = vec2<f32>(textureDimensions(src));
-        let eq_pixel = vec2<i32>(eq_uv * src_dims);
-        let sample_color = textureLoad(src, clamp(eq_pixel, vec2(0), vec2<i32>(src_dims) - 1), 0);
+        let sample_color = load_source(L);
 
         irradiance += sample_color.rgb;
     }
@@ -1,8 +1,4 @@
-const INV_ATAN = vec2<f32>(0.1591, 0.3183);
-
-@group(0) @binding(0)
-var src: texture_2d<f32>;
-
+// Source bindings and `load_source` are prepended, see `IblSource`
 @group(0) @binding(1)
 var dst: texture_storage_2d_array<rgba16float, write>;
 
@@ -79,7 +75,6 @@ fn main(
     @builtin(global_invocation_id)
     gid: vec3<u32>,
 ) {
-    let src_dimensions = vec2<f32>(textureDimensions(src));
     let dst_dimensions = vec2<f32>(textureDimensions(dst));
 
     // If texture size is not divisible by 32, we
@@ -96,13 +91,6 @@ fn main(
     let face = gid_z_to_face(gid.z);
     let N = normalize(face.forward + face.right * cube_uv.x + face.up * cube_uv.y);
 
-    // Convert N to equirectangular UV
-    let eq_uv = vec2(
-        atan2(N.z, N.x), 
-        asin(N.y)
-    ) * INV_ATAN + 0.5;
-    let eq_pixel = vec2<u32>(eq_uv * src_dimensions);
-
-    let sample = textureLoad(src, eq_pixel, 0);
+    let sample = load_source(N);
     textureStore(dst, gid.xy, gid.z, sample);
 }
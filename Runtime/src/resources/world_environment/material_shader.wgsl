@@ -1,3 +1,6 @@
+// Provides `output_transform`, matching the surface format
+#import <output_transform>
+
 // Light types
 const LIGHT_TYPE_POINT: f32 = 0.0;
 const LIGHT_TYPE_DIRECTIONAL: f32 = 1.0;
@@ -19,6 +22,11 @@ struct Light {
     params: vec4<f32>,       // x: inner cone angle, y: outer cone angle, zw: padding
 }
 
+struct LightStore {
+    count: u32,
+    lights: array<Light>,
+}
+
 struct VertexOutput {
     @builtin(position) frag_position: vec4<f32>,
     // idk why this split is needed but these are actually two 
@@ -28,7 +36,7 @@ struct VertexOutput {
 
 @group(0) @binding(0) var<uniform> camera: CameraUniform;
 
-@group(0) @binding(1) var<storage> light_store: array<Light>;
+@group(0) @binding(1) var<storage> light_store: LightStore;
 
 @group(0) @binding(2) var diffuse_env_map: texture_cube<f32>;
 @group(0) @binding(3) var diffuse_env_sampler: sampler;
@@ -70,7 +78,8 @@ fn entrypoint_fragment(in: VertexOutput) -> @location(0) vec4<f32> {
     // ACES Tone Map (HDR mapping)
     let aces_tone_mapped = aces_tone_map(gamma_adjustment);
 
-    return vec4<f32>(gamma_adjustment, 1.0);
+    // Encode for the surface (e.g. sRGB encode on linear surfaces)
+    return vec4<f32>(output_transform(gamma_adjustment), 1.0);
 }
 
 // ACES tone mapping
@@ -4,12 +4,13 @@ use log::{debug, info, warn};
 use std::error::Error;
 use std::{
     hash::{DefaultHasher, Hash, Hasher},
+    io::Cursor,
     path::PathBuf,
 };
 use wgpu::{
     include_wgsl,
     util::{BufferInitDescriptor, DeviceExt},
-    AddressMode, BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout,
+    AddressMode as WAddressMode, BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout,
     BindGroupLayoutDescriptor, BindGroupLayoutEntry, BindingResource, BindingType,
     BufferBindingType, BufferUsages, CommandEncoder, CompareFunction, ComputePassDescriptor,
     ComputePipeline, ComputePipelineDescriptor, Device, Extent3d, FilterMode as WFilterMode,
@@ -18,8 +19,9 @@ use wgpu::{
     TextureUsages, TextureView, TextureViewDescriptor, TextureViewDimension,
 };
 
+use crate::asset;
 use crate::mip_level::max_mip_level;
-use crate::resources::{FilterMode, MaterialShader, Texture, TextureSize};
+use crate::resources::{AddressMode, FilterMode, MaterialShader, Texture, TextureSize};
 
 mod error;
 pub use error::*;
@@ -36,6 +38,9 @@ pub use sampling_type::*;
 mod descriptor;
 pub use descriptor::*;
 
+mod ibl_source;
+use ibl_source::*;
+
 use super::{MaterialShaderDescriptor, ShaderSource, TextureDescriptor};
 
 #[cfg(test)]
@@ -60,32 +65,15 @@ pub struct WorldEnvironment {
 
 impl WorldEnvironment {
     pub fn bind_group_layout_descriptor() -> BindGroupLayoutDescriptor<'static> {
+        Self::source_bind_group_layout_descriptor(IblSource::Equirectangular)
+    }
+
+    fn source_bind_group_layout_descriptor(
+        source: IblSource,
+    ) -> BindGroupLayoutDescriptor<'static> {
         BindGroupLayoutDescriptor {
-            label: Some("Equirectangular to PBR IBL Environment Maps"),
-            entries: &[
-                // Input: Equirectangular Image as source
-                BindGroupLayoutEntry {
-                    binding: 0,
-                    visibility: ShaderStages::COMPUTE,
-                    ty: BindingType::Texture {
-                        sample_type: TextureSampleType::Float { filterable: false },
-                        view_dimension: TextureViewDimension::D2,
-                        multisampled: false,
-                    },
-                    count: None,
-                },
-                // Output
-                BindGroupLayoutEntry {
-                    binding: 1,
-                    visibility: ShaderStages::COMPUTE,
-                    ty: BindingType::StorageTexture {
-                        access: StorageTextureAccess::WriteOnly,
-                        format: TextureFormat::Rgba16Float,
-                        view_dimension: TextureViewDimension::D2Array,
-                    },
-                    count: None,
-                },
-            ],
+            label: Some("Environment to PBR IBL Environment Maps"),
+            entries: source.bind_group_layout_entries(),
         }
     }
 
@@ -288,6 +276,26 @@ impl WorldEnvironment {
                     queue,
                 )
             }
+            WorldEnvironmentDescriptor::FromCubeFaces {
+                cube_face_size,
+                faces,
+                sampling_type,
+                specular_mip_level_count,
+            } => {
+                let clamped_mip_levels = Self::calculate_specular_mip_level_count(
+                    *cube_face_size,
+                    specular_mip_level_count.as_ref(),
+                );
+
+                Self::radiance_cube_face_files(
+                    faces,
+                    *cube_face_size,
+                    sampling_type,
+                    clamped_mip_levels,
+                    device,
+                    queue,
+                )
+            }
         }
     }
 
@@ -299,7 +307,9 @@ impl WorldEnvironment {
         device: &Device,
         queue: &Queue,
     ) -> Result<(Texture, Texture), Box<dyn Error>> {
-        let img = ImageReader::open(file_path)
+        let bytes = asset::read(file_path).map_err(WorldEnvironmentError::IO)?;
+        let img = ImageReader::new(Cursor::new(bytes))
+            .with_guessed_format()
             .map_err(WorldEnvironmentError::IO)?
             .decode()
             .map_err(WorldEnvironmentError::Image)?;
@@ -328,6 +338,82 @@ impl WorldEnvironment {
         )
     }
 
+    /// Same as [Self::radiance_hdr_file], but loads the six faces of a cube
+    /// texture, ordered +X, -X, +Y, -Y, +Z, -Z.
+    ///
+    /// The faces are uploaded as they are and sampled directly by the IBL
+    /// processing.
+    pub fn radiance_cube_face_files(
+        file_paths: &[String; 6],
+        dst_size: u32,
+        sampling_type: &SamplingType,
+        specular_mip_level_count: u32,
+        device: &Device,
+        queue: &Queue,
+    ) -> Result<(Texture, Texture), Box<dyn Error>> {
+        let mut face_size = None;
+        let mut data = Vec::new();
+        for file_path in file_paths {
+            let bytes = asset::read(file_path).map_err(WorldEnvironmentError::IO)?;
+            let img = ImageReader::new(Cursor::new(bytes))
+                .with_guessed_format()
+                .map_err(WorldEnvironmentError::IO)?
+                .decode()
+                .map_err(WorldEnvironmentError::Image)?;
+
+            let (width, height) = img.dimensions();
+            if width != height || *face_size.get_or_insert(width) != width {
+                return Err(Box::new(WorldEnvironmentError::CubeFaceSize {
+                    path: file_path.clone(),
+                    width,
+                    height,
+                }));
+            }
+
+            data.extend(img.into_rgba32f().iter().flat_map(|x| x.to_le_bytes()));
+        }
+        let face_size = face_size.expect("Cube faces can't be empty");
+
+        let size = Extent3d {
+            width: face_size,
+            height: face_size,
+            depth_or_array_layers: 6,
+        };
+        let src_texture = Texture::from_descriptors_and_data(
+            &wgpu::TextureDescriptor {
+                label: Some("Cube Faces SRC"),
+                size,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: TextureFormat::Rgba32Float,
+                usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+                view_formats: &[],
+            },
+            &TextureViewDescriptor {
+                dimension: Some(TextureViewDimension::D2Array),
+                ..Default::default()
+            },
+            &SamplerDescriptor {
+                label: Some("Cube Faces SRC Sampler"),
+                ..Default::default()
+            },
+            Some((&data, size)),
+            device,
+            queue,
+        );
+
+        Self::radiance_source(
+            &src_texture,
+            IblSource::CubeFaces,
+            dst_size,
+            sampling_type,
+            specular_mip_level_count,
+            device,
+            queue,
+        )
+    }
+
     pub fn radiance_hdr_vec(
         data: &[u8],
         src_size: Vector2<u32>,
@@ -357,9 +443,9 @@ impl WorldEnvironment {
             &TextureViewDescriptor::default(),
             &SamplerDescriptor {
                 label: Some("Equirectangular SRC Sampler"),
-                address_mode_u: AddressMode::ClampToEdge,
-                address_mode_v: AddressMode::ClampToEdge,
-                address_mode_w: AddressMode::ClampToEdge,
+                address_mode_u: WAddressMode::ClampToEdge,
+                address_mode_v: WAddressMode::ClampToEdge,
+                address_mode_w: WAddressMode::ClampToEdge,
                 mag_filter: WFilterMode::Linear,
                 min_filter: WFilterMode::Linear,
                 mipmap_filter: WFilterMode::Linear,
@@ -378,12 +464,38 @@ impl WorldEnvironment {
             queue,
         );
 
+        Self::radiance_source(
+            &src_texture,
+            IblSource::Equirectangular,
+            dst_size,
+            sampling_type,
+            specular_mip_level_count,
+            device,
+            queue,
+        )
+    }
+
+    /// Generates the diffuse and specular IBL from an already uploaded
+    /// source.
+    fn radiance_source(
+        src_texture: &Texture,
+        source: IblSource,
+        dst_size: u32,
+        sampling_type: &SamplingType,
+        specular_mip_level_count: u32,
+        device: &Device,
+        queue: &Queue,
+    ) -> Result<(Texture, Texture), Box<dyn Error>> {
+        let bind_group_layout =
+            device.create_bind_group_layout(&Self::source_bind_group_layout_descriptor(source));
+
         // Generate diffuse IBL and wait for completion
         let diffuse = {
             let mut encoder = device.create_command_encoder(&Default::default());
             let result = Self::make_ibl_diffuse(
                 dst_size,
-                &device.create_bind_group_layout(&Self::bind_group_layout_descriptor()),
+                source,
+                &bind_group_layout,
                 src_texture.view(),
                 &mut encoder,
                 device,
@@ -398,7 +510,8 @@ impl WorldEnvironment {
             let mut encoder = device.create_command_encoder(&Default::default());
             let result = Self::make_ibl_specular(
                 dst_size,
-                &device.create_bind_group_layout(&Self::bind_group_layout_descriptor()),
+                source,
+                &bind_group_layout,
                 src_texture.view(),
                 specular_mip_level_count,
                 &mut encoder,
@@ -423,6 +536,7 @@ impl WorldEnvironment {
 
     fn make_ibl_diffuse(
         dst_size: u32,
+        source: IblSource,
         bind_group_layout: &BindGroupLayout,
         src_view: &TextureView,
         encoder: &mut CommandEncoder,
@@ -430,7 +544,11 @@ impl WorldEnvironment {
     ) -> Texture {
         let pipeline = Self::make_compute_pipeline(
             &[bind_group_layout],
-            include_wgsl!("make_ibl_diffuse.wgsl"),
+            Self::ibl_shader_module_descriptor(
+                "make_ibl_diffuse.wgsl",
+                source,
+                include_str!("make_ibl_diffuse.wgsl"),
+            ),
             "main",
             device,
         );
@@ -488,6 +606,7 @@ impl WorldEnvironment {
 
     fn make_ibl_specular(
         dst_size: u32,
+        source: IblSource,
         bind_group_layout: &BindGroupLayout,
         src_view: &TextureView,
         specular_mip_level_count: u32,
@@ -496,7 +615,11 @@ impl WorldEnvironment {
     ) -> Texture {
         let pipeline = Self::make_compute_pipeline(
             &[bind_group_layout],
-            include_wgsl!("make_ibl_specular.wgsl"),
+            Self::ibl_shader_module_descriptor(
+                "make_ibl_specular.wgsl",
+                source,
+                include_str!("make_ibl_specular.wgsl"),
+            ),
             "main",
             device,
         );
@@ -800,6 +923,20 @@ impl WorldEnvironment {
         })
     }
 
+    /// Prepends the sampling of `source` to an IBL shader.
+    fn ibl_shader_module_descriptor<'a>(
+        label: &'a str,
+        source: IblSource,
+        shader: &str,
+    ) -> ShaderModuleDescriptor<'a> {
+        ShaderModuleDescriptor {
+            label: Some(label),
+            source: wgpu::ShaderSource::Wgsl(
+                format!("{}\n{shader}", source.shader_source()).into(),
+            ),
+        }
+    }
+
     fn make_compute_pipeline(
         bind_group_layouts: &[&BindGroupLayout],
         shader_module_descriptor: ShaderModuleDescriptor,
@@ -846,6 +983,8 @@ impl WorldEnvironment {
             texture_dimension: TextureDimension::D2,
             texture_view_dimension: TextureViewDimension::Cube,
             filter_mode: FilterMode::nearest(),
+            address_mode: AddressMode::default(),
+            generate_mips: false,
         };
 
         let ibl_specular_data = pbr_ibl_specular.read_as_binary(device, queue);
@@ -865,6 +1004,8 @@ impl WorldEnvironment {
             texture_dimension: TextureDimension::D2,
             texture_view_dimension: TextureViewDimension::Cube,
             filter_mode: FilterMode::nearest(),
+            address_mode: AddressMode::default(),
+            generate_mips: false,
         };
 
         (ibl_diffuse_descriptor, ibl_specular_descriptor)
@@ -876,6 +1017,10 @@ impl WorldEnvironment {
             shader_source: ShaderSource::String(include_str!("material_shader.wgsl")),
             variables: vec![],
             depth_stencil: false,
+            // The sky box is drawn behind everything else and must never
+            // occlude any geometry.
+            depth_compare: CompareFunction::LessEqual,
+            depth_write_enabled: false,
             vertex_stage_layouts: None,
             cull_mode: None,
             ..Default::default()
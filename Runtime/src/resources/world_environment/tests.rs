@@ -151,3 +151,86 @@ fn test_cache_file() {
     let cache_file = WorldEnvironment::find_cache_file(&descriptor);
     debug!("{:?}", cache_file);
 }
+
+fn assert_ibl_textures(descriptor: &WorldEnvironmentDescriptor, size: u32, mip_levels: u32) {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+
+    let (diffuse, specular) = WorldEnvironment::make_from_descriptor(descriptor, &device, &queue)
+        .expect("Failed making IBL textures");
+
+    for texture in [&diffuse, &specular] {
+        assert_eq!(texture.texture().width(), size);
+        assert_eq!(texture.texture().height(), size);
+        assert_eq!(texture.texture().depth_or_array_layers(), 6);
+    }
+    assert_eq!(diffuse.texture().mip_level_count(), 1);
+    assert_eq!(specular.texture().mip_level_count(), mip_levels);
+}
+
+#[test]
+fn equirectangular_file() {
+    logging::test_init();
+
+    let path = std::env::temp_dir().join("orbital_equirectangular.hdr");
+    image::DynamicImage::ImageRgb32F(image::Rgb32FImage::from_fn(32, 16, |x, y| {
+        image::Rgb([x as f32 / 32.0, y as f32 / 16.0, 1.0])
+    }))
+    .save(&path)
+    .expect("Failed writing equirectangular image");
+
+    let descriptor = WorldEnvironmentDescriptor::FromFile {
+        cube_face_size: 16,
+        path: path.to_string_lossy().to_string(),
+        sampling_type: SamplingType::BoxBlur,
+        custom_specular_mip_level_count: Some(3),
+    };
+    assert_ibl_textures(&descriptor, 16, 3);
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn cube_face_files() {
+    logging::test_init();
+
+    let directory = std::env::temp_dir().join("orbital_cube_faces");
+    std::fs::create_dir_all(&directory).unwrap();
+
+    let faces: [String; 6] = std::array::from_fn(|i| {
+        let path = directory.join(format!("face_{i}.png"));
+        image::RgbaImage::from_pixel(8, 8, image::Rgba([i as u8 * 40, 0, 0, 255]))
+            .save(&path)
+            .expect("Failed writing cube face");
+        path.to_string_lossy().to_string()
+    });
+
+    let descriptor = WorldEnvironmentDescriptor::FromCubeFaces {
+        cube_face_size: 16,
+        faces: faces.clone(),
+        sampling_type: SamplingType::BoxBlur,
+        specular_mip_level_count: None,
+    };
+    // Defaults to the maximum possible mip levels below 7
+    assert_ibl_textures(&descriptor, 16, 5);
+
+    // Faces of different sizes are rejected
+    let odd_face = directory.join("odd_face.png");
+    image::RgbaImage::new(4, 4).save(&odd_face).unwrap();
+    let mut mismatching_faces = faces;
+    mismatching_faces[3] = odd_face.to_string_lossy().to_string();
+
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+    assert!(WorldEnvironment::make_from_descriptor(
+        &WorldEnvironmentDescriptor::FromCubeFaces {
+            cube_face_size: 16,
+            faces: mismatching_faces,
+            sampling_type: SamplingType::BoxBlur,
+            specular_mip_level_count: None,
+        },
+        &device,
+        &queue,
+    )
+    .is_err());
+
+    std::fs::remove_dir_all(directory).unwrap();
+}
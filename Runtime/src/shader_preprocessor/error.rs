@@ -1,9 +1,86 @@
-use std::{ffi::OsString, io};
+use std::{
+    error::Error,
+    ffi::OsString,
+    fmt::{Display, Formatter, Result},
+    io,
+};
 
 #[derive(Debug)]
 pub enum ShaderPreprocessorError {
-    UnknownDirective { directive: String },
-    NonUTF8FileName { file_name: OsString },
+    /// An `#import` references a directive that isn't known.
+    UnknownDirective {
+        directive: String,
+        /// Line (starting at 1) of the `#import` in the shader or import
+        /// it occurred in.
+        line: usize,
+        /// Directives being imported when the error occurred, outermost
+        /// first.
+        /// Empty if the `#import` is part of the shader itself.
+        import_chain: Vec<String>,
+    },
+    /// An `#import` references a directive that is currently being
+    /// expanded, e.g. `a` imports `b`, which imports `a` again.
+    CircularImport {
+        /// Directives forming the cycle, outermost first, ending with the
+        /// repeated directive.
+        import_chain: Vec<String>,
+    },
+    NonUTF8FileName {
+        file_name: OsString,
+    },
     IOError(io::Error),
     PatternError(glob::PatternError),
+    /// An `#else` or `#endif` without a matching `#ifdef`/`#ifndef`.
+    UnexpectedConditional {
+        directive: String,
+    },
+    /// An `#ifdef`/`#ifndef` that is never closed with `#endif`.
+    UnterminatedConditional {
+        name: String,
+    },
+    /// Watching a folder for changes failed.
+    #[cfg(feature = "hot_reload")]
+    Watch(notify::Error),
 }
+
+impl Display for ShaderPreprocessorError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            ShaderPreprocessorError::UnknownDirective {
+                directive,
+                line,
+                import_chain,
+            } => {
+                write!(f, "Unknown import '{directive}' at line {line}")?;
+                if !import_chain.is_empty() {
+                    let chain = import_chain
+                        .iter()
+                        .rev()
+                        .map(|x| format!("'{x}'"))
+                        .collect::<Vec<_>>()
+                        .join(" <- ");
+                    write!(f, " (imported from {chain})")?;
+                }
+                Ok(())
+            }
+            ShaderPreprocessorError::CircularImport { import_chain } => {
+                write!(f, "Circular import detected: {}", import_chain.join(" -> "))
+            }
+            ShaderPreprocessorError::UnexpectedConditional { directive } => {
+                write!(
+                    f,
+                    "Unexpected '{directive}' without an open conditional block"
+                )
+            }
+            ShaderPreprocessorError::UnterminatedConditional { name } => {
+                write!(
+                    f,
+                    "Conditional block '{name}' is never closed with '#endif'"
+                )
+            }
+            _ => write!(f, "{self:?}"),
+        }
+    }
+}
+
+impl Error for ShaderPreprocessorError {}
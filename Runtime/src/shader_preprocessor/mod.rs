@@ -1,20 +1,52 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fs::{canonicalize, read_to_string},
-    path::PathBuf,
+    path::{Path, PathBuf},
+    sync::{LazyLock, Mutex},
 };
 
+use crate::asset;
+
 use glob::glob;
 use log::debug;
+#[cfg(feature = "hot_reload")]
+use log::warn;
 
 mod error;
 pub use error::ShaderPreprocessorError;
 
+#[cfg(feature = "hot_reload")]
+mod watcher;
+#[cfg(feature = "hot_reload")]
+use watcher::ShaderWatcher;
+
 #[cfg(test)]
 mod tests;
 
+/// The preprocessor shared by all shaders without a preprocessor of their
+/// own, see [ShaderPreprocessor::shared].
+/// Created with the default imports on first use.
+static SHARED_PREPROCESSOR: LazyLock<Mutex<Option<ShaderPreprocessor>>> =
+    LazyLock::new(|| Mutex::new(None));
+
 pub struct ShaderPreprocessor {
     known_imports: HashMap<String, String>,
+    defines: HashSet<String>,
+    #[cfg(feature = "hot_reload")]
+    watchers: Vec<ShaderWatcher>,
+}
+
+/// Clones the imports and defines.
+/// Watched folders stay with the original, clones don't reload.
+impl Clone for ShaderPreprocessor {
+    fn clone(&self) -> Self {
+        Self {
+            known_imports: self.known_imports.clone(),
+            defines: self.defines.clone(),
+            #[cfg(feature = "hot_reload")]
+            watchers: Vec::new(),
+        }
+    }
 }
 
 impl ShaderPreprocessor {
@@ -34,6 +66,31 @@ impl ShaderPreprocessor {
     /// Where $1 is the name of your import.
     pub const IMPORT_EXPRESSION_END: &'static str = ">";
 
+    /// Defines a name, e.g.:
+    /// ```wgsl
+    /// #define NORMAL_MAPPING
+    /// ```
+    pub const DEFINE_EXPRESSION: &'static str = "#define";
+
+    /// Starts a block that is only included if the name is defined, e.g.:
+    /// ```wgsl
+    /// #ifdef NORMAL_MAPPING
+    /// ```
+    pub const IFDEF_EXPRESSION: &'static str = "#ifdef";
+
+    /// Starts a block that is only included if the name is **not** defined,
+    /// e.g.:
+    /// ```wgsl
+    /// #ifndef NORMAL_MAPPING
+    /// ```
+    pub const IFNDEF_EXPRESSION: &'static str = "#ifndef";
+
+    /// Switches to the opposite branch of the current conditional block.
+    pub const ELSE_EXPRESSION: &'static str = "#else";
+
+    /// Ends the current conditional block.
+    pub const ENDIF_EXPRESSION: &'static str = "#endif";
+
     /// Path to the expected shader lib to be used for default importing.
     #[cfg(debug_assertions)]
     pub const SHADER_LIB_IMPORT_FOLDER_PATH_DEBUG_BUILD: &'static str = "../../Assets/Shaders";
@@ -42,25 +99,135 @@ impl ShaderPreprocessor {
     #[cfg(not(debug_assertions))]
     pub const SHADER_LIB_IMPORT_FOLDER_PATH: &'static str = "Assets/shaders";
 
+    /// The shader lib, embedded at compile time by the build script.  
+    /// Pairs of directive and content, as produced by [Self::import_folder].
+    #[cfg(feature = "embed_shaders")]
+    pub const EMBEDDED_SHADER_LIB: &'static [(&'static str, &'static str)] =
+        include!(concat!(env!("OUT_DIR"), "/embedded_shaders.rs"));
+
     /// Creates a new shader compiler.
     /// Note that each instance of this needs to have your imports imported!
+    ///
+    /// With the `embed_shaders` feature enabled, the shader lib embedded
+    /// at compile time is used and the filesystem isn't touched.
     pub fn new_with_defaults() -> Result<Self, ShaderPreprocessorError> {
-        let mut s = Self {
-            known_imports: HashMap::new(),
+        #[cfg(feature = "embed_shaders")]
+        return Ok(Self::from_embedded(Self::EMBEDDED_SHADER_LIB));
+
+        #[cfg(not(feature = "embed_shaders"))]
+        {
+            let mut s = Self::new_empty();
+
+            #[cfg(debug_assertions)]
+            s.import_folder(Self::SHADER_LIB_IMPORT_FOLDER_PATH_DEBUG_BUILD)?;
+
+            #[cfg(not(debug_assertions))]
+            s.import_folder(Self::SHADER_LIB_IMPORT_FOLDER_PATH)?;
+
+            Ok(s)
+        }
+    }
+
+    /// Creates a new shader compiler from directive and content pairs,
+    /// e.g. embedded via `include_str!`:
+    ///
+    /// ```
+    /// # use orbital::shader_preprocessor::ShaderPreprocessor;
+    /// let shader_preprocessor = ShaderPreprocessor::from_embedded(&[
+    ///     ("lib/constants", "const PI: f32 = 3.14159;"),
+    /// ]);
+    /// ```
+    ///
+    /// No filesystem access happens, which makes this suitable for targets
+    /// where disk access is unavailable or slow, such as the Web.
+    pub fn from_embedded(entries: &[(&str, &str)]) -> Self {
+        let mut s = Self::new_empty();
+        for (directive, content) in entries {
+            s.add_import(*directive, *content);
+        }
+
+        s
+    }
+
+    /// Returns a copy of the shared preprocessor, which is created via
+    /// [Self::new_with_defaults] on first use.
+    /// Shaders without a preprocessor of their own are parsed with this,
+    /// thus the default imports are only read once.
+    ///
+    /// With the `hot_reload` feature enabled, the shared preprocessor
+    /// watches the shader lib, see [Self::take_shared_dirty].
+    pub fn shared() -> Result<Self, ShaderPreprocessorError> {
+        Self::with_shared(|preprocessor| preprocessor.clone())
+    }
+
+    /// Runs `f` on the shared preprocessor, e.g. to add imports all shaders
+    /// should have access to.
+    /// Shaders that are already compiled aren't affected.
+    pub fn with_shared<T>(
+        f: impl FnOnce(&mut ShaderPreprocessor) -> T,
+    ) -> Result<T, ShaderPreprocessorError> {
+        let mut lock = SHARED_PREPROCESSOR
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let preprocessor = match lock.as_mut() {
+            Some(preprocessor) => preprocessor,
+            None => lock.insert(Self::new_shared()?),
         };
 
-        #[cfg(debug_assertions)]
-        s.import_folder(Self::SHADER_LIB_IMPORT_FOLDER_PATH_DEBUG_BUILD)?;
+        Ok(f(preprocessor))
+    }
 
-        #[cfg(not(debug_assertions))]
-        s.import_folder(Self::SHADER_LIB_IMPORT_FOLDER_PATH)?;
+    /// Replaces the shared preprocessor, see [Self::shared].
+    pub fn set_shared(preprocessor: ShaderPreprocessor) {
+        *SHARED_PREPROCESSOR
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(preprocessor);
+    }
 
-        Ok(s)
+    /// Re-reads any changed files of the shared preprocessor, see
+    /// [Self::take_dirty].
+    /// Returns `false` if the shared preprocessor doesn't exist yet, as
+    /// nothing could have been compiled with it.
+    #[cfg(feature = "hot_reload")]
+    pub fn take_shared_dirty() -> bool {
+        SHARED_PREPROCESSOR
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .as_mut()
+            .is_some_and(|preprocessor| preprocessor.take_dirty())
+    }
+
+    fn new_shared() -> Result<Self, ShaderPreprocessorError> {
+        #[cfg(all(feature = "hot_reload", not(feature = "embed_shaders")))]
+        {
+            let mut s = Self::new_with_defaults()?;
+
+            #[cfg(debug_assertions)]
+            let path = Self::SHADER_LIB_IMPORT_FOLDER_PATH_DEBUG_BUILD;
+
+            #[cfg(not(debug_assertions))]
+            let path = Self::SHADER_LIB_IMPORT_FOLDER_PATH;
+
+            // Shaders still work without reloading, e.g. if the shader lib
+            // got moved
+            if let Err(e) = s.watch(path) {
+                warn!("Failed watching shader lib '{path}' for changes: {e}");
+            }
+
+            Ok(s)
+        }
+
+        #[cfg(not(all(feature = "hot_reload", not(feature = "embed_shaders"))))]
+        Self::new_with_defaults()
     }
 
     pub fn new_empty() -> Self {
         Self {
             known_imports: HashMap::new(),
+            defines: HashSet::new(),
+            #[cfg(feature = "hot_reload")]
+            watchers: Vec::new(),
         }
     }
 
@@ -77,6 +244,16 @@ impl ShaderPreprocessor {
         self.known_imports.insert(directive.into(), content.into());
     }
 
+    /// Defines a name for all shaders parsed by this preprocessor.
+    ///
+    /// Blocks guarded by `#ifdef <name>` will be included, blocks guarded by
+    /// `#ifndef <name>` will be dropped.
+    /// Shaders can additionally define names themselves via `#define <name>`,
+    /// which only applies to the shader being parsed.
+    pub fn add_define<S: Into<String>>(&mut self, name: S) {
+        self.defines.insert(name.into());
+    }
+
     /// Adds an import directive by reading the contents of a file.
     ///
     /// This method reads the contents of a specified file and registers it as a custom import
@@ -106,7 +283,7 @@ impl ShaderPreprocessor {
         );
 
         // Read the file content
-        let content = read_to_string(&path).map_err(ShaderPreprocessorError::IOError)?;
+        let content = asset::read_to_string(&path).map_err(ShaderPreprocessorError::IOError)?;
 
         // Register the directive and content using add_known_import
         self.add_import(directive, content);
@@ -122,6 +299,8 @@ impl ShaderPreprocessor {
     /// directory separators replaced by slashes. The registration allows these imported shaders
     /// to be included in other shaders using the `#import` directive.
     ///
+    /// Relative paths are resolved against the asset root, see [`crate::asset`].
+    ///
     /// # Arguments
     /// * `path` - The path to the directory containing the WGSL files to import.
     pub fn import_folder<S: Into<String>>(
@@ -130,7 +309,7 @@ impl ShaderPreprocessor {
     ) -> Result<(), ShaderPreprocessorError> {
         const PATTERN: &str = "**/*.wgsl";
 
-        let path_into = path.into();
+        let path_into = asset::resolve(path.into()).to_string_lossy().to_string();
 
         let mut pattern_path = path_into.clone();
         if !pattern_path.ends_with("/") {
@@ -142,16 +321,7 @@ impl ShaderPreprocessor {
             .map_err(ShaderPreprocessorError::PatternError)?
             .filter_map(Result::ok)
         {
-            let directive = &entry
-                .strip_prefix(&path_into)
-                .expect("Base got merged into pattern. It cannot not be here.")
-                .to_str()
-                .ok_or(ShaderPreprocessorError::NonUTF8FileName {
-                    file_name: entry.clone().into_os_string(),
-                })?
-                .replace("\\", "/")
-                .replace(".wgsl", "")
-                .to_lowercase();
+            let directive = &Self::folder_directive(Path::new(&path_into), &entry)?;
 
             let content = read_to_string(&entry).map_err(ShaderPreprocessorError::IOError)?;
             debug!(
@@ -168,72 +338,266 @@ impl ShaderPreprocessor {
         Ok(())
     }
 
+    /// Derives the directive of a file imported via [Self::import_folder]
+    /// from its path relative to the imported folder.
+    fn folder_directive(folder: &Path, file: &Path) -> Result<String, ShaderPreprocessorError> {
+        Ok(file
+            .strip_prefix(folder)
+            .expect("File must be inside of the folder")
+            .to_str()
+            .ok_or(ShaderPreprocessorError::NonUTF8FileName {
+                file_name: file.as_os_str().to_os_string(),
+            })?
+            .replace("\\", "/")
+            .replace(".wgsl", "")
+            .to_lowercase())
+    }
+
+    /// Imports a folder like [Self::import_folder] and keeps watching it
+    /// for changes.
+    ///
+    /// Changed files are re-read into the known imports once
+    /// [Self::take_dirty] is called, e.g. once per frame, to rebuild any
+    /// shaders using them.
+    /// The watcher shuts down once this preprocessor is dropped.
+    #[cfg(feature = "hot_reload")]
+    pub fn watch_folder<S: Into<String>>(
+        &mut self,
+        path: S,
+    ) -> Result<(), ShaderPreprocessorError> {
+        let path = path.into();
+        self.import_folder(path.clone())?;
+        self.watch(path)
+    }
+
+    /// Starts watching an already imported folder, see
+    /// [Self::watch_folder].
+    #[cfg(feature = "hot_reload")]
+    fn watch(&mut self, path: impl Into<String>) -> Result<(), ShaderPreprocessorError> {
+        let watcher = ShaderWatcher::new(asset::resolve(path.into()))?;
+        self.watchers.push(watcher);
+
+        Ok(())
+    }
+
+    /// Re-reads all files changed in folders watched via
+    /// [Self::watch_folder].  
+    /// Returns `true` if any import changed since the last call, meaning
+    /// shaders need to be rebuilt.
+    #[cfg(feature = "hot_reload")]
+    pub fn take_dirty(&mut self) -> bool {
+        let mut dirty = false;
+
+        let changes: Vec<_> = self
+            .watchers
+            .iter()
+            .flat_map(|watcher| {
+                watcher
+                    .take_changed_files()
+                    .into_iter()
+                    .map(|file| (watcher.root().clone(), file))
+            })
+            .collect();
+        for (root, file) in changes {
+            let directive = match Self::folder_directive(&root, &file) {
+                Ok(x) => x,
+                Err(e) => {
+                    warn!("Failed reloading shader {file:?}: {e}");
+                    continue;
+                }
+            };
+            // Editors may still be writing, any later write is reported again
+            let content = match read_to_string(&file) {
+                Ok(x) => x,
+                Err(e) => {
+                    warn!("Failed reloading shader {file:?}: {e}");
+                    continue;
+                }
+            };
+
+            if self.known_imports.get(&directive) != Some(&content) {
+                debug!("Reloaded shader import '{directive}' from {file:?}");
+                self.add_import(directive, content);
+                dirty = true;
+            }
+        }
+
+        dirty
+    }
+
     /// Parses a shader from source code.
     /// Any supported preprocessor definitions will be added as they are imported.
+    /// Conditional blocks (`#ifdef`, `#ifndef`, `#else`, `#endif`) are
+    /// resolved given the defines of this preprocessor and any `#define`
+    /// inside the shader.
     pub fn parse_shader<S: Into<String>>(
         &self,
         source: S,
     ) -> Result<String, ShaderPreprocessorError> {
         let source = source.into();
-        let imported_directives = Vec::new();
-        self.parse_shader_(source, imported_directives)
+        let mut imported_directives = Vec::new();
+        let mut defines = self.defines.clone();
+        let mut import_chain = Vec::new();
+
+        let mut output = Vec::new();
+        self.parse_shader_(
+            &source,
+            &mut output,
+            &mut imported_directives,
+            &mut defines,
+            &mut import_chain,
+        )?;
+
+        Ok(output.join("\n"))
     }
 
     /// Part of [Self::parse_shader].  
     /// Does the work, but is designed for recursive calls.
-    fn parse_shader_(
-        &self,
-        source: String,
-        imported_directives: Vec<&str>,
-    ) -> Result<String, ShaderPreprocessorError> {
-        let mut output = String::new();
-        let mut imported_directives = imported_directives;
-        let mut import_found = false;
+    ///
+    /// Imports are expanded in place, `import_chain` holds the directives
+    /// currently being expanded (outermost first) to be able to tell where
+    /// an error originates from.
+    fn parse_shader_<'a>(
+        &'a self,
+        source: &'a str,
+        output: &mut Vec<&'a str>,
+        imported_directives: &mut Vec<&'a str>,
+        defines: &mut HashSet<String>,
+        import_chain: &mut Vec<&'a str>,
+    ) -> Result<(), ShaderPreprocessorError> {
+        let mut conditionals: Vec<Conditional> = Vec::new();
+
+        for (index, line) in source.lines().enumerate() {
+            let trimmed = line.trim();
+            let active = conditionals.iter().all(|x| x.is_active());
+
+            if let Some(name) = Self::directive_argument(trimmed, Self::IFDEF_EXPRESSION) {
+                conditionals.push(Conditional::new(name, defines.contains(name)));
+                continue;
+            }
+
+            if let Some(name) = Self::directive_argument(trimmed, Self::IFNDEF_EXPRESSION) {
+                conditionals.push(Conditional::new(name, !defines.contains(name)));
+                continue;
+            }
+
+            if Self::directive_argument(trimmed, Self::ELSE_EXPRESSION).is_some() {
+                match conditionals.last_mut() {
+                    Some(conditional) if !conditional.in_else => {
+                        conditional.in_else = true;
+                    }
+                    _ => {
+                        return Err(ShaderPreprocessorError::UnexpectedConditional {
+                            directive: Self::ELSE_EXPRESSION.to_string(),
+                        })
+                    }
+                }
+                continue;
+            }
+
+            if Self::directive_argument(trimmed, Self::ENDIF_EXPRESSION).is_some() {
+                if conditionals.pop().is_none() {
+                    return Err(ShaderPreprocessorError::UnexpectedConditional {
+                        directive: Self::ENDIF_EXPRESSION.to_string(),
+                    });
+                }
+                continue;
+            }
+
+            if !active {
+                // Inside a disabled block, drop the line
+                continue;
+            }
+
+            if let Some(name) = Self::directive_argument(trimmed, Self::DEFINE_EXPRESSION) {
+                defines.insert(name.to_string());
+                continue;
+            }
 
-        for line in source.lines() {
             if let Some(start) = line.find(Self::IMPORT_EXPRESSION_START) {
                 if let Some(end) = line.find(Self::IMPORT_EXPRESSION_END) {
                     let directive = &line[start + Self::IMPORT_EXPRESSION_START.len()..end];
+                    if import_chain.last() == Some(&directive) {
+                        // Importing itself, nothing to add
+                        continue;
+                    }
+                    if let Some(cycle_start) = import_chain.iter().position(|x| *x == directive) {
+                        // Must be checked before deduplication, as a cycle
+                        // would otherwise silently truncate the import
+                        return Err(ShaderPreprocessorError::CircularImport {
+                            import_chain: import_chain[cycle_start..]
+                                .iter()
+                                .chain([&directive])
+                                .map(|x| x.to_string())
+                                .collect(),
+                        });
+                    }
                     if imported_directives.contains(&directive) {
                         // Already imported in this shader so SKIP!
                         continue;
-                    } else {
-                        // Otherwise we need to add this directive
-                        imported_directives.push(directive);
-
-                        // Flag import found to true, this indicates that we need to run the shader preprocessor _again_ until there are no more imports found.
-                        import_found = true;
                     }
+                    imported_directives.push(directive);
 
-                    let import = self.known_imports.get(directive).ok_or(
+                    let import = self.known_imports.get(directive).ok_or_else(|| {
                         ShaderPreprocessorError::UnknownDirective {
                             directive: directive.to_string(),
-                        },
-                    )?;
+                            line: index + 1,
+                            import_chain: import_chain.iter().map(|x| x.to_string()).collect(),
+                        }
+                    })?;
 
-                    if output.is_empty() {
-                        output = import.clone();
-                    } else {
-                        output = format!("{output}\n{import}");
-                    }
+                    // Replace this line with the (processed) import
+                    import_chain.push(directive);
+                    self.parse_shader_(import, output, imported_directives, defines, import_chain)?;
+                    import_chain.pop();
 
-                    // Skip this line from being included as we replaced it with the import
                     continue;
                 }
             }
 
             // No match, so just add the line to the shader
-            if output.is_empty() {
-                output = line.to_string();
-            } else {
-                output = format!("{output}\n{line}");
-            }
+            output.push(line);
+        }
+
+        if let Some(conditional) = conditionals.pop() {
+            return Err(ShaderPreprocessorError::UnterminatedConditional {
+                name: conditional.name,
+            });
         }
 
-        if import_found {
-            self.parse_shader_(output, imported_directives)
+        Ok(())
+    }
+
+    /// Checks if a (trimmed) line is the given directive.
+    /// If so, returns the (possibly empty) argument following it.
+    fn directive_argument<'a>(line: &'a str, directive: &str) -> Option<&'a str> {
+        let rest = line.strip_prefix(directive)?;
+        if rest.is_empty() || rest.starts_with(char::is_whitespace) {
+            Some(rest.trim())
         } else {
-            Ok(output)
+            None
         }
     }
 }
+
+/// A conditional block (`#ifdef`/`#ifndef`) currently being parsed.
+struct Conditional {
+    name: String,
+    condition: bool,
+    in_else: bool,
+}
+
+impl Conditional {
+    fn new(name: &str, condition: bool) -> Self {
+        Self {
+            name: name.to_string(),
+            condition,
+            in_else: false,
+        }
+    }
+
+    /// Whether lines inside the current branch of this block are included.
+    fn is_active(&self) -> bool {
+        self.condition != self.in_else
+    }
+}
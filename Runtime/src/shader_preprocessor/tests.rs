@@ -472,3 +472,339 @@
 //
 //     assert!(!shader_preprocessor.known_imports.is_empty());
 // }
+
+mod embedded {
+    use crate::shader_preprocessor::ShaderPreprocessor;
+
+    const CONSTANTS: &str = "const PI: f32 = 3.14159;";
+
+    #[test]
+    fn import_from_embedded() {
+        let shader_preprocessor = ShaderPreprocessor::from_embedded(&[
+            ("lib/constants", CONSTANTS),
+            ("lib/unused", "const UNUSED: f32 = 0.0;"),
+        ]);
+
+        let parsed_shader = shader_preprocessor
+            .parse_shader("#import <lib/constants>\nfn main() {}")
+            .expect("Shader parsing failed!");
+        assert_eq!(parsed_shader, format!("{CONSTANTS}\nfn main() {{}}"));
+    }
+
+    #[cfg(feature = "embed_shaders")]
+    #[test]
+    fn default_uses_embedded_shader_lib() {
+        let shader_preprocessor =
+            ShaderPreprocessor::new_with_defaults().expect("Embedded shader lib must not fail");
+
+        assert!(shader_preprocessor.known_imports.contains_key("pbr"));
+    }
+}
+
+mod conditionals {
+    use crate::shader_preprocessor::{ShaderPreprocessor, ShaderPreprocessorError};
+
+    const SHADER: &str = "#ifdef FEATURE
+enabled
+#else
+disabled
+#endif
+always";
+
+    #[test]
+    fn define_set() {
+        let mut shader_preprocessor = ShaderPreprocessor::new_empty();
+        shader_preprocessor.add_define("FEATURE");
+
+        let parsed_shader = shader_preprocessor
+            .parse_shader(SHADER)
+            .expect("Shader parsing failed!");
+        assert_eq!(parsed_shader, "enabled\nalways");
+    }
+
+    #[test]
+    fn define_unset() {
+        let shader_preprocessor = ShaderPreprocessor::new_empty();
+
+        let parsed_shader = shader_preprocessor
+            .parse_shader(SHADER)
+            .expect("Shader parsing failed!");
+        assert_eq!(parsed_shader, "disabled\nalways");
+    }
+
+    #[test]
+    fn define_in_shader() {
+        let shader_preprocessor = ShaderPreprocessor::new_empty();
+
+        let parsed_shader = shader_preprocessor
+            .parse_shader(format!("#define FEATURE\n{SHADER}"))
+            .expect("Shader parsing failed!");
+        assert_eq!(parsed_shader, "enabled\nalways");
+
+        // Defines of a shader must not leak into other shaders
+        let parsed_shader = shader_preprocessor
+            .parse_shader(SHADER)
+            .expect("Shader parsing failed!");
+        assert_eq!(parsed_shader, "disabled\nalways");
+    }
+
+    #[test]
+    fn ifndef() {
+        let mut shader_preprocessor = ShaderPreprocessor::new_empty();
+        const SHADER: &str = "#ifndef FEATURE\nfallback\n#endif";
+
+        let parsed_shader = shader_preprocessor
+            .parse_shader(SHADER)
+            .expect("Shader parsing failed!");
+        assert_eq!(parsed_shader, "fallback");
+
+        shader_preprocessor.add_define("FEATURE");
+        let parsed_shader = shader_preprocessor
+            .parse_shader(SHADER)
+            .expect("Shader parsing failed!");
+        assert_eq!(parsed_shader, "");
+    }
+
+    #[test]
+    fn nested() {
+        const SHADER: &str = "#ifdef OUTER
+outer
+    #ifdef INNER
+    inner
+    #else
+    not inner
+    #endif
+#else
+    #ifdef INNER
+    inner without outer
+    #endif
+#endif";
+
+        let parse = |defines: &[&str]| {
+            let mut shader_preprocessor = ShaderPreprocessor::new_empty();
+            for define in defines {
+                shader_preprocessor.add_define(*define);
+            }
+            shader_preprocessor
+                .parse_shader(SHADER)
+                .expect("Shader parsing failed!")
+        };
+
+        assert_eq!(parse(&["OUTER", "INNER"]), "outer\n    inner");
+        assert_eq!(parse(&["OUTER"]), "outer\n    not inner");
+        assert_eq!(parse(&["INNER"]), "    inner without outer");
+        assert_eq!(parse(&[]), "");
+    }
+
+    #[test]
+    fn import_inside_ifdef() {
+        const SHADER: &str = "#ifdef FEATURE\n#import <feature>\n#endif\nmain";
+
+        let mut shader_preprocessor = ShaderPreprocessor::new_empty();
+        shader_preprocessor.add_import("feature", "#ifdef FEATURE\nfeature code\n#endif");
+
+        let parsed_shader = shader_preprocessor
+            .parse_shader(SHADER)
+            .expect("Shader parsing failed!");
+        assert_eq!(parsed_shader, "main");
+
+        shader_preprocessor.add_define("FEATURE");
+        let parsed_shader = shader_preprocessor
+            .parse_shader(format!("{SHADER}\n#import <feature>"))
+            .expect("Shader parsing failed!");
+        // Imported once, even though it's imported twice
+        assert_eq!(parsed_shader, "feature code\nmain");
+    }
+
+    #[test]
+    fn unknown_import_inside_disabled_block() {
+        let shader_preprocessor = ShaderPreprocessor::new_empty();
+
+        let parsed_shader = shader_preprocessor
+            .parse_shader("#ifdef FEATURE\n#import <missing>\n#endif")
+            .expect("Shader parsing failed!");
+        assert_eq!(parsed_shader, "");
+    }
+
+    #[test]
+    fn unbalanced() {
+        let shader_preprocessor = ShaderPreprocessor::new_empty();
+
+        assert!(matches!(
+            shader_preprocessor.parse_shader("#ifdef FEATURE\ncode"),
+            Err(ShaderPreprocessorError::UnterminatedConditional { name }) if name == "FEATURE"
+        ));
+        assert!(matches!(
+            shader_preprocessor.parse_shader("code\n#endif"),
+            Err(ShaderPreprocessorError::UnexpectedConditional { .. })
+        ));
+        assert!(matches!(
+            shader_preprocessor.parse_shader("#ifdef A\n#else\n#else\n#endif"),
+            Err(ShaderPreprocessorError::UnexpectedConditional { .. })
+        ));
+    }
+}
+
+mod errors {
+    use crate::shader_preprocessor::{ShaderPreprocessor, ShaderPreprocessorError};
+
+    #[test]
+    fn unknown_import_reports_line() {
+        let shader_preprocessor = ShaderPreprocessor::new_empty();
+
+        let error = shader_preprocessor
+            .parse_shader("fn a() {}\n\n#import <foo>\nfn b() {}")
+            .expect_err("Unknown import must fail!");
+        assert!(matches!(
+            &error,
+            ShaderPreprocessorError::UnknownDirective { line: 3, import_chain, .. } if import_chain.is_empty()
+        ));
+        assert_eq!(error.to_string(), "Unknown import 'foo' at line 3");
+    }
+
+    #[test]
+    fn unknown_nested_import_reports_chain() {
+        let mut shader_preprocessor = ShaderPreprocessor::new_empty();
+        shader_preprocessor.add_import("pbr/pbr", "#import <pbr/brdf>");
+        shader_preprocessor.add_import("pbr/brdf", "fn brdf() {}\n// missing\n#import <foo>");
+
+        let error = shader_preprocessor
+            .parse_shader("#import <pbr/pbr>")
+            .expect_err("Unknown import must fail!");
+        assert!(matches!(
+            &error,
+            ShaderPreprocessorError::UnknownDirective { line: 3, import_chain, .. }
+                if import_chain == &["pbr/pbr", "pbr/brdf"]
+        ));
+        assert_eq!(
+            error.to_string(),
+            "Unknown import 'foo' at line 3 (imported from 'pbr/brdf' <- 'pbr/pbr')"
+        );
+    }
+
+    #[test]
+    fn two_node_cycle() {
+        let mut shader_preprocessor = ShaderPreprocessor::new_empty();
+        shader_preprocessor.add_import("a", "fn a() {}\n#import <b>");
+        shader_preprocessor.add_import("b", "fn b() {}\n#import <a>");
+
+        let error = shader_preprocessor
+            .parse_shader("#import <a>")
+            .expect_err("Circular import must fail!");
+        assert!(matches!(
+            &error,
+            ShaderPreprocessorError::CircularImport { import_chain } if import_chain == &["a", "b", "a"]
+        ));
+        assert_eq!(error.to_string(), "Circular import detected: a -> b -> a");
+    }
+
+    #[test]
+    fn three_node_cycle() {
+        let mut shader_preprocessor = ShaderPreprocessor::new_empty();
+        shader_preprocessor.add_import("main", "#import <a>");
+        shader_preprocessor.add_import("a", "#import <b>");
+        shader_preprocessor.add_import("b", "#import <c>");
+        shader_preprocessor.add_import("c", "#import <a>");
+
+        let error = shader_preprocessor
+            .parse_shader("#import <main>")
+            .expect_err("Circular import must fail!");
+        // Only the cycle itself is reported
+        assert_eq!(
+            error.to_string(),
+            "Circular import detected: a -> b -> c -> a"
+        );
+    }
+
+    #[test]
+    fn self_import_is_dropped() {
+        let mut shader_preprocessor = ShaderPreprocessor::new_empty();
+        shader_preprocessor.add_import("a", "fn a() {}\n#import <a>");
+
+        let parsed_shader = shader_preprocessor
+            .parse_shader("#import <a>")
+            .expect("Self import must not fail!");
+        assert_eq!(parsed_shader, "fn a() {}");
+    }
+
+    #[test]
+    fn diamond_imports_leaf_once() {
+        let mut shader_preprocessor = ShaderPreprocessor::new_empty();
+        shader_preprocessor.add_import("left", "#import <leaf>\nfn left() {}");
+        shader_preprocessor.add_import("right", "#import <leaf>\nfn right() {}");
+        shader_preprocessor.add_import("leaf", "fn leaf() {}");
+
+        let parsed_shader = shader_preprocessor
+            .parse_shader("#import <left>\n#import <right>")
+            .expect("Diamond import must not fail!");
+        assert_eq!(parsed_shader, "fn leaf() {}\nfn left() {}\nfn right() {}");
+    }
+}
+
+mod shared {
+    use crate::shader_preprocessor::ShaderPreprocessor;
+
+    #[test]
+    fn shared_imports_are_visible_to_copies() {
+        ShaderPreprocessor::with_shared(|preprocessor| {
+            preprocessor.add_import("test/shared", "const SHARED: f32 = 1.0;")
+        })
+        .expect("Shared preprocessor must not fail");
+
+        let parsed_shader = ShaderPreprocessor::shared()
+            .expect("Shared preprocessor must not fail")
+            .parse_shader("#import <test/shared>")
+            .expect("Shader parsing failed!");
+        assert_eq!(parsed_shader, "const SHARED: f32 = 1.0;");
+    }
+}
+
+#[cfg(feature = "hot_reload")]
+mod hot_reload {
+    use std::{
+        fs::{create_dir_all, remove_dir_all, write},
+        thread::sleep,
+        time::{Duration, Instant},
+    };
+
+    use crate::shader_preprocessor::ShaderPreprocessor;
+
+    #[test]
+    fn changed_file_is_reloaded() {
+        let folder = std::env::temp_dir().join("orbital_shader_hot_reload");
+        let _ = remove_dir_all(&folder);
+        create_dir_all(folder.join("lib")).unwrap();
+        let file = folder.join("lib/constants.wgsl");
+        write(&file, "const VALUE: f32 = 1.0;").unwrap();
+
+        let mut shader_preprocessor = ShaderPreprocessor::new_empty();
+        shader_preprocessor
+            .watch_folder(folder.to_string_lossy())
+            .expect("Watching must not fail");
+        assert_eq!(
+            shader_preprocessor.known_imports["lib/constants"],
+            "const VALUE: f32 = 1.0;"
+        );
+        assert!(!shader_preprocessor.take_dirty());
+
+        write(&file, "const VALUE: f32 = 2.0;").unwrap();
+
+        // Events arrive asynchronously
+        let start = Instant::now();
+        while !shader_preprocessor.take_dirty() {
+            assert!(
+                start.elapsed() < Duration::from_secs(5),
+                "Change wasn't picked up in time"
+            );
+            sleep(Duration::from_millis(10));
+        }
+        assert_eq!(
+            shader_preprocessor.known_imports["lib/constants"],
+            "const VALUE: f32 = 2.0;"
+        );
+
+        // Shuts down the watcher
+        drop(shader_preprocessor);
+        remove_dir_all(&folder).unwrap();
+    }
+}
@@ -0,0 +1,74 @@
+use std::{
+    fs::canonicalize,
+    path::PathBuf,
+    sync::mpsc::{channel, Receiver},
+};
+
+use log::warn;
+use notify::{recommended_watcher, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use super::ShaderPreprocessorError;
+
+/// Watches a shader folder for changes, see
+/// [ShaderPreprocessor::watch_folder](super::ShaderPreprocessor::watch_folder).
+///
+/// Changes are only collected here and applied once taken.
+/// Dropping this shuts down the underlying watcher thread.
+pub(super) struct ShaderWatcher {
+    /// Canonical path of the watched folder.
+    root: PathBuf,
+    receiver: Receiver<notify::Result<Event>>,
+    // Kept alive for as long as the folder should be watched
+    _watcher: RecommendedWatcher,
+}
+
+impl ShaderWatcher {
+    pub(super) fn new(root: PathBuf) -> Result<Self, ShaderPreprocessorError> {
+        let root = canonicalize(root).map_err(ShaderPreprocessorError::IOError)?;
+
+        let (sender, receiver) = channel();
+        let mut watcher = recommended_watcher(sender).map_err(ShaderPreprocessorError::Watch)?;
+        watcher
+            .watch(&root, RecursiveMode::Recursive)
+            .map_err(ShaderPreprocessorError::Watch)?;
+
+        Ok(Self {
+            root,
+            receiver,
+            _watcher: watcher,
+        })
+    }
+
+    pub(super) fn root(&self) -> &PathBuf {
+        &self.root
+    }
+
+    /// Returns all `.wgsl` files created or modified since the last call.
+    /// Files may appear multiple times.
+    pub(super) fn take_changed_files(&self) -> Vec<PathBuf> {
+        let mut changed_files = Vec::new();
+
+        for result in self.receiver.try_iter() {
+            let event = match result {
+                Ok(event) => event,
+                Err(e) => {
+                    warn!("Watching shader folder {:?} failed: {e}", self.root);
+                    continue;
+                }
+            };
+
+            if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                continue;
+            }
+
+            changed_files.extend(
+                event
+                    .paths
+                    .into_iter()
+                    .filter(|path| path.extension().is_some_and(|x| x == "wgsl")),
+            );
+        }
+
+        changed_files
+    }
+}
@@ -0,0 +1,10 @@
+//! Small, dependency free helpers that don't belong to any other module.
+
+mod rng;
+pub use rng::*;
+
+mod half;
+pub use half::*;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,101 @@
+/// A small, deterministic random number generator (PCG32, XSH-RR variant).
+///
+/// Sequences only depend on the seed, thus are identical across platforms
+/// and runs.
+/// Useful for reproducible procedural placement or tests.  
+/// **Not** suitable for anything security related!
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Rng {
+    state: u64,
+    increment: u64,
+}
+
+impl Rng {
+    const MULTIPLIER: u64 = 6364136223846793005;
+    const DEFAULT_STREAM: u64 = 0xda3e39cb94b95bdb;
+
+    /// Creates a generator seeded with `seed`.
+    pub fn new(seed: u64) -> Self {
+        Self::with_stream(seed, Self::DEFAULT_STREAM)
+    }
+
+    /// Creates a generator seeded with `seed`, using the given `stream`.  
+    /// Generators with the same seed, but different streams, produce
+    /// independent sequences.
+    pub fn with_stream(seed: u64, stream: u64) -> Self {
+        let mut rng = Self {
+            state: 0,
+            increment: (stream << 1) | 1,
+        };
+        rng.next_u32();
+        rng.state = rng.state.wrapping_add(seed);
+        rng.next_u32();
+
+        rng
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        let old_state = self.state;
+        self.state = old_state
+            .wrapping_mul(Self::MULTIPLIER)
+            .wrapping_add(self.increment);
+
+        let xor_shifted = (((old_state >> 18) ^ old_state) >> 27) as u32;
+        let rotation = (old_state >> 59) as u32;
+        xor_shifted.rotate_right(rotation)
+    }
+
+    /// Returns a value in `[0.0, 1.0)`.
+    pub fn next_f32_01(&mut self) -> f32 {
+        // 24 bits fit the mantissa exactly, avoiding rounding up to 1.0
+        (self.next_u32() >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    /// Returns a value in `[min, max)`.  
+    /// If the range is empty, i.e. `max <= min`, `min` is returned.
+    pub fn range(&mut self, min: i32, max: i32) -> i32 {
+        if max <= min {
+            return min;
+        }
+
+        let span = (max as i64 - min as i64) as u32;
+        (min as i64 + self.bounded(span) as i64) as i32
+    }
+
+    /// Returns a value in `[min, max)`.  
+    /// If the range is empty, i.e. `max <= min`, `min` is returned.
+    pub fn range_f32(&mut self, min: f32, max: f32) -> f32 {
+        if max <= min {
+            return min;
+        }
+
+        let value = min + (max - min) * self.next_f32_01();
+        // Rounding may end up exactly on `max` for large ranges
+        if value < max {
+            value
+        } else {
+            min
+        }
+    }
+
+    /// Shuffles `items` in place (Fisher-Yates).
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.bounded(i as u32 + 1) as usize;
+            items.swap(i, j);
+        }
+    }
+
+    /// Returns an unbiased value in `[0, bound)`.
+    /// `bound` must not be zero.
+    fn bounded(&mut self, bound: u32) -> u32 {
+        // Values below the threshold would favour lower results
+        let threshold = bound.wrapping_neg() % bound;
+        loop {
+            let value = self.next_u32();
+            if value >= threshold {
+                return value % bound;
+            }
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use super::{f32_to_f16, Rng};
+use crate::renderer::f16_to_f32;
+
+#[test]
+fn rng_reference_sequence() {
+    // Output of the PCG32 reference implementation for this seed and stream
+    let mut rng = Rng::with_stream(42, 54);
+    let sequence: Vec<u32> = (0..6).map(|_| rng.next_u32()).collect();
+
+    assert_eq!(
+        sequence,
+        [0xa15c02b7, 0x7b47f409, 0xba1d3330, 0x83d2f293, 0xbfa4784b, 0xcbed606e]
+    );
+}
+
+#[test]
+fn rng_seeded_sequence() {
+    let mut rng = Rng::new(0x0123456789abcdef);
+    let sequence: Vec<u32> = (0..4).map(|_| rng.next_u32()).collect();
+    assert_eq!(sequence, [0x683ae4b0, 0x465b94e2, 0x8e78504b, 0x716c5c5d]);
+
+    let mut a = Rng::new(7);
+    let mut b = Rng::new(7);
+    let mut c = Rng::new(8);
+    let a: Vec<u32> = (0..16).map(|_| a.next_u32()).collect();
+    let b: Vec<u32> = (0..16).map(|_| b.next_u32()).collect();
+    let c: Vec<u32> = (0..16).map(|_| c.next_u32()).collect();
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+}
+
+#[test]
+fn rng_range_bounds() {
+    let mut rng = Rng::new(1);
+
+    let mut seen = [false; 4];
+    for _ in 0..1000 {
+        let value = rng.range(-2, 2);
+        assert!((-2..2).contains(&value));
+        seen[(value + 2) as usize] = true;
+    }
+    // Min is inclusive, max exclusive
+    assert!(seen.iter().all(|x| *x));
+
+    for _ in 0..1000 {
+        let value = rng.range(i32::MIN, i32::MAX);
+        assert!(value < i32::MAX);
+
+        let value = rng.range_f32(-1.0, 1.0);
+        assert!((-1.0..1.0).contains(&value));
+
+        let value = rng.next_f32_01();
+        assert!((0.0..1.0).contains(&value));
+    }
+
+    assert_eq!(rng.range(5, 5), 5);
+    assert_eq!(rng.range(5, 3), 5);
+    assert_eq!(rng.range_f32(2.0, 2.0), 2.0);
+}
+
+#[test]
+fn rng_shuffle() {
+    let mut items: Vec<u32> = (0..32).collect();
+    Rng::new(3).shuffle(&mut items);
+
+    let mut again: Vec<u32> = (0..32).collect();
+    Rng::new(3).shuffle(&mut again);
+    assert_eq!(items, again);
+    assert_ne!(items, (0..32).collect::<Vec<_>>());
+
+    items.sort();
+    assert_eq!(items, (0..32).collect::<Vec<_>>());
+
+    // Nothing to shuffle
+    Rng::new(3).shuffle::<u32>(&mut []);
+}
+
+#[test]
+fn f32_to_f16_conversion() {
+    assert_eq!(f32_to_f16(1.0), 0x3c00);
+    assert_eq!(f32_to_f16(0.5), 0x3800);
+    assert_eq!(f32_to_f16(-2.0), 0xc000);
+    assert_eq!(f32_to_f16(0.0), 0x0000);
+    assert_eq!(f32_to_f16(-0.0), 0x8000);
+
+    // Largest finite value and overflow
+    assert_eq!(f32_to_f16(65504.0), 0x7bff);
+    assert_eq!(f32_to_f16(1e6), 0x7c00);
+    assert_eq!(f32_to_f16(f32::NEG_INFINITY), 0xfc00);
+    assert!(f16_to_f32(f32_to_f16(f32::NAN)).is_nan());
+
+    // Smallest subnormal and underflow
+    assert_eq!(f32_to_f16(2f32.powi(-24)), 0x0001);
+    assert_eq!(f32_to_f16(2f32.powi(-26)), 0x0000);
+
+    // Rounds to the nearest representable value
+    for value in [0.1, 3.3, 1000.5, -42.25, 6e-5] {
+        let converted = f16_to_f32(f32_to_f16(value));
+        assert!(
+            (converted - value).abs() <= value.abs() / 1024.0,
+            "{value} != {converted}"
+        );
+    }
+}
@@ -70,9 +70,9 @@ use wgpu::{
     Queue, RequestAdapterOptions,
 };
 
-use crate::logging;
+use crate::{app::GpuConnector, logging};
 
-pub async fn make_wgpu_connection_async() -> (Adapter, Device, Queue) {
+pub async fn make_wgpu_adapter_async() -> (Instance, Adapter) {
     logging::test_init();
 
     debug!("{:#^88}", " WGPU Test Adapter ");
@@ -110,6 +110,16 @@ pub async fn make_wgpu_connection_async() -> (Adapter, Device, Queue) {
         format!("Driver Info: {}", adapter.get_info().driver_info)
     );
 
+    (instance, adapter)
+}
+
+pub fn make_wgpu_adapter() -> (Instance, Adapter) {
+    block_on(async { make_wgpu_adapter_async().await })
+}
+
+pub async fn make_wgpu_connector_async() -> GpuConnector {
+    let (instance, adapter) = make_wgpu_adapter_async().await;
+
     let (device, queue) = adapter
         .request_device(&DeviceDescriptor {
             ..Default::default()
@@ -121,7 +131,21 @@ pub async fn make_wgpu_connection_async() -> (Adapter, Device, Queue) {
 
     debug!("{:#^88}", "");
 
-    (adapter, device, queue)
+    GpuConnector::from_parts(instance, adapter, device, queue)
+}
+
+pub fn make_wgpu_connector() -> GpuConnector {
+    block_on(async { make_wgpu_connector_async().await })
+}
+
+pub async fn make_wgpu_connection_async() -> (Adapter, Device, Queue) {
+    let connector = make_wgpu_connector_async().await;
+
+    (
+        connector.adapter().clone(),
+        connector.device().clone(),
+        connector.queue().clone(),
+    )
 }
 
 pub fn make_wgpu_connection() -> (Adapter, Device, Queue) {
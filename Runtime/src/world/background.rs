@@ -0,0 +1,42 @@
+use wgpu::Color;
+
+use crate::resources::WorldEnvironmentDescriptor;
+
+/// What is drawn behind all models.  
+/// Changed at runtime via
+/// [WorldEvent::SetBackground](crate::element::WorldEvent::SetBackground).
+#[derive(Debug, Clone, PartialEq)]
+pub enum Background {
+    /// Clears the screen with a solid color.  
+    /// The world environment is kept and still lights the scene, only its
+    /// sky box isn't drawn anymore.
+    Color(Color),
+    /// Draws the sky box of the world environment.  
+    /// If a descriptor is given, the world environment gets changed to it,
+    /// like with [EnvironmentEvent::Change].
+    /// Otherwise, the current world environment is used, e.g. when
+    /// switching back from [Background::Color].
+    ///
+    /// Until a world environment is available, nothing is drawn behind the
+    /// models.
+    ///
+    /// [EnvironmentEvent::Change]: crate::element::EnvironmentEvent::Change
+    Skybox(Option<WorldEnvironmentDescriptor>),
+}
+
+impl Background {
+    /// The color to clear the screen with, if any.
+    /// `None` means the sky box is drawn instead.
+    pub fn clear_color(&self) -> Option<Color> {
+        match self {
+            Background::Color(color) => Some(*color),
+            Background::Skybox(_) => None,
+        }
+    }
+}
+
+impl Default for Background {
+    fn default() -> Self {
+        Self::Skybox(None)
+    }
+}
@@ -0,0 +1,74 @@
+use std::time::{Duration, Instant};
+
+/// Defines how often the [World](super::World) runs cache cleanup across
+/// all of its stores.
+///
+/// Cleanup only evicts resources that are no longer in use (see
+/// [Cache::cleanup](crate::cache::Cache::cleanup)), thus running it more
+/// often only costs the time of the check itself.
+/// Keep the cadence at or below the cache timeout, otherwise unused
+/// resources will linger for longer than the timeout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheCleanupCadence {
+    /// Runs cleanup every N frames (i.e. [World::update](super::World::update) calls).
+    Frames(u64),
+    /// Runs cleanup whenever the given time has passed since the last cleanup.
+    Interval(Duration),
+}
+
+impl Default for CacheCleanupCadence {
+    fn default() -> Self {
+        Self::Interval(Duration::from_secs(5))
+    }
+}
+
+/// Keeps track of when the next cache cleanup is due.
+#[derive(Debug)]
+pub struct CacheCleanupSchedule {
+    cadence: CacheCleanupCadence,
+    frames_since_cleanup: u64,
+    last_cleanup: Instant,
+}
+
+impl CacheCleanupSchedule {
+    pub fn new(cadence: CacheCleanupCadence) -> Self {
+        Self {
+            cadence,
+            frames_since_cleanup: 0,
+            last_cleanup: Instant::now(),
+        }
+    }
+
+    pub fn cadence(&self) -> CacheCleanupCadence {
+        self.cadence
+    }
+
+    pub fn set_cadence(&mut self, cadence: CacheCleanupCadence) {
+        self.cadence = cadence;
+    }
+
+    /// Advances the schedule by one frame.  
+    /// Returns `true` if cleanup is due, in which case the schedule is
+    /// reset.
+    pub fn tick(&mut self) -> bool {
+        self.frames_since_cleanup += 1;
+
+        let due = match self.cadence {
+            CacheCleanupCadence::Frames(frames) => self.frames_since_cleanup >= frames,
+            CacheCleanupCadence::Interval(interval) => self.last_cleanup.elapsed() >= interval,
+        };
+
+        if due {
+            self.frames_since_cleanup = 0;
+            self.last_cleanup = Instant::now();
+        }
+
+        due
+    }
+}
+
+impl Default for CacheCleanupSchedule {
+    fn default() -> Self {
+        Self::new(CacheCleanupCadence::default())
+    }
+}
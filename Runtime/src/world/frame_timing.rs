@@ -0,0 +1,149 @@
+use std::collections::VecDeque;
+
+use hashbrown::HashMap;
+
+use crate::element::{Message, Origin, Target, Variant};
+
+/// Keeps track of the frame times over a rolling window of frames.
+#[derive(Debug)]
+pub struct FrameTiming {
+    window: usize,
+    frame_times: VecDeque<f64>,
+}
+
+impl FrameTiming {
+    pub const DEFAULT_WINDOW: usize = 120;
+
+    /// Creates a new accumulator averaging over the last `window` frames.
+    pub fn new(window: usize) -> Self {
+        Self {
+            window: window.max(1),
+            frame_times: VecDeque::new(),
+        }
+    }
+
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Changes the amount of frames the stats are calculated over.
+    /// Older frames exceeding the new window are dropped.
+    pub fn set_window(&mut self, window: usize) {
+        self.window = window.max(1);
+        while self.frame_times.len() > self.window {
+            self.frame_times.pop_front();
+        }
+    }
+
+    /// Records the time a frame took in seconds.
+    /// Frames without any time passing, e.g. while the app is paused, are
+    /// ignored.
+    pub fn record(&mut self, frame_time: f64) {
+        if frame_time <= 0.0 {
+            return;
+        }
+
+        if self.frame_times.len() == self.window {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(frame_time);
+    }
+
+    /// Returns the stats of the current window.
+    /// `None` until a frame got recorded.
+    pub fn stats(&self) -> Option<FrameStats> {
+        let frame_time = *self.frame_times.back()?;
+        let average_frame_time =
+            self.frame_times.iter().sum::<f64>() / self.frame_times.len() as f64;
+
+        Some(FrameStats {
+            fps: 1.0 / frame_time,
+            frame_time,
+            average_fps: 1.0 / average_frame_time,
+            average_frame_time,
+            min_frame_time: self.frame_times.iter().copied().fold(f64::MAX, f64::min),
+            max_frame_time: self.frame_times.iter().copied().fold(0.0, f64::max),
+        })
+    }
+}
+
+impl Default for FrameTiming {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_WINDOW)
+    }
+}
+
+/// Frame timing stats, sent in reply to
+/// [WorldEvent::QueryFrameStats](crate::element::WorldEvent::QueryFrameStats).
+/// All times are in seconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameStats {
+    /// FPS based on the last frame only.
+    pub fps: f64,
+    /// Time the last frame took.
+    pub frame_time: f64,
+    /// FPS based on the average frame time of the window.
+    pub average_fps: f64,
+    pub average_frame_time: f64,
+    /// Fastest frame within the window.
+    pub min_frame_time: f64,
+    /// Slowest frame within the window.
+    pub max_frame_time: f64,
+}
+
+impl FrameStats {
+    /// Content key identifying a [Message] as [FrameStats].
+    pub const MESSAGE_KEY: &'static str = "frame_stats";
+
+    const FIELDS: [&'static str; 6] = [
+        "fps",
+        "frame_time",
+        "average_fps",
+        "average_frame_time",
+        "min_frame_time",
+        "max_frame_time",
+    ];
+
+    /// Creates a message containing these stats, sent by the app.
+    pub fn to_message(&self, to: Target) -> Message {
+        let stats = HashMap::from_iter(
+            Self::FIELDS
+                .into_iter()
+                .zip(self.values())
+                .map(|(key, value)| (key.to_string(), Variant::F64(value))),
+        );
+
+        Message::new(Origin::App, to)
+            .add_content(Self::MESSAGE_KEY.to_string(), Variant::Map(stats))
+    }
+
+    /// Reads the stats of a message created by [Self::to_message].
+    /// Returns `None` for any other message.
+    pub fn from_message(message: &Message) -> Option<Self> {
+        let stats = message.get(Self::MESSAGE_KEY)?.as_map()?;
+        let f64_of = |key: &str| match stats.get(key)? {
+            Variant::F64(x) => Some(*x),
+            _ => None,
+        };
+
+        Some(Self {
+            fps: f64_of("fps")?,
+            frame_time: f64_of("frame_time")?,
+            average_fps: f64_of("average_fps")?,
+            average_frame_time: f64_of("average_frame_time")?,
+            min_frame_time: f64_of("min_frame_time")?,
+            max_frame_time: f64_of("max_frame_time")?,
+        })
+    }
+
+    fn values(&self) -> [f64; 6] {
+        [
+            self.fps,
+            self.frame_time,
+            self.average_fps,
+            self.average_frame_time,
+            self.min_frame_time,
+            self.max_frame_time,
+        ]
+    }
+}
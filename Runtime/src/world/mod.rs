@@ -6,7 +6,7 @@
 //! ## Key Components
 //!
 //! - **World**: The main struct that manages all resources and handles world events
-//! - **Stores**: ModelStore, CameraStore, EnvironmentStore, and LightStore for managing resources
+//! - **Stores**: ModelStore, CameraStore, EnvironmentStore, LightStore, and AnimationStore for managing resources
 //! - **Importer**: Handles asynchronous asset loading and processing
 //! - **World Bind Group**: A global bind group containing shared resources for shaders
 //!
@@ -17,14 +17,16 @@
 //! resources shared across all draw calls, such as camera data, lighting information,
 //! and IBL (Image-Based Lighting) textures.
 
-use std::sync::OnceLock;
-use std::time::{Duration, Instant};
-
-use crate::element::{CameraEvent, ModelEvent, WorldEvent};
-use crate::importer::Importer;
+use crate::audio::{AudioBackend, NoAudioBackend, SoundRequest};
+use crate::element::{
+    AnimationEvent, CameraEvent, CameraState, LightEvent, Message, ModelEvent, Target, WorldEvent,
+};
+use crate::importer::{ImportError, ImportProgress, ImportWarning, Importer};
 use crate::resources::{Camera, CameraDescriptor, IblBrdf, Model, Texture, WorldEnvironment};
+#[cfg(feature = "hot_reload")]
+use crate::shader_preprocessor::ShaderPreprocessor;
 use cgmath::Vector2;
-use log::debug;
+use log::{debug, warn};
 use wgpu::{
     BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
     BindGroupLayoutEntry, BindingResource, BindingType, Buffer, BufferBindingType, Device, Queue,
@@ -35,6 +37,18 @@ use wgpu::{
 mod store;
 pub use store::*;
 
+mod cleanup;
+pub use cleanup::*;
+
+mod background;
+pub use background::*;
+
+mod frame_timing;
+pub use frame_timing::*;
+
+#[cfg(test)]
+mod tests;
+
 /// The main world state manager that handles all resources and their lifecycle.
 ///
 /// The World struct maintains stores for different types of resources (models, cameras,
@@ -45,9 +59,24 @@ pub struct World {
     camera_store: CameraStore,
     environment_store: EnvironmentStore,
     light_store: LightStore,
-    last_cleanup: Instant,
+    animation_store: AnimationStore,
+    cleanup_schedule: CacheCleanupSchedule,
     importer: Option<Importer>,
     ibl_brdf: Option<Texture>,
+    fallbacks: Option<WorldFallbacks>,
+    audio_backend: Box<dyn AudioBackend>,
+    /// Whether models outside of the active camera's
+    /// [Frustum](crate::resources::Frustum) are skipped
+    /// when rendering.
+    frustum_culling: bool,
+    /// Warnings of finished imports, until taken.
+    import_warnings: Vec<ImportWarning>,
+    /// Errors of finished imports, until taken.
+    import_errors: Vec<ImportError>,
+    /// Messages to be delivered to elements, e.g. replies to queries.
+    queue_messages: Vec<Message>,
+    background: Background,
+    frame_timing: FrameTiming,
     /// The _Engine_ [`BindGroup`].
     /// > This may also be called _World_ [`BindGroup`]!
     ///
@@ -55,6 +84,44 @@ pub struct World {
     world_bind_group: Option<BindGroup>,
 }
 
+/// Resources bound in place of missing lights, environment or camera.
+struct WorldFallbacks {
+    light_buffer: Buffer,
+    ibl_diffuse: Texture,
+    ibl_specular: Texture,
+    camera: Camera,
+}
+
+impl WorldFallbacks {
+    fn new(device: &Device, queue: &Queue) -> Self {
+        Self {
+            light_buffer: device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Fallback Light Buffer"),
+                size: (LIGHT_BUFFER_HEADER_SIZE + LIGHT_BUFFER_ENTRY_SIZE) as u64,
+                usage: wgpu::BufferUsages::STORAGE,
+                mapped_at_creation: false,
+            }),
+            ibl_diffuse: Texture::create_empty_cube_texture(
+                Some("default IBL diffuse"),
+                Vector2::new(1, 1),
+                TextureFormat::R8Unorm,
+                TextureUsages::TEXTURE_BINDING,
+                1,
+                device,
+            ),
+            ibl_specular: Texture::create_empty_cube_texture(
+                Some("default IBL specular"),
+                Vector2::new(1, 1),
+                TextureFormat::R8Unorm,
+                TextureUsages::TEXTURE_BINDING,
+                1,
+                device,
+            ),
+            camera: Camera::from_descriptor(CameraDescriptor::default(), device, queue),
+        }
+    }
+}
+
 impl Default for World {
     fn default() -> Self {
         Self::new()
@@ -149,10 +216,19 @@ impl World {
             camera_store: CameraStore::new(),
             environment_store: EnvironmentStore::new(),
             light_store: LightStore::new(),
-            last_cleanup: Instant::now(),
+            animation_store: AnimationStore::new(),
+            cleanup_schedule: CacheCleanupSchedule::default(),
             importer: Some(Importer::new(4)),
             world_bind_group: None,
             ibl_brdf: None,
+            fallbacks: None,
+            audio_backend: Box::new(NoAudioBackend),
+            frustum_culling: true,
+            import_warnings: Vec::new(),
+            import_errors: Vec::new(),
+            queue_messages: Vec::new(),
+            background: Background::default(),
+            frame_timing: FrameTiming::default(),
         }
     }
 
@@ -172,6 +248,14 @@ impl World {
         &mut self.camera_store
     }
 
+    pub fn light_store(&self) -> &LightStore {
+        &self.light_store
+    }
+
+    pub fn light_store_mut(&mut self) -> &mut LightStore {
+        &mut self.light_store
+    }
+
     pub fn environment_store(&self) -> &EnvironmentStore {
         &self.environment_store
     }
@@ -180,7 +264,17 @@ impl World {
         &mut self.environment_store
     }
 
-    pub async fn update(&mut self, world_events: Vec<WorldEvent>) {
+    pub fn animation_store(&self) -> &AnimationStore {
+        &self.animation_store
+    }
+
+    pub fn animation_store_mut(&mut self) -> &mut AnimationStore {
+        &mut self.animation_store
+    }
+
+    /// Processes `world_events`, spawns finished imports and advances
+    /// playing animations by `delta_time` seconds.
+    pub async fn update(&mut self, world_events: Vec<WorldEvent>, delta_time: f64) {
         // Process through other world events
         for world_event in world_events {
             self.process_event(world_event);
@@ -194,29 +288,169 @@ impl World {
         self.importer = Some(importer);
 
         for importer_result in importer_results {
-            for model in importer_result.models {
-                self.process_event(WorldEvent::Model(ModelEvent::Spawn(model)));
-            }
+            self.process_event(WorldEvent::Model(ModelEvent::SpawnBatch(
+                importer_result.models,
+            )));
             for camera in importer_result.cameras {
                 self.process_event(WorldEvent::Camera(CameraEvent::Spawn(camera)));
             }
+            for light in importer_result.lights {
+                self.process_event(WorldEvent::Light(LightEvent::Spawn(light)));
+            }
+            for animation in importer_result.animations {
+                self.process_event(WorldEvent::Animation(AnimationEvent::Spawn(animation)));
+            }
+            self.import_warnings.extend(importer_result.warnings);
+            self.import_errors.extend(importer_result.errors);
+        }
+
+        self.advance_animations(delta_time);
+
+        if self.cleanup_schedule.tick() {
+            self.cleanup();
+        }
+    }
+
+    /// What is currently drawn behind all models.  
+    /// Any world environment passed through [WorldEvent::SetBackground] is
+    /// already handed to the [EnvironmentStore], thus not part of this.
+    pub fn background(&self) -> &Background {
+        &self.background
+    }
+
+    /// Records the time the current frame took, see [FrameTiming::record].
+    /// Expects the actual, unclamped frame time rather than the delta time
+    /// used for updating.
+    pub fn record_frame(&mut self, frame_time: f64) {
+        self.frame_timing.record(frame_time);
+    }
+
+    /// Frame timing stats of the recent frames.
+    /// Elements can query them via [WorldEvent::QueryFrameStats].
+    pub fn frame_stats(&self) -> Option<FrameStats> {
+        self.frame_timing.stats()
+    }
+
+    pub fn frame_timing_mut(&mut self) -> &mut FrameTiming {
+        &mut self.frame_timing
+    }
+
+    /// Progress of the imports registered through [WorldEvent::Import].
+    pub fn import_progress(&self) -> ImportProgress {
+        self.importer
+            .as_ref()
+            .map(Importer::progress)
+            .unwrap_or_default()
+    }
+
+    /// Takes the warnings of all imports finished since the last call.
+    /// Imports with warnings still spawn anything that could be imported.
+    pub fn take_import_warnings(&mut self) -> Vec<ImportWarning> {
+        std::mem::take(&mut self.import_warnings)
+    }
+
+    /// Takes the errors of all imports finished since the last call.
+    /// Anything of a failed import that could be imported is still spawned.
+    pub fn take_import_errors(&mut self) -> Vec<ImportError> {
+        std::mem::take(&mut self.import_errors)
+    }
+
+    /// Advances all playing animations by `delta_time` seconds and applies
+    /// them to the [Model]s they target.
+    pub fn advance_animations(&mut self, delta_time: f64) {
+        for (animation, time) in self.animation_store.advance(delta_time) {
+            self.model_store.apply_animation(animation, time);
         }
+    }
+
+    /// Takes the messages the world wants to send to elements, e.g. replies
+    /// to [CameraEvent::Query].
+    /// Apps are expected to queue them for delivery.
+    pub fn take_messages(&mut self) -> Vec<Message> {
+        std::mem::take(&mut self.queue_messages)
+    }
+
+    /// Runs cache cleanup on all stores.  
+    /// Usually called automatically based on the [CacheCleanupCadence].
+    ///
+    /// Realizations of cameras, lights and models are only referenced by
+    /// their store, thus they are dropped once their descriptor is gone,
+    /// regardless of the cache timeout.
+    pub fn cleanup(&mut self) {
+        self.model_store
+            .cleanup()
+            .expect("ModelStore cleanup failure");
+        self.camera_store.cleanup();
+        self.light_store.cleanup();
+    }
+
+    /// Drops all GPU resources and queues them for recreation with the
+    /// next [World::prepare_render].
+    /// Required once the [Device] changes, e.g. after it got lost.
+    pub fn invalidate_realizations(&mut self) {
+        self.model_store
+            .invalidate_realizations()
+            .expect("ModelStore invalidation failure");
+        self.camera_store.invalidate_realizations();
+        self.environment_store.invalidate_realizations();
+        self.light_store.invalidate_realizations();
 
-        // Needs to be at most the same as the cache timeout time!
-        // Otherwise, cache cleanup will never be efficient.
-        if self.last_cleanup.elapsed() >= Duration::from_secs(5) {
-            self.model_store
-                .cleanup()
-                .expect("ModelStore cleanup failure");
-            self.camera_store.cleanup();
+        self.world_bind_group = None;
+        self.ibl_brdf = None;
+        self.fallbacks = None;
+    }
+
+    pub fn cache_cleanup_cadence(&self) -> CacheCleanupCadence {
+        self.cleanup_schedule.cadence()
+    }
+
+    /// Changes how often cache cleanup runs.
+    pub fn set_cache_cleanup_cadence(&mut self, cadence: CacheCleanupCadence) {
+        self.cleanup_schedule.set_cadence(cadence);
+    }
+
+    pub fn frustum_culling(&self) -> bool {
+        self.frustum_culling
+    }
+
+    /// Enables or disables frustum culling.  
+    /// Enabled by default. Disabling it renders all models, regardless of
+    /// whether they are visible, which can be useful for debugging.
+    pub fn set_frustum_culling(&mut self, enabled: bool) {
+        self.frustum_culling = enabled;
+    }
+
+    /// Replaces the [AudioBackend] handling [WorldEvent::PlaySound].
+    pub fn set_audio_backend(&mut self, audio_backend: Box<dyn AudioBackend>) {
+        self.audio_backend = audio_backend;
+    }
 
-            self.last_cleanup = Instant::now();
+    /// Informs the [World] about the current surface size.  
+    /// Cameras with [CameraDescriptor::auto_aspect] enabled adopt its
+    /// aspect ratio.
+    pub fn set_surface_size(&mut self, size: Vector2<u32>) {
+        if size.x == 0 || size.y == 0 {
+            return;
         }
+
+        self.camera_store
+            .set_surface_aspect(size.x as f32 / size.y as f32);
     }
 
     fn recreate_bind_group(&mut self, device: &Device, queue: &Queue) {
         // Create light buffer first to avoid borrowing issues
-        self.light_store.create_light_buffer(device, queue);
+        let viewer = self
+            .camera_store
+            .active_camera_descriptor()
+            .map(|camera| camera.position);
+        self.light_store.create_light_buffer(device, viewer);
+
+        // Fallbacks are owned by the world, as they are bound to the device
+        // they have been created on.
+        if self.fallbacks.is_none() {
+            self.fallbacks = Some(WorldFallbacks::new(device, queue));
+        }
+        let fallbacks = self.fallbacks.as_ref().unwrap();
 
         // Get the light buffer binding first to avoid borrowing conflicts
         let light_buffer_binding = {
@@ -224,17 +458,8 @@ impl World {
             light_buffer
                 .map(|buffer| buffer.as_entire_buffer_binding())
                 .unwrap_or_else(|| {
-                    // Create a dummy buffer binding if no lights
-                    static FALLBACK_ONCE: OnceLock<Buffer> = OnceLock::new();
-                    let fallback = FALLBACK_ONCE.get_or_init(|| {
-                        device.create_buffer(&wgpu::BufferDescriptor {
-                            label: Some("Fallback Light Buffer"),
-                            size: 4,
-                            usage: wgpu::BufferUsages::STORAGE,
-                            mapped_at_creation: false,
-                        })
-                    });
-                    fallback.as_entire_buffer_binding()
+                    // Use a dummy buffer binding if no lights
+                    fallbacks.light_buffer.as_entire_buffer_binding()
                 })
         };
 
@@ -258,34 +483,11 @@ impl World {
             ),
             None => {
                 debug!("Attempting to recreate World BindGroup without an active WorldEnvironment! Using a default fallback.");
-                static FALLBACK_ONCE: OnceLock<(Texture, Texture)> = OnceLock::new();
-                let (fallback_ibl_diffuse, fallback_ibl_specular) =
-                    FALLBACK_ONCE.get_or_init(|| {
-                        (
-                            Texture::create_empty_cube_texture(
-                                Some("default IBL diffuse"),
-                                Vector2::new(1, 1),
-                                TextureFormat::R8Unorm,
-                                TextureUsages::TEXTURE_BINDING,
-                                1,
-                                device,
-                            ),
-                            Texture::create_empty_cube_texture(
-                                Some("default IBL specular"),
-                                Vector2::new(1, 1),
-                                TextureFormat::R8Unorm,
-                                TextureUsages::TEXTURE_BINDING,
-                                1,
-                                device,
-                            ),
-                        )
-                    });
-
                 (
-                    fallback_ibl_diffuse.view(),
-                    fallback_ibl_diffuse.sampler(),
-                    fallback_ibl_specular.view(),
-                    fallback_ibl_specular.sampler(),
+                    fallbacks.ibl_diffuse.view(),
+                    fallbacks.ibl_diffuse.sampler(),
+                    fallbacks.ibl_specular.view(),
+                    fallbacks.ibl_specular.sampler(),
                 )
             }
         };
@@ -294,11 +496,7 @@ impl World {
             Some(x) => x.camera_buffer().as_entire_buffer_binding(),
             None => {
                 debug!("Attempting to recreate World BindGroup without an active Camera! Using a default fallback.");
-                static FALLBACK_ONCE: OnceLock<Camera> = OnceLock::new();
-                let fallback = FALLBACK_ONCE.get_or_init(|| {
-                    Camera::from_descriptor(CameraDescriptor::default(), device, queue)
-                });
-                fallback.camera_buffer().as_entire_buffer_binding()
+                fallbacks.camera.camera_buffer().as_entire_buffer_binding()
             }
         };
 
@@ -348,21 +546,75 @@ impl World {
     pub fn process_event(&mut self, event: WorldEvent) {
         match event {
             WorldEvent::Model(model_event) => self.model_store.handle_event(model_event),
+            WorldEvent::Camera(CameraEvent::Query { label, requester }) => {
+                match self.camera_store.descriptor_by_label(&label) {
+                    Some(descriptor) => self.queue_messages.push(
+                        CameraState::from(descriptor).to_message(Target::Element {
+                            labels: vec![requester],
+                        }),
+                    ),
+                    None => warn!("Attempting to query Camera with label '{label}', but label cannot be found!"),
+                }
+            }
             WorldEvent::Camera(camera_event) => self.camera_store.handle_event(camera_event),
+            WorldEvent::QueryFrameStats { requester } => match self.frame_timing.stats() {
+                Some(stats) => self.queue_messages.push(stats.to_message(Target::Element {
+                    labels: vec![requester],
+                })),
+                None => {
+                    warn!("Frame stats queried by '{requester}', but no frame got recorded yet!")
+                }
+            },
+            WorldEvent::QueryElements { tag } => {
+                warn!("Elements tagged '{tag}' got queried outside of an Element, but only Elements can query other Elements!")
+            }
+            WorldEvent::ReplaceElement { label, .. } => {
+                warn!("Replacement of Element '{label}' reached the World, but only Elements can replace other Elements!")
+            }
             WorldEvent::Environment(environment_event) => {
                 self.environment_store.handle_event(environment_event);
             }
             WorldEvent::Light(light_event) => {
                 self.light_store.handle_event(light_event);
             }
+            WorldEvent::Animation(animation_event) => {
+                self.animation_store.handle_event(animation_event);
+            }
+            WorldEvent::SetBackground(background) => {
+                self.background = match background {
+                    Background::Skybox(Some(descriptor)) => {
+                        self.environment_store.queue_change(descriptor);
+                        Background::Skybox(None)
+                    }
+                    background => background,
+                };
+            }
             WorldEvent::Import(import_task) => {
                 self.importer.as_mut().unwrap().register_task(import_task);
             }
+            WorldEvent::PlaySound {
+                id,
+                volume,
+                position,
+            } => {
+                let listener = self
+                    .camera_store
+                    .active_camera_descriptor()
+                    .map(|camera| camera.position);
+
+                self.audio_backend.play_sound(SoundRequest {
+                    id,
+                    volume,
+                    position,
+                    listener,
+                });
+            }
             WorldEvent::Clear => {
                 self.model_store.clear().expect("ModelStore clear failure");
                 self.camera_store.clear();
                 self.environment_store.clear();
                 self.light_store.clear();
+                self.animation_store.clear();
             }
         }
     }
@@ -373,9 +625,16 @@ impl World {
         device: &Device,
         queue: &Queue,
     ) {
+        #[cfg(feature = "hot_reload")]
+        if ShaderPreprocessor::take_shared_dirty() {
+            let dropped = self.model_store.invalidate_changed_shaders();
+            debug!("Shader imports changed, recompiling {dropped} material(s)");
+        }
+
         self.model_store.process_bounding_boxes(device);
         self.model_store
             .realize_and_cache(surface_texture_format, device, queue);
+        self.fit_depth_range();
         self.camera_store.realize_and_cache(device, queue);
         if let Err(e) =
             self.environment_store
@@ -388,12 +647,42 @@ impl World {
         self.recreate_bind_group(device, queue);
     }
 
+    /// Fits the depth range of the active camera around all models, if it
+    /// has [CameraDescriptor::auto_depth_range] enabled.  
+    /// Needs processed bounding boxes, thus runs during
+    /// [Self::prepare_render].
+    fn fit_depth_range(&mut self) {
+        let auto_depth_range = self
+            .camera_store
+            .active_camera_descriptor()
+            .is_some_and(|camera| camera.auto_depth_range);
+        if !auto_depth_range {
+            return;
+        }
+
+        if let Some(scene_bounds) = self.model_store.scene_bounds() {
+            self.camera_store.fit_active_depth_range(&scene_bounds);
+        }
+    }
+
     pub fn retrieve_render_resources(
         &self,
     ) -> (Option<&BindGroup>, Option<&WorldEnvironment>, Vec<&Model>) {
-        // TODO: This effectively realizes all BoundingBoxes/Models, without checking if they are actually visible or not. A proper frustum check should be used to determine if the given models actually are visible or near the camera and thus should be rendered and activated.
+        // Without an active camera nothing can be culled
+        let frustum = match self.camera_store.active_camera_descriptor() {
+            Some(camera) if self.frustum_culling => Some(camera.frustum()),
+            _ => None,
+        };
+
         let bounding_boxes = self.model_store.get_bounding_boxes();
-        let ids = bounding_boxes.keys().copied().collect::<Vec<_>>();
+        let ids = bounding_boxes
+            .keys()
+            .copied()
+            .filter(|id| match &frustum {
+                Some(frustum) => self.model_store.is_in_frustum(*id, frustum),
+                None => true,
+            })
+            .collect::<Vec<_>>();
         let models = self.model_store.get_realizations(ids);
 
         (
@@ -0,0 +1,117 @@
+use hashbrown::HashMap;
+use log::warn;
+
+use crate::{element::AnimationEvent, resources::AnimationDescriptor};
+
+/// Playback state of a playing animation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Playback {
+    /// Seconds since the animation started.
+    time: f32,
+    looping: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct AnimationStore {
+    map_descriptors: HashMap<String, AnimationDescriptor>,
+    map_playback: HashMap<String, Playback>,
+}
+
+impl AnimationStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn descriptor_by_label(&self, label: &str) -> Option<&AnimationDescriptor> {
+        self.map_descriptors.get(label)
+    }
+
+    pub fn is_playing(&self, label: &str) -> bool {
+        self.map_playback.contains_key(label)
+    }
+
+    /// Seconds since the animation started, if it's playing.
+    /// Wraps around for looping animations.
+    pub fn playback_time(&self, label: &str) -> Option<f32> {
+        self.map_playback.get(label).map(|playback| playback.time)
+    }
+
+    /// Advances all playing animations by `delta_time` seconds.
+    /// Returns each animation that changed together with the time it has
+    /// to be evaluated at.
+    ///
+    /// Non-looping animations are returned a last time once they reach
+    /// their end, so the final pose gets applied, and stop afterwards.
+    pub fn advance(&mut self, delta_time: f64) -> Vec<(&AnimationDescriptor, f32)> {
+        let mut finished = Vec::new();
+        let mut advanced = Vec::new();
+
+        for (label, playback) in &mut self.map_playback {
+            let Some(descriptor) = self.map_descriptors.get(label) else {
+                finished.push(label.clone());
+                continue;
+            };
+
+            let duration = descriptor.duration();
+            playback.time += delta_time as f32;
+            if playback.time >= duration {
+                if playback.looping && duration > 0.0 {
+                    playback.time %= duration;
+                } else {
+                    playback.time = duration;
+                    finished.push(label.clone());
+                }
+            }
+
+            advanced.push((label.clone(), playback.time));
+        }
+
+        for label in finished {
+            self.map_playback.remove(&label);
+        }
+
+        advanced
+            .into_iter()
+            .filter_map(|(label, time)| Some((self.map_descriptors.get(&label)?, time)))
+            .collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.map_descriptors.clear();
+        self.map_playback.clear();
+    }
+
+    pub fn handle_event(&mut self, animation_event: AnimationEvent) {
+        match animation_event {
+            AnimationEvent::Spawn(descriptor) => {
+                self.map_playback.remove(&descriptor.label);
+                self.map_descriptors
+                    .insert(descriptor.label.clone(), descriptor);
+            }
+            AnimationEvent::Despawn(label) => {
+                self.map_playback.remove(&label);
+                if self.map_descriptors.remove(&label).is_none() {
+                    warn!("Attempting to despawn Animation with label '{label}', which cannot be found!");
+                }
+            }
+            AnimationEvent::Play { label, looping } => {
+                if !self.map_descriptors.contains_key(&label) {
+                    warn!(
+                        "Attempting to play Animation with label '{label}', which cannot be found!"
+                    );
+                    return;
+                }
+
+                self.map_playback
+                    .insert(label, Playback { time: 0.0, looping });
+            }
+            AnimationEvent::Stop(label) => {
+                if self.map_playback.remove(&label).is_none() {
+                    warn!(
+                        "Attempting to stop Animation with label '{label}', which isn't playing!"
+                    );
+                }
+            }
+        }
+    }
+}
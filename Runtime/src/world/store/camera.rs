@@ -9,7 +9,7 @@ use crate::{
     cache::{Cache, CacheEntry},
     element::CameraEvent,
     or::Or,
-    resources::{Camera, CameraDescriptor},
+    resources::{BoundingBoxDescriptor, Camera, CameraDescriptor},
 };
 
 use super::StoreError;
@@ -21,6 +21,7 @@ pub struct CameraStore {
     cache_realizations: Cache<Ulid, Camera>,
     queue_realizations: Vec<Ulid>,
     active_camera: Option<Ulid>,
+    surface_aspect: Option<f32>,
 }
 
 impl CameraStore {
@@ -28,9 +29,15 @@ impl CameraStore {
         Self::default()
     }
 
-    pub fn store(&mut self, descriptor: CameraDescriptor) {
+    pub fn store(&mut self, mut descriptor: CameraDescriptor) {
         let id = Ulid::new();
 
+        if descriptor.auto_aspect {
+            if let Some(surface_aspect) = self.surface_aspect {
+                descriptor.projection.set_aspect(surface_aspect);
+            }
+        }
+
         self.map_label.insert(descriptor.label.clone(), id);
         self.map_descriptors.insert(id, descriptor);
     }
@@ -69,6 +76,12 @@ impl CameraStore {
             .map(|descriptor| descriptor.label.as_str())
     }
 
+    /// Returns the [CameraDescriptor] for the given label, if it exists.
+    pub fn descriptor_by_label(&self, label: &str) -> Option<&CameraDescriptor> {
+        let id = self.label_to_id(label)?;
+        self.map_descriptors.get(&id)
+    }
+
     pub fn target_camera(&mut self, id: Ulid) {
         if !self.map_descriptors.contains_key(&id) {
             error!("Attempting to target a Camera with id #{id}, which doesn't exist!");
@@ -79,6 +92,65 @@ impl CameraStore {
         self.flag_realization(vec![id], true);
     }
 
+    /// The aspect ratio of the surface, if known.
+    pub fn surface_aspect(&self) -> Option<f32> {
+        self.surface_aspect
+    }
+
+    /// Sets the aspect ratio of the surface.  
+    /// Every camera with [CameraDescriptor::auto_aspect] enabled, including
+    /// ones spawned afterwards, adopts it.
+    /// Changed cameras are flagged for realization to update their
+    /// projection.
+    pub fn set_surface_aspect(&mut self, surface_aspect: f32) {
+        self.surface_aspect = Some(surface_aspect);
+
+        let mut changed = Vec::new();
+        for (id, descriptor) in self.map_descriptors.iter_mut() {
+            if descriptor.auto_aspect && descriptor.projection.aspect() != surface_aspect {
+                descriptor.projection.set_aspect(surface_aspect);
+                changed.push(*id);
+            }
+        }
+
+        self.flag_realization(changed, true);
+    }
+
+    /// Fits the depth range of the active camera around `scene_bounds`, if
+    /// it has [CameraDescriptor::auto_depth_range] enabled.  
+    /// The camera is flagged for realization if its depth range changed.
+    pub fn fit_active_depth_range(&mut self, scene_bounds: &BoundingBoxDescriptor) {
+        let Some(id) = self.active_camera else {
+            return;
+        };
+
+        let changed = match self.map_descriptors.get_mut(&id) {
+            Some(descriptor) if descriptor.auto_depth_range => {
+                descriptor.fit_depth_range(scene_bounds)
+            }
+            _ => false,
+        };
+
+        if changed {
+            self.flag_realization(vec![id], true);
+        }
+    }
+
+    /// Returns the id of the currently targeted camera, if any.
+    pub fn active_camera(&self) -> Option<Ulid> {
+        self.active_camera
+    }
+
+    /// Returns the [CameraDescriptor] of the currently targeted camera, if any.
+    pub fn active_camera_descriptor(&self) -> Option<&CameraDescriptor> {
+        self.map_descriptors.get(&self.active_camera?)
+    }
+
+    /// Clears the currently targeted camera.
+    pub fn untarget_camera(&mut self) {
+        self.active_camera = None;
+    }
+
     pub fn flag_realization(&mut self, ids: Vec<Ulid>, update_existing: bool) {
         for id in ids {
             if self.cache_realizations.contains_key(&id) && !update_existing {
@@ -139,8 +211,19 @@ impl CameraStore {
         }
     }
 
+    /// Drops any realization whose descriptor no longer exists.
     pub fn cleanup(&mut self) {
-        self.cache_realizations.cleanup();
+        self.cache_realizations
+            .retain(|id, _| self.map_descriptors.contains_key(id));
+    }
+
+    /// Drops all realizations, e.g. after the device got lost, and queues
+    /// every camera for realization with the next device.
+    pub fn invalidate_realizations(&mut self) {
+        self.cache_realizations.clear();
+
+        let ids = self.map_descriptors.keys().copied().collect();
+        self.flag_realization(ids, true);
     }
 
     pub fn clear(&mut self) {
@@ -187,6 +270,10 @@ impl CameraStore {
                     self.flag_realization(vec![id], true);
                 }
             },
+            CameraEvent::Query { label, .. } => {
+                // Replies are messages, which only the World can send
+                warn!("Camera query for '{label}' must be processed by the World, ignoring!");
+            },
         }
     }
 }
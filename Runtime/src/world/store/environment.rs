@@ -11,6 +11,9 @@ use crate::{
 #[derive(Debug, Default)]
 pub struct EnvironmentStore {
     world_environment: Option<WorldEnvironment>,
+    /// Descriptor of the current [WorldEnvironment].
+    /// Kept to recreate it, e.g. after the device got lost.
+    descriptor: Option<WorldEnvironmentDescriptor>,
     queued_descriptor: Option<WorldEnvironmentDescriptor>,
 }
 
@@ -44,6 +47,7 @@ impl EnvironmentStore {
             device,
             queue,
         )?);
+        self.descriptor = Some(descriptor);
 
         Ok(())
     }
@@ -52,8 +56,20 @@ impl EnvironmentStore {
         self.world_environment.as_ref()
     }
 
+    /// Drops the current [WorldEnvironment], e.g. after the device got lost,
+    /// and queues it for realization with the next device.
+    /// An already queued change takes precedence.
+    pub fn invalidate_realizations(&mut self) {
+        self.world_environment = None;
+
+        if self.queued_descriptor.is_none() {
+            self.queued_descriptor = self.descriptor.take();
+        }
+    }
+
     pub fn clear(&mut self) {
         self.world_environment = None;
+        self.descriptor = None;
     }
 
     pub fn handle_event(&mut self, environment_event: EnvironmentEvent) {
@@ -1,11 +1,12 @@
 use std::error::Error;
 
+use cgmath::{EuclideanSpace, MetricSpace, Point3};
 use hashbrown::HashMap;
 use log::warn;
 use ulid::Ulid;
 use wgpu::{
     util::{BufferInitDescriptor, DeviceExt},
-    Buffer, BufferDescriptor, BufferUsages, Device, Queue,
+    Buffer, BufferUsages, Device, Queue,
 };
 
 use crate::{
@@ -17,13 +18,42 @@ use crate::{
 
 use super::StoreError;
 
-#[derive(Debug, Default)]
+/// Default for [LightStore::max_lights].
+pub const DEFAULT_MAX_LIGHTS: usize = 64;
+
+/// Size of a single light inside the light buffer:
+/// position, color, direction and params, each a `vec4<f32>`.
+pub const LIGHT_BUFFER_ENTRY_SIZE: usize = 64;
+
+/// Size of the light buffer header: the light count (`u32`), padded to the
+/// alignment of the light array.
+pub const LIGHT_BUFFER_HEADER_SIZE: usize = 16;
+
+#[derive(Debug)]
 pub struct LightStore {
     map_label: HashMap<String, Ulid>,
     map_descriptors: HashMap<Ulid, LightDescriptor>,
     cache_realizations: Cache<Ulid, Light>,
     queue_realizations: Vec<Ulid>,
     light_buffer: Option<Buffer>,
+    max_lights: usize,
+    /// Amount of lights dropped when the light buffer was last created.
+    /// Used to only log once the amount changes.
+    dropped_lights: usize,
+}
+
+impl Default for LightStore {
+    fn default() -> Self {
+        Self {
+            map_label: HashMap::new(),
+            map_descriptors: HashMap::new(),
+            cache_realizations: Cache::default(),
+            queue_realizations: Vec::new(),
+            light_buffer: None,
+            max_lights: DEFAULT_MAX_LIGHTS,
+            dropped_lights: 0,
+        }
+    }
 }
 
 impl LightStore {
@@ -31,6 +61,54 @@ impl LightStore {
         Self::default()
     }
 
+    /// The maximum amount of lights passed to shaders.
+    pub fn max_lights(&self) -> usize {
+        self.max_lights
+    }
+
+    /// Sets the maximum amount of lights passed to shaders.  
+    /// If more lights are spawned, only the ones contributing the most
+    /// light to the viewer are used.
+    /// Check [LightStore::prioritized_lights] for details.
+    pub fn set_max_lights(&mut self, max_lights: usize) {
+        self.max_lights = max_lights;
+    }
+
+    /// Returns the lights ordered by how much they contribute to the scene,
+    /// limited to [LightStore::max_lights].  
+    /// The contribution is the light's brightness attenuated by its distance
+    /// to `viewer`. Directional lights aren't attenuated.
+    /// Without a `viewer`, lights are ordered by brightness alone.
+    pub fn prioritized_lights(&self, viewer: Option<Point3<f32>>) -> Vec<&LightDescriptor> {
+        let mut lights = self
+            .map_descriptors
+            .values()
+            .map(|descriptor| {
+                let attenuation = viewer
+                    .map(|viewer| {
+                        descriptor
+                            .attenuation(Point3::from_vec(descriptor.position).distance(viewer))
+                    })
+                    .unwrap_or(1.0);
+
+                (descriptor.brightness() * attenuation, descriptor)
+            })
+            .collect::<Vec<_>>();
+
+        // Label as tiebreaker to keep the order stable between frames
+        lights.sort_by(|(a_priority, a), (b_priority, b)| {
+            b_priority
+                .total_cmp(a_priority)
+                .then_with(|| a.label.cmp(&b.label))
+        });
+        lights.truncate(self.max_lights);
+
+        lights
+            .into_iter()
+            .map(|(_, descriptor)| descriptor)
+            .collect()
+    }
+
     pub fn store(&mut self, descriptor: LightDescriptor) {
         let id = Ulid::new();
 
@@ -132,8 +210,21 @@ impl LightStore {
             .collect::<Vec<_>>()
     }
 
+    /// Drops any realization whose descriptor no longer exists.
     pub fn cleanup(&mut self) {
-        self.cache_realizations.cleanup();
+        self.cache_realizations
+            .retain(|id, _| self.map_descriptors.contains_key(id));
+    }
+
+    /// Drops all realizations and the light buffer, e.g. after the device
+    /// got lost, and queues every light for realization with the next
+    /// device.
+    pub fn invalidate_realizations(&mut self) {
+        self.cache_realizations.clear();
+        self.light_buffer = None;
+
+        let ids = self.map_descriptors.keys().copied().collect();
+        self.flag_realization(ids, true);
     }
 
     pub fn clear(&mut self) {
@@ -142,40 +233,44 @@ impl LightStore {
         self.cache_realizations.clear();
     }
 
-    pub fn create_light_buffer(&mut self, device: &Device, queue: &Queue) {
-        // Create a buffer with all light data
-        let light_count = self.map_descriptors.len();
-        if light_count == 0 {
-            // Create an empty buffer with sufficient size to satisfy shader requirements
-            // Shader expects at least 64 bytes for the light buffer
-            self.light_buffer = Some(device.create_buffer(&BufferDescriptor {
-                label: Some("Light Storage Buffer"),
-                size: 64, // Minimum size that satisfies shader requirements
-                usage: BufferUsages::STORAGE | BufferUsages::COPY_DST,
-                mapped_at_creation: false,
-            }));
-            return;
+    /// (Re-)Creates the light buffer from all spawned lights.  
+    /// The buffer starts with the light count, followed by the light array.
+    /// If more than [LightStore::max_lights] lights exist, the ones
+    /// contributing the least light to `viewer` are left out.
+    pub fn create_light_buffer(&mut self, device: &Device, viewer: Option<Point3<f32>>) {
+        let lights = self.prioritized_lights(viewer);
+        let light_count = lights.len();
+
+        let mut buffer_data = Vec::with_capacity(
+            LIGHT_BUFFER_HEADER_SIZE + light_count.max(1) * LIGHT_BUFFER_ENTRY_SIZE,
+        );
+        buffer_data.extend_from_slice(&(light_count as u32).to_le_bytes());
+        buffer_data.resize(LIGHT_BUFFER_HEADER_SIZE, 0);
+        for descriptor in lights {
+            buffer_data.extend_from_slice(&descriptor.to_buffer_data());
         }
 
-        // Calculate buffer size: Each light needs 64 bytes (position: 16, color: 16, direction: 16, params: 16)
-        let light_size = 64;
-        let buffer_size = (light_count * light_size) as u64;
-
-        // Create buffer data
-        let mut buffer_data = Vec::new();
-        for descriptor in self.map_descriptors.values() {
-            buffer_data.extend_from_slice(&descriptor.to_buffer_data());
+        if light_count == 0 {
+            // Shaders expect the light array to contain at least one entry
+            buffer_data.resize(LIGHT_BUFFER_HEADER_SIZE + LIGHT_BUFFER_ENTRY_SIZE, 0);
         }
 
-        // Pad buffer data to multiple of 4 bytes if needed
-        while buffer_data.len() % 4 != 0 {
-            buffer_data.push(0);
+        let dropped_lights = self.map_descriptors.len() - light_count;
+        if dropped_lights != self.dropped_lights {
+            if dropped_lights > 0 {
+                warn!(
+                    "{} lights exist, but only {} are supported! Dropping the {dropped_lights} least contributing lights.",
+                    self.map_descriptors.len(),
+                    self.max_lights
+                );
+            }
+            self.dropped_lights = dropped_lights;
         }
 
         let light_buffer = device.create_buffer_init(&BufferInitDescriptor {
             label: Some("Light Storage Buffer"),
             contents: &buffer_data,
-            usage: BufferUsages::STORAGE | BufferUsages::COPY_DST,
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_DST | BufferUsages::COPY_SRC,
         });
 
         self.light_buffer = Some(light_buffer);
@@ -10,5 +10,8 @@ pub use environment::*;
 mod light;
 pub use light::*;
 
+mod animation;
+pub use animation::*;
+
 mod error;
 pub use error::*;
@@ -1,6 +1,7 @@
 use std::{
     error::Error,
     sync::{Arc, RwLock},
+    time::Duration,
 };
 
 use hashbrown::HashMap;
@@ -12,12 +13,12 @@ use wgpu::{Device, Queue, TextureFormat};
 mod tests;
 
 use crate::{
-    cache::{Cache, CacheEntry},
+    cache::{Cache, CacheEntry, CacheStats},
     element::ModelEvent,
     or::Or,
     resources::{
-        BoundingBox, MaterialShader, MaterialShaderDescriptor, Mesh, MeshDescriptor, Model,
-        ModelDescriptor,
+        AnimationDescriptor, BoundingBox, BoundingBoxDescriptor, Frustum, Instance, MaterialShader,
+        MaterialShaderDescriptor, Mesh, MeshDescriptor, Model, ModelDescriptor, PipelineInfo,
     },
 };
 
@@ -28,6 +29,8 @@ pub struct ModelStore {
     map_descriptors: HashMap<Ulid, ModelDescriptor>,
     cache_realizations: Cache<Ulid, Model>,
     queue_realizations: Vec<Ulid>,
+    /// Models with a posed skin, whose joint palette needs to be uploaded.
+    queue_skin_updates: Vec<Ulid>,
     queue_bounding_boxes: Vec<Ulid>,
     map_bounding_boxes: HashMap<Ulid, BoundingBox>,
     map_label: HashMap<String, Ulid>,
@@ -87,10 +90,63 @@ impl ModelStore {
             .map(|descriptor| descriptor.label.as_str())
     }
 
+    /// Returns the [ModelDescriptor] for the given label.
+    /// Instance labels resolve to their base [ModelDescriptor].
+    pub fn descriptor_by_label(&self, label: &str) -> Option<&ModelDescriptor> {
+        let id = self.label_to_id(label)?;
+        self.map_descriptors.get(&id)
+    }
+
     pub fn get_bounding_boxes(&self) -> &HashMap<Ulid, BoundingBox> {
         &self.map_bounding_boxes
     }
 
+    /// Returns the box enclosing every instance of all models, e.g. to fit
+    /// a camera's depth range around the scene.  
+    /// Only models with a processed [BoundingBox] are included, thus this is
+    /// [None] if there are none.
+    pub fn scene_bounds(&self) -> Option<BoundingBoxDescriptor> {
+        self.map_bounding_boxes
+            .iter()
+            .filter_map(|(id, bounding_box)| {
+                Some((self.map_descriptors.get(id)?, bounding_box.descriptor()))
+            })
+            .flat_map(|(descriptor, bounding_box)| {
+                descriptor.transforms.values().map(|transform| {
+                    let model_space_matrix = Instance::from(transform).make_model_space_matrix();
+                    bounding_box.transformed(&model_space_matrix)
+                })
+            })
+            .reduce(|a, b| a.union(&b))
+    }
+
+    /// Checks whether any instance of the model is inside the [Frustum].  
+    /// Models without a processed [BoundingBox] are never inside.
+    ///
+    /// Skinned models are always inside, as their joints can move the mesh
+    /// far away from the bounding box of its bind pose.
+    pub fn is_in_frustum(&self, id: Ulid, frustum: &Frustum) -> bool {
+        let (Some(descriptor), Some(bounding_box)) = (
+            self.map_descriptors.get(&id),
+            self.map_bounding_boxes.get(&id),
+        ) else {
+            return false;
+        };
+
+        if descriptor.skin.is_some() {
+            return true;
+        }
+
+        descriptor.transforms.values().any(|transform| {
+            let model_space_matrix = Instance::from(transform).make_model_space_matrix();
+
+            bounding_box
+                .descriptor()
+                .transformed(&model_space_matrix)
+                .intersects_frustum(frustum)
+        })
+    }
+
     pub fn flag_realization(&mut self, ids: Vec<Ulid>, update_existing: bool) {
         for id in ids {
             if self.cache_realizations.contains_key(&id) && !update_existing {
@@ -121,7 +177,7 @@ impl ModelStore {
                 }
             };
 
-            let bounding_box_descriptor = descriptor.mesh.find_bounding_box();
+            let bounding_box_descriptor = descriptor.mesh.bounding_box();
             let bounding_box = BoundingBox::new(&bounding_box_descriptor, device);
             self.map_bounding_boxes.insert(id, bounding_box);
         }
@@ -166,6 +222,25 @@ impl ModelStore {
             self.cache_realizations.insert(id, cache_entry);
         }
 
+        // Posed skins only need their joint palette uploaded, the model
+        // itself stays as is
+        for id in self
+            .queue_skin_updates
+            .drain(0..self.queue_skin_updates.len())
+        {
+            let (Some(descriptor), Some(model)) = (
+                self.map_descriptors.get(&id),
+                self.cache_realizations.get(&id),
+            ) else {
+                // Not realized yet, will be realized with the current pose
+                continue;
+            };
+
+            if let (Some(skin_descriptor), Some(skin)) = (&descriptor.skin, model.inner().skin()) {
+                skin.update(skin_descriptor, queue);
+            }
+        }
+
         errors
     }
 
@@ -181,8 +256,115 @@ impl ModelStore {
             .collect::<Vec<_>>()
     }
 
+    /// Returns the realized [Model] for the given label, if it has been
+    /// realized already.  
+    /// Instance labels resolve to their base [Model].
+    ///
+    /// The returned [Model] and its GPU resources are read-only.
+    /// A [Model] is replaced whenever it gets re-realized (e.g. after a
+    /// transform change) and dropped once despawned.
+    /// Shared handles (like [Model::shared_mesh]) stay valid after that,
+    /// but won't reflect any later changes.
+    pub fn realization_by_label(&self, label: &str) -> Option<&Model> {
+        let id = self.label_to_id(label)?;
+        self.cache_realizations.get(&id).map(|entry| entry.inner())
+    }
+
+    /// Lists the [PipelineInfo] of all currently cached [MaterialShader]s.  
+    /// Meant for debugging, e.g. to inspect the compiled shaders or to find
+    /// duplicated pipelines via [PipelineInfo::is_similar_to].
+    pub fn pipelines(&self) -> Vec<PipelineInfo> {
+        match self.cache_material.read() {
+            Ok(lock) => lock
+                .values()
+                .map(|entry| entry.inner().info().clone())
+                .collect(),
+            Err(e) => {
+                warn!("Material cache is poisoned: {e}");
+                Vec::new()
+            }
+        }
+    }
+
+    /// Drops cached [MaterialShader]s whose preprocessed source changed,
+    /// e.g. after shader imports got reloaded, and re-realizes all realized
+    /// [Model]s.
+    /// Only the changed [MaterialShader]s get recompiled, the others are
+    /// taken from the cache again.
+    ///
+    /// Returns the number of dropped [MaterialShader]s.
+    pub fn invalidate_changed_shaders(&mut self) -> usize {
+        let dropped = match self.cache_material.write() {
+            Ok(mut lock) => {
+                let before = lock.len();
+                lock.retain(|descriptor, entry| {
+                    let info = entry.inner().info();
+                    descriptor
+                        .preprocessed_source_for_format(info.surface_format)
+                        .is_ok_and(|source| source == info.source)
+                });
+                before - lock.len()
+            }
+            Err(e) => {
+                warn!("Material cache is poisoned: {e}");
+                return 0;
+            }
+        };
+
+        if dropped > 0 {
+            let ids = self.cache_realizations.keys().copied().collect();
+            self.flag_realization(ids, true);
+        }
+
+        dropped
+    }
+
+    /// Sets how long a cached [Mesh] may stay unused before it's evicted.
+    pub fn set_mesh_cache_timeout(&mut self, timeout: Duration) {
+        match self.cache_mesh.write() {
+            Ok(mut lock) => lock.set_timeout(timeout),
+            Err(e) => warn!("Mesh cache is poisoned: {e}"),
+        }
+    }
+
+    /// Sets how long a cached [MaterialShader] may stay unused before it's
+    /// evicted.
+    pub fn set_material_cache_timeout(&mut self, timeout: Duration) {
+        match self.cache_material.write() {
+            Ok(mut lock) => lock.set_timeout(timeout),
+            Err(e) => warn!("Material cache is poisoned: {e}"),
+        }
+    }
+
+    /// Returns the statistics of the [Mesh] cache.
+    pub fn mesh_cache_stats(&self) -> CacheStats {
+        match self.cache_mesh.read() {
+            Ok(lock) => lock.stats(),
+            Err(e) => {
+                warn!("Mesh cache is poisoned: {e}");
+                CacheStats::default()
+            }
+        }
+    }
+
+    /// Returns the statistics of the [MaterialShader] cache.
+    pub fn material_cache_stats(&self) -> CacheStats {
+        match self.cache_material.read() {
+            Ok(lock) => lock.stats(),
+            Err(e) => {
+                warn!("Material cache is poisoned: {e}");
+                CacheStats::default()
+            }
+        }
+    }
+
+    /// Drops realizations of despawned [Model]s and any cached [Mesh] or
+    /// [MaterialShader] no longer referenced by a realized [Model] for
+    /// longer than the cache timeout.
     pub fn cleanup(&mut self) -> Result<(), Box<dyn Error + '_>> {
-        self.cache_realizations.cleanup();
+        self.cache_realizations
+            .retain(|id, _| self.map_descriptors.contains_key(id));
+
         match self.cache_mesh.write() {
             Ok(mut lock) => lock.cleanup(),
             Err(e) => {
@@ -214,52 +396,160 @@ impl ModelStore {
         self.map_descriptors.clear();
         self.map_bounding_boxes.clear();
         self.cache_realizations.clear();
+        self.queue_skin_updates.clear();
         self.instance_map.clear();
         self.instance_tracker.clear();
 
         Ok(())
     }
 
+    /// Drops all GPU resources, e.g. after the device got lost, and queues
+    /// every [Model] for realization with the next device.
+    pub fn invalidate_realizations(&mut self) -> Result<(), Box<dyn Error + '_>> {
+        self.map_bounding_boxes.clear();
+        self.cache_realizations.clear();
+
+        let ids: Vec<Ulid> = self.map_descriptors.keys().copied().collect();
+        self.queue_bounding_boxes = ids.clone();
+        self.flag_realization(ids, true);
+
+        match self.cache_mesh.write() {
+            Ok(mut lock) => lock.clear(),
+            Err(e) => return Err(Box::new(e)),
+        };
+
+        match self.cache_material.write() {
+            Ok(mut lock) => lock.clear(),
+            Err(e) => return Err(Box::new(e)),
+        };
+
+        Ok(())
+    }
+
+    /// Evaluates `animation` at `time` in seconds and applies it to all
+    /// [Model]s targeted by its channels.
+    /// Base models get all their instances animated, instance labels only
+    /// the specific instance.
+    ///
+    /// Channels targeting joints pose the skins of skinned [Model]s
+    /// instead, which only requires uploading the joint palette rather than
+    /// a re-realization.
+    pub fn apply_animation(&mut self, animation: &AnimationDescriptor, time: f32) {
+        let mut targets: Vec<&str> = animation
+            .channels
+            .iter()
+            .map(|channel| channel.target.as_str())
+            .collect();
+        targets.sort_unstable();
+        targets.dedup();
+
+        for (id, descriptor) in &mut self.map_descriptors {
+            let Some(skin) = descriptor.skin.as_mut() else {
+                continue;
+            };
+            if !skin
+                .joints
+                .iter()
+                .any(|joint| targets.contains(&joint.label.as_str()))
+            {
+                continue;
+            }
+
+            Arc::make_mut(skin).apply_animation(animation, time);
+            if !self.queue_skin_updates.contains(id) {
+                self.queue_skin_updates.push(*id);
+            }
+        }
+
+        for target in targets {
+            let Some(id) = self.label_to_id(target) else {
+                continue;
+            };
+            let descriptor = self.map_descriptors.get_mut(&id).unwrap();
+
+            match self.instance_tracker.get(target) {
+                Some((_, transform_ulid)) => {
+                    if let Some(transform) = descriptor.transforms.get_mut(transform_ulid) {
+                        animation.apply(target, time, transform);
+                    }
+                }
+                None => descriptor
+                    .transforms
+                    .values_mut()
+                    .for_each(|transform| animation.apply(target, time, transform)),
+            }
+
+            if self.cache_realizations.contains_key(&id) {
+                self.flag_realization(vec![id], true);
+            }
+        }
+    }
+
     pub fn is_empty(&self) -> bool {
         self.map_descriptors.is_empty()
     }
 
+    /// Stores the given [ModelDescriptor].
+    /// If an identical [Model] (same mesh and materials) already exists,
+    /// the descriptor is added as an instance to it instead and the id of
+    /// the base [Model] is returned, which needs to be re-realized.
+    fn spawn(&mut self, descriptor: ModelDescriptor) -> Option<Ulid> {
+        // Check for duplicate models
+        let hash = descriptor.instance_hash();
+        if let Some(&base_id) = self.instance_map.get(&hash) {
+            // Found duplicate - create instance
+            let base_descriptor = self.map_descriptors.get_mut(&base_id).unwrap();
+            let transform_ulid =
+                base_descriptor.add_transform(*descriptor.transforms.values().next().unwrap());
+
+            // Use the original label from descriptor, or generate new if it conflicts
+            let instance_label = if self.map_label.contains_key(&descriptor.label) {
+                format!("instance_{}", Ulid::new().to_string())
+            } else {
+                descriptor.label.clone()
+            };
+            let base_label = base_descriptor.label.clone();
+
+            // Track the instance
+            self.instance_tracker
+                .insert(instance_label.clone(), (base_label, transform_ulid));
+            self.map_label.insert(instance_label, base_id);
+
+            Some(base_id)
+        } else {
+            // No duplicate - store as base model
+            let id = Ulid::new();
+
+            self.map_label.insert(descriptor.label.clone(), id);
+            self.map_descriptors.insert(id, descriptor);
+            self.instance_map.insert(hash, id);
+            self.queue_bounding_boxes.push(id);
+
+            None
+        }
+    }
+
     pub fn handle_event(&mut self, model_event: ModelEvent) {
         match model_event {
             ModelEvent::Spawn(descriptor) => {
-                // Check for duplicate models
-                let hash = descriptor.instance_hash();
-                if let Some(&base_id) = self.instance_map.get(&hash) {
-                    // Found duplicate - create instance
-                    let base_descriptor = self.map_descriptors.get_mut(&base_id).unwrap();
-                    let transform_ulid = base_descriptor
-                        .add_transform(*descriptor.transforms.values().next().unwrap());
-
-                    // Use the original label from descriptor, or generate new if it conflicts
-                    let instance_label = if self.map_label.contains_key(&descriptor.label) {
-                        format!("instance_{}", Ulid::new().to_string())
-                    } else {
-                        descriptor.label.clone()
-                    };
-                    let base_label = base_descriptor.label.clone();
-
-                    // Track the instance
-                    self.instance_tracker
-                        .insert(instance_label.clone(), (base_label, transform_ulid));
-                    self.map_label.insert(instance_label, base_id);
-
+                if let Some(base_id) = self.spawn(descriptor) {
                     // Flag for re-realization
                     self.flag_realization(vec![base_id], true);
-                } else {
-                    // No duplicate - store as base model
-                    let id = Ulid::new();
-
-                    self.map_label.insert(descriptor.label.clone(), id);
-                    self.map_descriptors.insert(id, descriptor);
-                    self.instance_map.insert(hash, id);
-                    self.queue_bounding_boxes.push(id);
                 }
             }
+            ModelEvent::SpawnBatch(descriptors) => {
+                // Instances of the same base only need one re-realization
+                let mut base_ids = Vec::new();
+                for descriptor in descriptors {
+                    if let Some(base_id) = self.spawn(descriptor) {
+                        if !base_ids.contains(&base_id) {
+                            base_ids.push(base_id);
+                        }
+                    }
+                }
+
+                self.flag_realization(base_ids, true);
+            }
             ModelEvent::Despawn(label) => {
                 if let Some((base_label, transform_ulid)) =
                     self.instance_tracker.get(&label).cloned()
@@ -297,6 +587,10 @@ impl ModelStore {
 
                         // Remove the base model
                         self.remove(Or::Right(id));
+                    } else {
+                        warn!(
+                            "Attempting to despawn Model with label '{label}', which cannot be found!"
+                        );
                     }
                 }
             }
@@ -314,6 +608,50 @@ impl ModelStore {
                     );
                 }
             }
+            ModelEvent::SetTransforms(label, transforms) => {
+                if transforms.is_empty() {
+                    warn!("Attempting to set zero transforms on Model with label '{label}', use despawning instead!");
+                    return;
+                }
+
+                if self.instance_tracker.contains_key(&label) {
+                    warn!(
+                        "Attempting to set transforms on instance '{label}', use the base Model instead!"
+                    );
+                    return;
+                }
+
+                let Some(idx) = self.label_to_id(&label) else {
+                    warn!(
+                        "Attempting to modify Model with label '{label}', which cannot be found!"
+                    );
+                    return;
+                };
+
+                let descriptor = self.map_descriptors.get_mut(&idx).unwrap();
+                descriptor.set_transforms(
+                    transforms
+                        .into_iter()
+                        .map(|transform| (Ulid::new(), transform))
+                        .collect(),
+                );
+
+                // Previous instances don't exist anymore
+                let instances_to_remove: Vec<String> = self
+                    .instance_tracker
+                    .iter()
+                    .filter(|(_, (base_label, _))| base_label == &label)
+                    .map(|(instance_label, _)| instance_label.clone())
+                    .collect();
+                for instance_label in instances_to_remove {
+                    self.instance_tracker.remove(&instance_label);
+                    self.map_label.remove(&instance_label);
+                }
+
+                if self.cache_realizations.contains_key(&idx) {
+                    self.flag_realization(vec![idx], true);
+                }
+            }
             ModelEvent::TransformInstance(label, mode, transform_ulid_str) => {
                 if let Ok(transform_ulid) = Ulid::from_string(&transform_ulid_str) {
                     if let Some(idx) = self.label_to_id(&label) {
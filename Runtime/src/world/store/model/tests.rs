@@ -1,12 +1,19 @@
 use std::sync::Arc;
 
-use cgmath::{Vector2, Vector3};
+use cgmath::{Deg, InnerSpace, Matrix4, Quaternion, Rotation3, SquareMatrix, Vector2, Vector3};
 use hashbrown::HashMap;
 use ulid::Ulid;
 
+use wgpu::TextureFormat;
+
 use crate::{
     element::ModelEvent,
-    resources::{MaterialDescriptor, MeshDescriptor, ModelDescriptor, Transform, Vertex},
+    resources::{
+        AnimationChannel, AnimationDescriptor, AnimationValues, CameraDescriptor, Interpolation,
+        MaterialDescriptor, MeshDescriptor, ModelDescriptor, RenderLayer, ShaderSource,
+        SkinDescriptor, SkinJoint, Transform, Vertex,
+    },
+    wgpu_test_adapter,
     world::store::model::ModelStore,
 };
 
@@ -27,11 +34,16 @@ fn test_basic_instancing() {
                 tangent: Vector3::new(1.0, 2.0, 3.0),
                 bitangent: Vector3::new(1.0, 2.0, 3.0),
                 uv: Vector2::new(1.0, 2.0),
+                color: None,
+                skin: None,
             }],
             indices: vec![0],
+            ..Default::default()
         }),
         materials: vec![Arc::new(MaterialDescriptor::default())],
         transforms,
+        skin: None,
+        render_layer: RenderLayer::default(),
     };
 
     // Spawn the base model
@@ -57,6 +69,8 @@ fn test_basic_instancing() {
         mesh: base_descriptor.mesh.clone(),
         materials: base_descriptor.materials.clone(),
         transforms: duplicate_transforms,
+        skin: None,
+        render_layer: RenderLayer::default(),
     };
 
     store.handle_event(ModelEvent::Spawn(duplicate_descriptor));
@@ -95,11 +109,16 @@ fn test_instance_despawning() {
                 tangent: Vector3::new(1.0, 2.0, 3.0),
                 bitangent: Vector3::new(1.0, 2.0, 3.0),
                 uv: Vector2::new(1.0, 2.0),
+                color: None,
+                skin: None,
             }],
             indices: vec![0],
+            ..Default::default()
         }),
         materials: vec![Arc::new(MaterialDescriptor::default())],
         transforms,
+        skin: None,
+        render_layer: RenderLayer::default(),
     };
 
     store.handle_event(ModelEvent::Spawn(base_descriptor.clone()));
@@ -119,6 +138,8 @@ fn test_instance_despawning() {
         mesh: base_descriptor.mesh.clone(),
         materials: base_descriptor.materials.clone(),
         transforms: instance_transforms,
+        skin: None,
+        render_layer: RenderLayer::default(),
     };
 
     store.handle_event(ModelEvent::Spawn(instance_descriptor));
@@ -162,11 +183,16 @@ fn test_base_model_despawning_with_instances() {
                 tangent: Vector3::new(1.0, 2.0, 3.0),
                 bitangent: Vector3::new(1.0, 2.0, 3.0),
                 uv: Vector2::new(1.0, 2.0),
+                color: None,
+                skin: None,
             }],
             indices: vec![0],
+            ..Default::default()
         }),
         materials: vec![Arc::new(MaterialDescriptor::default())],
         transforms,
+        skin: None,
+        render_layer: RenderLayer::default(),
     };
 
     store.handle_event(ModelEvent::Spawn(base_descriptor.clone()));
@@ -187,6 +213,8 @@ fn test_base_model_despawning_with_instances() {
             mesh: base_descriptor.mesh.clone(),
             materials: base_descriptor.materials.clone(),
             transforms: instance_transforms,
+            skin: None,
+            render_layer: RenderLayer::default(),
         };
 
         store.handle_event(ModelEvent::Spawn(instance_descriptor));
@@ -226,11 +254,16 @@ fn test_different_materials_prevent_instancing() {
                 tangent: Vector3::new(1.0, 2.0, 3.0),
                 bitangent: Vector3::new(1.0, 2.0, 3.0),
                 uv: Vector2::new(1.0, 2.0),
+                color: None,
+                skin: None,
             }],
             indices: vec![0],
+            ..Default::default()
         }),
         materials: vec![Arc::new(MaterialDescriptor::default())],
         transforms: transforms1,
+        skin: None,
+        render_layer: RenderLayer::default(),
     };
 
     store.handle_event(ModelEvent::Spawn(descriptor1.clone()));
@@ -247,6 +280,8 @@ fn test_different_materials_prevent_instancing() {
         mesh: descriptor1.mesh.clone(),                // Same mesh
         materials: vec![Arc::new(different_material)], // Different material
         transforms: transforms2,
+        skin: None,
+        render_layer: RenderLayer::default(),
     };
 
     store.handle_event(ModelEvent::Spawn(descriptor2));
@@ -278,11 +313,16 @@ fn test_different_meshes_prevent_instancing() {
                 tangent: Vector3::new(1.0, 2.0, 3.0),
                 bitangent: Vector3::new(1.0, 2.0, 3.0),
                 uv: Vector2::new(1.0, 2.0),
+                color: None,
+                skin: None,
             }],
             indices: vec![0],
+            ..Default::default()
         }),
         materials: vec![Arc::new(MaterialDescriptor::default())],
         transforms: transforms1,
+        skin: None,
+        render_layer: RenderLayer::default(),
     };
 
     store.handle_event(ModelEvent::Spawn(descriptor1.clone()));
@@ -300,11 +340,16 @@ fn test_different_meshes_prevent_instancing() {
                 tangent: Vector3::new(1.0, 2.0, 3.0),
                 bitangent: Vector3::new(1.0, 2.0, 3.0),
                 uv: Vector2::new(1.0, 2.0),
+                color: None,
+                skin: None,
             }],
             indices: vec![0],
+            ..Default::default()
         }),
         materials: descriptor1.materials.clone(), // Same material
         transforms: transforms2,
+        skin: None,
+        render_layer: RenderLayer::default(),
     };
 
     store.handle_event(ModelEvent::Spawn(descriptor2));
@@ -333,11 +378,16 @@ fn test_instance_hash_consistency() {
                 tangent: Vector3::new(1.0, 2.0, 3.0),
                 bitangent: Vector3::new(1.0, 2.0, 3.0),
                 uv: Vector2::new(1.0, 2.0),
+                color: None,
+                skin: None,
             }],
             indices: vec![0],
+            ..Default::default()
         }),
         materials: vec![Arc::new(MaterialDescriptor::default())],
         transforms: transforms.clone(),
+        skin: None,
+        render_layer: RenderLayer::default(),
     };
 
     let descriptor2 = ModelDescriptor {
@@ -345,6 +395,8 @@ fn test_instance_hash_consistency() {
         mesh: descriptor1.mesh.clone(),
         materials: descriptor1.materials.clone(),
         transforms: transforms.clone(),
+        skin: None,
+        render_layer: RenderLayer::default(),
     };
 
     // Hash should be the same for identical mesh/material combinations
@@ -368,11 +420,16 @@ fn test_instance_label_generation() {
                 tangent: Vector3::new(1.0, 2.0, 3.0),
                 bitangent: Vector3::new(1.0, 2.0, 3.0),
                 uv: Vector2::new(1.0, 2.0),
+                color: None,
+                skin: None,
             }],
             indices: vec![0],
+            ..Default::default()
         }),
         materials: vec![Arc::new(MaterialDescriptor::default())],
         transforms,
+        skin: None,
+        render_layer: RenderLayer::default(),
     };
 
     store.handle_event(ModelEvent::Spawn(base_descriptor.clone()));
@@ -386,6 +443,8 @@ fn test_instance_label_generation() {
         mesh: base_descriptor.mesh.clone(),
         materials: base_descriptor.materials.clone(),
         transforms: instance_transforms,
+        skin: None,
+        render_layer: RenderLayer::default(),
     };
 
     store.handle_event(ModelEvent::Spawn(instance_descriptor));
@@ -413,11 +472,16 @@ fn test_clear_cleans_up_instancing_data() {
                 tangent: Vector3::new(1.0, 2.0, 3.0),
                 bitangent: Vector3::new(1.0, 2.0, 3.0),
                 uv: Vector2::new(1.0, 2.0),
+                color: None,
+                skin: None,
             }],
             indices: vec![0],
+            ..Default::default()
         }),
         materials: vec![Arc::new(MaterialDescriptor::default())],
         transforms,
+        skin: None,
+        render_layer: RenderLayer::default(),
     };
 
     store.handle_event(ModelEvent::Spawn(base_descriptor.clone()));
@@ -431,6 +495,8 @@ fn test_clear_cleans_up_instancing_data() {
         mesh: base_descriptor.mesh.clone(),
         materials: base_descriptor.materials.clone(),
         transforms: instance_transforms,
+        skin: None,
+        render_layer: RenderLayer::default(),
     };
 
     store.handle_event(ModelEvent::Spawn(instance_descriptor));
@@ -448,3 +514,608 @@ fn test_clear_cleans_up_instancing_data() {
     assert!(store.map_descriptors.is_empty());
     assert!(store.map_label.is_empty());
 }
+
+#[test]
+fn test_realization_by_label() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+    let mut store = ModelStore::new();
+
+    let vertex = Vertex {
+        position: Vector3::new(1.0, 2.0, 3.0),
+        normal: Vector3::new(1.0, 2.0, 3.0),
+        tangent: Vector3::new(1.0, 2.0, 3.0),
+        bitangent: Vector3::new(1.0, 2.0, 3.0),
+        uv: Vector2::new(1.0, 2.0),
+        color: None,
+        skin: None,
+    };
+    let mut transforms = HashMap::new();
+    transforms.insert(Ulid::new(), Transform::default());
+
+    let descriptor = ModelDescriptor {
+        label: "Triangle".to_string(),
+        mesh: Arc::new(MeshDescriptor {
+            vertices: vec![vertex.clone(); 3],
+            indices: vec![0, 1, 2],
+            ..Default::default()
+        }),
+        materials: vec![Arc::new(MaterialDescriptor::default())],
+        transforms,
+        skin: None,
+        render_layer: RenderLayer::default(),
+    };
+
+    store.handle_event(ModelEvent::Spawn(descriptor));
+    assert!(store.realization_by_label("Triangle").is_none());
+
+    let id = store.label_to_id("Triangle").unwrap();
+    store.flag_realization(vec![id], false);
+    assert!(store
+        .realize_and_cache(&TextureFormat::Rgba16Float, &device, &queue)
+        .is_empty());
+
+    let model = store.realization_by_label("Triangle").unwrap();
+    let mesh = model.shared_mesh();
+    assert_eq!(mesh.index_count(), 3);
+    assert_eq!(mesh.index_buffer().size(), 3 * size_of::<u32>() as u64);
+    assert_eq!(
+        mesh.vertex_buffer().size(),
+        3 * vertex.to_bytes().len() as u64
+    );
+
+    assert!(store.realization_by_label("Unknown").is_none());
+}
+
+#[test]
+fn test_cleanup_keeps_live_realizations() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+    let mut store = ModelStore::new();
+
+    for (label, x) in [("Live", 1.0), ("Despawned", 2.0)] {
+        let mut transforms = HashMap::new();
+        transforms.insert(Ulid::new(), Transform::default());
+
+        store.handle_event(ModelEvent::Spawn(ModelDescriptor {
+            label: label.to_string(),
+            mesh: Arc::new(MeshDescriptor {
+                vertices: vec![Vertex {
+                    position: Vector3::new(x, 2.0, 3.0),
+                    normal: Vector3::new(1.0, 2.0, 3.0),
+                    tangent: Vector3::new(1.0, 2.0, 3.0),
+                    bitangent: Vector3::new(1.0, 2.0, 3.0),
+                    uv: Vector2::new(1.0, 2.0),
+                    color: None,
+                    skin: None,
+                }],
+                indices: vec![0],
+                ..Default::default()
+            }),
+            materials: vec![Arc::new(MaterialDescriptor::default())],
+            transforms,
+            skin: None,
+            render_layer: RenderLayer::default(),
+        }));
+    }
+
+    let ids = vec![
+        store.label_to_id("Live").unwrap(),
+        store.label_to_id("Despawned").unwrap(),
+    ];
+    store.flag_realization(ids, false);
+    assert!(store
+        .realize_and_cache(&TextureFormat::Rgba16Float, &device, &queue)
+        .is_empty());
+    assert_eq!(store.cache_realizations.len(), 2);
+
+    store.handle_event(ModelEvent::Despawn("Despawned".to_string()));
+    store.cleanup().unwrap();
+
+    assert_eq!(store.cache_realizations.len(), 1);
+    assert!(store.realization_by_label("Live").is_some());
+    // The live model still references its mesh
+    assert_eq!(store.cache_mesh.read().unwrap().len(), 2);
+}
+
+#[test]
+fn test_spawn_batch() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+    let mut store = ModelStore::new();
+
+    let material = Arc::new(MaterialDescriptor::default());
+    let mesh = |x: f32| {
+        Arc::new(MeshDescriptor {
+            vertices: vec![
+                Vertex {
+                    position: Vector3::new(x, 2.0, 3.0),
+                    normal: Vector3::new(1.0, 2.0, 3.0),
+                    tangent: Vector3::new(1.0, 2.0, 3.0),
+                    bitangent: Vector3::new(1.0, 2.0, 3.0),
+                    uv: Vector2::new(1.0, 2.0),
+                    color: None,
+                    skin: None,
+                };
+                3
+            ],
+            indices: vec![0, 1, 2],
+            ..Default::default()
+        })
+    };
+    let descriptor = |label: &str, mesh: Arc<MeshDescriptor>| {
+        let mut transforms = HashMap::new();
+        transforms.insert(Ulid::new(), Transform::default());
+
+        ModelDescriptor {
+            label: label.to_string(),
+            mesh,
+            materials: vec![material.clone()],
+            transforms,
+            skin: None,
+            render_layer: RenderLayer::default(),
+        }
+    };
+
+    let shared_mesh = mesh(0.0);
+    store.handle_event(ModelEvent::SpawnBatch(vec![
+        descriptor("A", shared_mesh.clone()),
+        descriptor("B", mesh(1.0)),
+        descriptor("C", mesh(2.0)),
+        // Instances of "A"
+        descriptor("A2", shared_mesh.clone()),
+        descriptor("A3", shared_mesh),
+    ]));
+
+    for label in ["A", "B", "C", "A2", "A3"] {
+        assert!(store.label_to_id(label).is_some(), "{label} is missing");
+    }
+    assert_eq!(store.map_descriptors.len(), 3);
+
+    // Instances only flag their base model once
+    let base_id = store.label_to_id("A").unwrap();
+    assert_eq!(store.map_descriptors[&base_id].transforms.len(), 3);
+    assert_eq!(store.queue_realizations, vec![base_id]);
+
+    let ids = store.map_descriptors.keys().copied().collect();
+    store.flag_realization(ids, false);
+    assert!(store
+        .realize_and_cache(&TextureFormat::Rgba16Float, &device, &queue)
+        .is_empty());
+
+    // All models are realized within the same update ...
+    let models = ["A", "B", "C"].map(|label| store.realization_by_label(label).unwrap());
+    assert_eq!(models[0].instance_count(), 3);
+
+    // ... and share one realized material
+    assert_eq!(store.cache_material.read().unwrap().len(), 1);
+    // Only the first realization misses, everything else hits
+    let material_stats = store.material_cache_stats();
+    assert_eq!(material_stats.misses, 1);
+    assert_eq!(material_stats.insertions, 1);
+    assert!(material_stats.hits >= 2);
+    assert!(Arc::ptr_eq(
+        &models[0].materials()[0],
+        &models[1].materials()[0]
+    ));
+    assert!(Arc::ptr_eq(
+        &models[1].materials()[0],
+        &models[2].materials()[0]
+    ));
+}
+
+#[test]
+fn test_pipeline_introspection() {
+    const RED_SHADER: &str = "
+@vertex
+fn entrypoint_vertex(@builtin(vertex_index) vertex_index: u32) -> @builtin(position) vec4<f32> {
+    return vec4<f32>(f32(vertex_index), 0.0, 0.0, 1.0);
+}
+
+@fragment
+fn entrypoint_fragment() -> @location(0) vec4<f32> {
+    return vec4<f32>(1.0, 0.0, 0.0, 1.0);
+}
+";
+    const GREEN_SHADER: &str = "
+@vertex
+fn entrypoint_vertex(@builtin(vertex_index) vertex_index: u32) -> @builtin(position) vec4<f32> {
+    return vec4<f32>(f32(vertex_index), 0.0, 0.0, 1.0);
+}
+
+@fragment
+fn entrypoint_fragment() -> @location(0) vec4<f32> {
+    return vec4<f32>(0.0, 1.0, 0.0, 1.0);
+}
+";
+
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+    let mut store = ModelStore::new();
+
+    let mesh = Arc::new(MeshDescriptor {
+        vertices: vec![
+            Vertex {
+                position: Vector3::new(1.0, 2.0, 3.0),
+                normal: Vector3::new(1.0, 2.0, 3.0),
+                tangent: Vector3::new(1.0, 2.0, 3.0),
+                bitangent: Vector3::new(1.0, 2.0, 3.0),
+                uv: Vector2::new(1.0, 2.0),
+                color: None,
+                skin: None,
+            };
+            3
+        ],
+        indices: vec![0, 1, 2],
+        ..Default::default()
+    });
+
+    for (label, source) in [("Red", RED_SHADER), ("Green", GREEN_SHADER)] {
+        let mut transforms = HashMap::new();
+        transforms.insert(Ulid::new(), Transform::default());
+
+        store.handle_event(ModelEvent::Spawn(ModelDescriptor {
+            label: label.to_string(),
+            mesh: mesh.clone(),
+            materials: vec![Arc::new(MaterialDescriptor {
+                name: Some(label.to_string()),
+                shader_source: ShaderSource::String(source),
+                vertex_stage_layouts: None,
+                ..Default::default()
+            })],
+            transforms,
+            skin: None,
+            render_layer: RenderLayer::default(),
+        }));
+
+        let id = store.label_to_id(label).unwrap();
+        store.flag_realization(vec![id], false);
+    }
+
+    assert!(store
+        .realize_and_cache(&TextureFormat::Rgba8UnormSrgb, &device, &queue)
+        .is_empty());
+
+    let mut pipelines = store.pipelines();
+    pipelines.sort_by(|a, b| a.label.cmp(&b.label));
+
+    assert_eq!(pipelines.len(), 2);
+    assert_eq!(pipelines[0].label.as_deref(), Some("Green"));
+    assert_eq!(pipelines[0].source.trim(), GREEN_SHADER.trim());
+    assert_eq!(pipelines[1].label.as_deref(), Some("Red"));
+    assert_eq!(pipelines[1].source.trim(), RED_SHADER.trim());
+    assert!(pipelines.iter().all(|pipeline| pipeline.surface_format
+        == TextureFormat::Rgba8UnormSrgb
+        && pipeline.bind_group_layout.is_empty()));
+    assert!(!pipelines[0].is_similar_to(&pipelines[1]));
+}
+
+#[test]
+fn test_frustum_check_considers_all_instances() {
+    let (_, device, _) = wgpu_test_adapter::make_wgpu_connection();
+    let mut store = ModelStore::new();
+
+    // Default camera looks along +X, this one is placed behind it
+    let mut transforms = HashMap::new();
+    transforms.insert(
+        Ulid::new(),
+        Transform {
+            position: Vector3::new(-5.0, 0.0, 0.0),
+            ..Default::default()
+        },
+    );
+
+    store.store(ModelDescriptor {
+        label: "Point".to_string(),
+        mesh: Arc::new(MeshDescriptor {
+            vertices: vec![Vertex::new(
+                Vector3::new(0.0, 0.0, 0.0),
+                Vector3::unit_y(),
+                Vector3::unit_x(),
+                Vector2::new(0.0, 0.0),
+            )],
+            indices: vec![0],
+            ..Default::default()
+        }),
+        materials: vec![Arc::new(MaterialDescriptor::default())],
+        transforms,
+        skin: None,
+        render_layer: RenderLayer::default(),
+    });
+    let id = store.label_to_id("Point").unwrap();
+    let frustum = CameraDescriptor::default().frustum();
+
+    // Bounding box not yet processed
+    assert!(!store.is_in_frustum(id, &frustum));
+
+    store.process_bounding_boxes(&device);
+    assert!(!store.is_in_frustum(id, &frustum));
+
+    store
+        .map_descriptors
+        .get_mut(&id)
+        .unwrap()
+        .add_transform(Transform {
+            position: Vector3::new(5.0, 0.0, 0.0),
+            ..Default::default()
+        });
+    assert!(store.is_in_frustum(id, &frustum));
+}
+
+#[test]
+fn test_frustum_check_keeps_skinned_models() {
+    let (_, device, _) = wgpu_test_adapter::make_wgpu_connection();
+    let mut store = ModelStore::new();
+
+    // Behind the default camera in bind pose, but joints may move it into view
+    let mut descriptor = triangle(
+        "Arm",
+        &[Transform {
+            position: Vector3::new(-5.0, 0.0, 0.0),
+            ..Default::default()
+        }],
+    );
+    descriptor.skin = Some(Arc::new(SkinDescriptor {
+        label: "Arm".into(),
+        joints: vec![SkinJoint {
+            label: "Elbow".into(),
+            parent: None,
+            transform: Transform::default(),
+            inverse_bind_matrix: Matrix4::identity(),
+        }],
+        ..Default::default()
+    }));
+    store.store(descriptor);
+    let id = store.label_to_id("Arm").unwrap();
+    store.process_bounding_boxes(&device);
+
+    assert!(store.is_in_frustum(id, &CameraDescriptor::default().frustum()));
+}
+
+fn triangle(label: &str, transforms: &[Transform]) -> ModelDescriptor {
+    ModelDescriptor {
+        label: label.to_string(),
+        mesh: Arc::new(MeshDescriptor {
+            vertices: vec![
+                Vertex {
+                    position: Vector3::new(1.0, 2.0, 3.0),
+                    normal: Vector3::new(1.0, 2.0, 3.0),
+                    tangent: Vector3::new(1.0, 2.0, 3.0),
+                    bitangent: Vector3::new(1.0, 2.0, 3.0),
+                    uv: Vector2::new(1.0, 2.0),
+                    color: None,
+                    skin: None,
+                };
+                3
+            ],
+            indices: vec![0, 1, 2],
+            ..Default::default()
+        }),
+        materials: vec![Arc::new(MaterialDescriptor::default())],
+        transforms: transforms
+            .iter()
+            .map(|transform| (Ulid::new(), *transform))
+            .collect(),
+        skin: None,
+        render_layer: RenderLayer::default(),
+    }
+}
+
+#[test]
+fn test_despawn_removes_realization() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+    let mut store = ModelStore::new();
+
+    store.handle_event(ModelEvent::Spawn(triangle(
+        "Chunk",
+        &[Transform::default()],
+    )));
+    let id = store.label_to_id("Chunk").unwrap();
+    store.flag_realization(vec![id], false);
+    assert!(store
+        .realize_and_cache(&TextureFormat::Rgba16Float, &device, &queue)
+        .is_empty());
+    assert!(store.realization_by_label("Chunk").is_some());
+
+    store.handle_event(ModelEvent::Despawn("Chunk".to_string()));
+    assert!(store.label_to_id("Chunk").is_none());
+    assert!(store.realization_by_label("Chunk").is_none());
+    assert!(store.get_realizations(vec![id]).is_empty());
+    assert!(store.is_empty());
+
+    // Unknown labels are ignored
+    store.handle_event(ModelEvent::Despawn("Chunk".to_string()));
+    store.handle_event(ModelEvent::SetTransforms(
+        "Chunk".to_string(),
+        vec![Transform::default()],
+    ));
+    assert!(store.is_empty());
+}
+
+#[test]
+fn test_invalidate_realizations_reuploads_to_new_device() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+    let mut store = ModelStore::new();
+
+    store.handle_event(ModelEvent::Spawn(triangle(
+        "Chunk",
+        &[Transform::default()],
+    )));
+    let id = store.label_to_id("Chunk").unwrap();
+    store.process_bounding_boxes(&device);
+    store.flag_realization(vec![id], false);
+    assert!(store
+        .realize_and_cache(&TextureFormat::Rgba16Float, &device, &queue)
+        .is_empty());
+
+    store.invalidate_realizations().unwrap();
+    assert!(store.realization_by_label("Chunk").is_none());
+    assert!(store.get_bounding_boxes().is_empty());
+
+    // Realized again with the new device, without being flagged again
+    let (_, new_device, new_queue) = wgpu_test_adapter::make_wgpu_connection();
+    store.process_bounding_boxes(&new_device);
+    assert!(store
+        .realize_and_cache(&TextureFormat::Rgba16Float, &new_device, &new_queue)
+        .is_empty());
+    assert!(store.realization_by_label("Chunk").is_some());
+    assert!(store.get_bounding_boxes().contains_key(&id));
+}
+
+#[test]
+fn test_set_transforms_updates_instance_buffer() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+    let mut store = ModelStore::new();
+
+    store.handle_event(ModelEvent::SpawnBatch(vec![
+        triangle("Tree", &[Transform::default()]),
+        // Instance of "Tree"
+        triangle("Tree2", &[Transform::default()]),
+    ]));
+    let id = store.label_to_id("Tree").unwrap();
+    store.flag_realization(vec![id], false);
+    assert!(store
+        .realize_and_cache(&TextureFormat::Rgba16Float, &device, &queue)
+        .is_empty());
+    let instance_buffer_size = {
+        let model = store.realization_by_label("Tree").unwrap();
+        assert_eq!(model.instance_count(), 2);
+        model.instance_buffer().size()
+    };
+
+    // Instances can't be replaced on their own
+    store.handle_event(ModelEvent::SetTransforms(
+        "Tree2".to_string(),
+        vec![Transform::default()],
+    ));
+    // Models can't be left without any transform
+    store.handle_event(ModelEvent::SetTransforms("Tree".to_string(), Vec::new()));
+    assert_eq!(store.map_descriptors[&id].transforms.len(), 2);
+
+    let transforms: Vec<_> = (0..4)
+        .map(|x| Transform::from_position(Vector3::new(x as f32, 0.0, 0.0)))
+        .collect();
+    store.handle_event(ModelEvent::SetTransforms(
+        "Tree".to_string(),
+        transforms.clone(),
+    ));
+    assert_eq!(store.queue_realizations, vec![id]);
+    // The old instance is gone
+    assert!(store.label_to_id("Tree2").is_none());
+    assert!(store.instance_tracker.is_empty());
+
+    let mut stored: Vec<_> = store.map_descriptors[&id]
+        .transforms
+        .values()
+        .map(|transform| transform.position.x)
+        .collect();
+    stored.sort_by(f32::total_cmp);
+    assert_eq!(stored, vec![0.0, 1.0, 2.0, 3.0]);
+
+    assert!(store
+        .realize_and_cache(&TextureFormat::Rgba16Float, &device, &queue)
+        .is_empty());
+    let model = store.realization_by_label("Tree").unwrap();
+    assert_eq!(model.instance_count(), 4);
+    assert_eq!(model.instance_buffer().size(), instance_buffer_size * 2);
+}
+
+#[test]
+fn test_animation_poses_skin() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+    let mut store = ModelStore::new();
+
+    let mut descriptor = triangle("Arm", &[Transform::default()]);
+    descriptor.skin = Some(Arc::new(SkinDescriptor {
+        label: "Arm".into(),
+        joints: vec![SkinJoint {
+            label: "Elbow".into(),
+            parent: None,
+            transform: Transform::default(),
+            inverse_bind_matrix: Matrix4::identity(),
+        }],
+        ..Default::default()
+    }));
+    store.handle_event(ModelEvent::Spawn(descriptor));
+    let id = store.label_to_id("Arm").unwrap();
+    store.flag_realization(vec![id], false);
+    assert!(store
+        .realize_and_cache(&TextureFormat::Rgba16Float, &device, &queue)
+        .is_empty());
+
+    let animation = AnimationDescriptor {
+        label: "Bend".into(),
+        channels: vec![AnimationChannel {
+            target: "Elbow".into(),
+            interpolation: Interpolation::Step,
+            times: vec![0.0],
+            values: AnimationValues::Rotation(vec![Quaternion::from_angle_z(Deg(90.0))]),
+        }],
+    };
+    store.apply_animation(&animation, 0.0);
+
+    // Only the joint palette changes, the model stays as is
+    assert!(store.queue_realizations.is_empty());
+    assert_eq!(store.queue_skin_updates, vec![id]);
+    let skin = store.map_descriptors[&id].skin.clone().unwrap();
+    assert!(
+        (skin.joints[0].transform.rotation - Quaternion::from_angle_z(Deg(90.0))).magnitude()
+            < 1e-6
+    );
+
+    assert!(store
+        .realize_and_cache(&TextureFormat::Rgba16Float, &device, &queue)
+        .is_empty());
+    assert!(store.queue_skin_updates.is_empty());
+    assert!(store.realization_by_label("Arm").unwrap().skin().is_some());
+}
+
+#[test]
+fn test_changed_shaders_are_recompiled() {
+    const SHADER: &str = "
+@vertex
+fn entrypoint_vertex(@builtin(vertex_index) vertex_index: u32) -> @builtin(position) vec4<f32> {
+    return vec4<f32>(f32(vertex_index), 0.0, 0.0, 1.0);
+}
+
+@fragment
+fn entrypoint_fragment() -> @location(0) vec4<f32> {
+    return vec4<f32>(1.0, 0.0, 0.0, 1.0);
+}
+";
+
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+    let mut store = ModelStore::new();
+
+    let path = std::env::temp_dir().join("orbital_changed_shader.wgsl");
+    std::fs::write(&path, SHADER).unwrap();
+    let path: &'static str = Box::leak(path.to_string_lossy().into_owned().into_boxed_str());
+
+    let mut descriptor = triangle("Shaded", &[Transform::default()]);
+    descriptor.materials = vec![Arc::new(MaterialDescriptor {
+        name: Some("Shaded".to_string()),
+        shader_source: ShaderSource::Path(path),
+        vertex_stage_layouts: None,
+        ..Default::default()
+    })];
+    store.handle_event(ModelEvent::Spawn(descriptor));
+    let id = store.label_to_id("Shaded").unwrap();
+    store.flag_realization(vec![id], false);
+    assert!(store
+        .realize_and_cache(&TextureFormat::Rgba8UnormSrgb, &device, &queue)
+        .is_empty());
+
+    // Nothing changed
+    assert_eq!(store.invalidate_changed_shaders(), 0);
+    assert!(store.queue_realizations.is_empty());
+
+    let changed_shader = SHADER.replace("1.0, 0.0, 0.0, 1.0", "0.0, 1.0, 0.0, 1.0");
+    std::fs::write(path, &changed_shader).unwrap();
+    assert_eq!(store.invalidate_changed_shaders(), 1);
+    assert_eq!(store.queue_realizations, vec![id]);
+
+    assert!(store
+        .realize_and_cache(&TextureFormat::Rgba8UnormSrgb, &device, &queue)
+        .is_empty());
+    let pipelines = store.pipelines();
+    assert_eq!(pipelines.len(), 1);
+    assert_eq!(pipelines[0].source.trim(), changed_shader.trim());
+
+    std::fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,419 @@
+use std::{sync::Arc, thread::sleep, time::Duration};
+
+use cgmath::{EuclideanSpace, Point3, Vector2, Vector3};
+use hashbrown::HashMap;
+use ulid::Ulid;
+use wgpu::{
+    Buffer, BufferDescriptor, BufferUsages, CommandEncoderDescriptor, Device, MapMode, PollType,
+    Queue, TextureFormat,
+};
+
+use crate::{
+    element::{AnimationEvent, CameraEvent, LightEvent, ModelEvent, Target, WorldEvent},
+    resources::{
+        AnimationChannel, AnimationDescriptor, AnimationValues, BoundingBoxDescriptor,
+        CameraDescriptor, Interpolation, LightDescriptor, MaterialDescriptor, MeshDescriptor,
+        ModelDescriptor, RenderLayer, Transform, Vertex,
+    },
+    wgpu_test_adapter,
+};
+
+use super::{
+    CacheCleanupCadence, CacheCleanupSchedule, FrameStats, FrameTiming, LightStore, World,
+    LIGHT_BUFFER_ENTRY_SIZE, LIGHT_BUFFER_HEADER_SIZE,
+};
+
+#[test]
+fn cleanup_schedule_frames() {
+    let mut schedule = CacheCleanupSchedule::new(CacheCleanupCadence::Frames(3));
+
+    assert!(!schedule.tick());
+    assert!(!schedule.tick());
+    assert!(schedule.tick());
+
+    // Resets after being due
+    assert!(!schedule.tick());
+    assert!(!schedule.tick());
+    assert!(schedule.tick());
+}
+
+#[test]
+fn cleanup_schedule_interval() {
+    let mut schedule =
+        CacheCleanupSchedule::new(CacheCleanupCadence::Interval(Duration::from_millis(10)));
+
+    assert!(!schedule.tick());
+
+    sleep(Duration::from_millis(15));
+    assert!(schedule.tick());
+    assert!(!schedule.tick());
+}
+
+#[test]
+fn frame_timing_stats() {
+    let mut frame_timing = FrameTiming::new(4);
+    assert_eq!(frame_timing.stats(), None);
+
+    for frame_time in [0.010, 0.020, 0.040, 0.030] {
+        frame_timing.record(frame_time);
+    }
+    // Paused frames are ignored
+    frame_timing.record(0.0);
+
+    let stats = frame_timing.stats().unwrap();
+    assert_eq!(stats.frame_time, 0.030);
+    assert_eq!(stats.fps, 1.0 / 0.030);
+    assert!((stats.average_frame_time - 0.025).abs() < 1e-9);
+    assert!((stats.average_fps - 40.0).abs() < 1e-6);
+    assert_eq!(stats.min_frame_time, 0.010);
+    assert_eq!(stats.max_frame_time, 0.040);
+
+    // The oldest frame leaves the window
+    frame_timing.record(0.020);
+    let stats = frame_timing.stats().unwrap();
+    assert_eq!(stats.min_frame_time, 0.020);
+    assert!((stats.average_frame_time - 0.0275).abs() < 1e-9);
+
+    frame_timing.set_window(2);
+    let stats = frame_timing.stats().unwrap();
+    assert!((stats.average_frame_time - 0.025).abs() < 1e-9);
+    assert_eq!(stats.max_frame_time, 0.030);
+}
+
+#[test]
+fn frame_stats_query_replies_with_stats() {
+    let mut world = World::new();
+    world.record_frame(0.5);
+
+    world.process_event(WorldEvent::QueryFrameStats {
+        requester: "hud".into(),
+    });
+
+    let messages = world.take_messages();
+    assert_eq!(messages.len(), 1);
+    assert_eq!(
+        *messages[0].to(),
+        Target::Element {
+            labels: vec!["hud".into()]
+        }
+    );
+
+    let stats = FrameStats::from_message(&messages[0]).unwrap();
+    assert_eq!(stats.fps, 2.0);
+    assert_eq!(Some(stats), world.frame_stats());
+}
+
+#[test]
+fn resize_updates_auto_aspect_cameras() {
+    let mut world = World::new();
+    world.set_surface_size(Vector2::new(1920, 1080));
+
+    world.process_event(WorldEvent::Camera(CameraEvent::Spawn(CameraDescriptor {
+        label: "auto".into(),
+        ..Default::default()
+    })));
+    world.process_event(WorldEvent::Camera(CameraEvent::Spawn(CameraDescriptor {
+        label: "fixed".into(),
+        auto_aspect: false,
+        ..Default::default()
+    })));
+    world.process_event(WorldEvent::Camera(CameraEvent::Target("auto".into())));
+
+    let auto = world.camera_store().descriptor_by_label("auto").unwrap();
+    assert!((auto.projection.aspect() - 16.0 / 9.0).abs() < 1e-6);
+    let widescreen_projection = auto.projection_matrix();
+    let fixed_aspect = world
+        .camera_store()
+        .descriptor_by_label("fixed")
+        .unwrap()
+        .projection
+        .aspect();
+
+    world.set_surface_size(Vector2::new(1024, 768));
+
+    let auto = world.camera_store().descriptor_by_label("auto").unwrap();
+    assert!((auto.projection.aspect() - 4.0 / 3.0).abs() < 1e-6);
+    assert_ne!(auto.projection_matrix(), widescreen_projection);
+    assert_eq!(world.camera_store().active_camera_descriptor(), Some(auto));
+
+    let fixed = world.camera_store().descriptor_by_label("fixed").unwrap();
+    assert_eq!(fixed.projection.aspect(), fixed_aspect);
+}
+
+fn unit_cube_at(label: &str, x: f32) -> ModelDescriptor {
+    let mut descriptor = MeshDescriptor::new(
+        BoundingBoxDescriptor::new(Point3::new(-1.0, -1.0, -1.0), Point3::new(1.0, 1.0, 1.0))
+            .corners()
+            .map(|corner| {
+                Vertex::new(
+                    corner.to_vec(),
+                    Vector3::unit_z(),
+                    Vector3::unit_x(),
+                    Vector2::new(0.0, 0.0),
+                )
+            })
+            .to_vec(),
+        vec![0, 1, 2, 0, 2, 3],
+    );
+    descriptor.compute_bounding_box();
+
+    let mut transforms = HashMap::new();
+    transforms.insert(
+        Ulid::new(),
+        Transform {
+            position: Vector3::new(x, 0.0, 0.0),
+            ..Default::default()
+        },
+    );
+
+    ModelDescriptor {
+        label: label.into(),
+        mesh: Arc::new(descriptor),
+        materials: vec![Arc::new(MaterialDescriptor::default())],
+        transforms,
+        skin: None,
+        render_layer: RenderLayer::default(),
+    }
+}
+
+#[test]
+fn auto_depth_range_fits_scene() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+    let mut world = World::new();
+
+    // Looking along +X at cubes spanning 9..11 and 499..501
+    world.process_event(WorldEvent::Camera(CameraEvent::Spawn(CameraDescriptor {
+        label: "auto".into(),
+        auto_depth_range: true,
+        ..Default::default()
+    })));
+    world.process_event(WorldEvent::Camera(CameraEvent::Target("auto".into())));
+    world.process_event(WorldEvent::Model(ModelEvent::SpawnBatch(vec![
+        unit_cube_at("near", 10.0),
+        unit_cube_at("far", 500.0),
+    ])));
+
+    world.prepare_render(&TextureFormat::Rgba8UnormSrgb, &device, &queue);
+
+    let camera = world.camera_store().active_camera_descriptor().unwrap();
+    assert!(camera.near <= 9.0 && camera.near > 8.0, "{}", camera.near);
+    assert!(camera.far >= 501.0 && camera.far < 550.0, "{}", camera.far);
+
+    // Cameras without auto depth range keep theirs
+    world.process_event(WorldEvent::Camera(CameraEvent::Spawn(CameraDescriptor {
+        label: "fixed".into(),
+        ..Default::default()
+    })));
+    world.process_event(WorldEvent::Camera(CameraEvent::Target("fixed".into())));
+    world.prepare_render(&TextureFormat::Rgba8UnormSrgb, &device, &queue);
+
+    let camera = world.camera_store().active_camera_descriptor().unwrap();
+    let default = CameraDescriptor::default();
+    assert_eq!((camera.near, camera.far), (default.near, default.far));
+}
+
+fn read_buffer(buffer: &Buffer, device: &Device, queue: &Queue) -> Vec<u8> {
+    let staging = device.create_buffer(&BufferDescriptor {
+        label: None,
+        size: buffer.size(),
+        usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor::default());
+    encoder.copy_buffer_to_buffer(buffer, 0, &staging, 0, buffer.size());
+    queue.submit([encoder.finish()]);
+
+    staging.slice(..).map_async(MapMode::Read, |_| {});
+    device
+        .poll(PollType::Wait)
+        .expect("Waiting for buffer mapping failed!");
+
+    let data = staging.slice(..).get_mapped_range().to_vec();
+    data
+}
+
+fn read_f32s(data: &[u8]) -> Vec<f32> {
+    data.chunks_exact(4)
+        .map(|x| f32::from_le_bytes(x.try_into().unwrap()))
+        .collect()
+}
+
+#[test]
+fn multiple_directional_lights() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+
+    let lights = [
+        LightDescriptor::new_directional(
+            "sun".into(),
+            Vector3::new(0.0, -1.0, 0.0),
+            Vector3::new(1.0, 1.0, 1.0),
+            3.0,
+        ),
+        LightDescriptor::new_directional(
+            "rim".into(),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            2.0,
+        ),
+        LightDescriptor::new_directional(
+            "fill".into(),
+            Vector3::new(0.0, 0.0, -1.0),
+            Vector3::new(0.0, 0.0, 1.0),
+            1.0,
+        ),
+    ];
+
+    let mut light_store = LightStore::new();
+    for light in &lights {
+        light_store.handle_event(LightEvent::Spawn(light.clone()));
+    }
+    light_store.create_light_buffer(&device, None);
+
+    let data = read_buffer(light_store.light_buffer().unwrap(), &device, &queue);
+    assert_eq!(
+        data.len(),
+        LIGHT_BUFFER_HEADER_SIZE + lights.len() * LIGHT_BUFFER_ENTRY_SIZE
+    );
+    assert_eq!(u32::from_le_bytes(data[0..4].try_into().unwrap()), 3);
+
+    // Lights are ordered by brightness, which matches the spawn order here
+    for (i, light) in lights.iter().enumerate() {
+        let start = LIGHT_BUFFER_HEADER_SIZE + i * LIGHT_BUFFER_ENTRY_SIZE;
+        let entry = read_f32s(&data[start..start + LIGHT_BUFFER_ENTRY_SIZE]);
+
+        // Color (xyz) and intensity (w)
+        assert_eq!(
+            entry[4..8],
+            [
+                light.color.x,
+                light.color.y,
+                light.color.z,
+                light.intensity()
+            ]
+        );
+        // Direction (xyz) and type (w)
+        assert_eq!(
+            entry[8..12],
+            [light.direction.x, light.direction.y, light.direction.z, 1.0]
+        );
+    }
+}
+
+#[test]
+fn light_limit_prioritizes_contribution() {
+    let mut light_store = LightStore::new();
+    light_store.set_max_lights(2);
+
+    light_store.store(LightDescriptor::new_point(
+        "far".into(),
+        Vector3::new(100.0, 0.0, 0.0),
+        Vector3::new(1.0, 1.0, 1.0),
+        10.0,
+    ));
+    light_store.store(LightDescriptor::new_point(
+        "near".into(),
+        Vector3::new(1.0, 0.0, 0.0),
+        Vector3::new(1.0, 1.0, 1.0),
+        10.0,
+    ));
+    light_store.store(LightDescriptor::new_directional(
+        "sun".into(),
+        Vector3::new(0.0, -1.0, 0.0),
+        Vector3::new(1.0, 1.0, 1.0),
+        1.0,
+    ));
+
+    let labels = light_store
+        .prioritized_lights(Some(Point3::new(0.0, 0.0, 0.0)))
+        .into_iter()
+        .map(|light| light.label())
+        .collect::<Vec<_>>();
+
+    assert_eq!(labels, vec!["near", "sun"]);
+}
+
+#[test]
+fn empty_light_buffer() {
+    let (_, device, queue) = wgpu_test_adapter::make_wgpu_connection();
+
+    let mut light_store = LightStore::new();
+    light_store.create_light_buffer(&device, None);
+
+    let data = read_buffer(light_store.light_buffer().unwrap(), &device, &queue);
+    assert_eq!(
+        data.len(),
+        LIGHT_BUFFER_HEADER_SIZE + LIGHT_BUFFER_ENTRY_SIZE
+    );
+    assert_eq!(u32::from_le_bytes(data[0..4].try_into().unwrap()), 0);
+}
+
+fn slide_animation(target: &str) -> AnimationDescriptor {
+    AnimationDescriptor {
+        label: "Slide".into(),
+        channels: vec![AnimationChannel {
+            target: target.into(),
+            interpolation: Interpolation::Linear,
+            times: vec![0.0, 2.0],
+            values: AnimationValues::Translation(vec![
+                Vector3::new(0.0, 0.0, 0.0),
+                Vector3::new(4.0, 0.0, 0.0),
+            ]),
+        }],
+    }
+}
+
+fn position_of(world: &World, label: &str) -> Vector3<f32> {
+    let descriptor = world.model_store().descriptor_by_label(label).unwrap();
+    descriptor.transforms.values().next().unwrap().position
+}
+
+#[test]
+fn animations_move_models() {
+    let mut world = World::new();
+    world.process_event(WorldEvent::Model(ModelEvent::Spawn(unit_cube_at(
+        "cube", 0.0,
+    ))));
+    world.process_event(WorldEvent::Animation(AnimationEvent::Spawn(
+        slide_animation("cube"),
+    )));
+
+    // Nothing moves until played
+    world.advance_animations(1.0);
+    assert_eq!(position_of(&world, "cube"), Vector3::new(0.0, 0.0, 0.0));
+
+    world.process_event(WorldEvent::Animation(AnimationEvent::Play {
+        label: "Slide".into(),
+        looping: false,
+    }));
+    world.advance_animations(0.5);
+    assert_eq!(position_of(&world, "cube"), Vector3::new(1.0, 0.0, 0.0));
+
+    // Stops at the final pose
+    world.advance_animations(5.0);
+    assert_eq!(position_of(&world, "cube"), Vector3::new(4.0, 0.0, 0.0));
+    assert!(!world.animation_store().is_playing("Slide"));
+}
+
+#[test]
+fn looping_animations_wrap_around() {
+    let mut world = World::new();
+    world.process_event(WorldEvent::Model(ModelEvent::Spawn(unit_cube_at(
+        "cube", 0.0,
+    ))));
+    world.process_event(WorldEvent::Animation(AnimationEvent::Spawn(
+        slide_animation("cube"),
+    )));
+    world.process_event(WorldEvent::Animation(AnimationEvent::Play {
+        label: "Slide".into(),
+        looping: true,
+    }));
+
+    world.advance_animations(2.5);
+    assert_eq!(world.animation_store().playback_time("Slide"), Some(0.5));
+    assert_eq!(position_of(&world, "cube"), Vector3::new(1.0, 0.0, 0.0));
+
+    world.process_event(WorldEvent::Animation(AnimationEvent::Stop("Slide".into())));
+    world.advance_animations(1.0);
+    assert_eq!(position_of(&world, "cube"), Vector3::new(1.0, 0.0, 0.0));
+}